@@ -2,7 +2,8 @@ use {
     criterion::{criterion_group, criterion_main, BenchmarkId, Criterion},
     rand::{rngs::SmallRng, seq::IndexedRandom as _, SeedableRng},
     solana_accounts_db::{
-        accounts_db::AccountsDb, read_only_accounts_cache::ReadOnlyAccountsCache,
+        accounts_db::AccountsDb,
+        read_only_accounts_cache::{ReadCacheAdmissionPolicy, ReadOnlyAccountsCache},
     },
     std::{
         hint::black_box,
@@ -65,6 +66,8 @@ fn bench_read_only_accounts_cache(c: &mut Criterion) {
             AccountsDb::DEFAULT_MAX_READ_ONLY_CACHE_DATA_SIZE_LO,
             AccountsDb::DEFAULT_MAX_READ_ONLY_CACHE_DATA_SIZE_HI,
             AccountsDb::DEFAULT_READ_ONLY_CACHE_EVICT_SAMPLE_SIZE,
+            ReadCacheAdmissionPolicy::default(),
+            false,
         ));
 
         for (pubkey, account) in accounts.iter() {
@@ -185,6 +188,8 @@ fn bench_read_only_accounts_cache_eviction(
             max_data_size_lo,
             max_data_size_hi,
             AccountsDb::DEFAULT_READ_ONLY_CACHE_EVICT_SAMPLE_SIZE,
+            ReadCacheAdmissionPolicy::default(),
+            false,
         ));
 
         // Fill up the cache.
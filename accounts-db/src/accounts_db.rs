@@ -20,14 +20,22 @@
 
 mod accounts_db_config;
 mod geyser_plugin_utils;
+mod persisted_index;
 pub mod stats;
 pub(crate) mod tests;
 
-pub use accounts_db_config::{
-    AccountsDbConfig, ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS, ACCOUNTS_DB_CONFIG_FOR_TESTING,
-};
 #[cfg(feature = "dev-context-only-utils")]
 use qualifier_attr::qualifiers;
+pub use {
+    crate::read_only_accounts_cache::{ReadCacheAdmissionPolicy, ReadCacheCaller},
+    accounts_db_config::{
+        AccountsDbConfig, ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS, ACCOUNTS_DB_CONFIG_FOR_TESTING,
+    },
+    persisted_index::{
+        write_persisted_accounts_index, PersistedAccount, PersistedAccountsIndexError,
+        PersistedAccountsIndexReader, PersistedStorage,
+    },
+};
 use {
     crate::{
         account_info::{AccountInfo, Offset, StorageLocation},
@@ -1138,6 +1146,8 @@ impl AccountsDb {
                 read_cache_size.0,
                 read_cache_size.1,
                 read_cache_evict_sample_size,
+                accounts_db_config.read_cache_admission_policy,
+                accounts_db_config.read_cache_owner_stats,
             ),
             write_cache_limit_bytes: accounts_db_config.write_cache_limit_bytes,
             partitioned_epoch_rewards_config: accounts_db_config.partitioned_epoch_rewards_config,
@@ -1166,7 +1176,7 @@ impl AccountsDb {
             remove_unrooted_slots_synchronization: RemoveUnrootedSlotsSynchronization::default(),
             dirty_stores: DashMap::default(),
             zero_lamport_accounts_to_purge_after_full_snapshot: DashSet::default(),
-            accounts_file_provider: AccountsFileProvider::default(),
+            accounts_file_provider: accounts_db_config.accounts_file_provider,
             latest_full_snapshot_slot: SeqLock::new(None),
             best_ancient_slots_to_shrink: RwLock::default(),
             mark_obsolete_accounts: accounts_db_config.mark_obsolete_accounts,
@@ -6048,6 +6058,65 @@ impl AccountsDb {
                     i64
                 ),
             );
+
+            datapoint_info!(
+                "accounts_db_read_only_cache",
+                ("admission_rejects", read_cache_stats.admission_rejects, i64),
+                (
+                    "replay_hits",
+                    read_cache_stats.hits_by(ReadCacheCaller::Replay),
+                    i64
+                ),
+                (
+                    "replay_misses",
+                    read_cache_stats.misses_by(ReadCacheCaller::Replay),
+                    i64
+                ),
+                (
+                    "banking_hits",
+                    read_cache_stats.hits_by(ReadCacheCaller::Banking),
+                    i64
+                ),
+                (
+                    "banking_misses",
+                    read_cache_stats.misses_by(ReadCacheCaller::Banking),
+                    i64
+                ),
+                (
+                    "rpc_hits",
+                    read_cache_stats.hits_by(ReadCacheCaller::Rpc),
+                    i64
+                ),
+                (
+                    "rpc_misses",
+                    read_cache_stats.misses_by(ReadCacheCaller::Rpc),
+                    i64
+                ),
+                (
+                    "other_hits",
+                    read_cache_stats.hits_by(ReadCacheCaller::Other),
+                    i64
+                ),
+                (
+                    "other_misses",
+                    read_cache_stats.misses_by(ReadCacheCaller::Other),
+                    i64
+                ),
+            );
+
+            const MAX_REPORTED_READ_CACHE_OWNERS: usize = 10;
+            for owner_stats in self
+                .read_only_accounts_cache
+                .get_and_reset_owner_stats(MAX_REPORTED_READ_CACHE_OWNERS)
+            {
+                datapoint_info!(
+                    "accounts_db_read_only_cache_owner",
+                    ("owner", owner_stats.owner.to_string(), String),
+                    ("hits", owner_stats.hits, i64),
+                    ("stores", owner_stats.stores, i64),
+                    ("admission_rejects", owner_stats.admission_rejects, i64),
+                );
+            }
         }
     }
 
@@ -6259,7 +6328,7 @@ impl AccountsDb {
         limit_load_slot_count_from_snapshot: Option<usize>,
         verify: bool,
     ) -> IndexGenerationInfo {
-        let mut total_time = Measure::start("generate_index");
+        let total_time = Measure::start("generate_index");
 
         let mut storages = self.storage.all_storages();
         storages.sort_unstable_by_key(|storage| storage.slot);
@@ -6341,6 +6410,23 @@ impl AccountsDb {
         });
         index_time.stop();
 
+        self.finish_generate_index(total_time, index_time, &storages, total_accum, verify)
+    }
+
+    /// Completes index generation once every storage's accounts have been inserted
+    ///
+    /// This handles the duplicate and zero lamport accounts, sets the storages' alive counts and
+    /// bytes, and adds the roots.  It is shared by `generate_index()` and
+    /// `generate_index_from_persisted()`.
+    fn finish_generate_index(
+        &self,
+        mut total_time: Measure,
+        index_time: Measure,
+        storages: &[Arc<AccountStorageEntry>],
+        mut total_accum: IndexGenerationAccumulator,
+        verify: bool,
+    ) -> IndexGenerationInfo {
+        let num_storages = storages.len();
         {
             // Update the index stats now.
             let index_stats = self.accounts_index.stats();
@@ -6539,7 +6625,7 @@ impl AccountsDb {
         }
 
         // Need to add these last, otherwise older updates will be cleaned
-        for storage in &storages {
+        for storage in storages {
             self.accounts_index.add_root(storage.slot());
         }
 
@@ -6919,9 +7005,11 @@ impl AccountsDb {
         accounts_file_provider: AccountsFileProvider,
         accounts_db_config: AccountsDbConfig,
     ) -> Self {
-        let mut db = AccountsDb::new_with_config(paths, accounts_db_config, None, Arc::default());
-        db.accounts_file_provider = accounts_file_provider;
-        db
+        let accounts_db_config = AccountsDbConfig {
+            accounts_file_provider,
+            ..accounts_db_config
+        };
+        AccountsDb::new_with_config(paths, accounts_db_config, None, Arc::default())
     }
 
     /// Return the number of slots marked with uncleaned pubkeys.
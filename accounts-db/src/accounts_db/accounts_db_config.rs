@@ -3,7 +3,7 @@ use {
         AccountShrinkThreshold, MarkObsoleteAccounts, DEFAULT_ACCOUNTS_SHRINK_THRESHOLD_OPTION,
     },
     crate::{
-        accounts_file::StorageAccess,
+        accounts_file::{AccountsFileProvider, StorageAccess},
         accounts_index::{
             AccountSecondaryIndexes, AccountsIndexConfig, ScanFilter,
             ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS, ACCOUNTS_INDEX_CONFIG_FOR_TESTING,
@@ -11,6 +11,7 @@ use {
         partitioned_rewards::{
             PartitionedEpochRewardsConfig, DEFAULT_PARTITIONED_EPOCH_REWARDS_CONFIG,
         },
+        read_only_accounts_cache::ReadCacheAdmissionPolicy,
     },
     std::{num::NonZeroUsize, path::PathBuf},
 };
@@ -28,6 +29,10 @@ pub struct AccountsDbConfig {
     /// The number of elements that will be randomly sampled at eviction time,
     /// the oldest of which will get evicted.
     pub read_cache_evict_sample_size: Option<usize>,
+    /// The policy deciding whether loaded accounts are admitted into the read cache.
+    pub read_cache_admission_policy: ReadCacheAdmissionPolicy,
+    /// Whether to collect read cache statistics per account owner.
+    pub read_cache_owner_stats: bool,
     pub write_cache_limit_bytes: Option<u64>,
     /// if None, ancient append vecs are set to ANCIENT_APPEND_VEC_DEFAULT_OFFSET
     /// Some(offset) means include slots up to (max_slot - (slots_per_epoch - 'offset'))
@@ -38,6 +43,8 @@ pub struct AccountsDbConfig {
     pub exhaustively_verify_refcounts: bool,
     pub partitioned_epoch_rewards_config: PartitionedEpochRewardsConfig,
     pub storage_access: StorageAccess,
+    /// The format of newly created storages
+    pub accounts_file_provider: AccountsFileProvider,
     pub scan_filter_for_shrinking: ScanFilter,
     pub mark_obsolete_accounts: MarkObsoleteAccounts,
     /// Number of threads for background operations (`thread_pool_background')
@@ -58,6 +65,8 @@ pub const ACCOUNTS_DB_CONFIG_FOR_TESTING: AccountsDbConfig = AccountsDbConfig {
     shrink_ratio: DEFAULT_ACCOUNTS_SHRINK_THRESHOLD_OPTION,
     read_cache_limit_bytes: None,
     read_cache_evict_sample_size: None,
+    read_cache_admission_policy: ReadCacheAdmissionPolicy::Always,
+    read_cache_owner_stats: false,
     write_cache_limit_bytes: None,
     ancient_append_vec_offset: None,
     ancient_storage_ideal_size: None,
@@ -66,6 +75,7 @@ pub const ACCOUNTS_DB_CONFIG_FOR_TESTING: AccountsDbConfig = AccountsDbConfig {
     exhaustively_verify_refcounts: false,
     partitioned_epoch_rewards_config: DEFAULT_PARTITIONED_EPOCH_REWARDS_CONFIG,
     storage_access: StorageAccess::File,
+    accounts_file_provider: AccountsFileProvider::AppendVec,
     scan_filter_for_shrinking: ScanFilter::OnlyAbnormalTest,
     mark_obsolete_accounts: MarkObsoleteAccounts::Enabled,
     num_background_threads: None,
@@ -81,6 +91,8 @@ pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig
     shrink_ratio: DEFAULT_ACCOUNTS_SHRINK_THRESHOLD_OPTION,
     read_cache_limit_bytes: None,
     read_cache_evict_sample_size: None,
+    read_cache_admission_policy: ReadCacheAdmissionPolicy::Always,
+    read_cache_owner_stats: false,
     write_cache_limit_bytes: None,
     ancient_append_vec_offset: None,
     ancient_storage_ideal_size: None,
@@ -89,6 +101,7 @@ pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig
     exhaustively_verify_refcounts: false,
     partitioned_epoch_rewards_config: DEFAULT_PARTITIONED_EPOCH_REWARDS_CONFIG,
     storage_access: StorageAccess::File,
+    accounts_file_provider: AccountsFileProvider::AppendVec,
     scan_filter_for_shrinking: ScanFilter::OnlyAbnormal,
     mark_obsolete_accounts: MarkObsoleteAccounts::Enabled,
     num_background_threads: None,
@@ -0,0 +1,850 @@
+//! Persisting the results of accounts index generation
+//!
+//! Generating the accounts index at startup scans every account in every storage.  When a
+//! validator shuts down cleanly it writes a fastboot bank snapshot, and the next boot loads
+//! exactly those storages again.  So the results of the scan can be written alongside the bank
+//! snapshot, and read back on the next boot instead of scanning the storages.  Only the alive
+//! accounts at the persisted offsets are read, to check them and calculate the accounts lt hash.
+//!
+//! The file holds, per storage, the id and len of its accounts file, and the pubkey, offset, data
+//! len, and owner of each alive account.  A blake3 checksum of the contents is appended.  If
+//! anything does not match (the checksum, the version, or the storages being loaded), an error is
+//! returned and the caller should fall back to `generate_index()`.  Storages that are rewritten,
+//! e.g. by shrinking, get a new id, so a stale index is not applied to them even if their len is
+//! unchanged.
+//!
+//! File layout, with all integers little endian:
+//!
+//! ```text
+//! header:   magic [u8; 8], version u32, slot u64, num storages u64
+//! storage:  slot u64, accounts file id u32, accounts file len u64,
+//!           num owners u32, owners [Pubkey],
+//!           num accounts u64, accounts [pubkey Pubkey, offset u64, data len u64, owner index u32, flags u8]
+//! trailer:  blake3 checksum [u8; 32] of everything above
+//! ```
+use {
+    super::{AccountsDb, IndexGenerationAccumulator, IndexGenerationInfo, MarkObsoleteAccounts},
+    crate::{
+        account_info::{AccountInfo, Offset, StorageLocation},
+        account_storage_entry::AccountStorageEntry,
+        accounts_db::AccountsFileId,
+        accounts_index::{AccountIndex, Startup},
+        is_zero_lamport::IsZeroLamport,
+    },
+    agave_fs::buffered_writer::large_file_buf_writer,
+    log::*,
+    rayon::prelude::*,
+    solana_clock::Slot,
+    solana_measure::{measure::Measure, measure_us},
+    solana_nohash_hasher::IntSet,
+    solana_pubkey::Pubkey,
+    std::{
+        collections::HashMap,
+        fs::File,
+        io::{self, BufReader, Read, Write},
+        path::Path,
+        sync::{Arc, Mutex},
+        thread,
+    },
+    thiserror::Error,
+};
+
+/// Identifies a persisted accounts index file
+const MAGIC: [u8; 8] = *b"ACCTSIDX";
+
+/// The current version of the persisted accounts index file format
+const VERSION: u32 = 2;
+
+/// Number of storages to scan in parallel before writing them out
+const WRITE_CHUNK_SIZE: usize = 1024;
+
+/// Set in an account's flags when the account has zero lamports
+const FLAG_ZERO_LAMPORT: u8 = 1 << 0;
+
+#[derive(Error, Debug)]
+pub enum PersistedAccountsIndexError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid magic")]
+    InvalidMagic,
+
+    #[error("unsupported version: {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("checksum mismatch")]
+    ChecksumMismatch,
+
+    #[error("invalid owner index {owner_index} for storage in slot {slot}")]
+    InvalidOwnerIndex { slot: Slot, owner_index: u32 },
+
+    #[error("persisted index has {persisted} storages, but {actual} were loaded")]
+    MismatchedNumStorages { persisted: usize, actual: usize },
+
+    #[error("storage for slot {0} is missing")]
+    MissingStorage(Slot),
+
+    #[error("storage for slot {slot} has id {actual}, but the persisted index expects {expected}")]
+    MismatchedStorageId {
+        slot: Slot,
+        expected: AccountsFileId,
+        actual: AccountsFileId,
+    },
+
+    #[error(
+        "storage for slot {slot} has len {actual}, but the persisted index expects {expected}"
+    )]
+    MismatchedStorageLen {
+        slot: Slot,
+        expected: u64,
+        actual: u64,
+    },
+
+    #[error("geyser startup notifications require reading all accounts")]
+    GeyserNotificationsEnabled,
+
+    #[error(
+        "account at offset {offset} in storage for slot {slot} does not match the persisted index"
+    )]
+    MismatchedAccount { slot: Slot, offset: Offset },
+}
+
+/// An alive account, as recorded in the persisted accounts index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistedAccount {
+    pub pubkey: Pubkey,
+    pub offset: Offset,
+    pub data_len: u64,
+    /// Index into the storage's owners
+    pub owner_index: u32,
+    pub is_zero_lamport: bool,
+}
+
+/// The alive accounts in a single storage, as recorded in the persisted accounts index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistedStorage {
+    pub slot: Slot,
+    /// The id of the storage's accounts file when the index was persisted
+    pub id: AccountsFileId,
+    /// The len of the storage's accounts file when the index was persisted
+    pub accounts_file_len: u64,
+    /// The unique owners of the accounts in this storage
+    pub owners: Vec<Pubkey>,
+    pub accounts: Vec<PersistedAccount>,
+}
+
+impl PersistedStorage {
+    /// Builds the persisted form of `storage`, as of `snapshot_slot`
+    ///
+    /// Accounts that were marked obsolete at or before `snapshot_slot` are skipped, which
+    /// mirrors what index generation does when loading the snapshot.
+    fn new_from_storage(
+        storage: &AccountStorageEntry,
+        snapshot_slot: Slot,
+    ) -> io::Result<PersistedStorage> {
+        let obsolete_accounts: IntSet<_> = storage
+            .obsolete_accounts_read_lock()
+            .filter_obsolete_accounts(Some(snapshot_slot))
+            .map(|(offset, _)| offset)
+            .collect();
+
+        let mut owners = Vec::new();
+        let mut owner_indexes = HashMap::<Pubkey, u32>::new();
+        let mut accounts = Vec::with_capacity(storage.count());
+        storage
+            .accounts
+            .scan_accounts_without_data(|offset, account| {
+                if obsolete_accounts.contains(&offset) {
+                    return;
+                }
+                let owner_index = *owner_indexes.entry(*account.owner).or_insert_with(|| {
+                    owners.push(*account.owner);
+                    (owners.len() - 1) as u32
+                });
+                accounts.push(PersistedAccount {
+                    pubkey: *account.pubkey(),
+                    offset,
+                    data_len: account.data_len as u64,
+                    owner_index,
+                    is_zero_lamport: account.is_zero_lamport(),
+                });
+            })
+            .map_err(io::Error::other)?;
+
+        Ok(PersistedStorage {
+            slot: storage.slot(),
+            id: storage.id(),
+            accounts_file_len: storage.accounts.len() as u64,
+            owners,
+            accounts,
+        })
+    }
+
+    fn owner(&self, owner_index: u32) -> Result<&Pubkey, PersistedAccountsIndexError> {
+        self.owners.get(owner_index as usize).ok_or(
+            PersistedAccountsIndexError::InvalidOwnerIndex {
+                slot: self.slot,
+                owner_index,
+            },
+        )
+    }
+
+    fn serialize(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(&self.slot.to_le_bytes())?;
+        writer.write_all(&self.id.to_le_bytes())?;
+        writer.write_all(&self.accounts_file_len.to_le_bytes())?;
+        writer.write_all(&(self.owners.len() as u32).to_le_bytes())?;
+        for owner in &self.owners {
+            writer.write_all(owner.as_ref())?;
+        }
+        writer.write_all(&(self.accounts.len() as u64).to_le_bytes())?;
+        for account in &self.accounts {
+            let flags = if account.is_zero_lamport {
+                FLAG_ZERO_LAMPORT
+            } else {
+                0
+            };
+            writer.write_all(account.pubkey.as_ref())?;
+            writer.write_all(&(account.offset as u64).to_le_bytes())?;
+            writer.write_all(&account.data_len.to_le_bytes())?;
+            writer.write_all(&account.owner_index.to_le_bytes())?;
+            writer.write_all(&[flags])?;
+        }
+        Ok(())
+    }
+
+    /// Deserializes a storage from `reader`
+    ///
+    /// If `accounts` is false, the accounts are read (so they are checksummed) but discarded.
+    fn deserialize(reader: &mut impl Read, accounts: bool) -> io::Result<PersistedStorage> {
+        let slot = read_u64(reader)?;
+        let id = read_u32(reader)?;
+        let accounts_file_len = read_u64(reader)?;
+        let num_owners = read_u32(reader)?;
+        let owners = (0..num_owners)
+            .map(|_| read_pubkey(reader))
+            .collect::<io::Result<Vec<_>>>()?;
+        let num_accounts = read_u64(reader)?;
+        let mut persisted_accounts = Vec::new();
+        for _ in 0..num_accounts {
+            let pubkey = read_pubkey(reader)?;
+            let offset = read_u64(reader)? as Offset;
+            let data_len = read_u64(reader)?;
+            let owner_index = read_u32(reader)?;
+            let mut flags = [0; 1];
+            reader.read_exact(&mut flags)?;
+            if accounts {
+                persisted_accounts.push(PersistedAccount {
+                    pubkey,
+                    offset,
+                    data_len,
+                    owner_index,
+                    is_zero_lamport: flags[0] & FLAG_ZERO_LAMPORT != 0,
+                });
+            }
+        }
+        Ok(PersistedStorage {
+            slot,
+            id,
+            accounts_file_len,
+            owners,
+            accounts: persisted_accounts,
+        })
+    }
+}
+
+/// Writes the persisted accounts index for `snapshot_storages` to `path`
+///
+/// Returns the number of bytes written.
+pub fn write_persisted_accounts_index(
+    path: impl AsRef<Path>,
+    snapshot_storages: &[Arc<AccountStorageEntry>],
+    snapshot_slot: Slot,
+) -> io::Result<u64> {
+    let file_writer = large_file_buf_writer(path.as_ref())?;
+    let mut writer = ChecksumWriter::new(file_writer);
+
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&snapshot_slot.to_le_bytes())?;
+    writer.write_all(&(snapshot_storages.len() as u64).to_le_bytes())?;
+
+    for storages in snapshot_storages.chunks(WRITE_CHUNK_SIZE) {
+        let persisted_storages = storages
+            .par_iter()
+            .map(|storage| PersistedStorage::new_from_storage(storage, snapshot_slot))
+            .collect::<io::Result<Vec<_>>>()?;
+        for persisted_storage in &persisted_storages {
+            persisted_storage.serialize(&mut writer)?;
+        }
+    }
+
+    let (mut file_writer, checksum, num_bytes) = writer.finish();
+    file_writer.write_all(checksum.as_bytes())?;
+    file_writer.flush()?;
+    Ok(num_bytes + checksum.as_bytes().len() as u64)
+}
+
+/// Reads a persisted accounts index file
+///
+/// Opening the file reads the whole file once to verify its checksum, so once opened, the
+/// contents are known to be what was written.
+#[derive(Debug)]
+pub struct PersistedAccountsIndexReader {
+    reader: BufReader<File>,
+    slot: Slot,
+    /// The slot, accounts file id, and accounts file len of each storage, in file order
+    storages: Vec<(Slot, AccountsFileId, u64)>,
+    num_storages_read: usize,
+}
+
+impl PersistedAccountsIndexReader {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PersistedAccountsIndexError> {
+        let path = path.as_ref();
+
+        // First pass: verify the checksum and collect the storages
+        let mut reader = ChecksumReader::new(BufReader::new(File::open(path)?));
+        let (slot, num_storages) = Self::read_header(&mut reader)?;
+        let storages = (0..num_storages)
+            .map(|_| {
+                PersistedStorage::deserialize(&mut reader, false)
+                    .map(|storage| (storage.slot, storage.id, storage.accounts_file_len))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let (mut file_reader, checksum) = reader.finish();
+        let mut expected_checksum = [0; blake3::OUT_LEN];
+        file_reader.read_exact(&mut expected_checksum)?;
+        if checksum != blake3::Hash::from(expected_checksum) {
+            return Err(PersistedAccountsIndexError::ChecksumMismatch);
+        }
+
+        // Second pass: position the reader at the first storage
+        let mut reader = BufReader::new(File::open(path)?);
+        Self::read_header(&mut reader)?;
+
+        Ok(Self {
+            reader,
+            slot,
+            storages,
+            num_storages_read: 0,
+        })
+    }
+
+    /// The slot of the bank snapshot this index was persisted with
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    /// The slot, accounts file id, and accounts file len of each storage in the index
+    pub fn storages(&self) -> &[(Slot, AccountsFileId, u64)] {
+        &self.storages
+    }
+
+    /// Reads the next storage, or returns None once all storages have been read
+    pub fn next_storage(&mut self) -> io::Result<Option<PersistedStorage>> {
+        if self.num_storages_read == self.storages.len() {
+            return Ok(None);
+        }
+        let storage = PersistedStorage::deserialize(&mut self.reader, true)?;
+        self.num_storages_read += 1;
+        Ok(Some(storage))
+    }
+
+    fn read_header(reader: &mut impl Read) -> Result<(Slot, usize), PersistedAccountsIndexError> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(PersistedAccountsIndexError::InvalidMagic);
+        }
+        let version = read_u32(reader)?;
+        if version != VERSION {
+            return Err(PersistedAccountsIndexError::UnsupportedVersion(version));
+        }
+        let slot = read_u64(reader)?;
+        let num_storages = read_u64(reader)? as usize;
+        Ok((slot, num_storages))
+    }
+}
+
+impl AccountsDb {
+    /// Generates the accounts index from a persisted accounts index, instead of scanning storages
+    ///
+    /// The storages must have already been loaded, and must be the ones the index was persisted
+    /// with.  This is checked before the index is modified.  Each persisted account is then
+    /// checked against the account stored at its offset while it is inserted, so an error can
+    /// also be returned after the index was partially generated.  On error, the caller must fall
+    /// back to `generate_index()` with a new `AccountsDb`.
+    ///
+    /// The returned `calculated_accounts_lt_hash` is computed from the stored accounts, just like
+    /// `generate_index()` does, so it can be used to verify the accounts.
+    pub fn generate_index_from_persisted(
+        &self,
+        mut persisted_index: PersistedAccountsIndexReader,
+        verify: bool,
+    ) -> Result<IndexGenerationInfo, PersistedAccountsIndexError> {
+        let total_time = Measure::start("generate_index");
+
+        if self
+            .accounts_update_notifier
+            .as_ref()
+            .is_some_and(|notifier| notifier.snapshot_notifications_enabled())
+        {
+            return Err(PersistedAccountsIndexError::GeyserNotificationsEnabled);
+        }
+
+        let mut storages = self.storage.all_storages();
+        storages.sort_unstable_by_key(|storage| storage.slot);
+        if storages.len() != persisted_index.storages().len() {
+            return Err(PersistedAccountsIndexError::MismatchedNumStorages {
+                persisted: persisted_index.storages().len(),
+                actual: storages.len(),
+            });
+        }
+        let storage_indexes: HashMap<_, _> = storages
+            .iter()
+            .enumerate()
+            .map(|(storage_index, storage)| (storage.slot(), storage_index))
+            .collect();
+        for (slot, id, accounts_file_len) in persisted_index.storages() {
+            let storage_index = storage_indexes
+                .get(slot)
+                .ok_or(PersistedAccountsIndexError::MissingStorage(*slot))?;
+            let actual_id = storages[*storage_index].id();
+            if actual_id != *id {
+                return Err(PersistedAccountsIndexError::MismatchedStorageId {
+                    slot: *slot,
+                    expected: *id,
+                    actual: actual_id,
+                });
+            }
+            let actual_len = storages[*storage_index].accounts.len() as u64;
+            if actual_len != *accounts_file_len {
+                return Err(PersistedAccountsIndexError::MismatchedStorageLen {
+                    slot: *slot,
+                    expected: *accounts_file_len,
+                    actual: actual_len,
+                });
+            }
+        }
+
+        info!(
+            "Generating index from persisted index for slot {}...",
+            persisted_index.slot(),
+        );
+        let num_storages = storages.len();
+        self.accounts_index.set_startup(Startup::Startup);
+
+        let mut total_accum = IndexGenerationAccumulator::with_slots_capacity(num_storages);
+        let persisted_index = Mutex::new(persisted_index);
+        let num_threads = num_cpus::get();
+        let mut index_time = Measure::start("index");
+        thread::scope(|s| -> Result<(), PersistedAccountsIndexError> {
+            let thread_handles = (0..num_threads)
+                .map(|i| {
+                    thread::Builder::new()
+                        .name(format!("solGenIndexP{i:02}"))
+                        .spawn_scoped(s, || {
+                            let mut thread_accum = IndexGenerationAccumulator::with_slots_capacity(
+                                num_storages / num_threads,
+                            );
+                            loop {
+                                // Reading is cheap compared to inserting, so hold the lock only
+                                // long enough to read the next storage.
+                                let persisted_storage =
+                                    persisted_index.lock().unwrap().next_storage()?;
+                                let Some(persisted_storage) = persisted_storage else {
+                                    break;
+                                };
+                                let storage_index = storage_indexes[&persisted_storage.slot];
+                                self.generate_index_for_persisted_slot(
+                                    &mut thread_accum,
+                                    storage_index,
+                                    &storages[storage_index],
+                                    persisted_storage,
+                                )?;
+                            }
+                            Ok::<_, PersistedAccountsIndexError>(thread_accum)
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .expect("spawn threads");
+            // Join every thread before returning an error, so none are still inserting
+            let thread_results: Vec<_> = thread_handles
+                .into_iter()
+                .map(|thread_handle| thread_handle.join().expect("join thread"))
+                .collect();
+            for thread_accum in thread_results {
+                total_accum.accumulate(thread_accum?);
+            }
+            Ok(())
+        })?;
+        index_time.stop();
+
+        Ok(self.finish_generate_index(total_time, index_time, &storages, total_accum, verify))
+    }
+
+    /// Inserts the accounts of a single persisted storage into the index
+    ///
+    /// This is the counterpart of `generate_index_for_slot()`, but only reads the alive accounts
+    /// at the persisted offsets instead of scanning the whole storage.  Each one must match its
+    /// persisted pubkey, owner, and data len, and is mixed into the accounts lt hash.
+    fn generate_index_for_persisted_slot(
+        &self,
+        accum: &mut IndexGenerationAccumulator,
+        storage_index: usize,
+        storage: &AccountStorageEntry,
+        persisted_storage: PersistedStorage,
+    ) -> Result<(), PersistedAccountsIndexError> {
+        let slot = storage.slot();
+        let store_id = storage.id();
+        let zero_lamport_pubkeys_original_len = accum.zero_lamport_pubkeys.len();
+
+        let mut accounts_data_len = 0;
+        let mut stored_size_alive = 0;
+        let mut all_accounts_are_zero_lamports = true;
+        accum.slot_arena.ensure_empty();
+        let keyed_account_infos = &mut accum.slot_arena.keyed_account_infos;
+        let zero_lamport_offsets = &mut accum.slot_arena.zero_lamport_offsets;
+
+        for account in &persisted_storage.accounts {
+            let data_len = account.data_len as usize;
+            stored_size_alive += storage.accounts.calculate_stored_size(data_len);
+            if !account.is_zero_lamport {
+                accounts_data_len += account.data_len;
+                all_accounts_are_zero_lamports = false;
+            } else {
+                if self.mark_obsolete_accounts == MarkObsoleteAccounts::Enabled {
+                    zero_lamport_offsets.push(account.offset);
+                }
+                accum.zero_lamport_pubkeys.push(account.pubkey);
+            }
+            keyed_account_infos.push((
+                account.pubkey,
+                AccountInfo::new(
+                    StorageLocation::AppendVec(store_id, account.offset), // will never be cached
+                    account.is_zero_lamport,
+                ),
+            ));
+
+            let owner = persisted_storage.owner(account.owner_index)?;
+            let is_match = storage
+                .accounts
+                .get_stored_account_callback(account.offset, |stored_account| {
+                    let is_match = stored_account.pubkey() == &account.pubkey
+                        && stored_account.owner == owner
+                        && stored_account.data.len() as u64 == account.data_len
+                        && stored_account.is_zero_lamport() == account.is_zero_lamport;
+                    if is_match {
+                        let account_lt_hash =
+                            Self::lt_hash_account(&stored_account, &account.pubkey);
+                        accum.lt_hash.mix_in(&account_lt_hash.0);
+                        if !self.account_indexes.is_empty()
+                            && self.is_owner_secondary_indexed(owner)
+                        {
+                            self.accounts_index.update_secondary_indexes(
+                                &account.pubkey,
+                                &stored_account,
+                                &self.account_indexes,
+                            );
+                        }
+                    }
+                    is_match
+                })
+                .unwrap_or(false);
+            if !is_match {
+                return Err(PersistedAccountsIndexError::MismatchedAccount {
+                    slot,
+                    offset: account.offset,
+                });
+            }
+        }
+
+        let (insert_info, insert_time_us) = measure_us!(self
+            .accounts_index
+            .insert_new_if_missing_into_primary_index(slot, keyed_account_infos));
+
+        if insert_info.count > 0 {
+            let info = super::StorageSizeAndCount {
+                stored_size: stored_size_alive,
+                count: insert_info.count,
+            };
+            accum.storage_info.push((store_id, info));
+        }
+        if accum.zero_lamport_pubkeys.len() != zero_lamport_pubkeys_original_len {
+            let old = self.uncleaned_pubkeys.insert(
+                slot,
+                accum.zero_lamport_pubkeys[zero_lamport_pubkeys_original_len..].to_vec(),
+            );
+            assert!(old.is_none());
+            if self.mark_obsolete_accounts == MarkObsoleteAccounts::Enabled {
+                storage.batch_insert_zero_lamport_single_ref_account_offsets(zero_lamport_offsets);
+                accum.zero_lamport_pubkeys.clear();
+            };
+        }
+
+        accum.num_accounts += insert_info.count as u64;
+        accum.insert_time_us += insert_time_us;
+        accum.accounts_data_len += accounts_data_len;
+        accum.num_did_not_exist += insert_info.num_did_not_exist;
+        accum.num_existed_in_mem += insert_info.num_existed_in_mem;
+        accum.num_existed_on_disk += insert_info.num_existed_on_disk;
+        if all_accounts_are_zero_lamports {
+            accum.all_accounts_are_zero_lamports_slots += 1;
+            accum.all_zeros_slots.push((slot, storage_index));
+        }
+        Ok(())
+    }
+
+    /// Could an account owned by `owner` be in any of the secondary indexes?
+    fn is_owner_secondary_indexed(&self, owner: &Pubkey) -> bool {
+        (self.account_indexes.contains(&AccountIndex::ProgramId)
+            && self.account_indexes.include_key(owner))
+            || owner == &spl_generic_token::token::id()
+            || owner == &spl_generic_token::token_2022::id()
+    }
+}
+
+/// Wraps a writer and computes the checksum of everything written through it
+struct ChecksumWriter<W> {
+    inner: W,
+    hasher: blake3::Hasher,
+    num_bytes: u64,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+            num_bytes: 0,
+        }
+    }
+
+    /// Returns the inner writer, the checksum, and the number of bytes written
+    fn finish(self) -> (W, blake3::Hash, u64) {
+        (self.inner, self.hasher.finalize(), self.num_bytes)
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let num_bytes = self.inner.write(buf)?;
+        self.hasher.update(&buf[..num_bytes]);
+        self.num_bytes += num_bytes as u64;
+        Ok(num_bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Wraps a reader and computes the checksum of everything read through it
+struct ChecksumReader<R> {
+    inner: R,
+    hasher: blake3::Hasher,
+}
+
+impl<R: Read> ChecksumReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+        }
+    }
+
+    /// Returns the inner reader and the checksum
+    fn finish(self) -> (R, blake3::Hash) {
+        (self.inner, self.hasher.finalize())
+    }
+}
+
+impl<R: Read> Read for ChecksumReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let num_bytes = self.inner.read(buf)?;
+        self.hasher.update(&buf[..num_bytes]);
+        Ok(num_bytes)
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; size_of::<u32>()];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; size_of::<u64>()];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_pubkey(reader: &mut impl Read) -> io::Result<Pubkey> {
+    let mut bytes = [0; size_of::<Pubkey>()];
+    reader.read_exact(&mut bytes)?;
+    Ok(Pubkey::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_account::AccountSharedData,
+        std::{fs, sync::atomic::Ordering},
+        tempfile::TempDir,
+    };
+
+    /// Creates an AccountsDb with storages for a few slots, but *without* generating the index,
+    /// similar to loading storages from a fastboot snapshot
+    fn new_accounts_db_with_storages() -> AccountsDb {
+        let db = AccountsDb::new_single_for_tests();
+        add_storages(&db);
+        db
+    }
+
+    /// Adds storages for a few slots to `db`
+    fn add_storages(db: &AccountsDb) {
+        let owners = [Pubkey::from([100; 32]), Pubkey::from([101; 32])];
+        for slot in 0..4 {
+            let accounts: Vec<_> = (0..10u8)
+                .map(|i| {
+                    // all slots write pubkey 0, so it is a duplicate across slots
+                    let pubkey = Pubkey::from([i * 4 + (slot as u8) * (i != 0) as u8; 32]);
+                    // pubkey 1 is zero lamport in every slot
+                    let lamports = if i == 1 { 0 } else { slot + u64::from(i) };
+                    let owner = &owners[i as usize % owners.len()];
+                    (pubkey, AccountSharedData::new(lamports, i as usize, owner))
+                })
+                .collect();
+            let data: Vec<_> = accounts
+                .iter()
+                .map(|(pubkey, account)| (pubkey, account))
+                .collect();
+            let storage = db.create_and_insert_store(slot, 10_000, "test");
+            storage.accounts.write_accounts(&(slot, &data[..]), 0);
+        }
+    }
+
+    fn sorted_storages(db: &AccountsDb) -> Vec<Arc<AccountStorageEntry>> {
+        let mut storages = db.storage.all_storages();
+        storages.sort_unstable_by_key(|storage| storage.slot());
+        storages
+    }
+
+    #[test]
+    fn test_persisted_index_roundtrip() {
+        let expected_db = new_accounts_db_with_storages();
+        let expected_info = expected_db.generate_index(None, false);
+
+        let actual_db = new_accounts_db_with_storages();
+        let storages = sorted_storages(&actual_db);
+        let snapshot_slot = storages.last().unwrap().slot();
+        let index_dir = TempDir::new().unwrap();
+        let index_path = index_dir.path().join("accounts_index");
+        write_persisted_accounts_index(&index_path, &storages, snapshot_slot).unwrap();
+
+        let mut reader = PersistedAccountsIndexReader::open(&index_path).unwrap();
+        assert_eq!(reader.slot(), snapshot_slot);
+        assert_eq!(reader.storages().len(), storages.len());
+        for storage in &storages {
+            let expected = PersistedStorage::new_from_storage(storage, snapshot_slot).unwrap();
+            let actual = reader.next_storage().unwrap().unwrap();
+            assert_eq!(actual, expected);
+        }
+        assert!(reader.next_storage().unwrap().is_none());
+
+        let reader = PersistedAccountsIndexReader::open(&index_path).unwrap();
+        let actual_info = actual_db
+            .generate_index_from_persisted(reader, true)
+            .unwrap();
+        assert_eq!(
+            actual_info.accounts_data_len,
+            expected_info.accounts_data_len
+        );
+        assert_eq!(
+            actual_info.calculated_accounts_lt_hash,
+            expected_info.calculated_accounts_lt_hash,
+        );
+
+        // the index and the storages' alive info must match regular index generation
+        for (actual_storage, expected_storage) in storages.iter().zip(sorted_storages(&expected_db))
+        {
+            assert_eq!(actual_storage.count(), expected_storage.count());
+            assert_eq!(actual_storage.alive_bytes(), expected_storage.alive_bytes());
+            actual_storage
+                .accounts
+                .scan_pubkeys(|pubkey| {
+                    let slot_list = |db: &AccountsDb| {
+                        db.accounts_index.get_and_then(pubkey, |entry| {
+                            let entry = entry.unwrap();
+                            let slot_list = entry.slot_list_read_lock();
+                            let slots: Vec<_> = slot_list.iter().map(|(slot, _)| *slot).collect();
+                            (false, (slots, entry.ref_count()))
+                        })
+                    };
+                    assert_eq!(slot_list(&actual_db), slot_list(&expected_db));
+                })
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_persisted_index_checksum_mismatch() {
+        let db = new_accounts_db_with_storages();
+        let storages = sorted_storages(&db);
+        let index_dir = TempDir::new().unwrap();
+        let index_path = index_dir.path().join("accounts_index");
+        write_persisted_accounts_index(&index_path, &storages, storages.last().unwrap().slot())
+            .unwrap();
+
+        // flip a bit in the middle of the file
+        let mut contents = fs::read(&index_path).unwrap();
+        let middle = contents.len() / 2;
+        contents[middle] ^= 1;
+        fs::write(&index_path, contents).unwrap();
+
+        assert!(matches!(
+            PersistedAccountsIndexReader::open(&index_path),
+            Err(PersistedAccountsIndexError::ChecksumMismatch),
+        ));
+    }
+
+    #[test]
+    fn test_persisted_index_mismatched_storages() {
+        let db = new_accounts_db_with_storages();
+        let storages = sorted_storages(&db);
+        let index_dir = TempDir::new().unwrap();
+        let index_path = index_dir.path().join("accounts_index");
+        write_persisted_accounts_index(&index_path, &storages, storages.last().unwrap().slot())
+            .unwrap();
+
+        // the storages being loaded do not match the ones the index was persisted with
+        let db = AccountsDb::new_single_for_tests();
+        db.create_and_insert_store(0, 10_000, "test");
+        let reader = PersistedAccountsIndexReader::open(&index_path).unwrap();
+        assert!(matches!(
+            db.generate_index_from_persisted(reader, false),
+            Err(PersistedAccountsIndexError::MismatchedNumStorages { .. }),
+        ));
+    }
+
+    #[test]
+    fn test_persisted_index_mismatched_storage_id() {
+        let db = new_accounts_db_with_storages();
+        let storages = sorted_storages(&db);
+        let index_dir = TempDir::new().unwrap();
+        let index_path = index_dir.path().join("accounts_index");
+        write_persisted_accounts_index(&index_path, &storages, storages.last().unwrap().slot())
+            .unwrap();
+
+        // the storages have the same slots and lens, but are different accounts files
+        let db = AccountsDb::new_single_for_tests();
+        db.next_id.store(100, Ordering::Release);
+        add_storages(&db);
+        let reader = PersistedAccountsIndexReader::open(&index_path).unwrap();
+        assert!(matches!(
+            db.generate_index_from_persisted(reader, false),
+            Err(PersistedAccountsIndexError::MismatchedStorageId { .. }),
+        ));
+    }
+}
@@ -464,6 +464,41 @@ define_accounts_db_test!(test_accountsdb_add_root, |db| {
     );
 });
 
+#[test]
+fn test_accountsdb_hot_storage_compressed() {
+    use crate::tiered_storage::hot::{HOT_COMPRESSED_FORMAT, HOT_COMPRESSION_MIN_DATA_LEN};
+
+    let db = AccountsDb::new_with_config(
+        Vec::new(),
+        AccountsDbConfig {
+            accounts_file_provider: AccountsFileProvider::HotStorageCompressed,
+            ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+        },
+        None,
+        Arc::default(),
+    );
+    let key = solana_pubkey::new_rand();
+    let data_len = HOT_COMPRESSION_MIN_DATA_LEN * 10;
+    let account = AccountSharedData::new(1, data_len, &key);
+
+    db.store_for_tests((0, [(&key, &account)].as_slice()));
+    db.add_root_and_flush_write_cache(0);
+
+    let storage = db.get_storage_for_slot(0).unwrap();
+    let AccountsFile::TieredStorage(tiered_storage) = &storage.accounts else {
+        panic!("expected tiered storage");
+    };
+    assert_eq!(tiered_storage.format(), &HOT_COMPRESSED_FORMAT);
+    // the zero-filled data is stored compressed
+    assert!(tiered_storage.len() < data_len);
+
+    let ancestors = vec![(0, 0)].into_iter().collect();
+    assert_eq!(
+        db.load_without_fixed_root(&ancestors, &key),
+        Some((account, 0))
+    );
+}
+
 define_accounts_db_test!(test_accountsdb_latest_ancestor, |db| {
     let key = Pubkey::default();
     let account0 = AccountSharedData::new(1, 0, &key);
@@ -3678,7 +3713,7 @@ define_accounts_db_test!(
                     0
                 );
             }
-            AccountsFileProvider::HotStorage => {
+            AccountsFileProvider::HotStorage | AccountsFileProvider::HotStorageCompressed => {
                 // For tired-storage, alive bytes are only an approximation.
                 // Therefore, it won't be zero.
                 assert!(
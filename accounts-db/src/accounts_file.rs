@@ -6,7 +6,10 @@ use {
         append_vec::{AppendVec, AppendVecError},
         storable_accounts::StorableAccounts,
         tiered_storage::{
-            error::TieredStorageError, hot::HOT_FORMAT, index::IndexOffset, TieredStorage,
+            error::TieredStorageError,
+            hot::{HOT_COMPRESSED_FORMAT, HOT_FORMAT},
+            index::IndexOffset,
+            TieredStorage,
         },
     },
     agave_fs::{buffered_reader::RequiredLenBufFileRead, FileInfo},
@@ -328,7 +331,7 @@ impl AccountsFile {
             // assumes all offsets are multiple of 8 while TieredStorage uses
             // IndexOffset that is equivalent to AccountInfo::reduced_offset.
             Self::TieredStorage(ts) => ts
-                .write_accounts(accounts, skip, &HOT_FORMAT)
+                .write_accounts(accounts, skip)
                 .map(|mut stored_accounts_info| {
                     stored_accounts_info.offsets.iter_mut().for_each(|offset| {
                         *offset = AccountInfo::reduced_offset_to_offset(*offset as u32);
@@ -358,6 +361,11 @@ pub enum AccountsFileProvider {
     #[default]
     AppendVec,
     HotStorage,
+    /// Hot storage that lz4-compresses the data of large accounts
+    ///
+    /// Like `HotStorage`, this is only used by tests, and can't be selected
+    /// for a validator.
+    HotStorageCompressed,
 }
 
 impl AccountsFileProvider {
@@ -374,7 +382,13 @@ impl AccountsFileProvider {
                 file_size as usize,
                 storage_access,
             )),
-            Self::HotStorage => AccountsFile::TieredStorage(TieredStorage::new_writable(path)),
+            Self::HotStorage => {
+                AccountsFile::TieredStorage(TieredStorage::new_writable(path, HOT_FORMAT))
+            }
+            Self::HotStorageCompressed => AccountsFile::TieredStorage(TieredStorage::new_writable(
+                path,
+                HOT_COMPRESSED_FORMAT,
+            )),
         }
     }
 }
@@ -16,9 +16,11 @@ use {
     solana_measure::{measure::Measure, measure_us},
     solana_pubkey::Pubkey,
     std::{
+        cell::Cell,
+        hash::BuildHasher,
         mem::ManuallyDrop,
         sync::{
-            atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+            atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering},
             Arc,
         },
         thread,
@@ -39,6 +41,66 @@ const NUM_SHARDS: usize = 65536;
 
 type ReadOnlyCacheKey = Pubkey;
 
+/// Number of rows in the frequency sketch used by the admission policy.
+const FREQUENCY_SKETCH_DEPTH: usize = 4;
+/// Bounds on the number of counters in each row of the frequency sketch.
+const FREQUENCY_SKETCH_MIN_WIDTH: usize = 1 << 10;
+const FREQUENCY_SKETCH_MAX_WIDTH: usize = 1 << 20;
+/// Counters saturate at this value, as in TinyLFU's 4-bit counters.
+const FREQUENCY_SKETCH_MAX_COUNT: u8 = 15;
+/// The sketch is aged after this many increments per counter in a row.
+const FREQUENCY_SKETCH_SAMPLE_FACTOR: usize = 10;
+/// Seeds used to derive an independent counter index for each row.
+const FREQUENCY_SKETCH_SEEDS: [u64; FREQUENCY_SKETCH_DEPTH] = [
+    0xc3a5_c85c_97cb_3127,
+    0xb492_b66f_be98_f273,
+    0x9ae1_6a3b_2f90_404f,
+    0xcbf2_9ce4_8422_2325,
+];
+
+/// The policy used to decide whether a loaded account is admitted into the
+/// cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReadCacheAdmissionPolicy {
+    /// Every loaded account is admitted; entries are evicted using sampled LRU.
+    #[default]
+    Always,
+    /// Once the cache is full, an account is only admitted if it has been
+    /// accessed more frequently than a sampled eviction candidate (TinyLFU).
+    ///
+    /// This keeps one-off loads, such as scans over large sets of program
+    /// accounts, from evicting accounts that are used by every slot.
+    Frequency,
+}
+
+/// The component loading from the cache, used to break down hits and misses.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReadCacheCaller {
+    #[default]
+    Other = 0,
+    Replay = 1,
+    Banking = 2,
+    Rpc = 3,
+}
+
+impl ReadCacheCaller {
+    pub const NUM_CALLERS: usize = 4;
+
+    /// Attributes all subsequent cache loads on the current thread to `self`.
+    pub fn set_for_current_thread(self) {
+        READ_CACHE_CALLER.set(self);
+    }
+
+    /// Returns the caller that loads on the current thread are attributed to.
+    pub fn current() -> Self {
+        READ_CACHE_CALLER.get()
+    }
+}
+
+thread_local! {
+    static READ_CACHE_CALLER: Cell<ReadCacheCaller> = const { Cell::new(ReadCacheCaller::Other) };
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "dev-context-only-utils", qualifiers(pub))]
 #[cfg_attr(
@@ -66,6 +128,21 @@ pub struct ReadOnlyCacheStats {
     pub evict_us: u64,
     pub evictor_wakeup_count_all: u64,
     pub evictor_wakeup_count_productive: u64,
+    pub admission_rejects: u64,
+    /// hits, indexed by `ReadCacheCaller`
+    pub hits_by_caller: [u64; ReadCacheCaller::NUM_CALLERS],
+    /// misses, indexed by `ReadCacheCaller`
+    pub misses_by_caller: [u64; ReadCacheCaller::NUM_CALLERS],
+}
+
+impl ReadOnlyCacheStats {
+    pub fn hits_by(&self, caller: ReadCacheCaller) -> u64 {
+        self.hits_by_caller[caller as usize]
+    }
+
+    pub fn misses_by(&self, caller: ReadCacheCaller) -> u64 {
+        self.misses_by_caller[caller as usize]
+    }
 }
 
 #[derive(Default, Debug)]
@@ -78,18 +155,120 @@ struct AtomicReadOnlyCacheStats {
     evict_us: AtomicU64,
     evictor_wakeup_count_all: AtomicU64,
     evictor_wakeup_count_productive: AtomicU64,
+    admission_rejects: AtomicU64,
+    hits_by_caller: [AtomicU64; ReadCacheCaller::NUM_CALLERS],
+    misses_by_caller: [AtomicU64; ReadCacheCaller::NUM_CALLERS],
+}
+
+/// Statistics for the accounts of a single owner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadOnlyCacheOwnerStats {
+    pub owner: Pubkey,
+    pub hits: u64,
+    pub stores: u64,
+    pub admission_rejects: u64,
+}
+
+#[derive(Default, Debug)]
+struct AtomicReadOnlyCacheOwnerStats {
+    hits: AtomicU64,
+    stores: AtomicU64,
+    admission_rejects: AtomicU64,
+}
+
+/// A count-min sketch estimating how often each key has been accessed.
+///
+/// Counters are periodically halved so that the estimates favor recent
+/// accesses over historical ones.
+#[derive(Debug)]
+struct FrequencySketch {
+    /// `FREQUENCY_SKETCH_DEPTH` rows of `width` counters each
+    counters: Box<[AtomicU8]>,
+    width: usize,
+    hasher: AHashRandomState,
+    /// Number of increments since the sketch was last aged
+    num_increments: AtomicUsize,
+    /// Number of increments after which the sketch is aged
+    reset_threshold: usize,
+}
+
+impl FrequencySketch {
+    fn new(width: usize) -> Self {
+        let width = width
+            .checked_next_power_of_two()
+            .unwrap_or(FREQUENCY_SKETCH_MAX_WIDTH)
+            .clamp(FREQUENCY_SKETCH_MIN_WIDTH, FREQUENCY_SKETCH_MAX_WIDTH);
+        Self {
+            counters: (0..FREQUENCY_SKETCH_DEPTH * width)
+                .map(|_| AtomicU8::new(0))
+                .collect(),
+            width,
+            hasher: AHashRandomState::default(),
+            num_increments: AtomicUsize::new(0),
+            reset_threshold: width * FREQUENCY_SKETCH_SAMPLE_FACTOR,
+        }
+    }
+
+    /// Returns the index of `key`'s counter in each row.
+    fn indexes(&self, key: &Pubkey) -> impl Iterator<Item = usize> + '_ {
+        let hash = self.hasher.hash_one(key);
+        FREQUENCY_SKETCH_SEEDS
+            .iter()
+            .enumerate()
+            .map(move |(row, seed)| {
+                let mixed = (hash ^ seed).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+                row * self.width + ((mixed >> 32) as usize & (self.width - 1))
+            })
+    }
+
+    /// Records an access of `key`.
+    fn increment(&self, key: &Pubkey) {
+        for index in self.indexes(key) {
+            let _ =
+                self.counters[index].fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                    (count < FREQUENCY_SKETCH_MAX_COUNT).then_some(count + 1)
+                });
+        }
+        if self.num_increments.fetch_add(1, Ordering::Relaxed) + 1 == self.reset_threshold {
+            self.age();
+        }
+    }
+
+    /// Returns the estimated number of recent accesses of `key`.
+    fn estimate(&self, key: &Pubkey) -> u8 {
+        self.indexes(key)
+            .map(|index| self.counters[index].load(Ordering::Relaxed))
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Halves all counters.
+    fn age(&self) {
+        for counter in self.counters.iter() {
+            let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                Some(count / 2)
+            });
+        }
+        self.num_increments.store(0, Ordering::Relaxed);
+    }
 }
 
 #[cfg_attr(feature = "dev-context-only-utils", qualifiers(pub))]
 #[derive(Debug)]
 pub(crate) struct ReadOnlyAccountsCache {
     cache: Arc<DashMap<ReadOnlyCacheKey, ReadOnlyAccountCacheEntry, AHashRandomState>>,
-    _max_data_size_lo: usize,
+    max_data_size_lo: usize,
     _max_data_size_hi: usize,
+    evict_sample_size: usize,
     data_size: Arc<AtomicUsize>,
 
+    /// Access frequencies, present if the admission policy is `Frequency`.
+    frequency_sketch: Option<FrequencySketch>,
+
     // Performance statistics
     stats: Arc<AtomicReadOnlyCacheStats>,
+    /// Per-owner statistics, present if enabled at startup.
+    owner_stats: Option<DashMap<Pubkey, AtomicReadOnlyCacheOwnerStats, AHashRandomState>>,
     highest_slot_stored: AtomicU64,
 
     /// Timer for generating timestamps for entries.
@@ -109,6 +288,8 @@ impl ReadOnlyAccountsCache {
         max_data_size_lo: usize,
         max_data_size_hi: usize,
         evict_sample_size: usize,
+        admission_policy: ReadCacheAdmissionPolicy,
+        track_owner_stats: bool,
     ) -> Self {
         assert!(max_data_size_lo <= max_data_size_hi);
         assert!(evict_sample_size > 0);
@@ -130,13 +311,24 @@ impl ReadOnlyAccountsCache {
             stats.clone(),
         );
 
+        let frequency_sketch = match admission_policy {
+            ReadCacheAdmissionPolicy::Always => None,
+            ReadCacheAdmissionPolicy::Frequency => {
+                Some(FrequencySketch::new(max_data_size_hi / CACHE_ENTRY_SIZE))
+            }
+        };
+        let owner_stats = track_owner_stats.then(DashMap::default);
+
         Self {
             highest_slot_stored: AtomicU64::default(),
-            _max_data_size_lo: max_data_size_lo,
+            max_data_size_lo,
             _max_data_size_hi: max_data_size_hi,
+            evict_sample_size,
             cache,
             data_size,
+            frequency_sketch,
             stats,
+            owner_stats,
             timer,
             evictor_thread_handle: ManuallyDrop::new(evictor_thread_handle),
             evictor_exit_flag,
@@ -146,6 +338,11 @@ impl ReadOnlyAccountsCache {
     #[cfg_attr(feature = "dev-context-only-utils", qualifiers(pub))]
     pub(crate) fn load(&self, pubkey: Pubkey, slot: Slot) -> Option<AccountSharedData> {
         let (account, load_us) = measure_us!({
+            let caller = ReadCacheCaller::current() as usize;
+            if let Some(frequency_sketch) = &self.frequency_sketch {
+                frequency_sketch.increment(&pubkey);
+            }
+
             let mut found = None;
             if let Some(entry) = self.cache.get(&pubkey) {
                 if entry.slot == slot {
@@ -155,12 +352,15 @@ impl ReadOnlyAccountsCache {
                     let account = entry.account.clone();
                     drop(entry);
                     self.stats.hits.fetch_add(1, Ordering::Relaxed);
+                    self.stats.hits_by_caller[caller].fetch_add(1, Ordering::Relaxed);
+                    self.update_owner_stats(account.owner(), |stats| &stats.hits);
                     found = Some(account);
                 }
             }
 
             if found.is_none() {
                 self.stats.misses.fetch_add(1, Ordering::Relaxed);
+                self.stats.misses_by_caller[caller].fetch_add(1, Ordering::Relaxed);
             }
             found
         });
@@ -186,8 +386,16 @@ impl ReadOnlyAccountsCache {
         timestamp: u64,
     ) {
         let measure_store = Measure::start("");
-        self.highest_slot_stored.fetch_max(slot, Ordering::Release);
         let account_size = Self::account_size(&account);
+        if !self.should_admit(&pubkey, account_size) {
+            self.stats.admission_rejects.fetch_add(1, Ordering::Relaxed);
+            self.update_owner_stats(account.owner(), |stats| &stats.admission_rejects);
+            let store_us = measure_store.end_as_us();
+            self.stats.store_us.fetch_add(store_us, Ordering::Relaxed);
+            return;
+        }
+        self.update_owner_stats(account.owner(), |stats| &stats.stores);
+        self.highest_slot_stored.fetch_max(slot, Ordering::Release);
         self.data_size.fetch_add(account_size, Ordering::Relaxed);
         match self.cache.entry(pubkey) {
             Entry::Vacant(entry) => {
@@ -206,6 +414,80 @@ impl ReadOnlyAccountsCache {
         self.stats.store_us.fetch_add(store_us, Ordering::Relaxed);
     }
 
+    /// Returns true if `pubkey` should be stored in the cache.
+    ///
+    /// With the `Frequency` admission policy, once the cache is full a new
+    /// account is only admitted if it has been accessed more frequently than
+    /// the account that would likely be evicted to make room for it.
+    fn should_admit(&self, pubkey: &Pubkey, account_size: usize) -> bool {
+        let Some(frequency_sketch) = &self.frequency_sketch else {
+            return true;
+        };
+        if self.data_size.load(Ordering::Relaxed) + account_size <= self.max_data_size_lo
+            || self.cache.contains_key(pubkey)
+        {
+            return true;
+        }
+
+        let Some(candidate) =
+            Self::sample_eviction_candidate(&self.cache, self.evict_sample_size, &mut rand::rng())
+        else {
+            return true;
+        };
+        frequency_sketch.estimate(pubkey) > frequency_sketch.estimate(&candidate)
+    }
+
+    /// Returns the least recently used key among up to `sample_size` randomly
+    /// sampled entries, or None if the cache is empty.
+    ///
+    /// Unlike `evict()`, this may run while the cache holds only a few (large)
+    /// entries, so shards are visited in order starting from a random one,
+    /// which guarantees termination.
+    fn sample_eviction_candidate<R: Rng>(
+        cache: &DashMap<ReadOnlyCacheKey, ReadOnlyAccountCacheEntry, AHashRandomState>,
+        sample_size: usize,
+        rng: &mut R,
+    ) -> Option<ReadOnlyCacheKey> {
+        let mut candidate = None;
+        let mut min_update_time = u64::MAX;
+        let mut remaining_samples = sample_size;
+        let shards = cache.shards();
+        let start = rng.random_range(0..shards.len());
+        for shard in shards[start..].iter().chain(&shards[..start]) {
+            if remaining_samples == 0 {
+                break;
+            }
+            let shard = shard.read();
+            for (key, entry) in shard.iter().choose_multiple(rng, remaining_samples) {
+                let last_update_time = entry.get().last_update_time.load(Ordering::Relaxed);
+                if last_update_time < min_update_time {
+                    min_update_time = last_update_time;
+                    candidate = Some(key.to_owned());
+                }
+                remaining_samples = remaining_samples.saturating_sub(1);
+            }
+        }
+        candidate
+    }
+
+    /// Increments the per-owner counter selected by `counter`, if per-owner
+    /// statistics are enabled.
+    fn update_owner_stats(
+        &self,
+        owner: &Pubkey,
+        counter: impl FnOnce(&AtomicReadOnlyCacheOwnerStats) -> &AtomicU64,
+    ) {
+        let Some(owner_stats) = &self.owner_stats else {
+            return;
+        };
+        if let Some(stats) = owner_stats.get(owner) {
+            counter(&stats).fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let stats = owner_stats.entry(*owner).or_default();
+        counter(&stats).fetch_add(1, Ordering::Relaxed);
+    }
+
     /// true if any pubkeys could have ever been stored into the cache at `slot`
     pub(crate) fn can_slot_be_in_cache(&self, slot: Slot) -> bool {
         self.highest_slot_stored.load(Ordering::Acquire) >= slot
@@ -263,6 +545,14 @@ impl ReadOnlyAccountsCache {
             .evictor_wakeup_count_productive
             .swap(0, Ordering::Relaxed);
 
+        let admission_rejects = self.stats.admission_rejects.swap(0, Ordering::Relaxed);
+        let hits_by_caller = std::array::from_fn(|caller| {
+            self.stats.hits_by_caller[caller].swap(0, Ordering::Relaxed)
+        });
+        let misses_by_caller = std::array::from_fn(|caller| {
+            self.stats.misses_by_caller[caller].swap(0, Ordering::Relaxed)
+        });
+
         ReadOnlyCacheStats {
             hits,
             misses,
@@ -272,9 +562,39 @@ impl ReadOnlyAccountsCache {
             evict_us,
             evictor_wakeup_count_all,
             evictor_wakeup_count_productive,
+            admission_rejects,
+            hits_by_caller,
+            misses_by_caller,
         }
     }
 
+    /// Returns the per-owner statistics of the `max_owners` owners with the
+    /// most hits, and resets all per-owner statistics.
+    ///
+    /// Returns an empty vector if per-owner statistics are not enabled.
+    pub(crate) fn get_and_reset_owner_stats(
+        &self,
+        max_owners: usize,
+    ) -> Vec<ReadOnlyCacheOwnerStats> {
+        let Some(owner_stats) = &self.owner_stats else {
+            return Vec::new();
+        };
+        let mut stats: Vec<_> = owner_stats
+            .iter()
+            .map(|entry| ReadOnlyCacheOwnerStats {
+                owner: *entry.key(),
+                hits: entry.hits.load(Ordering::Relaxed),
+                stores: entry.stores.load(Ordering::Relaxed),
+                admission_rejects: entry.admission_rejects.load(Ordering::Relaxed),
+            })
+            .collect();
+        owner_stats.clear();
+
+        stats.sort_unstable_by(|a, b| b.hits.cmp(&a.hits).then(b.stores.cmp(&a.stores)));
+        stats.truncate(max_owners);
+        stats
+    }
+
     /// Spawns the background thread to handle evictions
     fn spawn_evictor(
         exit: Arc<AtomicBool>,
@@ -415,8 +735,7 @@ impl ReadOnlyAccountsCache {
         R: Rng,
         C: FnMut(&Pubkey, Option<ReadOnlyAccountCacheEntry>),
     {
-        #[allow(clippy::used_underscore_binding)]
-        let target_data_size = self._max_data_size_lo;
+        let target_data_size = self.max_data_size_lo;
         Self::evict(
             target_data_size,
             &self.data_size,
@@ -493,6 +812,8 @@ mod tests {
             MAX_CACHE_SIZE,
             usize::MAX, // <-- do not evict in the background
             evict_sample_size,
+            ReadCacheAdmissionPolicy::Always,
+            false,
         );
         let slots: Vec<Slot> = repeat_with(|| rng.random_range(0..1000)).take(5).collect();
         let pubkeys: Vec<Pubkey> = repeat_with(|| {
@@ -554,7 +875,13 @@ mod tests {
         const ACCOUNT_DATA_SIZE: usize = 200;
         const MAX_ENTRIES: usize = 7;
         const MAX_CACHE_SIZE: usize = MAX_ENTRIES * (CACHE_ENTRY_SIZE + ACCOUNT_DATA_SIZE);
-        let cache = ReadOnlyAccountsCache::new(MAX_CACHE_SIZE, MAX_CACHE_SIZE, evict_sample_size);
+        let cache = ReadOnlyAccountsCache::new(
+            MAX_CACHE_SIZE,
+            MAX_CACHE_SIZE,
+            evict_sample_size,
+            ReadCacheAdmissionPolicy::Always,
+            false,
+        );
 
         for i in 0..MAX_ENTRIES {
             let pubkey = Pubkey::new_unique();
@@ -586,4 +913,132 @@ mod tests {
         assert_eq!(cache.cache_len(), MAX_ENTRIES);
         assert_eq!(cache.data_size(), MAX_CACHE_SIZE);
     }
+
+    #[test]
+    fn test_frequency_sketch() {
+        let sketch = FrequencySketch::new(FREQUENCY_SKETCH_MIN_WIDTH);
+        let hot = Pubkey::new_unique();
+        let cold = Pubkey::new_unique();
+
+        for _ in 0..5 {
+            sketch.increment(&hot);
+        }
+        sketch.increment(&cold);
+        assert!(sketch.estimate(&hot) >= 5);
+        assert!(sketch.estimate(&cold) >= 1);
+        assert!(sketch.estimate(&hot) > sketch.estimate(&cold));
+
+        // counters saturate
+        for _ in 0..100 {
+            sketch.increment(&hot);
+        }
+        assert_eq!(sketch.estimate(&hot), FREQUENCY_SKETCH_MAX_COUNT);
+
+        // aging halves the counters
+        sketch.age();
+        assert_eq!(sketch.estimate(&hot), FREQUENCY_SKETCH_MAX_COUNT / 2);
+        assert_eq!(sketch.estimate(&cold), 0);
+    }
+
+    #[test]
+    fn test_frequency_admission_policy() {
+        const DATA_SIZE: usize = 100;
+        const NUM_HOT_ACCOUNTS: usize = 3;
+        const MAX_CACHE_SIZE: usize = NUM_HOT_ACCOUNTS * (CACHE_ENTRY_SIZE + DATA_SIZE);
+        let cache = ReadOnlyAccountsCache::new(
+            MAX_CACHE_SIZE,
+            usize::MAX, // <-- do not evict in the background
+            NUM_HOT_ACCOUNTS,
+            ReadCacheAdmissionPolicy::Frequency,
+            false,
+        );
+        let slot = 0;
+        let new_account = || AccountSharedData::new(1, DATA_SIZE, &Pubkey::default());
+
+        // fill the cache with accounts that are loaded often
+        let hot_pubkeys: Vec<_> = repeat_with(Pubkey::new_unique)
+            .take(NUM_HOT_ACCOUNTS)
+            .collect();
+        for pubkey in &hot_pubkeys {
+            for _ in 0..5 {
+                assert!(cache.load(*pubkey, slot).is_none());
+            }
+            cache.store(*pubkey, slot, new_account());
+        }
+        assert_eq!(cache.cache_len(), NUM_HOT_ACCOUNTS);
+
+        // accounts loaded once, e.g. by a scan, are not admitted
+        for _ in 0..10 {
+            let pubkey = Pubkey::new_unique();
+            assert!(cache.load(pubkey, slot).is_none());
+            cache.store(pubkey, slot, new_account());
+            assert!(!cache.cache.contains_key(&pubkey));
+        }
+        assert_eq!(cache.cache_len(), NUM_HOT_ACCOUNTS);
+        for pubkey in &hot_pubkeys {
+            assert!(cache.load(*pubkey, slot).is_some());
+        }
+
+        // updates of cached accounts are always admitted
+        cache.store(hot_pubkeys[0], slot + 1, new_account());
+        assert!(cache.load(hot_pubkeys[0], slot + 1).is_some());
+
+        // an account loaded more often than the cached ones is admitted
+        let pubkey = Pubkey::new_unique();
+        for _ in 0..FREQUENCY_SKETCH_MAX_COUNT {
+            assert!(cache.load(pubkey, slot).is_none());
+        }
+        cache.store(pubkey, slot, new_account());
+        assert!(cache.load(pubkey, slot).is_some());
+
+        let stats = cache.get_and_reset_stats();
+        assert_eq!(stats.admission_rejects, 10);
+    }
+
+    #[test]
+    fn test_stats_by_caller_and_owner() {
+        let cache = ReadOnlyAccountsCache::new(
+            usize::MAX,
+            usize::MAX,
+            16,
+            ReadCacheAdmissionPolicy::Always,
+            true,
+        );
+        let slot = 0;
+        let owner = Pubkey::new_unique();
+        let pubkey = Pubkey::new_unique();
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                ReadCacheCaller::Rpc.set_for_current_thread();
+                assert!(cache.load(pubkey, slot).is_none());
+                cache.store(pubkey, slot, AccountSharedData::new(1, 0, &owner));
+                assert!(cache.load(pubkey, slot).is_some());
+            });
+        });
+        assert_eq!(ReadCacheCaller::current(), ReadCacheCaller::Other);
+        assert!(cache.load(pubkey, slot).is_some());
+
+        let stats = cache.get_and_reset_stats();
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits_by(ReadCacheCaller::Rpc), 1);
+        assert_eq!(stats.misses_by(ReadCacheCaller::Rpc), 1);
+        assert_eq!(stats.hits_by(ReadCacheCaller::Other), 1);
+        assert_eq!(stats.misses_by(ReadCacheCaller::Other), 0);
+        assert_eq!(stats.hits_by(ReadCacheCaller::Replay), 0);
+        assert_eq!(stats.hits_by(ReadCacheCaller::Banking), 0);
+
+        let owner_stats = cache.get_and_reset_owner_stats(10);
+        assert_eq!(
+            owner_stats,
+            vec![ReadOnlyCacheOwnerStats {
+                owner,
+                hits: 2,
+                stores: 1,
+                admission_rejects: 0,
+            }],
+        );
+        assert!(cache.get_and_reset_owner_stats(10).is_empty());
+    }
 }
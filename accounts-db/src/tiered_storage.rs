@@ -16,7 +16,7 @@ use {
     crate::{accounts_file::StoredAccountsInfo, storable_accounts::StorableAccounts},
     error::TieredStorageError,
     footer::{AccountBlockFormat, AccountMetaFormat},
-    hot::{HotStorageWriter, HOT_FORMAT},
+    hot::{HotStorageWriter, HOT_COMPRESSED_FORMAT, HOT_FORMAT},
     index::IndexBlockFormat,
     owners::OwnersBlockFormat,
     readable::TieredStorageReader,
@@ -54,6 +54,8 @@ pub struct TieredStorage {
     already_written: AtomicBool,
     /// The path to the file that stores accounts.
     path: PathBuf,
+    /// The format of the accounts file.
+    format: TieredStorageFormat,
 }
 
 impl Drop for TieredStorage {
@@ -77,11 +79,12 @@ impl TieredStorage {
     ///
     /// Note that the actual file will not be created until write_accounts
     /// is called.
-    pub fn new_writable(path: impl Into<PathBuf>) -> Self {
+    pub fn new_writable(path: impl Into<PathBuf>, format: TieredStorageFormat) -> Self {
         Self {
             reader: OnceLock::<TieredStorageReader>::new(),
             already_written: false.into(),
             path: path.into(),
+            format,
         }
     }
 
@@ -89,10 +92,20 @@ impl TieredStorage {
     /// specified path.
     pub fn new_readonly(path: impl Into<PathBuf>) -> TieredStorageResult<Self> {
         let path = path.into();
+        let reader = TieredStorageReader::new_from_path(&path)?;
+        let footer = reader.footer();
+        let format = TieredStorageFormat {
+            meta_entry_size: footer.account_meta_entry_size as usize,
+            account_meta_format: footer.account_meta_format,
+            owners_block_format: footer.owners_block_format,
+            index_block_format: footer.index_block_format,
+            account_block_format: footer.account_block_format,
+        };
         Ok(Self {
-            reader: TieredStorageReader::new_from_path(&path).map(OnceLock::from)?,
+            reader: OnceLock::from(reader),
             already_written: true.into(),
             path,
+            format,
         })
     }
 
@@ -101,7 +114,13 @@ impl TieredStorage {
         self.path.as_path()
     }
 
-    /// Writes the specified accounts into this TieredStorage.
+    /// Returns the format of this TieredStorage.
+    pub fn format(&self) -> &TieredStorageFormat {
+        &self.format
+    }
+
+    /// Writes the specified accounts into this TieredStorage using its
+    /// format.
     ///
     /// Note that this function can only be called once per a TieredStorage
     /// instance.  Otherwise, it will trigger panic.
@@ -109,17 +128,17 @@ impl TieredStorage {
         &self,
         accounts: &impl StorableAccounts<'a>,
         skip: usize,
-        format: &TieredStorageFormat,
     ) -> TieredStorageResult<StoredAccountsInfo> {
+        let format = &self.format;
         let was_written = self.already_written.swap(true, Ordering::AcqRel);
 
         if was_written {
             panic!("cannot write same tiered storage file more than once");
         }
 
-        if format == &HOT_FORMAT {
+        if format == &HOT_FORMAT || format == &HOT_COMPRESSED_FORMAT {
             let stored_accounts_info = {
-                let mut writer = HotStorageWriter::new(&self.path)?;
+                let mut writer = HotStorageWriter::new_with_format(&self.path, format)?;
                 let stored_accounts_info = writer.write_accounts(accounts, skip)?;
                 writer.flush()?;
                 stored_accounts_info
@@ -177,7 +196,7 @@ mod tests {
         super::*,
         file::TieredStorageMagicNumber,
         footer::TieredStorageFooter,
-        hot::HOT_FORMAT,
+        hot::{HOT_COMPRESSED_FORMAT, HOT_FORMAT},
         solana_account::AccountSharedData,
         solana_clock::Slot,
         solana_pubkey::Pubkey,
@@ -206,7 +225,7 @@ mod tests {
         let account_refs = Vec::<(&Pubkey, &AccountSharedData)>::new();
         let storable_accounts = (slot_ignored, account_refs.as_slice());
 
-        let result = tiered_storage.write_accounts(&storable_accounts, 0, &HOT_FORMAT);
+        let result = tiered_storage.write_accounts(&storable_accounts, 0);
 
         match (&result, &expected_result) {
             (
@@ -236,8 +255,10 @@ mod tests {
         let tiered_storage_path = temp_dir.path().join("test_new_meta_file_only");
 
         {
-            let tiered_storage =
-                ManuallyDrop::new(TieredStorage::new_writable(&tiered_storage_path));
+            let tiered_storage = ManuallyDrop::new(TieredStorage::new_writable(
+                &tiered_storage_path,
+                HOT_FORMAT,
+            ));
 
             assert!(!tiered_storage.is_read_only());
             assert_eq!(tiered_storage.path(), tiered_storage_path);
@@ -274,7 +295,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let tiered_storage_path = temp_dir.path().join("test_write_accounts_twice");
 
-        let tiered_storage = TieredStorage::new_writable(&tiered_storage_path);
+        let tiered_storage = TieredStorage::new_writable(&tiered_storage_path, HOT_FORMAT);
         write_zero_accounts(
             &tiered_storage,
             Ok(StoredAccountsInfo {
@@ -298,7 +319,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let tiered_storage_path = temp_dir.path().join("test_remove_on_drop");
         {
-            let tiered_storage = TieredStorage::new_writable(&tiered_storage_path);
+            let tiered_storage = TieredStorage::new_writable(&tiered_storage_path, HOT_FORMAT);
             write_zero_accounts(
                 &tiered_storage,
                 Ok(StoredAccountsInfo {
@@ -311,8 +332,10 @@ mod tests {
         assert!(!tiered_storage_path.try_exists().unwrap());
 
         {
-            let tiered_storage =
-                ManuallyDrop::new(TieredStorage::new_writable(&tiered_storage_path));
+            let tiered_storage = ManuallyDrop::new(TieredStorage::new_writable(
+                &tiered_storage_path,
+                HOT_FORMAT,
+            ));
             write_zero_accounts(
                 &tiered_storage,
                 Ok(StoredAccountsInfo {
@@ -356,8 +379,8 @@ mod tests {
 
         let temp_dir = tempdir().unwrap();
         let tiered_storage_path = temp_dir.path().join(path_suffix);
-        let tiered_storage = TieredStorage::new_writable(tiered_storage_path);
-        _ = tiered_storage.write_accounts(&storable_accounts, 0, &format);
+        let tiered_storage = TieredStorage::new_writable(tiered_storage_path, format);
+        _ = tiered_storage.write_accounts(&storable_accounts, 0);
 
         let reader = tiered_storage.reader().unwrap();
         let num_accounts = storable_accounts.len();
@@ -432,4 +455,24 @@ mod tests {
             HOT_FORMAT.clone(),
         );
     }
+
+    #[test]
+    fn test_write_accounts_compressed_one_max_len() {
+        do_test_write_accounts(
+            "test_write_accounts_compressed_one_max_len",
+            &[MAX_PERMITTED_DATA_LENGTH],
+            HOT_COMPRESSED_FORMAT.clone(),
+        );
+    }
+
+    #[test]
+    fn test_write_accounts_compressed_mixed_size() {
+        do_test_write_accounts(
+            "test_write_accounts_compressed_mixed_size",
+            &[
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1000, 2000, 3000, 4000, 9, 8, 7, 6, 5, 4, 3, 2, 1,
+            ],
+            HOT_COMPRESSED_FORMAT.clone(),
+        );
+    }
 }
@@ -34,4 +34,10 @@ pub enum TieredStorageError {
 
     #[error("failed to flush hot storage writer: {0}")]
     FlushHotWriter(#[source] std::io::Error),
+
+    #[error("InvalidCompressedAccountData: compressed account data of {0} bytes is too short")]
+    InvalidCompressedAccountData(usize),
+
+    #[error("DecompressedSizeMismatch: expected {0} bytes, found {1}")]
+    DecompressedSizeMismatch(usize, usize),
 }
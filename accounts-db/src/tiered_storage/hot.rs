@@ -6,7 +6,7 @@ use {
         account_storage::stored_account_info::{StoredAccountInfo, StoredAccountInfoWithoutData},
         accounts_file::StoredAccountsInfo,
         tiered_storage::{
-            byte_block,
+            byte_block::{self, ByteBlockReader, ByteBlockWriter},
             file::{TieredReadableFile, TieredWritableFile},
             footer::{AccountBlockFormat, AccountMetaFormat, TieredStorageFooter},
             index::{AccountIndexWriterEntry, AccountOffset, IndexBlockFormat, IndexOffset},
@@ -24,7 +24,7 @@ use {
     solana_account::{AccountSharedData, ReadableAccount},
     solana_clock::Epoch,
     solana_pubkey::Pubkey,
-    std::{io::Write, option::Option, path::Path, sync::Arc},
+    std::{borrow::Cow, io::Write, option::Option, path::Path, sync::Arc},
 };
 
 /// When rent is collected from an exempt account, rent_epoch is set to this
@@ -45,15 +45,29 @@ pub const HOT_FORMAT: TieredStorageFormat = TieredStorageFormat {
     account_block_format: AccountBlockFormat::AlignedRaw,
 };
 
+/// The hot format that lz4-compresses the data of large accounts.
+///
+/// Accounts whose data is smaller than HOT_COMPRESSION_MIN_DATA_LEN, or
+/// whose data does not shrink when compressed, are stored exactly as in
+/// HOT_FORMAT so that they remain zero-copy readable.
+pub const HOT_COMPRESSED_FORMAT: TieredStorageFormat = TieredStorageFormat {
+    account_block_format: AccountBlockFormat::Lz4,
+    ..HOT_FORMAT
+};
+
+/// The minimum account data length for which compression is attempted
+/// when writing with HOT_COMPRESSED_FORMAT.
+pub const HOT_COMPRESSION_MIN_DATA_LEN: usize = 1024;
+
 /// A helper function that creates a new default footer for hot
 /// accounts storage.
-fn new_hot_footer() -> TieredStorageFooter {
+fn new_hot_footer(format: &TieredStorageFormat) -> TieredStorageFooter {
     TieredStorageFooter {
-        account_meta_format: HOT_FORMAT.account_meta_format,
-        account_meta_entry_size: HOT_FORMAT.meta_entry_size as u32,
-        account_block_format: HOT_FORMAT.account_block_format,
-        index_block_format: HOT_FORMAT.index_block_format,
-        owners_block_format: HOT_FORMAT.owners_block_format,
+        account_meta_format: format.account_meta_format,
+        account_meta_entry_size: format.meta_entry_size as u32,
+        account_block_format: format.account_block_format,
+        index_block_format: format.index_block_format,
+        owners_block_format: format.owners_block_format,
         ..TieredStorageFooter::default()
     }
 }
@@ -280,15 +294,56 @@ impl TieredAccountMeta for HotAccountMeta {
 
     /// Returns the length of the data associated to this account based on the
     /// specified account block.
+    ///
+    /// For a compressed account, this is the length of the decompressed data.
     fn account_data_size(&self, account_block: &[u8]) -> usize {
-        self.optional_fields_offset(account_block)
-            .saturating_sub(self.account_data_padding() as usize)
+        if self.flags.is_compressed() {
+            byte_block::read_pod::<u64>(account_block, 0).map_or(0, |len| *len as usize)
+        } else {
+            self.stored_data_size(account_block)
+        }
     }
 
     /// Returns the data associated to this account based on the specified
-    /// account block.
-    fn account_data<'a>(&self, account_block: &'a [u8]) -> &'a [u8] {
-        &account_block[..self.account_data_size(account_block)]
+    /// account block, decompressing it if needed.
+    ///
+    /// The data of an uncompressed account is borrowed from the account block
+    /// without copying.
+    fn account_data<'a>(&self, account_block: &'a [u8]) -> TieredStorageResult<Cow<'a, [u8]>> {
+        let stored_data_size = self.stored_data_size(account_block);
+        if !self.flags.is_compressed() {
+            return Ok(Cow::Borrowed(&account_block[..stored_data_size]));
+        }
+
+        // A compressed account stores the length of its decompressed data
+        // followed by the lz4-encoded data.
+        let header_len = std::mem::size_of::<u64>();
+        if stored_data_size < header_len {
+            return Err(TieredStorageError::InvalidCompressedAccountData(
+                stored_data_size,
+            ));
+        }
+        let expected_len = self.account_data_size(account_block);
+        let data = ByteBlockReader::decode(
+            AccountBlockFormat::Lz4,
+            &account_block[header_len..stored_data_size],
+        )?;
+        if data.len() != expected_len {
+            return Err(TieredStorageError::DecompressedSizeMismatch(
+                expected_len,
+                data.len(),
+            ));
+        }
+        Ok(Cow::Owned(data))
+    }
+}
+
+impl HotAccountMeta {
+    /// Returns the number of bytes the account data occupies inside the
+    /// specified account block, excluding padding and optional fields.
+    fn stored_data_size(&self, account_block: &[u8]) -> usize {
+        self.optional_fields_offset(account_block)
+            .saturating_sub(self.account_data_padding() as usize)
     }
 }
 
@@ -319,30 +374,36 @@ impl<'accounts_file, M: TieredAccountMeta> HotAccount<'accounts_file, M> {
         self.index
     }
 
-    /// Returns the data associated to this account.
-    pub fn data(&self) -> &'accounts_file [u8] {
+    /// Returns the data associated to this account, decompressing it if
+    /// needed.
+    pub fn data(&self) -> TieredStorageResult<Cow<'accounts_file, [u8]>> {
         self.meta.account_data(self.account_block)
     }
 
     /// Returns the approximate stored size of this account.
+    ///
+    /// For a compressed account, this is based on the length of the
+    /// compressed data, which is what the account occupies in the file.
     pub fn stored_size(&self) -> usize {
-        stored_size(self.meta.account_data_size(self.account_block))
+        let stored_data_size = self
+            .meta
+            .optional_fields_offset(self.account_block)
+            .saturating_sub(self.meta.account_data_padding() as usize);
+        stored_size(stored_data_size)
     }
-}
 
-impl<'accounts_file, M: TieredAccountMeta> ReadableAccount for HotAccount<'accounts_file, M> {
     /// Returns the balance of the lamports of this account.
-    fn lamports(&self) -> u64 {
+    pub fn lamports(&self) -> u64 {
         self.meta.lamports()
     }
 
     /// Returns the address of the owner of this account.
-    fn owner(&self) -> &'accounts_file Pubkey {
+    pub fn owner(&self) -> &'accounts_file Pubkey {
         self.owner
     }
 
     /// Returns true if the data associated to this account is executable.
-    fn executable(&self) -> bool {
+    pub fn executable(&self) -> bool {
         self.meta.flags().executable()
     }
 
@@ -352,14 +413,9 @@ impl<'accounts_file, M: TieredAccountMeta> ReadableAccount for HotAccount<'accou
     ///
     /// For a zero-lamport account, Epoch::default() will be returned to
     /// default states of an AccountSharedData.
-    fn rent_epoch(&self) -> Epoch {
+    pub fn rent_epoch(&self) -> Epoch {
         self.meta.final_rent_epoch(self.account_block)
     }
-
-    /// Returns the data associated to this account.
-    fn data(&self) -> &'accounts_file [u8] {
-        self.data()
-    }
 }
 
 /// The reader to a hot accounts file.
@@ -550,11 +606,13 @@ impl HotStorageReader {
         let meta = self.get_account_meta_from_offset(account_offset)?;
         let account_block = self.get_account_block(account_offset, index_offset)?;
 
+        let data = meta.account_data(account_block)?;
+
         let stored_account = StoredAccountInfo {
             pubkey: self.get_account_address(index_offset)?,
             lamports: meta.lamports(),
             owner: self.get_owner_address(meta.owner_offset())?,
-            data: meta.account_data(account_block),
+            data: &data,
             executable: meta.flags().executable(),
             rent_epoch: meta.final_rent_epoch(account_block),
         };
@@ -577,7 +635,7 @@ impl HotStorageReader {
         let account_block = self.get_account_block(account_offset, index_offset)?;
 
         let lamports = meta.lamports();
-        let data = Arc::new(meta.account_data(account_block).to_vec());
+        let data = Arc::new(meta.account_data(account_block)?.into_owned());
         let owner = *self.get_owner_address(meta.owner_offset())?;
         let executable = meta.flags().executable();
         let rent_epoch = meta.final_rent_epoch(account_block);
@@ -683,17 +741,51 @@ fn write_optional_fields(
     Ok(size)
 }
 
+/// Returns the lz4-encoded form of `data`, prefixed by the length of `data`,
+/// if `data` is large enough to be compressed and actually shrinks.
+fn compress_account_data(data: &[u8]) -> TieredStorageResult<Option<Vec<u8>>> {
+    if data.len() < HOT_COMPRESSION_MIN_DATA_LEN {
+        return Ok(None);
+    }
+
+    let mut writer = ByteBlockWriter::new(AccountBlockFormat::Lz4);
+    writer.write(data)?;
+    let encoded = writer.finish()?;
+
+    // The length is kept uncompressed so that data_len can be obtained
+    // without decompressing the account data.
+    let mut compressed = Vec::with_capacity(std::mem::size_of::<u64>() + encoded.len());
+    compressed.extend_from_slice(bytemuck::bytes_of(&(data.len() as u64)));
+    compressed.extend_from_slice(&encoded);
+    Ok((compressed.len() < data.len()).then_some(compressed))
+}
+
 /// The writer that creates a hot accounts file.
 #[derive(Debug)]
 pub struct HotStorageWriter {
     storage: TieredWritableFile,
+    format: TieredStorageFormat,
 }
 
 impl HotStorageWriter {
     /// Create a new HotStorageWriter with the specified path.
     pub fn new(file_path: impl AsRef<Path>) -> TieredStorageResult<Self> {
+        Self::new_with_format(file_path, &HOT_FORMAT)
+    }
+
+    /// Create a new HotStorageWriter with the specified path and hot format,
+    /// which must be either HOT_FORMAT or HOT_COMPRESSED_FORMAT.
+    pub fn new_with_format(
+        file_path: impl AsRef<Path>,
+        format: &TieredStorageFormat,
+    ) -> TieredStorageResult<Self> {
+        let file_path = file_path.as_ref();
+        if format != &HOT_FORMAT && format != &HOT_COMPRESSED_FORMAT {
+            return Err(TieredStorageError::UnknownFormat(file_path.to_path_buf()));
+        }
         Ok(Self {
             storage: TieredWritableFile::new(file_path)?,
+            format: format.clone(),
         })
     }
 
@@ -712,7 +804,14 @@ impl HotStorageWriter {
         let mut flags = AccountMetaFlags::new_from(&optional_fields);
         flags.set_executable(executable);
 
-        let padding_len = padding_bytes(account_data.len());
+        let compressed_data = match self.format.account_block_format {
+            AccountBlockFormat::AlignedRaw => None,
+            AccountBlockFormat::Lz4 => compress_account_data(account_data)?,
+        };
+        flags.set_is_compressed(compressed_data.is_some());
+        let stored_data = compressed_data.as_deref().unwrap_or(account_data);
+
+        let padding_len = padding_bytes(stored_data.len());
         let meta = HotAccountMeta::new()
             .with_lamports(lamports)
             .with_owner_offset(owner_offset)
@@ -723,7 +822,7 @@ impl HotStorageWriter {
         let mut stored_size = 0;
 
         stored_size += self.storage.write_pod(&meta)?;
-        stored_size += self.storage.write_bytes(account_data)?;
+        stored_size += self.storage.write_bytes(stored_data)?;
         stored_size += self
             .storage
             .write_bytes(&PADDING_BUFFER[0..(padding_len as usize)])?;
@@ -740,7 +839,7 @@ impl HotStorageWriter {
         accounts: &impl StorableAccounts<'a>,
         skip: usize,
     ) -> TieredStorageResult<StoredAccountsInfo> {
-        let mut footer = new_hot_footer();
+        let mut footer = new_hot_footer(&self.format);
         let mut index = vec![];
         let mut owners_table = OwnersTable::default();
         let mut cursor = 0;
@@ -1107,7 +1206,7 @@ mod tests {
                 .saturating_sub(AccountMetaOptionalFields::size_from_flags(&flags))
         );
         assert_eq!(account_data.len(), meta.account_data_size(account_block));
-        assert_eq!(account_data, meta.account_data(account_block));
+        assert_eq!(account_data, &*meta.account_data(account_block).unwrap());
         assert_eq!(meta.rent_epoch(account_block), optional_fields.rent_epoch);
     }
 
@@ -1537,4 +1636,124 @@ mod tests {
         assert!(!hot_storage.is_empty());
         assert_eq!(expected_size, hot_storage.len());
     }
+
+    #[test]
+    fn test_write_compressed_accounts() {
+        let account_data_sizes = &[
+            0,
+            1,
+            8,
+            HOT_COMPRESSION_MIN_DATA_LEN as u64 - 1,
+            HOT_COMPRESSION_MIN_DATA_LEN as u64,
+            4000,
+            100_000,
+            7,
+        ];
+        let mut accounts: Vec<_> = account_data_sizes
+            .iter()
+            .map(|size| create_test_account(*size))
+            .collect();
+
+        // random data does not shrink when compressed, so it is stored as-is
+        let mut rng = rand::rng();
+        let random_data: Vec<u8> = (0..8000).map(|_| rng.random()).collect();
+        let (_, mut random_account) = create_test_account(8000);
+        random_account.set_data_from_slice(&random_data);
+        accounts.push((Pubkey::new_unique(), random_account));
+
+        // Slot information is not used here
+        let storable_accounts = (Slot::MAX, &accounts[..]);
+
+        let write_file = |format: &TieredStorageFormat, path: &Path| {
+            let mut writer = HotStorageWriter::new_with_format(path, format).unwrap();
+            let stored_accounts_info = writer.write_accounts(&storable_accounts, 0).unwrap();
+            writer.flush().unwrap();
+            stored_accounts_info
+        };
+
+        let temp_dir = TempDir::new().unwrap();
+        let raw_path = temp_dir.path().join("raw");
+        let compressed_path = temp_dir.path().join("compressed");
+        let raw_info = write_file(&HOT_FORMAT, &raw_path);
+        let compressed_info = write_file(&HOT_COMPRESSED_FORMAT, &compressed_path);
+        assert!(compressed_info.size < raw_info.size);
+
+        let file = TieredReadableFile::new(&compressed_path).unwrap();
+        let hot_storage = HotStorageReader::new(file).unwrap();
+        assert_eq!(
+            hot_storage.footer().account_block_format,
+            AccountBlockFormat::Lz4
+        );
+
+        for (i, (_, expected_account)) in accounts.iter().enumerate() {
+            let index_offset = IndexOffset(i as u32);
+            let account_offset = hot_storage.get_account_offset(index_offset).unwrap();
+            let meta = hot_storage
+                .get_account_meta_from_offset(account_offset)
+                .unwrap();
+            let expected_compressed = expected_account.data().len() >= HOT_COMPRESSION_MIN_DATA_LEN
+                && expected_account.data() != random_data.as_slice();
+            assert_eq!(meta.flags().is_compressed(), expected_compressed);
+
+            let hot_account = HotAccount {
+                meta,
+                address: &Pubkey::default(),
+                owner: &Pubkey::default(),
+                index: index_offset,
+                account_block: hot_storage
+                    .get_account_block(account_offset, index_offset)
+                    .unwrap(),
+            };
+            let uncompressed_stored_size = stored_size(expected_account.data().len());
+            if expected_compressed {
+                assert!(hot_account.stored_size() < uncompressed_stored_size);
+            } else {
+                assert_eq!(hot_account.stored_size(), uncompressed_stored_size);
+            }
+
+            hot_storage
+                .get_stored_account_callback(index_offset, |stored_account| {
+                    storable_accounts.account_default_if_zero_lamport(i, |account| {
+                        verify_test_account(
+                            &stored_account,
+                            &account.take_account(),
+                            account.pubkey(),
+                        );
+                    });
+                })
+                .unwrap()
+                .unwrap();
+
+            let data_len = hot_storage
+                .get_stored_account_without_data_callback(index_offset, |stored_account| {
+                    stored_account.data_len
+                })
+                .unwrap()
+                .unwrap();
+            let shared_account = hot_storage
+                .get_account_shared_data(index_offset)
+                .unwrap()
+                .unwrap();
+            storable_accounts.account_default_if_zero_lamport(i, |account| {
+                assert_eq!(data_len, account.data().len());
+                assert_eq!(shared_account.data(), account.data());
+            });
+        }
+    }
+
+    #[test]
+    fn test_hot_storage_writer_unknown_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir
+            .path()
+            .join("test_hot_storage_writer_unknown_format");
+        let format = TieredStorageFormat {
+            meta_entry_size: 0,
+            ..HOT_FORMAT
+        };
+        assert_matches!(
+            HotStorageWriter::new_with_format(&path, &format),
+            Err(TieredStorageError::UnknownFormat(_))
+        );
+    }
 }
@@ -1,11 +1,12 @@
 //! The account meta and related structs for the tiered storage.
 
 use {
-    crate::tiered_storage::owners::OwnerOffset,
+    crate::tiered_storage::{owners::OwnerOffset, TieredStorageResult},
     bytemuck_derive::{Pod, Zeroable},
     modular_bitfield::prelude::*,
     solana_clock::Epoch,
     solana_pubkey::Pubkey,
+    std::borrow::Cow,
 };
 
 /// The struct that handles the account meta flags.
@@ -17,8 +18,10 @@ pub struct AccountMetaFlags {
     pub has_rent_epoch: bool,
     /// whether the account is executable
     pub executable: bool,
+    /// whether the account data is stored compressed
+    pub is_compressed: bool,
     /// the reserved bits.
-    reserved: B30,
+    reserved: B29,
 }
 
 // Ensure there are no implicit padding bytes
@@ -86,8 +89,8 @@ pub trait TieredAccountMeta: Sized {
     fn account_data_size(&self, _account_block: &[u8]) -> usize;
 
     /// Returns the data associated to this account based on the specified
-    /// account block.
-    fn account_data<'a>(&self, _account_block: &'a [u8]) -> &'a [u8];
+    /// account block, decoding it if it is stored compressed.
+    fn account_data<'a>(&self, _account_block: &'a [u8]) -> TieredStorageResult<Cow<'a, [u8]>>;
 }
 
 impl AccountMetaFlags {
@@ -174,6 +177,8 @@ mod tests {
         let flags = AccountMetaFlags::new();
 
         assert!(!flags.has_rent_epoch());
+        assert!(!flags.executable());
+        assert!(!flags.is_compressed());
         assert_eq!(flags.reserved(), 0u32);
 
         assert_eq!(
@@ -201,6 +206,12 @@ mod tests {
         assert!(flags.executable());
         verify_flags_serialization(&flags);
 
+        flags.set_is_compressed(true);
+        assert!(flags.has_rent_epoch());
+        assert!(flags.executable());
+        assert!(flags.is_compressed());
+        verify_flags_serialization(&flags);
+
         // make sure the reserved bits are untouched.
         assert_eq!(flags.reserved(), 0u32);
     }
@@ -1,7 +1,9 @@
 use {
     rand::{rngs::SmallRng, SeedableRng},
     solana_account::{Account, AccountSharedData},
-    solana_accounts_db::read_only_accounts_cache::{ReadOnlyAccountsCache, CACHE_ENTRY_SIZE},
+    solana_accounts_db::read_only_accounts_cache::{
+        ReadCacheAdmissionPolicy, ReadOnlyAccountsCache, CACHE_ENTRY_SIZE,
+    },
     solana_pubkey::Pubkey,
     std::{collections::HashSet, sync::atomic::Ordering},
     test_case::test_matrix,
@@ -28,6 +30,8 @@ fn test_read_only_accounts_cache_eviction(num_accounts: (usize, usize), evict_sa
         max_cache_size,
         usize::MAX, // <-- do not evict in the background
         evict_sample_size,
+        ReadCacheAdmissionPolicy::default(),
+        false,
     );
     let data = vec![0u8; DATA_SIZE];
     let mut newer_half = HashSet::new();
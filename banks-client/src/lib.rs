@@ -8,14 +8,21 @@
 
 pub use {
     crate::error::BanksClientError,
-    solana_banks_interface::{BanksClient as TarpcClient, TransactionStatus},
+    solana_banks_interface::{
+        AccountNotification, BanksClient as TarpcClient, LogsFilter, LogsNotification,
+        ProgramAccountsFilter, SlotNotification, SubscriptionId, TransactionStatus,
+        MAX_MULTIPLE_ACCOUNTS,
+    },
 };
 use {
     borsh::BorshDeserialize,
-    futures::future::join_all,
+    futures::{
+        future::join_all,
+        stream::{self, BoxStream, Stream, StreamExt},
+    },
     solana_account::{from_account, Account},
     solana_banks_interface::{
-        BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
+        BanksNotification, BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
         BanksTransactionResultWithSimulation,
     },
     solana_clock::Slot,
@@ -28,13 +35,18 @@ use {
     solana_signature::Signature,
     solana_sysvar::SysvarSerialize,
     solana_transaction::versioned::VersionedTransaction,
+    std::{
+        collections::VecDeque,
+        pin::Pin,
+        task::{Context as TaskContext, Poll},
+    },
     tarpc::{
         client::{self, NewClient, RequestDispatch},
         context::{self, Context},
         serde_transport::tcp,
         ClientMessage, Response, Transport,
     },
-    tokio::net::ToSocketAddrs,
+    tokio::{net::ToSocketAddrs, runtime::Handle},
     tokio_serde::formats::Bincode,
 };
 
@@ -171,6 +183,31 @@ impl BanksClient {
             .map_err(Into::into)
     }
 
+    pub async fn get_multiple_accounts_with_commitment_and_context(
+        &self,
+        ctx: Context,
+        addresses: Vec<Pubkey>,
+        commitment: CommitmentLevel,
+    ) -> Result<Option<Vec<Option<Account>>>, BanksClientError> {
+        self.inner
+            .get_multiple_accounts_with_commitment_and_context(ctx, addresses, commitment)
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn get_program_accounts_with_commitment_and_context(
+        &self,
+        ctx: Context,
+        program_id: Pubkey,
+        filters: Vec<ProgramAccountsFilter>,
+        commitment: CommitmentLevel,
+    ) -> Result<Option<Vec<(Pubkey, Account)>>, BanksClientError> {
+        self.inner
+            .get_program_accounts_with_commitment_and_context(ctx, program_id, filters, commitment)
+            .await
+            .map_err(Into::into)
+    }
+
     /// Send a transaction and return immediately. The server will resend the
     /// transaction until either it is accepted by the cluster or the transaction's
     /// blockhash expires.
@@ -367,6 +404,65 @@ impl BanksClient {
             .await
     }
 
+    /// Return the accounts at the given addresses at the slot corresponding to the given
+    /// commitment level, in the same order. Accounts that are not found are None.
+    /// At most `MAX_MULTIPLE_ACCOUNTS` addresses may be requested at once.
+    pub async fn get_multiple_accounts_with_commitment(
+        &self,
+        addresses: Vec<Pubkey>,
+        commitment: CommitmentLevel,
+    ) -> Result<Vec<Option<Account>>, BanksClientError> {
+        self.get_multiple_accounts_with_commitment_and_context(
+            context::current(),
+            addresses,
+            commitment,
+        )
+        .await?
+        .ok_or(BanksClientError::ClientError(
+            "Too many addresses requested",
+        ))
+    }
+
+    /// Return the accounts at the given addresses at the time of the most recent root slot,
+    /// in the same order. Accounts that are not found are None.
+    /// At most `MAX_MULTIPLE_ACCOUNTS` addresses may be requested at once.
+    pub async fn get_multiple_accounts(
+        &self,
+        addresses: Vec<Pubkey>,
+    ) -> Result<Vec<Option<Account>>, BanksClientError> {
+        self.get_multiple_accounts_with_commitment(addresses, CommitmentLevel::default())
+            .await
+    }
+
+    /// Return the accounts owned by the given program that pass all the given filters, at the
+    /// slot corresponding to the given commitment level, sorted by address
+    pub async fn get_program_accounts_with_commitment(
+        &self,
+        program_id: Pubkey,
+        filters: Vec<ProgramAccountsFilter>,
+        commitment: CommitmentLevel,
+    ) -> Result<Vec<(Pubkey, Account)>, BanksClientError> {
+        self.get_program_accounts_with_commitment_and_context(
+            context::current(),
+            program_id,
+            filters,
+            commitment,
+        )
+        .await?
+        .ok_or(BanksClientError::ClientError("Program account scan failed"))
+    }
+
+    /// Return the accounts owned by the given program that pass all the given filters, at the
+    /// time of the most recent root slot, sorted by address
+    pub async fn get_program_accounts(
+        &self,
+        program_id: Pubkey,
+        filters: Vec<ProgramAccountsFilter>,
+    ) -> Result<Vec<(Pubkey, Account)>, BanksClientError> {
+        self.get_program_accounts_with_commitment(program_id, filters, CommitmentLevel::default())
+            .await
+    }
+
     /// Return the unpacked account data at the given address
     /// If the account is not found, an error is returned
     pub async fn get_packed_account_data<T: Pack>(
@@ -511,6 +607,188 @@ impl BanksClient {
             .await
             .map_err(Into::into)
     }
+
+    pub async fn subscribe_account_with_context(
+        &self,
+        ctx: Context,
+        address: Pubkey,
+    ) -> Result<SubscriptionId, BanksClientError> {
+        self.inner
+            .subscribe_account_with_context(ctx, address)
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn subscribe_slot_with_context(
+        &self,
+        ctx: Context,
+    ) -> Result<SubscriptionId, BanksClientError> {
+        self.inner
+            .subscribe_slot_with_context(ctx)
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn subscribe_logs_with_context(
+        &self,
+        ctx: Context,
+        filter: LogsFilter,
+    ) -> Result<SubscriptionId, BanksClientError> {
+        self.inner
+            .subscribe_logs_with_context(ctx, filter)
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn get_notifications_with_context(
+        &self,
+        ctx: Context,
+        subscription: SubscriptionId,
+    ) -> Result<Option<Vec<BanksNotification>>, BanksClientError> {
+        self.inner
+            .get_notifications_with_context(ctx, subscription)
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn unsubscribe_with_context(
+        &self,
+        ctx: Context,
+        subscription: SubscriptionId,
+    ) -> Result<bool, BanksClientError> {
+        self.inner
+            .unsubscribe_with_context(ctx, subscription)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Subscribe to the changes of the account at the given address. A
+    /// notification is sent each time a committed transaction writes the
+    /// account, at the processed commitment level.
+    pub async fn subscribe_account(
+        &self,
+        address: Pubkey,
+    ) -> Result<BanksSubscription<AccountNotification>, BanksClientError> {
+        let subscription = self
+            .subscribe_account_with_context(context::current(), address)
+            .await?;
+        Ok(BanksSubscription::new(
+            self.clone(),
+            subscription,
+            |notification| match notification {
+                BanksNotification::Account(notification) => Some(notification),
+                _ => None,
+            },
+        ))
+    }
+
+    /// Subscribe to the slots that are completed, notified once their bank
+    /// has been frozen
+    pub async fn subscribe_slot(
+        &self,
+    ) -> Result<BanksSubscription<SlotNotification>, BanksClientError> {
+        let subscription = self.subscribe_slot_with_context(context::current()).await?;
+        Ok(BanksSubscription::new(
+            self.clone(),
+            subscription,
+            |notification| match notification {
+                BanksNotification::Slot(notification) => Some(notification),
+                _ => None,
+            },
+        ))
+    }
+
+    /// Subscribe to the logs of the committed transactions that match the
+    /// given filter, at the processed commitment level
+    pub async fn subscribe_logs(
+        &self,
+        filter: LogsFilter,
+    ) -> Result<BanksSubscription<LogsNotification>, BanksClientError> {
+        let subscription = self
+            .subscribe_logs_with_context(context::current(), filter)
+            .await?;
+        Ok(BanksSubscription::new(
+            self.clone(),
+            subscription,
+            |notification| match notification {
+                BanksNotification::Logs(notification) => Some(notification),
+                _ => None,
+            },
+        ))
+    }
+}
+
+/// The notifications of a subscription, as a stream
+///
+/// The stream ends after yielding an error or once the subscription no longer
+/// exists on the server. Dropping it from within a Tokio runtime ends the
+/// subscription. Notifications that are not consumed are queued by the
+/// server, which drops the oldest ones if too many accumulate.
+pub struct BanksSubscription<T> {
+    client: BanksClient,
+    subscription: SubscriptionId,
+    notifications: BoxStream<'static, Result<T, BanksClientError>>,
+}
+
+impl<T: Send + 'static> BanksSubscription<T> {
+    fn new(
+        client: BanksClient,
+        subscription: SubscriptionId,
+        select: fn(BanksNotification) -> Option<T>,
+    ) -> Self {
+        let state = Some((client.clone(), VecDeque::new()));
+        let notifications = stream::unfold(state, move |state| async move {
+            let (client, mut queued) = state?;
+            loop {
+                if let Some(notification) = queued.pop_front() {
+                    return Some((Ok(notification), Some((client, queued))));
+                }
+                match client
+                    .get_notifications_with_context(context::current(), subscription)
+                    .await
+                {
+                    Ok(Some(notifications)) => {
+                        queued.extend(notifications.into_iter().filter_map(select))
+                    }
+                    Ok(None) => return None,
+                    Err(err) => return Some((Err(err), None)),
+                }
+            }
+        })
+        .boxed();
+        Self {
+            client,
+            subscription,
+            notifications,
+        }
+    }
+
+    /// The id of the subscription on the server
+    pub fn id(&self) -> SubscriptionId {
+        self.subscription
+    }
+}
+
+impl<T> Stream for BanksSubscription<T> {
+    type Item = Result<T, BanksClientError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.notifications.poll_next_unpin(cx)
+    }
+}
+
+impl<T> Drop for BanksSubscription<T> {
+    fn drop(&mut self) {
+        if let Ok(handle) = Handle::try_current() {
+            let client = self.client.clone();
+            let subscription = self.subscription;
+            handle.spawn(async move {
+                let _ = client
+                    .unsubscribe_with_context(context::current(), subscription)
+                    .await;
+            });
+        }
+    }
 }
 
 pub async fn start_client<C>(transport: C) -> Result<BanksClient, BanksClientError>
@@ -533,6 +811,7 @@ pub async fn start_tcp_client<T: ToSocketAddrs>(addr: T) -> Result<BanksClient,
 mod tests {
     use {
         super::*,
+        solana_account::AccountSharedData,
         solana_banks_server::banks_server::start_local_server,
         solana_runtime::{
             bank::Bank, bank_forks::BankForks, commitment::BlockCommitmentCache,
@@ -642,4 +921,140 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    #[allow(clippy::result_large_err)]
+    fn test_banks_server_multiple_and_program_accounts() -> Result<(), BanksClientError> {
+        let genesis = create_genesis_config(10);
+        let bank = Bank::new_for_tests(&genesis.genesis_config);
+        let slot = bank.slot();
+        let program_id = solana_pubkey::new_rand();
+        let accounts: Vec<_> = (0..3u8)
+            .map(|i| {
+                let account = Account {
+                    lamports: 1,
+                    data: vec![i; 2 + usize::from(i)],
+                    owner: program_id,
+                    ..Account::default()
+                };
+                let address = solana_pubkey::new_rand();
+                bank.store_account(&address, &AccountSharedData::from(account.clone()));
+                (address, account)
+            })
+            .collect();
+        let block_commitment_cache = Arc::new(RwLock::new(
+            BlockCommitmentCache::new_for_tests_with_slots(slot, slot),
+        ));
+        let bank_forks = BankForks::new_rw_arc(bank);
+
+        Runtime::new()?.block_on(async {
+            let client_transport =
+                start_local_server(bank_forks, block_commitment_cache, Duration::from_millis(1))
+                    .await;
+            let banks_client = start_client(client_transport).await?;
+
+            let missing_address = solana_pubkey::new_rand();
+            let fetched = banks_client
+                .get_multiple_accounts(vec![accounts[1].0, missing_address, accounts[0].0])
+                .await?;
+            assert_eq!(
+                fetched,
+                vec![
+                    Some(accounts[1].1.clone()),
+                    None,
+                    Some(accounts[0].1.clone())
+                ]
+            );
+            assert!(banks_client
+                .get_multiple_accounts(vec![missing_address; MAX_MULTIPLE_ACCOUNTS + 1])
+                .await
+                .is_err());
+
+            let mut sorted_accounts = accounts.clone();
+            sorted_accounts.sort_by_key(|(address, _)| *address);
+            assert_eq!(
+                banks_client
+                    .get_program_accounts(program_id, vec![])
+                    .await?,
+                sorted_accounts
+            );
+            assert_eq!(
+                banks_client
+                    .get_program_accounts(program_id, vec![ProgramAccountsFilter::DataSize(3)])
+                    .await?,
+                vec![accounts[1].clone()]
+            );
+            assert_eq!(
+                banks_client
+                    .get_program_accounts(
+                        program_id,
+                        vec![ProgramAccountsFilter::Memcmp {
+                            offset: 1,
+                            bytes: vec![2, 2],
+                        }],
+                    )
+                    .await?,
+                vec![accounts[2].clone()]
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    #[allow(clippy::result_large_err)]
+    fn test_banks_server_subscriptions() -> Result<(), BanksClientError> {
+        let genesis = create_genesis_config(10);
+        let bank = Bank::new_for_tests(&genesis.genesis_config);
+        let slot = bank.slot();
+        let block_commitment_cache = Arc::new(RwLock::new(
+            BlockCommitmentCache::new_for_tests_with_slots(slot, slot),
+        ));
+        let bank_forks = BankForks::new_rw_arc(bank);
+
+        let mint_pubkey = genesis.mint_keypair.pubkey();
+        let bob_pubkey = solana_pubkey::new_rand();
+        let instruction = system_instruction::transfer(&mint_pubkey, &bob_pubkey, 1);
+        let message = Message::new(&[instruction], Some(&mint_pubkey));
+
+        Runtime::new()?.block_on(async {
+            let client_transport = start_local_server(
+                bank_forks.clone(),
+                block_commitment_cache,
+                Duration::from_millis(1),
+            )
+            .await;
+            let banks_client = start_client(client_transport).await?;
+            let mut account_notifications = banks_client.subscribe_account(bob_pubkey).await?;
+            let mut logs_notifications = banks_client
+                .subscribe_logs(LogsFilter::Mentions(vec![bob_pubkey]))
+                .await?;
+            let mut slot_notifications = banks_client.subscribe_slot().await?;
+
+            let recent_blockhash = banks_client.get_latest_blockhash().await?;
+            let transaction = Transaction::new(&[&genesis.mint_keypair], message, recent_blockhash);
+            let signature = transaction.signatures[0];
+            banks_client.process_transaction(transaction).await?;
+
+            let account_notification = account_notifications.next().await.unwrap()?;
+            assert_eq!(account_notification.slot, slot);
+            assert_eq!(account_notification.account.unwrap().lamports, 1);
+            let logs_notification = logs_notifications.next().await.unwrap()?;
+            assert_eq!(logs_notification.signature, signature);
+            assert_eq!(logs_notification.err, None);
+            assert!(!logs_notification.logs.is_empty());
+
+            bank_forks.read().unwrap().working_bank().freeze();
+            let slot_notification = slot_notifications.next().await.unwrap()?;
+            assert_eq!(slot_notification.slot, slot);
+
+            let subscription = slot_notifications.id();
+            assert!(
+                banks_client
+                    .unsubscribe_with_context(context::current(), subscription)
+                    .await?
+            );
+            assert!(slot_notifications.next().await.is_none());
+            Ok(())
+        })
+    }
 }
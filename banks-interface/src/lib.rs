@@ -13,12 +13,17 @@ use {
     solana_transaction::versioned::VersionedTransaction,
     solana_transaction_context::transaction::TransactionReturnData,
     solana_transaction_error::TransactionError,
+    spl_generic_token::{token::GenericTokenAccount, token_2022::Account as TokenAccount},
 };
 
 mod transaction {
     pub use solana_transaction_error::TransactionResult as Result;
 }
 
+/// The most addresses a single multiple accounts request may ask for, as for
+/// the `getMultipleAccounts` RPC method
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TransactionConfirmationStatus {
     Processed,
@@ -64,6 +69,77 @@ pub struct BanksTransactionResultWithMetadata {
     pub metadata: Option<TransactionMetadata>,
 }
 
+/// A condition on the accounts returned by a program account scan, as the
+/// filters of the `getProgramAccounts` RPC method
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProgramAccountsFilter {
+    /// Accounts whose data is exactly this many bytes long
+    DataSize(u64),
+    /// Accounts whose data contains `bytes` at `offset`
+    Memcmp { offset: usize, bytes: Vec<u8> },
+    /// Initialized SPL Token and Token-2022 accounts
+    TokenAccountState,
+}
+
+impl ProgramAccountsFilter {
+    /// Returns whether an account with the given data passes the filter
+    pub fn allows(&self, data: &[u8]) -> bool {
+        match self {
+            Self::DataSize(size) => data.len() as u64 == *size,
+            Self::Memcmp { offset, bytes } => data
+                .get(*offset..)
+                .and_then(|data| data.get(..bytes.len()))
+                .is_some_and(|data| data == bytes.as_slice()),
+            Self::TokenAccountState => TokenAccount::valid_account_data(data),
+        }
+    }
+}
+
+/// Which transactions a logs subscription is notified of
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogsFilter {
+    /// All transactions except simple vote transactions
+    All,
+    /// All transactions, including simple vote transactions
+    AllWithVotes,
+    /// Transactions that reference any of the given accounts
+    Mentions(Vec<Pubkey>),
+}
+
+/// An account written by a committed transaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountNotification {
+    pub slot: Slot,
+    /// `None` once the account has been closed
+    pub account: Option<Account>,
+}
+
+/// A bank that has been frozen, after all of its transactions were processed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotNotification {
+    pub slot: Slot,
+    pub parent: Slot,
+}
+
+/// A committed transaction matching the filter of a logs subscription
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogsNotification {
+    pub slot: Slot,
+    pub signature: Signature,
+    pub err: Option<TransactionError>,
+    pub logs: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BanksNotification {
+    Account(AccountNotification),
+    Slot(SlotNotification),
+    Logs(LogsNotification),
+}
+
+/// Identifies a subscription on the server that created it
+pub type SubscriptionId = u64;
+
 #[tarpc::service]
 pub trait Banks {
     async fn send_transaction_with_context(transaction: VersionedTransaction);
@@ -90,6 +166,18 @@ pub trait Banks {
         address: Pubkey,
         commitment: CommitmentLevel,
     ) -> Option<Account>;
+    /// Returns `None` if more than [`MAX_MULTIPLE_ACCOUNTS`] addresses are
+    /// requested
+    async fn get_multiple_accounts_with_commitment_and_context(
+        addresses: Vec<Pubkey>,
+        commitment: CommitmentLevel,
+    ) -> Option<Vec<Option<Account>>>;
+    /// Returns `None` if the scan failed
+    async fn get_program_accounts_with_commitment_and_context(
+        program_id: Pubkey,
+        filters: Vec<ProgramAccountsFilter>,
+        commitment: CommitmentLevel,
+    ) -> Option<Vec<(Pubkey, Account)>>;
     async fn get_latest_blockhash_with_context() -> Hash;
     async fn get_latest_blockhash_with_commitment_and_context(
         commitment: CommitmentLevel,
@@ -98,6 +186,16 @@ pub trait Banks {
         message: Message,
         commitment: CommitmentLevel,
     ) -> Option<u64>;
+    async fn subscribe_account_with_context(address: Pubkey) -> SubscriptionId;
+    async fn subscribe_slot_with_context() -> SubscriptionId;
+    async fn subscribe_logs_with_context(filter: LogsFilter) -> SubscriptionId;
+    /// Wait for the next notifications of a subscription, returning an empty
+    /// batch if there are none by the request deadline and `None` if the
+    /// subscription does not exist
+    async fn get_notifications_with_context(
+        subscription: SubscriptionId,
+    ) -> Option<Vec<BanksNotification>>;
+    async fn unsubscribe_with_context(subscription: SubscriptionId) -> bool;
 }
 
 #[cfg(test)]
@@ -112,4 +210,29 @@ mod tests {
         let (client_transport, _server_transport) = transport::channel::unbounded();
         BanksClient::new(client::Config::default(), client_transport);
     }
+
+    #[test]
+    fn test_program_accounts_filter() {
+        let data = [1, 2, 3, 4];
+        assert!(ProgramAccountsFilter::DataSize(4).allows(&data));
+        assert!(!ProgramAccountsFilter::DataSize(3).allows(&data));
+        let memcmp = |offset, bytes: &[u8]| ProgramAccountsFilter::Memcmp {
+            offset,
+            bytes: bytes.to_vec(),
+        };
+        assert!(memcmp(0, &[1, 2]).allows(&data));
+        assert!(memcmp(2, &[3, 4]).allows(&data));
+        assert!(memcmp(4, &[]).allows(&data));
+        assert!(!memcmp(1, &[3]).allows(&data));
+        assert!(!memcmp(3, &[4, 5]).allows(&data));
+        assert!(!memcmp(5, &[]).allows(&data));
+
+        // The state of a token account follows its mint, owner, amount and
+        // delegate
+        let mut token_account_data = vec![0; 165];
+        assert!(!ProgramAccountsFilter::TokenAccountState.allows(&token_account_data));
+        token_account_data[108] = 1;
+        assert!(ProgramAccountsFilter::TokenAccountState.allows(&token_account_data));
+        assert!(!ProgramAccountsFilter::TokenAccountState.allows(&data));
+    }
 }
@@ -1,12 +1,15 @@
 use {
+    crate::subscriptions::{SubscriptionKind, Subscriptions},
     bincode::{deserialize, serialize},
     crossbeam_channel::{unbounded, Receiver, Sender},
     futures::{future, prelude::stream::StreamExt},
-    solana_account::Account,
+    solana_account::{Account, ReadableAccount},
+    solana_accounts_db::accounts_index::{ScanConfig, ScanOrder},
     solana_banks_interface::{
-        Banks, BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
-        BanksTransactionResultWithSimulation, TransactionConfirmationStatus, TransactionMetadata,
-        TransactionSimulationDetails, TransactionStatus,
+        Banks, BanksNotification, BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
+        BanksTransactionResultWithSimulation, LogsFilter, ProgramAccountsFilter, SubscriptionId,
+        TransactionConfirmationStatus, TransactionMetadata, TransactionSimulationDetails,
+        TransactionStatus, MAX_MULTIPLE_ACCOUNTS,
     },
     solana_clock::Slot,
     solana_commitment_config::CommitmentLevel,
@@ -38,7 +41,7 @@ use {
             Arc, RwLock,
         },
         thread::Builder,
-        time::Duration,
+        time::{Duration, SystemTime},
     },
     tarpc::{
         context::Context,
@@ -47,7 +50,7 @@ use {
         transport::{self, channel::UnboundedChannel},
         ClientMessage, Response,
     },
-    tokio::{runtime::Handle, time::sleep},
+    tokio::{runtime::Handle, task, time::sleep},
     tokio_serde::formats::Bincode,
     tokio_util::sync::CancellationToken,
 };
@@ -62,8 +65,13 @@ struct BanksServer {
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     transaction_sender: Sender<TransactionInfo>,
     poll_signature_status_sleep_duration: Duration,
+    subscriptions: Arc<Subscriptions>,
 }
 
+/// How long before the request deadline to answer a wait for notifications,
+/// so that the response reaches the client in time
+const NOTIFICATIONS_DEADLINE_MARGIN: Duration = Duration::from_millis(500);
+
 impl BanksServer {
     /// Return a BanksServer that forwards transactions to the
     /// given sender. If unit-testing, those transactions can go to
@@ -74,12 +82,14 @@ impl BanksServer {
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         transaction_sender: Sender<TransactionInfo>,
         poll_signature_status_sleep_duration: Duration,
+        subscriptions: Arc<Subscriptions>,
     ) -> Self {
         Self {
             bank_forks,
             block_commitment_cache,
             transaction_sender,
             poll_signature_status_sleep_duration,
+            subscriptions,
         }
     }
 
@@ -127,11 +137,13 @@ impl BanksServer {
             .name("solBankForksCli".to_string())
             .spawn(move || Self::run(server_bank_forks, transaction_receiver))
             .unwrap();
+        let subscriptions = Subscriptions::new_for_bank_forks(&bank_forks);
         Self::new(
             bank_forks,
             block_commitment_cache,
             transaction_sender,
             poll_signature_status_sleep_duration,
+            subscriptions,
         )
     }
 
@@ -394,6 +406,56 @@ impl Banks for BanksServer {
         bank.get_account(&address).map(Account::from)
     }
 
+    async fn get_multiple_accounts_with_commitment_and_context(
+        self,
+        _: Context,
+        addresses: Vec<Pubkey>,
+        commitment: CommitmentLevel,
+    ) -> Option<Vec<Option<Account>>> {
+        if addresses.len() > MAX_MULTIPLE_ACCOUNTS {
+            return None;
+        }
+        let bank = self.bank(commitment);
+        Some(
+            addresses
+                .iter()
+                .map(|address| bank.get_account(address).map(Account::from))
+                .collect(),
+        )
+    }
+
+    async fn get_program_accounts_with_commitment_and_context(
+        self,
+        _: Context,
+        program_id: Pubkey,
+        filters: Vec<ProgramAccountsFilter>,
+        commitment: CommitmentLevel,
+    ) -> Option<Vec<(Pubkey, Account)>> {
+        let bank = self.bank(commitment);
+        // Scans can take a while, don't hold up the other requests
+        task::spawn_blocking(move || {
+            bank.get_filtered_program_accounts(
+                &program_id,
+                |account| {
+                    // Accounts that were closed may remain in the program's
+                    // index as zero-lamport default accounts
+                    account.owner() == &program_id
+                        && filters.iter().all(|filter| filter.allows(account.data()))
+                },
+                &ScanConfig::new(ScanOrder::Sorted),
+            )
+        })
+        .await
+        .expect("Failed to spawn blocking task")
+        .ok()
+        .map(|accounts| {
+            accounts
+                .into_iter()
+                .map(|(address, account)| (address, Account::from(account)))
+                .collect()
+        })
+    }
+
     async fn get_latest_blockhash_with_context(self, _: Context) -> Hash {
         let bank = self.bank(CommitmentLevel::default());
         bank.last_blockhash()
@@ -422,6 +484,38 @@ impl Banks for BanksServer {
                 .ok()?;
         bank.get_fee_for_message(&sanitized_message)
     }
+
+    async fn subscribe_account_with_context(self, _: Context, address: Pubkey) -> SubscriptionId {
+        self.subscriptions
+            .subscribe(SubscriptionKind::Account(address))
+    }
+
+    async fn subscribe_slot_with_context(self, _: Context) -> SubscriptionId {
+        self.subscriptions.subscribe(SubscriptionKind::Slot)
+    }
+
+    async fn subscribe_logs_with_context(self, _: Context, filter: LogsFilter) -> SubscriptionId {
+        self.subscriptions.subscribe(SubscriptionKind::Logs(filter))
+    }
+
+    async fn get_notifications_with_context(
+        self,
+        ctx: Context,
+        subscription: SubscriptionId,
+    ) -> Option<Vec<BanksNotification>> {
+        let max_wait = ctx
+            .deadline
+            .duration_since(SystemTime::now())
+            .unwrap_or_default()
+            .saturating_sub(NOTIFICATIONS_DEADLINE_MARGIN);
+        self.subscriptions
+            .next_notifications(subscription, max_wait)
+            .await
+    }
+
+    async fn unsubscribe_with_context(self, _: Context, subscription: SubscriptionId) -> bool {
+        self.subscriptions.unsubscribe(subscription)
+    }
 }
 
 pub async fn start_local_server(
@@ -488,6 +582,7 @@ pub async fn start_tcp_server(
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     exit: Arc<AtomicBool>,
 ) -> io::Result<()> {
+    let subscriptions = Subscriptions::new_for_bank_forks(&bank_forks);
     // Note: These settings are copied straight from the tarpc example.
     let server = tcp::listen(listen_addr, Bincode::default)
         .await?
@@ -524,6 +619,7 @@ pub async fn start_tcp_server(
                 block_commitment_cache.clone(),
                 sender,
                 Duration::from_millis(200),
+                subscriptions.clone(),
             );
             chan.execute(server.serve())
         })
@@ -0,0 +1,207 @@
+//! Account, slot and logs subscriptions, fed by the commit notifications of
+//! the banks
+
+use {
+    solana_account::{Account, ReadableAccount},
+    solana_banks_interface::{
+        AccountNotification, BanksNotification, LogsFilter, LogsNotification, SlotNotification,
+        SubscriptionId,
+    },
+    solana_pubkey::Pubkey,
+    solana_runtime::{
+        bank::{
+            commit_notifier::{BankCommitNotifier, CommittedTransactionInfo},
+            Bank,
+        },
+        bank_forks::BankForks,
+    },
+    std::{
+        collections::{HashMap, VecDeque},
+        sync::{
+            atomic::{AtomicU64, AtomicUsize, Ordering},
+            Arc, Mutex, RwLock,
+        },
+        time::Duration,
+    },
+    tokio::{sync::Notify, time::timeout},
+};
+
+/// Notifications queued per subscription before the oldest are dropped, so
+/// that subscriptions abandoned by their client don't grow without bound
+const MAX_QUEUED_NOTIFICATIONS: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SubscriptionKind {
+    Account(Pubkey),
+    Slot,
+    Logs(LogsFilter),
+}
+
+struct Subscription {
+    kind: SubscriptionKind,
+    notifications: Mutex<VecDeque<BanksNotification>>,
+    ready: Notify,
+}
+
+/// The subscriptions of the clients of a banks server
+#[derive(Default)]
+pub(crate) struct Subscriptions {
+    next_id: AtomicU64,
+    subscriptions: RwLock<HashMap<SubscriptionId, Arc<Subscription>>>,
+    /// Logs are only recorded while there are logs subscriptions
+    logs_subscriptions: AtomicUsize,
+}
+
+impl Subscriptions {
+    /// Returns subscriptions that are notified by every bank in `bank_forks`
+    /// and all of their descendants
+    pub(crate) fn new_for_bank_forks(bank_forks: &RwLock<BankForks>) -> Arc<Self> {
+        let subscriptions = Arc::new(Self::default());
+        for bank in bank_forks.read().unwrap().banks().values() {
+            bank.add_commit_notifier(subscriptions.clone());
+        }
+        subscriptions
+    }
+
+    pub(crate) fn subscribe(&self, kind: SubscriptionKind) -> SubscriptionId {
+        if matches!(kind, SubscriptionKind::Logs(_)) {
+            self.logs_subscriptions.fetch_add(1, Ordering::Relaxed);
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let subscription = Subscription {
+            kind,
+            notifications: Mutex::default(),
+            ready: Notify::new(),
+        };
+        self.subscriptions
+            .write()
+            .unwrap()
+            .insert(id, Arc::new(subscription));
+        id
+    }
+
+    pub(crate) fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let Some(subscription) = self.subscriptions.write().unwrap().remove(&id) else {
+            return false;
+        };
+        if matches!(subscription.kind, SubscriptionKind::Logs(_)) {
+            self.logs_subscriptions.fetch_sub(1, Ordering::Relaxed);
+        }
+        // Wake up a pending `next_notifications`, so that it returns
+        subscription.ready.notify_one();
+        true
+    }
+
+    /// Waits up to `max_wait` for notifications of subscription `id`
+    ///
+    /// Returns all the notifications queued, possibly none, or `None` once
+    /// the subscription no longer exists.
+    pub(crate) async fn next_notifications(
+        &self,
+        id: SubscriptionId,
+        max_wait: Duration,
+    ) -> Option<Vec<BanksNotification>> {
+        let subscription = self.subscriptions.read().unwrap().get(&id)?.clone();
+        let notifications: Vec<_> = subscription
+            .notifications
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect();
+        if !notifications.is_empty() {
+            return Some(notifications);
+        }
+        // A notification queued since the queue was drained left a permit,
+        // so it can't be missed
+        let _ = timeout(max_wait, subscription.ready.notified()).await;
+        if !self.subscriptions.read().unwrap().contains_key(&id) {
+            return None;
+        }
+        Some(
+            subscription
+                .notifications
+                .lock()
+                .unwrap()
+                .drain(..)
+                .collect(),
+        )
+    }
+
+    /// Queues the notification `notification` returns for each subscription
+    fn notify(&self, notification: impl Fn(&SubscriptionKind) -> Option<BanksNotification>) {
+        for subscription in self.subscriptions.read().unwrap().values() {
+            let Some(notification) = notification(&subscription.kind) else {
+                continue;
+            };
+            let mut notifications = subscription.notifications.lock().unwrap();
+            if notifications.len() == MAX_QUEUED_NOTIFICATIONS {
+                notifications.pop_front();
+            }
+            notifications.push_back(notification);
+            subscription.ready.notify_one();
+        }
+    }
+}
+
+impl BankCommitNotifier for Subscriptions {
+    fn has_transaction_subscribers(&self) -> bool {
+        self.subscriptions
+            .read()
+            .unwrap()
+            .values()
+            .any(|subscription| subscription.kind != SubscriptionKind::Slot)
+    }
+
+    fn should_record_logs(&self) -> bool {
+        self.logs_subscriptions.load(Ordering::Relaxed) > 0
+    }
+
+    fn transaction_committed(&self, bank: &Bank, transaction: &CommittedTransactionInfo) {
+        self.notify(|kind| match kind {
+            SubscriptionKind::Account(address) => transaction
+                .written_accounts
+                .iter()
+                .find(|(written_address, _)| *written_address == address)
+                .map(|(_, account)| {
+                    BanksNotification::Account(AccountNotification {
+                        slot: bank.slot(),
+                        account: (account.lamports() != 0)
+                            .then(|| Account::from((*account).clone())),
+                    })
+                }),
+            SubscriptionKind::Slot => None,
+            SubscriptionKind::Logs(filter) => {
+                let matches = match filter {
+                    LogsFilter::All => !transaction.is_vote,
+                    LogsFilter::AllWithVotes => true,
+                    LogsFilter::Mentions(addresses) => transaction
+                        .account_keys
+                        .iter()
+                        .any(|key| addresses.contains(key)),
+                };
+                matches.then(|| {
+                    BanksNotification::Logs(LogsNotification {
+                        slot: bank.slot(),
+                        signature: *transaction.signature,
+                        err: transaction.status.clone().err(),
+                        logs: transaction
+                            .log_messages
+                            .map(<[String]>::to_vec)
+                            .unwrap_or_default(),
+                    })
+                })
+            }
+        });
+    }
+
+    fn bank_frozen(&self, bank: &Bank) {
+        self.notify(|kind| {
+            (*kind == SubscriptionKind::Slot).then(|| {
+                BanksNotification::Slot(SlotNotification {
+                    slot: bank.slot(),
+                    parent: bank.parent_slot(),
+                })
+            })
+        });
+    }
+}
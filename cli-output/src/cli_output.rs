@@ -2372,6 +2372,50 @@ impl fmt::Display for CliProgram {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliBuildMetadata {
+    pub address: String,
+    pub authority: String,
+    pub repository: Option<String>,
+    pub commit: Option<String>,
+    pub toolchain: Option<String>,
+    pub elf_hash: String,
+    pub program_elf_hash: String,
+    pub verified: bool,
+}
+impl fmt::Display for CliBuildMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unknown = "unknown".to_string();
+        writeln_name_value(f, "Build Metadata Address:", &self.address)?;
+        writeln_name_value(f, "Build Metadata Authority:", &self.authority)?;
+        writeln_name_value(
+            f,
+            "Source Repository:",
+            self.repository.as_ref().unwrap_or(&unknown),
+        )?;
+        writeln_name_value(
+            f,
+            "Source Commit:",
+            self.commit.as_ref().unwrap_or(&unknown),
+        )?;
+        writeln_name_value(f, "Toolchain:", self.toolchain.as_ref().unwrap_or(&unknown))?;
+        if self.verified {
+            writeln_name_value(f, "ELF Hash:", &format!("{} (verified)", self.elf_hash))?;
+        } else {
+            writeln_name_value(
+                f,
+                "ELF Hash:",
+                &format!(
+                    "{} (MISMATCH: the on-chain program has hash {})",
+                    self.elf_hash, self.program_elf_hash
+                ),
+            )?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CliProgramV4 {
@@ -2381,6 +2425,8 @@ pub struct CliProgramV4 {
     pub last_deploy_slot: u64,
     pub status: String,
     pub data_len: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_metadata: Option<CliBuildMetadata>,
 }
 impl QuietDisplay for CliProgramV4 {}
 impl VerboseDisplay for CliProgramV4 {}
@@ -2401,6 +2447,9 @@ impl fmt::Display for CliProgramV4 {
             "Data Length:",
             &format!("{:?} ({:#x?}) bytes", self.data_len, self.data_len),
         )?;
+        if let Some(build_metadata) = &self.build_metadata {
+            write!(f, "{build_metadata}")?;
+        }
         Ok(())
     }
 }
@@ -2450,6 +2499,8 @@ pub struct CliUpgradeableProgram {
     pub lamports: u64,
     #[serde(skip_serializing)]
     pub use_lamports_unit: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_metadata: Option<CliBuildMetadata>,
 }
 impl QuietDisplay for CliUpgradeableProgram {}
 impl VerboseDisplay for CliUpgradeableProgram {}
@@ -2475,6 +2526,9 @@ impl fmt::Display for CliUpgradeableProgram {
             "Balance:",
             &build_balance_message(self.lamports, self.use_lamports_unit, true),
         )?;
+        if let Some(build_metadata) = &self.build_metadata {
+            write!(f, "{build_metadata}")?;
+        }
         Ok(())
     }
 }
@@ -2772,6 +2826,49 @@ pub fn return_signers_data(tx: &Transaction, config: &ReturnSignersConfig) -> Cl
     }
 }
 
+pub fn return_versioned_signers_with_config(
+    tx: &VersionedTransaction,
+    output_format: &OutputFormat,
+    config: &ReturnSignersConfig,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let cli_command = return_versioned_signers_data(tx, config);
+    Ok(output_format.formatted_string(&cli_command))
+}
+
+pub fn return_versioned_signers_data(
+    tx: &VersionedTransaction,
+    config: &ReturnSignersConfig,
+) -> CliSignOnlyData {
+    let verify_results = tx.verify_with_results();
+    let mut signers = Vec::new();
+    let mut absent = Vec::new();
+    let mut bad_sig = Vec::new();
+    tx.signatures
+        .iter()
+        .zip(tx.message.static_account_keys().iter())
+        .zip(verify_results)
+        .for_each(|((sig, key), res)| {
+            if res {
+                signers.push(format!("{key}={sig}"))
+            } else if *sig == Signature::default() {
+                absent.push(key.to_string());
+            } else {
+                bad_sig.push(key.to_string());
+            }
+        });
+    let message = config
+        .dump_transaction_message
+        .then(|| BASE64_STANDARD.encode(tx.message.serialize()));
+
+    CliSignOnlyData {
+        blockhash: tx.message.recent_blockhash().to_string(),
+        message,
+        signers,
+        absent,
+        bad_sig,
+    }
+}
+
 pub fn parse_sign_only_reply_string(reply: &str) -> SignOnly {
     let object: Value = serde_json::from_str(reply).unwrap();
     let blockhash_str = object.get("blockhash").unwrap().as_str().unwrap();
@@ -1,5 +1,8 @@
 use {
-    crate::cli::{CliCommand, CliCommandInfo, CliConfig, CliError, ProcessResult},
+    crate::{
+        cli::{CliCommand, CliCommandInfo, CliConfig, CliError, ProcessResult},
+        compute_budget::SendWithComputeBudget,
+    },
     clap::{App, AppSettings, Arg, ArgMatches, SubCommand},
     solana_account::from_account,
     solana_address_lookup_table_interface::{
@@ -575,9 +578,9 @@ async fn process_create_lookup_table(
     let keypairs: Vec<&dyn Signer> = vec![config.signers[0], payer_signer];
     tx.try_sign(&keypairs, blockhash)?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             RpcSendTransactionConfig {
                 skip_preflight: false,
                 preflight_commitment: Some(config.commitment.commitment),
@@ -638,9 +641,9 @@ async fn process_freeze_lookup_table(
 
     tx.try_sign(&[config.signers[0], authority_signer], blockhash)?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             RpcSendTransactionConfig {
                 skip_preflight: false,
                 preflight_commitment: Some(config.commitment.commitment),
@@ -705,9 +708,9 @@ async fn process_extend_lookup_table(
         blockhash,
     )?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             RpcSendTransactionConfig {
                 skip_preflight: false,
                 preflight_commitment: Some(config.commitment.commitment),
@@ -767,9 +770,9 @@ async fn process_deactivate_lookup_table(
 
     tx.try_sign(&[config.signers[0], authority_signer], blockhash)?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             RpcSendTransactionConfig {
                 skip_preflight: false,
                 preflight_commitment: Some(config.commitment.commitment),
@@ -829,9 +832,9 @@ async fn process_close_lookup_table(
 
     tx.try_sign(&[config.signers[0], authority_signer], blockhash)?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             RpcSendTransactionConfig {
                 skip_preflight: false,
                 preflight_commitment: Some(config.commitment.commitment),
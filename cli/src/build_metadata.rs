@@ -0,0 +1,354 @@
+//! Build metadata that programs are published with, to verify where their
+//! on-chain ELF comes from.
+//!
+//! The build metadata of a program is stored as JSON in an account of the
+//! config program, like validator info, keyed by the program id and signed by
+//! the authority that published it. The account is at an address derived from
+//! the program id and the authority, so the build metadata published by the
+//! authority of a program is found with a single account lookup. Unlike
+//! loader-v3 buffers, these accounts can't be listed, closed or deployed by
+//! the `program` commands.
+
+use {
+    crate::{
+        cli::CliConfig,
+        compute_budget::{ComputeUnitConfig, SendWithComputeBudget, WithComputeUnitConfig},
+    },
+    bincode::{deserialize, serialized_size},
+    serde::{Deserialize, Serialize},
+    solana_account::Account,
+    solana_clap_utils::compute_budget::ComputeUnitLimit,
+    solana_cli_output::CliBuildMetadata,
+    solana_config_interface::{
+        instruction as config_instruction,
+        state::{get_config_data, ConfigKeys},
+    },
+    solana_keypair::{keypair_from_seed, Keypair},
+    solana_message::Message,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::nonblocking::rpc_client::RpcClient,
+    solana_signer::Signer,
+    solana_transaction::Transaction,
+    std::{error, fs},
+};
+
+solana_pubkey::declare_id!("BuiLdMetadata111111111111111111111111111111");
+
+/// Most bytes of JSON that the build metadata of a program can take.
+pub const MAX_BUILD_METADATA_LEN: usize = 512;
+
+/// The build metadata of a program, as written by `cargo-build-sbf`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildMetadata {
+    /// Repository of the source code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    /// Commit of the source code in the repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Toolchain that the program is built with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<String>,
+    /// See [`elf_hash`]
+    #[serde(default)]
+    pub elf_hash: String,
+}
+
+/// The keys of the build metadata account of `program_id`, published by
+/// `authority`.
+fn build_metadata_keys(program_id: &Pubkey, authority: &Pubkey) -> Vec<(Pubkey, bool)> {
+    vec![(id(), false), (*program_id, false), (*authority, true)]
+}
+
+/// The keypair of the build metadata account of `program_id` published by
+/// `authority`.
+///
+/// The config program requires a new config account to sign its
+/// initialization, which an address derived with a seed can't, so the keypair
+/// itself is derived from the program id and the authority. Anyone can derive
+/// it and create the account, but only the authority can store build metadata
+/// in an account whose keys name it as the signer.
+fn build_metadata_keypair(program_id: &Pubkey, authority: &Pubkey) -> Keypair {
+    let seed =
+        solana_sha256_hasher::hashv(&[id().as_ref(), program_id.as_ref(), authority.as_ref()]);
+    keypair_from_seed(seed.as_ref()).expect("the seed is 32 bytes long")
+}
+
+/// The address of the build metadata account of `program_id` published by
+/// `authority`.
+fn build_metadata_address(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    build_metadata_keypair(program_id, authority).pubkey()
+}
+
+/// Whether `account` is a config account with the build metadata `keys`.
+fn has_build_metadata_keys(account: &Account, keys: &[(Pubkey, bool)]) -> bool {
+    account.owner == solana_config_interface::id()
+        && deserialize::<ConfigKeys>(&account.data)
+            .is_ok_and(|config_keys| config_keys.keys == keys)
+}
+
+/// The hex-encoded SHA-256 hash of an ELF, without the zero bytes that pad it
+/// on-chain, so that it's the same for the built file and deployed program.
+pub fn elf_hash(program_data: &[u8]) -> String {
+    let len = program_data
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |position| position.saturating_add(1));
+    hex::encode(solana_sha256_hasher::hash(&program_data[..len]).to_bytes())
+}
+
+/// Reads the build metadata of `program_data` from the file at `location`.
+pub fn read_build_metadata_file(
+    location: &str,
+    program_data: &[u8],
+) -> Result<BuildMetadata, Box<dyn error::Error>> {
+    let file = fs::read(location)
+        .map_err(|err| format!("Unable to read build metadata file {location}: {err}"))?;
+    let mut build_metadata: BuildMetadata = serde_json::from_slice(&file)
+        .map_err(|err| format!("Unable to parse build metadata file {location}: {err}"))?;
+    let elf_hash = elf_hash(program_data);
+    if build_metadata.elf_hash.is_empty() {
+        build_metadata.elf_hash = elf_hash;
+    } else if build_metadata.elf_hash != elf_hash {
+        return Err(format!(
+            "Build metadata file {location} is for an ELF with hash {}, but the program has hash \
+             {elf_hash}",
+            build_metadata.elf_hash
+        )
+        .into());
+    }
+    let len = serde_json::to_vec(&build_metadata)?.len();
+    if len > MAX_BUILD_METADATA_LEN {
+        return Err(format!(
+            "Build metadata is {len} bytes long, more than the maximum of \
+             {MAX_BUILD_METADATA_LEN} bytes"
+        )
+        .into());
+    }
+    Ok(build_metadata)
+}
+
+/// Creates or updates the build metadata account of a program.
+///
+/// Only the build metadata published by the program's authority is taken for
+/// the program's, so `authority_signer` must be the program's authority.
+pub(crate) async fn publish_build_metadata(
+    rpc_client: &RpcClient,
+    config: &CliConfig<'_>,
+    fee_payer_signer: &dyn Signer,
+    program_id: &Pubkey,
+    authority_signer: &dyn Signer,
+    build_metadata: &BuildMetadata,
+    compute_unit_price: Option<u64>,
+) -> Result<Pubkey, Box<dyn error::Error>> {
+    let authority_pubkey = authority_signer.pubkey();
+    let keys = build_metadata_keys(program_id, &authority_pubkey);
+    let json = serde_json::to_string(build_metadata)?;
+    if json.len() > MAX_BUILD_METADATA_LEN {
+        return Err(format!(
+            "Build metadata is {} bytes long, more than the maximum of {MAX_BUILD_METADATA_LEN} \
+             bytes",
+            json.len()
+        )
+        .into());
+    }
+    let compute_unit_config = ComputeUnitConfig {
+        compute_unit_price,
+        compute_unit_limit: ComputeUnitLimit::Default,
+    };
+
+    let account_keypair = build_metadata_keypair(program_id, &authority_pubkey);
+    let address = account_keypair.pubkey();
+    let existing_account = rpc_client
+        .get_account_with_commitment(&address, config.commitment)
+        .await?
+        .value;
+    let instructions = if let Some(account) = &existing_account {
+        if !has_build_metadata_keys(account, &keys) {
+            return Err(format!(
+                "Build metadata account {address} is already in use with other keys"
+            )
+            .into());
+        }
+        vec![config_instruction::store(&address, false, keys, &json)]
+    } else {
+        let max_config_data_size = serialized_size(&" ".repeat(MAX_BUILD_METADATA_LEN))?;
+        let data_len = max_config_data_size
+            .saturating_add(serialized_size(&ConfigKeys { keys: keys.clone() })?);
+        let lamports = rpc_client
+            .get_minimum_balance_for_rent_exemption(data_len as usize)
+            .await?;
+        let mut instructions = config_instruction::create_account_with_max_config_space::<String>(
+            &fee_payer_signer.pubkey(),
+            &address,
+            lamports,
+            max_config_data_size,
+            keys.clone(),
+        );
+        instructions.push(config_instruction::store(&address, true, keys, &json));
+        instructions
+    };
+    let instructions = instructions.with_compute_unit_config(&compute_unit_config);
+    let message = Message::new(&instructions, Some(&fee_payer_signer.pubkey()));
+    let mut tx = Transaction::new_unsigned(message);
+    let blockhash = rpc_client.get_latest_blockhash().await?;
+    if existing_account.is_some() {
+        tx.try_sign(&[fee_payer_signer, authority_signer], blockhash)?;
+    } else {
+        tx.try_sign(
+            &[fee_payer_signer, authority_signer, &account_keypair],
+            blockhash,
+        )?;
+    }
+    rpc_client
+        .send_and_confirm_transaction_with_compute_budget(
+            &tx,
+            config,
+            config.send_transaction_config,
+        )
+        .await?;
+
+    Ok(address)
+}
+
+/// Fetches the build metadata that `program_authority` published for a
+/// program, if any, and checks it against the program's on-chain ELF.
+///
+/// The build metadata account is derived from the program's authority, so
+/// programs without an authority have no build metadata to show.
+pub(crate) async fn fetch_build_metadata(
+    rpc_client: &RpcClient,
+    config: &CliConfig<'_>,
+    program_id: &Pubkey,
+    program_authority: Option<&Pubkey>,
+    program_data: &[u8],
+) -> Result<Option<CliBuildMetadata>, Box<dyn error::Error>> {
+    let Some(authority) = program_authority else {
+        return Ok(None);
+    };
+    let address = build_metadata_address(program_id, authority);
+    let Some(account) = rpc_client
+        .get_account_with_commitment(&address, config.commitment)
+        .await?
+        .value
+        .filter(|account| {
+            has_build_metadata_keys(account, &build_metadata_keys(program_id, authority))
+        })
+    else {
+        return Ok(None);
+    };
+    let invalid = || format!("Build metadata account {address} is invalid");
+    let json: String = get_config_data(&account.data)
+        .ok()
+        .and_then(|data| deserialize(data).ok())
+        .ok_or_else(invalid)?;
+    let build_metadata: BuildMetadata = serde_json::from_str(&json).map_err(|_| invalid())?;
+
+    let program_elf_hash = elf_hash(program_data);
+    Ok(Some(CliBuildMetadata {
+        address: address.to_string(),
+        authority: authority.to_string(),
+        repository: build_metadata.repository,
+        commit: build_metadata.commit,
+        toolchain: build_metadata.toolchain,
+        verified: build_metadata.elf_hash == program_elf_hash,
+        elf_hash: build_metadata.elf_hash,
+        program_elf_hash,
+    }))
+}
+
+/// A line on whether a dumped program matches its build metadata, if any.
+pub(crate) fn verified_build_metadata_message(build_metadata: Option<&CliBuildMetadata>) -> String {
+    match build_metadata {
+        None => String::new(),
+        Some(build_metadata) if build_metadata.verified => format!(
+            "\nELF hash {} matches the build metadata",
+            build_metadata.program_elf_hash
+        ),
+        Some(build_metadata) => format!(
+            "\nWARNING: ELF hash {} does not match the build metadata hash {}",
+            build_metadata.program_elf_hash, build_metadata.elf_hash
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elf_hash() {
+        // The SHA-256 hash of "abc"
+        let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(elf_hash(b"abc"), hash);
+        assert_eq!(elf_hash(b"abc\0\0\0"), hash);
+        assert_ne!(elf_hash(b"ab\0c"), hash);
+    }
+
+    #[test]
+    fn test_build_metadata_address() {
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let address = build_metadata_address(&program_id, &authority);
+        assert_eq!(address, build_metadata_address(&program_id, &authority));
+        assert_ne!(
+            address,
+            build_metadata_address(&Pubkey::new_unique(), &authority)
+        );
+        assert_ne!(
+            address,
+            build_metadata_address(&program_id, &Pubkey::new_unique())
+        );
+    }
+
+    #[test]
+    fn test_read_build_metadata_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = dir.path().join("build-metadata.json");
+        let location = location.to_str().unwrap();
+        let program_data = b"program\0\0";
+
+        fs::write(
+            location,
+            r#"{"repository":"https://github.com/user/program","commit":"0123abc"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            read_build_metadata_file(location, program_data).unwrap(),
+            BuildMetadata {
+                repository: Some("https://github.com/user/program".to_string()),
+                commit: Some("0123abc".to_string()),
+                toolchain: None,
+                elf_hash: elf_hash(program_data),
+            }
+        );
+
+        fs::write(
+            location,
+            format!(
+                r#"{{"toolchain":"platform-tools v1.51","elfHash":"{}"}}"#,
+                elf_hash(b"program")
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            read_build_metadata_file(location, program_data)
+                .unwrap()
+                .toolchain
+                .as_deref(),
+            Some("platform-tools v1.51")
+        );
+        read_build_metadata_file(location, b"other program").unwrap_err();
+
+        fs::write(
+            location,
+            format!(
+                r#"{{"repository":"{}"}}"#,
+                "a".repeat(MAX_BUILD_METADATA_LEN)
+            ),
+        )
+        .unwrap();
+        read_build_metadata_file(location, program_data).unwrap_err();
+    }
+}
@@ -2,7 +2,8 @@ use {
     crate::{
         address_lookup_table::AddressLookupTableSubCommands, cli::*, cluster_query::*, feature::*,
         inflation::*, nonce::*, program::*, program_v4::ProgramV4SubCommands, stake::*,
-        validator_info::*, vote::*, wallet::*,
+        transaction::TransactionSubCommands, tx_bundle::TxBundleSubCommands, validator_info::*,
+        vote::*, wallet::*,
     },
     clap::{App, AppSettings, Arg, ArgGroup, SubCommand},
     solana_clap_utils::{self, hidden_unless_forced, input_validators::*, keypair::*},
@@ -10,7 +11,7 @@ use {
 };
 
 pub fn get_clap_app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> App<'ab, 'v> {
-    App::new(name)
+    let app = App::new(name)
         .about(about)
         .version(version)
         .setting(AppSettings::SubcommandRequiredElseHelp)
@@ -101,6 +102,44 @@ pub fn get_clap_app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> A
                 .global(true)
                 .help("Use TPU client when sending transactions."),
         )
+        .arg(
+            Arg::with_name("auto_compute_budget")
+                .long("auto-compute-budget")
+                .global(true)
+                .takes_value(false)
+                .help(
+                    "Set the compute unit limit of sent transactions from their simulation, and \
+                     their compute unit price from the recent prioritization fees of their \
+                     writable accounts, raising it while they expire unconfirmed. Replaces any \
+                     compute unit price or limit given to the command",
+                ),
+        )
+        .arg(
+            Arg::with_name("priority_fee_percentile")
+                .long("priority-fee-percentile")
+                .value_name("PERCENTILE")
+                .takes_value(true)
+                .default_value(DEFAULT_PRIORITY_FEE_PERCENTILE)
+                .global(true)
+                .validator(is_valid_percentage)
+                .help(
+                    "Percentile of the recent prioritization fees to price compute units at with \
+                     --auto-compute-budget",
+                ),
+        )
+        .arg(
+            Arg::with_name("max_priority_fee")
+                .long("max-priority-fee")
+                .value_name("LAMPORTS")
+                .takes_value(true)
+                .default_value(DEFAULT_MAX_PRIORITY_FEE_LAMPORTS)
+                .global(true)
+                .validator(is_parsable::<u64>)
+                .help(
+                    "Most lamports to pay as priority fee of each transaction with \
+                     --auto-compute-budget",
+                ),
+        )
         .arg(
             Arg::with_name("no_address_labels")
                 .long("no-address-labels")
@@ -141,18 +180,9 @@ pub fn get_clap_app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> A
                 .global(true)
                 .hidden(hidden_unless_forced())
                 .help("Timeout value for initial transaction status"),
-        )
-        .cluster_query_subcommands()
-        .feature_subcommands()
-        .inflation_subcommands()
-        .nonce_subcommands()
-        .program_subcommands()
-        .program_v4_subcommands()
-        .address_lookup_table_subcommands()
-        .stake_subcommands()
-        .validator_info_subcommands()
-        .vote_subcommands()
-        .wallet_subcommands()
+        );
+    add_command_subcommands(app)
+        .tx_bundle_subcommands()
         .subcommand(
             SubCommand::with_name("config")
                 .about("Solana command-line tool configuration settings")
@@ -221,3 +251,20 @@ pub fn get_clap_app<'ab, 'v>(name: &str, about: &'ab str, version: &'v str) -> A
                 ),
         )
 }
+
+/// Adds the subcommands of the commands that query or transact with a
+/// cluster, which `tx-bundle create` can also run in sign-only mode.
+pub(crate) fn add_command_subcommands<'ab, 'v>(app: App<'ab, 'v>) -> App<'ab, 'v> {
+    app.cluster_query_subcommands()
+        .feature_subcommands()
+        .inflation_subcommands()
+        .nonce_subcommands()
+        .program_subcommands()
+        .program_v4_subcommands()
+        .address_lookup_table_subcommands()
+        .stake_subcommands()
+        .transaction_subcommands()
+        .validator_info_subcommands()
+        .vote_subcommands()
+        .wallet_subcommands()
+}
@@ -1,7 +1,8 @@
 use {
     crate::{
-        address_lookup_table::*, clap_app::*, cluster_query::*, feature::*, inflation::*, nonce::*,
-        program::*, program_v4::*, spend_utils::*, stake::*, validator_info::*, vote::*, wallet::*,
+        address_lookup_table::*, clap_app::*, cluster_query::*, compute_budget::AutoComputeBudget,
+        feature::*, inflation::*, nonce::*, program::*, program_v4::*, spend_utils::*, stake::*,
+        transaction::*, tx_bundle::*, validator_info::*, vote::*, wallet::*,
     },
     clap::{crate_description, crate_name, value_t_or_exit, ArgMatches, Shell},
     num_traits::FromPrimitive,
@@ -43,6 +44,8 @@ use {
 
 pub const DEFAULT_RPC_TIMEOUT_SECONDS: &str = "30";
 pub const DEFAULT_CONFIRM_TX_TIMEOUT_SECONDS: &str = "5";
+pub const DEFAULT_PRIORITY_FEE_PERCENTILE: &str = "75";
+pub const DEFAULT_MAX_PRIORITY_FEE_LAMPORTS: &str = "5000000";
 const CHECKED: bool = true;
 pub const DEFAULT_PING_USE_TPU_CLIENT: bool = false;
 
@@ -460,6 +463,9 @@ pub enum CliCommand {
     },
     // Address lookup table commands
     AddressLookupTable(AddressLookupTableCliCommand),
+    // Transaction commands
+    Transaction(TransactionCliCommand),
+    TxBundle(TxBundleCliCommand),
     SignOffchainMessage {
         message: OffchainMessage,
     },
@@ -543,6 +549,7 @@ pub struct CliConfig<'a> {
     pub confirm_transaction_initial_timeout: Duration,
     pub address_labels: HashMap<String, String>,
     pub use_tpu_client: bool,
+    pub auto_compute_budget: Option<AutoComputeBudget>,
 }
 
 impl CliConfig<'_> {
@@ -591,6 +598,7 @@ impl Default for CliConfig<'_> {
             ),
             address_labels: HashMap::new(),
             use_tpu_client: DEFAULT_PING_USE_TPU_CLIENT,
+            auto_compute_budget: None,
         }
     }
 }
@@ -706,6 +714,12 @@ pub fn parse_command(
         ("address-lookup-table", Some(matches)) => {
             parse_address_lookup_table_subcommand(matches, default_signer, wallet_manager)
         }
+        ("transaction", Some(matches)) => {
+            parse_transaction_subcommand(matches, default_signer, wallet_manager)
+        }
+        ("tx-bundle", Some(matches)) => {
+            parse_tx_bundle_subcommand(matches, default_signer, wallet_manager)
+        }
         ("wait-for-max-stake", Some(matches)) => {
             let max_stake_percent = value_t_or_exit!(matches, "max_percent", f32);
             Ok(CliCommandInfo::without_signers(
@@ -856,6 +870,15 @@ pub fn parse_command(
 pub type ProcessResult = Result<String, Box<dyn std::error::Error>>;
 
 pub async fn process_command(config: &CliConfig<'_>) -> ProcessResult {
+    process_command_with(config, &config.command).await
+}
+
+/// Processes `command` rather than the command of `config`, for commands that
+/// run other commands.
+pub(crate) async fn process_command_with(
+    config: &CliConfig<'_>,
+    command: &CliCommand,
+) -> ProcessResult {
     if config.verbose && config.output_format == OutputFormat::DisplayVerbose {
         println_name_value("RPC URL:", &config.json_rpc_url);
         println_name_value("Default Signer Path:", &config.keypair_path);
@@ -881,7 +904,7 @@ pub async fn process_command(config: &CliConfig<'_>) -> ProcessResult {
         ))
     };
 
-    match &config.command {
+    match command {
         // Cluster Query Commands
         // Get address of this client
         CliCommand::Address => Ok(format!("{}", config.pubkey()?)),
@@ -1857,6 +1880,12 @@ pub async fn process_command(config: &CliConfig<'_>) -> ProcessResult {
         CliCommand::AddressLookupTable(subcommand) => {
             process_address_lookup_table_subcommand(rpc_client, config, subcommand).await
         }
+        CliCommand::Transaction(subcommand) => {
+            process_transaction_subcommand(&rpc_client, config, subcommand).await
+        }
+        CliCommand::TxBundle(subcommand) => {
+            process_tx_bundle_subcommand(&rpc_client, config, subcommand).await
+        }
         CliCommand::SignOffchainMessage { message } => {
             process_sign_offchain_message(config, message)
         }
@@ -1600,7 +1600,7 @@ where
             &config.signers[0].pubkey(),
             compute_unit_limit,
             build_message,
-            config.commitment,
+            config,
         )
         .await?;
         let mut tx = Transaction::new_unsigned(message);
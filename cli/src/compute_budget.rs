@@ -1,13 +1,28 @@
 use {
+    crate::{cli::CliConfig, transaction::get_address_lookup_table_account},
+    log::*,
     solana_borsh::v1::try_from_slice_unchecked,
     solana_clap_utils::compute_budget::ComputeUnitLimit,
+    solana_cli_output::{display::println_name_value, OutputFormat},
+    solana_commitment_config::CommitmentConfig,
     solana_compute_budget_interface::{self as compute_budget, ComputeBudgetInstruction},
-    solana_instruction::Instruction,
-    solana_message::Message,
+    solana_hash::Hash,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_message::{v0, AddressLookupTableAccount, Message, VersionedMessage},
     solana_program_runtime::execution_budget::MAX_COMPUTE_UNIT_LIMIT,
+    solana_pubkey::Pubkey,
     solana_rpc_client::nonblocking::rpc_client::RpcClient,
-    solana_rpc_client_api::config::RpcSimulateTransactionConfig,
-    solana_transaction::Transaction,
+    solana_rpc_client_api::{
+        client_error::{ErrorKind as ClientErrorKind, Result as ClientResult},
+        config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig},
+        response::RpcPrioritizationFee,
+    },
+    solana_sdk_ids::system_program,
+    solana_signature::Signature,
+    solana_signer::Signer,
+    solana_system_interface::instruction::SystemInstruction,
+    solana_transaction::{versioned::VersionedTransaction, Transaction},
+    std::time::Duration,
 };
 
 /// Enum capturing the possible results of updating a message based on the
@@ -160,3 +175,750 @@ impl WithComputeUnitConfig for Vec<Instruction> {
         self
     }
 }
+
+/// Extra compute units given to transactions over those they consumed in
+/// simulation, in percent, in the automatic mode.
+const AUTO_COMPUTE_UNIT_LIMIT_EXTRA_PERCENTAGE: u64 = 10;
+
+/// Lowest compute unit price that transactions are retried with, in
+/// micro-lamports, when they expire without a priority fee.
+const MIN_ESCALATED_COMPUTE_UNIT_PRICE: u64 = 10_000;
+
+const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
+/// The automatic compute budget of the transactions that the CLI sends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AutoComputeBudget {
+    /// Percentile of the recent prioritization fees paid to write the
+    /// writable accounts of a transaction, to price its compute units at
+    pub priority_fee_percentile: u8,
+    /// Most lamports to pay for the priority of a transaction, which its
+    /// price is raised up to when it expires unconfirmed
+    pub max_priority_fee: u64,
+}
+
+pub(crate) trait SendWithComputeBudget {
+    /// Like `send_and_confirm_transaction_with_spinner_and_config`, with the
+    /// automatic compute budget of `config` if it is enabled.
+    ///
+    /// The transaction is then built again with a simulated compute unit
+    /// limit and a compute unit price from the recent prioritization fees,
+    /// signed again by the signers of `config`, and sent with a higher price
+    /// each time its blockhash expires before it is confirmed. Transactions
+    /// that cannot be signed again, such as those signed offline, are sent as
+    /// they are, without being simulated.
+    async fn send_and_confirm_transaction_with_compute_budget(
+        &self,
+        transaction: &Transaction,
+        config: &CliConfig<'_>,
+        send_transaction_config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature>;
+
+    /// Like `send_and_confirm_transaction_with_compute_budget`, for versioned
+    /// transactions. The address lookup tables of the transaction are fetched
+    /// to build it again.
+    async fn send_and_confirm_versioned_transaction_with_compute_budget(
+        &self,
+        transaction: &VersionedTransaction,
+        config: &CliConfig<'_>,
+        send_transaction_config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature>;
+
+    /// Like `send_transaction_with_config`, with the automatic compute budget
+    /// of `config` if it is enabled. The transaction is sent once without
+    /// waiting for it to be confirmed, so its price is never raised.
+    async fn send_transaction_with_compute_budget(
+        &self,
+        transaction: &Transaction,
+        config: &CliConfig<'_>,
+        send_transaction_config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature>;
+}
+
+impl SendWithComputeBudget for RpcClient {
+    async fn send_and_confirm_transaction_with_compute_budget(
+        &self,
+        transaction: &Transaction,
+        config: &CliConfig<'_>,
+        send_transaction_config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature> {
+        self.send_and_confirm_versioned_transaction_with_compute_budget(
+            &VersionedTransaction::from(transaction.clone()),
+            config,
+            send_transaction_config,
+        )
+        .await
+    }
+
+    async fn send_and_confirm_versioned_transaction_with_compute_budget(
+        &self,
+        transaction: &VersionedTransaction,
+        config: &CliConfig<'_>,
+        send_transaction_config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature> {
+        if let Some(auto_compute_budget) = config
+            .auto_compute_budget
+            .as_ref()
+            .filter(|_| has_required_signers(&transaction.message, &config.signers))
+        {
+            let mut auto_sender = AutoComputeBudgetSender::new(
+                self,
+                auto_compute_budget,
+                &transaction.message,
+                config,
+            )
+            .await?;
+            if let Some(transaction) = auto_sender.try_sign(transaction.message.recent_blockhash())
+            {
+                return auto_sender
+                    .send_and_confirm(transaction, config.commitment, send_transaction_config)
+                    .await;
+            }
+        }
+        self.send_and_confirm_transaction_with_spinner_and_config(
+            transaction,
+            config.commitment,
+            send_transaction_config,
+        )
+        .await
+    }
+
+    async fn send_transaction_with_compute_budget(
+        &self,
+        transaction: &Transaction,
+        config: &CliConfig<'_>,
+        send_transaction_config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature> {
+        let message = VersionedMessage::Legacy(transaction.message.clone());
+        if let Some(auto_compute_budget) = config
+            .auto_compute_budget
+            .as_ref()
+            .filter(|_| has_required_signers(&message, &config.signers))
+        {
+            let auto_sender =
+                AutoComputeBudgetSender::new(self, auto_compute_budget, &message, config).await?;
+            if let Some(transaction) = auto_sender.try_sign(&transaction.message.recent_blockhash) {
+                return self
+                    .send_transaction_with_config(&transaction, send_transaction_config)
+                    .await;
+            }
+        }
+        self.send_transaction_with_config(transaction, send_transaction_config)
+            .await
+    }
+}
+
+/// Returns `true` if `signers` hold every signer required by `message`, and
+/// they can all sign it again with another compute budget. Signatures made
+/// offline, such as those of presigners and null signers, cannot.
+fn has_required_signers(message: &VersionedMessage, signers: &[&dyn Signer]) -> bool {
+    let num_required_signatures = usize::from(message.header().num_required_signatures);
+    message
+        .static_account_keys()
+        .iter()
+        .take(num_required_signatures)
+        .all(|signer_key| {
+            signers.iter().any(|signer| {
+                signer
+                    .try_pubkey()
+                    .is_ok_and(|pubkey| pubkey == *signer_key)
+                    && (signer.is_interactive()
+                        || signer
+                            .try_sign_message(&[])
+                            .is_ok_and(|signature| signature != Signature::default()))
+            })
+        })
+}
+
+/// The compute unit limit and price that the automatic compute budget gives
+/// a transaction.
+pub(crate) struct AutoComputeUnitConfig {
+    max_priority_fee: u64,
+    compute_unit_limit: u32,
+    compute_unit_price: u64,
+}
+
+impl AutoComputeUnitConfig {
+    /// Simulates `message` for its compute unit limit, and prices its compute
+    /// units from the recent prioritization fees of its writable accounts.
+    /// The compute unit limit and price of `message` itself are ignored.
+    pub(crate) async fn new(
+        rpc_client: &RpcClient,
+        auto_compute_budget: &AutoComputeBudget,
+        message: &Message,
+    ) -> ClientResult<Self> {
+        let instructions = decompile_instructions_without_compute_budget(
+            &VersionedMessage::Legacy(message.clone()),
+            &message.account_keys,
+        );
+        Self::new_for_instructions(
+            rpc_client,
+            auto_compute_budget,
+            &instructions,
+            &message.account_keys[0],
+            None,
+        )
+        .await
+    }
+
+    async fn new_for_instructions(
+        rpc_client: &RpcClient,
+        auto_compute_budget: &AutoComputeBudget,
+        instructions: &[Instruction],
+        fee_payer: &Pubkey,
+        address_lookup_table_accounts: Option<&[AddressLookupTableAccount]>,
+    ) -> ClientResult<Self> {
+        let simulation_message = compile_message(
+            with_compute_budget(instructions.to_vec(), MAX_COMPUTE_UNIT_LIMIT, 0),
+            fee_payer,
+            address_lookup_table_accounts,
+            &Hash::default(),
+        )?;
+        let units_consumed =
+            simulate_for_compute_unit_limit_with_client_error(rpc_client, simulation_message)
+                .await?;
+        let compute_unit_limit = u64::from(units_consumed)
+            .saturating_mul(100_u64.saturating_add(AUTO_COMPUTE_UNIT_LIMIT_EXTRA_PERCENTAGE))
+            .saturating_div(100)
+            .min(u64::from(MAX_COMPUTE_UNIT_LIMIT)) as u32;
+
+        let mut writable_accounts = instructions
+            .iter()
+            .flat_map(|instruction| &instruction.accounts)
+            .filter(|account_meta| account_meta.is_writable)
+            .map(|account_meta| account_meta.pubkey)
+            .chain(std::iter::once(*fee_payer))
+            .collect::<Vec<_>>();
+        writable_accounts.sort_unstable();
+        writable_accounts.dedup();
+        let prioritization_fees = rpc_client
+            .get_recent_prioritization_fees(&writable_accounts)
+            .await?;
+        let compute_unit_price = prioritization_fee_percentile(
+            &prioritization_fees,
+            auto_compute_budget.priority_fee_percentile,
+        )
+        .min(max_compute_unit_price(
+            auto_compute_budget.max_priority_fee,
+            compute_unit_limit,
+        ));
+
+        Ok(Self {
+            max_priority_fee: auto_compute_budget.max_priority_fee,
+            compute_unit_limit,
+            compute_unit_price,
+        })
+    }
+
+    pub(crate) fn compute_unit_price(&self) -> u64 {
+        self.compute_unit_price
+    }
+
+    /// Raises the compute unit price after a transaction expired unconfirmed.
+    /// Returns `false` if the price is already at the maximum priority fee.
+    pub(crate) fn escalate(&mut self) -> bool {
+        let max_compute_unit_price =
+            max_compute_unit_price(self.max_priority_fee, self.compute_unit_limit);
+        if self.compute_unit_price >= max_compute_unit_price {
+            return false;
+        }
+        self.compute_unit_price = self
+            .compute_unit_price
+            .saturating_mul(2)
+            .max(MIN_ESCALATED_COMPUTE_UNIT_PRICE)
+            .min(max_compute_unit_price);
+        true
+    }
+
+    /// Builds `message` again with this compute unit limit and price instead
+    /// of its own.
+    pub(crate) fn apply(&self, message: &Message) -> Message {
+        let instructions = decompile_instructions_without_compute_budget(
+            &VersionedMessage::Legacy(message.clone()),
+            &message.account_keys,
+        );
+        Message::new_with_blockhash(
+            &self.with_compute_budget(instructions),
+            Some(&message.account_keys[0]),
+            &message.recent_blockhash,
+        )
+    }
+
+    fn with_compute_budget(&self, instructions: Vec<Instruction>) -> Vec<Instruction> {
+        with_compute_budget(
+            instructions,
+            self.compute_unit_limit,
+            self.compute_unit_price,
+        )
+    }
+}
+
+struct AutoComputeBudgetSender<'a, 'b> {
+    rpc_client: &'a RpcClient,
+    /// Without compute unit limit and price
+    instructions: Vec<Instruction>,
+    fee_payer: Pubkey,
+    /// `None` for legacy transactions
+    address_lookup_table_accounts: Option<Vec<AddressLookupTableAccount>>,
+    signers: &'a [&'b dyn Signer],
+    uses_durable_nonce: bool,
+    compute_unit_config: AutoComputeUnitConfig,
+    verbose: bool,
+}
+
+impl<'a, 'b> AutoComputeBudgetSender<'a, 'b> {
+    async fn new(
+        rpc_client: &'a RpcClient,
+        auto_compute_budget: &AutoComputeBudget,
+        message: &VersionedMessage,
+        config: &'a CliConfig<'b>,
+    ) -> ClientResult<Self> {
+        let (account_keys, address_lookup_table_accounts) = match message {
+            VersionedMessage::Legacy(message) => (message.account_keys.clone(), None),
+            VersionedMessage::V0(message) => {
+                let mut address_lookup_table_accounts =
+                    Vec::with_capacity(message.address_table_lookups.len());
+                for lookup in &message.address_table_lookups {
+                    address_lookup_table_accounts.push(
+                        get_address_lookup_table_account(rpc_client, config, &lookup.account_key)
+                            .await
+                            .map_err(|err| ClientErrorKind::Custom(err.to_string()))?,
+                    );
+                }
+                let account_keys = lookup_account_keys(message, &address_lookup_table_accounts)?;
+                (account_keys, Some(address_lookup_table_accounts))
+            }
+        };
+        let instructions = decompile_instructions_without_compute_budget(message, &account_keys);
+        let fee_payer = account_keys[0];
+        let compute_unit_config = AutoComputeUnitConfig::new_for_instructions(
+            rpc_client,
+            auto_compute_budget,
+            &instructions,
+            &fee_payer,
+            address_lookup_table_accounts.as_deref(),
+        )
+        .await?;
+
+        Ok(Self {
+            rpc_client,
+            uses_durable_nonce: instructions
+                .first()
+                .is_some_and(is_advance_nonce_instruction),
+            instructions,
+            fee_payer,
+            address_lookup_table_accounts,
+            signers: &config.signers,
+            compute_unit_config,
+            verbose: config.verbose && config.output_format == OutputFormat::DisplayVerbose,
+        })
+    }
+
+    /// Signs the transaction with the current compute budget, or returns
+    /// `None` if it cannot be built again or its signers cannot sign it.
+    fn try_sign(&self, blockhash: &Hash) -> Option<VersionedTransaction> {
+        let message = compile_message(
+            self.compute_unit_config
+                .with_compute_budget(self.instructions.clone()),
+            &self.fee_payer,
+            self.address_lookup_table_accounts.as_deref(),
+            blockhash,
+        )
+        .ok()?;
+        let num_required_signatures = usize::from(message.header().num_required_signatures);
+        let signer_keys = &message.static_account_keys()[..num_required_signatures];
+        let mut signers = Vec::with_capacity(num_required_signatures);
+        for signer_key in signer_keys {
+            let signer = self.signers.iter().copied().find(|signer| {
+                signer
+                    .try_pubkey()
+                    .is_ok_and(|pubkey| pubkey == *signer_key)
+            })?;
+            signers.push(signer);
+        }
+        VersionedTransaction::try_new(message, &signers).ok()
+    }
+
+    async fn send_and_confirm(
+        &mut self,
+        mut transaction: VersionedTransaction,
+        commitment: CommitmentConfig,
+        send_transaction_config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature> {
+        // Transactions with a durable nonce don't expire, and all but one of
+        // those sent with the same nonce would fail
+        if self.uses_durable_nonce {
+            return self
+                .rpc_client
+                .send_and_confirm_transaction_with_spinner_and_config(
+                    &transaction,
+                    commitment,
+                    send_transaction_config,
+                )
+                .await;
+        }
+        loop {
+            if let Some(signature) = self
+                .send_and_confirm_before_expiry(&transaction, commitment, send_transaction_config)
+                .await?
+            {
+                return Ok(signature);
+            }
+            // The expired transaction can no longer land, so one with a higher
+            // price does not risk being processed as well
+            if !self.compute_unit_config.escalate() {
+                return Err(ClientErrorKind::Custom(format!(
+                    "transaction expired unconfirmed at the maximum priority fee of {} lamports",
+                    self.compute_unit_config.max_priority_fee
+                ))
+                .into());
+            }
+            let compute_unit_price = self.compute_unit_config.compute_unit_price();
+            info!(
+                "Transaction expired unconfirmed, retrying with a compute unit price of \
+                 {compute_unit_price} micro-lamports"
+            );
+            if self.verbose {
+                println_name_value(
+                    "Retrying with a compute unit price of:",
+                    &format!("{compute_unit_price} micro-lamports"),
+                );
+            }
+            let blockhash = self.rpc_client.get_latest_blockhash().await?;
+            transaction = self.try_sign(&blockhash).ok_or_else(|| {
+                ClientErrorKind::Custom("unable to sign the transaction again".to_string())
+            })?;
+        }
+    }
+
+    /// Returns `None` if the blockhash of the transaction expires before it
+    /// lands.
+    async fn send_and_confirm_before_expiry(
+        &self,
+        transaction: &VersionedTransaction,
+        commitment: CommitmentConfig,
+        send_transaction_config: RpcSendTransactionConfig,
+    ) -> ClientResult<Option<Signature>> {
+        let recent_blockhash = transaction.message.recent_blockhash();
+        let signature = self
+            .rpc_client
+            .send_transaction_with_config(transaction, send_transaction_config)
+            .await?;
+        let mut expired = false;
+        loop {
+            let status = self
+                .rpc_client
+                .get_signature_status_with_commitment(&signature, CommitmentConfig::processed())
+                .await?;
+            match status {
+                Some(Ok(())) => {
+                    self.rpc_client
+                        .confirm_transaction_with_spinner(&signature, recent_blockhash, commitment)
+                        .await?;
+                    return Ok(Some(signature));
+                }
+                Some(Err(err)) => return Err(err.into()),
+                // Checked once more after expiry, in case it landed meanwhile
+                None if expired => return Ok(None),
+                None => {
+                    expired = !self
+                        .rpc_client
+                        .is_blockhash_valid(recent_blockhash, CommitmentConfig::processed())
+                        .await?;
+                }
+            }
+            if !expired {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        }
+    }
+}
+
+/// Compiles a legacy message, or a v0 message with `address_lookup_table_accounts`.
+fn compile_message(
+    instructions: Vec<Instruction>,
+    fee_payer: &Pubkey,
+    address_lookup_table_accounts: Option<&[AddressLookupTableAccount]>,
+    blockhash: &Hash,
+) -> ClientResult<VersionedMessage> {
+    match address_lookup_table_accounts {
+        None => Ok(VersionedMessage::Legacy(Message::new_with_blockhash(
+            &instructions,
+            Some(fee_payer),
+            blockhash,
+        ))),
+        Some(address_lookup_table_accounts) => v0::Message::try_compile(
+            fee_payer,
+            &instructions,
+            address_lookup_table_accounts,
+            *blockhash,
+        )
+        .map(VersionedMessage::V0)
+        .map_err(|err| ClientErrorKind::Custom(err.to_string()).into()),
+    }
+}
+
+/// The static account keys of `message`, followed by those it loads from
+/// `address_lookup_table_accounts`: the writable ones first, then the
+/// read-only ones.
+fn lookup_account_keys(
+    message: &v0::Message,
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+) -> ClientResult<Vec<Pubkey>> {
+    let mut account_keys = message.account_keys.clone();
+    for writable in [true, false] {
+        for (lookup, table) in message
+            .address_table_lookups
+            .iter()
+            .zip(address_lookup_table_accounts)
+        {
+            let indexes = if writable {
+                &lookup.writable_indexes
+            } else {
+                &lookup.readonly_indexes
+            };
+            for index in indexes {
+                let address = table.addresses.get(usize::from(*index)).ok_or_else(|| {
+                    ClientErrorKind::Custom(format!(
+                        "the transaction looks up an address missing from the lookup table {}",
+                        table.key
+                    ))
+                })?;
+                account_keys.push(*address);
+            }
+        }
+    }
+    Ok(account_keys)
+}
+
+fn is_advance_nonce_instruction(instruction: &Instruction) -> bool {
+    system_program::check_id(&instruction.program_id)
+        && matches!(
+            bincode::deserialize::<SystemInstruction>(&instruction.data),
+            Ok(SystemInstruction::AdvanceNonceAccount)
+        )
+}
+
+fn with_compute_budget(
+    mut instructions: Vec<Instruction>,
+    compute_unit_limit: u32,
+    compute_unit_price: u64,
+) -> Vec<Instruction> {
+    instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+        compute_unit_limit,
+    ));
+    instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+        compute_unit_price,
+    ));
+    instructions
+}
+
+/// The instructions of `message`, but those setting its compute unit limit or
+/// price. `account_keys` are all of the account keys of `message`, including
+/// those it loads from address lookup tables.
+fn decompile_instructions_without_compute_budget(
+    message: &VersionedMessage,
+    account_keys: &[Pubkey],
+) -> Vec<Instruction> {
+    message
+        .instructions()
+        .iter()
+        .map(|instruction| Instruction {
+            program_id: account_keys[usize::from(instruction.program_id_index)],
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|index| {
+                    let index = usize::from(*index);
+                    AccountMeta {
+                        pubkey: account_keys[index],
+                        is_signer: message.is_signer(index),
+                        is_writable: message.is_maybe_writable(index, None),
+                    }
+                })
+                .collect(),
+            data: instruction.data.clone(),
+        })
+        .filter(|instruction| {
+            instruction.program_id != compute_budget::id()
+                || !matches!(
+                    try_from_slice_unchecked::<ComputeBudgetInstruction>(&instruction.data),
+                    Ok(ComputeBudgetInstruction::SetComputeUnitLimit(_)
+                        | ComputeBudgetInstruction::SetComputeUnitPrice(_))
+                )
+        })
+        .collect()
+}
+
+/// Like `simulate_for_compute_unit_limit_unchecked`, keeping the transaction
+/// error of failed simulations.
+async fn simulate_for_compute_unit_limit_with_client_error(
+    rpc_client: &RpcClient,
+    message: VersionedMessage,
+) -> ClientResult<u32> {
+    let num_required_signatures = usize::from(message.header().num_required_signatures);
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default(); num_required_signatures],
+        message,
+    };
+    let simulate_result = rpc_client
+        .simulate_transaction_with_config(
+            &transaction,
+            RpcSimulateTransactionConfig {
+                replace_recent_blockhash: true,
+                commitment: Some(rpc_client.commitment()),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .await?
+        .value;
+    if let Some(err) = simulate_result.err {
+        return Err(ClientErrorKind::TransactionError(err.into()).into());
+    }
+    simulate_result
+        .units_consumed
+        .and_then(|units_consumed| u32::try_from(units_consumed).ok())
+        .ok_or_else(|| ClientErrorKind::Custom("compute units unavailable".to_string()).into())
+}
+
+/// The `percentile` of recent prioritization fees, in micro-lamports per
+/// compute unit, or zero without any.
+fn prioritization_fee_percentile(fees: &[RpcPrioritizationFee], percentile: u8) -> u64 {
+    let mut fees = fees
+        .iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect::<Vec<_>>();
+    fees.sort_unstable();
+    let index = fees
+        .len()
+        .saturating_sub(1)
+        .saturating_mul(usize::from(percentile))
+        .saturating_div(100);
+    fees.get(index).copied().unwrap_or_default()
+}
+
+/// The highest compute unit price of a transaction, in micro-lamports, for
+/// its priority fee to stay within `max_priority_fee` lamports.
+fn max_compute_unit_price(max_priority_fee: u64, compute_unit_limit: u32) -> u64 {
+    max_priority_fee
+        .saturating_mul(MICRO_LAMPORTS_PER_LAMPORT)
+        .checked_div(u64::from(compute_unit_limit))
+        .unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_keypair::Keypair, solana_message::v0::MessageAddressTableLookup,
+        solana_presigner::Presigner, solana_signer::null_signer::NullSigner,
+        solana_system_interface::instruction as system_instruction,
+    };
+
+    #[test]
+    fn test_decompile_instructions_without_compute_budget() {
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        let transfer = system_instruction::transfer(&from, &to, 42);
+        let heap_frame = ComputeBudgetInstruction::request_heap_frame(64 * 1024);
+        let message = Message::new(
+            &with_compute_budget(vec![transfer.clone(), heap_frame.clone()], 1_000, 5),
+            Some(&from),
+        );
+        assert_eq!(
+            decompile_instructions_without_compute_budget(
+                &VersionedMessage::Legacy(message.clone()),
+                &message.account_keys
+            ),
+            vec![transfer, heap_frame]
+        );
+    }
+
+    #[test]
+    fn test_has_required_signers() {
+        let payer = Keypair::new();
+        let from = Keypair::new();
+        let to = Pubkey::new_unique();
+        let message = Message::new(
+            &[system_instruction::transfer(&from.pubkey(), &to, 42)],
+            Some(&payer.pubkey()),
+        );
+        let from_presigner =
+            Presigner::new(&from.pubkey(), &from.sign_message(&message.serialize()));
+        let message = VersionedMessage::Legacy(message);
+        assert!(has_required_signers(&message, &[&from, &payer]));
+        assert!(!has_required_signers(&message, &[&payer]));
+        assert!(!has_required_signers(&message, &[&payer, &from_presigner]));
+        assert!(!has_required_signers(
+            &message,
+            &[&payer, &NullSigner::new(&from.pubkey())]
+        ));
+    }
+
+    #[test]
+    fn test_lookup_account_keys() {
+        let payer = Pubkey::new_unique();
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: (0..4).map(|_| Pubkey::new_unique()).collect(),
+        };
+        let other_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: (0..2).map(|_| Pubkey::new_unique()).collect(),
+        };
+        let mut message = v0::Message {
+            account_keys: vec![payer],
+            address_table_lookups: vec![
+                MessageAddressTableLookup {
+                    account_key: table.key,
+                    writable_indexes: vec![2],
+                    readonly_indexes: vec![0, 3],
+                },
+                MessageAddressTableLookup {
+                    account_key: other_table.key,
+                    writable_indexes: vec![1],
+                    readonly_indexes: vec![0],
+                },
+            ],
+            ..v0::Message::default()
+        };
+        let tables = [table.clone(), other_table.clone()];
+        assert_eq!(
+            lookup_account_keys(&message, &tables).unwrap(),
+            vec![
+                payer,
+                table.addresses[2],
+                other_table.addresses[1],
+                table.addresses[0],
+                table.addresses[3],
+                other_table.addresses[0],
+            ]
+        );
+
+        message.address_table_lookups[1].readonly_indexes = vec![2];
+        assert!(lookup_account_keys(&message, &tables).is_err());
+    }
+
+    #[test]
+    fn test_prioritization_fee_percentile() {
+        let fees = [30, 10, 50, 20, 40]
+            .into_iter()
+            .enumerate()
+            .map(|(slot, prioritization_fee)| RpcPrioritizationFee {
+                slot: slot as u64,
+                prioritization_fee,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(prioritization_fee_percentile(&fees, 0), 10);
+        assert_eq!(prioritization_fee_percentile(&fees, 50), 30);
+        assert_eq!(prioritization_fee_percentile(&fees, 75), 40);
+        assert_eq!(prioritization_fee_percentile(&fees, 100), 50);
+        assert_eq!(prioritization_fee_percentile(&[], 75), 0);
+    }
+
+    #[test]
+    fn test_max_compute_unit_price() {
+        assert_eq!(max_compute_unit_price(5_000_000, 200_000), 25_000_000);
+        assert_eq!(max_compute_unit_price(1, 1_400_000), 0);
+        assert_eq!(max_compute_unit_price(1, 0), u64::MAX);
+    }
+}
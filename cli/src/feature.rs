@@ -4,6 +4,7 @@ use {
             log_instruction_custom_error, log_instruction_custom_error_to_str, CliCommand,
             CliCommandInfo, CliConfig, CliError, ProcessResult,
         },
+        compute_budget::SendWithComputeBudget,
         spend_utils::{resolve_spend_tx_and_check_account_balance, SpendAmount},
     },
     agave_feature_set::FEATURE_NAMES,
@@ -1040,7 +1041,7 @@ async fn process_activate(
                 Some(&fee_payer.pubkey()),
             )
         },
-        config.commitment,
+        config,
     )
     .await?;
     let mut transaction = Transaction::new_unsigned(message);
@@ -1052,9 +1053,9 @@ async fn process_activate(
         feature_id
     );
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &transaction,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await;
@@ -1097,7 +1098,7 @@ async fn process_revoke(
                 Some(&fee_payer.pubkey()),
             )
         },
-        config.commitment,
+        config,
     )
     .await?;
     let mut transaction = Transaction::new_unsigned(message);
@@ -1109,9 +1110,9 @@ async fn process_revoke(
         feature_id
     );
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &transaction,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await;
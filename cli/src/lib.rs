@@ -21,6 +21,7 @@ macro_rules! pubkey {
 extern crate const_format;
 
 pub mod address_lookup_table;
+pub mod build_metadata;
 pub mod checks;
 pub mod clap_app;
 pub mod cli;
@@ -31,10 +32,13 @@ pub mod inflation;
 pub mod memo;
 pub mod nonce;
 pub mod program;
+pub mod program_upload;
 pub mod program_v4;
 pub mod spend_utils;
 pub mod stake;
 pub mod test_utils;
+pub mod transaction;
+pub mod tx_bundle;
 pub mod validator_info;
 pub mod vote;
 pub mod wallet;
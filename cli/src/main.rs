@@ -9,6 +9,7 @@ use {
     solana_cli::{
         clap_app::get_clap_app,
         cli::{parse_command, process_command, CliCommandInfo, CliConfig},
+        compute_budget::AutoComputeBudget,
     },
     solana_cli_config::{Config, ConfigInput},
     solana_cli_output::{
@@ -204,6 +205,14 @@ pub fn parse_args<'a>(
 
     let use_tpu_client = matches.is_present("use_tpu_client");
 
+    let auto_compute_budget =
+        matches
+            .is_present("auto_compute_budget")
+            .then(|| AutoComputeBudget {
+                priority_fee_percentile: value_t_or_exit!(matches, "priority_fee_percentile", u8),
+                max_priority_fee: value_t_or_exit!(matches, "max_priority_fee", u64),
+            });
+
     Ok((
         CliConfig {
             command,
@@ -224,6 +233,7 @@ pub fn parse_args<'a>(
             confirm_transaction_initial_timeout,
             address_labels,
             use_tpu_client,
+            auto_compute_budget,
         },
         signers,
     ))
@@ -6,7 +6,8 @@ use {
             ProcessResult,
         },
         compute_budget::{
-            simulate_and_update_compute_unit_limit, ComputeUnitConfig, WithComputeUnitConfig,
+            simulate_and_update_compute_unit_limit, ComputeUnitConfig, SendWithComputeBudget,
+            WithComputeUnitConfig,
         },
         memo::WithMemo,
         spend_utils::{resolve_spend_tx_and_check_account_balance, SpendAmount},
@@ -439,9 +440,9 @@ pub async fn process_authorize_nonce_account(
     )
     .await?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await;
@@ -524,7 +525,7 @@ pub async fn process_create_nonce_account(
         &config.signers[0].pubkey(),
         compute_unit_limit,
         build_message,
-        config.commitment,
+        config,
     )
     .await?;
 
@@ -548,9 +549,9 @@ pub async fn process_create_nonce_account(
     let mut tx = Transaction::new_unsigned(message);
     tx.try_sign(&config.signers, latest_blockhash)?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await;
@@ -616,9 +617,9 @@ pub async fn process_new_nonce(
     )
     .await?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await;
@@ -695,9 +696,9 @@ pub async fn process_withdraw_from_nonce_account(
     )
     .await?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await;
@@ -732,9 +733,9 @@ pub(crate) async fn process_upgrade_nonce_account(
     )
     .await?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await;
@@ -1,15 +1,20 @@
 use {
     crate::{
+        build_metadata::{
+            fetch_build_metadata, publish_build_metadata, read_build_metadata_file,
+            verified_build_metadata_message,
+        },
         checks::*,
         cli::{
             log_instruction_custom_error, CliCommand, CliCommandInfo, CliConfig, CliError,
             ProcessResult,
         },
         compute_budget::{
-            simulate_and_update_compute_unit_limit, ComputeUnitConfig,
+            simulate_and_update_compute_unit_limit, ComputeUnitConfig, SendWithComputeBudget,
             UpdateComputeUnitLimitResult, WithComputeUnitConfig,
         },
         feature::{status_from_account, CliFeatureStatus},
+        program_upload::{ProgramDataUpload, WriteMessage},
     },
     agave_feature_set::{raise_cpi_nesting_limit_to_8, FeatureSet, FEATURE_NAMES},
     agave_syscalls::create_program_runtime_environment_v1,
@@ -34,12 +39,6 @@ use {
         CliUpgradeableProgram, CliUpgradeableProgramClosed, CliUpgradeableProgramExtended,
         CliUpgradeableProgramMigrated, CliUpgradeablePrograms, ReturnSignersConfig,
     },
-    solana_client::{
-        connection_cache::ConnectionCache,
-        send_and_confirm_transactions_in_parallel::{
-            send_and_confirm_transactions_in_parallel_v2, SendAndConfirmConfigV2,
-        },
-    },
     solana_commitment_config::CommitmentConfig,
     solana_instruction::{error::InstructionError, Instruction},
     solana_keypair::{keypair_from_seed, read_keypair_file, Keypair},
@@ -50,6 +49,7 @@ use {
     solana_message::Message,
     solana_packet::PACKET_DATA_SIZE,
     solana_program_runtime::{
+        deploy::morph_into_deployment_environment_v1,
         execution_budget::SVMTransactionExecutionBudget, invoke_context::InvokeContext,
     },
     solana_pubkey::Pubkey,
@@ -62,12 +62,15 @@ use {
         request::MAX_MULTIPLE_ACCOUNTS,
     },
     solana_rpc_client_nonce_utils::nonblocking::blockhash_query::BlockhashQuery,
-    solana_sbpf::{elf::Executable, verifier::RequisiteVerifier},
+    solana_sbpf::{
+        elf::{ElfError, Executable},
+        program::BuiltinProgram,
+        verifier::RequisiteVerifier,
+    },
     solana_sdk_ids::{bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, compute_budget},
     solana_signature::Signature,
     solana_signer::Signer,
     solana_system_interface::{error::SystemError, MAX_PERMITTED_DATA_LENGTH},
-    solana_tpu_client::tpu_client::TpuClientConfig,
     solana_transaction::Transaction,
     solana_transaction_error::TransactionError,
     std::{
@@ -105,6 +108,7 @@ pub enum ProgramCliCommand {
         auto_extend: bool,
         use_rpc: bool,
         skip_feature_verification: bool,
+        build_metadata_location: Option<String>,
     },
     Upgrade {
         fee_payer_signer_index: SignerIndex,
@@ -305,6 +309,18 @@ impl ProgramSubCommands for App<'_, '_> {
                                      on mainnet will succeed local verification, but fail during \
                                      the last step of deployment.",
                                 ),
+                        )
+                        .arg(
+                            Arg::with_name("build_metadata")
+                                .long("build-metadata")
+                                .value_name("FILEPATH")
+                                .takes_value(true)
+                                .help(
+                                    "Publish the build metadata written by `cargo-build-sbf \
+                                     --build-metadata`, such as \
+                                     /path/to/program-build-metadata.json, along with the \
+                                     program. It is signed by the program authority",
+                                ),
                         ),
                 )
                 .subcommand(
@@ -765,6 +781,9 @@ pub fn parse_program_subcommand(
                     use_rpc: matches.is_present("use_rpc"),
                     auto_extend,
                     skip_feature_verification: skip_feature_verify,
+                    build_metadata_location: matches
+                        .value_of("build_metadata")
+                        .map(|location| location.to_string()),
                 }),
                 signers: signer_info.signers,
             }
@@ -1090,6 +1109,7 @@ pub async fn process_program_subcommand(
             auto_extend,
             use_rpc,
             skip_feature_verification,
+            build_metadata_location,
         } => {
             process_program_deploy(
                 rpc_client,
@@ -1109,6 +1129,7 @@ pub async fn process_program_subcommand(
                 *auto_extend,
                 *use_rpc,
                 *skip_feature_verification,
+                build_metadata_location,
             )
             .await
         }
@@ -1336,6 +1357,7 @@ async fn process_program_deploy(
     auto_extend: bool,
     use_rpc: bool,
     skip_feature_verification: bool,
+    build_metadata_location: &Option<String>,
 ) -> ProcessResult {
     let fee_payer_signer = config.signers[fee_payer_signer_index];
     let upgrade_authority_signer = config.signers[upgrade_authority_signer_index];
@@ -1428,6 +1450,9 @@ async fn process_program_deploy(
         // do new deploy
         true
     };
+    if do_initial_deploy && program_signer.is_none() {
+        return Err("Initial deployments require a keypair be provided for the program id".into());
+    }
 
     let feature_set = if skip_feature_verification {
         FeatureSet::all_enabled()
@@ -1476,6 +1501,17 @@ async fn process_program_deploy(
             return Err("Program location required if buffer not supplied".into());
         };
 
+    let build_metadata = if let Some(build_metadata_location) = build_metadata_location {
+        let elf = if program_data.is_empty() {
+            buffer_program_data.as_deref().unwrap_or_default()
+        } else {
+            &program_data[..]
+        };
+        Some(read_build_metadata_file(build_metadata_location, elf)?)
+    } else {
+        None
+    };
+
     let program_data_max_len = if let Some(len) = max_len {
         if program_len > len {
             return Err(
@@ -1494,11 +1530,6 @@ async fn process_program_deploy(
         .await?;
 
     let result = if do_initial_deploy {
-        if program_signer.is_none() {
-            return Err(
-                "Initial deployments require a keypair be provided for the program id".into(),
-            );
-        }
         do_process_program_deploy(
             rpc_client.clone(),
             config,
@@ -1539,6 +1570,23 @@ async fn process_program_deploy(
         )
         .await
     };
+    if let (Ok(_), Some(build_metadata)) = (&result, &build_metadata) {
+        // The program is deployed at this point, so a failure to publish its build metadata
+        // must neither fail the command nor skip finalizing it
+        if let Err(err) = publish_build_metadata(
+            &rpc_client,
+            config,
+            fee_payer_signer,
+            &program_pubkey,
+            upgrade_authority_signer,
+            build_metadata,
+            compute_unit_price,
+        )
+        .await
+        {
+            eprintln!("Warning: Publishing build metadata failed: {err}");
+        }
+    }
     if result.is_ok() && is_final {
         process_set_authority(
             &rpc_client,
@@ -1711,9 +1759,9 @@ async fn process_program_upgrade(
         let signers = &[fee_payer_signer, upgrade_authority_signer];
         tx.try_sign(signers, blockhash)?;
         let final_tx_sig = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await
@@ -1872,9 +1920,9 @@ async fn process_set_authority(
     } else {
         tx.try_sign(signers, blockhash)?;
         rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await
@@ -1934,9 +1982,9 @@ async fn process_set_authority_checked(
     } else {
         tx.try_sign(signers, blockhash)?;
         rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await
@@ -2072,6 +2120,7 @@ async fn get_programs(
                     .saturating_sub(UpgradeableLoaderState::size_of_programdata_metadata()),
                 lamports: programdata_account.lamports,
                 use_lamports_unit,
+                build_metadata: None,
             });
         } else {
             return Err(format!("Error parsing ProgramData account {programdata_address}").into());
@@ -2142,6 +2191,15 @@ async fn process_show(
                             slot,
                         }) = programdata_account.state()
                         {
+                            let build_metadata = fetch_build_metadata(
+                                rpc_client,
+                                config,
+                                &account_pubkey,
+                                upgrade_authority_address.as_ref(),
+                                &programdata_account.data
+                                    [UpgradeableLoaderState::size_of_programdata_metadata()..],
+                            )
+                            .await?;
                             Ok(config
                                 .output_format
                                 .formatted_string(&CliUpgradeableProgram {
@@ -2157,6 +2215,7 @@ async fn process_show(
                                     ),
                                     lamports: programdata_account.lamports,
                                     use_lamports_unit,
+                                    build_metadata,
                                 }))
                         } else {
                             Err(format!("Program {account_pubkey} has been closed").into())
@@ -2232,14 +2291,27 @@ async fn process_dump(
                         .await?
                         .value
                     {
-                        if let Ok(UpgradeableLoaderState::ProgramData { .. }) =
-                            programdata_account.state()
+                        if let Ok(UpgradeableLoaderState::ProgramData {
+                            upgrade_authority_address,
+                            ..
+                        }) = programdata_account.state()
                         {
                             let offset = UpgradeableLoaderState::size_of_programdata_metadata();
                             let program_data = &programdata_account.data[offset..];
                             let mut f = File::create(output_location)?;
                             f.write_all(program_data)?;
-                            Ok(format!("Wrote program to {output_location}"))
+                            let build_metadata = fetch_build_metadata(
+                                rpc_client,
+                                config,
+                                &account_pubkey,
+                                upgrade_authority_address.as_ref(),
+                                program_data,
+                            )
+                            .await?;
+                            Ok(format!(
+                                "Wrote program to {output_location}{}",
+                                verified_build_metadata_message(build_metadata.as_ref())
+                            ))
                         } else {
                             Err(format!("Program {account_pubkey} has been closed").into())
                         }
@@ -2291,9 +2363,9 @@ async fn close(
 
     tx.try_sign(&[config.signers[0], authority_signer], blockhash)?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await;
@@ -2547,9 +2619,9 @@ async fn process_extend_program(
 
     tx.try_sign(&[config.signers[0], authority_signer], blockhash)?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await;
@@ -2647,9 +2719,9 @@ async fn process_migrate_program(
     let mut tx = Transaction::new_unsigned(message);
     tx.try_sign(&[config.signers[0], authority_signer], blockhash)?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await;
@@ -2758,7 +2830,10 @@ async fn do_process_program_deploy(
     for (chunk, i) in program_data.chunks(chunk_size).zip(0usize..) {
         let offset = i.saturating_mul(chunk_size);
         if chunk != &buffer_program_data[offset..offset.saturating_add(chunk.len())] {
-            write_messages.push(create_msg(offset as u32, chunk.to_vec()));
+            write_messages.push(WriteMessage {
+                range: offset..offset.saturating_add(chunk.len()),
+                message: create_msg(offset as u32, chunk.to_vec()),
+            });
         }
     }
 
@@ -2804,7 +2879,12 @@ async fn do_process_program_deploy(
         rpc_client,
         config,
         initial_message,
-        write_messages,
+        ProgramDataUpload {
+            address: *buffer_pubkey,
+            data_offset: UpgradeableLoaderState::size_of_buffer_metadata(),
+            program_data,
+            write_messages,
+        },
         final_message,
         fee_payer_signer,
         buffer_signer,
@@ -2894,7 +2974,10 @@ async fn do_process_write_buffer(
     for (chunk, i) in program_data.chunks(chunk_size).zip(0usize..) {
         let offset = i.saturating_mul(chunk_size);
         if chunk != &buffer_program_data[offset..offset.saturating_add(chunk.len())] {
-            write_messages.push(create_msg(offset as u32, chunk.to_vec()));
+            write_messages.push(WriteMessage {
+                range: offset..offset.saturating_add(chunk.len()),
+                message: create_msg(offset as u32, chunk.to_vec()),
+            });
         }
     }
 
@@ -2915,7 +2998,12 @@ async fn do_process_write_buffer(
         rpc_client,
         config,
         initial_message,
-        write_messages,
+        ProgramDataUpload {
+            address: *buffer_pubkey,
+            data_offset: UpgradeableLoaderState::size_of_buffer_metadata(),
+            program_data,
+            write_messages,
+        },
         None,
         fee_payer_signer,
         buffer_signer,
@@ -3022,7 +3110,10 @@ async fn do_process_program_upgrade(
         for (chunk, i) in program_data.chunks(chunk_size).zip(0usize..) {
             let offset = i.saturating_mul(chunk_size);
             if chunk != &buffer_program_data[offset..offset.saturating_add(chunk.len())] {
-                write_messages.push(create_msg(offset as u32, chunk.to_vec()));
+                write_messages.push(WriteMessage {
+                    range: offset..offset.saturating_add(chunk.len()),
+                    message: create_msg(offset as u32, chunk.to_vec()),
+                });
             }
         }
 
@@ -3066,7 +3157,12 @@ async fn do_process_program_upgrade(
         rpc_client,
         config,
         initial_message,
-        write_messages,
+        ProgramDataUpload {
+            address: *buffer_pubkey,
+            data_offset: UpgradeableLoaderState::size_of_buffer_metadata(),
+            program_data,
+            write_messages,
+        },
         final_message,
         fee_payer_signer,
         buffer_signer,
@@ -3171,11 +3267,50 @@ fn read_and_verify_elf(
     Ok(program_data)
 }
 
-fn verify_elf(
+/// Checks that the loader would accept the deployment of `program_data` on a
+/// cluster with `feature_set`, with the same syscalls and SBPF versions.
+pub(crate) fn verify_elf(
     program_data: &[u8],
     feature_set: FeatureSet,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Verify the program
+    let deployment_environment = create_deployment_environment(&feature_set)?;
+    let err = match load_and_verify_elf(program_data, deployment_environment.clone()) {
+        Ok(_) => return Ok(()),
+        Err(err) => err,
+    };
+
+    // Explain the rejection of programs that would deploy once the runtime
+    // features they depend on are active
+    let Ok(executable) = load_and_verify_elf(
+        program_data,
+        create_deployment_environment(&FeatureSet::all_enabled())?,
+    ) else {
+        return Err(format!("ELF error: {err}").into());
+    };
+    let reason = match err.downcast_ref::<ElfError>() {
+        Some(ElfError::UnsupportedSBPFVersion) => {
+            let enabled_sbpf_versions = &deployment_environment.get_config().enabled_sbpf_versions;
+            format!(
+                "the program is built for SBPF {:?}, but the cluster only supports SBPF {:?} to \
+                 {:?}",
+                executable.get_sbpf_version(),
+                enabled_sbpf_versions.start(),
+                enabled_sbpf_versions.end(),
+            )
+        }
+        Some(ElfError::UnresolvedSymbol(symbol, ..)) => {
+            format!("the syscall {symbol} is not active on the cluster")
+        }
+        _ => {
+            "the program depends on runtime features that are not active on the cluster".to_string()
+        }
+    };
+    Err(format!("ELF error: {err}: {reason}").into())
+}
+
+fn create_deployment_environment(
+    feature_set: &FeatureSet,
+) -> Result<Arc<BuiltinProgram<InvokeContext<'static, 'static>>>, Box<dyn std::error::Error>> {
     let program_runtime_environment = create_program_runtime_environment_v1(
         &feature_set.runtime_features(),
         &SVMTransactionExecutionBudget::new_with_defaults(
@@ -3183,15 +3318,19 @@ fn verify_elf(
         ),
         true,
         false,
-    )
-    .unwrap();
-    let executable =
-        Executable::<InvokeContext>::from_elf(program_data, Arc::new(program_runtime_environment))
-            .map_err(|err| format!("ELF error: {err}"))?;
-
-    executable
-        .verify::<RequisiteVerifier>()
-        .map_err(|err| format!("ELF error: {err}").into())
+    )?;
+    let deployment_environment =
+        morph_into_deployment_environment_v1(Arc::new(program_runtime_environment))?;
+    Ok(Arc::new(deployment_environment))
+}
+
+fn load_and_verify_elf(
+    program_data: &[u8],
+    deployment_environment: Arc<BuiltinProgram<InvokeContext<'static, 'static>>>,
+) -> Result<Executable<InvokeContext<'static, 'static>>, Box<dyn std::error::Error>> {
+    let executable = Executable::load(program_data, deployment_environment)?;
+    executable.verify::<RequisiteVerifier>()?;
+    Ok(executable)
 }
 
 async fn check_payer(
@@ -3200,7 +3339,7 @@ async fn check_payer(
     fee_payer_pubkey: Pubkey,
     balance_needed: u64,
     initial_message: &Option<Message>,
-    write_messages: &[Message],
+    write_messages: &[WriteMessage],
     final_message: &Option<Message>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut fee = Saturating(0);
@@ -3208,9 +3347,9 @@ async fn check_payer(
         fee += rpc_client.get_fee_for_message(message).await?;
     }
     // Assume all write messages cost the same
-    if let Some(message) = write_messages.first() {
+    if let Some(write_message) = write_messages.first() {
         fee += rpc_client
-            .get_fee_for_message(message)
+            .get_fee_for_message(&write_message.message)
             .await?
             .saturating_mul(write_messages.len() as u64);
     }
@@ -3233,7 +3372,7 @@ async fn send_deploy_messages(
     rpc_client: Arc<RpcClient>,
     config: &CliConfig<'_>,
     initial_message: Option<Message>,
-    mut write_messages: Vec<Message>,
+    mut upload: ProgramDataUpload<'_>,
     final_message: Option<Message>,
     fee_payer_signer: &dyn Signer,
     initial_signer: Option<&dyn Signer>,
@@ -3266,9 +3405,9 @@ async fn send_deploy_messages(
                 initial_transaction.try_sign(&[fee_payer_signer], blockhash)?;
             }
             let result = rpc_client
-                .send_and_confirm_transaction_with_spinner_and_config(
+                .send_and_confirm_transaction_with_compute_budget(
                     &initial_transaction,
-                    config.commitment,
+                    config,
                     config.send_transaction_config,
                 )
                 .await;
@@ -3279,7 +3418,7 @@ async fn send_deploy_messages(
         }
     }
 
-    if !write_messages.is_empty() {
+    if !upload.write_messages.is_empty() {
         if let Some(write_signer) = write_signer {
             trace!("Writing program data");
 
@@ -3287,7 +3426,7 @@ async fn send_deploy_messages(
             // consumed and then reuse that value as the compute unit limit for all
             // write messages.
             {
-                let mut message = write_messages[0].clone();
+                let mut message = upload.write_messages[0].message.clone();
                 if let UpdateComputeUnitLimitResult::UpdatedInstructionIndex(ix_index) =
                     simulate_and_update_compute_unit_limit(
                         compute_unit_limit,
@@ -3296,7 +3435,7 @@ async fn send_deploy_messages(
                     )
                     .await?
                 {
-                    for msg in &mut write_messages {
+                    for WriteMessage { message: msg, .. } in &mut upload.write_messages {
                         // Write messages are all assumed to be identical except
                         // the program data being written. But just in case that
                         // assumption is broken, assert that we are only ever
@@ -3310,73 +3449,16 @@ async fn send_deploy_messages(
                 }
             }
 
-            let connection_cache = {
-                #[cfg(feature = "dev-context-only-utils")]
-                let cache =
-                    ConnectionCache::new_quic_for_tests("connection_cache_cli_program_quic", 1);
-                #[cfg(not(feature = "dev-context-only-utils"))]
-                let cache = ConnectionCache::new_quic("connection_cache_cli_program_quic", 1);
-                cache
-            };
-            let transaction_errors = match connection_cache {
-                ConnectionCache::Udp(cache) => {
-                    solana_tpu_client::nonblocking::tpu_client::TpuClient::new_with_connection_cache(
-                        rpc_client.clone(),
-                        &config.websocket_url,
-                        TpuClientConfig::default(),
-                        cache,
-                    )
-                    .await?
-                    .send_and_confirm_messages_with_spinner(
-                        &write_messages,
-                        &[fee_payer_signer, write_signer],
-                    )
-                    .await
-                }
-                ConnectionCache::Quic(cache) => {
-                    // `solana_client` type currently required by `send_and_confirm_transactions_in_parallel_v2`
-                    let tpu_client_fut = solana_client::nonblocking::tpu_client::TpuClient::new_with_connection_cache(
-                        rpc_client.clone(),
-                        config.websocket_url.as_str(),
-                        TpuClientConfig::default(),
-                        cache,
-                    );
-                    let tpu_client = if use_rpc {
-                        None
-                    } else {
-                        Some(
-                            tpu_client_fut
-                                .await
-                                .expect("Should return a valid tpu client"),
-                        )
-                    };
-                    send_and_confirm_transactions_in_parallel_v2(
-                        rpc_client.clone(),
-                        tpu_client,
-                        &write_messages,
-                        &[fee_payer_signer, write_signer],
-                        SendAndConfirmConfigV2 {
-                            resign_txs_count: Some(max_sign_attempts),
-                            with_spinner: true,
-                            rpc_send_transaction_config: config.send_transaction_config,
-                        },
-                    )
-                    .await
-                }
-            }
-            .map_err(|err| format!("Data writes to account failed: {err}"))?
-            .into_iter()
-            .flatten()
-            .collect::<Vec<_>>();
-
-            if !transaction_errors.is_empty() {
-                for transaction_error in &transaction_errors {
-                    error!("{transaction_error:?}");
-                }
-                return Err(
-                    format!("{} write transactions failed", transaction_errors.len()).into(),
-                );
-            }
+            upload
+                .send_and_confirm(
+                    rpc_client.clone(),
+                    config,
+                    &[fee_payer_signer, write_signer],
+                    max_sign_attempts,
+                    use_rpc,
+                )
+                .await
+                .map_err(|err| format!("Data writes to account failed: {err}"))?;
         }
     }
 
@@ -3393,9 +3475,9 @@ async fn send_deploy_messages(
             final_tx.try_sign(&signers, blockhash)?;
             return Ok(Some(
                 rpc_client
-                    .send_and_confirm_transaction_with_spinner_and_config(
+                    .send_and_confirm_transaction_with_compute_budget(
                         &final_tx,
-                        config.commitment,
+                        config,
                         config.send_transaction_config,
                     )
                     .await
@@ -3429,7 +3511,7 @@ fn report_ephemeral_mnemonic(words: usize, mnemonic: bip39::Mnemonic, ephemeral_
     eprintln!("{divider}\nsolana program close {ephemeral_pubkey}\n{divider}");
 }
 
-async fn fetch_feature_set(
+pub(crate) async fn fetch_feature_set(
     rpc_client: &RpcClient,
 ) -> Result<FeatureSet, Box<dyn std::error::Error>> {
     let mut feature_set = FeatureSet::default();
@@ -3519,6 +3601,7 @@ mod tests {
                     auto_extend: true,
                     use_rpc: false,
                     skip_feature_verification: false,
+                    build_metadata_location: None,
                 }),
                 signers: vec![Box::new(read_keypair_file(&keypair_file).unwrap())],
             }
@@ -3551,6 +3634,7 @@ mod tests {
                     auto_extend: true,
                     use_rpc: false,
                     skip_feature_verification: false,
+                    build_metadata_location: None,
                 }),
                 signers: vec![Box::new(read_keypair_file(&keypair_file).unwrap())],
             }
@@ -3585,6 +3669,7 @@ mod tests {
                     auto_extend: true,
                     use_rpc: false,
                     skip_feature_verification: false,
+                    build_metadata_location: None,
                 }),
                 signers: vec![
                     Box::new(read_keypair_file(&keypair_file).unwrap()),
@@ -3621,6 +3706,7 @@ mod tests {
                     auto_extend: true,
                     use_rpc: false,
                     skip_feature_verification: false,
+                    build_metadata_location: None,
                 }),
                 signers: vec![Box::new(read_keypair_file(&keypair_file).unwrap())],
             }
@@ -3656,6 +3742,7 @@ mod tests {
                     auto_extend: true,
                     use_rpc: false,
                     skip_feature_verification: false,
+                    build_metadata_location: None,
                 }),
                 signers: vec![
                     Box::new(read_keypair_file(&keypair_file).unwrap()),
@@ -3694,6 +3781,7 @@ mod tests {
                     auto_extend: true,
                     use_rpc: false,
                     skip_feature_verification: false,
+                    build_metadata_location: None,
                 }),
                 signers: vec![
                     Box::new(read_keypair_file(&keypair_file).unwrap()),
@@ -3728,6 +3816,7 @@ mod tests {
                     auto_extend: true,
                     use_rpc: false,
                     skip_feature_verification: false,
+                    build_metadata_location: None,
                 }),
                 signers: vec![Box::new(read_keypair_file(&keypair_file).unwrap())],
             }
@@ -3760,6 +3849,7 @@ mod tests {
                     auto_extend: true,
                     use_rpc: false,
                     skip_feature_verification: false,
+                    build_metadata_location: None,
                 }),
                 signers: vec![Box::new(read_keypair_file(&keypair_file).unwrap())],
             }
@@ -3791,6 +3881,7 @@ mod tests {
                     auto_extend: true,
                     use_rpc: true,
                     skip_feature_verification: false,
+                    build_metadata_location: None,
                 }),
                 signers: vec![Box::new(read_keypair_file(&keypair_file).unwrap())],
             }
@@ -3822,6 +3913,7 @@ mod tests {
                     auto_extend: true,
                     use_rpc: false,
                     skip_feature_verification: true,
+                    build_metadata_location: None,
                 }),
                 signers: vec![Box::new(read_keypair_file(&keypair_file).unwrap())],
             }
@@ -4678,6 +4770,7 @@ mod tests {
                 auto_extend: true,
                 use_rpc: false,
                 skip_feature_verification: true,
+                build_metadata_location: None,
             }),
             signers: vec![&default_keypair],
             output_format: OutputFormat::JsonCompact,
@@ -4699,4 +4792,44 @@ mod tests {
             program_pubkey.pubkey()
         );
     }
+
+    #[test]
+    fn test_verify_elf() {
+        let fixture = |name: &str| {
+            let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+            path.push("tests");
+            path.push("fixtures");
+            path.push(name);
+            path.set_extension("so");
+            std::fs::read(path).unwrap()
+        };
+        let noop = fixture("noop");
+        let alt_bn128 = fixture("alt_bn128");
+
+        verify_elf(&noop, FeatureSet::all_enabled()).unwrap();
+        verify_elf(&alt_bn128, FeatureSet::all_enabled()).unwrap();
+        assert!(
+            verify_elf(&noop[..noop.len() / 2], FeatureSet::all_enabled())
+                .unwrap_err()
+                .to_string()
+                .starts_with("ELF error: ")
+        );
+
+        let mut feature_set = FeatureSet::all_enabled();
+        feature_set.deactivate(&agave_feature_set::enable_alt_bn128_syscall::id());
+        verify_elf(&noop, feature_set.clone()).unwrap();
+        assert!(verify_elf(&alt_bn128, feature_set)
+            .unwrap_err()
+            .to_string()
+            .ends_with(": the syscall sol_alt_bn128_group_op is not active on the cluster"));
+
+        let mut feature_set = FeatureSet::all_enabled();
+        feature_set.deactivate(&agave_feature_set::reenable_sbpf_v0_execution::id());
+        assert!(verify_elf(&noop, feature_set)
+            .unwrap_err()
+            .to_string()
+            .ends_with(
+                ": the program is built for SBPF V0, but the cluster only supports SBPF V3 to V3"
+            ));
+    }
 }
@@ -0,0 +1,336 @@
+//! Resumable uploads of program data, for loader-v3 buffers and loader-v4
+//! programs.
+//!
+//! Each round of an upload diffs the account on-chain against the local ELF,
+//! chunk by chunk, and only sends the writes of the chunks that are missing or
+//! mismatched. Writes that are dropped or fail are resent in the next round.
+//!
+//! Rerunning an interrupted upload only resumes it when it targets the same
+//! account, i.e. with the recovered keypair of the buffer passed as
+//! `--buffer`, or for `program-v4 deploy`, with the same `--program-keypair`.
+//! Otherwise the upload starts over.
+
+use {
+    crate::{cli::CliConfig, compute_budget::AutoComputeUnitConfig},
+    log::*,
+    solana_cli_output::display::new_spinner_progress_bar,
+    solana_message::Message,
+    solana_net_utils::bind_to_unspecified,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::nonblocking::rpc_client::RpcClient,
+    solana_rpc_client_api::{
+        config::RpcSendTransactionConfig, request::MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS,
+    },
+    solana_signer::signers::Signers,
+    solana_tpu_client_next::{
+        node_address_service::LeaderTpuCacheServiceConfig,
+        websocket_node_address_service::WebsocketNodeAddressService, Client, ClientBuilder,
+        TransactionSender,
+    },
+    solana_transaction::Transaction,
+    std::{collections::HashMap, error, ops::Range, sync::Arc, time::Duration},
+    tokio::time::sleep,
+    tokio_util::sync::CancellationToken,
+};
+
+/// How often the pending writes of a round are resent until they're
+/// confirmed or their blockhash expires.
+const RESEND_INTERVAL: Duration = Duration::from_secs(2);
+const STATUS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A write message, and the range of the program data that it writes.
+pub(crate) struct WriteMessage {
+    pub range: Range<usize>,
+    pub message: Message,
+}
+
+/// The write messages that upload `program_data` to an account.
+pub(crate) struct ProgramDataUpload<'a> {
+    pub address: Pubkey,
+    /// Offset of the program data in the data of the account
+    pub data_offset: usize,
+    pub program_data: &'a [u8],
+    pub write_messages: Vec<WriteMessage>,
+}
+
+impl ProgramDataUpload<'_> {
+    fn is_written(&self, write_message: &WriteMessage, account_program_data: &[u8]) -> bool {
+        account_program_data.get(write_message.range.clone())
+            == self.program_data.get(write_message.range.clone())
+    }
+
+    /// Drops the write messages of the chunks that the account already holds.
+    pub(crate) fn skip_written(&mut self, account_data: &[u8]) {
+        let account_program_data = account_data.get(self.data_offset..).unwrap_or_default();
+        let write_messages = std::mem::take(&mut self.write_messages);
+        self.write_messages = write_messages
+            .into_iter()
+            .filter(|write_message| !self.is_written(write_message, account_program_data))
+            .collect();
+    }
+
+    async fn pending_write_messages(
+        &self,
+        rpc_client: &RpcClient,
+        config: &CliConfig<'_>,
+    ) -> Result<Vec<&WriteMessage>, Box<dyn error::Error>> {
+        let account = rpc_client
+            .get_account_with_commitment(&self.address, config.commitment)
+            .await?
+            .value
+            .ok_or_else(|| format!("Unable to find the account {}", self.address))?;
+        let account_program_data = account.data.get(self.data_offset..).unwrap_or_default();
+        Ok(self
+            .write_messages
+            .iter()
+            .filter(|write_message| !self.is_written(write_message, account_program_data))
+            .collect())
+    }
+
+    /// Sends the write messages of all chunks that the account doesn't hold
+    /// yet, in up to `max_rounds` rounds.
+    pub(crate) async fn send_and_confirm<T: Signers + ?Sized>(
+        &self,
+        rpc_client: Arc<RpcClient>,
+        config: &CliConfig<'_>,
+        signers: &T,
+        max_rounds: usize,
+        use_rpc: bool,
+    ) -> Result<(), Box<dyn error::Error>> {
+        let total = self.write_messages.len();
+        let progress_bar = new_spinner_progress_bar();
+        let tpu_client = if use_rpc {
+            None
+        } else {
+            Some(TpuClient::new(rpc_client.clone(), &config.websocket_url).await?)
+        };
+
+        let mut errors = HashMap::new();
+        let mut pending = self.pending_write_messages(&rpc_client, config).await?;
+        // With the automatic compute budget, the writes are priced once from
+        // the first of them, since they only differ in the data they write
+        let mut compute_unit_config = match (&config.auto_compute_budget, pending.first()) {
+            (Some(auto_compute_budget), Some(write_message)) => Some(
+                AutoComputeUnitConfig::new(
+                    &rpc_client,
+                    auto_compute_budget,
+                    &write_message.message,
+                )
+                .await?,
+            ),
+            _ => None,
+        };
+        for round in 1..=max_rounds {
+            if pending.is_empty() {
+                break;
+            }
+            progress_bar.set_message(format!(
+                "[{}/{total}] Writing program data, round {round} of {max_rounds}",
+                total.saturating_sub(pending.len()),
+            ));
+
+            let (blockhash, last_valid_block_height) = rpc_client
+                .get_latest_blockhash_with_commitment(config.commitment)
+                .await?;
+            let mut unconfirmed = HashMap::with_capacity(pending.len());
+            for write_message in &pending {
+                let message = match &compute_unit_config {
+                    Some(compute_unit_config) => compute_unit_config.apply(&write_message.message),
+                    None => write_message.message.clone(),
+                };
+                let mut transaction = Transaction::new_unsigned(message);
+                transaction.try_sign(signers, blockhash)?;
+                unconfirmed.insert(transaction.signatures[0], transaction);
+            }
+
+            let mut confirmed = 0usize;
+            while !unconfirmed.is_empty()
+                && rpc_client
+                    .get_block_height_with_commitment(config.commitment)
+                    .await?
+                    <= last_valid_block_height
+            {
+                let transactions = unconfirmed.values().collect::<Vec<_>>();
+                if let Some(tpu_client) = &tpu_client {
+                    tpu_client.send(&transactions).await?;
+                } else {
+                    send_over_rpc(&rpc_client, config, &transactions).await;
+                }
+
+                let mut elapsed = Duration::ZERO;
+                while elapsed < RESEND_INTERVAL && !unconfirmed.is_empty() {
+                    sleep(STATUS_POLL_INTERVAL).await;
+                    elapsed = elapsed.saturating_add(STATUS_POLL_INTERVAL);
+                    let signatures = unconfirmed.keys().copied().collect::<Vec<_>>();
+                    for signatures in signatures.chunks(MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS) {
+                        let statuses = rpc_client.get_signature_statuses(signatures).await?.value;
+                        for (signature, status) in signatures.iter().zip(statuses) {
+                            let Some(status) = status else {
+                                continue;
+                            };
+                            if let Some(err) = status.err {
+                                // Failed writes are retried in the next round
+                                unconfirmed.remove(signature);
+                                errors.insert(*signature, err);
+                            } else if status.satisfies_commitment(config.commitment) {
+                                unconfirmed.remove(signature);
+                                confirmed = confirmed.saturating_add(1);
+                            }
+                        }
+                    }
+                    progress_bar.set_message(format!(
+                        "[{}/{total}] Writing program data, round {round} of {max_rounds}",
+                        total
+                            .saturating_sub(pending.len())
+                            .saturating_add(confirmed),
+                    ));
+                }
+            }
+            // The writes left unconfirmed expired, and are resent in the next
+            // round at a higher price
+            if !unconfirmed.is_empty() {
+                if let Some(compute_unit_config) = compute_unit_config.as_mut() {
+                    compute_unit_config.escalate();
+                }
+            }
+
+            pending = self.pending_write_messages(&rpc_client, config).await?;
+        }
+        progress_bar.finish_and_clear();
+        if let Some(tpu_client) = tpu_client {
+            tpu_client.shutdown().await;
+        }
+
+        if pending.is_empty() {
+            return Ok(());
+        }
+        for (signature, err) in &errors {
+            error!("Write transaction {signature} failed: {err}");
+        }
+        Err(format!(
+            "{} of {total} program data chunks are not written after {max_rounds} rounds, run \
+             the command again to resume the upload",
+            pending.len(),
+        )
+        .into())
+    }
+}
+
+async fn send_over_rpc(
+    rpc_client: &RpcClient,
+    config: &CliConfig<'_>,
+    transactions: &[&Transaction],
+) {
+    let send_transaction_config = RpcSendTransactionConfig {
+        skip_preflight: true,
+        ..config.send_transaction_config
+    };
+    for transaction in transactions {
+        if let Err(err) = rpc_client
+            .send_transaction_with_config(*transaction, send_transaction_config)
+            .await
+        {
+            debug!("Failed to send write transaction: {err}");
+        }
+    }
+}
+
+/// Sends transactions to the upcoming leaders with `tpu-client-next`.
+struct TpuClient {
+    sender: TransactionSender,
+    client: Client,
+    cancel: CancellationToken,
+}
+
+impl TpuClient {
+    async fn new(
+        rpc_client: Arc<RpcClient>,
+        websocket_url: &str,
+    ) -> Result<Self, Box<dyn error::Error>> {
+        let cancel = CancellationToken::new();
+        let leader_updater = WebsocketNodeAddressService::run(
+            rpc_client,
+            websocket_url.to_string(),
+            LeaderTpuCacheServiceConfig::default(),
+            cancel.child_token(),
+        )
+        .await?;
+        let (sender, client) = ClientBuilder::new(Box::new(leader_updater))
+            .bind_socket(bind_to_unspecified()?)
+            .cancel_token(cancel.child_token())
+            .build()?;
+        Ok(Self {
+            sender,
+            client,
+            cancel,
+        })
+    }
+
+    async fn send(&self, transactions: &[&Transaction]) -> Result<(), Box<dyn error::Error>> {
+        let wire_transactions = transactions
+            .iter()
+            .map(|transaction| bincode::serialize(transaction))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.sender
+            .send_transactions_in_batch(wire_transactions)
+            .await?;
+        Ok(())
+    }
+
+    async fn shutdown(self) {
+        if let Err(err) = self.client.shutdown().await {
+            warn!("Failed to shut down the TPU client: {err}");
+        }
+        self.cancel.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_written() {
+        let program_data = (0..=255).collect::<Vec<u8>>();
+        let write_messages = || {
+            [0..64, 64..128, 128..192, 192..256]
+                .into_iter()
+                .map(|range| WriteMessage {
+                    range,
+                    message: Message::default(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let ranges = |upload: &ProgramDataUpload| {
+            upload
+                .write_messages
+                .iter()
+                .map(|write_message| write_message.range.clone())
+                .collect::<Vec<_>>()
+        };
+        let mut upload = ProgramDataUpload {
+            address: Pubkey::new_unique(),
+            data_offset: 8,
+            program_data: &program_data,
+            write_messages: write_messages(),
+        };
+
+        // Nothing written yet
+        upload.skip_written(&[0; 8]);
+        assert_eq!(ranges(&upload), vec![0..64, 64..128, 128..192, 192..256]);
+
+        // The third chunk is mismatched and the last one partially written
+        let mut account_data = vec![0; 8];
+        account_data.extend_from_slice(&program_data[..200]);
+        account_data[138] = 0;
+        upload.skip_written(&account_data);
+        assert_eq!(ranges(&upload), vec![128..192, 192..256]);
+
+        // Everything written
+        let mut account_data = vec![0; 8];
+        account_data.extend_from_slice(&program_data);
+        upload.write_messages = write_messages();
+        upload.skip_written(&account_data);
+        assert!(upload.write_messages.is_empty());
+    }
+}
@@ -1,14 +1,18 @@
 use {
     crate::{
+        build_metadata::{
+            fetch_build_metadata, publish_build_metadata, read_build_metadata_file,
+            verified_build_metadata_message,
+        },
         checks::*,
         cli::{CliCommand, CliCommandInfo, CliConfig, CliError, ProcessResult},
         compute_budget::{
-            simulate_and_update_compute_unit_limit, ComputeUnitConfig, WithComputeUnitConfig,
+            simulate_and_update_compute_unit_limit, ComputeUnitConfig, SendWithComputeBudget,
+            WithComputeUnitConfig,
         },
-        feature::{status_from_account, CliFeatureStatus},
-        program::calculate_max_chunk_size,
+        program::{calculate_max_chunk_size, fetch_feature_set, verify_elf},
+        program_upload::{ProgramDataUpload, WriteMessage},
     },
-    agave_feature_set::{raise_cpi_nesting_limit_to_8, FeatureSet, FEATURE_NAMES},
     clap::{value_t, App, AppSettings, Arg, ArgMatches, SubCommand},
     log::*,
     solana_account::Account,
@@ -23,12 +27,6 @@ use {
     solana_cli_output::{
         return_signers_with_config, CliProgramId, CliProgramV4, CliProgramsV4, ReturnSignersConfig,
     },
-    solana_client::{
-        connection_cache::ConnectionCache,
-        send_and_confirm_transactions_in_parallel::{
-            send_and_confirm_transactions_in_parallel_v2, SendAndConfirmConfigV2,
-        },
-    },
     solana_instruction::Instruction,
     solana_loader_v4_interface::{
         instruction,
@@ -38,23 +36,17 @@ use {
         },
     },
     solana_message::Message,
-    solana_program_runtime::{
-        execution_budget::SVMTransactionExecutionBudget, invoke_context::InvokeContext,
-    },
     solana_pubkey::Pubkey,
     solana_remote_wallet::remote_wallet::RemoteWalletManager,
     solana_rpc_client::nonblocking::rpc_client::RpcClient,
     solana_rpc_client_api::{
         config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
         filter::{Memcmp, RpcFilterType},
-        request::MAX_MULTIPLE_ACCOUNTS,
     },
     solana_rpc_client_nonce_utils::nonblocking::blockhash_query::BlockhashQuery,
-    solana_sbpf::{elf::Executable, verifier::RequisiteVerifier},
     solana_sdk_ids::{loader_v4, system_program},
     solana_signer::Signer,
     solana_system_interface::{instruction as system_instruction, MAX_PERMITTED_DATA_LENGTH},
-    solana_tpu_client::tpu_client::TpuClientConfig,
     solana_transaction::Transaction,
     std::{
         cmp::Ordering,
@@ -99,6 +91,7 @@ pub enum ProgramV4CliCommand {
         authority_signer_index: SignerIndex,
         path_to_elf: Option<String>,
         upload_range: Range<Option<usize>>,
+        build_metadata_location: Option<String>,
     },
     Retract {
         additional_cli_config: AdditionalCliConfig,
@@ -199,6 +192,20 @@ impl ProgramV4SubCommands for App<'_, '_> {
                         .arg(Arg::with_name("use-rpc").long("use-rpc").help(
                             "Send transactions to the configured RPC instead of validator TPUs",
                         ))
+                        .arg(
+                            Arg::with_name("build-metadata")
+                                .long("build-metadata")
+                                .value_name("FILEPATH")
+                                .takes_value(true)
+                                .requires("path-to-elf")
+                                .conflicts_with(SIGN_ONLY_ARG.name)
+                                .help(
+                                    "Publish the build metadata written by `cargo-build-sbf \
+                                     --build-metadata`, such as \
+                                     /path/to/program-build-metadata.json, along with the \
+                                     program. It is signed by the program authority",
+                                ),
+                        )
                         .offline_args()
                         .arg(compute_unit_price_arg()),
                 )
@@ -412,6 +419,9 @@ pub fn parse_program_v4_subcommand(
                     path_to_elf,
                     upload_range: value_t!(matches, "start-offset", usize).ok()
                         ..value_t!(matches, "end-offset", usize).ok(),
+                    build_metadata_location: matches
+                        .value_of("build-metadata")
+                        .map(|location| location.to_string()),
                 }),
                 signers: signer_info.signers,
             }
@@ -544,6 +554,7 @@ pub async fn process_program_v4_subcommand(
             authority_signer_index,
             path_to_elf,
             upload_range,
+            build_metadata_location,
         } => {
             let mut program_data = Vec::new();
             if let Some(path_to_elf) = path_to_elf {
@@ -552,8 +563,12 @@ pub async fn process_program_v4_subcommand(
                 file.read_to_end(&mut program_data)
                     .map_err(|err| format!("Unable to read program file: {err}"))?;
             }
-            process_deploy_program(
-                rpc_client,
+            let build_metadata = build_metadata_location
+                .as_ref()
+                .map(|location| read_build_metadata_file(location, &program_data))
+                .transpose()?;
+            let result = process_deploy_program(
+                rpc_client.clone(),
                 config,
                 additional_cli_config,
                 program_address,
@@ -563,7 +578,24 @@ pub async fn process_program_v4_subcommand(
                 &program_data,
                 upload_range.clone(),
             )
-            .await
+            .await?;
+            if let Some(build_metadata) = build_metadata {
+                // The program is deployed at this point, so don't fail the command
+                if let Err(err) = publish_build_metadata(
+                    &rpc_client,
+                    config,
+                    config.signers[0],
+                    program_address,
+                    config.signers[*authority_signer_index],
+                    &build_metadata,
+                    additional_cli_config.compute_unit_price,
+                )
+                .await
+                {
+                    eprintln!("Warning: Publishing build metadata failed: {err}");
+                }
+            }
+            Ok(result)
         }
         ProgramV4CliCommand::Retract {
             additional_cli_config,
@@ -629,7 +661,7 @@ pub async fn process_program_v4_subcommand(
 // * Upload a new buffer account without deploying it (preparation for two-step redeployment)
 //   - buffer_address must be `Some(program_signer.pubkey())`
 //   - upload_signer_index must be `Some(program_signer_index)`
-// * Upload a new program account and deploy it
+// * Upload a new program account and deploy it, or resume an interrupted upload to it
 //   - buffer_address must be `None`
 //   - upload_signer_index must be `Some(program_signer_index)`
 // * Single-step redeploy an existing program using the original program account
@@ -677,12 +709,18 @@ pub async fn process_deploy_program(
         .as_ref()
         .map(|account| loader_v4::check_id(&account.owner))
         .unwrap_or(false);
+    let program_is_retracted = program_account
+        .as_ref()
+        .filter(|account| loader_v4::check_id(&account.owner))
+        .and_then(|account| solana_loader_v4_program::get_state(&account.data).ok())
+        .is_some_and(|state| matches!(state.status, Retracted));
     if upload_signer_index
         .map(|index| &config.signers[*index].pubkey() == program_address)
         .unwrap_or(false)
     {
-        // Deploy new program
-        if program_account_exists {
+        // Deploy new program, unless it is retracted because a previous
+        // deployment of it was interrupted, in which case resume its upload
+        if program_account_exists && !program_is_retracted {
             return Err(
                 "Program account does exist already. Did you perhaps intent to redeploy an \
                  existing program instead? Then use --program-id instead of --program-keypair."
@@ -714,37 +752,6 @@ pub async fn process_deploy_program(
         }
     }
 
-    // Download feature set
-    let mut feature_set = FeatureSet::default();
-    for feature_ids in FEATURE_NAMES
-        .keys()
-        .cloned()
-        .collect::<Vec<Pubkey>>()
-        .chunks(MAX_MULTIPLE_ACCOUNTS)
-    {
-        rpc_client
-            .get_multiple_accounts(feature_ids)
-            .await?
-            .into_iter()
-            .zip(feature_ids)
-            .for_each(|(account, feature_id)| {
-                let activation_slot = account.and_then(status_from_account);
-
-                if let Some(CliFeatureStatus::Active(slot)) = activation_slot {
-                    feature_set.activate(feature_id, slot);
-                }
-            });
-    }
-    let program_runtime_environment = agave_syscalls::create_program_runtime_environment_v1(
-        &feature_set.runtime_features(),
-        &SVMTransactionExecutionBudget::new_with_defaults(
-            feature_set.is_active(&raise_cpi_nesting_limit_to_8::id()),
-        ),
-        true,
-        false,
-    )
-    .unwrap();
-
     // Verify the program
     let upload_range =
         upload_range.start.unwrap_or(0)..upload_range.end.unwrap_or(program_data.len());
@@ -766,12 +773,7 @@ pub async fn process_deploy_program(
         )
         .into());
     }
-    let executable =
-        Executable::<InvokeContext>::from_elf(program_data, Arc::new(program_runtime_environment))
-            .map_err(|err| format!("ELF error: {err}"))?;
-    executable
-        .verify::<RequisiteVerifier>()
-        .map_err(|err| format!("ELF error: {err}"))?;
+    verify_elf(program_data, fetch_feature_set(&rpc_client).await?)?;
 
     // Create and add retract and set_program_length instructions
     let mut initial_instructions = Vec::default();
@@ -854,18 +856,43 @@ pub async fn process_deploy_program(
             Some(&payer_pubkey),
         );
         let chunk_size = calculate_max_chunk_size(first_write_message);
+        let compute_unit_config = ComputeUnitConfig {
+            compute_unit_price: additional_cli_config.compute_unit_price,
+            compute_unit_limit: ComputeUnitLimit::Simulated,
+        };
         for (chunk, i) in program_data[upload_range.clone()]
             .chunks(chunk_size)
             .zip(0usize..)
         {
-            write_messages.push(vec![instruction::write(
+            let offset = upload_range
+                .start
+                .saturating_add(i.saturating_mul(chunk_size));
+            let instructions = vec![instruction::write(
                 upload_address,
                 &authority_pubkey,
-                (upload_range.start as u32).saturating_add(i.saturating_mul(chunk_size) as u32),
+                offset as u32,
                 chunk.to_vec(),
-            )]);
+            )]
+            .with_compute_unit_config(&compute_unit_config);
+            write_messages.push(WriteMessage {
+                range: offset..offset.saturating_add(chunk.len()),
+                message: Message::new(&instructions, Some(&payer_pubkey)),
+            });
         }
     }
+    let mut upload = ProgramDataUpload {
+        address: *upload_address,
+        data_offset: LoaderV4State::program_data_offset(),
+        program_data,
+        write_messages,
+    };
+    if let Some(upload_account) = upload_account
+        .as_ref()
+        .filter(|account| loader_v4::check_id(&account.owner))
+    {
+        // Resume a previous upload, only writing the chunks which differ
+        upload.skip_written(&upload_account.data);
+    }
 
     let final_instructions = if buffer_address == Some(program_address) {
         // Upload to buffer only and skip actual deployment
@@ -899,7 +926,7 @@ pub async fn process_deploy_program(
         } else {
             vec![initial_instructions]
         },
-        write_messages,
+        Some(upload),
         if final_instructions.is_empty() {
             Vec::default()
         } else {
@@ -956,7 +983,7 @@ async fn process_retract_program(
         additional_cli_config,
         auth_signer_index,
         vec![instructions],
-        Vec::default(),
+        None,
         Vec::default(),
         0,
         config.output_format.formatted_string(&CliProgramId {
@@ -1002,7 +1029,7 @@ async fn process_transfer_authority_of_program(
         additional_cli_config,
         auth_signer_index,
         messages,
-        Vec::default(),
+        None,
         Vec::default(),
         0,
         config.output_format.formatted_string(&CliProgramId {
@@ -1048,7 +1075,7 @@ async fn process_finalize_program(
         additional_cli_config,
         auth_signer_index,
         messages,
-        Vec::default(),
+        None,
         Vec::default(),
         0,
         config.output_format.formatted_string(&CliProgramId {
@@ -1079,6 +1106,14 @@ async fn process_show(
                         LoaderV4Status::Deployed => "deployed",
                         LoaderV4Status::Finalized => "finalized",
                     };
+                    let build_metadata = fetch_build_metadata(
+                        &rpc_client,
+                        config,
+                        &program_address,
+                        build_metadata_authority(state),
+                        &account.data[LoaderV4State::program_data_offset()..],
+                    )
+                    .await?;
                     Ok(config.output_format.formatted_string(&CliProgramV4 {
                         program_id: program_address.to_string(),
                         owner: account.owner.to_string(),
@@ -1089,6 +1124,7 @@ async fn process_show(
                             .len()
                             .saturating_sub(LoaderV4State::program_data_offset()),
                         status: status.to_string(),
+                        build_metadata,
                     }))
                 } else {
                     Err(format!("{program_address} program state is invalid").into())
@@ -1119,9 +1155,22 @@ pub async fn process_dump(
             .value
         {
             if loader_v4::check_id(&account.owner) {
+                let program_data = &account.data[LoaderV4State::program_data_offset()..];
                 let mut f = File::create(output_location)?;
-                f.write_all(&account.data[LoaderV4State::program_data_offset()..])?;
-                Ok(format!("Wrote program to {output_location}"))
+                f.write_all(program_data)?;
+                let state = solana_loader_v4_program::get_state(&account.data)?;
+                let build_metadata = fetch_build_metadata(
+                    &rpc_client,
+                    config,
+                    &account_pubkey,
+                    build_metadata_authority(state),
+                    program_data,
+                )
+                .await?;
+                Ok(format!(
+                    "Wrote program to {output_location}{}",
+                    verified_build_metadata_message(build_metadata.as_ref())
+                ))
             } else {
                 Err(format!("{account_pubkey} is not owned by loader-v4").into())
             }
@@ -1224,7 +1273,7 @@ async fn send_messages(
     additional_cli_config: &AdditionalCliConfig,
     auth_signer_index: &SignerIndex,
     initial_messages: Vec<Vec<Instruction>>,
-    write_messages: Vec<Vec<Instruction>>,
+    mut upload: Option<ProgramDataUpload<'_>>,
     final_messages: Vec<Vec<Instruction>>,
     balance_needed: u64,
     ok_result: String,
@@ -1257,7 +1306,17 @@ async fn send_messages(
         Ok::<Vec<solana_message::Message>, Box<dyn std::error::Error>>(messages)
     };
     let initial_messages = simulate_messages(initial_messages).await?;
-    let write_messages = simulate_messages(write_messages).await?;
+    if let Some(upload) = upload.as_mut() {
+        for WriteMessage { message, .. } in &mut upload.write_messages {
+            message.recent_blockhash = blockhash;
+            simulate_and_update_compute_unit_limit(
+                &ComputeUnitLimit::Simulated,
+                &rpc_client,
+                message,
+            )
+            .await?;
+        }
+    }
     let final_messages = simulate_messages(final_messages).await?;
 
     let mut fee = Saturating(0);
@@ -1268,9 +1327,13 @@ async fn send_messages(
         fee += rpc_client.get_fee_for_message(message).await?;
     }
     // Assume all write messages cost the same
-    if let Some(message) = write_messages.first() {
+    let write_messages = upload
+        .as_ref()
+        .map(|upload| upload.write_messages.as_slice())
+        .unwrap_or_default();
+    if let Some(write_message) = write_messages.first() {
         fee += rpc_client
-            .get_fee_for_message(message)
+            .get_fee_for_message(&write_message.message)
             .await?
             .saturating_mul(write_messages.len() as u64);
     }
@@ -1306,9 +1369,9 @@ async fn send_messages(
             )
         } else {
             rpc_client
-                .send_and_confirm_transaction_with_spinner_and_config(
+                .send_and_confirm_transaction_with_compute_budget(
                     &tx,
-                    config.commitment,
+                    config,
                     config.send_transaction_config,
                 )
                 .await
@@ -1324,73 +1387,17 @@ async fn send_messages(
         }
     }
 
-    if !write_messages.is_empty() {
-        let connection_cache = {
-            #[cfg(feature = "dev-context-only-utils")]
-            let cache =
-                ConnectionCache::new_quic_for_tests("connection_cache_cli_program_v4_quic", 1);
-            #[cfg(not(feature = "dev-context-only-utils"))]
-            let cache = ConnectionCache::new_quic("connection_cache_cli_program_v4_quic", 1);
-            cache
-        };
-
-        let transaction_errors = match connection_cache {
-            ConnectionCache::Udp(cache) => {
-                solana_tpu_client::nonblocking::tpu_client::TpuClient::new_with_connection_cache(
-                    rpc_client.clone(),
-                    &config.websocket_url,
-                    TpuClientConfig::default(),
-                    cache,
-                )
-                .await?
-                .send_and_confirm_messages_with_spinner(
-                    &write_messages,
-                    &[config.signers[0], config.signers[*auth_signer_index]],
-                )
-                .await
-            }
-            ConnectionCache::Quic(cache) => {
-                let tpu_client = if additional_cli_config.use_rpc {
-                    None
-                } else {
-                    // `solana_client` type currently required by `send_and_confirm_transactions_in_parallel_v2`
-                    Some(
-                        solana_client::nonblocking::tpu_client::TpuClient::new_with_connection_cache(
-                            rpc_client.clone(),
-                            &config.websocket_url,
-                            TpuClientConfig::default(),
-                            cache,
-                        )
-                        .await
-                        .expect("Should return a valid tpu client"),
-                    )
-                };
-
-                send_and_confirm_transactions_in_parallel_v2(
-                    rpc_client.clone(),
-                    tpu_client,
-                    &write_messages,
-                    &[config.signers[0], config.signers[*auth_signer_index]],
-                    SendAndConfirmConfigV2 {
-                        resign_txs_count: Some(5),
-                        with_spinner: true,
-                        rpc_send_transaction_config: config.send_transaction_config,
-                    },
-                )
-                .await
-            }
-        }
-        .map_err(|err| format!("Data writes to account failed: {err}"))?
-        .into_iter()
-        .flatten()
-        .collect::<Vec<_>>();
-
-        if !transaction_errors.is_empty() {
-            for transaction_error in &transaction_errors {
-                error!("{transaction_error:?}");
-            }
-            return Err(format!("{} write transactions failed", transaction_errors.len()).into());
-        }
+    if let Some(upload) = upload.filter(|upload| !upload.write_messages.is_empty()) {
+        upload
+            .send_and_confirm(
+                rpc_client.clone(),
+                config,
+                &[config.signers[0], config.signers[*auth_signer_index]],
+                5,
+                additional_cli_config.use_rpc,
+            )
+            .await
+            .map_err(|err| format!("Data writes to account failed: {err}"))?;
     }
 
     for message in final_messages.into_iter() {
@@ -1403,6 +1410,12 @@ async fn send_messages(
     Ok(ok_result)
 }
 
+/// Finalized programs store their next version instead of an authority.
+fn build_metadata_authority(state: &LoaderV4State) -> Option<&Pubkey> {
+    (!matches!(state.status, LoaderV4Status::Finalized))
+        .then_some(&state.authority_address_or_next_version)
+}
+
 fn build_retract_instruction(
     account: &Account,
     buffer_address: &Pubkey,
@@ -1596,6 +1609,7 @@ async fn get_programs(
                 data_len: data_bytes
                     .len()
                     .saturating_sub(LoaderV4State::program_data_offset()),
+                build_metadata: None,
             });
         } else {
             return Err(format!("Error parsing Program account {program}").into());
@@ -2105,6 +2119,7 @@ mod tests {
                     authority_signer_index: 0,
                     path_to_elf: Some("/Users/test/program.so".to_string()),
                     upload_range: None..None,
+                    build_metadata_location: None,
                 }),
                 signers: vec![
                     Box::new(read_keypair_file(&keypair_file).unwrap()),
@@ -2132,6 +2147,7 @@ mod tests {
                     authority_signer_index: 0,
                     path_to_elf: Some("/Users/test/program.so".to_string()),
                     upload_range: None..None,
+                    build_metadata_location: None,
                 }),
                 signers: vec![
                     Box::new(read_keypair_file(&keypair_file).unwrap()),
@@ -2161,6 +2177,7 @@ mod tests {
                     authority_signer_index: 0,
                     path_to_elf: Some("/Users/test/program.so".to_string()),
                     upload_range: None..None,
+                    build_metadata_location: None,
                 }),
                 signers: vec![
                     Box::new(read_keypair_file(&keypair_file).unwrap()),
@@ -2190,6 +2207,7 @@ mod tests {
                     authority_signer_index: 2,
                     path_to_elf: Some("/Users/test/program.so".to_string()),
                     upload_range: None..None,
+                    build_metadata_location: None,
                 }),
                 signers: vec![
                     Box::new(read_keypair_file(&keypair_file).unwrap()),
@@ -2220,6 +2238,7 @@ mod tests {
                     authority_signer_index: 1,
                     path_to_elf: Some("/Users/test/program.so".to_string()),
                     upload_range: None..None,
+                    build_metadata_location: None,
                 }),
                 signers: vec![
                     Box::new(read_keypair_file(&keypair_file).unwrap()),
@@ -2251,6 +2270,7 @@ mod tests {
                     authority_signer_index: 2,
                     path_to_elf: Some("/Users/test/program.so".to_string()),
                     upload_range: None..None,
+                    build_metadata_location: None,
                 }),
                 signers: vec![
                     Box::new(read_keypair_file(&keypair_file).unwrap()),
@@ -2282,6 +2302,7 @@ mod tests {
                     authority_signer_index: 2,
                     path_to_elf: None,
                     upload_range: None..None,
+                    build_metadata_location: None,
                 }),
                 signers: vec![
                     Box::new(read_keypair_file(&keypair_file).unwrap()),
@@ -2323,6 +2344,7 @@ mod tests {
                     authority_signer_index: 0,
                     path_to_elf: Some("/Users/test/program.so".to_string()),
                     upload_range: Some(16)..Some(32),
+                    build_metadata_location: None,
                 }),
                 signers: vec![Box::new(read_keypair_file(&keypair_file).unwrap()),],
             }
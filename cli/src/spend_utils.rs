@@ -1,7 +1,7 @@
 use {
     crate::{
         checks::{check_account_for_balance_with_commitment, get_fee_for_messages},
-        cli::CliError,
+        cli::{CliConfig, CliError},
         compute_budget::{simulate_and_update_compute_unit_limit, UpdateComputeUnitLimitResult},
         stake,
     },
@@ -10,7 +10,6 @@ use {
         compute_budget::ComputeUnitLimit, input_parsers::lamports_of_sol, offline::SIGN_ONLY_ARG,
     },
     solana_cli_output::display::build_balance_message,
-    solana_commitment_config::CommitmentConfig,
     solana_hash::Hash,
     solana_message::Message,
     solana_pubkey::Pubkey,
@@ -68,7 +67,7 @@ pub async fn resolve_spend_tx_and_check_account_balance<F>(
     from_pubkey: &Pubkey,
     compute_unit_limit: ComputeUnitLimit,
     build_message: F,
-    commitment: CommitmentConfig,
+    config: &CliConfig<'_>,
 ) -> Result<(Message, u64), CliError>
 where
     F: Fn(u64) -> Message,
@@ -82,7 +81,7 @@ where
         from_pubkey,
         compute_unit_limit,
         build_message,
-        commitment,
+        config,
     )
     .await
 }
@@ -96,7 +95,7 @@ pub async fn resolve_spend_tx_and_check_account_balances<F>(
     fee_pubkey: &Pubkey,
     compute_unit_limit: ComputeUnitLimit,
     build_message: F,
-    commitment: CommitmentConfig,
+    config: &CliConfig<'_>,
 ) -> Result<(Message, u64), CliError>
 where
     F: Fn(u64) -> Message,
@@ -110,12 +109,14 @@ where
             from_pubkey,
             fee_pubkey,
             0,
+            0,
             compute_unit_limit,
             build_message,
         )
         .await?;
         Ok((message, spend))
     } else {
+        let commitment = config.commitment;
         let account = rpc_client
             .get_account_with_commitment(from_pubkey, commitment)
             .await?
@@ -162,6 +163,12 @@ where
             from_pubkey,
             fee_pubkey,
             from_rent_exempt_minimum,
+            // The automatic compute budget may pay up to its maximum priority
+            // fee on top of the base fee
+            config
+                .auto_compute_budget
+                .map(|auto_compute_budget| auto_compute_budget.max_priority_fee)
+                .unwrap_or_default(),
             compute_unit_limit,
             build_message,
         )
@@ -202,6 +209,7 @@ async fn resolve_spend_message<F>(
     from_pubkey: &Pubkey,
     fee_pubkey: &Pubkey,
     from_rent_exempt_minimum: u64,
+    max_priority_fee: u64,
     compute_unit_limit: ComputeUnitLimit,
     build_message: F,
 ) -> Result<(Message, SpendAndFee), CliError>
@@ -256,7 +264,9 @@ where
                     None
                 };
             (
-                get_fee_for_messages(rpc_client, &[&dummy_message]).await?,
+                get_fee_for_messages(rpc_client, &[&dummy_message])
+                    .await?
+                    .saturating_add(max_priority_fee),
                 compute_unit_info,
             )
         }
@@ -6,7 +6,8 @@ use {
             ProcessResult,
         },
         compute_budget::{
-            simulate_and_update_compute_unit_limit, ComputeUnitConfig, WithComputeUnitConfig,
+            simulate_and_update_compute_unit_limit, ComputeUnitConfig, SendWithComputeBudget,
+            WithComputeUnitConfig,
         },
         feature::get_feature_activation_epoch,
         memo::WithMemo,
@@ -1485,7 +1486,7 @@ pub async fn process_create_stake_account(
         &fee_payer.pubkey(),
         compute_unit_limit,
         build_message,
-        config.commitment,
+        config,
     )
     .await?;
 
@@ -1535,9 +1536,9 @@ pub async fn process_create_stake_account(
     } else {
         tx.try_sign(&config.signers, recent_blockhash)?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
@@ -1683,13 +1684,13 @@ pub async fn process_stake_authorize(
         .await?;
         let result = if no_wait {
             rpc_client
-                .send_transaction_with_config(&tx, config.send_transaction_config)
+                .send_transaction_with_compute_budget(&tx, config, config.send_transaction_config)
                 .await
         } else {
             rpc_client
-                .send_and_confirm_transaction_with_spinner_and_config(
+                .send_and_confirm_transaction_with_compute_budget(
                     &tx,
-                    config.commitment,
+                    config,
                     config.send_transaction_config,
                 )
                 .await
@@ -1852,9 +1853,9 @@ pub async fn process_deactivate_stake_account(
         )
         .await?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
@@ -1936,7 +1937,7 @@ pub async fn process_withdraw_stake(
         &fee_payer.pubkey(),
         compute_unit_limit,
         build_message,
-        config.commitment,
+        config,
     )
     .await?;
 
@@ -1971,9 +1972,9 @@ pub async fn process_withdraw_stake(
         )
         .await?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
@@ -2175,9 +2176,9 @@ pub async fn process_split_stake(
         )
         .await?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
@@ -2300,9 +2301,9 @@ pub async fn process_merge_stake(
         )
         .await?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
@@ -2411,9 +2412,9 @@ pub async fn process_stake_set_lockup(
         )
         .await?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
@@ -2933,9 +2934,9 @@ pub async fn process_delegate_stake(
         )
         .await?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
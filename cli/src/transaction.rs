@@ -0,0 +1,1365 @@
+//! Building, signing and sending arbitrary transactions described in JSON or
+//! YAML files.
+//!
+//! A transaction spec lists the instructions of the transaction, and
+//! optionally its compute budget and the address lookup tables to compile it
+//! with:
+//!
+//! ```yaml
+//! computeUnitLimit: 20000
+//! computeUnitPrice: 1000
+//! addressLookupTables:
+//!   - 4QwSwNriKPrz8DLW4ju5uxC2TN5cksJx6tPUPj7DGLAW
+//! instructions:
+//!   - programId: MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr
+//!     accounts:
+//!       - pubkey: ~/.config/solana/id.json
+//!         isSigner: true
+//!     data:
+//!       hex: 68656c6c6f
+//! ```
+//!
+//! The `pubkey` of an account is an address or, for signers, any signing
+//! source. Instruction data is given as `hex`, `base58` or `base64`, or as
+//! `borsh` arguments encoded with an Anchor IDL:
+//!
+//! ```yaml
+//!     data:
+//!       borsh:
+//!         idl: counter.json
+//!         instruction: increment
+//!         args:
+//!           amount: 5
+//! ```
+//!
+//! Lookup tables are fetched from the cluster, unless their addresses are
+//! listed in the spec (`address` and `addresses`), which offline signers need.
+//!
+//! Transactions are passed between hosts as files of base64 encoded
+//! transactions, as accepted by `solana decode-transaction`: `build
+//! --output-file` writes one, `sign` adds signatures to it and `send` submits
+//! it once fully signed.
+
+use {
+    crate::{
+        cli::{CliCommand, CliCommandInfo, CliConfig, CliError, ProcessResult},
+        compute_budget::SendWithComputeBudget,
+        nonce::check_nonce_account,
+    },
+    base64::{prelude::BASE64_STANDARD, Engine},
+    clap::{App, AppSettings, Arg, ArgMatches, SubCommand},
+    serde::Deserialize,
+    serde_json::Value,
+    solana_address_lookup_table_interface::{
+        self as address_lookup_table, state::AddressLookupTable,
+    },
+    solana_clap_utils::{
+        compute_budget::{compute_unit_price_arg, COMPUTE_UNIT_PRICE_ARG},
+        fee_payer::*,
+        input_parsers::*,
+        input_validators::*,
+        keypair::*,
+        nonce::*,
+        offline::*,
+    },
+    solana_cli_output::{return_versioned_signers_with_config, CliSignature, ReturnSignersConfig},
+    solana_compute_budget_interface::ComputeBudgetInstruction,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_message::{v0, AddressLookupTableAccount, Message, VersionedMessage},
+    solana_presigner::Presigner,
+    solana_pubkey::Pubkey,
+    solana_remote_wallet::remote_wallet::RemoteWalletManager,
+    solana_rpc_client::nonblocking::rpc_client::RpcClient,
+    solana_rpc_client_nonce_utils::nonblocking::blockhash_query::BlockhashQuery,
+    solana_sha256_hasher::hashv,
+    solana_signature::Signature,
+    solana_signer::Signer,
+    solana_system_interface::instruction as system_instruction,
+    solana_transaction::versioned::VersionedTransaction,
+    solana_transaction_status::{
+        Encodable, EncodedTransaction, TransactionBinaryEncoding, UiTransactionEncoding,
+    },
+    std::{
+        collections::HashMap,
+        error,
+        ffi::OsStr,
+        fs::{self, File},
+        path::Path,
+        rc::Rc,
+        str::FromStr,
+    },
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AddressLookupTableSource {
+    /// Fetched from the cluster
+    Address(Pubkey),
+    Account(AddressLookupTableAccount),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TransactionCliCommand {
+    Build {
+        instructions: Vec<Instruction>,
+        address_lookup_tables: Vec<AddressLookupTableSource>,
+        compute_unit_limit: Option<u32>,
+        compute_unit_price: Option<u64>,
+        fee_payer: SignerIndex,
+        sign_only: bool,
+        dump_transaction_message: bool,
+        blockhash_query: BlockhashQuery,
+        nonce_account: Option<Pubkey>,
+        nonce_authority: SignerIndex,
+        output_file: Option<String>,
+    },
+    Sign {
+        transaction_file: String,
+        output_file: Option<String>,
+    },
+    Send {
+        transaction_file: String,
+    },
+}
+
+pub trait TransactionSubCommands {
+    fn transaction_subcommands(self) -> Self;
+}
+
+impl TransactionSubCommands for App<'_, '_> {
+    fn transaction_subcommands(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("transaction")
+                .about("Build, sign and send arbitrary transactions")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("build")
+                        .about("Build a transaction from a JSON or YAML description")
+                        .arg(
+                            Arg::with_name("spec_file")
+                                .index(1)
+                                .value_name("SPEC_FILEPATH")
+                                .takes_value(true)
+                                .required(true)
+                                .help(
+                                    "JSON or YAML file describing the instructions of the \
+                                     transaction",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("output_file")
+                                .long("output-file")
+                                .value_name("FILEPATH")
+                                .takes_value(true)
+                                .conflicts_with(SIGN_ONLY_ARG.name)
+                                .help(
+                                    "Write the transaction to this file instead of sending it, \
+                                     signed by the available signers only",
+                                ),
+                        )
+                        .offline_args()
+                        .nonce_args(false)
+                        .arg(fee_payer_arg())
+                        .arg(compute_unit_price_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("sign")
+                        .about("Add signatures to a transaction file")
+                        .arg(transaction_file_arg())
+                        .arg(
+                            Arg::with_name("signers")
+                                .index(2)
+                                .value_name("SIGNER")
+                                .takes_value(true)
+                                .multiple(true)
+                                .validator(is_valid_signer)
+                                .help(
+                                    "Signers to sign the transaction with [default: the default \
+                                     configured keypair, unless signatures are provided]",
+                                ),
+                        )
+                        .arg(presigner_arg())
+                        .arg(
+                            Arg::with_name("output_file")
+                                .long("output-file")
+                                .value_name("FILEPATH")
+                                .takes_value(true)
+                                .help(
+                                    "Write the signed transaction to this file [default: the \
+                                     transaction file]",
+                                ),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("send")
+                        .about("Send a fully signed transaction file")
+                        .arg(transaction_file_arg())
+                        .arg(presigner_arg()),
+                ),
+        )
+    }
+}
+
+fn transaction_file_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("transaction_file")
+        .index(1)
+        .value_name("TRANSACTION_FILEPATH")
+        .takes_value(true)
+        .required(true)
+        .help("File of the base64 encoded transaction")
+}
+
+/// Like the `--signer` offline argument, for transactions that already have
+/// a blockhash.
+pub(crate) fn presigner_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(SIGNER_ARG.name)
+        .long(SIGNER_ARG.long)
+        .takes_value(true)
+        .value_name("PUBKEY=SIGNATURE")
+        .validator(is_pubkey_sig)
+        .multiple(true)
+        .number_of_values(1)
+        .help(SIGNER_ARG.help)
+}
+
+pub fn parse_transaction_subcommand(
+    matches: &ArgMatches<'_>,
+    default_signer: &DefaultSigner,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let response = match matches.subcommand() {
+        ("build", Some(matches)) => {
+            parse_build_transaction(matches, default_signer, wallet_manager)?
+        }
+        ("sign", Some(matches)) => {
+            let mut signers = presigners_of(matches);
+            for path in matches.values_of("signers").into_iter().flatten() {
+                signers.push(signer_from_path(matches, path, "signer", wallet_manager)?);
+            }
+            if signers.is_empty() {
+                signers.push(default_signer.signer_from_path(matches, wallet_manager)?);
+            }
+            CliCommandInfo {
+                command: CliCommand::Transaction(TransactionCliCommand::Sign {
+                    transaction_file: matches.value_of("transaction_file").unwrap().to_string(),
+                    output_file: matches.value_of("output_file").map(String::from),
+                }),
+                signers,
+            }
+        }
+        ("send", Some(matches)) => CliCommandInfo {
+            command: CliCommand::Transaction(TransactionCliCommand::Send {
+                transaction_file: matches.value_of("transaction_file").unwrap().to_string(),
+            }),
+            signers: presigners_of(matches),
+        },
+        _ => unreachable!(),
+    };
+    Ok(response)
+}
+
+pub(crate) fn presigners_of(matches: &ArgMatches<'_>) -> CliSigners {
+    pubkeys_sigs_of(matches, SIGNER_ARG.name)
+        .into_iter()
+        .flatten()
+        .map(|(pubkey, signature)| Box::new(Presigner::new(&pubkey, &signature)) as Box<dyn Signer>)
+        .collect()
+}
+
+fn parse_build_transaction(
+    matches: &ArgMatches<'_>,
+    default_signer: &DefaultSigner,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let spec_file = matches.value_of("spec_file").unwrap();
+    let spec = read_transaction_spec(spec_file).map_err(CliError::BadParameter)?;
+    let spec_dir = Path::new(spec_file).parent().unwrap_or(Path::new(""));
+    let sign_only = matches.is_present(SIGN_ONLY_ARG.name);
+    let dump_transaction_message = matches.is_present(DUMP_TRANSACTION_MESSAGE.name);
+    let blockhash_query = BlockhashQuery::new_from_matches(matches);
+    let output_file = matches.value_of("output_file").map(String::from);
+    // Transactions written to files can be signed by their other signers later
+    let signer_config = SignerFromPathConfig {
+        allow_null_signer: output_file.is_some(),
+    };
+    let nonce_account = pubkey_of_signer(matches, NONCE_ARG.name, wallet_manager)?;
+    let (nonce_authority, nonce_authority_pubkey) = signer_of_with_config(
+        matches,
+        NONCE_AUTHORITY_ARG.name,
+        wallet_manager,
+        &signer_config,
+    )?;
+    let (fee_payer, fee_payer_pubkey) =
+        signer_of_with_config(matches, FEE_PAYER_ARG.name, wallet_manager, &signer_config)?;
+
+    let mut bulk_signers = vec![fee_payer];
+    if nonce_account.is_some() {
+        bulk_signers.push(nonce_authority);
+    }
+    // Each account is resolved once, not to prompt for the same signer twice
+    let mut resolved_signers = HashMap::<String, Pubkey>::new();
+    let mut resolved_accounts = HashMap::<String, Pubkey>::new();
+    let mut instructions = Vec::with_capacity(spec.instructions.len());
+    for (index, instruction) in spec.instructions.into_iter().enumerate() {
+        let bad_instruction =
+            |err: String| CliError::BadParameter(format!("Instruction {index}: {err}"));
+        let program_id = Pubkey::from_str(&instruction.program_id)
+            .map_err(|err| bad_instruction(format!("invalid program id: {err}")))?;
+        let mut accounts = Vec::with_capacity(instruction.accounts.len());
+        for account in instruction.accounts {
+            let resolved = if account.is_signer {
+                &mut resolved_signers
+            } else {
+                &mut resolved_accounts
+            };
+            let pubkey = match resolved.get(&account.pubkey) {
+                Some(pubkey) => *pubkey,
+                None if account.is_signer => {
+                    let signer = signer_from_path_with_config(
+                        matches,
+                        &account.pubkey,
+                        "signer",
+                        wallet_manager,
+                        &signer_config,
+                    )?;
+                    let pubkey = signer.pubkey();
+                    bulk_signers.push(Some(signer));
+                    resolved.insert(account.pubkey, pubkey);
+                    pubkey
+                }
+                None => {
+                    let pubkey =
+                        pubkey_from_path(matches, &account.pubkey, "account", wallet_manager)?;
+                    resolved.insert(account.pubkey, pubkey);
+                    pubkey
+                }
+            };
+            accounts.push(AccountMeta {
+                pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            });
+        }
+        let data = instruction
+            .data
+            .map(|data| data.decode(spec_dir))
+            .transpose()
+            .map_err(bad_instruction)?
+            .unwrap_or_default();
+        instructions.push(Instruction {
+            program_id,
+            accounts,
+            data,
+        });
+    }
+    let address_lookup_tables = spec
+        .address_lookup_tables
+        .into_iter()
+        .map(AddressLookupTableSource::try_from)
+        .collect::<Result<_, _>>()
+        .map_err(CliError::BadParameter)?;
+
+    let signer_info =
+        default_signer.generate_unique_signers(bulk_signers, matches, wallet_manager)?;
+    Ok(CliCommandInfo {
+        command: CliCommand::Transaction(TransactionCliCommand::Build {
+            instructions,
+            address_lookup_tables,
+            compute_unit_limit: spec.compute_unit_limit,
+            compute_unit_price: value_of(matches, COMPUTE_UNIT_PRICE_ARG.name)
+                .or(spec.compute_unit_price),
+            fee_payer: signer_info.index_of(fee_payer_pubkey).unwrap(),
+            sign_only,
+            dump_transaction_message,
+            blockhash_query,
+            nonce_account,
+            nonce_authority: signer_info.index_of(nonce_authority_pubkey).unwrap(),
+            output_file,
+        }),
+        signers: signer_info.signers,
+    })
+}
+
+#[allow(clippy::type_complexity)]
+fn signer_of_with_config(
+    matches: &ArgMatches<'_>,
+    name: &str,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+    config: &SignerFromPathConfig,
+) -> Result<(Option<Box<dyn Signer>>, Option<Pubkey>), Box<dyn error::Error>> {
+    if let Some(location) = matches.value_of(name) {
+        let signer = signer_from_path_with_config(matches, location, name, wallet_manager, config)?;
+        let signer_pubkey = signer.pubkey();
+        Ok((Some(signer), Some(signer_pubkey)))
+    } else {
+        Ok((None, None))
+    }
+}
+
+pub async fn process_transaction_subcommand(
+    rpc_client: &RpcClient,
+    config: &CliConfig<'_>,
+    subcommand: &TransactionCliCommand,
+) -> ProcessResult {
+    match subcommand {
+        TransactionCliCommand::Build {
+            instructions,
+            address_lookup_tables,
+            compute_unit_limit,
+            compute_unit_price,
+            fee_payer,
+            sign_only,
+            dump_transaction_message,
+            blockhash_query,
+            nonce_account,
+            nonce_authority,
+            output_file,
+        } => {
+            process_build_transaction(
+                rpc_client,
+                config,
+                instructions,
+                address_lookup_tables,
+                *compute_unit_limit,
+                *compute_unit_price,
+                *fee_payer,
+                *sign_only,
+                *dump_transaction_message,
+                blockhash_query,
+                nonce_account.as_ref(),
+                *nonce_authority,
+                output_file.as_deref(),
+            )
+            .await
+        }
+        TransactionCliCommand::Sign {
+            transaction_file,
+            output_file,
+        } => process_sign_transaction(config, transaction_file, output_file.as_deref()),
+        TransactionCliCommand::Send { transaction_file } => {
+            let mut tx = read_transaction_file(transaction_file)?;
+            try_partial_sign(&mut tx, &config.signers)?;
+            send_transaction(rpc_client, config, &tx).await
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn process_build_transaction(
+    rpc_client: &RpcClient,
+    config: &CliConfig<'_>,
+    instructions: &[Instruction],
+    address_lookup_tables: &[AddressLookupTableSource],
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
+    fee_payer: SignerIndex,
+    sign_only: bool,
+    dump_transaction_message: bool,
+    blockhash_query: &BlockhashQuery,
+    nonce_account: Option<&Pubkey>,
+    nonce_authority: SignerIndex,
+    output_file: Option<&str>,
+) -> ProcessResult {
+    let fee_payer = config.signers[fee_payer];
+    let nonce_authority = config.signers[nonce_authority];
+    let recent_blockhash = blockhash_query
+        .get_blockhash(rpc_client, config.commitment)
+        .await?;
+
+    let mut ixs = Vec::with_capacity(instructions.len().saturating_add(3));
+    if let Some(nonce_account) = nonce_account {
+        ixs.push(system_instruction::advance_nonce_account(
+            nonce_account,
+            &nonce_authority.pubkey(),
+        ));
+    }
+    ixs.extend_from_slice(instructions);
+    if let Some(compute_unit_limit) = compute_unit_limit {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(
+            compute_unit_limit,
+        ));
+    }
+    if let Some(compute_unit_price) = compute_unit_price {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_price(
+            compute_unit_price,
+        ));
+    }
+
+    let mut lookup_table_accounts = Vec::with_capacity(address_lookup_tables.len());
+    for lookup_table in address_lookup_tables {
+        lookup_table_accounts.push(match lookup_table {
+            AddressLookupTableSource::Address(address) => {
+                get_address_lookup_table_account(rpc_client, config, address).await?
+            }
+            AddressLookupTableSource::Account(account) => account.clone(),
+        });
+    }
+    let message = if lookup_table_accounts.is_empty() {
+        VersionedMessage::Legacy(Message::new_with_blockhash(
+            &ixs,
+            Some(&fee_payer.pubkey()),
+            &recent_blockhash,
+        ))
+    } else {
+        VersionedMessage::V0(v0::Message::try_compile(
+            &fee_payer.pubkey(),
+            &ixs,
+            &lookup_table_accounts,
+            recent_blockhash,
+        )?)
+    };
+    let mut tx = VersionedTransaction {
+        signatures: vec![
+            Signature::default();
+            usize::from(message.header().num_required_signatures)
+        ],
+        message,
+    };
+    try_partial_sign(&mut tx, &config.signers)?;
+
+    if sign_only {
+        return return_versioned_signers_with_config(
+            &tx,
+            &config.output_format,
+            &ReturnSignersConfig {
+                dump_transaction_message,
+            },
+        );
+    }
+    if let Some(output_file) = output_file {
+        write_transaction_file(output_file, &tx)?;
+        return return_versioned_signers_with_config(
+            &tx,
+            &config.output_format,
+            &ReturnSignersConfig::default(),
+        );
+    }
+    if let Some(nonce_account) = nonce_account {
+        let nonce_account =
+            solana_rpc_client_nonce_utils::nonblocking::get_account_with_commitment(
+                rpc_client,
+                nonce_account,
+                config.commitment,
+            )
+            .await?;
+        check_nonce_account(&nonce_account, &nonce_authority.pubkey(), &recent_blockhash)?;
+    }
+    send_transaction(rpc_client, config, &tx).await
+}
+
+fn process_sign_transaction(
+    config: &CliConfig<'_>,
+    transaction_file: &str,
+    output_file: Option<&str>,
+) -> ProcessResult {
+    let mut tx = read_transaction_file(transaction_file)?;
+    try_partial_sign(&mut tx, &config.signers)?;
+    write_transaction_file(output_file.unwrap_or(transaction_file), &tx)?;
+    return_versioned_signers_with_config(
+        &tx,
+        &config.output_format,
+        &ReturnSignersConfig::default(),
+    )
+}
+
+pub(crate) async fn send_transaction(
+    rpc_client: &RpcClient,
+    config: &CliConfig<'_>,
+    tx: &VersionedTransaction,
+) -> ProcessResult {
+    let unsigned = tx
+        .verify_with_results()
+        .into_iter()
+        .zip(tx.message.static_account_keys())
+        .filter(|(is_valid, _)| !is_valid)
+        .map(|(_, pubkey)| pubkey.to_string())
+        .collect::<Vec<_>>();
+    if !unsigned.is_empty() {
+        return Err(format!(
+            "The transaction is missing valid signatures from {}",
+            unsigned.join(", ")
+        )
+        .into());
+    }
+    let signature = rpc_client
+        .send_and_confirm_versioned_transaction_with_compute_budget(
+            tx,
+            config,
+            config.send_transaction_config,
+        )
+        .await?;
+    Ok(config.output_format.formatted_string(&CliSignature {
+        signature: signature.to_string(),
+    }))
+}
+
+pub(crate) async fn get_address_lookup_table_account(
+    rpc_client: &RpcClient,
+    config: &CliConfig<'_>,
+    lookup_table_pubkey: &Pubkey,
+) -> Result<AddressLookupTableAccount, Box<dyn error::Error>> {
+    let lookup_table_account = rpc_client
+        .get_account_with_commitment(lookup_table_pubkey, config.commitment)
+        .await?
+        .value
+        .ok_or_else(|| format!("Lookup table account {lookup_table_pubkey} not found"))?;
+    if !address_lookup_table::program::check_id(&lookup_table_account.owner) {
+        return Err(format!(
+            "Lookup table account {lookup_table_pubkey} is not owned by the Address Lookup Table \
+             program",
+        )
+        .into());
+    }
+    let lookup_table = AddressLookupTable::deserialize(&lookup_table_account.data)?;
+    Ok(AddressLookupTableAccount {
+        key: *lookup_table_pubkey,
+        addresses: lookup_table.addresses.to_vec(),
+    })
+}
+
+/// Signs `tx` with each of `signers`, keeping the signatures of its other
+/// signers. Null signers don't erase the signatures already there.
+pub fn try_partial_sign(
+    tx: &mut VersionedTransaction,
+    signers: &[&dyn Signer],
+) -> Result<(), Box<dyn error::Error>> {
+    let message_data = tx.message.serialize();
+    let num_required_signatures = usize::from(tx.message.header().num_required_signatures);
+    for signer in signers {
+        let pubkey = signer.try_pubkey()?;
+        let position = tx
+            .message
+            .static_account_keys()
+            .iter()
+            .take(num_required_signatures)
+            .position(|signer_pubkey| *signer_pubkey == pubkey)
+            .ok_or_else(|| format!("{pubkey} is not a signer of the transaction"))?;
+        let signature = signer.try_sign_message(&message_data)?;
+        if signature != Signature::default() {
+            tx.signatures[position] = signature;
+        }
+    }
+    Ok(())
+}
+
+pub fn read_transaction_file(path: &str) -> Result<VersionedTransaction, Box<dyn error::Error>> {
+    let blob = fs::read_to_string(path).map_err(|err| format!("Unable to read {path}: {err}"))?;
+    EncodedTransaction::Binary(blob.trim().to_string(), TransactionBinaryEncoding::Base64)
+        .decode()
+        .ok_or_else(|| format!("Unable to decode the transaction in {path}").into())
+}
+
+pub fn write_transaction_file(
+    path: &str,
+    tx: &VersionedTransaction,
+) -> Result<(), Box<dyn error::Error>> {
+    let EncodedTransaction::Binary(blob, _) = tx.encode(UiTransactionEncoding::Base64) else {
+        unreachable!("base64 encoded transactions are binary");
+    };
+    fs::write(path, format!("{blob}\n")).map_err(|err| format!("Unable to write {path}: {err}"))?;
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TransactionSpec {
+    instructions: Vec<InstructionSpec>,
+    #[serde(default)]
+    compute_unit_limit: Option<u32>,
+    #[serde(default)]
+    compute_unit_price: Option<u64>,
+    #[serde(default)]
+    address_lookup_tables: Vec<AddressLookupTableSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct InstructionSpec {
+    program_id: String,
+    #[serde(default)]
+    accounts: Vec<AccountMetaSpec>,
+    #[serde(default)]
+    data: Option<InstructionDataSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct AccountMetaSpec {
+    pubkey: String,
+    #[serde(default)]
+    is_signer: bool,
+    #[serde(default)]
+    is_writable: bool,
+}
+
+/// Exactly one of the encodings must be set.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct InstructionDataSpec {
+    hex: Option<String>,
+    base58: Option<String>,
+    base64: Option<String>,
+    borsh: Option<BorshDataSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct BorshDataSpec {
+    /// Path to the Anchor IDL of the program, relative to the spec
+    idl: String,
+    instruction: String,
+    #[serde(default)]
+    args: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum AddressLookupTableSpec {
+    Address(String),
+    Account {
+        address: String,
+        addresses: Vec<String>,
+    },
+}
+
+fn read_transaction_spec(path: &str) -> Result<TransactionSpec, String> {
+    let file = File::open(path).map_err(|err| format!("Unable to open {path}: {err}"))?;
+    // JSON is mostly YAML, but the JSON parser has the better error messages
+    if Path::new(path).extension() == Some(OsStr::new("json")) {
+        serde_json::from_reader(file).map_err(|err| format!("Unable to parse {path}: {err}"))
+    } else {
+        serde_yaml::from_reader(file).map_err(|err| format!("Unable to parse {path}: {err}"))
+    }
+}
+
+impl InstructionDataSpec {
+    fn decode(self, spec_dir: &Path) -> Result<Vec<u8>, String> {
+        match self {
+            Self {
+                hex: Some(hex),
+                base58: None,
+                base64: None,
+                borsh: None,
+            } => hex::decode(hex).map_err(|err| format!("invalid hex data: {err}")),
+            Self {
+                hex: None,
+                base58: Some(base58),
+                base64: None,
+                borsh: None,
+            } => bs58::decode(base58)
+                .into_vec()
+                .map_err(|err| format!("invalid base58 data: {err}")),
+            Self {
+                hex: None,
+                base58: None,
+                base64: Some(base64),
+                borsh: None,
+            } => BASE64_STANDARD
+                .decode(base64)
+                .map_err(|err| format!("invalid base64 data: {err}")),
+            Self {
+                hex: None,
+                base58: None,
+                base64: None,
+                borsh: Some(borsh),
+            } => {
+                let idl_path = spec_dir.join(&borsh.idl);
+                let idl = fs::read_to_string(&idl_path)
+                    .map_err(|err| err.to_string())
+                    .and_then(|idl| serde_json::from_str(&idl).map_err(|err| err.to_string()))
+                    .map_err(|err| format!("unable to read IDL {}: {err}", idl_path.display()))?;
+                idl_instruction_data(&idl, &borsh.instruction, &borsh.args)
+            }
+            _ => Err("data must have exactly one of hex, base58, base64 or borsh".to_string()),
+        }
+    }
+}
+
+impl TryFrom<AddressLookupTableSpec> for AddressLookupTableSource {
+    type Error = String;
+
+    fn try_from(spec: AddressLookupTableSpec) -> Result<Self, Self::Error> {
+        let parse_address = |address: &str| {
+            Pubkey::from_str(address)
+                .map_err(|err| format!("Invalid lookup table address {address}: {err}"))
+        };
+        match spec {
+            AddressLookupTableSpec::Address(address) => {
+                parse_address(&address).map(AddressLookupTableSource::Address)
+            }
+            AddressLookupTableSpec::Account { address, addresses } => Ok(
+                AddressLookupTableSource::Account(AddressLookupTableAccount {
+                    key: parse_address(&address)?,
+                    addresses: addresses
+                        .iter()
+                        .map(|address| parse_address(address))
+                        .collect::<Result<_, _>>()?,
+                }),
+            ),
+        }
+    }
+}
+
+/// Borsh encodes the `args` of the `instruction` of an Anchor IDL, after its
+/// discriminator.
+fn idl_instruction_data(idl: &Value, instruction: &str, args: &Value) -> Result<Vec<u8>, String> {
+    let idl_instruction = idl["instructions"]
+        .as_array()
+        .and_then(|instructions| instructions.iter().find(|ix| ix["name"] == instruction))
+        .ok_or_else(|| format!("instruction {instruction} not found in the IDL"))?;
+    let mut data = match idl_instruction.get("discriminator") {
+        Some(discriminator) => serde_json::from_value(discriminator.clone())
+            .map_err(|err| format!("invalid discriminator in the IDL: {err}"))?,
+        // IDLs from before Anchor 0.30 leave the discriminator implicit
+        None => {
+            hashv(&[b"global:", to_snake_case(instruction).as_bytes()]).to_bytes()[..8].to_vec()
+        }
+    };
+    for arg in idl_instruction["args"].as_array().into_iter().flatten() {
+        let name = arg["name"]
+            .as_str()
+            .ok_or_else(|| format!("invalid argument of {instruction} in the IDL"))?;
+        let value = args
+            .get(name)
+            .ok_or_else(|| format!("missing argument {name}"))?;
+        encode_idl_value(idl, &arg["type"], value, &mut data)
+            .map_err(|err| format!("argument {name}: {err}"))?;
+    }
+    Ok(data)
+}
+
+fn encode_idl_value(
+    idl: &Value,
+    ty: &Value,
+    value: &Value,
+    data: &mut Vec<u8>,
+) -> Result<(), String> {
+    if let Some(ty) = ty.as_str() {
+        match ty {
+            "bool" => data.push(u8::from(value.as_bool().ok_or("expected a boolean")?)),
+            "u8" => data.extend(parse_idl_number::<u8>(value)?.to_le_bytes()),
+            "i8" => data.extend(parse_idl_number::<i8>(value)?.to_le_bytes()),
+            "u16" => data.extend(parse_idl_number::<u16>(value)?.to_le_bytes()),
+            "i16" => data.extend(parse_idl_number::<i16>(value)?.to_le_bytes()),
+            "u32" => data.extend(parse_idl_number::<u32>(value)?.to_le_bytes()),
+            "i32" => data.extend(parse_idl_number::<i32>(value)?.to_le_bytes()),
+            "u64" => data.extend(parse_idl_number::<u64>(value)?.to_le_bytes()),
+            "i64" => data.extend(parse_idl_number::<i64>(value)?.to_le_bytes()),
+            "u128" => data.extend(parse_idl_number::<u128>(value)?.to_le_bytes()),
+            "i128" => data.extend(parse_idl_number::<i128>(value)?.to_le_bytes()),
+            "f32" => data.extend(parse_idl_number::<f32>(value)?.to_le_bytes()),
+            "f64" => data.extend(parse_idl_number::<f64>(value)?.to_le_bytes()),
+            "string" => {
+                let string = value.as_str().ok_or("expected a string")?;
+                encode_idl_length(string.len(), data)?;
+                data.extend_from_slice(string.as_bytes());
+            }
+            "bytes" => {
+                let bytes: Vec<u8> = serde_json::from_value(value.clone())
+                    .map_err(|_| "expected an array of bytes")?;
+                encode_idl_length(bytes.len(), data)?;
+                data.extend_from_slice(&bytes);
+            }
+            "pubkey" | "publicKey" => {
+                let pubkey = value
+                    .as_str()
+                    .and_then(|pubkey| Pubkey::from_str(pubkey).ok())
+                    .ok_or("expected an address")?;
+                data.extend_from_slice(pubkey.as_ref());
+            }
+            _ => return Err(format!("unsupported type {ty}")),
+        }
+    } else if let Some(inner) = ty.get("option") {
+        if value.is_null() {
+            data.push(0);
+        } else {
+            data.push(1);
+            encode_idl_value(idl, inner, value, data)?;
+        }
+    } else if let Some(inner) = ty.get("vec") {
+        let values = value.as_array().ok_or("expected an array")?;
+        encode_idl_length(values.len(), data)?;
+        for value in values {
+            encode_idl_value(idl, inner, value, data)?;
+        }
+    } else if let Some(array) = ty.get("array") {
+        let values = value.as_array().ok_or("expected an array")?;
+        let length = array[1].as_u64().ok_or("unsupported array length")?;
+        if u64::try_from(values.len()) != Ok(length) {
+            return Err(format!("expected an array of {length} elements"));
+        }
+        for value in values {
+            encode_idl_value(idl, &array[0], value, data)?;
+        }
+    } else if let Some(defined) = ty.get("defined") {
+        // Anchor 0.30 IDLs name defined types in an object, for generics
+        let name = defined
+            .as_str()
+            .or_else(|| defined["name"].as_str())
+            .ok_or("invalid defined type")?;
+        encode_idl_defined(idl, name, value, data).map_err(|err| format!("{name}: {err}"))?;
+    } else {
+        return Err(format!("unsupported type {ty}"));
+    }
+    Ok(())
+}
+
+fn encode_idl_defined(
+    idl: &Value,
+    name: &str,
+    value: &Value,
+    data: &mut Vec<u8>,
+) -> Result<(), String> {
+    let ty = idl["types"]
+        .as_array()
+        .and_then(|types| types.iter().find(|ty| ty["name"] == name))
+        .map(|ty| &ty["type"])
+        .ok_or("type not found in the IDL")?;
+    match ty["kind"].as_str() {
+        Some("struct") => encode_idl_fields(idl, &ty["fields"], value, data),
+        Some("enum") => {
+            // Unit variants are given by name, the others as an object from
+            // their name to their fields
+            let no_fields = Value::Null;
+            let (variant, fields) = match value {
+                Value::String(variant) => (variant, &no_fields),
+                Value::Object(object) if object.len() == 1 => object.iter().next().unwrap(),
+                _ => return Err("expected a variant name or an object with one variant".into()),
+            };
+            let variants = ty["variants"].as_array().ok_or("invalid enum")?;
+            let index = variants
+                .iter()
+                .position(|ty| ty["name"] == *variant)
+                .ok_or_else(|| format!("unknown variant {variant}"))?;
+            data.push(u8::try_from(index).map_err(|_| "too many variants")?);
+            match variants[index].get("fields") {
+                Some(variant_fields) => encode_idl_fields(idl, variant_fields, fields, data),
+                None => Ok(()),
+            }
+        }
+        Some("alias" | "type") => encode_idl_value(idl, &ty["alias"], value, data),
+        _ => Err("unsupported kind of type".to_string()),
+    }
+}
+
+/// Named fields are given as an object, tuple fields as an array.
+fn encode_idl_fields(
+    idl: &Value,
+    fields: &Value,
+    value: &Value,
+    data: &mut Vec<u8>,
+) -> Result<(), String> {
+    let fields = fields.as_array().ok_or("invalid fields")?;
+    for (index, field) in fields.iter().enumerate() {
+        match field["name"].as_str() {
+            Some(name) => {
+                let value = value
+                    .get(name)
+                    .ok_or_else(|| format!("missing field {name}"))?;
+                encode_idl_value(idl, &field["type"], value, data)
+                    .map_err(|err| format!("field {name}: {err}"))?;
+            }
+            None => {
+                let value = value
+                    .get(index)
+                    .ok_or_else(|| format!("missing field {index}"))?;
+                encode_idl_value(idl, field, value, data)
+                    .map_err(|err| format!("field {index}: {err}"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn encode_idl_length(length: usize, data: &mut Vec<u8>) -> Result<(), String> {
+    let length = u32::try_from(length).map_err(|_| "too long")?;
+    data.extend(length.to_le_bytes());
+    Ok(())
+}
+
+/// Numbers may also be given as strings, past the precision of JSON numbers.
+fn parse_idl_number<T: FromStr>(value: &Value) -> Result<T, String> {
+    let number = match value {
+        Value::Number(number) => number.to_string(),
+        Value::String(number) => number.clone(),
+        _ => return Err("expected a number".to_string()),
+    };
+    number
+        .parse()
+        .map_err(|_| format!("{number} is out of range for the type"))
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake_case = String::with_capacity(name.len());
+    for (index, c) in name.char_indices() {
+        if c.is_uppercase() {
+            if index > 0 {
+                snake_case.push('_');
+            }
+            snake_case.extend(c.to_lowercase());
+        } else {
+            snake_case.push(c);
+        }
+    }
+    snake_case
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{clap_app::get_clap_app, cli::parse_command},
+        serde_json::json,
+        solana_hash::Hash,
+        solana_keypair::{write_keypair_file, Keypair},
+        solana_signer::null_signer::NullSigner,
+        solana_system_interface::instruction::transfer,
+    };
+
+    #[test]
+    fn test_parse_command() {
+        let test_commands = get_clap_app("test", "desc", "version");
+        let dir = tempfile::tempdir().unwrap();
+        let default_keypair = Keypair::new();
+        let default_keypair_file = dir.path().join("default.json");
+        write_keypair_file(&default_keypair, &default_keypair_file).unwrap();
+        let default_signer = DefaultSigner::new("", default_keypair_file.to_str().unwrap());
+        let signer_keypair = Keypair::new();
+        let signer_keypair_file = dir.path().join("signer.json");
+        write_keypair_file(&signer_keypair, &signer_keypair_file).unwrap();
+        let program_id = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+
+        // Build from JSON, signed by keypair files and sent
+        let spec_file = dir.path().join("spec.json");
+        fs::write(
+            &spec_file,
+            json!({
+                "instructions": [{
+                    "programId": program_id.to_string(),
+                    "accounts": [
+                        {
+                            "pubkey": signer_keypair_file,
+                            "isSigner": true,
+                            "isWritable": true,
+                        },
+                        { "pubkey": account.to_string() },
+                    ],
+                    "data": { "hex": "0102" },
+                }],
+                "computeUnitLimit": 1000,
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let test_build = test_commands.clone().get_matches_from(vec![
+            "test",
+            "transaction",
+            "build",
+            spec_file.to_str().unwrap(),
+        ]);
+        assert_eq!(
+            parse_command(&test_build, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Transaction(TransactionCliCommand::Build {
+                    instructions: vec![Instruction {
+                        program_id,
+                        accounts: vec![
+                            AccountMeta::new(signer_keypair.pubkey(), true),
+                            AccountMeta::new_readonly(account, false),
+                        ],
+                        data: vec![1, 2],
+                    }],
+                    address_lookup_tables: vec![],
+                    compute_unit_limit: Some(1000),
+                    compute_unit_price: None,
+                    fee_payer: 0,
+                    sign_only: false,
+                    dump_transaction_message: false,
+                    blockhash_query: BlockhashQuery::default(),
+                    nonce_account: None,
+                    nonce_authority: 0,
+                    output_file: None,
+                }),
+                signers: vec![
+                    Box::new(default_keypair.insecure_clone()),
+                    Box::new(signer_keypair.insecure_clone()),
+                ],
+            }
+        );
+
+        // Build from YAML to a file, without the signatures of pubkey signers
+        let lookup_table = Pubkey::new_unique();
+        let spec_file = dir.path().join("spec.yaml");
+        fs::write(
+            &spec_file,
+            format!(
+                "instructions:
+  - programId: {program_id}
+    accounts:
+      - pubkey: {account}
+        isSigner: true
+    data:
+      base58: '2'
+addressLookupTables:
+  - {lookup_table}
+  - address: {lookup_table}
+    addresses: [{account}]
+"
+            ),
+        )
+        .unwrap();
+        let test_build = test_commands.clone().get_matches_from(vec![
+            "test",
+            "transaction",
+            "build",
+            spec_file.to_str().unwrap(),
+            "--output-file",
+            "tx.b64",
+            "--with-compute-unit-price",
+            "99",
+        ]);
+        assert_eq!(
+            parse_command(&test_build, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Transaction(TransactionCliCommand::Build {
+                    instructions: vec![Instruction {
+                        program_id,
+                        accounts: vec![AccountMeta::new_readonly(account, true)],
+                        data: vec![1],
+                    }],
+                    address_lookup_tables: vec![
+                        AddressLookupTableSource::Address(lookup_table),
+                        AddressLookupTableSource::Account(AddressLookupTableAccount {
+                            key: lookup_table,
+                            addresses: vec![account],
+                        }),
+                    ],
+                    compute_unit_limit: None,
+                    compute_unit_price: Some(99),
+                    fee_payer: 0,
+                    sign_only: false,
+                    dump_transaction_message: false,
+                    blockhash_query: BlockhashQuery::default(),
+                    nonce_account: None,
+                    nonce_authority: 0,
+                    output_file: Some("tx.b64".to_string()),
+                }),
+                signers: vec![
+                    Box::new(default_keypair.insecure_clone()),
+                    Box::new(NullSigner::new(&account)),
+                ],
+            }
+        );
+
+        // Pubkey signers need a signature to send
+        let test_build = test_commands.clone().get_matches_from(vec![
+            "test",
+            "transaction",
+            "build",
+            spec_file.to_str().unwrap(),
+        ]);
+        assert!(parse_command(&test_build, &default_signer, &mut None).is_err());
+
+        // Sign with the default signer
+        let test_sign =
+            test_commands
+                .clone()
+                .get_matches_from(vec!["test", "transaction", "sign", "tx.b64"]);
+        assert_eq!(
+            parse_command(&test_sign, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Transaction(TransactionCliCommand::Sign {
+                    transaction_file: "tx.b64".to_string(),
+                    output_file: None,
+                }),
+                signers: vec![Box::new(default_keypair.insecure_clone())],
+            }
+        );
+
+        // Sign with other signers and signatures
+        let signature = signer_keypair.sign_message(&[0u8]);
+        let presigner = format!("{account}={signature}");
+        let test_sign = test_commands.clone().get_matches_from(vec![
+            "test",
+            "transaction",
+            "sign",
+            "tx.b64",
+            signer_keypair_file.to_str().unwrap(),
+            "--signer",
+            &presigner,
+            "--output-file",
+            "signed.b64",
+        ]);
+        assert_eq!(
+            parse_command(&test_sign, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Transaction(TransactionCliCommand::Sign {
+                    transaction_file: "tx.b64".to_string(),
+                    output_file: Some("signed.b64".to_string()),
+                }),
+                signers: vec![
+                    Box::new(Presigner::new(&account, &signature)),
+                    Box::new(signer_keypair.insecure_clone()),
+                ],
+            }
+        );
+
+        // Send
+        let test_send = test_commands.clone().get_matches_from(vec![
+            "test",
+            "transaction",
+            "send",
+            "tx.b64",
+            "--signer",
+            &presigner,
+        ]);
+        assert_eq!(
+            parse_command(&test_send, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Transaction(TransactionCliCommand::Send {
+                    transaction_file: "tx.b64".to_string(),
+                }),
+                signers: vec![Box::new(Presigner::new(&account, &signature))],
+            }
+        );
+    }
+
+    #[test]
+    fn test_instruction_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let decode = |data: Value| {
+            serde_json::from_value::<InstructionDataSpec>(data)
+                .unwrap()
+                .decode(dir.path())
+        };
+        assert_eq!(decode(json!({ "hex": "0aff" })), Ok(vec![10, 255]));
+        assert_eq!(decode(json!({ "base58": "5R" })), Ok(vec![1, 0]));
+        assert_eq!(decode(json!({ "base64": "AQI=" })), Ok(vec![1, 2]));
+        assert!(decode(json!({})).is_err());
+        assert!(decode(json!({ "hex": "00", "base58": "1" })).is_err());
+        assert!(decode(json!({ "hex": "0" })).is_err());
+    }
+
+    #[test]
+    fn test_idl_instruction_data() {
+        let authority = Pubkey::new_unique();
+        let idl = json!({
+            "instructions": [
+                {
+                    "name": "initialize",
+                    "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                    "args": [
+                        { "name": "amount", "type": "u64" },
+                        { "name": "config", "type": { "defined": { "name": "Config" } } },
+                    ],
+                },
+                {
+                    "name": "setData",
+                    "args": [
+                        { "name": "data", "type": { "vec": "i16" } },
+                        { "name": "mode", "type": { "defined": "Mode" } },
+                    ],
+                },
+            ],
+            "types": [
+                {
+                    "name": "Config",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "authority", "type": { "option": "pubkey" } },
+                            { "name": "mode", "type": { "defined": { "name": "Mode" } } },
+                            { "name": "label", "type": "string" },
+                            { "name": "enabled", "type": "bool" },
+                        ],
+                    },
+                },
+                {
+                    "name": "Mode",
+                    "type": {
+                        "kind": "enum",
+                        "variants": [
+                            { "name": "Off" },
+                            { "name": "Limit", "fields": ["u16", { "array": ["u8", 2] }] },
+                        ],
+                    },
+                },
+            ],
+        });
+
+        let mut expected = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        expected.extend(u64::MAX.to_le_bytes());
+        expected.push(1);
+        expected.extend_from_slice(authority.as_ref());
+        expected.extend([1, 7, 0, 8, 9]);
+        expected.extend([2, 0, 0, 0]);
+        expected.extend(b"hi");
+        expected.push(1);
+        assert_eq!(
+            idl_instruction_data(
+                &idl,
+                "initialize",
+                &json!({
+                    "amount": u64::MAX.to_string(),
+                    "config": {
+                        "authority": authority.to_string(),
+                        "mode": { "Limit": [7, [8, 9]] },
+                        "label": "hi",
+                        "enabled": true,
+                    },
+                }),
+            ),
+            Ok(expected)
+        );
+
+        // Discriminators are implicit before Anchor 0.30
+        let mut expected = hashv(&[b"global:set_data".as_slice()]).to_bytes()[..8].to_vec();
+        expected.extend([2, 0, 0, 0, 0xff, 0xff, 2, 0]);
+        expected.push(0);
+        assert_eq!(
+            idl_instruction_data(&idl, "setData", &json!({ "data": [-1, 2], "mode": "Off" })),
+            Ok(expected)
+        );
+
+        assert!(idl_instruction_data(&idl, "close", &json!({})).is_err());
+        assert!(idl_instruction_data(&idl, "setData", &json!({ "data": [] })).is_err());
+        assert!(
+            idl_instruction_data(&idl, "setData", &json!({ "data": [40000], "mode": "Off" }))
+                .is_err()
+        );
+        assert!(
+            idl_instruction_data(&idl, "setData", &json!({ "data": [], "mode": "On" })).is_err()
+        );
+    }
+
+    #[test]
+    fn test_partial_sign_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let transaction_file = dir.path().join("tx.b64");
+        let transaction_file = transaction_file.to_str().unwrap();
+        let fee_payer = Keypair::new();
+        let from = Keypair::new();
+        let message = Message::new_with_blockhash(
+            &[transfer(&from.pubkey(), &Pubkey::new_unique(), 1)],
+            Some(&fee_payer.pubkey()),
+            &Hash::new_unique(),
+        );
+        let mut tx = VersionedTransaction {
+            signatures: vec![Signature::default(); 2],
+            message: VersionedMessage::Legacy(message),
+        };
+
+        try_partial_sign(&mut tx, &[&fee_payer, &NullSigner::new(&from.pubkey())]).unwrap();
+        assert_eq!(tx.verify_with_results(), vec![true, false]);
+        write_transaction_file(transaction_file, &tx).unwrap();
+
+        let mut tx = read_transaction_file(transaction_file).unwrap();
+        assert!(try_partial_sign(&mut tx, &[&Keypair::new()]).is_err());
+        try_partial_sign(&mut tx, &[&NullSigner::new(&fee_payer.pubkey()), &from]).unwrap();
+        assert_eq!(tx.verify_with_results(), vec![true, true]);
+
+        // Signatures from elsewhere are checked
+        let presigner = Presigner::new(&from.pubkey(), &Keypair::new().sign_message(b"other"));
+        assert!(try_partial_sign(&mut tx, &[&presigner]).is_err());
+    }
+}
@@ -0,0 +1,860 @@
+//! Collecting the signatures of a transaction from signers on separate hosts.
+//!
+//! `tx-bundle create` runs any command that supports offline signing in
+//! sign-only mode and writes its unsigned transaction to a bundle file,
+//! instead of printing the signatures. The bundle is then passed around:
+//! each signer adds their signature with `tx-bundle sign`, from a keypair,
+//! a hardware wallet or a signature obtained elsewhere, and copies signed in
+//! parallel are combined with `tx-bundle merge`. `tx-bundle verify` checks
+//! the collected signatures, and `tx-bundle send` broadcasts the transaction
+//! once it is fully signed.
+//!
+//! A bundle is a JSON file with the base64 encoded message of the
+//! transaction, its blockhash, the nonce account it advances, if any, and
+//! the signatures collected so far:
+//!
+//! ```json
+//! {
+//!   "message": "AQABAy...",
+//!   "blockhash": "6nsLr5CoigRiwPbMDxB6dnk6iNdMnmLDrq7Fcd8kQVzK",
+//!   "nonceAccount": "2YcLd9WUpFYXfyVHBf6DtQ5HDcbtT59H4RMFwHkFf6y6",
+//!   "signatures": [
+//!     {
+//!       "pubkey": "EpSVx3kYv5s1ZQALbZiXwFmhe5j6hGVQmb3JmYQHkUPq",
+//!       "signature": "4oRh2w..."
+//!     },
+//!     {
+//!       "pubkey": "9ycJ1ZbJmmhvTKjH4EGQLGLJ6xkqW8p3YadJE6ouomXg",
+//!       "signature": null
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Signers are usually far apart in time as well, so bundles should be
+//! created with a durable nonce: a recent blockhash expires in about a
+//! minute.
+
+use {
+    crate::{
+        clap_app::add_command_subcommands,
+        cli::{
+            parse_command, process_command_with, CliCommand, CliCommandInfo, CliConfig, CliError,
+            ProcessResult,
+        },
+        transaction::{presigner_arg, presigners_of, send_transaction, try_partial_sign},
+    },
+    base64::{prelude::BASE64_STANDARD, Engine},
+    clap::{App, AppSettings, Arg, ArgMatches, SubCommand},
+    serde::{Deserialize, Serialize},
+    solana_clap_utils::{input_validators::*, keypair::*, offline::*},
+    solana_cli_output::{
+        return_versioned_signers_data, return_versioned_signers_with_config, CliSignOnlyData,
+        OutputFormat, ReturnSignersConfig,
+    },
+    solana_hash::Hash,
+    solana_message::VersionedMessage,
+    solana_pubkey::Pubkey,
+    solana_remote_wallet::remote_wallet::RemoteWalletManager,
+    solana_rpc_client::nonblocking::rpc_client::RpcClient,
+    solana_rpc_client_nonce_utils::nonblocking,
+    solana_signature::Signature,
+    solana_system_interface::instruction::SystemInstruction,
+    solana_transaction::versioned::VersionedTransaction,
+    std::{error, fs, future::Future, pin::Pin, rc::Rc, str::FromStr},
+};
+
+#[derive(Debug, PartialEq)]
+pub enum TxBundleCliCommand {
+    Create {
+        /// Run in sign-only mode for its transaction
+        command: Box<CliCommand>,
+        bundle_file: String,
+    },
+    Sign {
+        bundle_file: String,
+    },
+    Merge {
+        bundle_file: String,
+        other_bundle_files: Vec<String>,
+    },
+    Verify {
+        bundle_file: String,
+    },
+    Send {
+        bundle_file: String,
+    },
+}
+
+pub trait TxBundleSubCommands {
+    fn tx_bundle_subcommands(self) -> Self;
+}
+
+impl TxBundleSubCommands for App<'_, '_> {
+    fn tx_bundle_subcommands(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("tx-bundle")
+                .about("Collect the signatures of a transaction from several signers")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .about(
+                            "Write the unsigned transaction of a command to a bundle file, \
+                             instead of sending it",
+                        )
+                        .after_help(
+                            "The command must support offline signing, and is given after \
+                             `--` with its blockhash, preferably that of a durable nonce, \
+                             e.g.:\n  solana tx-bundle create transfer.json -- transfer \
+                             RECIPIENT 10 --from TREASURY_PUBKEY --fee-payer FEE_PAYER_PUBKEY \
+                             --nonce NONCE_ACCOUNT --nonce-authority AUTHORITY_PUBKEY \
+                             --blockhash NONCE_BLOCKHASH\nGlobal arguments, such as the RPC \
+                             URL, go before `tx-bundle` and apply to the command as well.",
+                        )
+                        .arg(bundle_file_arg())
+                        .arg(
+                            Arg::with_name("command")
+                                .value_name("COMMAND")
+                                .takes_value(true)
+                                .multiple(true)
+                                .required(true)
+                                .last(true)
+                                .help("Command to create the transaction with, and its arguments"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("sign")
+                        .about("Add signatures to a bundle")
+                        .arg(bundle_file_arg())
+                        .arg(
+                            Arg::with_name("signers")
+                                .index(2)
+                                .value_name("SIGNER")
+                                .takes_value(true)
+                                .multiple(true)
+                                .validator(is_valid_signer)
+                                .help(
+                                    "Signers to sign the transaction with, e.g. a keypair file \
+                                     or usb://ledger [default: the default configured keypair, \
+                                     unless signatures are provided]",
+                                ),
+                        )
+                        .arg(presigner_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("merge")
+                        .about("Add the signatures of other copies of a bundle to it")
+                        .arg(bundle_file_arg())
+                        .arg(
+                            Arg::with_name("other_bundle_files")
+                                .index(2)
+                                .value_name("OTHER_BUNDLE_FILEPATH")
+                                .takes_value(true)
+                                .multiple(true)
+                                .required(true)
+                                .help("Copies of the bundle signed by other signers"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("verify")
+                        .about("Check the signatures collected in a bundle")
+                        .arg(bundle_file_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("send")
+                        .about("Send the transaction of a fully signed bundle")
+                        .arg(bundle_file_arg()),
+                ),
+        )
+    }
+}
+
+fn bundle_file_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("bundle_file")
+        .index(1)
+        .value_name("BUNDLE_FILEPATH")
+        .takes_value(true)
+        .required(true)
+        .help("Transaction bundle file")
+}
+
+pub fn parse_tx_bundle_subcommand(
+    matches: &ArgMatches<'_>,
+    default_signer: &DefaultSigner,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let (subcommand, sub_matches) = matches.subcommand();
+    let sub_matches = sub_matches.unwrap();
+    let bundle_file = sub_matches.value_of("bundle_file").unwrap().to_string();
+    let response = match subcommand {
+        "create" => {
+            let CliCommandInfo { command, signers } =
+                parse_bundled_command(sub_matches, default_signer, wallet_manager)?;
+            CliCommandInfo {
+                command: CliCommand::TxBundle(TxBundleCliCommand::Create {
+                    command: Box::new(command),
+                    bundle_file,
+                }),
+                signers,
+            }
+        }
+        "sign" => {
+            let mut signers = presigners_of(sub_matches);
+            for path in sub_matches.values_of("signers").into_iter().flatten() {
+                signers.push(signer_from_path(
+                    sub_matches,
+                    path,
+                    "signer",
+                    wallet_manager,
+                )?);
+            }
+            if signers.is_empty() {
+                signers.push(default_signer.signer_from_path(sub_matches, wallet_manager)?);
+            }
+            CliCommandInfo {
+                command: CliCommand::TxBundle(TxBundleCliCommand::Sign { bundle_file }),
+                signers,
+            }
+        }
+        "merge" => {
+            CliCommandInfo::without_signers(CliCommand::TxBundle(TxBundleCliCommand::Merge {
+                bundle_file,
+                other_bundle_files: sub_matches
+                    .values_of("other_bundle_files")
+                    .unwrap()
+                    .map(String::from)
+                    .collect(),
+            }))
+        }
+        "verify" => {
+            CliCommandInfo::without_signers(CliCommand::TxBundle(TxBundleCliCommand::Verify {
+                bundle_file,
+            }))
+        }
+        "send" => CliCommandInfo::without_signers(CliCommand::TxBundle(TxBundleCliCommand::Send {
+            bundle_file,
+        })),
+        _ => unreachable!(),
+    };
+    Ok(response)
+}
+
+/// Parses the command of `tx-bundle create`, in sign-only mode.
+fn parse_bundled_command(
+    matches: &ArgMatches<'_>,
+    default_signer: &DefaultSigner,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let mut args = matches
+        .values_of("command")
+        .unwrap()
+        .map(String::from)
+        .collect::<Vec<_>>();
+    for offline_arg in [SIGN_ONLY_ARG.long, DUMP_TRANSACTION_MESSAGE.long] {
+        let offline_arg = format!("--{offline_arg}");
+        if !args.contains(&offline_arg) {
+            args.push(offline_arg);
+        }
+    }
+    if matches.is_present(SKIP_SEED_PHRASE_VALIDATION_ARG.name) {
+        args.push(format!("--{}", SKIP_SEED_PHRASE_VALIDATION_ARG.long));
+    }
+    // Only the subcommands are parsed, the global arguments of the
+    // `tx-bundle create` invocation apply to the bundled command as well
+    let bundled_command_app = add_command_subcommands(
+        App::new("solana tx-bundle create BUNDLE_FILEPATH --")
+            .setting(AppSettings::NoBinaryName)
+            .setting(AppSettings::SubcommandRequired),
+    )
+    .arg(
+        Arg::with_name(SKIP_SEED_PHRASE_VALIDATION_ARG.name)
+            .long(SKIP_SEED_PHRASE_VALIDATION_ARG.long)
+            .global(true)
+            .hidden(true),
+    );
+    let bundled_matches = bundled_command_app
+        .get_matches_from_safe(args)
+        .map_err(|err| {
+            CliError::BadParameter(format!(
+                "Invalid command to create the bundle with, it must support offline signing \
+                 and global arguments go before `tx-bundle`: {}",
+                err.message
+            ))
+        })?;
+    Ok(parse_command(
+        &bundled_matches,
+        default_signer,
+        wallet_manager,
+    )?)
+}
+
+pub async fn process_tx_bundle_subcommand(
+    rpc_client: &RpcClient,
+    config: &CliConfig<'_>,
+    subcommand: &TxBundleCliCommand,
+) -> ProcessResult {
+    match subcommand {
+        TxBundleCliCommand::Create {
+            command,
+            bundle_file,
+        } => process_create_tx_bundle(config, command, bundle_file).await,
+        TxBundleCliCommand::Sign { bundle_file } => {
+            let mut tx = read_tx_bundle(bundle_file)?;
+            try_partial_sign(&mut tx, &config.signers)?;
+            write_tx_bundle(bundle_file, &tx)?;
+            return_versioned_signers_with_config(
+                &tx,
+                &config.output_format,
+                &ReturnSignersConfig::default(),
+            )
+        }
+        TxBundleCliCommand::Merge {
+            bundle_file,
+            other_bundle_files,
+        } => {
+            let mut tx = read_tx_bundle(bundle_file)?;
+            for other_bundle_file in other_bundle_files {
+                let other_tx = read_tx_bundle(other_bundle_file)?;
+                merge_signatures(&mut tx, &other_tx)
+                    .map_err(|err| format!("Unable to merge {other_bundle_file}: {err}"))?;
+            }
+            write_tx_bundle(bundle_file, &tx)?;
+            return_versioned_signers_with_config(
+                &tx,
+                &config.output_format,
+                &ReturnSignersConfig::default(),
+            )
+        }
+        TxBundleCliCommand::Verify { bundle_file } => {
+            let tx = read_tx_bundle(bundle_file)?;
+            let signers_data = return_versioned_signers_data(
+                &tx,
+                &ReturnSignersConfig {
+                    dump_transaction_message: true,
+                },
+            );
+            if !signers_data.bad_sig.is_empty() {
+                return Err(format!(
+                    "The bundle has invalid signatures from {}",
+                    signers_data.bad_sig.join(", ")
+                )
+                .into());
+            }
+            Ok(config.output_format.formatted_string(&signers_data))
+        }
+        TxBundleCliCommand::Send { bundle_file } => {
+            let tx = read_tx_bundle(bundle_file)?;
+            let blockhash = tx.message.recent_blockhash();
+            match nonce_account_of(&tx) {
+                Some(nonce_account) => {
+                    let nonce_data = nonblocking::get_account_with_commitment(
+                        rpc_client,
+                        &nonce_account,
+                        config.commitment,
+                    )
+                    .await
+                    .and_then(|ref account| nonblocking::data_from_account(account))?;
+                    if nonce_data.blockhash() != *blockhash {
+                        return Err(format!(
+                            "Nonce account {nonce_account} has advanced since the bundle was \
+                             created"
+                        )
+                        .into());
+                    }
+                }
+                None => {
+                    if !rpc_client
+                        .is_blockhash_valid(blockhash, config.commitment)
+                        .await?
+                    {
+                        return Err(format!(
+                            "Blockhash {blockhash} of the bundle has expired, create it with \
+                             a durable nonce instead"
+                        )
+                        .into());
+                    }
+                }
+            }
+            send_transaction(rpc_client, config, &tx).await
+        }
+    }
+}
+
+async fn process_create_tx_bundle(
+    config: &CliConfig<'_>,
+    command: &CliCommand,
+    bundle_file: &str,
+) -> ProcessResult {
+    let sign_only_config = CliConfig {
+        json_rpc_url: config.json_rpc_url.clone(),
+        websocket_url: config.websocket_url.clone(),
+        keypair_path: config.keypair_path.clone(),
+        commitment: config.commitment,
+        signers: config.signers.clone(),
+        rpc_client: config.rpc_client.clone(),
+        rpc_timeout: config.rpc_timeout,
+        output_format: OutputFormat::Json,
+        send_transaction_config: config.send_transaction_config,
+        confirm_transaction_initial_timeout: config.confirm_transaction_initial_timeout,
+        // Unused, the bundled command is processed instead
+        command: CliCommand::ClusterVersion,
+        verbose: config.verbose,
+        address_labels: config.address_labels.clone(),
+        use_tpu_client: config.use_tpu_client,
+        auto_compute_budget: config.auto_compute_budget,
+    };
+    // Boxed as this runs within the processing of another command
+    let process: Pin<Box<dyn Future<Output = ProcessResult> + '_>> =
+        Box::pin(process_command_with(&sign_only_config, command));
+    let reply = process.await?;
+    let signers_data = serde_json::from_str::<CliSignOnlyData>(&reply)
+        .map_err(|_| "The command did not return a transaction to sign")?;
+    let tx = tx_from_signers_data(&signers_data)?;
+    write_tx_bundle(bundle_file, &tx)?;
+    return_versioned_signers_with_config(
+        &tx,
+        &config.output_format,
+        &ReturnSignersConfig::default(),
+    )
+}
+
+/// Rebuilds the transaction of a sign-only reply with its message.
+fn tx_from_signers_data(
+    signers_data: &CliSignOnlyData,
+) -> Result<VersionedTransaction, Box<dyn error::Error>> {
+    let message = signers_data
+        .message
+        .as_ref()
+        .ok_or("The command did not return the message of its transaction")?;
+    let message = decode_message(message)?;
+    let mut signatures = vec![Signature::default(); required_signers(&message).len()];
+    for signer in &signers_data.signers {
+        let (pubkey, signature) = signer
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid signer {signer}"))?;
+        let position = required_signers(&message)
+            .iter()
+            .position(|signer_pubkey| signer_pubkey.to_string() == pubkey)
+            .ok_or_else(|| format!("{pubkey} is not a signer of the transaction"))?;
+        signatures[position] = Signature::from_str(signature)?;
+    }
+    Ok(VersionedTransaction {
+        signatures,
+        message,
+    })
+}
+
+/// Adds the valid signatures of `other_tx` that `tx` is missing.
+fn merge_signatures(
+    tx: &mut VersionedTransaction,
+    other_tx: &VersionedTransaction,
+) -> Result<(), Box<dyn error::Error>> {
+    if tx.message != other_tx.message {
+        return Err("the bundles are of different transactions".into());
+    }
+    let verify_results = tx.verify_with_results();
+    let other_verify_results = other_tx.verify_with_results();
+    for (position, (is_valid, other_is_valid)) in verify_results
+        .into_iter()
+        .zip(other_verify_results)
+        .enumerate()
+    {
+        if !is_valid && other_is_valid {
+            tx.signatures[position] = other_tx.signatures[position];
+        }
+    }
+    Ok(())
+}
+
+/// The nonce account advanced by the transaction, if it uses a durable
+/// nonce.
+fn nonce_account_of(tx: &VersionedTransaction) -> Option<Pubkey> {
+    let account_keys = tx.message.static_account_keys();
+    let instruction = tx.message.instructions().first()?;
+    let program_id = account_keys.get(usize::from(instruction.program_id_index))?;
+    let is_advance_nonce = solana_sdk_ids::system_program::check_id(program_id)
+        && matches!(
+            bincode::deserialize::<SystemInstruction>(&instruction.data),
+            Ok(SystemInstruction::AdvanceNonceAccount)
+        );
+    if !is_advance_nonce {
+        return None;
+    }
+    account_keys
+        .get(usize::from(*instruction.accounts.first()?))
+        .copied()
+}
+
+fn required_signers(message: &VersionedMessage) -> &[Pubkey] {
+    let num_required_signatures = usize::from(message.header().num_required_signatures);
+    let account_keys = message.static_account_keys();
+    &account_keys[..num_required_signatures.min(account_keys.len())]
+}
+
+fn decode_message(message: &str) -> Result<VersionedMessage, Box<dyn error::Error>> {
+    let message = BASE64_STANDARD.decode(message)?;
+    Ok(bincode::deserialize(&message)?)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TxBundleFile {
+    message: String,
+    blockhash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nonce_account: Option<String>,
+    signatures: Vec<TxBundleSignature>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TxBundleSignature {
+    pubkey: String,
+    /// Absent until signed
+    signature: Option<String>,
+}
+
+/// Reads the transaction of a bundle file, with the signatures collected so
+/// far. Signatures that are absent are default.
+pub fn read_tx_bundle(path: &str) -> Result<VersionedTransaction, Box<dyn error::Error>> {
+    let bundle = fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|bundle| {
+            serde_json::from_str::<TxBundleFile>(&bundle).map_err(|err| err.to_string())
+        })
+        .map_err(|err| format!("Unable to read bundle {path}: {err}"))?;
+    let invalid_bundle = |err: &str| format!("Invalid bundle {path}: {err}");
+    let message = decode_message(&bundle.message)
+        .map_err(|err| invalid_bundle(&format!("invalid message: {err}")))?;
+    // The other fields are for the eyes of the signers, who must not be
+    // misled by them
+    if Hash::from_str(&bundle.blockhash).ok().as_ref() != Some(message.recent_blockhash()) {
+        return Err(invalid_bundle("the blockhash is not that of the message").into());
+    }
+    let signers = required_signers(&message);
+    if bundle.signatures.len() != signers.len()
+        || bundle
+            .signatures
+            .iter()
+            .zip(signers)
+            .any(|(signature, signer)| signature.pubkey != signer.to_string())
+    {
+        return Err(invalid_bundle("the signers are not those of the message").into());
+    }
+    let signatures = bundle
+        .signatures
+        .iter()
+        .map(|signature| {
+            signature
+                .signature
+                .as_deref()
+                .map(Signature::from_str)
+                .transpose()
+                .map(Option::unwrap_or_default)
+                .map_err(|err| invalid_bundle(&format!("invalid signature: {err}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let tx = VersionedTransaction {
+        signatures,
+        message,
+    };
+    if bundle.nonce_account != nonce_account_of(&tx).map(|pubkey| pubkey.to_string()) {
+        return Err(invalid_bundle("the nonce account is not that of the message").into());
+    }
+    Ok(tx)
+}
+
+pub fn write_tx_bundle(path: &str, tx: &VersionedTransaction) -> Result<(), Box<dyn error::Error>> {
+    let bundle = TxBundleFile {
+        message: BASE64_STANDARD.encode(tx.message.serialize()),
+        blockhash: tx.message.recent_blockhash().to_string(),
+        nonce_account: nonce_account_of(tx).map(|pubkey| pubkey.to_string()),
+        signatures: tx
+            .signatures
+            .iter()
+            .zip(required_signers(&tx.message))
+            .map(|(signature, pubkey)| TxBundleSignature {
+                pubkey: pubkey.to_string(),
+                signature: (*signature != Signature::default()).then(|| signature.to_string()),
+            })
+            .collect(),
+    };
+    let bundle = serde_json::to_string_pretty(&bundle)?;
+    fs::write(path, format!("{bundle}\n"))
+        .map_err(|err| format!("Unable to write {path}: {err}"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{clap_app::get_clap_app, spend_utils::SpendAmount},
+        solana_keypair::{write_keypair_file, Keypair},
+        solana_message::Message,
+        solana_presigner::Presigner,
+        solana_rpc_client_nonce_utils::nonblocking::blockhash_query::BlockhashQuery,
+        solana_signer::{null_signer::NullSigner, Signer},
+        solana_system_interface::instruction as system_instruction,
+    };
+
+    #[test]
+    fn test_parse_command() {
+        let test_commands = get_clap_app("test", "desc", "version");
+        let dir = tempfile::tempdir().unwrap();
+        let default_keypair = Keypair::new();
+        let default_keypair_file = dir.path().join("default.json");
+        write_keypair_file(&default_keypair, &default_keypair_file).unwrap();
+        let default_signer = DefaultSigner::new("", default_keypair_file.to_str().unwrap());
+        let signer_keypair = Keypair::new();
+        let signer_keypair_file = dir.path().join("signer.json");
+        write_keypair_file(&signer_keypair, &signer_keypair_file).unwrap();
+        let treasury = Pubkey::new_unique().to_string();
+        let to = Pubkey::new_unique().to_string();
+        let blockhash = Hash::new_unique().to_string();
+
+        // Create, from a command run in sign-only mode
+        let test_create = test_commands.clone().get_matches_from(vec![
+            "test",
+            "tx-bundle",
+            "create",
+            "bundle.json",
+            "--",
+            "transfer",
+            &to,
+            "42",
+            "--from",
+            &treasury,
+            "--blockhash",
+            &blockhash,
+        ]);
+        assert_eq!(
+            parse_command(&test_create, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::TxBundle(TxBundleCliCommand::Create {
+                    command: Box::new(CliCommand::Transfer {
+                        amount: SpendAmount::Some(42_000_000_000),
+                        to: to.parse().unwrap(),
+                        from: 1,
+                        sign_only: true,
+                        dump_transaction_message: true,
+                        allow_unfunded_recipient: false,
+                        no_wait: false,
+                        blockhash_query: BlockhashQuery::Static(blockhash.parse().unwrap()),
+                        nonce_account: None,
+                        nonce_authority: 0,
+                        memo: None,
+                        fee_payer: 0,
+                        derived_address_seed: None,
+                        derived_address_program_id: None,
+                        compute_unit_price: None,
+                    }),
+                    bundle_file: "bundle.json".to_string(),
+                }),
+                signers: vec![
+                    Box::new(default_keypair.insecure_clone()),
+                    Box::new(NullSigner::new(&treasury.parse().unwrap())),
+                ],
+            }
+        );
+
+        // The command needs a blockhash, must support offline signing and
+        // takes no global arguments
+        for command in [
+            vec!["transfer", &to, "42"],
+            vec![
+                "transfer",
+                &to,
+                "42",
+                "--blockhash",
+                &blockhash,
+                "--url",
+                "devnet",
+            ],
+            vec!["balance", "--blockhash", &blockhash],
+            vec!["tx-bundle", "verify", "bundle.json"],
+        ] {
+            let test_create = test_commands.clone().get_matches_from(
+                ["test", "tx-bundle", "create", "bundle.json", "--"]
+                    .into_iter()
+                    .chain(command),
+            );
+            assert!(parse_command(&test_create, &default_signer, &mut None).is_err());
+        }
+
+        // Sign with the default signer
+        let test_sign = test_commands.clone().get_matches_from(vec![
+            "test",
+            "tx-bundle",
+            "sign",
+            "bundle.json",
+        ]);
+        assert_eq!(
+            parse_command(&test_sign, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::TxBundle(TxBundleCliCommand::Sign {
+                    bundle_file: "bundle.json".to_string(),
+                }),
+                signers: vec![Box::new(default_keypair.insecure_clone())],
+            }
+        );
+
+        // Sign with other signers and signatures
+        let treasury_signature = signer_keypair.sign_message(&[0u8]);
+        let treasury_presigner = format!("{treasury}={treasury_signature}");
+        let test_sign = test_commands.clone().get_matches_from(vec![
+            "test",
+            "tx-bundle",
+            "sign",
+            "bundle.json",
+            signer_keypair_file.to_str().unwrap(),
+            "--signer",
+            &treasury_presigner,
+        ]);
+        assert_eq!(
+            parse_command(&test_sign, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::TxBundle(TxBundleCliCommand::Sign {
+                    bundle_file: "bundle.json".to_string(),
+                }),
+                signers: vec![
+                    Box::new(Presigner::new(
+                        &treasury.parse().unwrap(),
+                        &treasury_signature
+                    )),
+                    Box::new(signer_keypair.insecure_clone()),
+                ],
+            }
+        );
+
+        // Merge, verify and send
+        let test_merge = test_commands.clone().get_matches_from(vec![
+            "test",
+            "tx-bundle",
+            "merge",
+            "bundle.json",
+            "bundle-a.json",
+            "bundle-b.json",
+        ]);
+        assert_eq!(
+            parse_command(&test_merge, &default_signer, &mut None).unwrap(),
+            CliCommandInfo::without_signers(CliCommand::TxBundle(TxBundleCliCommand::Merge {
+                bundle_file: "bundle.json".to_string(),
+                other_bundle_files: vec!["bundle-a.json".to_string(), "bundle-b.json".to_string()],
+            }))
+        );
+        let test_verify = test_commands.clone().get_matches_from(vec![
+            "test",
+            "tx-bundle",
+            "verify",
+            "bundle.json",
+        ]);
+        assert_eq!(
+            parse_command(&test_verify, &default_signer, &mut None).unwrap(),
+            CliCommandInfo::without_signers(CliCommand::TxBundle(TxBundleCliCommand::Verify {
+                bundle_file: "bundle.json".to_string(),
+            }))
+        );
+        let test_send = test_commands.clone().get_matches_from(vec![
+            "test",
+            "tx-bundle",
+            "send",
+            "bundle.json",
+        ]);
+        assert_eq!(
+            parse_command(&test_send, &default_signer, &mut None).unwrap(),
+            CliCommandInfo::without_signers(CliCommand::TxBundle(TxBundleCliCommand::Send {
+                bundle_file: "bundle.json".to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_tx_bundle_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle_file = dir.path().join("bundle.json");
+        let bundle_file = bundle_file.to_str().unwrap();
+        let fee_payer = Keypair::new();
+        let treasury = Keypair::new();
+        let nonce_account = Pubkey::new_unique();
+        let message = Message::new_with_blockhash(
+            &[
+                system_instruction::advance_nonce_account(&nonce_account, &fee_payer.pubkey()),
+                system_instruction::transfer(&treasury.pubkey(), &Pubkey::new_unique(), 1),
+            ],
+            Some(&fee_payer.pubkey()),
+            &Hash::new_unique(),
+        );
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::default(); 2],
+            message: VersionedMessage::Legacy(message),
+        };
+        assert_eq!(nonce_account_of(&tx), Some(nonce_account));
+
+        write_tx_bundle(bundle_file, &tx).unwrap();
+        let bundle: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(bundle_file).unwrap()).unwrap();
+        assert_eq!(bundle["nonceAccount"], nonce_account.to_string());
+        assert_eq!(
+            bundle["signatures"][1]["pubkey"],
+            treasury.pubkey().to_string()
+        );
+        assert!(bundle["signatures"][1]["signature"].is_null());
+        assert_eq!(read_tx_bundle(bundle_file).unwrap(), tx);
+
+        // Signed independently, then merged
+        let mut fee_payer_tx = tx.clone();
+        try_partial_sign(&mut fee_payer_tx, &[&fee_payer]).unwrap();
+        let mut treasury_tx = tx.clone();
+        try_partial_sign(&mut treasury_tx, &[&treasury]).unwrap();
+        let mut merged_tx = tx.clone();
+        merge_signatures(&mut merged_tx, &fee_payer_tx).unwrap();
+        merge_signatures(&mut merged_tx, &treasury_tx).unwrap();
+        assert_eq!(merged_tx.verify_with_results(), vec![true, true]);
+        write_tx_bundle(bundle_file, &merged_tx).unwrap();
+        assert_eq!(read_tx_bundle(bundle_file).unwrap(), merged_tx);
+
+        // Invalid signatures are not merged
+        let mut bad_tx = tx.clone();
+        bad_tx.signatures[1] = treasury.sign_message(b"other");
+        let mut merged_tx = fee_payer_tx.clone();
+        merge_signatures(&mut merged_tx, &bad_tx).unwrap();
+        assert_eq!(merged_tx, fee_payer_tx);
+
+        // Only copies of the same transaction are merged
+        let mut other_tx = tx.clone();
+        if let VersionedMessage::Legacy(message) = &mut other_tx.message {
+            message.recent_blockhash = Hash::new_unique();
+        }
+        assert!(merge_signatures(&mut merged_tx, &other_tx).is_err());
+
+        // Sign-only replies rebuild the same transaction
+        let signers_data = return_versioned_signers_data(
+            &fee_payer_tx,
+            &ReturnSignersConfig {
+                dump_transaction_message: true,
+            },
+        );
+        assert_eq!(tx_from_signers_data(&signers_data).unwrap(), fee_payer_tx);
+        let signers_data = return_versioned_signers_data(&tx, &ReturnSignersConfig::default());
+        assert!(tx_from_signers_data(&signers_data).is_err());
+
+        // The summary of the bundle must match its message
+        for (field, value) in [
+            (
+                "blockhash",
+                serde_json::json!(Hash::new_unique().to_string()),
+            ),
+            ("nonceAccount", serde_json::Value::Null),
+            (
+                "signatures",
+                serde_json::json!([bundle["signatures"][1], bundle["signatures"][0]]),
+            ),
+        ] {
+            let mut tampered_bundle = bundle.clone();
+            tampered_bundle[field] = value;
+            fs::write(bundle_file, tampered_bundle.to_string()).unwrap();
+            assert!(read_tx_bundle(bundle_file).is_err());
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use {
     crate::{
         cli::{CliCommand, CliCommandInfo, CliConfig, CliError, ProcessResult},
-        compute_budget::{ComputeUnitConfig, WithComputeUnitConfig},
+        compute_budget::{ComputeUnitConfig, SendWithComputeBudget, WithComputeUnitConfig},
         spend_utils::{resolve_spend_tx_and_check_account_balance, SpendAmount},
     },
     bincode::{deserialize, serialized_size},
@@ -412,15 +412,15 @@ pub async fn process_set_validator_info(
         &config.signers[0].pubkey(),
         compute_unit_limit,
         build_message,
-        config.commitment,
+        config,
     )
     .await?;
     let mut tx = Transaction::new_unsigned(message);
     tx.try_sign(&signers, latest_blockhash)?;
     let signature_str = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await?;
@@ -6,7 +6,8 @@ use {
             ProcessResult,
         },
         compute_budget::{
-            simulate_and_update_compute_unit_limit, ComputeUnitConfig, WithComputeUnitConfig,
+            simulate_and_update_compute_unit_limit, ComputeUnitConfig, SendWithComputeBudget,
+            WithComputeUnitConfig,
         },
         feature::get_feature_is_active,
         memo::WithMemo,
@@ -1103,7 +1104,7 @@ pub async fn process_create_vote_account(
         &fee_payer.pubkey(),
         compute_unit_limit,
         build_message,
-        config.commitment,
+        config,
     )
     .await?;
 
@@ -1149,9 +1150,9 @@ pub async fn process_create_vote_account(
     } else {
         tx.try_sign(&config.signers, recent_blockhash)?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
@@ -1368,9 +1369,9 @@ pub async fn process_vote_authorize(
         )
         .await?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
@@ -1463,9 +1464,9 @@ pub async fn process_vote_update_validator(
         )
         .await?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
@@ -1551,9 +1552,9 @@ pub async fn process_vote_update_commission(
         )
         .await?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
@@ -1750,7 +1751,7 @@ pub async fn process_withdraw_from_vote_account(
         &fee_payer.pubkey(),
         compute_unit_limit,
         build_message,
-        config.commitment,
+        config,
     )
     .await?;
 
@@ -1803,9 +1804,9 @@ pub async fn process_withdraw_from_vote_account(
         )
         .await?;
         let result = rpc_client
-            .send_and_confirm_transaction_with_spinner_and_config(
+            .send_and_confirm_transaction_with_compute_budget(
                 &tx,
-                config.commitment,
+                config,
                 config.send_transaction_config,
             )
             .await;
@@ -1875,9 +1876,9 @@ pub async fn process_close_vote_account(
     )
     .await?;
     let result = rpc_client
-        .send_and_confirm_transaction_with_spinner_and_config(
+        .send_and_confirm_transaction_with_compute_budget(
             &tx,
-            config.commitment,
+            config,
             config.send_transaction_config,
         )
         .await;
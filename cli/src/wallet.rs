@@ -4,7 +4,7 @@ use {
             log_instruction_custom_error, request_and_confirm_airdrop, CliCommand, CliCommandInfo,
             CliConfig, CliError, ProcessResult,
         },
-        compute_budget::{ComputeUnitConfig, WithComputeUnitConfig},
+        compute_budget::{ComputeUnitConfig, SendWithComputeBudget, WithComputeUnitConfig},
         memo::WithMemo,
         nonce::check_nonce_account,
         spend_utils::{resolve_spend_tx_and_check_account_balances, SpendAmount},
@@ -969,7 +969,7 @@ pub async fn process_transfer(
         &fee_payer.pubkey(),
         compute_unit_limit,
         build_message,
-        config.commitment,
+        config,
     )
     .await?;
     let mut tx = Transaction::new_unsigned(message);
@@ -998,13 +998,13 @@ pub async fn process_transfer(
         tx.try_sign(&config.signers, recent_blockhash)?;
         let result = if no_wait {
             rpc_client
-                .send_transaction_with_config(&tx, config.send_transaction_config)
+                .send_transaction_with_compute_budget(&tx, config, config.send_transaction_config)
                 .await
         } else {
             rpc_client
-                .send_and_confirm_transaction_with_spinner_and_config(
+                .send_and_confirm_transaction_with_compute_budget(
                     &tx,
-                    config.commitment,
+                    config,
                     config.send_transaction_config,
                 )
                 .await
@@ -20,8 +20,16 @@ use {
     solana_compute_budget_interface::ComputeBudgetInstruction,
     solana_faucet::faucet::run_local_faucet_with_unique_port_for_tests,
     solana_fee_calculator::FeeRateGovernor,
+    solana_instruction::Instruction,
     solana_keypair::Keypair,
-    solana_loader_v3_interface::state::UpgradeableLoaderState,
+    solana_loader_v3_interface::{
+        instruction::{self as loader_v3_instruction, UpgradeableLoaderInstruction},
+        state::UpgradeableLoaderState,
+    },
+    solana_loader_v4_interface::{
+        instruction::{self as loader_v4_instruction, LoaderV4Instruction},
+        state::LoaderV4State,
+    },
     solana_net_utils::SocketAddrSpace,
     solana_pubkey::Pubkey,
     solana_rent::Rent,
@@ -42,6 +50,7 @@ use {
         env,
         fs::File,
         io::{Read, Seek, SeekFrom},
+        ops::Range,
         path::{Path, PathBuf},
         str::FromStr,
         sync::Arc,
@@ -162,6 +171,7 @@ async fn test_cli_program_deploy_non_upgradeable() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     let response = process_command(&config).await;
@@ -212,6 +222,7 @@ async fn test_cli_program_deploy_non_upgradeable() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     process_command(&config).await.unwrap();
     let account1 = rpc_client
@@ -273,6 +284,7 @@ async fn test_cli_program_deploy_non_upgradeable() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     expect_command_failure(
         &config,
@@ -301,6 +313,7 @@ async fn test_cli_program_deploy_non_upgradeable() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     expect_command_failure(
         &config,
@@ -376,6 +389,7 @@ async fn test_cli_program_deploy_no_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     let response = process_command(&config).await;
@@ -407,6 +421,7 @@ async fn test_cli_program_deploy_no_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     expect_command_failure(
         &config,
@@ -489,6 +504,7 @@ async fn test_cli_program_deploy_feature(enable_feature: bool, skip_preflight: b
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: false,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
 
@@ -500,7 +516,8 @@ async fn test_cli_program_deploy_feature(enable_feature: bool, skip_preflight: b
             &config,
             "Program contains a syscall from a deactivated feature",
             "ELF error: ELF error: Unresolved symbol (sol_alt_bn128_group_op) at instruction #49 \
-             (ELF file offset 0x188)",
+             (ELF file offset 0x188): the syscall sol_alt_bn128_group_op is not active on the \
+             cluster",
         )
         .await;
 
@@ -521,6 +538,7 @@ async fn test_cli_program_deploy_feature(enable_feature: bool, skip_preflight: b
             auto_extend: true,
             use_rpc: false,
             skip_feature_verification: true,
+            build_metadata_location: None,
         });
 
         // When we skip verification, we fail at a later stage
@@ -629,6 +647,7 @@ async fn test_cli_program_upgrade_with_feature(enable_feature: bool) {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: false,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     process_command(&config).await.unwrap();
@@ -682,7 +701,8 @@ async fn test_cli_program_upgrade_with_feature(enable_feature: bool) {
             "Program contains a syscall to a disabled feature",
             format!(
                 "Buffer account {} has invalid program data: \"ELF error: ELF error: Unresolved \
-                 symbol (sol_alt_bn128_group_op) at instruction #49 (ELF file offset 0x188)\"",
+                 symbol (sol_alt_bn128_group_op) at instruction #49 (ELF file offset 0x188): the \
+                 syscall sol_alt_bn128_group_op is not active on the cluster\"",
                 buffer_signer.pubkey()
             )
             .as_str(),
@@ -774,6 +794,7 @@ async fn test_cli_program_deploy_with_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     let response = process_command(&config).await;
@@ -830,6 +851,7 @@ async fn test_cli_program_deploy_with_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     let response = process_command(&config).await;
     let json: Value = serde_json::from_str(&response.unwrap()).unwrap();
@@ -877,6 +899,7 @@ async fn test_cli_program_deploy_with_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     process_command(&config).await.unwrap();
     let program_account = rpc_client.get_account(&program_pubkey).await.unwrap();
@@ -956,6 +979,7 @@ async fn test_cli_program_deploy_with_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     process_command(&config).await.unwrap();
     let program_account = rpc_client.get_account(&program_pubkey).await.unwrap();
@@ -1039,6 +1063,7 @@ async fn test_cli_program_deploy_with_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     expect_command_failure(
         &config,
@@ -1065,6 +1090,7 @@ async fn test_cli_program_deploy_with_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     let response = process_command(&config).await;
     let json: Value = serde_json::from_str(&response.unwrap()).unwrap();
@@ -1188,6 +1214,7 @@ async fn test_cli_program_upgrade_auto_extend(skip_preflight: bool) {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     config.send_transaction_config.skip_preflight = skip_preflight;
@@ -1212,6 +1239,7 @@ async fn test_cli_program_upgrade_auto_extend(skip_preflight: bool) {
         auto_extend: false, // --no-auto-extend flag is present
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     if skip_preflight {
         expect_command_failure(
@@ -1260,6 +1288,7 @@ async fn test_cli_program_upgrade_auto_extend(skip_preflight: bool) {
         auto_extend: true, // --no-auto-extend flag is absent
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     let response = process_command(&config).await;
     let json: Value = serde_json::from_str(&response.unwrap()).unwrap();
@@ -1352,6 +1381,7 @@ async fn test_cli_program_close_program() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     process_command(&config).await.unwrap();
@@ -1479,6 +1509,7 @@ async fn test_cli_program_extend_program() {
         auto_extend: false,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     process_command(&config).await.unwrap();
@@ -1531,6 +1562,7 @@ async fn test_cli_program_extend_program() {
         auto_extend: false,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
 
     #[rustfmt::skip]
@@ -1585,6 +1617,7 @@ async fn test_cli_program_extend_program() {
         auto_extend: false,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     process_command(&config).await.unwrap();
 }
@@ -1652,6 +1685,7 @@ async fn test_cli_program_migrate_program() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     process_command(&config).await.unwrap();
@@ -2044,6 +2078,7 @@ async fn test_cli_program_write_buffer() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     let buffer_account_len = {
@@ -2138,7 +2173,8 @@ async fn test_cli_program_write_buffer_feature(enable_feature: bool) {
             &config,
             "Program contains a syscall from a deactivated feature",
             "ELF error: ELF error: Unresolved symbol (sol_alt_bn128_group_op) at instruction #49 \
-             (ELF file offset 0x188)",
+             (ELF file offset 0x188): the syscall sol_alt_bn128_group_op is not active on the \
+             cluster",
         )
         .await;
 
@@ -2279,6 +2315,7 @@ async fn test_cli_program_set_buffer_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     expect_command_failure(
@@ -2340,6 +2377,7 @@ async fn test_cli_program_set_buffer_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     process_command(&config).await.unwrap();
@@ -2431,6 +2469,7 @@ async fn test_cli_program_mismatch_buffer_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     expect_command_failure(
         &config,
@@ -2461,6 +2500,7 @@ async fn test_cli_program_mismatch_buffer_authority() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     process_command(&config).await.unwrap();
 }
@@ -2548,6 +2588,7 @@ async fn test_cli_program_deploy_with_offline_signing(use_offline_signer_as_fee_
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     process_command(&config).await.unwrap();
@@ -2793,6 +2834,7 @@ async fn test_cli_program_show() {
         auto_extend: true,
         use_rpc: false,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     let min_slot = rpc_client.get_slot().await.unwrap();
@@ -2950,6 +2992,154 @@ async fn test_cli_program_dump() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cli_program_deploy_with_build_metadata() {
+    agave_logger::setup();
+
+    let mut noop_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    noop_path.push("tests");
+    noop_path.push("fixtures");
+    noop_path.push("noop");
+    noop_path.set_extension("so");
+
+    let mint_keypair = Keypair::new();
+    let test_validator = test_validator_genesis(&mint_keypair)
+        .start_async_with_mint_address(&mint_keypair, SocketAddrSpace::Unspecified)
+        .await
+        .expect("validator start failed");
+
+    let mut config = CliConfig::recent_for_tests();
+    config.json_rpc_url = test_validator.rpc_url();
+    let rpc_client = setup_rpc_client(&mut config);
+
+    let minimum_balance_for_programdata = rpc_client
+        .get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_programdata(
+            File::open(&noop_path).unwrap().metadata().unwrap().len() as usize,
+        ))
+        .await
+        .unwrap();
+
+    let keypair = Keypair::new();
+    config.signers = vec![&keypair];
+    config.command = CliCommand::Airdrop {
+        pubkey: None,
+        lamports: 10 * minimum_balance_for_programdata,
+    };
+    process_command(&config).await.unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let build_metadata_path = dir.path().join("noop-build-metadata.json");
+    std::fs::write(
+        &build_metadata_path,
+        r#"{"repository":"https://github.com/user/noop","commit":"0123abc"}"#,
+    )
+    .unwrap();
+
+    // Deploy the program with its build metadata
+    let program_keypair = Keypair::new();
+    config.signers = vec![&keypair, &program_keypair];
+    config.command = CliCommand::Program(ProgramCliCommand::Deploy {
+        program_location: Some(noop_path.to_str().unwrap().to_string()),
+        fee_payer_signer_index: 0,
+        program_signer_index: Some(1),
+        program_pubkey: Some(program_keypair.pubkey()),
+        buffer_signer_index: None,
+        buffer_pubkey: None,
+        upgrade_authority_signer_index: 0,
+        is_final: false,
+        max_len: None,
+        skip_fee_check: false,
+        compute_unit_price: None,
+        max_sign_attempts: 5,
+        auto_extend: true,
+        use_rpc: false,
+        skip_feature_verification: true,
+        build_metadata_location: Some(build_metadata_path.to_str().unwrap().to_string()),
+    });
+    config.output_format = OutputFormat::JsonCompact;
+    process_command(&config).await.unwrap();
+
+    // Show verifies the build metadata against the deployed program
+    config.signers = vec![&keypair];
+    config.command = CliCommand::Program(ProgramCliCommand::Show {
+        account_pubkey: Some(program_keypair.pubkey()),
+        authority_pubkey: keypair.pubkey(),
+        get_programs: false,
+        get_buffers: false,
+        all: false,
+        use_lamports_unit: false,
+    });
+    let response = process_command(&config).await;
+    let json: Value = serde_json::from_str(&response.unwrap()).unwrap();
+    let build_metadata = json.get("buildMetadata").unwrap();
+    assert_eq!(
+        build_metadata.get("repository").unwrap().as_str().unwrap(),
+        "https://github.com/user/noop"
+    );
+    assert_eq!(
+        build_metadata.get("authority").unwrap().as_str().unwrap(),
+        keypair.pubkey().to_string()
+    );
+    assert!(build_metadata.get("verified").unwrap().as_bool().unwrap());
+
+    // Updating the build metadata requires its authority, but not the program keypair
+    std::fs::write(
+        &build_metadata_path,
+        r#"{"repository":"https://github.com/user/noop","commit":"4567def"}"#,
+    )
+    .unwrap();
+    config.signers = vec![&keypair];
+    config.command = CliCommand::Program(ProgramCliCommand::Deploy {
+        program_location: Some(noop_path.to_str().unwrap().to_string()),
+        fee_payer_signer_index: 0,
+        program_signer_index: None,
+        program_pubkey: Some(program_keypair.pubkey()),
+        buffer_signer_index: None,
+        buffer_pubkey: None,
+        upgrade_authority_signer_index: 0,
+        is_final: false,
+        max_len: None,
+        skip_fee_check: false,
+        compute_unit_price: None,
+        max_sign_attempts: 5,
+        auto_extend: true,
+        use_rpc: false,
+        skip_feature_verification: true,
+        build_metadata_location: Some(build_metadata_path.to_str().unwrap().to_string()),
+    });
+    process_command(&config).await.unwrap();
+
+    config.command = CliCommand::Program(ProgramCliCommand::Show {
+        account_pubkey: Some(program_keypair.pubkey()),
+        authority_pubkey: keypair.pubkey(),
+        get_programs: false,
+        get_buffers: false,
+        all: false,
+        use_lamports_unit: false,
+    });
+    let response = process_command(&config).await;
+    let json: Value = serde_json::from_str(&response.unwrap()).unwrap();
+    let build_metadata = json.get("buildMetadata").unwrap();
+    assert_eq!(
+        build_metadata.get("commit").unwrap().as_str().unwrap(),
+        "4567def"
+    );
+    assert!(build_metadata.get("verified").unwrap().as_bool().unwrap());
+
+    // The build metadata is not a buffer of its authority
+    config.command = CliCommand::Program(ProgramCliCommand::Show {
+        account_pubkey: None,
+        authority_pubkey: keypair.pubkey(),
+        get_programs: false,
+        get_buffers: true,
+        all: false,
+        use_lamports_unit: false,
+    });
+    let response = process_command(&config).await;
+    let json: Value = serde_json::from_str(&response.unwrap()).unwrap();
+    assert!(json.get("buffers").unwrap().as_array().unwrap().is_empty());
+}
+
 async fn create_buffer_with_offline_authority<'a>(
     rpc_client: &RpcClient,
     program_path: &Path,
@@ -3081,6 +3271,7 @@ async fn test_cli_program_deploy_with_args(compute_unit_price: Option<u64>, use_
         auto_extend: true,
         use_rpc,
         skip_feature_verification: true,
+        build_metadata_location: None,
     });
     config.output_format = OutputFormat::JsonCompact;
     let response = process_command(&config).await;
@@ -3240,6 +3431,7 @@ async fn test_cli_program_v4() {
         authority_signer_index: 1,
         path_to_elf: Some(noop_path.to_str().unwrap().to_string()),
         upload_range: None..None,
+        build_metadata_location: None,
     });
     assert!(process_command(&config).await.is_ok());
     let program_account = rpc_client
@@ -3258,6 +3450,7 @@ async fn test_cli_program_v4() {
         authority_signer_index: 1,
         path_to_elf: Some(noop_path.to_str().unwrap().to_string()),
         upload_range: None..None,
+        build_metadata_location: None,
     });
     assert!(process_command(&config).await.is_ok());
     let program_account = rpc_client
@@ -3276,6 +3469,7 @@ async fn test_cli_program_v4() {
         authority_signer_index: 1,
         path_to_elf: Some(noop_path.to_str().unwrap().to_string()),
         upload_range: None..None,
+        build_metadata_location: None,
     });
     assert!(process_command(&config).await.is_ok());
     let program_account = rpc_client
@@ -3298,6 +3492,7 @@ async fn test_cli_program_v4() {
         authority_signer_index: 1,
         path_to_elf: Some(noop_path.to_str().unwrap().to_string()),
         upload_range: None..None,
+        build_metadata_location: None,
     });
     assert!(process_command(&config).await.is_ok());
     let buffer_account = rpc_client
@@ -3314,6 +3509,7 @@ async fn test_cli_program_v4() {
         authority_signer_index: 1,
         path_to_elf: Some(noop_path.to_str().unwrap().to_string()),
         upload_range: None..None,
+        build_metadata_location: None,
     });
     assert!(process_command(&config).await.is_ok());
     let program_account = rpc_client
@@ -3364,6 +3560,7 @@ async fn test_cli_program_v4() {
         authority_signer_index: 1,
         path_to_elf: Some(noop_path.to_str().unwrap().to_string()),
         upload_range: None..None,
+        build_metadata_location: None,
     });
     assert!(process_command(&config).await.is_ok());
     let program_account = rpc_client
@@ -3388,3 +3585,255 @@ async fn test_cli_program_v4() {
     assert_eq!(program_account.owner, loader_v4::id());
     assert!(program_account.executable);
 }
+
+/// Returns the ranges of program data written by the transactions which
+/// reference `address` and were confirmed after `until`.
+async fn program_data_writes_since(
+    rpc_client: &RpcClient,
+    address: &Pubkey,
+    until: Signature,
+) -> Vec<Range<usize>> {
+    let signature_statuses = rpc_client
+        .get_signatures_for_address_with_config(
+            address,
+            GetConfirmedSignaturesForAddress2Config {
+                until: Some(until),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )
+        .await
+        .unwrap();
+    let mut writes = vec![];
+    for status in signature_statuses {
+        let transaction = rpc_client
+            .get_transaction_with_config(
+                &Signature::from_str(&status.signature).unwrap(),
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    ..RpcTransactionConfig::default()
+                },
+            )
+            .await
+            .unwrap()
+            .transaction
+            .transaction
+            .decode()
+            .unwrap();
+        let message = &transaction.message;
+        for instruction in message.instructions() {
+            let program_id = &message.static_account_keys()[instruction.program_id_index as usize];
+            let write = if bpf_loader_upgradeable::check_id(program_id) {
+                match bincode::deserialize(&instruction.data) {
+                    Ok(UpgradeableLoaderInstruction::Write { offset, bytes }) => {
+                        Some((offset, bytes.len()))
+                    }
+                    _ => None,
+                }
+            } else if loader_v4::check_id(program_id) {
+                match bincode::deserialize(&instruction.data) {
+                    Ok(LoaderV4Instruction::Write { offset, bytes }) => Some((offset, bytes.len())),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+            if let Some((offset, len)) = write {
+                writes.push(offset as usize..offset as usize + len);
+            }
+        }
+    }
+    // Writes which were resent in a later round may have landed twice
+    writes.sort_by_key(|range| range.start);
+    writes.dedup();
+    writes
+}
+
+/// Reverts the first and last bytes of the uploaded program data with the
+/// given writes, as if the first and last writes of the upload were dropped.
+async fn revert_first_and_last_chunks(
+    rpc_client: &RpcClient,
+    authority: &Keypair,
+    writes: [Instruction; 2],
+) {
+    let blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &writes,
+        Some(&authority.pubkey()),
+        &[authority],
+        blockhash,
+    );
+    rpc_client
+        .send_and_confirm_transaction(&transaction)
+        .await
+        .unwrap();
+}
+
+async fn latest_signature_for_address(rpc_client: &RpcClient, address: &Pubkey) -> Signature {
+    let signature_statuses = rpc_client
+        .get_signatures_for_address_with_config(
+            address,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(1),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )
+        .await
+        .unwrap();
+    Signature::from_str(&signature_statuses[0].signature).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cli_program_resume_interrupted_upload() {
+    agave_logger::setup();
+
+    let mut noop_large_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    noop_large_path.push("tests");
+    noop_large_path.push("fixtures");
+    noop_large_path.push("noop_large");
+    noop_large_path.set_extension("so");
+
+    let mint_keypair = Keypair::new();
+    let test_validator = test_validator_genesis(&mint_keypair)
+        .start_async_with_mint_address(&mint_keypair, SocketAddrSpace::Unspecified)
+        .await
+        .expect("validator start failed");
+
+    let mut config = CliConfig::recent_for_tests();
+    config.json_rpc_url = test_validator.rpc_url();
+    // The writes of an upload are only listed once confirmed
+    config.commitment = CommitmentConfig::confirmed();
+    let rpc_client = setup_rpc_client(&mut config);
+
+    let mut file = File::open(noop_large_path.to_str().unwrap()).unwrap();
+    let mut program_data = Vec::new();
+    file.read_to_end(&mut program_data).unwrap();
+    let last_offset = program_data.len() - 1;
+
+    let keypair = Keypair::new();
+    let buffer_keypair = Keypair::new();
+    let program_keypair = Keypair::new();
+    config.signers = vec![&keypair, &buffer_keypair, &program_keypair];
+    config.command = CliCommand::Airdrop {
+        pubkey: None,
+        lamports: 100_000_000,
+    };
+    process_command(&config).await.unwrap();
+
+    // Loader-v3 buffer
+    let write_buffer = || {
+        CliCommand::Program(ProgramCliCommand::WriteBuffer {
+            program_location: noop_large_path.to_str().unwrap().to_string(),
+            fee_payer_signer_index: 0,
+            buffer_signer_index: Some(1),
+            buffer_pubkey: Some(buffer_keypair.pubkey()),
+            buffer_authority_signer_index: 0,
+            max_len: None,
+            skip_fee_check: false,
+            compute_unit_price: None,
+            max_sign_attempts: 5,
+            use_rpc: false,
+            skip_feature_verification: true,
+        })
+    };
+    config.command = write_buffer();
+    process_command(&config).await.unwrap();
+    revert_first_and_last_chunks(
+        &rpc_client,
+        &keypair,
+        [
+            loader_v3_instruction::write(
+                &buffer_keypair.pubkey(),
+                &keypair.pubkey(),
+                0,
+                vec![!program_data[0]],
+            ),
+            loader_v3_instruction::write(
+                &buffer_keypair.pubkey(),
+                &keypair.pubkey(),
+                last_offset as u32,
+                vec![!program_data[last_offset]],
+            ),
+        ],
+    )
+    .await;
+    let interrupted_at = latest_signature_for_address(&rpc_client, &buffer_keypair.pubkey()).await;
+
+    config.command = write_buffer();
+    process_command(&config).await.unwrap();
+    let writes =
+        program_data_writes_since(&rpc_client, &buffer_keypair.pubkey(), interrupted_at).await;
+    assert_eq!(writes.len(), 2);
+    assert_eq!(writes[0].start, 0);
+    assert_eq!(writes[1].end, program_data.len());
+    let buffer_account = rpc_client
+        .get_account(&buffer_keypair.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        buffer_account.data[UpgradeableLoaderState::size_of_buffer_metadata()..],
+        program_data[..]
+    );
+
+    // Retracted loader-v4 program
+    let deploy_program = || {
+        CliCommand::ProgramV4(ProgramV4CliCommand::Deploy {
+            additional_cli_config: AdditionalCliConfig::default(),
+            program_address: program_keypair.pubkey(),
+            buffer_address: None,
+            upload_signer_index: Some(2),
+            authority_signer_index: 0,
+            path_to_elf: Some(noop_large_path.to_str().unwrap().to_string()),
+            upload_range: None..None,
+            build_metadata_location: None,
+        })
+    };
+    config.command = deploy_program();
+    process_command(&config).await.unwrap();
+    config.command = CliCommand::ProgramV4(ProgramV4CliCommand::Retract {
+        additional_cli_config: AdditionalCliConfig::default(),
+        program_address: program_keypair.pubkey(),
+        authority_signer_index: 0,
+        close_program_entirely: false,
+    });
+    process_command(&config).await.unwrap();
+    revert_first_and_last_chunks(
+        &rpc_client,
+        &keypair,
+        [
+            loader_v4_instruction::write(
+                &program_keypair.pubkey(),
+                &keypair.pubkey(),
+                0,
+                vec![!program_data[0]],
+            ),
+            loader_v4_instruction::write(
+                &program_keypair.pubkey(),
+                &keypair.pubkey(),
+                last_offset as u32,
+                vec![!program_data[last_offset]],
+            ),
+        ],
+    )
+    .await;
+    let interrupted_at = latest_signature_for_address(&rpc_client, &program_keypair.pubkey()).await;
+
+    config.command = deploy_program();
+    process_command(&config).await.unwrap();
+    let writes =
+        program_data_writes_since(&rpc_client, &program_keypair.pubkey(), interrupted_at).await;
+    assert_eq!(writes.len(), 2);
+    assert_eq!(writes[0].start, 0);
+    assert_eq!(writes[1].end, program_data.len());
+    let program_account = rpc_client
+        .get_account(&program_keypair.pubkey())
+        .await
+        .unwrap();
+    assert!(program_account.executable);
+    assert_eq!(
+        program_account.data[LoaderV4State::program_data_offset()..],
+        program_data[..]
+    );
+}
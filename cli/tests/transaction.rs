@@ -0,0 +1,141 @@
+#![allow(clippy::arithmetic_side_effects)]
+use {
+    solana_cli::{
+        check_balance,
+        cli::{process_command, request_and_confirm_airdrop, CliCommand, CliConfig},
+        test_utils::check_ready,
+        transaction::TransactionCliCommand,
+    },
+    solana_cli_output::{parse_sign_only_reply_string, OutputFormat},
+    solana_client::nonblocking::blockhash_query::Source,
+    solana_commitment_config::CommitmentConfig,
+    solana_faucet::faucet::run_local_faucet_with_unique_port_for_tests,
+    solana_keypair::Keypair,
+    solana_native_token::LAMPORTS_PER_SOL,
+    solana_net_utils::SocketAddrSpace,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::nonblocking::rpc_client::RpcClient,
+    solana_rpc_client_nonce_utils::nonblocking::blockhash_query::BlockhashQuery,
+    solana_signer::{null_signer::NullSigner, Signer},
+    solana_system_interface::instruction as system_instruction,
+    solana_test_validator::TestValidator,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transaction_build_sign_send() {
+    agave_logger::setup();
+    let mint_keypair = Keypair::new();
+    let faucet_addr = run_local_faucet_with_unique_port_for_tests(mint_keypair.insecure_clone());
+    let test_validator = TestValidator::async_with_no_fees(
+        &mint_keypair,
+        Some(faucet_addr),
+        SocketAddrSpace::Unspecified,
+    )
+    .await;
+
+    let rpc_client =
+        RpcClient::new_with_commitment(test_validator.rpc_url(), CommitmentConfig::processed());
+
+    let fee_payer = Keypair::new();
+    let sender = Keypair::new();
+    let recipient = Pubkey::new_unique();
+
+    let mut config = CliConfig::recent_for_tests();
+    config.json_rpc_url = test_validator.rpc_url();
+    config.signers = vec![&fee_payer];
+    request_and_confirm_airdrop(&rpc_client, &config, &fee_payer.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    request_and_confirm_airdrop(&rpc_client, &config, &sender.pubkey(), 5 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    check_ready(&rpc_client).await;
+
+    let instructions = vec![system_instruction::transfer(
+        &sender.pubkey(),
+        &recipient,
+        LAMPORTS_PER_SOL,
+    )];
+    let dir = tempfile::tempdir().unwrap();
+    let transaction_file = dir.path().join("tx.b64").to_str().unwrap().to_string();
+
+    // Build on the host of the fee payer, without the signature of the sender
+    let sender_null_signer = NullSigner::new(&sender.pubkey());
+    config.signers = vec![&fee_payer, &sender_null_signer];
+    config.command = CliCommand::Transaction(TransactionCliCommand::Build {
+        instructions: instructions.clone(),
+        address_lookup_tables: vec![],
+        compute_unit_limit: Some(10_000),
+        compute_unit_price: None,
+        fee_payer: 0,
+        sign_only: false,
+        dump_transaction_message: false,
+        blockhash_query: BlockhashQuery::Rpc(Source::Cluster),
+        nonce_account: None,
+        nonce_authority: 0,
+        output_file: Some(transaction_file.clone()),
+    });
+    process_command(&config).await.unwrap();
+
+    config.signers = vec![];
+    config.command = CliCommand::Transaction(TransactionCliCommand::Send {
+        transaction_file: transaction_file.clone(),
+    });
+    process_command(&config).await.unwrap_err();
+    check_balance!(0, &rpc_client, &recipient);
+
+    // Sign on the host of the sender, then send
+    config.signers = vec![&sender];
+    config.command = CliCommand::Transaction(TransactionCliCommand::Sign {
+        transaction_file: transaction_file.clone(),
+        output_file: None,
+    });
+    process_command(&config).await.unwrap();
+
+    config.signers = vec![];
+    config.command = CliCommand::Transaction(TransactionCliCommand::Send { transaction_file });
+    process_command(&config).await.unwrap();
+    check_balance!(LAMPORTS_PER_SOL, &rpc_client, &recipient);
+
+    // Sign offline, then send with the signature
+    let blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+    let fee_payer_null_signer = NullSigner::new(&fee_payer.pubkey());
+    let mut offline = CliConfig::recent_for_tests();
+    offline.json_rpc_url = String::default();
+    offline.signers = vec![&fee_payer_null_signer, &sender];
+    offline.output_format = OutputFormat::JsonCompact;
+    offline.command = CliCommand::Transaction(TransactionCliCommand::Build {
+        instructions: instructions.clone(),
+        address_lookup_tables: vec![],
+        compute_unit_limit: None,
+        compute_unit_price: Some(1),
+        fee_payer: 0,
+        sign_only: true,
+        dump_transaction_message: false,
+        blockhash_query: BlockhashQuery::Static(blockhash),
+        nonce_account: None,
+        nonce_authority: 0,
+        output_file: None,
+    });
+    let sign_only_reply = process_command(&offline).await.unwrap();
+    let sign_only = parse_sign_only_reply_string(&sign_only_reply);
+    assert!(!sign_only.has_all_signers());
+    let sender_presigner = sign_only.presigner_of(&sender.pubkey()).unwrap();
+
+    config.signers = vec![&fee_payer, &sender_presigner];
+    config.command = CliCommand::Transaction(TransactionCliCommand::Build {
+        instructions,
+        address_lookup_tables: vec![],
+        compute_unit_limit: None,
+        compute_unit_price: Some(1),
+        fee_payer: 0,
+        sign_only: false,
+        dump_transaction_message: false,
+        blockhash_query: BlockhashQuery::Validated(Source::Cluster, blockhash),
+        nonce_account: None,
+        nonce_authority: 0,
+        output_file: None,
+    });
+    process_command(&config).await.unwrap();
+    check_balance!(2 * LAMPORTS_PER_SOL, &rpc_client, &recipient);
+}
@@ -3,6 +3,7 @@ use {
     solana_cli::{
         check_balance,
         cli::{process_command, request_and_confirm_airdrop, CliCommand, CliConfig},
+        compute_budget::AutoComputeBudget,
         spend_utils::SpendAmount,
         test_utils::check_ready,
     },
@@ -627,6 +628,112 @@ async fn test_transfer_unfunded_recipient() {
     process_command(&config).await.unwrap_err();
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transfer_auto_compute_budget() {
+    agave_logger::setup();
+    let fee = FeeStructure::default().get_max_fee(1, 0);
+    let mint_keypair = Keypair::new();
+    let faucet_addr = run_local_faucet_with_unique_port_for_tests(mint_keypair.insecure_clone());
+    let test_validator = TestValidator::async_with_custom_fees(
+        &mint_keypair,
+        fee,
+        Some(faucet_addr),
+        SocketAddrSpace::Unspecified,
+    )
+    .await;
+
+    let rpc_client =
+        RpcClient::new_with_commitment(test_validator.rpc_url(), CommitmentConfig::processed());
+
+    let default_signer = Keypair::new();
+
+    let mut config = CliConfig::recent_for_tests();
+    config.json_rpc_url = test_validator.rpc_url();
+    config.signers = vec![&default_signer];
+    config.send_transaction_config.skip_preflight = false;
+    config.auto_compute_budget = Some(AutoComputeBudget {
+        priority_fee_percentile: 75,
+        max_priority_fee: 1_000,
+    });
+
+    let sender_pubkey = config.signers[0].pubkey();
+    let recipient_pubkey = Pubkey::from([1u8; 32]);
+
+    request_and_confirm_airdrop(&rpc_client, &config, &sender_pubkey, LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    check_ready(&rpc_client).await;
+
+    // Without recent prioritization fees, only the base fee is paid
+    config.command = CliCommand::Transfer {
+        amount: SpendAmount::Some(LAMPORTS_PER_SOL / 2),
+        to: recipient_pubkey,
+        from: 0,
+        sign_only: false,
+        dump_transaction_message: false,
+        allow_unfunded_recipient: true,
+        no_wait: false,
+        blockhash_query: BlockhashQuery::Rpc(Source::Cluster),
+        nonce_account: None,
+        nonce_authority: 0,
+        memo: None,
+        fee_payer: 0,
+        derived_address_seed: None,
+        derived_address_program_id: None,
+        compute_unit_price: Some(1_000_000),
+    };
+    process_command(&config).await.unwrap();
+    check_balance!(LAMPORTS_PER_SOL / 2, &rpc_client, &recipient_pubkey);
+    check_balance!(LAMPORTS_PER_SOL / 2 - fee, &rpc_client, &sender_pubkey);
+
+    // Offline signatures are sent as they are
+    let blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+    let mut offline_config = CliConfig::recent_for_tests();
+    offline_config.json_rpc_url = String::default();
+    offline_config.signers = vec![&default_signer];
+    offline_config.output_format = OutputFormat::JsonCompact;
+    offline_config.command = CliCommand::Transfer {
+        amount: SpendAmount::Some(1),
+        to: recipient_pubkey,
+        from: 0,
+        sign_only: true,
+        dump_transaction_message: false,
+        allow_unfunded_recipient: true,
+        no_wait: false,
+        blockhash_query: BlockhashQuery::Static(blockhash),
+        nonce_account: None,
+        nonce_authority: 0,
+        memo: None,
+        fee_payer: 0,
+        derived_address_seed: None,
+        derived_address_program_id: None,
+        compute_unit_price: None,
+    };
+    let sign_only_reply = process_command(&offline_config).await.unwrap();
+    let sign_only = parse_sign_only_reply_string(&sign_only_reply);
+    let presigner = sign_only.presigner_of(&sender_pubkey).unwrap();
+    config.signers = vec![&presigner];
+    config.command = CliCommand::Transfer {
+        amount: SpendAmount::Some(1),
+        to: recipient_pubkey,
+        from: 0,
+        sign_only: false,
+        dump_transaction_message: false,
+        allow_unfunded_recipient: true,
+        no_wait: false,
+        blockhash_query: BlockhashQuery::Validated(Source::Cluster, blockhash),
+        nonce_account: None,
+        nonce_authority: 0,
+        memo: None,
+        fee_payer: 0,
+        derived_address_seed: None,
+        derived_address_program_id: None,
+        compute_unit_price: None,
+    };
+    process_command(&config).await.unwrap();
+    check_balance!(LAMPORTS_PER_SOL / 2 + 1, &rpc_client, &recipient_pubkey);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_transfer_with_seed() {
     agave_logger::setup();
@@ -0,0 +1,131 @@
+#![allow(clippy::arithmetic_side_effects)]
+use {
+    solana_cli::{
+        check_balance,
+        cli::{process_command, request_and_confirm_airdrop, CliCommand, CliConfig},
+        spend_utils::SpendAmount,
+        test_utils::check_ready,
+        tx_bundle::{read_tx_bundle, TxBundleCliCommand},
+    },
+    solana_commitment_config::CommitmentConfig,
+    solana_faucet::faucet::run_local_faucet_with_unique_port_for_tests,
+    solana_keypair::Keypair,
+    solana_native_token::LAMPORTS_PER_SOL,
+    solana_net_utils::SocketAddrSpace,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::nonblocking::rpc_client::RpcClient,
+    solana_rpc_client_nonce_utils::nonblocking::blockhash_query::BlockhashQuery,
+    solana_signer::{null_signer::NullSigner, Signer},
+    solana_test_validator::TestValidator,
+    std::fs,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_tx_bundle() {
+    agave_logger::setup();
+    let mint_keypair = Keypair::new();
+    let faucet_addr = run_local_faucet_with_unique_port_for_tests(mint_keypair.insecure_clone());
+    let test_validator = TestValidator::async_with_no_fees(
+        &mint_keypair,
+        Some(faucet_addr),
+        SocketAddrSpace::Unspecified,
+    )
+    .await;
+
+    let rpc_client =
+        RpcClient::new_with_commitment(test_validator.rpc_url(), CommitmentConfig::processed());
+
+    let fee_payer = Keypair::new();
+    let treasury = Keypair::new();
+    let recipient = Pubkey::new_unique();
+
+    let mut config = CliConfig::recent_for_tests();
+    config.json_rpc_url = test_validator.rpc_url();
+    config.signers = vec![&fee_payer];
+    request_and_confirm_airdrop(&rpc_client, &config, &fee_payer.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    request_and_confirm_airdrop(
+        &rpc_client,
+        &config,
+        &treasury.pubkey(),
+        5 * LAMPORTS_PER_SOL,
+    )
+    .await
+    .unwrap();
+    check_ready(&rpc_client).await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let bundle_file = dir.path().join("bundle.json");
+    let bundle_file = bundle_file.to_str().unwrap().to_string();
+    let fee_payer_bundle_file = dir.path().join("fee-payer.json");
+    let fee_payer_bundle_file = fee_payer_bundle_file.to_str().unwrap().to_string();
+    let treasury_bundle_file = dir.path().join("treasury.json");
+    let treasury_bundle_file = treasury_bundle_file.to_str().unwrap().to_string();
+
+    // Create the bundle of a transfer, without signatures
+    let blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+    let fee_payer_null_signer = NullSigner::new(&fee_payer.pubkey());
+    let treasury_null_signer = NullSigner::new(&treasury.pubkey());
+    config.signers = vec![&fee_payer_null_signer, &treasury_null_signer];
+    config.command = CliCommand::TxBundle(TxBundleCliCommand::Create {
+        command: Box::new(CliCommand::Transfer {
+            amount: SpendAmount::Some(LAMPORTS_PER_SOL),
+            to: recipient,
+            from: 1,
+            sign_only: true,
+            dump_transaction_message: true,
+            allow_unfunded_recipient: true,
+            no_wait: false,
+            blockhash_query: BlockhashQuery::Static(blockhash),
+            nonce_account: None,
+            nonce_authority: 0,
+            memo: None,
+            fee_payer: 0,
+            derived_address_seed: None,
+            derived_address_program_id: None,
+            compute_unit_price: None,
+        }),
+        bundle_file: bundle_file.clone(),
+    });
+    process_command(&config).await.unwrap();
+    let tx = read_tx_bundle(&bundle_file).unwrap();
+    assert_eq!(*tx.message.recent_blockhash(), blockhash);
+    assert_eq!(tx.verify_with_results(), vec![false, false]);
+
+    // Each signer signs a copy of the bundle
+    fs::copy(&bundle_file, &fee_payer_bundle_file).unwrap();
+    fs::copy(&bundle_file, &treasury_bundle_file).unwrap();
+    for (signer, bundle_file) in [
+        (&fee_payer, &fee_payer_bundle_file),
+        (&treasury, &treasury_bundle_file),
+    ] {
+        config.signers = vec![signer];
+        config.command = CliCommand::TxBundle(TxBundleCliCommand::Sign {
+            bundle_file: bundle_file.clone(),
+        });
+        process_command(&config).await.unwrap();
+    }
+
+    config.signers = vec![];
+    config.command = CliCommand::TxBundle(TxBundleCliCommand::Send {
+        bundle_file: fee_payer_bundle_file.clone(),
+    });
+    process_command(&config).await.unwrap_err();
+    check_balance!(0, &rpc_client, &recipient);
+
+    // Merge the signatures, then verify and send
+    config.command = CliCommand::TxBundle(TxBundleCliCommand::Merge {
+        bundle_file: bundle_file.clone(),
+        other_bundle_files: vec![fee_payer_bundle_file, treasury_bundle_file],
+    });
+    process_command(&config).await.unwrap();
+    config.command = CliCommand::TxBundle(TxBundleCliCommand::Verify {
+        bundle_file: bundle_file.clone(),
+    });
+    process_command(&config).await.unwrap();
+    config.command = CliCommand::TxBundle(TxBundleCliCommand::Send { bundle_file });
+    process_command(&config).await.unwrap();
+    check_balance!(LAMPORTS_PER_SOL, &rpc_client, &recipient);
+    check_balance!(4 * LAMPORTS_PER_SOL, &rpc_client, &treasury.pubkey());
+}
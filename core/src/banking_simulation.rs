@@ -774,6 +774,7 @@ impl BankingSimulator {
             poh_service_message_receiver,
             Arc::new(MigrationStatus::default()),
             record_receiver_sender,
+            None,
         );
 
         // Enable BankingTracer to approximate the real environment as close as possible because
@@ -26,6 +26,7 @@ use {
     crossbeam_channel::{unbounded, Receiver, Sender},
     futures::{stream::FuturesUnordered, StreamExt},
     histogram::Histogram,
+    solana_accounts_db::accounts_db::ReadCacheCaller,
     solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfoQuery},
     solana_ledger::blockstore_processor::TransactionStatusSender,
     solana_perf::packet::PACKETS_PER_BATCH,
@@ -592,6 +593,7 @@ impl BankingStage {
                 Builder::new()
                     .name(format!("solCoWorker{id:02}"))
                     .spawn(|| {
+                        ReadCacheCaller::Banking.set_for_current_thread();
                         if let Err(err) = consume_worker.run() {
                             error!("Internal consume worker error; err={err}");
                         }
@@ -699,6 +701,7 @@ impl BankingStage {
         Builder::new()
             .name("solBanknStgVote".to_string())
             .spawn(move || {
+                ReadCacheCaller::Banking.set_for_current_thread();
                 VoteWorker::new(
                     worker_exit_signal,
                     shutdown_signal,
@@ -790,6 +793,7 @@ mod external {
                     Builder::new()
                         .name(format!("solECoWorker{id:02}"))
                         .spawn(move || {
+                            ReadCacheCaller::Banking.set_for_current_thread();
                             if let Err(err) = consume_worker.run(pack_to_worker) {
                                 error!("External consume worker error; err={err}");
                             }
@@ -353,6 +353,8 @@ impl Consumer {
                     ),
                     drop_on_failure: flags.drop_on_failure,
                     all_or_nothing: flags.all_or_nothing,
+                    instruction_recorder: None,
+                    ..TransactionProcessingConfig::default()
                 }
             ));
         execute_and_commit_timings.load_execute_us = load_execute_us;
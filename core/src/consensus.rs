@@ -689,6 +689,16 @@ impl Tower {
         )
     }
 
+    /// Records a vote for the given bank and roots it right away, instead of
+    /// once the vote reaches the maximum lockout. Only sound when this node is
+    /// the whole cluster. Returns the new root.
+    pub(crate) fn record_bank_vote_as_root(&mut self, bank: &Bank) -> Slot {
+        self.record_bank_vote(bank);
+        self.vote_state.votes.clear();
+        self.vote_state.root_slot = Some(bank.slot());
+        bank.slot()
+    }
+
     /// If we've recently updated the vote state by applying a new vote
     /// or syncing from a bank, generate the proper last_vote.
     pub(crate) fn update_last_vote_from_vote_state(
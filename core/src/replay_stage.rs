@@ -76,6 +76,7 @@ use {
         optimistically_confirmed_bank_tracker::{BankNotification, BankNotificationSenderConfig},
         rpc_subscriptions::RpcSubscriptions,
         slot_status_notifier::SlotStatusNotifier,
+        slot_warper::{WarpRequest, WarpRequestReceiver},
     },
     solana_rpc_client_api::response::SlotUpdate,
     solana_runtime::{
@@ -265,6 +266,9 @@ pub struct ReplayStageConfig {
     pub leader_schedule_cache: Arc<LeaderScheduleCache>,
     pub block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     pub wait_for_vote_to_start_leader: bool,
+    // Root every votable bank right away instead of voting on it. Only sound
+    // when this node is the whole cluster, as in automine test validators.
+    pub automine: bool,
     pub tower_storage: Arc<dyn TowerStorage>,
     // Stops voting until this slot has been reached. Should be used to avoid
     // duplicate voting which can lead to slashing.
@@ -315,6 +319,7 @@ pub struct ReplayReceivers {
     pub duplicate_confirmed_slots_receiver: Receiver<Vec<(u64, Hash)>>,
     pub gossip_verified_vote_hash_receiver: Receiver<(Pubkey, u64, Hash)>,
     pub popular_pruned_forks_receiver: Receiver<Vec<u64>>,
+    pub warp_request_receiver: Option<WarpRequestReceiver>,
 }
 
 /// Timing information for the ReplayStage main processing loop
@@ -578,6 +583,7 @@ impl ReplayStage {
             leader_schedule_cache,
             block_commitment_cache,
             wait_for_vote_to_start_leader,
+            automine,
             tower_storage,
             wait_to_vote_slot,
             replay_forks_threads,
@@ -626,6 +632,7 @@ impl ReplayStage {
             duplicate_confirmed_slots_receiver,
             gossip_verified_vote_hash_receiver,
             popular_pruned_forks_receiver,
+            warp_request_receiver,
         } = receivers;
 
         trace!("replay stage");
@@ -751,6 +758,50 @@ impl ReplayStage {
                     break;
                 }
 
+                if let Some(warp_request_receiver) = &warp_request_receiver {
+                    for WarpRequest {
+                        slot,
+                        response_sender,
+                    } in warp_request_receiver.try_iter()
+                    {
+                        let response = Self::warp_bank_forks(
+                            slot,
+                            &my_pubkey,
+                            &vote_account,
+                            &bank_forks,
+                            &mut progress,
+                            &mut tower,
+                            &blockstore,
+                            &leader_schedule_cache,
+                            snapshot_controller.as_deref(),
+                            rpc_subscriptions.as_deref(),
+                            &block_commitment_cache,
+                            &bank_notification_sender,
+                            &mut has_new_vote_been_rooted,
+                            &mut tracked_vote_transactions,
+                            &drop_bank_sender,
+                            migration_status.as_ref(),
+                            &mut tbft_structs,
+                        )
+                        .map(|warp_bank| match warp_bank {
+                            Some(warp_bank) => {
+                                Self::reset_poh_recorder(
+                                    &my_pubkey,
+                                    &blockstore,
+                                    warp_bank.clone(),
+                                    &mut poh_controller,
+                                    &leader_schedule_cache,
+                                );
+                                last_reset = warp_bank.last_blockhash();
+                                last_reset_bank_descendants = vec![];
+                                warp_bank.slot()
+                            }
+                            None => slot,
+                        });
+                        let _ = response_sender.send(response);
+                    }
+                }
+
                 let mut generate_new_bank_forks_time =
                     Measure::start("generate_new_bank_forks_time");
                 Self::generate_new_bank_forks(
@@ -1099,6 +1150,7 @@ impl ReplayStage {
                             wait_to_vote_slot,
                             migration_status.as_ref(),
                             &mut tbft_structs,
+                            automine,
                         );
                     }
                     voting_time.stop();
@@ -2608,17 +2660,41 @@ impl ReplayStage {
         wait_to_vote_slot: Option<Slot>,
         migration_status: &MigrationStatus,
         tbft_structs: &mut TowerBFTStructures,
+        automine: bool,
     ) {
         assert!(!migration_status.is_alpenglow_enabled());
         if bank.is_empty() {
             datapoint_info!("replay_stage-voted_empty_bank", ("slot", bank.slot(), i64));
         }
         trace!("handle votable bank {}", bank.slot());
-        let new_root = tower.record_bank_vote(bank).filter(|root| {
+        let new_root = if automine {
+            Some(tower.record_bank_vote_as_root(bank))
+        } else {
+            tower.record_bank_vote(bank)
+        }
+        .filter(|root| {
             // We do not root during the migration - post genesis rooting is handled by votor
             migration_status.should_report_commitment_or_root(*root)
         });
 
+        if automine {
+            // No votes will confirm the bank either, confirm it along with
+            // rooting it
+            if let Some(sender) = bank_notification_sender {
+                let dependency_work = sender
+                    .dependency_tracker
+                    .as_ref()
+                    .map(|s| s.get_current_declared_work());
+                sender
+                    .sender
+                    .send((
+                        BankNotification::OptimisticallyConfirmed(bank.slot()),
+                        dependency_work,
+                    ))
+                    .unwrap_or_else(|err| warn!("bank_notification_sender failed: {err:?}"));
+            }
+        }
+
         if let Some(new_root) = new_root {
             let highest_super_majority_root = Some(
                 block_commitment_cache
@@ -2690,6 +2766,12 @@ impl ReplayStage {
         update_commitment_cache_time.stop();
         replay_timing.update_commitment_cache_us += update_commitment_cache_time.as_us();
 
+        if automine {
+            // Like for non voting validators, there is no vote to refresh
+            tower.mark_last_vote_tx_blockhash_non_voting();
+            return;
+        }
+
         Self::push_vote(
             bank,
             vote_account_pubkey,
@@ -2705,6 +2787,118 @@ impl ReplayStage {
         );
     }
 
+    /// Roots a bank warped ahead to `warp_slot` from the heaviest bank, for
+    /// the test validator RPC methods. Only sound when this node is the whole
+    /// cluster. Returns the warped bank for PoH to reset onto, or `None` if
+    /// the bank forks already reached `warp_slot`.
+    #[allow(clippy::too_many_arguments)]
+    fn warp_bank_forks(
+        warp_slot: Slot,
+        my_pubkey: &Pubkey,
+        vote_account: &Pubkey,
+        bank_forks: &RwLock<BankForks>,
+        progress: &mut ProgressMap,
+        tower: &mut Tower,
+        blockstore: &Blockstore,
+        leader_schedule_cache: &Arc<LeaderScheduleCache>,
+        snapshot_controller: Option<&SnapshotController>,
+        rpc_subscriptions: Option<&RpcSubscriptions>,
+        block_commitment_cache: &RwLock<BlockCommitmentCache>,
+        bank_notification_sender: &Option<BankNotificationSenderConfig>,
+        has_new_vote_been_rooted: &mut bool,
+        tracked_vote_transactions: &mut Vec<TrackedVoteTransaction>,
+        drop_bank_sender: &Sender<Vec<BankWithScheduler>>,
+        migration_status: &MigrationStatus,
+        tbft_structs: &mut TowerBFTStructures,
+    ) -> Result<Option<Arc<Bank>>, String> {
+        if migration_status.is_alpenglow_enabled() {
+            return Err("Cannot warp the bank forks once Alpenglow is enabled".to_string());
+        }
+        let parent = {
+            let r_bank_forks = bank_forks.read().unwrap();
+            if warp_slot <= r_bank_forks.working_bank().slot() {
+                return Ok(None);
+            }
+            let (heaviest_slot, _hash) = tbft_structs
+                .heaviest_subtree_fork_choice
+                .best_overall_slot();
+            r_bank_forks.get(heaviest_slot).ok_or_else(|| {
+                format!("Heaviest slot {heaviest_slot} is missing from bank forks")
+            })?
+        };
+        let parent_slot = parent.slot();
+        let parent_hash = parent.hash();
+        info!("{my_pubkey}: warping from slot {parent_slot} to slot {warp_slot}");
+
+        // Any bank that PoH is still recording into is not an ancestor of the
+        // warped bank, and is pruned along with its fork once it is rooted
+        let warp_bank = bank_forks
+            .write()
+            .unwrap()
+            .insert(Bank::warp_from_parent(
+                parent,
+                &Pubkey::default(),
+                warp_slot,
+            ))
+            .clone_without_scheduler();
+        let prev_leader_slot = progress.get_bank_prev_leader_slot(&warp_bank);
+        progress.insert(
+            warp_slot,
+            ForkProgress::new_from_bank(
+                &warp_bank,
+                my_pubkey,
+                vote_account,
+                prev_leader_slot,
+                0,
+                0,
+            ),
+        );
+        tbft_structs.heaviest_subtree_fork_choice.add_new_leaf_slot(
+            (warp_slot, warp_bank.hash()),
+            Some((parent_slot, parent_hash)),
+        );
+
+        // There is no other node to vote for the warped bank either, root and
+        // confirm it right away
+        let new_root = tower.record_bank_vote_as_root(&warp_bank);
+        tower.mark_last_vote_tx_blockhash_non_voting();
+        block_commitment_cache
+            .write()
+            .unwrap()
+            .set_all_slots(warp_slot, warp_slot);
+        Self::check_and_handle_new_root(
+            my_pubkey,
+            parent_slot,
+            new_root,
+            bank_forks,
+            progress,
+            blockstore,
+            leader_schedule_cache,
+            snapshot_controller,
+            rpc_subscriptions,
+            Some(new_root),
+            bank_notification_sender,
+            has_new_vote_been_rooted,
+            tracked_vote_transactions,
+            drop_bank_sender,
+            tbft_structs,
+        );
+        if let Some(sender) = bank_notification_sender {
+            let dependency_work = sender
+                .dependency_tracker
+                .as_ref()
+                .map(|s| s.get_current_declared_work());
+            sender
+                .sender
+                .send((
+                    BankNotification::OptimisticallyConfirmed(warp_slot),
+                    dependency_work,
+                ))
+                .unwrap_or_else(|err| warn!("bank_notification_sender failed: {err:?}"));
+        }
+        Ok(Some(warp_bank))
+    }
+
     fn generate_vote_tx(
         node_keypair: &Keypair,
         bank: &Bank,
@@ -2,7 +2,7 @@ mod snapshot_gossip_manager;
 use {
     agave_snapshots::{
         paths as snapshot_paths, snapshot_config::SnapshotConfig,
-        snapshot_hash::StartingSnapshotHashes, SnapshotKind,
+        snapshot_hash::StartingSnapshotHashes, SnapshotArchiveKind, SnapshotKind,
     },
     snapshot_gossip_manager::SnapshotGossipManager,
     solana_accounts_db::account_storage_entry::AccountStorageEntry,
@@ -82,8 +82,19 @@ impl SnapshotPackagerService {
                     let enqueued_time = snapshot_package.enqueued.elapsed();
 
                     let measure_handling = Measure::start("");
-                    let snapshot_kind = snapshot_package.snapshot_kind;
                     let snapshot_slot = snapshot_package.slot;
+                    let snapshot_kind = match snapshot_package.snapshot_kind {
+                        SnapshotKind::Archive(SnapshotArchiveKind::Incremental(
+                            full_snapshot_slot,
+                        )) => SnapshotKind::Archive(SnapshotArchiveKind::Incremental(
+                            snapshot_utils::get_incremental_snapshot_archive_base_slot(
+                                snapshot_config,
+                                full_snapshot_slot,
+                                snapshot_slot,
+                            ),
+                        )),
+                        snapshot_kind => snapshot_kind,
+                    };
                     let snapshot_hash = snapshot_package.hash;
 
                     snapshot_controller.set_latest_bank_snapshot_slot(snapshot_slot);
@@ -306,6 +317,22 @@ impl SnapshotPackagerService {
         }
         info!("Saving obsolete accounts... Done in {:?}", start.elapsed());
 
+        if snapshot_config.persist_accounts_index {
+            info!("Saving accounts index...");
+            let start = Instant::now();
+            let result = snapshot_utils::write_accounts_index_to_snapshot(
+                &bank_snapshot_dir,
+                &snapshot_storages,
+                snapshot_slot,
+            );
+            match result {
+                Ok(_) => info!("Saving accounts index... Done in {:?}", start.elapsed()),
+                // The accounts index is optional when loading the bank snapshot, so the bank
+                // snapshot can still be marked as loadable.
+                Err(err) => warn!("Failed to save accounts index: {err}"),
+            }
+        }
+
         let result = snapshot_utils::mark_bank_snapshot_as_loadable(&bank_snapshot_dir);
         if let Err(err) = result {
             warn!("Failed to mark bank snapshot as loadable: {err}");
@@ -1,5 +1,6 @@
 use {
     agave_snapshots::{
+        snapshot_config::MAX_INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_LEN,
         snapshot_hash::{
             FullSnapshotHash, IncrementalSnapshotHash, SnapshotHash, StartingSnapshotHashes,
         },
@@ -38,11 +39,13 @@ impl SnapshotGossipManager {
     /// Push starting snapshot hashes to the cluster via CRDS
     fn push_starting_snapshot_hashes(&mut self, starting_snapshot_hashes: StartingSnapshotHashes) {
         self.update_latest_full_snapshot_hash(starting_snapshot_hashes.full);
-        if let Some(starting_incremental_snapshot_hash) = starting_snapshot_hashes.incremental {
+        let mut base_slot = starting_snapshot_hashes.full.0 .0;
+        for starting_incremental_snapshot_hash in starting_snapshot_hashes.incremental {
             self.update_latest_incremental_snapshot_hash(
                 starting_incremental_snapshot_hash,
-                starting_snapshot_hashes.full.0 .0,
+                base_slot,
             );
+            base_slot = starting_incremental_snapshot_hash.0 .0;
         }
         self.push_latest_snapshot_hashes_to_cluster();
     }
@@ -91,11 +94,14 @@ impl SnapshotGossipManager {
             full: full_snapshot_hash,
             // If we've gotten a new full snapshot, we know there cannot be any
             // incremental snapshots yet (based on this full snapshot).
-            incremental: None,
+            incremental: Vec::new(),
         });
     }
 
     /// Update the latest snapshot hashes with a new incremental snapshot
+    ///
+    /// An incremental snapshot based on the full snapshot starts a new chain, and one based on the
+    /// latest incremental snapshot extends the chain.
     fn update_latest_incremental_snapshot_hash(
         &mut self,
         incremental_snapshot_hash: IncrementalSnapshotHash,
@@ -105,13 +111,26 @@ impl SnapshotGossipManager {
             .latest_snapshot_hashes
             .as_mut()
             .expect("there must already be a full snapshot hash");
-        assert_eq!(
-            base_slot, latest_snapshot_hashes.full.0 .0,
-            "the incremental snapshot's base slot ({}) must match the latest full snapshot's slot \
-             ({})",
-            base_slot, latest_snapshot_hashes.full.0 .0,
-        );
-        latest_snapshot_hashes.incremental = Some(incremental_snapshot_hash);
+        if base_slot == latest_snapshot_hashes.full.0 .0 {
+            latest_snapshot_hashes.incremental.clear();
+        } else {
+            let latest_incremental_snapshot_slot = latest_snapshot_hashes
+                .incremental
+                .last()
+                .map(|incremental_snapshot_hash| incremental_snapshot_hash.0 .0);
+            assert_eq!(
+                Some(base_slot),
+                latest_incremental_snapshot_slot,
+                "the incremental snapshot's base slot ({}) must match the latest full snapshot's \
+                 slot ({}) or the latest incremental snapshot's slot ({:?})",
+                base_slot,
+                latest_snapshot_hashes.full.0 .0,
+                latest_incremental_snapshot_slot,
+            );
+        }
+        latest_snapshot_hashes
+            .incremental
+            .push(incremental_snapshot_hash);
     }
 
     /// Push the latest snapshot hashes to the cluster via CRDS
@@ -120,18 +139,35 @@ impl SnapshotGossipManager {
             return;
         };
 
+        // The whole chain of incremental snapshots is pushed, so that peers can download every
+        // archive of the chain.  Chains created by this node are never too long to push, but one
+        // loaded at startup may be, and then the peers can only download the full snapshot.
+        let incremental_snapshot_hashes = if latest_snapshot_hashes.incremental.len()
+            > MAX_INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_LEN
+        {
+            warn!(
+                "The chain of {} incremental snapshots is too long to push to the cluster, only \
+                 pushing the full snapshot hash",
+                latest_snapshot_hashes.incremental.len(),
+            );
+            Vec::new()
+        } else {
+            latest_snapshot_hashes
+                .incremental
+                .iter()
+                .map(AsSnapshotHash::clone_for_crds)
+                .collect()
+        };
+
         // Pushing snapshot hashes to the cluster should never fail.  The only error case is when
-        // the length of the incremental hashes is too big, (and we send a maximum of one here).
-        // If this call ever does error, it's a programmer bug!  Check to see what changed in
-        // `push_snapshot_hashes()` and handle the new error condition here.
+        // the length of the incremental hashes is too big, (and we send a maximum of
+        // `MAX_INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_LEN` here).  If this call ever does error, it's
+        // a programmer bug!  Check to see what changed in `push_snapshot_hashes()` and handle the
+        // new error condition here.
         self.cluster_info
             .push_snapshot_hashes(
                 latest_snapshot_hashes.full.clone_for_crds(),
-                latest_snapshot_hashes
-                    .incremental
-                    .iter()
-                    .map(AsSnapshotHash::clone_for_crds)
-                    .collect(),
+                incremental_snapshot_hashes,
             )
             .expect(
                 "Bug! The programmer contract has changed for push_snapshot_hashes() and a new \
@@ -140,10 +176,11 @@ impl SnapshotGossipManager {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 struct LatestSnapshotHashes {
     full: FullSnapshotHash,
-    incremental: Option<IncrementalSnapshotHash>,
+    /// The chain of incremental snapshots on top of the full snapshot, in order
+    incremental: Vec<IncrementalSnapshotHash>,
 }
 
 trait AsSnapshotHash {
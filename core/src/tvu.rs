@@ -53,6 +53,7 @@ use {
     solana_rpc::{
         max_slots::MaxSlots, optimistically_confirmed_bank_tracker::BankNotificationSenderConfig,
         rpc_subscriptions::RpcSubscriptions, slot_status_notifier::SlotStatusNotifier,
+        slot_warper::WarpRequestReceiver,
     },
     solana_runtime::{
         bank::MAX_ALPENGLOW_VOTE_ACCOUNTS, bank_forks::BankForks, commitment::BlockCommitmentCache,
@@ -127,10 +128,14 @@ pub struct TvuConfig {
     // Validators which should be given priority when serving repairs
     pub repair_whitelist: Arc<RwLock<HashSet<Pubkey>>>,
     pub wait_for_vote_to_start_leader: bool,
+    // Root every block right away without voting, for automine test clusters
+    pub automine: bool,
     pub replay_forks_threads: NonZeroUsize,
     pub replay_transactions_threads: NonZeroUsize,
     pub shred_sigverify_threads: NonZeroUsize,
     pub xdp_sender: Option<XdpSender>,
+    // Warp requests from the test validator RPC methods
+    pub warp_request_receiver: Option<WarpRequestReceiver>,
 }
 
 impl Default for TvuConfig {
@@ -141,10 +146,12 @@ impl Default for TvuConfig {
             repair_validators: None,
             repair_whitelist: Arc::new(RwLock::new(HashSet::default())),
             wait_for_vote_to_start_leader: false,
+            automine: false,
             replay_forks_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             replay_transactions_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             shred_sigverify_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             xdp_sender: None,
+            warp_request_receiver: None,
         }
     }
 }
@@ -495,6 +502,7 @@ impl Tvu {
             duplicate_confirmed_slots_receiver,
             gossip_verified_vote_hash_receiver,
             popular_pruned_forks_receiver,
+            warp_request_receiver: tvu_config.warp_request_receiver,
         };
 
         let replay_stage_config = ReplayStageConfig {
@@ -504,6 +512,7 @@ impl Tvu {
             leader_schedule_cache: leader_schedule_cache.clone(),
             block_commitment_cache,
             wait_for_vote_to_start_leader: tvu_config.wait_for_vote_to_start_leader,
+            automine: tvu_config.automine,
             tower_storage: tower_storage.clone(),
             wait_to_vote_slot,
             replay_forks_threads: tvu_config.replay_forks_threads,
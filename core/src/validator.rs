@@ -97,6 +97,7 @@ use {
     solana_metrics::{datapoint_info, metrics::metrics_config_sanity_check},
     solana_net_utils::SocketAddrSpace,
     solana_poh::{
+        automine::{automine_channel, AutomineConfig},
         poh_controller::PohController,
         poh_recorder::PohRecorder,
         poh_service::{self, PohService},
@@ -116,6 +117,7 @@ use {
         rpc_pubsub_service::{PubSubConfig, PubSubService},
         rpc_service::{JsonRpcService, JsonRpcServiceConfig, RpcTpuClientArgs},
         rpc_subscriptions::RpcSubscriptions,
+        slot_warper::slot_warper_channel,
         transaction_notifier_interface::TransactionNotifierArc,
         transaction_status_service::TransactionStatusService,
     },
@@ -124,7 +126,10 @@ use {
             AbsRequestHandlers, AccountsBackgroundService, DroppedSlotsReceiver,
             PendingSnapshotPackages, PrunedBanksRequestHandler, SnapshotRequestHandler,
         },
-        bank::Bank,
+        bank::{
+            account_fallback::BankAccountFallback, instruction_recorder::BankInstructionRecorder,
+            Bank,
+        },
         bank_forks::BankForks,
         commitment::BlockCommitmentCache,
         dependency_tracker::DependencyTracker,
@@ -369,6 +374,11 @@ pub struct ValidatorConfig {
     pub tower_storage: Arc<dyn TowerStorage>,
     pub vote_history_storage: Arc<dyn VoteHistoryStorage>,
     pub debug_keys: Option<Arc<HashSet<Pubkey>>>,
+    /// Receives the top-level instructions executed while replaying.
+    pub instruction_recorder: Option<Arc<dyn BankInstructionRecorder>>,
+    /// Supplies the accounts missing from the banks, e.g. to fork another
+    /// cluster.
+    pub account_fallback: Option<Arc<dyn BankAccountFallback>>,
     pub contact_debug_interval: u64,
     pub contact_save_interval: u64,
     pub send_transaction_service_config: SendTransactionServiceConfig,
@@ -380,6 +390,10 @@ pub struct ValidatorConfig {
     pub enforce_ulimit_nofile: bool,
     pub poh_pinned_cpu_core: usize,
     pub poh_hashes_per_batch: u64,
+    /// Mines blocks on demand rather than at the pace of the PoH config,
+    /// rooting them right away without voting. Only for single node test
+    /// clusters.
+    pub automine: Option<AutomineConfig>,
     pub process_ledger_before_services: bool,
     pub accounts_db_config: AccountsDbConfig,
     pub warp_slot: Option<Slot>,
@@ -449,6 +463,8 @@ impl ValidatorConfig {
             tower_storage: Arc::new(NullTowerStorage::default()),
             vote_history_storage: Arc::new(NullVoteHistoryStorage::default()),
             debug_keys: None,
+            instruction_recorder: None,
+            account_fallback: None,
             contact_debug_interval: DEFAULT_CONTACT_DEBUG_INTERVAL_MILLIS,
             contact_save_interval: DEFAULT_CONTACT_SAVE_INTERVAL_MILLIS,
             send_transaction_service_config: SendTransactionServiceConfig::default(),
@@ -461,6 +477,7 @@ impl ValidatorConfig {
             enforce_ulimit_nofile: false,
             poh_pinned_cpu_core: poh_service::DEFAULT_PINNED_CPU_CORE,
             poh_hashes_per_batch: poh_service::DEFAULT_HASHES_PER_BATCH,
+            automine: None,
             process_ledger_before_services: false,
             warp_slot: None,
             accounts_db_skip_shrink: false,
@@ -1254,6 +1271,13 @@ impl Validator {
                 .unwrap()
         });
 
+        let (block_miner, automine) = config.automine.as_ref().map(automine_channel).unzip();
+        let (slot_warper, warp_request_receiver) = config
+            .rpc_config
+            .enable_test_methods
+            .then(slot_warper_channel)
+            .unzip();
+
         let rpc_override_health_check =
             Arc::new(AtomicBool::new(config.rpc_config.disable_health_check));
         let (
@@ -1313,6 +1337,8 @@ impl Validator {
                 max_complete_transaction_status_slot: max_complete_transaction_status_slot.clone(),
                 prioritization_fee_cache: prioritization_fee_cache.clone(),
                 rpc_tpu_client_args,
+                block_miner,
+                slot_warper,
             };
             let json_rpc_service =
                 JsonRpcService::new_with_config(rpc_svc_config).map_err(ValidatorError::Other)?;
@@ -1485,6 +1511,7 @@ impl Validator {
             poh_service_message_receiver,
             migration_status.clone(),
             record_receiver_sender,
+            automine,
         );
 
         let replay_highest_frozen = Arc::new(ReplayHighestFrozen::default());
@@ -1663,10 +1690,12 @@ impl Validator {
                 repair_validators: config.repair_validators.clone(),
                 repair_whitelist: config.repair_whitelist.clone(),
                 wait_for_vote_to_start_leader,
+                automine: config.automine.is_some(),
                 replay_forks_threads: config.replay_forks_threads,
                 replay_transactions_threads: config.replay_transactions_threads,
                 shred_sigverify_threads: config.tvu_shred_sigverify_threads,
                 xdp_sender: xdp_sender.clone(),
+                warp_request_receiver,
             },
             &max_slots,
             block_metadata_notifier,
@@ -2225,6 +2254,8 @@ fn load_blockstore(
         halt_at_slot: None,
         new_hard_forks: config.new_hard_forks.clone(),
         debug_keys: config.debug_keys.clone(),
+        instruction_recorder: config.instruction_recorder.clone(),
+        account_fallback: config.account_fallback.clone(),
         accounts_db_config: config.accounts_db_config.clone(),
         accounts_db_skip_shrink: config.accounts_db_skip_shrink,
         accounts_db_force_initial_clean: config.accounts_db_force_initial_clean,
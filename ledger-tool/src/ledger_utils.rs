@@ -2,6 +2,7 @@ use {
     crate::LEDGER_TOOL_DIRECTORY,
     agave_snapshots::{
         paths::{self as snapshot_paths, BANK_SNAPSHOTS_DIR},
+        snapshot_archive_info::SnapshotArchiveInfoGetter,
         snapshot_config::{SnapshotConfig, SnapshotUsage},
         snapshot_hash::StartingSnapshotHashes,
     },
@@ -161,10 +162,12 @@ pub fn load_and_process_ledger(
             snapshot_paths::get_highest_full_snapshot_archive_slot(&full_snapshot_archives_dir)
         {
             let incremental_snapshot_slot =
-                snapshot_paths::get_highest_incremental_snapshot_archive_slot(
+                snapshot_paths::get_highest_incremental_snapshot_archive_chain(
                     &incremental_snapshot_archives_dir,
                     full_snapshot_slot,
                 )
+                .last()
+                .map(SnapshotArchiveInfoGetter::slot)
                 .unwrap_or_default();
             starting_slot = std::cmp::max(full_snapshot_slot, incremental_snapshot_slot);
         }
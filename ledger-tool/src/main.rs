@@ -11,6 +11,7 @@ use {
             SlotBankHash,
         },
         program::*,
+        verify_snapshot::*,
     },
     agave_feature_set::{self as feature_set, FeatureSet},
     agave_reserved_account_keys::ReservedAccountKeys,
@@ -54,6 +55,7 @@ use {
         blockstore_processor::{
             ProcessSlotCallback, TransactionStatusMessage, TransactionStatusSender,
         },
+        instruction_fixtures::InstructionFixtureWriter,
     },
     solana_measure::{measure::Measure, measure_time},
     solana_message::SimpleAddressLoader,
@@ -63,6 +65,7 @@ use {
     solana_runtime::{
         bank::{
             bank_hash_details::{self, SlotDetails, TransactionDetails},
+            instruction_recorder::BankInstructionRecorder,
             Bank, RewardCalculationEvent,
         },
         bank_forks::BankForks,
@@ -109,6 +112,7 @@ mod ledger_path;
 mod ledger_utils;
 mod output;
 mod program;
+mod verify_snapshot;
 
 fn render_dot(dot: String, output_file: &str, output_format: &str) -> io::Result<()> {
     let mut child = Command::new("dot")
@@ -609,6 +613,24 @@ struct SlotRecorderConfig {
     file: File,
 }
 
+fn setup_instruction_fixture_recording(
+    arg_matches: &ArgMatches,
+) -> Option<Arc<dyn BankInstructionRecorder>> {
+    let output_dir = Path::new(arg_matches.value_of_os("record_instruction_fixtures")?);
+    let program_ids = pubkeys_of(arg_matches, "record_instruction_fixtures_program_id")
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    let writer = InstructionFixtureWriter::new(output_dir, program_ids).unwrap_or_else(|err| {
+        eprintln!(
+            "Unable to create instruction fixture directory {}: {err}",
+            output_dir.display()
+        );
+        exit(1);
+    });
+    Some(Arc::new(writer))
+}
+
 fn setup_slot_recording(
     arg_matches: &ArgMatches,
 ) -> (Option<ProcessSlotCallback>, Option<SlotRecorderConfig>) {
@@ -1226,6 +1248,28 @@ fn main() {
                              transactions details for the slot",
                         ),
                 )
+                .arg(
+                    Arg::with_name("record_instruction_fixtures")
+                        .long("record-instruction-fixtures")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .requires("record_instruction_fixtures_program_id")
+                        .help(
+                            "Write an SVM test harness fixture into DIR for every replayed \
+                             top-level instruction that invokes one of the programs given with \
+                             --record-instruction-fixtures-program-id",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("record_instruction_fixtures_program_id")
+                        .long("record-instruction-fixtures-program-id")
+                        .value_name("ADDRESS")
+                        .validator(is_pubkey)
+                        .multiple(true)
+                        .takes_value(true)
+                        .requires("record_instruction_fixtures")
+                        .help("Program whose instructions are recorded as fixtures"),
+                )
                 .arg(
                     Arg::with_name("abort_on_invalid_block")
                         .long("abort-on-invalid-block")
@@ -1455,6 +1499,19 @@ fn main() {
                         )
                         .conflicts_with("no_snapshot"),
                 )
+                .arg(
+                    Arg::with_name("chained")
+                        .long("chained")
+                        .takes_value(false)
+                        .requires("incremental")
+                        .help(
+                            "Base the incremental snapshot on the latest incremental snapshot \
+                             the ledger is loaded from, instead of on the full snapshot. The \
+                             incremental snapshot then only contains the changes since that \
+                             incremental snapshot, and loading it requires the whole chain of \
+                             incremental snapshots.",
+                        ),
+                )
                 .arg(
                     Arg::with_name("minimized")
                         .long("minimized")
@@ -1697,6 +1754,7 @@ fn main() {
                 .arg(&allow_dead_slots_arg),
         )
         .program_subcommand()
+        .verify_snapshot_subcommand()
         .get_matches();
 
     let logfile = value_t!(matches, "logfile", PathBuf).ok();
@@ -1723,6 +1781,7 @@ fn main() {
         ("bigtable", Some(arg_matches)) => bigtable_process_command(&ledger_path, arg_matches),
         ("blockstore", Some(arg_matches)) => blockstore_process_command(&ledger_path, arg_matches),
         ("program", Some(arg_matches)) => program(&ledger_path, arg_matches),
+        ("verify-snapshot", Some(arg_matches)) => verify_snapshot(&ledger_path, arg_matches),
         // This match case provides legacy support for commands that were previously top level
         // subcommands of the binary, but have been moved under the blockstore subcommand.
         ("analyze-storage", Some(_))
@@ -1868,6 +1927,8 @@ fn main() {
 
                     let (slot_callback, slot_recorder_config) = setup_slot_recording(arg_matches);
                     process_options.slot_callback = slot_callback;
+                    process_options.instruction_recorder =
+                        setup_instruction_fixture_recording(arg_matches);
                     let transaction_status_sender = slot_recorder_config
                         .as_ref()
                         .and_then(|config| config.transaction_status_sender.clone());
@@ -2537,13 +2598,25 @@ fn main() {
                             );
                             exit(1);
                         }
-                        let full_snapshot_slot = starting_snapshot_hashes.unwrap().full.0 .0;
-                        if bank.slot() <= full_snapshot_slot {
+                        let starting_snapshot_hashes = starting_snapshot_hashes.unwrap();
+                        let full_snapshot_slot = starting_snapshot_hashes.full.0 .0;
+                        let base_slot = if arg_matches.is_present("chained") {
+                            // The end of the chain of incremental snapshots the ledger was
+                            // loaded from
+                            starting_snapshot_hashes
+                                .incremental
+                                .last()
+                                .map(|incremental_snapshot_hash| incremental_snapshot_hash.0 .0)
+                                .unwrap_or(full_snapshot_slot)
+                        } else {
+                            full_snapshot_slot
+                        };
+                        if bank.slot() <= base_slot {
                             eprintln!(
                                 "Unable to create incremental snapshot: Slot must be greater than \
-                                 full snapshot slot. slot: {}, full snapshot slot: {}",
+                                 base slot. slot: {}, base slot: {}",
                                 bank.slot(),
-                                full_snapshot_slot,
+                                base_slot,
                             );
                             exit(1);
                         }
@@ -2552,7 +2625,7 @@ fn main() {
                             snapshot_bank_utils::bank_to_incremental_snapshot_archive(
                                 ledger_path,
                                 &bank,
-                                full_snapshot_slot,
+                                base_slot,
                                 Some(snapshot_version),
                                 output_directory.clone(),
                                 output_directory,
@@ -2568,7 +2641,7 @@ fn main() {
                              slot: {}: {}",
                             bank.slot(),
                             bank.hash(),
-                            full_snapshot_slot,
+                            base_slot,
                             incremental_snapshot_archive_info.path().display(),
                         );
                     } else {
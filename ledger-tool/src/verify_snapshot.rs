@@ -0,0 +1,359 @@
+use {
+    crate::{args::*, canonicalize_ledger_path, ledger_utils::*, LEDGER_TOOL_DIRECTORY},
+    agave_snapshots::snapshot_archive_info::{
+        FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo,
+    },
+    clap::{value_t_or_exit, values_t, App, Arg, ArgMatches, SubCommand},
+    log::*,
+    serde::{Deserialize, Serialize},
+    solana_accounts_db::utils::create_all_accounts_run_and_snapshot_dirs,
+    solana_clap_utils::{input_parsers::keypair_of, input_validators::is_keypair},
+    solana_clock::Slot,
+    solana_hash::Hash,
+    solana_runtime::{
+        runtime_config::RuntimeConfig,
+        snapshot_bank_utils::{self, SnapshotArchivesVerification},
+    },
+    solana_signer::Signer,
+    std::{
+        collections::HashMap,
+        fs::{self, File},
+        io::{self, Write},
+        path::{Path, PathBuf},
+        process::exit,
+        str::FromStr,
+        sync::Arc,
+    },
+};
+
+/// The results of verifying snapshot archives, in a form suitable for auditing
+///
+/// Values are recorded as strings so the report is readable without any
+/// Solana-specific tooling.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotVerificationReport {
+    pub full_snapshot_archive: String,
+    pub incremental_snapshot_archive: Option<String>,
+    pub genesis_hash: String,
+    pub slot: Slot,
+    pub full_snapshot_slot: Slot,
+    pub archive_snapshot_hash: String,
+    pub snapshot_hash: String,
+    pub calculated_snapshot_hash: String,
+    pub bank_hash: String,
+    pub calculated_bank_hash: String,
+    /// The bank hash expected for `slot`, if one was provided
+    pub known_good_bank_hash: Option<String>,
+    pub capitalization: u64,
+    pub calculated_capitalization: u64,
+    pub status_cache_error: Option<String>,
+    pub epoch_stakes_error: Option<String>,
+    pub verified: bool,
+}
+
+/// A verification report, signed by the keypair that ran the verification
+///
+/// The signature is over the compact JSON serialization of `report`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedSnapshotVerificationReport {
+    pub report: SnapshotVerificationReport,
+    pub signer: String,
+    pub signature: String,
+}
+
+pub trait VerifySnapshotSubCommand {
+    fn verify_snapshot_subcommand(self) -> Self;
+}
+
+impl VerifySnapshotSubCommand for App<'_, '_> {
+    fn verify_snapshot_subcommand(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("verify-snapshot")
+                .about(
+                    "Verify snapshot archives, such as those downloaded from an RPC node, and \
+                     emit a signed verification report",
+                )
+                .long_about(
+                    "Unpacks the snapshot archives, rebuilds the accounts storages, and \
+                     recalculates the accounts lattice hash, capitalization, and bank hash. The \
+                     status cache and epoch stakes are checked against the bank, and the bank \
+                     hash must match a known-good bank hash for the snapshot slot. The ledger \
+                     directory is only used for the genesis config and, by default, as scratch \
+                     space. Exits with an error if any check fails or no known-good bank hash is \
+                     given for the snapshot slot.",
+                )
+                .arg(load_genesis_arg())
+                .args(&accounts_db_args())
+                .arg(
+                    Arg::with_name("full_snapshot_archive")
+                        .long("full-snapshot-archive")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The full snapshot archive to verify"),
+                )
+                .arg(
+                    Arg::with_name("incremental_snapshot_archive")
+                        .long("incremental-snapshot-archive")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help(
+                            "The incremental snapshot archive to verify, on top of the full \
+                             snapshot archive",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("known_good_bank_hash")
+                        .long("known-good-bank-hash")
+                        .value_name("SLOT:HASH")
+                        .takes_value(true)
+                        .multiple(true)
+                        .validator(|value| parse_known_good_bank_hash(&value).map(|_| ()))
+                        .help(
+                            "A bank hash known to be correct for SLOT. May be specified multiple \
+                             times",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("known_good_bank_hashes_file")
+                        .long("known-good-bank-hashes-file")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help(
+                            "A file of known-good bank hashes, one SLOT:HASH per line. Blank \
+                             lines and lines starting with '#' are ignored",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("keypair")
+                        .long("keypair")
+                        .value_name("KEYPAIR")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_keypair)
+                        .help("Keypair used to sign the verification report"),
+                )
+                .arg(
+                    Arg::with_name("report_path")
+                        .long("report")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help("Write the signed verification report to FILE [default: stdout]"),
+                ),
+        )
+    }
+}
+
+pub fn verify_snapshot(ledger_path: &Path, matches: &ArgMatches<'_>) {
+    let ledger_path = canonicalize_ledger_path(ledger_path);
+    let genesis_config = open_genesis_config_by(&ledger_path, matches);
+    let keypair = keypair_of(matches, "keypair").unwrap_or_else(|| {
+        eprintln!("Failed to read keypair");
+        exit(1);
+    });
+
+    let full_snapshot_archive_path = value_t_or_exit!(matches, "full_snapshot_archive", PathBuf);
+    let full_snapshot_archive_info = FullSnapshotArchiveInfo::new_from_path(
+        full_snapshot_archive_path.clone(),
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("Invalid full snapshot archive {full_snapshot_archive_path:?}: {err}");
+        exit(1);
+    });
+    let incremental_snapshot_archive_path = matches
+        .value_of("incremental_snapshot_archive")
+        .map(PathBuf::from);
+    let incremental_snapshot_archive_info =
+        incremental_snapshot_archive_path.as_ref().map(|path| {
+            IncrementalSnapshotArchiveInfo::new_from_path(path.clone()).unwrap_or_else(|err| {
+                eprintln!("Invalid incremental snapshot archive {path:?}: {err}");
+                exit(1);
+            })
+        });
+
+    let known_good_bank_hashes = known_good_bank_hashes_of(matches).unwrap_or_else(|err| {
+        eprintln!("Failed to read known-good bank hashes: {err}");
+        exit(1);
+    });
+
+    // Unless accounts paths are given, snapshots are unpacked into scratch space under the
+    // ledger-tool directory so that the accounts of a validator using this ledger are not touched
+    let scratch_dir = ledger_path
+        .join(LEDGER_TOOL_DIRECTORY)
+        .join("verify_snapshot");
+    if scratch_dir.exists() {
+        fs::remove_dir_all(&scratch_dir).unwrap_or_else(|err| {
+            eprintln!("Failed to clean scratch directory {scratch_dir:?}: {err}");
+            exit(1);
+        });
+    }
+    let bank_snapshots_dir = scratch_dir.join("bank_snapshots");
+    fs::create_dir_all(&bank_snapshots_dir).unwrap_or_else(|err| {
+        eprintln!("Failed to create directory {bank_snapshots_dir:?}: {err}");
+        exit(1);
+    });
+    let account_paths = matches
+        .value_of("account_paths")
+        .map(|account_paths| account_paths.split(',').map(PathBuf::from).collect())
+        .unwrap_or_else(|| vec![scratch_dir.join("accounts")]);
+    let (account_paths, _account_snapshot_paths) =
+        create_all_accounts_run_and_snapshot_dirs(&account_paths).unwrap_or_else(|err| {
+            eprintln!("Failed to create accounts directories: {err}");
+            exit(1);
+        });
+
+    let verification = snapshot_bank_utils::verify_snapshot_archives(
+        &account_paths,
+        &bank_snapshots_dir,
+        &full_snapshot_archive_info,
+        incremental_snapshot_archive_info.as_ref(),
+        &genesis_config,
+        &RuntimeConfig::default(),
+        get_accounts_db_config(&ledger_path, matches),
+        Arc::default(),
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("Failed to load snapshot archives: {err}");
+        exit(1);
+    });
+
+    let known_good_bank_hash = known_good_bank_hashes.get(&verification.slot).copied();
+    if known_good_bank_hash.is_none() {
+        warn!(
+            "No known-good bank hash for slot {}, the snapshot is reported as unverified",
+            verification.slot
+        );
+    }
+    let report = SnapshotVerificationReport::new(
+        &full_snapshot_archive_path,
+        incremental_snapshot_archive_path.as_deref(),
+        &genesis_config.hash(),
+        &verification,
+        known_good_bank_hash,
+    );
+    let verified = report.verified;
+
+    let message = serde_json::to_vec(&report).unwrap();
+    let signed_report = SignedSnapshotVerificationReport {
+        signature: keypair.sign_message(&message).to_string(),
+        signer: keypair.pubkey().to_string(),
+        report,
+    };
+    let result = match matches.value_of("report_path") {
+        Some(report_path) => {
+            File::create(report_path).and_then(|file| write_signed_report(file, &signed_report))
+        }
+        None => write_signed_report(io::stdout(), &signed_report),
+    };
+    if let Err(err) = result {
+        eprintln!("Failed to write verification report: {err}");
+        exit(1);
+    }
+
+    if !verified {
+        if known_good_bank_hash.is_none() {
+            eprintln!(
+                "Snapshot verification failed: no known-good bank hash for slot {}",
+                verification.slot
+            );
+        } else {
+            eprintln!("Snapshot verification failed: {verification:?}");
+        }
+        exit(1);
+    }
+    info!(
+        "Snapshot verification succeeded for slot {}",
+        verification.slot
+    );
+}
+
+impl SnapshotVerificationReport {
+    fn new(
+        full_snapshot_archive_path: &Path,
+        incremental_snapshot_archive_path: Option<&Path>,
+        genesis_hash: &Hash,
+        verification: &SnapshotArchivesVerification,
+        known_good_bank_hash: Option<Hash>,
+    ) -> Self {
+        // Internal consistency alone doesn't verify the snapshot, its bank hash must be anchored
+        // to a known-good one
+        let verified = verification.is_ok() && known_good_bank_hash == Some(verification.bank_hash);
+        Self {
+            full_snapshot_archive: full_snapshot_archive_path.display().to_string(),
+            incremental_snapshot_archive: incremental_snapshot_archive_path
+                .map(|path| path.display().to_string()),
+            genesis_hash: genesis_hash.to_string(),
+            slot: verification.slot,
+            full_snapshot_slot: verification.full_snapshot_slot,
+            archive_snapshot_hash: verification.archive_snapshot_hash.0.to_string(),
+            snapshot_hash: verification.snapshot_hash.0.to_string(),
+            calculated_snapshot_hash: verification.calculated_snapshot_hash.0.to_string(),
+            bank_hash: verification.bank_hash.to_string(),
+            calculated_bank_hash: verification.calculated_bank_hash.to_string(),
+            known_good_bank_hash: known_good_bank_hash.map(|hash| hash.to_string()),
+            capitalization: verification.capitalization,
+            calculated_capitalization: verification.calculated_capitalization,
+            status_cache_error: verification
+                .status_cache
+                .as_ref()
+                .err()
+                .map(ToString::to_string),
+            epoch_stakes_error: verification
+                .epoch_stakes
+                .as_ref()
+                .err()
+                .map(ToString::to_string),
+            verified,
+        }
+    }
+}
+
+fn write_signed_report(
+    mut writer: impl Write,
+    signed_report: &SignedSnapshotVerificationReport,
+) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut writer, signed_report)?;
+    writeln!(writer)
+}
+
+fn parse_known_good_bank_hash(value: &str) -> Result<(Slot, Hash), String> {
+    let (slot, hash) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected SLOT:HASH, found '{value}'"))?;
+    let slot = slot
+        .trim()
+        .parse::<Slot>()
+        .map_err(|err| format!("invalid slot '{slot}': {err}"))?;
+    let hash =
+        Hash::from_str(hash.trim()).map_err(|err| format!("invalid hash '{hash}': {err}"))?;
+    Ok((slot, hash))
+}
+
+fn known_good_bank_hashes_of(matches: &ArgMatches<'_>) -> Result<HashMap<Slot, Hash>, String> {
+    let mut known_good_bank_hashes = HashMap::new();
+    let mut insert = |slot, hash| match known_good_bank_hashes.insert(slot, hash) {
+        Some(other_hash) if other_hash != hash => Err(format!(
+            "conflicting known-good bank hashes for slot {slot}: {other_hash} and {hash}"
+        )),
+        _ => Ok(()),
+    };
+
+    for value in values_t!(matches, "known_good_bank_hash", String).unwrap_or_default() {
+        let (slot, hash) = parse_known_good_bank_hash(&value)?;
+        insert(slot, hash)?;
+    }
+    if let Some(path) = matches.value_of("known_good_bank_hashes_file") {
+        let contents =
+            fs::read_to_string(path).map_err(|err| format!("failed to read {path}: {err}"))?;
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (slot, hash) = parse_known_good_bank_hash(line)?;
+            insert(slot, hash)?;
+        }
+    }
+    Ok(known_good_bank_hashes)
+}
@@ -92,10 +92,7 @@ pub fn load_bank_forks_from_genesis(
 
 fn get_snapshots_to_load(
     snapshot_config: &SnapshotConfig,
-) -> Option<(
-    FullSnapshotArchiveInfo,
-    Option<IncrementalSnapshotArchiveInfo>,
-)> {
+) -> Option<(FullSnapshotArchiveInfo, Vec<IncrementalSnapshotArchiveInfo>)> {
     if !snapshot_config.should_load_snapshots() {
         info!("Snapshots disabled");
         return None;
@@ -111,15 +108,15 @@ fn get_snapshots_to_load(
         return None;
     };
 
-    let incremental_snapshot_archive_info =
-        snapshot_paths::get_highest_incremental_snapshot_archive_info(
+    let incremental_snapshot_archive_infos =
+        snapshot_paths::get_highest_incremental_snapshot_archive_chain(
             &snapshot_config.incremental_snapshot_archives_dir,
             full_snapshot_archive_info.slot(),
         );
 
     Some((
         full_snapshot_archive_info,
-        incremental_snapshot_archive_info,
+        incremental_snapshot_archive_infos,
     ))
 }
 
@@ -132,7 +129,7 @@ pub fn try_load_bank_forks_from_snapshot(
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     exit: Arc<AtomicBool>,
 ) -> Result<Option<BankAndHashes>, BankForksUtilsError> {
-    let Some((full_snapshot_archive_info, incremental_snapshot_archive_info)) =
+    let Some((full_snapshot_archive_info, incremental_snapshot_archive_infos)) =
         get_snapshots_to_load(snapshot_config)
     else {
         return Ok(None);
@@ -152,8 +149,8 @@ pub fn try_load_bank_forks_from_snapshot(
 
     let latest_snapshot_archive_slot = std::cmp::max(
         full_snapshot_archive_info.slot(),
-        incremental_snapshot_archive_info
-            .as_ref()
+        incremental_snapshot_archive_infos
+            .last()
             .map(SnapshotArchiveInfoGetter::slot)
             .unwrap_or(0),
     );
@@ -214,11 +211,11 @@ pub fn try_load_bank_forks_from_snapshot(
         // the archives, causing the out-of-memory problem.  So, purge the snapshot dirs upfront before loading from the archive.
         snapshot_utils::purge_all_bank_snapshots(&snapshot_config.bank_snapshots_dir);
 
-        snapshot_bank_utils::bank_from_snapshot_archives(
+        snapshot_bank_utils::bank_from_snapshot_archive_chain(
             account_paths,
             &snapshot_config.bank_snapshots_dir,
             &full_snapshot_archive_info,
-            incremental_snapshot_archive_info.as_ref(),
+            &incremental_snapshot_archive_infos,
             genesis_config,
             &process_options.runtime_config,
             process_options.debug_keys.clone(),
@@ -233,10 +230,15 @@ pub fn try_load_bank_forks_from_snapshot(
         .map_err(|err| BankForksUtilsError::BankFromSnapshotsArchive {
             source: Box::new(err),
             full_snapshot_archive: full_snapshot_archive_info.path().display().to_string(),
-            incremental_snapshot_archive: incremental_snapshot_archive_info
-                .as_ref()
-                .map(|archive| archive.path().display().to_string())
-                .unwrap_or("none".to_string()),
+            incremental_snapshot_archive: if incremental_snapshot_archive_infos.is_empty() {
+                "none".to_string()
+            } else {
+                incremental_snapshot_archive_infos
+                    .iter()
+                    .map(|archive| archive.path().display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
         })?
     };
 
@@ -263,16 +265,19 @@ pub fn try_load_bank_forks_from_snapshot(
         full_snapshot_archive_info.slot(),
         *full_snapshot_archive_info.hash(),
     ));
-    let incremental_snapshot_hash =
-        incremental_snapshot_archive_info.map(|incremental_snapshot_archive_info| {
+    // Peers bootstrapping from this node download the whole chain of incremental snapshots
+    let incremental_snapshot_hashes = incremental_snapshot_archive_infos
+        .iter()
+        .map(|incremental_snapshot_archive_info| {
             IncrementalSnapshotHash((
                 incremental_snapshot_archive_info.slot(),
                 *incremental_snapshot_archive_info.hash(),
             ))
-        });
+        })
+        .collect();
     let starting_snapshot_hashes = StartingSnapshotHashes {
         full: full_snapshot_hash,
-        incremental: incremental_snapshot_hash,
+        incremental: incremental_snapshot_hashes,
     };
     bank.register_hard_forks(process_options.new_hard_forks.as_ref());
 
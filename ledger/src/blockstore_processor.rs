@@ -16,7 +16,8 @@ use {
     rayon::{prelude::*, ThreadPool},
     scopeguard::defer,
     solana_accounts_db::{
-        accounts_db::AccountsDbConfig, accounts_update_notifier_interface::AccountsUpdateNotifier,
+        accounts_db::{AccountsDbConfig, ReadCacheCaller},
+        accounts_update_notifier_interface::AccountsUpdateNotifier,
     },
     solana_clock::{Slot, MAX_PROCESSING_AGE},
     solana_cost_model::{cost_model::CostModel, transaction_cost::TransactionCost},
@@ -28,7 +29,10 @@ use {
     solana_metrics::datapoint_error,
     solana_pubkey::Pubkey,
     solana_runtime::{
-        bank::{Bank, PreCommitResult, TransactionBalancesSet},
+        bank::{
+            account_fallback::BankAccountFallback, instruction_recorder::BankInstructionRecorder,
+            Bank, PreCommitResult, TransactionBalancesSet,
+        },
         bank_forks::BankForks,
         bank_utils,
         commitment::VOTE_THRESHOLD_SIZE,
@@ -141,6 +145,7 @@ fn create_thread_pool(num_threads: usize) -> ThreadPool {
     rayon::ThreadPoolBuilder::new()
         .num_threads(num_threads)
         .thread_name(|i| format!("solReplayTx{i:02}"))
+        .start_handler(|_| ReadCacheCaller::Replay.set_for_current_thread())
         .build()
         .expect("new rayon threadpool")
 }
@@ -840,6 +845,11 @@ pub struct ProcessOptions {
     pub hash_overrides: Option<HashOverrides>,
     pub abort_on_invalid_block: bool,
     pub no_block_cost_limits: bool,
+    /// Receives the top-level instructions executed while processing the
+    /// blockstore, starting from the root bank.
+    pub instruction_recorder: Option<Arc<dyn BankInstructionRecorder>>,
+    /// Supplies the accounts missing from the root bank and its descendants.
+    pub account_fallback: Option<Arc<dyn BankAccountFallback>>,
 }
 
 pub fn test_process_blockstore(
@@ -948,6 +958,14 @@ pub fn process_blockstore_from_root(
                 .unwrap()
                 .set_limits(u64::MAX, u64::MAX, u64::MAX);
         }
+        if let Some(instruction_recorder) = &opts.instruction_recorder {
+            info!("Recording top-level instructions of replayed transactions");
+            bank.set_instruction_recorder(Some(Arc::clone(instruction_recorder)));
+        }
+        if let Some(account_fallback) = &opts.account_fallback {
+            info!("Loading missing accounts from the account fallback");
+            bank.set_account_fallback(Arc::clone(account_fallback));
+        }
         assert!(bank.parent().is_none());
         (bank.slot(), bank.hash())
     };
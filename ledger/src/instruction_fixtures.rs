@@ -0,0 +1,189 @@
+//! Capture of instruction fixtures while replaying the ledger.
+//!
+//! Top-level instructions invoking one of the selected programs are written
+//! out as `InstrFixture` protobufs, the format consumed by the SVM test
+//! harness, so that they can be re-executed in isolation. The sysvars and
+//! program data accounts that an instruction may read are captured along
+//! with its own accounts, before it is executed.
+
+use {
+    log::*,
+    prost::Message,
+    solana_account::{state_traits::StateMut, Account},
+    solana_instruction::error::InstructionError,
+    solana_loader_v3_interface::state::UpgradeableLoaderState,
+    solana_pubkey::Pubkey,
+    solana_runtime::bank::{instruction_recorder::BankInstructionRecorder, Bank},
+    solana_sdk_ids::{bpf_loader_upgradeable, sysvar},
+    solana_svm::instruction_recorder::RecordedInstruction,
+    solana_svm_test_harness_fixture::{
+        instr_effects::InstrEffects,
+        proto::{
+            EpochContext, FeatureSet as ProtoFeatureSet, FixtureMetadata, InstrAcct, InstrContext,
+            InstrFixture, SlotContext,
+        },
+    },
+    std::{
+        collections::HashSet,
+        fs, io,
+        path::{Path, PathBuf},
+    },
+};
+
+/// Entrypoint of the harness that executes instruction fixtures.
+const INSTR_EXECUTE_ENTRYPOINT: &str = "sol_compat_instr_execute_v1";
+
+/// Sysvars that programs may read through the sysvar cache.
+const SYSVAR_IDS: [Pubkey; 7] = [
+    sysvar::clock::ID,
+    sysvar::epoch_rewards::ID,
+    sysvar::epoch_schedule::ID,
+    sysvar::last_restart_slot::ID,
+    sysvar::rent::ID,
+    sysvar::slot_hashes::ID,
+    sysvar::stake_history::ID,
+];
+
+/// Writes a fixture for every top-level instruction invoking one of
+/// `program_ids`.
+pub struct InstructionFixtureWriter {
+    output_dir: PathBuf,
+    program_ids: HashSet<Pubkey>,
+}
+
+impl InstructionFixtureWriter {
+    pub fn new(output_dir: &Path, program_ids: HashSet<Pubkey>) -> io::Result<Self> {
+        fs::create_dir_all(output_dir)?;
+        Ok(Self {
+            output_dir: output_dir.to_path_buf(),
+            program_ids,
+        })
+    }
+
+    fn write_fixture(&self, bank: &Bank, instruction: RecordedInstruction) -> io::Result<()> {
+        let path = self.output_dir.join(format!(
+            "{}_{}_{}.fix",
+            bank.slot(),
+            instruction.signature,
+            instruction.instruction_index,
+        ));
+        let fixture = instr_fixture(bank, instruction)?;
+        fs::write(path, fixture.encode_to_vec())
+    }
+}
+
+impl BankInstructionRecorder for InstructionFixtureWriter {
+    fn should_record(&self, program_id: &Pubkey) -> bool {
+        self.program_ids.contains(program_id)
+    }
+
+    fn capture_extra_accounts(
+        &self,
+        bank: &Bank,
+        accounts: &[(Pubkey, Account)],
+    ) -> Vec<(Pubkey, Account)> {
+        let programdata_addresses = accounts
+            .iter()
+            .filter_map(|(_, account)| programdata_address(account));
+        let mut captured_keys: HashSet<Pubkey> =
+            accounts.iter().map(|(pubkey, _)| *pubkey).collect();
+        SYSVAR_IDS
+            .into_iter()
+            .chain(programdata_addresses)
+            .filter(|pubkey| captured_keys.insert(*pubkey))
+            .filter_map(|pubkey| Some((pubkey, bank.get_account(&pubkey)?.into())))
+            .collect()
+    }
+
+    fn record(&self, bank: &Bank, instruction: RecordedInstruction) {
+        let signature = instruction.signature;
+        if let Err(err) = self.write_fixture(bank, instruction) {
+            warn!("Unable to write instruction fixture for transaction {signature}: {err}");
+        }
+    }
+}
+
+/// Builds the fixture for `instruction`, with its own accounts followed by
+/// the sysvars and program data accounts it may have read.
+fn instr_fixture(bank: &Bank, instruction: RecordedInstruction) -> io::Result<InstrFixture> {
+    let RecordedInstruction {
+        program_id,
+        instruction_accounts,
+        data,
+        mut accounts,
+        extra_accounts,
+        compute_units_available,
+        result,
+        resulting_accounts,
+        compute_units_remaining,
+        return_data,
+        ..
+    } = instruction;
+
+    let instr_accounts = instruction_accounts
+        .iter()
+        .map(|account_meta| {
+            let index = accounts
+                .iter()
+                .position(|(pubkey, _)| *pubkey == account_meta.pubkey)
+                .ok_or_else(|| {
+                    io::Error::other(format!(
+                        "account {} of the instruction was not captured",
+                        account_meta.pubkey
+                    ))
+                })?;
+            Ok(InstrAcct {
+                index: index as u32,
+                is_writable: account_meta.is_writable,
+                is_signer: account_meta.is_signer,
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    accounts.extend(extra_accounts);
+
+    let effects = InstrEffects {
+        custom_err: match result {
+            Err(InstructionError::Custom(code)) => Some(code),
+            _ => None,
+        },
+        result: result.err(),
+        resulting_accounts,
+        cu_avail: compute_units_remaining,
+        return_data,
+        logs: vec![],
+    };
+
+    Ok(InstrFixture {
+        metadata: Some(FixtureMetadata {
+            fn_entrypoint: INSTR_EXECUTE_ENTRYPOINT.to_string(),
+        }),
+        input: Some(InstrContext {
+            program_id: program_id.to_bytes().to_vec(),
+            accounts: accounts.into_iter().map(Into::into).collect(),
+            instr_accounts,
+            data,
+            cu_avail: compute_units_available,
+            slot_context: Some(SlotContext {
+                slot: bank.slot(),
+                ..SlotContext::default()
+            }),
+            epoch_context: Some(EpochContext {
+                features: Some(ProtoFeatureSet::from(&*bank.feature_set)),
+                ..EpochContext::default()
+            }),
+        }),
+        output: Some(effects.into()),
+    })
+}
+
+fn programdata_address(account: &Account) -> Option<Pubkey> {
+    if !bpf_loader_upgradeable::check_id(&account.owner) {
+        return None;
+    }
+    match account.state() {
+        Ok(UpgradeableLoaderState::Program {
+            programdata_address,
+        }) => Some(programdata_address),
+        _ => None,
+    }
+}
@@ -22,6 +22,7 @@ pub mod blockstore_processor;
 pub mod entry_notifier_interface;
 pub mod entry_notifier_service;
 pub mod genesis_utils;
+pub mod instruction_fixtures;
 pub mod leader_schedule_cache;
 pub mod next_slots_iterator;
 pub mod rooted_slot_iterator;
@@ -36,6 +36,8 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         tower_storage: config.tower_storage.clone(),
         vote_history_storage: config.vote_history_storage.clone(),
         debug_keys: config.debug_keys.clone(),
+        instruction_recorder: config.instruction_recorder.clone(),
+        account_fallback: config.account_fallback.clone(),
         contact_debug_interval: config.contact_debug_interval,
         contact_save_interval: config.contact_save_interval,
         send_transaction_service_config: config.send_transaction_service_config.clone(),
@@ -57,6 +59,7 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
             .map(|name| (name.clone(), Arc::new(AtomicBool::new(false))))
             .collect(),
         poh_hashes_per_batch: config.poh_hashes_per_batch,
+        automine: config.automine.clone(),
         process_ledger_before_services: config.process_ledger_before_services,
         no_wait_for_vote_to_start_leader: config.no_wait_for_vote_to_start_leader,
         accounts_db_config: config.accounts_db_config.clone(),
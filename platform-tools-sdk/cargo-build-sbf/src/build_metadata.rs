@@ -0,0 +1,105 @@
+use {
+    log::{error, info, warn},
+    serde::Serialize,
+    sha2::{Digest, Sha256},
+    std::{
+        fs::{self, File},
+        path::Path,
+        process::{exit, Command},
+    },
+};
+
+/// Build metadata of a program, which `solana program deploy --build-metadata`
+/// publishes on-chain along with it.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
+    toolchain: String,
+    elf_hash: String,
+}
+
+// The SHA-256 hash of a program, without the zero bytes that pad it when it
+// is stored on-chain.
+fn elf_hash(program: &[u8]) -> String {
+    let len = program
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |position| position.saturating_add(1));
+    format!("{:x}", Sha256::digest(&program[..len]))
+}
+
+// The commit that the package is checked out at, if it's in a git repository.
+fn git_commit(package_dir: &Path) -> Option<String> {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .arg("-C")
+            .arg(package_dir)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let commit = git(&["rev-parse", "HEAD"])?;
+    if git(&["status", "--porcelain", "--untracked-files=no"])
+        .is_some_and(|status| !status.is_empty())
+    {
+        warn!(
+            "{} has uncommitted changes, which the build metadata of commit {commit} doesn't \
+             include",
+            package_dir.display(),
+        );
+    }
+    Some(commit)
+}
+
+pub(crate) fn write_build_metadata(
+    package: &cargo_metadata::Package,
+    platform_tools_version: &str,
+    program_so: &Path,
+    build_metadata_json: &Path,
+) {
+    let program = fs::read(program_so).unwrap_or_else(|err| {
+        error!("Unable to read {}: {}", program_so.display(), err);
+        exit(1);
+    });
+    let build_metadata = BuildMetadata {
+        repository: package.repository.clone(),
+        commit: package
+            .manifest_path
+            .parent()
+            .and_then(|package_dir| git_commit(package_dir.as_std_path())),
+        toolchain: format!("platform-tools {platform_tools_version}"),
+        elf_hash: elf_hash(&program),
+    };
+    let file = File::create(build_metadata_json).unwrap_or_else(|err| {
+        error!(
+            "Unable to create {}: {}",
+            build_metadata_json.display(),
+            err
+        );
+        exit(1);
+    });
+    serde_json::to_writer_pretty(file, &build_metadata).unwrap_or_else(|err| {
+        error!("Unable to write {}: {}", build_metadata_json.display(), err);
+        exit(1);
+    });
+    info!("Wrote {}", build_metadata_json.display());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elf_hash() {
+        // The SHA-256 hash of "abc"
+        let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(elf_hash(b"abc"), hash);
+        assert_eq!(elf_hash(b"abc\0\0\0"), hash);
+    }
+}
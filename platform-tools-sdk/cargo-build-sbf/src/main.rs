@@ -1,3 +1,4 @@
+mod build_metadata;
 mod post_processing;
 mod syscalls;
 mod toolchain;
@@ -33,6 +34,7 @@ pub struct Config<'a> {
     sbf_out_dir: Option<PathBuf>,
     platform_tools_version: Option<&'a str>,
     dump: bool,
+    build_metadata: bool,
     features: Vec<String>,
     force_tools_install: bool,
     skip_tools_install: bool,
@@ -61,6 +63,7 @@ impl Default for Config<'_> {
             sbf_out_dir: None,
             platform_tools_version: None,
             dump: false,
+            build_metadata: false,
             features: vec![],
             force_tools_install: false,
             skip_tools_install: false,
@@ -322,11 +325,17 @@ fn build_solana(config: Config, manifest_path: Option<PathBuf>) {
                 prepare_environment(&config, Some(root_package), &metadata);
             let platform_tools_dir =
                 make_platform_tools_path_for_version(&validated_toolchain_version);
-            invoke_cargo(&config, &platform_tools_dir, validated_toolchain_version);
+            invoke_cargo(
+                &config,
+                &platform_tools_dir,
+                validated_toolchain_version.clone(),
+            );
             post_process(
                 &config,
                 &platform_tools_dir,
                 target_dir.as_ref(),
+                root_package,
+                &validated_toolchain_version,
                 program_name,
             );
             return;
@@ -335,7 +344,11 @@ fn build_solana(config: Config, manifest_path: Option<PathBuf>) {
 
     let validated_toolchain_version = prepare_environment(&config, None, &metadata);
     let platform_tools_dir = make_platform_tools_path_for_version(&validated_toolchain_version);
-    invoke_cargo(&config, &platform_tools_dir, validated_toolchain_version);
+    invoke_cargo(
+        &config,
+        &platform_tools_dir,
+        validated_toolchain_version.clone(),
+    );
 
     let all_sbf_packages = metadata
         .packages
@@ -358,6 +371,8 @@ fn build_solana(config: Config, manifest_path: Option<PathBuf>) {
             &config,
             &platform_tools_dir,
             target_dir.as_ref(),
+            package,
+            &validated_toolchain_version,
             program_name,
         );
     }
@@ -425,6 +440,16 @@ fn main() {
             "Dump ELF information to a text file on success. Requires `rustfilt` to demangle Rust \
              symbols.",
         ))
+        .arg(
+            Arg::new("build_metadata")
+                .long("build-metadata")
+                .takes_value(false)
+                .help(
+                    "Write the source repository, git commit, toolchain and ELF hash of each \
+                     program to `program-build-metadata.json` on success, to publish with `solana \
+                     program deploy --build-metadata`",
+                ),
+        )
         .arg(
             Arg::new("features")
                 .long("features")
@@ -620,6 +645,7 @@ fn main() {
         }),
         platform_tools_version: matches.value_of("tools_version"),
         dump: matches.is_present("dump"),
+        build_metadata: matches.is_present("build_metadata"),
         features: matches.values_of_t("features").ok().unwrap_or_default(),
         force_tools_install: matches.is_present("force_tools_install"),
         skip_tools_install: matches.is_present("skip_tools_install"),
@@ -1,5 +1,6 @@
 use {
     crate::{
+        build_metadata::write_build_metadata,
         spawn,
         syscalls::SYSCALLS,
         toolchain::rust_target_triple,
@@ -130,6 +131,8 @@ pub(crate) fn post_process(
     config: &Config,
     platform_tools_dir: &Path,
     target_directory: &Path,
+    package: &cargo_metadata::Package,
+    platform_tools_version: &str,
     program_name: Option<String>,
 ) {
     let sbf_out_dir = config
@@ -183,6 +186,18 @@ pub(crate) fn post_process(
             )
         };
 
+        let build_metadata_json = config.build_metadata.then(|| {
+            let build_metadata_json =
+                program_so.with_file_name(format!("{program_name}-build-metadata.json"));
+            write_build_metadata(
+                package,
+                platform_tools_version,
+                &program_so,
+                &build_metadata_json,
+            );
+            build_metadata_json
+        });
+
         if config.dump && file_older_or_missing(&program_unstripped_so, &program_dump) {
             let mangled_name = format!("{}.mangled", program_dump.display());
             {
@@ -239,6 +254,10 @@ pub(crate) fn post_process(
         if !config.debug {
             info!("To deploy this program:");
             info!("  $ solana program deploy {}", program_so.display());
+            if let Some(build_metadata_json) = &build_metadata_json {
+                info!("To also publish its build metadata, add:");
+                info!("  --build-metadata {}", build_metadata_json.display());
+            }
             info!("The program address will default to this keypair (override with --program-id):");
             info!("  {}", deploy_keypair.display());
         }
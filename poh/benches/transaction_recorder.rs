@@ -90,6 +90,7 @@ fn bench_record_transactions(c: &mut Criterion) {
         poh_service_receiver,
         Arc::new(MigrationStatus::default()),
         record_receiver_sender,
+        None,
     );
     poh_controller
         .set_bank_sync(BankWithScheduler::new_without_scheduler(bank.clone()))
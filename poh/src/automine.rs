@@ -0,0 +1,71 @@
+//! Mining blocks on demand, for single node test clusters.
+//!
+//! Rather than ticking at the pace of the PoH config, a PoH service in
+//! automine mode idles in the working bank until transactions are recorded
+//! in it, a block is requested through a [`BlockMiner`], or the configured
+//! interval elapses, and then ticks through the rest of the slot at once.
+use {
+    crossbeam_channel::{bounded, unbounded, Receiver, RecvTimeoutError, Sender},
+    solana_clock::Slot,
+    std::time::{Duration, Instant},
+};
+
+/// How long the PoH service waits for more transactions after the first one
+/// recorded in a block before mining it, so that the transactions sent
+/// together land in the same block.
+pub const AUTOMINE_BATCH_DELAY: Duration = Duration::from_millis(5);
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AutomineConfig {
+    /// Also mine a block whenever this long has passed since the last one,
+    /// with or without transactions.
+    pub interval: Option<Duration>,
+}
+
+/// Create a pair of a [`BlockMiner`], to request blocks, and of the
+/// [`Automine`] end that the PoH service mines them with.
+pub fn automine_channel(config: &AutomineConfig) -> (BlockMiner, Automine) {
+    let (sender, receiver) = unbounded();
+    (
+        BlockMiner { sender },
+        Automine {
+            interval: config.interval,
+            mine_requests: receiver,
+        },
+    )
+}
+
+/// Requests blocks from a PoH service in automine mode.
+#[derive(Clone, Debug)]
+pub struct BlockMiner {
+    sender: Sender<Sender<Slot>>,
+}
+
+impl BlockMiner {
+    /// Mines the current block, with or without transactions, and returns
+    /// its slot once its last tick has been recorded.
+    pub fn mine_block(&self, timeout: Duration) -> Result<Slot, RecvTimeoutError> {
+        let (slot_sender, slot_receiver) = bounded(1);
+        self.sender
+            .send(slot_sender)
+            .map_err(|_| RecvTimeoutError::Disconnected)?;
+        slot_receiver.recv_timeout(timeout)
+    }
+}
+
+/// The end of a [`BlockMiner`] that a PoH service mines blocks with.
+pub struct Automine {
+    interval: Option<Duration>,
+    mine_requests: Receiver<Sender<Slot>>,
+}
+
+impl Automine {
+    pub(crate) fn take_mine_requests(&self, pending: &mut Vec<Sender<Slot>>) {
+        pending.extend(self.mine_requests.try_iter());
+    }
+
+    pub(crate) fn is_interval_elapsed(&self, last_block: Instant) -> bool {
+        self.interval
+            .is_some_and(|interval| last_block.elapsed() >= interval)
+    }
+}
@@ -998,6 +998,7 @@ fn do_create_test_recorder(
         poh_service_message_receiver,
         Arc::new(MigrationStatus::default()),
         record_receiver_sender,
+        None,
     );
 
     poh_controller
@@ -2,6 +2,7 @@
 //! "ticks", a measure of time in the PoH stream
 use {
     crate::{
+        automine::{Automine, AUTOMINE_BATCH_DELAY},
         poh_controller::{PohServiceMessage, PohServiceMessageGuard, PohServiceMessageReceiver},
         poh_recorder::{PohRecorder, Record},
         record_channels::RecordReceiver,
@@ -41,6 +42,9 @@ pub const DEFAULT_PINNED_CPU_CORE: usize = 0;
 
 const TARGET_SLOT_ADJUSTMENT_NS: u64 = 50_000_000;
 
+// How often an idle PoH service in automine mode checks for blocks to mine
+const AUTOMINE_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
 #[derive(Debug)]
 struct PohTiming {
     num_ticks: u64,
@@ -106,6 +110,7 @@ impl PohService {
         poh_service_receiver: PohServiceMessageReceiver,
         migration_status: Arc<MigrationStatus>,
         record_receiver_sender: Sender<RecordReceiver>,
+        automine: Option<Automine>,
     ) -> Self {
         let poh_config = poh_config.clone();
         let tick_producer = Builder::new()
@@ -121,7 +126,18 @@ impl PohService {
                     }
                     return;
                 }
-                if poh_config.hashes_per_tick.is_none() {
+                if let Some(automine) = automine {
+                    Self::automine_tick_producer(
+                        poh_recorder,
+                        &poh_config,
+                        &poh_exit,
+                        &mut record_receiver,
+                        poh_service_receiver,
+                        &migration_status.shutdown_poh,
+                        ticks_per_slot,
+                        automine,
+                    )
+                } else if poh_config.hashes_per_tick.is_none() {
                     if poh_config.target_tick_count.is_none() {
                         Self::low_power_tick_producer(
                             poh_recorder,
@@ -289,31 +305,33 @@ impl PohService {
         }
     }
 
+    /// Returns true if a record was received and recorded.
     pub fn read_record_receiver_and_process(
         poh_recorder: &Arc<RwLock<PohRecorder>>,
         record_receiver: &mut RecordReceiver,
         timeout: Duration,
         ticks_per_slot: u64,
-    ) {
-        let record = record_receiver.recv_timeout(timeout);
-        if let Ok(record) = record {
-            match poh_recorder.write().unwrap().record(
-                record.bank_id,
-                record.mixins,
-                record.transaction_batches,
-            ) {
-                Ok(record_summary) => {
-                    if record_receiver
-                        .should_shutdown(record_summary.remaining_hashes_in_slot, ticks_per_slot)
-                    {
-                        record_receiver.shutdown();
-                    }
-                }
-                Err(err) => {
-                    panic!("PohRecorder::record failed: {err:?}");
+    ) -> bool {
+        let Ok(record) = record_receiver.recv_timeout(timeout) else {
+            return false;
+        };
+        match poh_recorder.write().unwrap().record(
+            record.bank_id,
+            record.mixins,
+            record.transaction_batches,
+        ) {
+            Ok(record_summary) => {
+                if record_receiver
+                    .should_shutdown(record_summary.remaining_hashes_in_slot, ticks_per_slot)
+                {
+                    record_receiver.shutdown();
                 }
             }
+            Err(err) => {
+                panic!("PohRecorder::record failed: {err:?}");
+            }
         }
+        true
     }
 
     fn short_lived_low_power_tick_producer(
@@ -410,6 +428,130 @@ impl PohService {
         }
     }
 
+    /// Ticks through the slot of the working bank at once when a block is to
+    /// be mined, and doesn't tick otherwise. See [`crate::automine`].
+    #[allow(clippy::too_many_arguments)]
+    fn automine_tick_producer(
+        poh_recorder: Arc<RwLock<PohRecorder>>,
+        poh_config: &PohConfig,
+        poh_exit: &AtomicBool,
+        record_receiver: &mut RecordReceiver,
+        poh_service_receiver: PohServiceMessageReceiver,
+        shutdown_poh: &AtomicBool,
+        ticks_per_slot: u64,
+        automine: Automine,
+    ) {
+        let poh = poh_recorder.read().unwrap().poh.clone();
+        let mut mine_requests = vec![];
+        let mut mining_slot = None;
+        let mut first_record: Option<Instant> = None;
+        let mut last_block = Instant::now();
+        let mut last_tick = Instant::now();
+        let mut should_shutdown_for_test_producers =
+            Self::should_shutdown_for_test_producers(&poh_recorder);
+        if should_shutdown_for_test_producers {
+            record_receiver.shutdown();
+        }
+        while !poh_exit.load(Ordering::Relaxed) && !shutdown_poh.load(Ordering::Relaxed) {
+            let service_message =
+                Self::check_for_service_message(&poh_service_receiver, record_receiver);
+            loop {
+                automine.take_mine_requests(&mut mine_requests);
+                let timeout = if mining_slot.is_some() {
+                    Duration::ZERO
+                } else {
+                    AUTOMINE_POLL_INTERVAL
+                };
+                if Self::read_record_receiver_and_process(
+                    &poh_recorder,
+                    record_receiver,
+                    timeout,
+                    ticks_per_slot,
+                ) {
+                    first_record.get_or_insert_with(Instant::now);
+                }
+
+                let working_slot = poh_recorder.read().unwrap().bank().map(|bank| bank.slot());
+                if mining_slot != working_slot {
+                    // Not mining yet, or the working bank was reset away
+                    mining_slot = working_slot.filter(|_| {
+                        !mine_requests.is_empty()
+                            || first_record.is_some_and(|first_record| {
+                                first_record.elapsed() >= AUTOMINE_BATCH_DELAY
+                            })
+                            || automine.is_interval_elapsed(last_block)
+                    });
+                }
+
+                let should_tick = if mining_slot.is_some() {
+                    // Same as in the low power tick producer, the last tick
+                    // waits for all the records
+                    !should_shutdown_for_test_producers || record_receiver.is_safe_to_restart()
+                } else {
+                    // Blocks are requested but there is no working bank yet,
+                    // tick at the pace of the PoH config to reach the next
+                    // leader slot
+                    working_slot.is_none()
+                        && !mine_requests.is_empty()
+                        && last_tick.elapsed() >= poh_config.target_tick_duration
+                };
+                if should_tick {
+                    if poh_config.hashes_per_tick.is_some() {
+                        poh.lock().unwrap().hash(u64::MAX);
+                    }
+                    last_tick = Instant::now();
+                    poh_recorder.write().unwrap().tick();
+
+                    should_shutdown_for_test_producers =
+                        Self::should_shutdown_for_test_producers(&poh_recorder);
+                    if should_shutdown_for_test_producers
+                        || record_receiver.should_shutdown(
+                            poh.lock().unwrap().remaining_hashes_in_slot(ticks_per_slot),
+                            ticks_per_slot,
+                        )
+                    {
+                        record_receiver.shutdown();
+                    }
+
+                    if let Some(slot) = mining_slot {
+                        if !poh_recorder.read().unwrap().has_bank() {
+                            // That was the last tick of the block
+                            mining_slot = None;
+                            first_record = None;
+                            last_block = Instant::now();
+                            for slot_sender in mine_requests.drain(..) {
+                                let _ = slot_sender.send(slot);
+                            }
+                        }
+                    }
+                }
+
+                if Self::can_process_service_message(&service_message, record_receiver) {
+                    break;
+                }
+            }
+
+            if let Some(service_message) = service_message {
+                Self::handle_service_message(&poh_recorder, service_message, record_receiver);
+                should_shutdown_for_test_producers =
+                    Self::should_shutdown_for_test_producers(&poh_recorder);
+                if should_shutdown_for_test_producers {
+                    record_receiver.shutdown();
+                }
+            }
+        }
+
+        record_receiver.shutdown();
+        while !record_receiver.is_safe_to_restart() {
+            Self::read_record_receiver_and_process(
+                &poh_recorder,
+                record_receiver,
+                Duration::ZERO,
+                ticks_per_slot,
+            );
+        }
+    }
+
     /// Returns true if the receiver should be shutdown. This is for test variants of the poh service.
     fn should_shutdown_for_test_producers(poh_recorder: &RwLock<PohRecorder>) -> bool {
         let poh_recorder = poh_recorder.read().unwrap();
@@ -670,7 +812,9 @@ mod tests {
     use {
         super::*,
         crate::{
-            poh_controller::PohController, poh_recorder::PohRecorderError::MaxHeightReached,
+            automine::{automine_channel, AutomineConfig},
+            poh_controller::PohController,
+            poh_recorder::PohRecorderError::MaxHeightReached,
             record_channels::record_channels,
         },
         crossbeam_channel::bounded,
@@ -685,6 +829,7 @@ mod tests {
         },
         solana_measure::measure::Measure,
         solana_perf::test_tx::test_tx,
+        solana_pubkey::Pubkey,
         solana_runtime::bank::Bank,
         solana_sha256_hasher::hash,
         solana_transaction::versioned::VersionedTransaction,
@@ -817,6 +962,7 @@ mod tests {
             poh_service_message_receiver,
             Arc::new(MigrationStatus::default()),
             record_receiver_sender,
+            None,
         );
         poh_recorder.write().unwrap().set_bank_for_test(bank);
 
@@ -960,6 +1106,7 @@ mod tests {
             poh_service_message_receiver,
             Arc::new(MigrationStatus::default()),
             record_receiver_sender,
+            None,
         );
 
         // Let poh service process the messages.
@@ -973,4 +1120,101 @@ mod tests {
         exit.store(true, Ordering::Relaxed);
         poh_service.join().unwrap();
     }
+
+    #[test]
+    fn test_poh_service_automine() {
+        agave_logger::setup();
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(2);
+        let (bank, _bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let poh_config = PohConfig::default();
+        let exit = Arc::new(AtomicBool::new(false));
+        let ticks_per_slot = bank.ticks_per_slot();
+        let leader_schedule_cache = Arc::new(LeaderScheduleCache::new_from_bank(&bank));
+        let next_leader_slot = Some((1_000_000, 1_000_000));
+        let (poh_recorder, entry_receiver) = PohRecorder::new(
+            bank.tick_height(),
+            bank.last_blockhash(),
+            bank.clone(),
+            next_leader_slot,
+            ticks_per_slot,
+            blockstore,
+            &leader_schedule_cache,
+            &poh_config,
+            exit.clone(),
+        );
+        let poh_recorder = Arc::new(RwLock::new(poh_recorder));
+        let (record_sender, mut record_receiver) = record_channels(false);
+        record_receiver.restart(bank.bank_id());
+        poh_recorder
+            .write()
+            .unwrap()
+            .set_bank_for_test(bank.clone());
+
+        let (block_miner, automine) = automine_channel(&AutomineConfig::default());
+        let (_poh_controller, poh_service_message_receiver) = PohController::new();
+        let (record_receiver_sender, _record_receiver_receiver) = bounded(1);
+        let poh_service = PohService::new(
+            poh_recorder.clone(),
+            &poh_config,
+            exit.clone(),
+            ticks_per_slot,
+            DEFAULT_PINNED_CPU_CORE,
+            DEFAULT_HASHES_PER_BATCH,
+            record_receiver,
+            poh_service_message_receiver,
+            Arc::new(MigrationStatus::default()),
+            record_receiver_sender,
+            Some(automine),
+        );
+
+        // Nothing is ticked while idle
+        sleep(Duration::from_millis(3 * DEFAULT_MS_PER_SLOT));
+        assert!(entry_receiver.is_empty());
+
+        // Transactions are mined right away, with the rest of the slot
+        record_sender
+            .try_send(Record {
+                mixins: vec![Hash::new_unique()],
+                transaction_batches: vec![vec![VersionedTransaction::from(test_tx())]],
+                bank_id: bank.bank_id(),
+            })
+            .unwrap();
+        let start = Instant::now();
+        let mut num_ticks = 0;
+        let mut num_entries = 0;
+        while num_ticks < ticks_per_slot {
+            let (_bank, (entry, _tick_height)) = entry_receiver
+                .recv_timeout(Duration::from_millis(DEFAULT_MS_PER_SLOT))
+                .unwrap();
+            if entry.is_tick() {
+                num_ticks += 1;
+            } else {
+                num_entries += 1;
+            }
+        }
+        assert_eq!(num_entries, 1);
+        assert!(start.elapsed() < Duration::from_millis(DEFAULT_MS_PER_SLOT));
+        assert!(!poh_recorder.read().unwrap().has_bank());
+
+        // Blocks are mined on request, even without transactions
+        let bank = Arc::new(Bank::new_from_parent(
+            bank.clone(),
+            &Pubkey::default(),
+            bank.slot() + 1,
+        ));
+        poh_recorder
+            .write()
+            .unwrap()
+            .set_bank_for_test(bank.clone());
+        assert_eq!(
+            block_miner.mine_block(Duration::from_secs(5)),
+            Ok(bank.slot())
+        );
+        assert_eq!(entry_receiver.len(), ticks_per_slot as usize);
+
+        exit.store(true, Ordering::Relaxed);
+        poh_service.join().unwrap();
+    }
 }
@@ -23,7 +23,9 @@ use {
     std::{cell::RefCell, rc::Rc},
 };
 
-fn morph_into_deployment_environment_v1<'a>(
+/// The stricter environment that programs are verified against when they are
+/// deployed, derived from the `from` environment they later execute in.
+pub fn morph_into_deployment_environment_v1<'a>(
     from: Arc<BuiltinProgram<InvokeContext<'a, 'a>>>,
 ) -> Result<BuiltinProgram<InvokeContext<'a, 'a>>, ElfError> {
     let mut config = from.get_config().clone();
@@ -0,0 +1,49 @@
+//! Hooks for profiling program execution.
+//!
+//! An [`ExecutionProfiler`] attached to an [`InvokeContext`](crate::invoke_context::InvokeContext)
+//! is told about every instruction, top-level or CPI, once it finished executing, and about every
+//! syscall made by an SBF program. Profiling has no effect on execution, it only costs the time
+//! spent reporting.
+//!
+//! When the program runtime environment has debugging features enabled, the profiler also receives
+//! the register trace of every SBF program execution, which is what coverage is measured from.
+
+use {crate::invoke_context::RegisterTrace, solana_pubkey::Pubkey, std::fmt::Debug};
+
+/// Measurements taken for one instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstructionProfile {
+    /// Program which processed the instruction
+    pub program_id: Pubkey,
+    /// Program which invoked the instruction, `None` for top-level instructions
+    pub caller_program_id: Option<Pubkey>,
+    /// Height of the instruction stack while processing the instruction
+    pub stack_height: usize,
+    /// Compute units consumed, including those of nested CPIs
+    pub compute_units_consumed: u64,
+    /// Data bytes of the (deduplicated) accounts passed to the instruction
+    pub account_data_bytes_read: u64,
+    /// Data bytes of the writable accounts whose data the instruction changed
+    pub account_data_bytes_written: u64,
+    /// Whether the instruction succeeded
+    pub succeeded: bool,
+}
+
+/// Receives measurements of the instructions and syscalls executed
+pub trait ExecutionProfiler: Debug + Send + Sync {
+    /// Called after an instruction was processed
+    fn instruction_processed(&self, profile: &InstructionProfile);
+
+    /// Called after `program_id` invoked the syscall registered as `name`
+    ///
+    /// `compute_units` is what the syscall charged, which for CPI syscalls
+    /// includes the units consumed by the callee.
+    fn syscall_invoked(&self, program_id: &Pubkey, name: &'static str, compute_units: u64);
+
+    /// Called after the SBF program `program_id` executed, with one entry per
+    /// executed instruction whose last element is the program counter
+    ///
+    /// Only called when register tracing is enabled in the program runtime
+    /// environment.
+    fn program_traced(&self, _program_id: &Pubkey, _register_trace: RegisterTrace) {}
+}
@@ -1,6 +1,7 @@
 use {
     crate::{
         execution_budget::{SVMTransactionExecutionBudget, SVMTransactionExecutionCost},
+        execution_profiler::{ExecutionProfiler, InstructionProfile},
         loaded_programs::{
             ProgramCacheEntry, ProgramCacheEntryType, ProgramCacheForTxBatch,
             ProgramRuntimeEnvironments,
@@ -8,7 +9,7 @@ use {
         stable_log,
         sysvar_cache::SysvarCache,
     },
-    solana_account::{AccountSharedData, create_account_shared_data_for_test},
+    solana_account::{AccountSharedData, ReadableAccount, create_account_shared_data_for_test},
     solana_epoch_schedule::EpochSchedule,
     solana_hash::Hash,
     solana_instruction::{AccountMeta, Instruction, error::InstructionError},
@@ -203,6 +204,8 @@ pub struct InvokeContext<'a, 'ix_data> {
     pub syscall_context: Vec<Option<SyscallContext>>,
     /// Pairs of index in TX instruction trace and VM register trace
     register_traces: Vec<(usize, Vec<[u64; 12]>)>,
+    /// Receives measurements of the instructions and syscalls executed
+    execution_profiler: Option<&'a dyn ExecutionProfiler>,
     /// Debug port to use for this executing transaction.
     #[cfg(feature = "sbpf-debugger")]
     pub debug_port: Option<u16>,
@@ -230,11 +233,22 @@ impl<'a, 'ix_data> InvokeContext<'a, 'ix_data> {
             timings: ExecuteDetailsTimings::default(),
             syscall_context: Vec::new(),
             register_traces: Vec::new(),
+            execution_profiler: None,
             #[cfg(feature = "sbpf-debugger")]
             debug_port: None,
         }
     }
 
+    /// Reports the instructions and syscalls executed to `execution_profiler`
+    pub fn set_execution_profiler(&mut self, execution_profiler: &'a dyn ExecutionProfiler) {
+        self.execution_profiler = Some(execution_profiler);
+    }
+
+    /// Get the execution profiler, if any
+    pub fn get_execution_profiler(&self) -> Option<&'a dyn ExecutionProfiler> {
+        self.execution_profiler
+    }
+
     /// Push a stack frame onto the invocation stack
     pub fn push(&mut self) -> Result<(), InstructionError> {
         let instruction_context = self
@@ -494,10 +508,92 @@ impl<'a, 'ix_data> InvokeContext<'a, 'ix_data> {
     ) -> Result<(), InstructionError> {
         *compute_units_consumed = 0;
         self.push()?;
-        self.process_executable_chain(compute_units_consumed, timings)
-            // MUST pop if and only if `push` succeeded, independent of `result`.
-            // Thus, the `.and()` instead of an `.and_then()`.
-            .and(self.pop())
+        let profile = self
+            .execution_profiler
+            .map(|_| self.start_instruction_profile());
+        let result = self.process_executable_chain(compute_units_consumed, timings);
+        if let (Some(execution_profiler), Some(Ok((mut profile, pre_data)))) =
+            (self.execution_profiler, profile)
+        {
+            profile.compute_units_consumed = *compute_units_consumed;
+            profile.succeeded = result.is_ok();
+            if let Ok(bytes_written) = self.account_data_bytes_written(pre_data) {
+                profile.account_data_bytes_written = bytes_written;
+            }
+            execution_profiler.instruction_processed(&profile);
+        }
+        // MUST pop if and only if `push` succeeded, independent of `result`.
+        // Thus, the `.and()` instead of an `.and_then()`.
+        result.and(self.pop())
+    }
+
+    /// Measures the current instruction before it is processed, returning the
+    /// data of its writable accounts to compare against afterwards
+    fn start_instruction_profile(
+        &self,
+    ) -> Result<(InstructionProfile, Vec<(IndexOfAccount, Vec<u8>)>), InstructionError> {
+        let instruction_context = self.transaction_context.get_current_instruction_context()?;
+        let caller_program_id = if instruction_context.get_stack_height() > 1 {
+            let caller = self
+                .transaction_context
+                .get_instruction_context_at_index_in_trace(
+                    instruction_context.get_index_of_caller(),
+                )?;
+            Some(*caller.get_program_key()?)
+        } else {
+            None
+        };
+        let mut account_data_bytes_read = 0u64;
+        let mut pre_data = Vec::new();
+        for index_in_instruction in 0..instruction_context.get_number_of_instruction_accounts() {
+            if instruction_context
+                .is_instruction_account_duplicate(index_in_instruction)?
+                .is_some()
+            {
+                continue;
+            }
+            let index_in_transaction = instruction_context
+                .get_index_of_instruction_account_in_transaction(index_in_instruction)?;
+            let account = self
+                .transaction_context
+                .accounts()
+                .try_borrow(index_in_transaction)?;
+            account_data_bytes_read =
+                account_data_bytes_read.saturating_add(account.data().len() as u64);
+            if instruction_context.is_instruction_account_writable(index_in_instruction)? {
+                pre_data.push((index_in_transaction, account.data().to_vec()));
+            }
+        }
+        Ok((
+            InstructionProfile {
+                program_id: *instruction_context.get_program_key()?,
+                caller_program_id,
+                stack_height: instruction_context.get_stack_height(),
+                compute_units_consumed: 0,
+                account_data_bytes_read,
+                account_data_bytes_written: 0,
+                succeeded: false,
+            },
+            pre_data,
+        ))
+    }
+
+    /// Sums the data lengths of the accounts whose data differs from `pre_data`
+    fn account_data_bytes_written(
+        &self,
+        pre_data: Vec<(IndexOfAccount, Vec<u8>)>,
+    ) -> Result<u64, InstructionError> {
+        let mut bytes_written = 0u64;
+        for (index_in_transaction, pre_data) in pre_data {
+            let account = self
+                .transaction_context
+                .accounts()
+                .try_borrow(index_in_transaction)?;
+            if account.data() != pre_data.as_slice() {
+                bytes_written = bytes_written.saturating_add(account.data().len() as u64);
+            }
+        }
+        Ok(bytes_written)
     }
 
     /// Processes a precompile instruction
@@ -1337,6 +1433,67 @@ mod tests {
         invoke_context.pop().unwrap();
     }
 
+    #[test]
+    fn test_process_instruction_execution_profiler() {
+        #[derive(Debug, Default)]
+        struct MockProfiler(std::sync::Mutex<Vec<InstructionProfile>>);
+        impl ExecutionProfiler for MockProfiler {
+            fn instruction_processed(&self, profile: &InstructionProfile) {
+                self.0.lock().unwrap().push(profile.clone());
+            }
+            fn syscall_invoked(&self, _program_id: &Pubkey, _name: &'static str, _units: u64) {}
+        }
+
+        let program_key = Pubkey::new_unique();
+        let user_account = AccountSharedData::new(100, 123, &program_key);
+        let dummy_account = AccountSharedData::new(10, 7, &program_key);
+        let mut program_account = AccountSharedData::new(500, 500, &native_loader::id());
+        program_account.set_executable(true);
+        let transaction_accounts = vec![
+            (Pubkey::new_unique(), user_account),
+            (Pubkey::new_unique(), dummy_account),
+            (program_key, program_account),
+        ];
+        let instruction_accounts = vec![
+            InstructionAccount::new(0, false, true),
+            InstructionAccount::new(1, false, false),
+            InstructionAccount::new(0, false, true),
+        ];
+        let profiler = MockProfiler::default();
+        with_mock_invoke_context!(invoke_context, transaction_context, transaction_accounts);
+        invoke_context.set_execution_profiler(&profiler);
+        let mut program_cache_for_tx_batch = ProgramCacheForTxBatch::default();
+        program_cache_for_tx_batch.replenish(
+            program_key,
+            Arc::new(ProgramCacheEntry::new_builtin(0, 0, MockBuiltin::vm)),
+        );
+        invoke_context.program_cache_for_tx_batch = &mut program_cache_for_tx_batch;
+
+        let instruction_data =
+            bincode::serialize(&MockInstruction::Resize { new_len: 10 }).unwrap();
+        invoke_context
+            .transaction_context
+            .configure_top_level_instruction_for_tests(2, instruction_accounts, instruction_data)
+            .unwrap();
+        let mut compute_units_consumed = 0;
+        invoke_context
+            .process_instruction(&mut compute_units_consumed, &mut ExecuteTimings::default())
+            .unwrap();
+
+        assert_eq!(
+            *profiler.0.lock().unwrap(),
+            vec![InstructionProfile {
+                program_id: program_key,
+                caller_program_id: None,
+                stack_height: 1,
+                compute_units_consumed: MOCK_BUILTIN_COMPUTE_UNIT_COST,
+                account_data_bytes_read: 130,
+                account_data_bytes_written: 10,
+                succeeded: true,
+            }],
+        );
+    }
+
     #[test]
     fn test_invoke_context_compute_budget() {
         let transaction_accounts = vec![(solana_pubkey::new_rand(), AccountSharedData::default())];
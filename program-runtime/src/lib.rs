@@ -7,6 +7,7 @@ pub use solana_sbpf;
 pub mod cpi;
 pub mod deploy;
 pub mod execution_budget;
+pub mod execution_profiler;
 pub mod invoke_context;
 pub mod loaded_programs;
 pub mod mem_pool;
@@ -1247,6 +1247,21 @@ impl<FG: ForkGraph> ProgramCache<FG> {
         }
     }
 
+    /// Returns the addresses of the programs with an entry owned by `loader`.
+    pub fn get_program_ids_owned_by(&self, loader: &Pubkey) -> Vec<Pubkey> {
+        match &self.index {
+            IndexImplementation::V1 { entries, .. } => entries
+                .iter()
+                .filter(|(_id, second_level)| {
+                    second_level
+                        .iter()
+                        .any(|program| &program.account_owner() == loader)
+                })
+                .map(|(id, _second_level)| *id)
+                .collect(),
+        }
+    }
+
     /// Returns the list of all entries in the cache.
     pub fn get_flattened_entries_for_tests(&self) -> Vec<(Pubkey, Arc<ProgramCacheEntry>)> {
         match &self.index {
@@ -270,6 +270,12 @@ pub fn execute<'a, 'b: 'a>(
             debug_assert!(memory_pool.heap_len() <= MAX_INSTRUCTION_STACK_DEPTH);
         });
         drop(vm);
+        if let Some(execution_profiler) = invoke_context
+            .get_execution_profiler()
+            .filter(|_| !register_trace.is_empty())
+        {
+            execution_profiler.program_traced(&program_id, &register_trace);
+        }
         invoke_context.insert_register_trace(register_trace);
         if let Some(execute_time) = invoke_context.execute_time.as_mut() {
             execute_time.stop();
@@ -0,0 +1,330 @@
+//! Offline sources of cluster accounts, see [`ProgramTest::clone_accounts`]
+//!
+//! [`ProgramTest::clone_accounts`]: crate::ProgramTest::clone_accounts
+
+use {
+    agave_snapshots::{
+        error::SnapshotError,
+        snapshot_archive_info::{FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo},
+    },
+    log::*,
+    serde::Deserialize,
+    solana_account::{AccountSharedData, ReadableAccount, WritableAccount},
+    solana_account_decoder_client_types::UiAccount,
+    solana_accounts_db::{
+        account_storage_entry::AccountStorageEntry, accounts_db::ACCOUNTS_DB_CONFIG_FOR_TESTING,
+    },
+    solana_address_lookup_table_interface::{
+        self as address_lookup_table, state::AddressLookupTable,
+    },
+    solana_clock::Slot,
+    solana_loader_v3_interface::state::UpgradeableLoaderState,
+    solana_pubkey::Pubkey,
+    solana_runtime::snapshot_utils::{
+        verify_and_unarchive_snapshots, UnarchivedSnapshots, UnarchivedSnapshotsGuard,
+    },
+    solana_sdk_ids::bpf_loader_upgradeable,
+    std::{
+        collections::HashMap,
+        ffi::OsStr,
+        fs, io,
+        path::{Path, PathBuf},
+        str::FromStr,
+        sync::Arc,
+    },
+    tempfile::TempDir,
+    thiserror::Error,
+};
+
+/// Errors from reading accounts out of an [`AccountSource`] or cloning them
+#[derive(Error, Debug)]
+pub enum AccountSourceError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to read snapshot: {0}")]
+    Snapshot(#[from] SnapshotError),
+    #[error("Failed to parse account file {0}: {1}")]
+    InvalidAccountFile(PathBuf, String),
+    #[error("Account {0} not found")]
+    AccountNotFound(Pubkey),
+    #[error("Account {0} is not an upgradeable program")]
+    NotAnUpgradeableProgram(Pubkey),
+    #[error("Account {0} is not a valid address lookup table")]
+    InvalidLookupTable(Pubkey),
+}
+
+/// A source of accounts to clone into the test environment
+pub trait AccountSource {
+    /// Returns the accounts at `addresses`, in the same order, `None` for
+    /// those that do not exist
+    fn get_multiple_accounts(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<Option<AccountSharedData>>, AccountSourceError>;
+}
+
+/// Accounts of a full snapshot archive and optionally a chain of incremental
+/// snapshot archives
+///
+/// The archives are unpacked into a temporary directory and the account
+/// storages are indexed once, when the source is created, so that
+/// [`AccountSource::get_multiple_accounts`] only reads the requested accounts.
+#[derive(Debug)]
+pub struct SnapshotAccountSource {
+    storages: Vec<Arc<AccountStorageEntry>>,
+    // Location of the latest version of each account: its slot, the index of
+    // its storage and its offset within the storage
+    index: HashMap<Pubkey, (Slot, usize, usize)>,
+    // The storages are backed by files in these directories
+    _unarchived_snapshots_guard: UnarchivedSnapshotsGuard,
+    _bank_snapshots_dir: TempDir,
+    _accounts_dir: TempDir,
+}
+
+impl SnapshotAccountSource {
+    /// Unpack the full snapshot archive at `full_snapshot_archive` and the
+    /// incremental snapshot archives at `incremental_snapshot_archives`, which
+    /// must be in chain order
+    pub fn new(
+        full_snapshot_archive: impl Into<PathBuf>,
+        incremental_snapshot_archives: impl IntoIterator<Item = PathBuf>,
+    ) -> Result<Self, AccountSourceError> {
+        let full_snapshot_archive_info =
+            FullSnapshotArchiveInfo::new_from_path(full_snapshot_archive.into())?;
+        let incremental_snapshot_archive_infos = incremental_snapshot_archives
+            .into_iter()
+            .map(IncrementalSnapshotArchiveInfo::new_from_path)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let bank_snapshots_dir = TempDir::new()?;
+        let accounts_dir = TempDir::new()?;
+        let (
+            UnarchivedSnapshots {
+                full_storage,
+                incremental_storage,
+                ..
+            },
+            unarchived_snapshots_guard,
+        ) = verify_and_unarchive_snapshots(
+            &bank_snapshots_dir,
+            &full_snapshot_archive_info,
+            &incremental_snapshot_archive_infos,
+            &[accounts_dir.path().to_path_buf()],
+            &ACCOUNTS_DB_CONFIG_FOR_TESTING,
+        )?;
+        let storages: Vec<_> = full_storage
+            .into_iter()
+            .chain(incremental_storage.into_iter().flatten())
+            .map(|(_slot, storage)| storage)
+            .collect();
+
+        // The latest version of each account is the one stored in the highest
+        // slot, and within a storage the one at the highest offset
+        let mut index: HashMap<Pubkey, (Slot, usize, usize)> = HashMap::new();
+        for (storage_index, storage) in storages.iter().enumerate() {
+            let slot = storage.slot();
+            storage
+                .accounts
+                .scan_accounts_without_data(|offset, account| {
+                    let newer = index.get(account.pubkey()).is_none_or(
+                        |(latest_slot, _, latest_offset)| {
+                            (slot, offset) > (*latest_slot, *latest_offset)
+                        },
+                    );
+                    if newer {
+                        index.insert(*account.pubkey(), (slot, storage_index, offset));
+                    }
+                })?;
+        }
+
+        Ok(Self {
+            storages,
+            index,
+            _unarchived_snapshots_guard: unarchived_snapshots_guard,
+            _bank_snapshots_dir: bank_snapshots_dir,
+            _accounts_dir: accounts_dir,
+        })
+    }
+}
+
+impl AccountSource for SnapshotAccountSource {
+    fn get_multiple_accounts(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<Option<AccountSharedData>>, AccountSourceError> {
+        Ok(addresses
+            .iter()
+            .map(|address| {
+                let (_slot, storage_index, offset) = self.index.get(address)?;
+                self.storages[*storage_index]
+                    .accounts
+                    .get_stored_account_callback(*offset, |account| {
+                        AccountSharedData::create(
+                            account.lamports,
+                            account.data.to_vec(),
+                            *account.owner,
+                            account.executable,
+                            account.rent_epoch,
+                        )
+                    })
+                    // Zero lamport accounts are deleted
+                    .filter(|account| account.lamports() != 0)
+            })
+            .collect())
+    }
+}
+
+/// JSON representation of an account, as written by `solana account --output json`
+#[derive(Deserialize)]
+struct KeyedAccount {
+    pubkey: String,
+    account: UiAccount,
+}
+
+/// Accounts of a directory of JSON account dumps
+///
+/// Every `.json` file in the directory is expected to hold one account in the
+/// format written by `solana account --output json <ADDRESS>`.
+#[derive(Debug, Default)]
+pub struct JsonAccountSource {
+    accounts: HashMap<Pubkey, AccountSharedData>,
+}
+
+impl JsonAccountSource {
+    /// Read all the account dumps in `dir`
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, AccountSourceError> {
+        let mut accounts = HashMap::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.is_file() || path.extension() != Some(OsStr::new("json")) {
+                continue;
+            }
+            let (address, account) = read_account_file(&path)
+                .map_err(|err| AccountSourceError::InvalidAccountFile(path.clone(), err))?;
+            debug!("Read account {address} from {}", path.display());
+            accounts.insert(address, account);
+        }
+        Ok(Self { accounts })
+    }
+}
+
+fn read_account_file(path: &Path) -> Result<(Pubkey, AccountSharedData), String> {
+    let contents = fs::read(path).map_err(|err| err.to_string())?;
+    let keyed_account: KeyedAccount =
+        serde_json::from_slice(&contents).map_err(|err| err.to_string())?;
+    let address = Pubkey::from_str(&keyed_account.pubkey).map_err(|err| err.to_string())?;
+    let account = keyed_account
+        .account
+        .to_account_shared_data()
+        .ok_or_else(|| "unsupported account data encoding".to_string())?;
+    Ok((address, account))
+}
+
+impl AccountSource for JsonAccountSource {
+    fn get_multiple_accounts(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<Option<AccountSharedData>>, AccountSourceError> {
+        Ok(addresses
+            .iter()
+            .map(|address| self.accounts.get(address).cloned())
+            .collect())
+    }
+}
+
+/// Reads `addresses` out of `source` along with the accounts they depend on:
+/// the programdata of upgradeable programs and the entries of address lookup
+/// tables
+///
+/// Missing `addresses` are an error unless `skip_missing` is set, missing
+/// programdata is always an error and missing lookup table entries are
+/// skipped. With `programs_only` every one of `addresses` must be an
+/// upgradeable program.
+pub(crate) fn clone_accounts(
+    source: &dyn AccountSource,
+    addresses: &[Pubkey],
+    skip_missing: bool,
+    programs_only: bool,
+) -> Result<Vec<(Pubkey, AccountSharedData)>, AccountSourceError> {
+    let mut cloned = HashMap::new();
+    let mut pending: Vec<(Pubkey, Dependency)> = addresses
+        .iter()
+        .map(|address| (*address, Dependency::Requested))
+        .collect();
+    while !pending.is_empty() {
+        pending.retain(|(address, _)| !cloned.contains_key(address));
+        // Of the duplicates, keep the strictest dependency
+        pending.sort();
+        pending.dedup_by_key(|(address, _)| *address);
+        let batch = std::mem::take(&mut pending);
+        let batch_addresses: Vec<Pubkey> = batch.iter().map(|(address, _)| *address).collect();
+        let accounts = source.get_multiple_accounts(&batch_addresses)?;
+
+        for ((address, dependency), account) in batch.into_iter().zip(accounts) {
+            let Some(mut account) = account else {
+                match dependency {
+                    Dependency::Requested if !skip_missing => {
+                        return Err(AccountSourceError::AccountNotFound(address));
+                    }
+                    Dependency::ProgramData => {
+                        return Err(AccountSourceError::AccountNotFound(address));
+                    }
+                    _ => warn!("Could not find {address}, skipping."),
+                }
+                continue;
+            };
+
+            let mut is_program = false;
+            if bpf_loader_upgradeable::check_id(account.owner()) {
+                match account.deserialize_data() {
+                    Ok(UpgradeableLoaderState::Program {
+                        programdata_address,
+                    }) => {
+                        is_program = true;
+                        pending.push((programdata_address, Dependency::ProgramData));
+                    }
+                    Ok(UpgradeableLoaderState::ProgramData {
+                        upgrade_authority_address,
+                        ..
+                    }) => {
+                        // Deploy the program at slot 0, so that it is visible
+                        // at every slot of the test environment
+                        bincode::serialize_into(
+                            account.data_as_mut_slice(),
+                            &UpgradeableLoaderState::ProgramData {
+                                slot: 0,
+                                upgrade_authority_address,
+                            },
+                        )
+                        .expect("programdata metadata was just deserialized");
+                    }
+                    _ => (),
+                }
+            } else if address_lookup_table::check_id(account.owner()) {
+                let lookup_table = AddressLookupTable::deserialize(account.data())
+                    .map_err(|_| AccountSourceError::InvalidLookupTable(address))?;
+                pending.extend(
+                    lookup_table
+                        .addresses
+                        .iter()
+                        .map(|entry| (*entry, Dependency::LookupTableEntry)),
+                );
+            }
+
+            if programs_only && dependency == Dependency::Requested && !is_program {
+                return Err(AccountSourceError::NotAnUpgradeableProgram(address));
+            }
+            cloned.insert(address, account);
+        }
+    }
+
+    Ok(cloned.into_iter().collect())
+}
+
+/// Why an account is being cloned, from the strictest to the most lenient
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Dependency {
+    Requested,
+    ProgramData,
+    LookupTableEntry,
+}
@@ -0,0 +1,161 @@
+//! Line and instruction coverage of SBF programs, see [`ProgramTest::enable_coverage`]
+//!
+//! [`ProgramTest::enable_coverage`]: crate::ProgramTest::enable_coverage
+
+use {
+    addr2line::gimli,
+    log::*,
+    object::{Object, ObjectSection},
+    solana_pubkey::Pubkey,
+    std::{
+        collections::{BTreeMap, HashMap},
+        error::Error,
+        fmt::Write as _,
+        fs,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+    },
+};
+
+/// Size of an SBF instruction slot, program counters count slots
+const INSTRUCTION_SIZE: u64 = 8;
+
+/// Index of the program counter in an entry of a register trace
+const PC_REGISTER: usize = 11;
+
+/// Execution counts per source file and line
+type LineCounts = BTreeMap<String, BTreeMap<u64, u64>>;
+
+/// Counts the executions of each instruction of the traced programs and
+/// writes them as one lcov tracefile per program
+#[derive(Debug)]
+pub(crate) struct CoverageCollector {
+    output_dir: PathBuf,
+    /// ELF files of the programs, preferably with debug info
+    program_files: HashMap<Pubkey, PathBuf>,
+    /// Execution count per program counter, per program
+    hits: Mutex<HashMap<Pubkey, HashMap<u64, u64>>>,
+}
+
+impl CoverageCollector {
+    pub(crate) fn new(output_dir: PathBuf, program_files: HashMap<Pubkey, PathBuf>) -> Self {
+        Self {
+            output_dir,
+            program_files,
+            hits: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn record(&self, program_id: &Pubkey, register_trace: &[[u64; 12]]) {
+        let mut hits = self.hits.lock().unwrap();
+        let program_hits = hits.entry(*program_id).or_default();
+        for registers in register_trace {
+            *program_hits.entry(registers[PC_REGISTER]).or_default() += 1;
+        }
+    }
+
+    /// Writes `<program_id>.lcov` to the output directory for every program
+    /// that was executed
+    pub(crate) fn write_output(&self) {
+        if let Err(err) = fs::create_dir_all(&self.output_dir) {
+            warn!(
+                "Failed to create coverage directory {}: {err}",
+                self.output_dir.display()
+            );
+            return;
+        }
+        let hits = self.hits.lock().unwrap();
+        for (program_id, program_hits) in hits.iter() {
+            let path = self.output_dir.join(format!("{program_id}.lcov"));
+            let line_counts = self
+                .program_files
+                .get(program_id)
+                .and_then(|program_file| {
+                    line_counts(program_file, program_hits)
+                        .inspect_err(|err| {
+                            warn!(
+                                "Failed to read line info of {}: {err}",
+                                program_file.display()
+                            )
+                        })
+                        .ok()
+                })
+                .filter(|line_counts| !line_counts.is_empty())
+                .unwrap_or_else(|| instruction_counts(program_id, program_hits));
+            match fs::write(&path, lcov(&line_counts)) {
+                Ok(()) => info!("Wrote coverage of {program_id} to {}", path.display()),
+                Err(err) => warn!("Failed to write coverage to {}: {err}", path.display()),
+            }
+        }
+    }
+}
+
+/// Maps every instruction of the program's text section to its source line
+/// through the DWARF line info of the ELF, if it has any
+///
+/// Lines with several instructions count as executed as often as their most
+/// executed instruction.
+fn line_counts(
+    program_file: &Path,
+    hits: &HashMap<u64, u64>,
+) -> Result<LineCounts, Box<dyn Error>> {
+    let elf = fs::read(program_file)?;
+    let object = object::File::parse(elf.as_slice())?;
+    let endian = if object.is_little_endian() {
+        gimli::RunTimeEndian::Little
+    } else {
+        gimli::RunTimeEndian::Big
+    };
+    let dwarf = gimli::Dwarf::load(|id| -> Result<_, gimli::Error> {
+        let data = object
+            .section_by_name(id.name())
+            .and_then(|section| section.uncompressed_data().ok())
+            .unwrap_or_default();
+        Ok(gimli::EndianArcSlice::new(Arc::from(&*data), endian))
+    })?;
+    let context = addr2line::Context::from_dwarf(dwarf)?;
+    let text = object
+        .section_by_name(".text")
+        .ok_or("ELF has no .text section")?;
+
+    let mut line_counts = LineCounts::new();
+    for pc in 0..text.size() / INSTRUCTION_SIZE {
+        let Some(location) = context.find_location(text.address() + pc * INSTRUCTION_SIZE)? else {
+            continue;
+        };
+        let (Some(file), Some(line)) = (location.file, location.line) else {
+            continue;
+        };
+        let count = line_counts
+            .entry(file.to_string())
+            .or_default()
+            .entry(u64::from(line))
+            .or_default();
+        *count = (*count).max(hits.get(&pc).copied().unwrap_or_default());
+    }
+    Ok(line_counts)
+}
+
+/// Without line info, reports each executed instruction as a line of a file
+/// named after the program, numbered from 1
+fn instruction_counts(program_id: &Pubkey, hits: &HashMap<u64, u64>) -> LineCounts {
+    let instructions = hits.iter().map(|(pc, count)| (pc + 1, *count)).collect();
+    LineCounts::from([(program_id.to_string(), instructions)])
+}
+
+/// Formats line counts as an lcov tracefile, as read by `genhtml` and most
+/// coverage tooling
+fn lcov(line_counts: &LineCounts) -> String {
+    let mut lcov = String::new();
+    for (file, lines) in line_counts {
+        writeln!(lcov, "SF:{file}").unwrap();
+        for (line, count) in lines {
+            writeln!(lcov, "DA:{line},{count}").unwrap();
+        }
+        let lines_hit = lines.values().filter(|count| **count > 0).count();
+        writeln!(lcov, "LF:{}", lines.len()).unwrap();
+        writeln!(lcov, "LH:{lines_hit}").unwrap();
+        writeln!(lcov, "end_of_record").unwrap();
+    }
+    lcov
+}
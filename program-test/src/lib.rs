@@ -5,6 +5,10 @@
 // Export tokio for test clients
 pub use tokio;
 use {
+    crate::{
+        coverage::CoverageCollector,
+        profiler::{ProfileWriter, Profiler},
+    },
     agave_feature_set::{
         increase_cpi_account_info_limit, raise_cpi_nesting_limit_to_8, FeatureSet, FEATURE_NAMES,
     },
@@ -75,6 +79,10 @@ use {
 };
 // Export types so test clients can limit their solana crate dependencies
 pub use {
+    account_source::{AccountSource, AccountSourceError, JsonAccountSource, SnapshotAccountSource},
+    profiler::{
+        CpiEdgeProfile, Profile, ProfileFormat, ProfiledInstruction, ProgramProfile, SyscallProfile,
+    },
     solana_banks_client::{BanksClient, BanksClientError},
     solana_banks_interface::BanksTransactionResultWithMetadata,
     solana_program_runtime::invoke_context::InvokeContext,
@@ -85,12 +93,20 @@ pub use {
     solana_transaction_context::IndexOfAccount,
 };
 
+mod account_source;
+mod coverage;
+mod profiler;
+
 /// Errors from the program test environment
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum ProgramTestError {
     /// The chosen warp slot is not in the future, so warp is not performed
     #[error("Warp slot not in the future")]
     InvalidWarpSlot,
+    /// The checkpoint was pruned by rooting another branch, so it cannot be
+    /// restored
+    #[error("Checkpoint no longer available")]
+    InvalidCheckpoint,
 }
 
 thread_local! {
@@ -478,6 +494,11 @@ pub struct ProgramTest {
     prefer_bpf: bool,
     deactivate_feature_set: HashSet<Pubkey>,
     transaction_account_lock_limit: Option<usize>,
+    profile_output: Option<(PathBuf, ProfileFormat)>,
+    coverage_dir: Option<PathBuf>,
+    /// SBF program files, by program id, to read line info from
+    program_files: HashMap<Pubkey, PathBuf>,
+    profiler: Option<Arc<Profiler>>,
 }
 
 impl Default for ProgramTest {
@@ -509,6 +530,10 @@ impl Default for ProgramTest {
             prefer_bpf,
             deactivate_feature_set: HashSet::default(),
             transaction_account_lock_limit: None,
+            profile_output: None,
+            coverage_dir: None,
+            program_files: HashMap::new(),
+            profiler: None,
         }
     }
 }
@@ -550,6 +575,35 @@ impl ProgramTest {
         self.transaction_account_lock_limit = Some(transaction_account_lock_limit);
     }
 
+    /// Profile the instructions executed by the test
+    ///
+    /// Compute units per instruction, program and CPI edge, syscall invocations and account data
+    /// bytes read and written are aggregated into a [`Profile`], which is written to `path` in
+    /// `format` once the test environment is dropped. It can also be inspected while the test runs
+    /// with [`ProgramTestContext::profile`]. Syscalls are only recorded for SBF programs, not for
+    /// programs added as native processors. To record them, SBF programs run with the VM's
+    /// debugging features enabled.
+    pub fn enable_profiling(&mut self, path: impl Into<PathBuf>, format: ProfileFormat) {
+        self.profile_output = Some((path.into(), format));
+    }
+
+    /// Measure the coverage of the SBF programs executed by the test
+    ///
+    /// Every instruction executed by an SBF program is counted, which requires running programs
+    /// with register tracing and slows them down considerably. Once the test environment is
+    /// dropped, an lcov tracefile named `<PROGRAM_ID>.lcov` is written to `dir` for every program
+    /// that was executed.
+    ///
+    /// The instructions are mapped to source lines with the DWARF line info of the program's ELF.
+    /// For programs added with [`add_program`](Self::add_program), the line info is read from
+    /// `<program_name>.debug` next to the shared object if it exists, as written by `cargo
+    /// build-sbf --debug`, and from the shared object itself otherwise. Programs without line
+    /// info are reported per instruction instead, as lines of a file named after the program id,
+    /// where line `N` is the `N`th instruction of the program's text section.
+    pub fn enable_coverage(&mut self, dir: impl Into<PathBuf>) {
+        self.coverage_dir = Some(dir.into());
+    }
+
     /// Add an account to the test environment's genesis config.
     pub fn add_genesis_account(&mut self, address: Pubkey, account: Account) {
         self.genesis_accounts
@@ -607,6 +661,38 @@ impl ProgramTest {
         );
     }
 
+    /// Clone accounts out of `source` into the test environment
+    ///
+    /// Accounts the cloned accounts depend on are cloned as well: the programdata accounts of
+    /// upgradeable programs and the accounts referenced by address lookup tables. Addresses that
+    /// are not found in `source` are an error unless `skip_missing` is set, in which case they are
+    /// skipped. Lookup table entries that are not found are always skipped.
+    ///
+    /// As on a test validator, the deployment slot of cloned programs is reset to `0`.
+    pub fn clone_accounts(
+        &mut self,
+        addresses: &[Pubkey],
+        source: &dyn AccountSource,
+        skip_missing: bool,
+    ) -> Result<(), AccountSourceError> {
+        let accounts = account_source::clone_accounts(source, addresses, skip_missing, false)?;
+        self.accounts.extend(accounts);
+        Ok(())
+    }
+
+    /// Clone upgradeable programs and their programdata out of `source` into the test environment
+    ///
+    /// Fails if any of `addresses` is not found in `source` or is not an upgradeable program.
+    pub fn clone_upgradeable_programs(
+        &mut self,
+        addresses: &[Pubkey],
+        source: &dyn AccountSource,
+    ) -> Result<(), AccountSourceError> {
+        let accounts = account_source::clone_accounts(source, addresses, false, true)?;
+        self.accounts.extend(accounts);
+        Ok(())
+    }
+
     pub fn add_sysvar_account<S: SysvarSerialize>(&mut self, address: Pubkey, sysvar: &S) {
         let account = create_account_shared_data_for_test(sysvar);
         self.add_account(address, account.into());
@@ -677,6 +763,15 @@ impl ProgramTest {
                     .unwrap_or_default()
             );
 
+            let debug_file = program_file.with_extension("debug");
+            this.program_files.insert(
+                program_id,
+                if debug_file.is_file() {
+                    debug_file
+                } else {
+                    program_file
+                },
+            );
             this.add_account(
                 program_id,
                 Account {
@@ -851,6 +946,7 @@ impl ProgramTest {
                     )
                 }),
                 transaction_account_lock_limit: self.transaction_account_lock_limit,
+                debugging_features: self.profile_output.is_some() || self.coverage_dir.is_some(),
                 ..RuntimeConfig::default()
             }),
             Vec::default(),
@@ -862,6 +958,15 @@ impl ProgramTest {
             None,
             None,
         );
+        if self.profile_output.is_some() || self.coverage_dir.is_some() {
+            let coverage = self
+                .coverage_dir
+                .take()
+                .map(|dir| CoverageCollector::new(dir, std::mem::take(&mut self.program_files)));
+            let profiler = Arc::new(Profiler::new(self.profile_output.take(), coverage));
+            bank.set_execution_profiler(Some(profiler.clone()));
+            self.profiler = Some(profiler);
+        }
 
         // Add commonly-used SPL programs as a convenience to the user
         for (program_id, account) in programs::spl_programs(&rent).iter() {
@@ -936,7 +1041,10 @@ impl ProgramTest {
         // Run a simulated PohService to provide the client with new blockhashes.  New blockhashes
         // are required when sending multiple otherwise identical transactions in series from a
         // test
+        let profile_writer = self.profiler.take().map(ProfileWriter);
         tokio::spawn(async move {
+            // Dropped with the task when the runtime shuts down
+            let _profile_writer = profile_writer;
             loop {
                 tokio::time::sleep(target_slot_duration).await;
                 bank_forks
@@ -973,6 +1081,7 @@ impl ProgramTest {
             banks_client,
             last_blockhash,
             gci,
+            self.profiler,
         )
     }
 }
@@ -1024,6 +1133,19 @@ impl<T> Drop for DroppableTask<T> {
     }
 }
 
+/// A saved state of the test environment, see [`ProgramTestContext::checkpoint`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    slot: Slot,
+}
+
+impl Checkpoint {
+    /// Slot of the frozen bank holding the saved state
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+}
+
 pub struct ProgramTestContext {
     pub banks_client: BanksClient,
     pub last_blockhash: Hash,
@@ -1031,6 +1153,7 @@ pub struct ProgramTestContext {
     genesis_config: GenesisConfig,
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
+    profiler: Option<ProfileWriter>,
     _bank_task: DroppableTask<()>,
 }
 
@@ -1041,6 +1164,7 @@ impl ProgramTestContext {
         banks_client: BanksClient,
         last_blockhash: Hash,
         genesis_config_info: GenesisConfigInfo,
+        profiler: Option<Arc<Profiler>>,
     ) -> Self {
         // Run a simulated PohService to provide the client with new blockhashes.  New blockhashes
         // are required when sending multiple otherwise identical transactions in series from a
@@ -1077,6 +1201,7 @@ impl ProgramTestContext {
             genesis_config: genesis_config_info.genesis_config,
             bank_forks,
             block_commitment_cache,
+            profiler: profiler.map(ProfileWriter),
             _bank_task: bank_task,
         }
     }
@@ -1085,6 +1210,12 @@ impl ProgramTestContext {
         &self.genesis_config
     }
 
+    /// The profile aggregated so far, if profiling was enabled with
+    /// [`ProgramTest::enable_profiling`]
+    pub fn profile(&self) -> Option<Profile> {
+        self.profiler.as_ref().map(|writer| writer.0.profile())
+    }
+
     /// Manually increment vote credits for the current epoch in the specified vote account to simulate validator voting activity
     pub fn increment_vote_account_credits(
         &mut self,
@@ -1269,6 +1400,81 @@ impl ProgramTestContext {
         Ok(())
     }
 
+    /// Save the current state so that it can be returned to later
+    ///
+    /// The working bank is frozen and kept in bank forks as the checkpoint,
+    /// and the test continues in a child bank. Checkpoints stay available
+    /// until a warp roots a branch that doesn't include them.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let bank_forks = self.bank_forks.clone();
+        let mut bank_forks = bank_forks.write().unwrap();
+        let slot = Self::freeze_working_bank(&bank_forks);
+        let parent = bank_forks.get(slot).unwrap();
+        self.start_branch(&mut bank_forks, parent);
+        Checkpoint { slot }
+    }
+
+    /// Return to the state saved by `checkpoint`, discarding any changes made
+    /// since the last checkpoint on the current branch
+    ///
+    /// The test continues in a new bank descending from the checkpoint, so the
+    /// same checkpoint can be restored any number of times to run several
+    /// scenarios from one setup. The discarded working bank is removed from
+    /// bank forks.
+    pub fn restore(&mut self, checkpoint: &Checkpoint) -> Result<(), ProgramTestError> {
+        let bank_forks = self.bank_forks.clone();
+        let mut bank_forks = bank_forks.write().unwrap();
+        let parent = bank_forks
+            .get(checkpoint.slot)
+            .ok_or(ProgramTestError::InvalidCheckpoint)?;
+        let discarded_slot = bank_forks.highest_slot();
+        // The new branch is started first, so that its slot is past the
+        // discarded one and no slot is reused by another bank
+        self.start_branch(&mut bank_forks, parent);
+        bank_forks.remove(discarded_slot);
+        Ok(())
+    }
+
+    /// Branch off from `checkpoint`, keeping the current state
+    ///
+    /// Like `restore`, but the current state is saved first. The returned
+    /// checkpoint can be restored to switch back to the branch being left.
+    pub fn fork(&mut self, checkpoint: &Checkpoint) -> Result<Checkpoint, ProgramTestError> {
+        let bank_forks = self.bank_forks.clone();
+        let mut bank_forks = bank_forks.write().unwrap();
+        let parent = bank_forks
+            .get(checkpoint.slot)
+            .ok_or(ProgramTestError::InvalidCheckpoint)?;
+        let slot = Self::freeze_working_bank(&bank_forks);
+        self.start_branch(&mut bank_forks, parent);
+        Ok(Checkpoint { slot })
+    }
+
+    fn freeze_working_bank(bank_forks: &BankForks) -> Slot {
+        let bank = bank_forks.working_bank();
+        // Fill ticks until a new blockhash is recorded, otherwise retried transactions will have
+        // the same signature
+        bank.fill_bank_with_ticks_for_tests();
+        bank.freeze();
+        bank.slot()
+    }
+
+    /// Make a new child of `parent` the working bank
+    fn start_branch(&mut self, bank_forks: &mut BankForks, parent: Arc<Bank>) {
+        // The working bank is the one with the highest slot, so the new branch
+        // has to skip past the slots of every other branch
+        let slot = bank_forks.highest_slot() + 1;
+        bank_forks.insert(Bank::new_from_parent(parent, &Pubkey::default(), slot));
+
+        // Update block commitment cache, otherwise banks server will poll at
+        // the wrong slot
+        self.block_commitment_cache
+            .write()
+            .unwrap()
+            .set_all_slots(slot, slot);
+        self.last_blockhash = bank_forks.working_bank().last_blockhash();
+    }
+
     /// Get a new latest blockhash, similar in spirit to RpcClient::get_latest_blockhash()
     pub async fn get_new_latest_blockhash(&mut self) -> io::Result<Hash> {
         let blockhash = self
@@ -0,0 +1,286 @@
+//! Compute unit, syscall and account data profiling, see [`ProgramTest::enable_profiling`]
+//!
+//! [`ProgramTest::enable_profiling`]: crate::ProgramTest::enable_profiling
+
+use {
+    crate::coverage::CoverageCollector,
+    log::*,
+    serde::{Serialize, Serializer},
+    solana_program_runtime::{
+        execution_profiler::{ExecutionProfiler, InstructionProfile},
+        invoke_context::RegisterTrace,
+    },
+    solana_pubkey::Pubkey,
+    std::{
+        collections::{BTreeMap, HashMap},
+        fmt::Write as _,
+        fs, io,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+        thread::{self, ThreadId},
+    },
+};
+
+/// File format of the profile written at the end of a test
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileFormat {
+    /// The [`Profile`] as a JSON document
+    Json,
+    /// One `caller;callee compute_units` line per call stack, as consumed by
+    /// flamegraph tools. Compute units are exclusive of nested CPIs.
+    FoldedStacks,
+}
+
+/// Compute units, syscalls and account data accesses of the instructions
+/// executed during a test
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Profile {
+    /// Every instruction executed, top-level or CPI, in the order in which
+    /// they completed
+    pub instructions: Vec<ProfiledInstruction>,
+    /// Totals per program
+    #[serde(serialize_with = "serialize_programs")]
+    pub programs: BTreeMap<Pubkey, ProgramProfile>,
+    /// Totals per `(caller, callee)` pair of programs
+    #[serde(serialize_with = "serialize_cpi_edges")]
+    pub cpi_edges: BTreeMap<(Pubkey, Pubkey), CpiEdgeProfile>,
+}
+
+/// A single executed instruction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ProfiledInstruction {
+    #[serde(serialize_with = "serialize_display")]
+    pub program_id: Pubkey,
+    /// 1 for top-level instructions, incremented by each level of CPI
+    pub stack_height: usize,
+    /// Compute units consumed, including those of nested CPIs
+    pub compute_units_consumed: u64,
+    pub account_data_bytes_read: u64,
+    pub account_data_bytes_written: u64,
+    pub succeeded: bool,
+}
+
+/// Totals of the instructions processed by one program
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ProgramProfile {
+    pub invocations: u64,
+    /// Compute units consumed, excluding those of nested CPIs
+    pub compute_units_consumed: u64,
+    pub account_data_bytes_read: u64,
+    pub account_data_bytes_written: u64,
+    /// Syscalls invoked by the program, by name
+    pub syscalls: BTreeMap<String, SyscallProfile>,
+}
+
+/// Totals of the invocations of one syscall
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SyscallProfile {
+    pub invocations: u64,
+    /// Compute units charged by the syscall, for CPI syscalls including those
+    /// consumed by the callee
+    pub compute_units: u64,
+}
+
+/// Totals of the CPIs from one program into another
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CpiEdgeProfile {
+    pub invocations: u64,
+    /// Compute units consumed by the callee, including those of nested CPIs
+    pub compute_units_consumed: u64,
+}
+
+/// Completed CPIs of an instruction that is still executing
+#[derive(Debug, Default)]
+struct PendingCalls {
+    compute_units_consumed: u64,
+    stacks: Vec<(String, u64)>,
+}
+
+#[derive(Debug, Default)]
+struct ProfilerState {
+    profile: Profile,
+    /// Folded call stacks and their exclusive compute units
+    stacks: BTreeMap<String, u64>,
+    /// Per executing thread, the completed CPIs by stack height of the caller
+    pending: HashMap<ThreadId, Vec<PendingCalls>>,
+}
+
+/// Aggregates the measurements of the banks into a [`Profile`], and the
+/// register traces into coverage if enabled
+#[derive(Debug)]
+pub(crate) struct Profiler {
+    output: Option<(PathBuf, ProfileFormat)>,
+    state: Mutex<ProfilerState>,
+    coverage: Option<CoverageCollector>,
+}
+
+impl Profiler {
+    pub(crate) fn new(
+        output: Option<(PathBuf, ProfileFormat)>,
+        coverage: Option<CoverageCollector>,
+    ) -> Self {
+        Self {
+            output,
+            state: Mutex::default(),
+            coverage,
+        }
+    }
+
+    pub(crate) fn profile(&self) -> Profile {
+        self.state.lock().unwrap().profile.clone()
+    }
+
+    /// Writes the profile to the output file and the coverage to the
+    /// coverage directory, if any
+    pub(crate) fn write_output(&self) {
+        if let Some((path, format)) = &self.output {
+            match self.write(path, *format) {
+                Ok(()) => info!("Wrote profile to {}", path.display()),
+                Err(err) => warn!("Failed to write profile to {}: {err}", path.display()),
+            }
+        }
+        if let Some(coverage) = &self.coverage {
+            coverage.write_output();
+        }
+    }
+
+    fn write(&self, path: &Path, format: ProfileFormat) -> io::Result<()> {
+        let state = self.state.lock().unwrap();
+        let contents = match format {
+            ProfileFormat::Json => serde_json::to_string_pretty(&state.profile)?,
+            ProfileFormat::FoldedStacks => {
+                let mut folded = String::new();
+                for (stack, compute_units) in &state.stacks {
+                    writeln!(folded, "{stack} {compute_units}").unwrap();
+                }
+                folded
+            }
+        };
+        fs::write(path, contents)
+    }
+}
+
+/// Writes the profile once the test environment is dropped, as the banks
+/// holding the profiler may outlive it
+pub(crate) struct ProfileWriter(pub(crate) Arc<Profiler>);
+
+impl Drop for ProfileWriter {
+    fn drop(&mut self) {
+        self.0.write_output();
+    }
+}
+
+impl ExecutionProfiler for Profiler {
+    fn instruction_processed(&self, profile: &InstructionProfile) {
+        let mut state = self.state.lock().unwrap();
+        let ProfilerState {
+            profile: totals,
+            stacks,
+            pending,
+        } = &mut *state;
+
+        // Instructions are reported once they completed, so the CPIs of an
+        // instruction are all reported before the instruction itself
+        let pending = pending.entry(thread::current().id()).or_default();
+        if pending.len() <= profile.stack_height {
+            pending.resize_with(profile.stack_height + 1, PendingCalls::default);
+        }
+        let calls = std::mem::take(&mut pending[profile.stack_height]);
+        let exclusive_units = profile
+            .compute_units_consumed
+            .saturating_sub(calls.compute_units_consumed);
+        let frame = profile.program_id.to_string();
+        let frame_stacks = std::iter::once((frame.clone(), exclusive_units)).chain(
+            calls
+                .stacks
+                .into_iter()
+                .map(|(stack, units)| (format!("{frame};{stack}"), units)),
+        );
+        if profile.stack_height > 1 {
+            let caller_calls = &mut pending[profile.stack_height - 1];
+            caller_calls.compute_units_consumed = caller_calls
+                .compute_units_consumed
+                .saturating_add(profile.compute_units_consumed);
+            caller_calls.stacks.extend(frame_stacks);
+        } else {
+            for (stack, units) in frame_stacks {
+                *stacks.entry(stack).or_default() += units;
+            }
+        }
+
+        totals.instructions.push(ProfiledInstruction {
+            program_id: profile.program_id,
+            stack_height: profile.stack_height,
+            compute_units_consumed: profile.compute_units_consumed,
+            account_data_bytes_read: profile.account_data_bytes_read,
+            account_data_bytes_written: profile.account_data_bytes_written,
+            succeeded: profile.succeeded,
+        });
+        let program = totals.programs.entry(profile.program_id).or_default();
+        program.invocations += 1;
+        program.compute_units_consumed += exclusive_units;
+        program.account_data_bytes_read += profile.account_data_bytes_read;
+        program.account_data_bytes_written += profile.account_data_bytes_written;
+        if let Some(caller_program_id) = profile.caller_program_id {
+            let edge = totals
+                .cpi_edges
+                .entry((caller_program_id, profile.program_id))
+                .or_default();
+            edge.invocations += 1;
+            edge.compute_units_consumed += profile.compute_units_consumed;
+        }
+    }
+
+    fn syscall_invoked(&self, program_id: &Pubkey, name: &'static str, compute_units: u64) {
+        let mut state = self.state.lock().unwrap();
+        let syscall = state
+            .profile
+            .programs
+            .entry(*program_id)
+            .or_default()
+            .syscalls
+            .entry(name.to_string())
+            .or_default();
+        syscall.invocations += 1;
+        syscall.compute_units += compute_units;
+    }
+
+    fn program_traced(&self, program_id: &Pubkey, register_trace: RegisterTrace) {
+        if let Some(coverage) = &self.coverage {
+            coverage.record(program_id, register_trace);
+        }
+    }
+}
+
+fn serialize_display<S: Serializer>(value: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+fn serialize_programs<S: Serializer>(
+    programs: &BTreeMap<Pubkey, ProgramProfile>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(
+        programs
+            .iter()
+            .map(|(program_id, program)| (program_id.to_string(), program)),
+    )
+}
+
+fn serialize_cpi_edges<S: Serializer>(
+    cpi_edges: &BTreeMap<(Pubkey, Pubkey), CpiEdgeProfile>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Edge<'a> {
+        caller: String,
+        callee: String,
+        #[serde(flatten)]
+        profile: &'a CpiEdgeProfile,
+    }
+    serializer.collect_seq(cpi_edges.iter().map(|((caller, callee), profile)| Edge {
+        caller: caller.to_string(),
+        callee: callee.to_string(),
+        profile,
+    }))
+}
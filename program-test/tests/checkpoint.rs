@@ -0,0 +1,98 @@
+use {
+    solana_keypair::Keypair,
+    solana_program_test::{ProgramTest, ProgramTestContext, ProgramTestError},
+    solana_pubkey::Pubkey,
+    solana_rent::Rent,
+    solana_signer::Signer,
+    solana_system_interface::instruction as system_instruction,
+    solana_transaction::Transaction,
+};
+
+async fn transfer(context: &mut ProgramTestContext, to: &Pubkey, lamports: u64) {
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            to,
+            lamports,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn restore_checkpoint() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let recipient = Keypair::new().pubkey();
+    let rent_exempt_minimum = Rent::default().minimum_balance(0);
+
+    transfer(&mut context, &recipient, rent_exempt_minimum).await;
+    let checkpoint = context.checkpoint();
+    assert!(context.banks_client.get_root_slot().await.unwrap() > checkpoint.slot());
+
+    // Each scenario starts from the state saved by the checkpoint, so the
+    // same transaction can be replayed after every restore.
+    for _ in 0..2 {
+        transfer(&mut context, &recipient, 1).await;
+        assert_eq!(
+            context.banks_client.get_balance(recipient).await.unwrap(),
+            rent_exempt_minimum + 1,
+        );
+        context.restore(&checkpoint).unwrap();
+        assert_eq!(
+            context.banks_client.get_balance(recipient).await.unwrap(),
+            rent_exempt_minimum,
+        );
+    }
+}
+
+#[tokio::test]
+async fn fork_and_switch_back() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let recipient = Keypair::new().pubkey();
+    let rent_exempt_minimum = Rent::default().minimum_balance(0);
+
+    transfer(&mut context, &recipient, rent_exempt_minimum).await;
+    let checkpoint = context.checkpoint();
+
+    transfer(&mut context, &recipient, 1).await;
+    let first_branch = context.fork(&checkpoint).unwrap();
+
+    transfer(&mut context, &recipient, 2).await;
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        rent_exempt_minimum + 2,
+    );
+    let second_branch = context.fork(&first_branch).unwrap();
+
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        rent_exempt_minimum + 1,
+    );
+    context.restore(&second_branch).unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        rent_exempt_minimum + 2,
+    );
+}
+
+#[tokio::test]
+async fn checkpoint_pruned_by_warp() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let checkpoint = context.checkpoint();
+    let other_branch = context.fork(&checkpoint).unwrap();
+
+    let slot = context.banks_client.get_root_slot().await.unwrap();
+    context.warp_to_slot(slot + 10).unwrap();
+
+    assert_eq!(
+        context.restore(&other_branch),
+        Err(ProgramTestError::InvalidCheckpoint),
+    );
+}
@@ -0,0 +1,174 @@
+use {
+    base64::{prelude::BASE64_STANDARD, Engine},
+    solana_address_lookup_table_interface::{
+        self as address_lookup_table,
+        state::{LookupTableMeta, ProgramState, LOOKUP_TABLE_META_SIZE},
+    },
+    solana_instruction::Instruction,
+    solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState},
+    solana_program_test::{
+        find_file, read_file, AccountSourceError, JsonAccountSource, ProgramTest,
+    },
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::bpf_loader_upgradeable,
+    solana_signer::Signer,
+    solana_transaction::Transaction,
+    std::{fs, path::Path},
+};
+
+/// Write `account` to `dir` the way `solana account --output json` does
+fn write_account(dir: &Path, address: &Pubkey, owner: &Pubkey, data: &[u8], executable: bool) {
+    let json = serde_json::json!({
+        "pubkey": address.to_string(),
+        "account": {
+            "lamports": 1_000_000_000,
+            "data": [BASE64_STANDARD.encode(data), "base64"],
+            "owner": owner.to_string(),
+            "executable": executable,
+            "rentEpoch": 0,
+            "space": data.len(),
+        },
+    });
+    fs::write(dir.join(format!("{address}.json")), json.to_string()).unwrap();
+}
+
+fn write_upgradeable_program(dir: &Path, program_id: &Pubkey, deployment_slot: u64) {
+    let programdata_address = get_program_data_address(program_id);
+    write_account(
+        dir,
+        program_id,
+        &bpf_loader_upgradeable::id(),
+        &bincode::serialize(&UpgradeableLoaderState::Program {
+            programdata_address,
+        })
+        .unwrap(),
+        true,
+    );
+    let mut programdata = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: deployment_slot,
+        upgrade_authority_address: Some(Pubkey::new_unique()),
+    })
+    .unwrap();
+    programdata.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+    programdata.extend(read_file(find_file("noop_program.so").unwrap()));
+    write_account(
+        dir,
+        &programdata_address,
+        &bpf_loader_upgradeable::id(),
+        &programdata,
+        false,
+    );
+}
+
+#[tokio::test]
+async fn clone_program_from_json_dumps() {
+    let dir = tempfile::tempdir().unwrap();
+    let program_id = Pubkey::new_unique();
+    // Deployed far past the slots the test environment runs at
+    write_upgradeable_program(dir.path(), &program_id, 1_000_000);
+    let source = JsonAccountSource::new(dir.path()).unwrap();
+
+    let mut program_test = ProgramTest::default();
+    program_test
+        .clone_upgradeable_programs(&[program_id], &source)
+        .unwrap();
+    let context = program_test.start_with_context().await;
+
+    let programdata = context
+        .banks_client
+        .get_account(get_program_data_address(&program_id))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        bincode::deserialize::<UpgradeableLoaderState>(&programdata.data).unwrap(),
+        UpgradeableLoaderState::ProgramData { slot: 0, .. }
+    ));
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(program_id, &[], vec![])],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn clone_lookup_table_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let lookup_table_address = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let plain_account = Pubkey::new_unique();
+    let missing_account = Pubkey::new_unique();
+    write_upgradeable_program(dir.path(), &program_id, 0);
+    write_account(
+        dir.path(),
+        &plain_account,
+        &Pubkey::new_unique(),
+        &[7; 3],
+        false,
+    );
+
+    let mut lookup_table =
+        bincode::serialize(&ProgramState::LookupTable(LookupTableMeta::default())).unwrap();
+    lookup_table.resize(LOOKUP_TABLE_META_SIZE, 0);
+    for entry in [program_id, plain_account, missing_account] {
+        lookup_table.extend_from_slice(entry.as_ref());
+    }
+    write_account(
+        dir.path(),
+        &lookup_table_address,
+        &address_lookup_table::program::id(),
+        &lookup_table,
+        false,
+    );
+    let source = JsonAccountSource::new(dir.path()).unwrap();
+
+    let mut program_test = ProgramTest::default();
+    assert!(matches!(
+        program_test.clone_accounts(&[Pubkey::new_unique()], &source, false),
+        Err(AccountSourceError::AccountNotFound(_))
+    ));
+    assert!(matches!(
+        program_test.clone_upgradeable_programs(&[plain_account], &source),
+        Err(AccountSourceError::NotAnUpgradeableProgram(address)) if address == plain_account
+    ));
+    program_test
+        .clone_accounts(&[lookup_table_address, Pubkey::new_unique()], &source, true)
+        .unwrap();
+    let context = program_test.start_with_context().await;
+
+    // The lookup table pulls in its entries, and the program its programdata
+    for address in [
+        lookup_table_address,
+        program_id,
+        get_program_data_address(&program_id),
+    ] {
+        assert!(context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .is_some());
+    }
+    let account = context
+        .banks_client
+        .get_account(plain_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data, vec![7; 3]);
+    assert_eq!(
+        context
+            .banks_client
+            .get_account(missing_account)
+            .await
+            .unwrap(),
+        None
+    );
+}
@@ -0,0 +1,38 @@
+use {
+    solana_instruction::Instruction, solana_program_test::ProgramTest, solana_pubkey::Pubkey,
+    solana_signer::Signer, solana_transaction::Transaction, std::fs,
+};
+
+#[tokio::test]
+async fn coverage_of_sbf_program() {
+    let program_id = Pubkey::new_unique();
+    let coverage_dir = tempfile::tempdir().unwrap();
+
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("noop_program", program_id, None);
+    program_test.enable_coverage(coverage_dir.path());
+    let context = program_test.start_with_context().await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(program_id, &[], vec![])],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+    drop(context);
+
+    let lcov = fs::read_to_string(coverage_dir.path().join(format!("{program_id}.lcov"))).unwrap();
+    assert!(lcov.starts_with("SF:"));
+    assert!(lcov.ends_with("end_of_record\n"));
+    let lines_hit = lcov
+        .lines()
+        .find_map(|line| line.strip_prefix("LH:"))
+        .unwrap();
+    assert_ne!(lines_hit, "0");
+}
@@ -0,0 +1,107 @@
+use {
+    solana_account_info::{next_account_info, AccountInfo},
+    solana_instruction::{AccountMeta, Instruction},
+    solana_program::program::invoke,
+    solana_program_entrypoint::ProgramResult,
+    solana_program_test::{processor, ProfileFormat, ProgramTest},
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_transaction::Transaction,
+    std::slice,
+};
+
+fn invoker_process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _input: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let invoked_program_info = next_account_info(account_info_iter)?;
+    invoke(
+        &Instruction::new_with_bincode(
+            *invoked_program_info.key,
+            &[0],
+            vec![AccountMeta::new_readonly(*invoked_program_info.key, false)],
+        ),
+        slice::from_ref(invoked_program_info),
+    )
+}
+
+fn invoked_process_instruction(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    _input: &[u8],
+) -> ProgramResult {
+    Ok(())
+}
+
+#[tokio::test]
+async fn profile_cpi() {
+    let invoker_program_id = Pubkey::new_unique();
+    let invoked_program_id = Pubkey::new_unique();
+    let profile_path = std::env::temp_dir().join(format!("profile-{invoker_program_id}.json"));
+    let mut program_test = ProgramTest::new(
+        "invoker",
+        invoker_program_id,
+        processor!(invoker_process_instruction),
+    );
+    program_test.add_program(
+        "invoked",
+        invoked_program_id,
+        processor!(invoked_process_instruction),
+    );
+    program_test.enable_profiling(&profile_path, ProfileFormat::Json);
+    let mut context = program_test.start_with_context().await;
+
+    let instruction = Instruction::new_with_bincode(
+        invoker_program_id,
+        &[0],
+        vec![AccountMeta::new_readonly(invoked_program_id, false)],
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let profile = context.profile().unwrap();
+    let invoker = &profile.programs[&invoker_program_id];
+    let invoked = &profile.programs[&invoked_program_id];
+    assert_eq!(invoker.invocations, 1);
+    assert_eq!(invoked.invocations, 1);
+    let edge = &profile.cpi_edges[&(invoker_program_id, invoked_program_id)];
+    assert_eq!(edge.invocations, 1);
+    assert_eq!(edge.compute_units_consumed, invoked.compute_units_consumed);
+
+    // Per instruction compute units include those of the CPIs, per program
+    // ones do not
+    let top_level = profile
+        .instructions
+        .iter()
+        .find(|instruction| instruction.program_id == invoker_program_id)
+        .unwrap();
+    assert_eq!(top_level.stack_height, 1);
+    assert_eq!(
+        top_level.compute_units_consumed,
+        invoker.compute_units_consumed + invoked.compute_units_consumed,
+    );
+
+    drop(context);
+    let json: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&profile_path).unwrap()).unwrap();
+    std::fs::remove_file(&profile_path).unwrap();
+    assert_eq!(
+        json["programs"][invoked_program_id.to_string()]["invocations"],
+        1,
+    );
+    assert_eq!(
+        json["cpi_edges"][0]["caller"],
+        invoker_program_id.to_string(),
+    );
+}
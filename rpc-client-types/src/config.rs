@@ -1,7 +1,7 @@
 use {
     crate::filter::RpcFilterType,
     serde::{Deserialize, Serialize},
-    solana_clock::{Epoch, Slot},
+    solana_clock::{Epoch, Slot, UnixTimestamp},
 };
 pub use {
     solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig},
@@ -343,3 +343,13 @@ pub struct RpcContextConfig {
     pub commitment: Option<CommitmentConfig>,
     pub min_context_slot: Option<Slot>,
 }
+
+/// Clock changes made by the `setClock` test validator method.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSetClockConfig {
+    /// Waits for the validator to reach this slot.
+    pub slot: Option<Slot>,
+    /// Moves the timestamp of the clock forward to this time.
+    pub unix_timestamp: Option<UnixTimestamp>,
+}
@@ -7,10 +7,12 @@ use {
     reqwest::{self, header::CONTENT_TYPE},
     serde_json::{json, Value},
     solana_account_decoder::UiAccount,
+    solana_clock::{Slot, DEFAULT_MS_PER_SLOT},
     solana_commitment_config::CommitmentConfig,
     solana_hash::Hash,
     solana_keypair::Keypair,
     solana_net_utils::{sockets, SocketAddrSpace},
+    solana_poh::automine::AutomineConfig,
     solana_pubkey::Pubkey,
     solana_pubsub_client::nonblocking::pubsub_client::PubsubClient,
     solana_rent::Rent,
@@ -18,13 +20,13 @@ use {
     solana_rpc_client_api::{
         client_error::{ErrorKind as ClientErrorKind, Result as ClientResult},
         config::{RpcAccountInfoConfig, RpcSignatureSubscribeConfig, RpcSimulateTransactionConfig},
-        request::RpcError,
+        request::{RpcError, RpcRequest},
         response::{Response as RpcResponse, RpcSignatureResult, SlotUpdate},
     },
     solana_signature::Signature,
     solana_signer::Signer,
     solana_system_transaction as system_transaction,
-    solana_test_validator::TestValidator,
+    solana_test_validator::{TestValidator, TestValidatorGenesis},
     solana_tpu_client_next::{client_builder::ClientBuilder, leader_updater::LeaderUpdater},
     solana_transaction::Transaction,
     solana_transaction_status::TransactionStatus,
@@ -597,3 +599,86 @@ fn deserialize_rpc_error() -> ClientResult<()> {
         }
     }
 }
+
+#[test]
+fn test_rpc_automine() {
+    agave_logger::setup();
+
+    let (test_validator, mint_keypair) = TestValidatorGenesis::default()
+        .automine(AutomineConfig::default())
+        .start();
+    let rpc_client =
+        RpcClient::new_with_commitment(test_validator.rpc_url(), CommitmentConfig::finalized());
+
+    // No blocks are mined while idle
+    let slot = rpc_client.get_slot().unwrap();
+    sleep(Duration::from_millis(4 * DEFAULT_MS_PER_SLOT));
+    assert_eq!(rpc_client.get_slot().unwrap(), slot);
+
+    // Transactions are mined and finalized right away, without votes
+    let blockhash = rpc_client.get_latest_blockhash().unwrap();
+    let tx = system_transaction::transfer(&mint_keypair, &Pubkey::new_unique(), 1, blockhash);
+    let now = Instant::now();
+    let signature = rpc_client.send_and_confirm_transaction(&tx).unwrap();
+    assert!(now.elapsed() < Duration::from_millis(8 * DEFAULT_MS_PER_SLOT));
+    let tx_slot = rpc_client
+        .get_signature_statuses(&[signature])
+        .unwrap()
+        .value[0]
+        .as_ref()
+        .unwrap()
+        .slot;
+    let block = rpc_client.get_block(tx_slot).unwrap();
+    assert_eq!(block.transactions.len(), 1);
+
+    // Blocks are mined on request, even empty
+    let mined_slot: Slot = rpc_client
+        .send(
+            RpcRequest::Custom {
+                method: "mineBlock",
+            },
+            Value::Null,
+        )
+        .unwrap();
+    assert!(mined_slot > tx_slot);
+    assert!(rpc_client
+        .get_block(mined_slot)
+        .unwrap()
+        .transactions
+        .is_empty());
+    assert_eq!(rpc_client.get_slot().unwrap(), mined_slot);
+}
+
+#[test]
+fn test_rpc_advance_epoch() {
+    agave_logger::setup();
+
+    let (test_validator, mint_keypair) = TestValidatorGenesis::default().start();
+    let rpc_client =
+        RpcClient::new_with_commitment(test_validator.rpc_url(), CommitmentConfig::processed());
+    let epoch_info = rpc_client.get_epoch_info().unwrap();
+
+    // The bank forks are warped to the next epoch rather than waiting for it
+    let now = Instant::now();
+    let epoch: u64 = rpc_client
+        .send(
+            RpcRequest::Custom {
+                method: "advanceEpoch",
+            },
+            Value::Null,
+        )
+        .unwrap();
+    assert_eq!(epoch, epoch_info.epoch + 1);
+    assert!(now.elapsed() < Duration::from_secs(10));
+    assert_eq!(rpc_client.get_epoch_info().unwrap().epoch, epoch);
+
+    // Blocks are still produced and finalized on top of the warped bank
+    let blockhash = rpc_client.get_latest_blockhash().unwrap();
+    let tx = system_transaction::transfer(&mint_keypair, &Pubkey::new_unique(), 1, blockhash);
+    rpc_client
+        .send_and_confirm_transaction_with_spinner_and_commitment(
+            &tx,
+            CommitmentConfig::finalized(),
+        )
+        .unwrap();
+}
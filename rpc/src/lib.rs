@@ -15,6 +15,7 @@ pub mod rpc_service;
 pub mod rpc_subscription_tracker;
 pub mod rpc_subscriptions;
 pub mod slot_status_notifier;
+pub mod slot_warper;
 pub mod transaction_notifier_interface;
 pub mod transaction_status_service;
 
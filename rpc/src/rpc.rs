@@ -6,6 +6,7 @@ use {
         filter::filter_allows, max_slots::MaxSlots,
         optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
         parsed_token_accounts::*, rpc_cache::LargestAccountsCache, rpc_health::*,
+        slot_warper::SlotWarper,
     },
     agave_snapshots::{paths as snapshot_paths, snapshot_config::SnapshotConfig},
     base64::{prelude::BASE64_STANDARD, Engine},
@@ -49,6 +50,7 @@ use {
     solana_message::{AddressLoader, SanitizedMessage},
     solana_metrics::inc_new_counter_info,
     solana_perf::packet::PACKET_DATA_SIZE,
+    solana_poh::automine::BlockMiner,
     solana_program_pack::Pack,
     solana_pubkey::{Pubkey, PUBKEY_BYTES},
     solana_rpc_client_api::{
@@ -176,6 +178,9 @@ pub struct JsonRpcConfig {
     pub max_request_body_size: Option<usize>,
     /// Disable the health check, used for tests and TestValidator
     pub disable_health_check: bool,
+    /// Enable the methods that modify the state of the working bank, used
+    /// for TestValidator
+    pub enable_test_methods: bool,
 }
 
 impl Default for JsonRpcConfig {
@@ -196,6 +201,7 @@ impl Default for JsonRpcConfig {
             rpc_scan_and_fix_roots: Default::default(),
             max_request_body_size: Option::default(),
             disable_health_check: Default::default(),
+            enable_test_methods: Default::default(),
         }
     }
 }
@@ -254,6 +260,9 @@ pub struct JsonRpcRequestProcessor {
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
     runtime: Arc<Runtime>,
+    state_snapshots: Arc<RwLock<rpc_test::StateSnapshots>>,
+    block_miner: Option<BlockMiner>,
+    slot_warper: Option<SlotWarper>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
@@ -417,6 +426,8 @@ impl JsonRpcRequestProcessor {
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
         runtime: Arc<Runtime>,
+        block_miner: Option<BlockMiner>,
+        slot_warper: Option<SlotWarper>,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (transaction_sender, transaction_receiver) = unbounded();
         (
@@ -439,6 +450,9 @@ impl JsonRpcRequestProcessor {
                 max_complete_transaction_status_slot,
                 prioritization_fee_cache,
                 runtime,
+                state_snapshots: Arc::default(),
+                block_miner,
+                slot_warper,
             },
             transaction_receiver,
         )
@@ -522,6 +536,9 @@ impl JsonRpcRequestProcessor {
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Some(Arc::new(PrioritizationFeeCache::default())),
             runtime,
+            state_snapshots: Arc::default(),
+            block_miner: None,
+            slot_warper: None,
         }
     }
 
@@ -4352,6 +4369,373 @@ fn rpc_perf_sample_from_perf_sample(slot: u64, sample: PerfSample) -> RpcPerfSam
     }
 }
 
+// Test validator methods
+pub mod rpc_test {
+    use {
+        super::*,
+        solana_clock::Epoch,
+        solana_runtime::bank::state_overrides::{BankStateSnapshot, StateOverrideError},
+        std::time::Instant,
+    };
+
+    // The working bank can be frozen for a short while until the next bank
+    // is created
+    const FROZEN_BANK_RETRIES: usize = 100;
+    const FROZEN_BANK_RETRY_DELAY: Duration = Duration::from_millis(10);
+    // How long mineBlock waits for the block to be mined, then to be rooted
+    const MINE_BLOCK_TIMEOUT: Duration = Duration::from_secs(10);
+    // How long the test methods wait for the bank forks to be warped, then
+    // for the working bank to reach the requested slot
+    const WARP_TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[derive(Default)]
+    pub(crate) struct StateSnapshots {
+        next_id: u64,
+        snapshots: HashMap<u64, Arc<BankStateSnapshot>>,
+    }
+
+    #[rpc]
+    pub trait Test {
+        type Metadata;
+
+        #[rpc(meta, name = "setAccount")]
+        fn set_account(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            account: UiAccount,
+        ) -> BoxFuture<Result<Slot>>;
+
+        #[rpc(meta, name = "setClock")]
+        fn set_clock(
+            &self,
+            meta: Self::Metadata,
+            config: RpcSetClockConfig,
+        ) -> BoxFuture<Result<Slot>>;
+
+        #[rpc(meta, name = "advanceEpoch")]
+        fn advance_epoch(&self, meta: Self::Metadata) -> BoxFuture<Result<Epoch>>;
+
+        #[rpc(meta, name = "activateFeature")]
+        fn activate_feature(
+            &self,
+            meta: Self::Metadata,
+            feature_id_str: String,
+        ) -> BoxFuture<Result<Slot>>;
+
+        #[rpc(meta, name = "deactivateFeature")]
+        fn deactivate_feature(
+            &self,
+            meta: Self::Metadata,
+            feature_id_str: String,
+        ) -> BoxFuture<Result<Slot>>;
+
+        #[rpc(meta, name = "replaceProgram")]
+        fn replace_program(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            elf: String,
+        ) -> BoxFuture<Result<Slot>>;
+
+        #[rpc(meta, name = "snapshotState")]
+        fn snapshot_state(&self, meta: Self::Metadata) -> BoxFuture<Result<u64>>;
+
+        #[rpc(meta, name = "revertState")]
+        fn revert_state(&self, meta: Self::Metadata, snapshot_id: u64) -> BoxFuture<Result<usize>>;
+
+        #[rpc(meta, name = "mineBlock")]
+        fn mine_block(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>>;
+    }
+
+    pub struct TestImpl;
+    impl Test for TestImpl {
+        type Metadata = JsonRpcRequestProcessor;
+
+        fn set_account(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            account: UiAccount,
+        ) -> BoxFuture<Result<Slot>> {
+            debug!("set_account rpc request received: {pubkey_str:?}");
+            async move {
+                let pubkey = verify_pubkey(&pubkey_str)?;
+                let account = account.to_account_shared_data().ok_or_else(|| {
+                    Error::invalid_params("Invalid param: failed to decode account")
+                })?;
+                meta.override_working_bank(move |bank| bank.override_account(&pubkey, &account))
+                    .await
+                    .map(|(slot, ())| slot)
+            }
+            .boxed()
+        }
+
+        fn set_clock(
+            &self,
+            meta: Self::Metadata,
+            config: RpcSetClockConfig,
+        ) -> BoxFuture<Result<Slot>> {
+            debug!("set_clock rpc request received: {config:?}");
+            async move {
+                let RpcSetClockConfig {
+                    slot,
+                    unix_timestamp,
+                } = config;
+                let slot = match slot {
+                    Some(slot) => meta.warp_to_slot(slot).await?,
+                    None => meta.bank_forks.read().unwrap().working_bank().slot(),
+                };
+                match unix_timestamp {
+                    Some(unix_timestamp) => meta
+                        .override_working_bank(move |bank| {
+                            bank.override_unix_timestamp(unix_timestamp)
+                        })
+                        .await
+                        .map(|(slot, ())| slot),
+                    None => Ok(slot),
+                }
+            }
+            .boxed()
+        }
+
+        fn advance_epoch(&self, meta: Self::Metadata) -> BoxFuture<Result<Epoch>> {
+            debug!("advance_epoch rpc request received");
+            async move {
+                let bank = meta.bank_forks.read().unwrap().working_bank();
+                let next_epoch = bank.epoch().saturating_add(1);
+                let next_epoch_slot = bank.epoch_schedule().get_first_slot_in_epoch(next_epoch);
+                meta.warp_to_slot(next_epoch_slot).await?;
+                Ok(next_epoch)
+            }
+            .boxed()
+        }
+
+        fn activate_feature(
+            &self,
+            meta: Self::Metadata,
+            feature_id_str: String,
+        ) -> BoxFuture<Result<Slot>> {
+            debug!("activate_feature rpc request received: {feature_id_str:?}");
+            async move {
+                let feature_id = verify_pubkey(&feature_id_str)?;
+                meta.override_working_bank(move |bank| {
+                    bank.schedule_feature_activation(&feature_id)
+                })
+                .await
+                .map(|(_slot, activation_slot)| activation_slot)
+            }
+            .boxed()
+        }
+
+        fn deactivate_feature(
+            &self,
+            meta: Self::Metadata,
+            feature_id_str: String,
+        ) -> BoxFuture<Result<Slot>> {
+            debug!("deactivate_feature rpc request received: {feature_id_str:?}");
+            async move {
+                let feature_id = verify_pubkey(&feature_id_str)?;
+                meta.override_working_bank(move |bank| {
+                    bank.schedule_feature_deactivation(&feature_id)
+                })
+                .await
+                .map(|(_slot, deactivation_slot)| deactivation_slot)
+            }
+            .boxed()
+        }
+
+        fn replace_program(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            elf: String,
+        ) -> BoxFuture<Result<Slot>> {
+            debug!("replace_program rpc request received: {program_id_str:?}");
+            async move {
+                let program_id = verify_pubkey(&program_id_str)?;
+                let elf = BASE64_STANDARD
+                    .decode(elf)
+                    .map_err(|e| Error::invalid_params(format!("Invalid param: {e:?}")))?;
+                meta.override_working_bank(move |bank| bank.override_program(&program_id, &elf))
+                    .await
+                    .map(|(slot, ())| slot)
+            }
+            .boxed()
+        }
+
+        fn snapshot_state(&self, meta: Self::Metadata) -> BoxFuture<Result<u64>> {
+            debug!("snapshot_state rpc request received");
+            async move {
+                let (_slot, snapshot) = meta
+                    .override_working_bank(|bank| bank.snapshot_state())
+                    .await?;
+                let mut state_snapshots = meta.state_snapshots.write().unwrap();
+                let snapshot_id = state_snapshots.next_id;
+                state_snapshots.next_id = snapshot_id.saturating_add(1);
+                state_snapshots
+                    .snapshots
+                    .insert(snapshot_id, Arc::new(snapshot));
+                Ok(snapshot_id)
+            }
+            .boxed()
+        }
+
+        fn revert_state(&self, meta: Self::Metadata, snapshot_id: u64) -> BoxFuture<Result<usize>> {
+            debug!("revert_state rpc request received: {snapshot_id:?}");
+            async move {
+                let snapshot = meta
+                    .state_snapshots
+                    .read()
+                    .unwrap()
+                    .snapshots
+                    .get(&snapshot_id)
+                    .cloned()
+                    .ok_or_else(|| {
+                        Error::invalid_params(format!("Unknown state snapshot: {snapshot_id}"))
+                    })?;
+                meta.override_working_bank(move |bank| bank.revert_state(&snapshot))
+                    .await
+                    .map(|(_slot, reverted_accounts)| reverted_accounts)
+            }
+            .boxed()
+        }
+
+        fn mine_block(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>> {
+            debug!("mine_block rpc request received");
+            async move {
+                let block_miner = meta.block_miner.clone().ok_or_else(|| Error {
+                    code: error::ErrorCode::InvalidRequest,
+                    message: "Automine is not enabled; restart the validator with --automine"
+                        .to_string(),
+                    data: None,
+                })?;
+                let block_commitment_cache = meta.block_commitment_cache.clone();
+                let max_complete_transaction_status_slot = meta
+                    .config
+                    .enable_rpc_transaction_history
+                    .then(|| meta.max_complete_transaction_status_slot.clone());
+                meta.runtime
+                    .spawn_blocking(move || {
+                        let timed_out = || Error {
+                            code: error::ErrorCode::InternalError,
+                            message: "Timed out mining the block".to_string(),
+                            data: None,
+                        };
+                        let slot = block_miner
+                            .mine_block(MINE_BLOCK_TIMEOUT)
+                            .map_err(|_| timed_out())?;
+                        // Return once the block is finalized and can be fetched
+                        let deadline = Instant::now() + MINE_BLOCK_TIMEOUT;
+                        while Instant::now() < deadline {
+                            let is_rooted = block_commitment_cache
+                                .read()
+                                .unwrap()
+                                .highest_super_majority_root()
+                                >= slot;
+                            let is_stored = max_complete_transaction_status_slot
+                                .as_ref()
+                                .is_none_or(|max_slot| max_slot.load(Ordering::SeqCst) >= slot);
+                            if is_rooted && is_stored {
+                                return Ok(slot);
+                            }
+                            std::thread::sleep(FROZEN_BANK_RETRY_DELAY);
+                        }
+                        Err(timed_out())
+                    })
+                    .await
+                    .expect("rpc: mine_block panicked")
+            }
+            .boxed()
+        }
+    }
+
+    impl JsonRpcRequestProcessor {
+        /// Applies `f` to the working bank, retrying while it is frozen.
+        /// Returns the slot of the bank it was applied to.
+        async fn override_working_bank<T, F>(&self, f: F) -> Result<(Slot, T)>
+        where
+            T: Send + 'static,
+            F: Fn(&Bank) -> std::result::Result<T, StateOverrideError> + Send + 'static,
+        {
+            let bank_forks = self.bank_forks.clone();
+            self.runtime
+                .spawn_blocking(move || {
+                    for _ in 0..FROZEN_BANK_RETRIES {
+                        let bank = bank_forks.read().unwrap().working_bank();
+                        match f(&bank) {
+                            Ok(value) => return Ok((bank.slot(), value)),
+                            Err(StateOverrideError::BankFrozen(_)) => {
+                                std::thread::sleep(FROZEN_BANK_RETRY_DELAY)
+                            }
+                            Err(err @ StateOverrideError::Scan(_)) => {
+                                return Err(Error {
+                                    code: error::ErrorCode::InternalError,
+                                    message: err.to_string(),
+                                    data: None,
+                                })
+                            }
+                            Err(err) => return Err(Error::invalid_params(err.to_string())),
+                        }
+                    }
+                    Err(Error {
+                        code: error::ErrorCode::InternalError,
+                        message: "The working bank stayed frozen".to_string(),
+                        data: None,
+                    })
+                })
+                .await
+                .expect("rpc: override_working_bank panicked")
+        }
+
+        /// Moves the working bank to `slot`. When `slot` is more than one slot
+        /// ahead, the bank forks are warped to the slot before it, and the
+        /// working bank is then created on top of the warped bank. Returns the
+        /// slot of the working bank.
+        async fn warp_to_slot(&self, slot: Slot) -> Result<Slot> {
+            let bank_forks = self.bank_forks.clone();
+            let slot_warper = self.slot_warper.clone();
+            self.runtime
+                .spawn_blocking(move || {
+                    let working_slot = bank_forks.read().unwrap().working_bank().slot();
+                    let warp_slot = slot.saturating_sub(1);
+                    if warp_slot > working_slot {
+                        let slot_warper = slot_warper.ok_or_else(|| Error {
+                            code: error::ErrorCode::InvalidRequest,
+                            message: format!(
+                                "Slot {slot} is ahead of slot {working_slot} and this validator \
+                                 cannot warp its bank forks"
+                            ),
+                            data: None,
+                        })?;
+                        slot_warper
+                            .warp_to_slot(warp_slot, WARP_TIMEOUT)
+                            .map_err(|message| Error {
+                                code: error::ErrorCode::InternalError,
+                                message,
+                                data: None,
+                            })?;
+                    }
+                    let deadline = Instant::now() + WARP_TIMEOUT;
+                    while Instant::now() < deadline {
+                        let working_slot = bank_forks.read().unwrap().working_bank().slot();
+                        if working_slot >= slot {
+                            return Ok(working_slot);
+                        }
+                        std::thread::sleep(FROZEN_BANK_RETRY_DELAY);
+                    }
+                    Err(Error {
+                        code: error::ErrorCode::InternalError,
+                        message: format!("Timed out waiting for slot {slot}"),
+                        data: None,
+                    })
+                })
+                .await
+                .expect("rpc: warp_to_slot panicked")
+        }
+    }
+}
+
 const MAX_BASE58_SIZE: usize = 1683; // Golden, bump if PACKET_DATA_SIZE changes
 const MAX_BASE64_SIZE: usize = 1644; // Golden, bump if PACKET_DATA_SIZE changes
 fn decode_and_deserialize<T>(
@@ -4528,7 +4912,8 @@ pub fn populate_blockstore_for_tests(
 pub mod tests {
     use {
         super::{
-            rpc_accounts::*, rpc_accounts_scan::*, rpc_bank::*, rpc_full::*, rpc_minimal::*, *,
+            rpc_accounts::*, rpc_accounts_scan::*, rpc_bank::*, rpc_full::*, rpc_minimal::*,
+            rpc_test::*, *,
         },
         crate::{
             optimistically_confirmed_bank_tracker::{
@@ -4851,6 +5236,8 @@ pub mod tests {
                 max_complete_transaction_status_slot.clone(),
                 prioritization_fee_cache,
                 service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
+                None,
+                None,
             )
             .0;
 
@@ -4860,6 +5247,7 @@ pub mod tests {
             io.extend_with(rpc_accounts::AccountsDataImpl.to_delegate());
             io.extend_with(rpc_accounts_scan::AccountsScanImpl.to_delegate());
             io.extend_with(rpc_full::FullImpl.to_delegate());
+            io.extend_with(rpc_test::TestImpl.to_delegate());
             Self {
                 io,
                 meta,
@@ -5436,6 +5824,76 @@ pub mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_rpc_set_account_and_revert_state() {
+        let rpc = RpcHandler::start();
+        let pubkey = Pubkey::new_unique();
+        let request = create_test_request("snapshotState", None);
+        let snapshot_id: u64 = parse_success_result(rpc.handle_request_sync(request));
+
+        let request = create_test_request(
+            "setAccount",
+            Some(json!([
+                pubkey.to_string(),
+                {
+                    "lamports": 42,
+                    "data": [BASE64_STANDARD.encode([1, 2, 3]), "base64"],
+                    "owner": system_program::id().to_string(),
+                    "executable": false,
+                    "rentEpoch": 0,
+                },
+            ])),
+        );
+        let slot: Slot = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(slot, rpc.working_bank().slot());
+        let account = rpc.working_bank().get_account(&pubkey).unwrap();
+        assert_eq!(account.lamports(), 42);
+        assert_eq!(account.data(), &[1, 2, 3]);
+
+        let request = create_test_request("revertState", Some(json!([snapshot_id])));
+        let reverted_accounts: usize = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(reverted_accounts, 1);
+        assert!(rpc.working_bank().get_account(&pubkey).is_none());
+
+        let request = create_test_request("revertState", Some(json!([snapshot_id + 1])));
+        let (code, _message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+    }
+
+    #[test]
+    fn test_rpc_set_clock() {
+        let rpc = RpcHandler::start();
+        let unix_timestamp = rpc.working_bank().clock().unix_timestamp + 3600;
+        let request = create_test_request(
+            "setClock",
+            Some(json!([{ "slot": 0, "unixTimestamp": unix_timestamp }])),
+        );
+        let _slot: Slot = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(rpc.working_bank().clock().unix_timestamp, unix_timestamp);
+
+        let request = create_test_request(
+            "setClock",
+            Some(json!([{ "unixTimestamp": unix_timestamp - 1 }])),
+        );
+        let (code, _message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+
+        let request = create_test_request(
+            "setClock",
+            Some(json!([{ "slot": rpc.working_bank().slot() + 2 }])),
+        );
+        let (code, _message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidRequest.code());
+    }
+
+    #[test]
+    fn test_rpc_mine_block_without_automine() {
+        let rpc = RpcHandler::start();
+        let request = create_test_request("mineBlock", None);
+        let (code, _message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidRequest.code());
+    }
+
     #[test]
     fn test_rpc_get_inflation() {
         let rpc = RpcHandler::start();
@@ -6903,6 +7361,8 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Some(Arc::new(PrioritizationFeeCache::default())),
             runtime.clone(),
+            None,
+            None,
         );
 
         let client = create_client_for_tests(runtime.handle().clone(), my_tpu_address, None, 1);
@@ -7217,6 +7677,8 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Some(Arc::new(PrioritizationFeeCache::default())),
             runtime,
+            None,
+            None,
         );
 
         SendTransactionService::new(
@@ -8961,6 +9423,8 @@ pub mod tests {
             max_complete_transaction_status_slot,
             prioritization_fee_cache_inner.clone(),
             service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
+            None,
+            None,
         );
 
         let mut io = MetaIoHandler::default();
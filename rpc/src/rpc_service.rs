@@ -5,9 +5,13 @@ use {
         cluster_tpu_info::ClusterTpuInfo,
         max_slots::MaxSlots,
         optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
-        rpc::{rpc_accounts::*, rpc_accounts_scan::*, rpc_bank::*, rpc_full::*, rpc_minimal::*, *},
+        rpc::{
+            rpc_accounts::*, rpc_accounts_scan::*, rpc_bank::*, rpc_full::*, rpc_minimal::*,
+            rpc_test::*, *,
+        },
         rpc_cache::LargestAccountsCache,
         rpc_health::*,
+        slot_warper::SlotWarper,
     },
     agave_snapshots::{
         paths as snapshot_paths, snapshot_archive_info::SnapshotArchiveInfoGetter,
@@ -20,6 +24,7 @@ use {
         RequestMiddlewareAction, ServerBuilder,
     },
     regex::Regex,
+    solana_accounts_db::accounts_db::ReadCacheCaller,
     solana_cli_output::display::build_balance_message,
     solana_client::connection_cache::Protocol,
     solana_genesis_config::DEFAULT_GENESIS_DOWNLOAD_PATH,
@@ -33,7 +38,7 @@ use {
     },
     solana_metrics::inc_new_counter_info,
     solana_perf::thread::renice_this_thread,
-    solana_poh::poh_recorder::PohRecorder,
+    solana_poh::{automine::BlockMiner, poh_recorder::PohRecorder},
     solana_runtime::{
         bank::Bank, bank_forks::BankForks, commitment::BlockCommitmentCache,
         non_circulating_supply::calculate_non_circulating_supply,
@@ -489,6 +494,10 @@ pub struct JsonRpcServiceConfig<'a> {
     pub max_complete_transaction_status_slot: Arc<AtomicU64>,
     pub prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
     pub rpc_tpu_client_args: RpcTpuClientArgs<'a>,
+    /// Requests blocks for the `mineBlock` test method, in automine mode
+    pub block_miner: Option<BlockMiner>,
+    /// Warps the bank forks for the test methods that move to a later slot
+    pub slot_warper: Option<SlotWarper>,
 }
 
 /// Arguments required to create a TPU client for the RPC service.
@@ -552,6 +561,8 @@ impl JsonRpcService {
             config.max_complete_transaction_status_slot,
             config.prioritization_fee_cache,
             runtime,
+            config.block_miner,
+            config.slot_warper,
         )?;
         Ok(json_rpc_service)
     }
@@ -585,6 +596,8 @@ impl JsonRpcService {
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
         runtime: Arc<TokioRuntime>,
+        block_miner: Option<BlockMiner>,
+        slot_warper: Option<SlotWarper>,
     ) -> Result<Self, String> {
         info!("rpc bound to {rpc_addr:?}");
         info!("rpc configuration: {config:?}");
@@ -655,6 +668,7 @@ impl JsonRpcService {
             };
 
         let full_api = config.full_api;
+        let enable_test_methods = config.enable_test_methods;
         let max_request_body_size = config
             .max_request_body_size
             .unwrap_or(MAX_REQUEST_BODY_SIZE);
@@ -676,6 +690,8 @@ impl JsonRpcService {
             max_complete_transaction_status_slot,
             prioritization_fee_cache,
             Arc::clone(&runtime),
+            block_miner,
+            slot_warper,
         );
 
         let _send_transaction_service = Arc::new(SendTransactionService::new(
@@ -706,6 +722,9 @@ impl JsonRpcService {
                     io.extend_with(rpc_accounts_scan::AccountsScanImpl.to_delegate());
                     io.extend_with(rpc_full::FullImpl.to_delegate());
                 }
+                if enable_test_methods {
+                    io.extend_with(rpc_test::TestImpl.to_delegate());
+                }
 
                 let request_middleware = RpcRequestMiddleware::new(
                     ledger_path,
@@ -811,7 +830,10 @@ pub fn service_runtime(
         TokioBuilder::new_multi_thread()
             .worker_threads(rpc_threads)
             .max_blocking_threads(rpc_blocking_threads)
-            .on_thread_start(move || renice_this_thread(rpc_niceness_adj).unwrap())
+            .on_thread_start(move || {
+                renice_this_thread(rpc_niceness_adj).unwrap();
+                ReadCacheCaller::Rpc.set_for_current_thread();
+            })
             .thread_name("solRpcEl")
             .enable_all()
             .build()
@@ -904,6 +926,8 @@ mod tests {
             Arc::new(AtomicU64::default()),
             Some(Arc::new(PrioritizationFeeCache::default())),
             runtime,
+            None,
+            None,
         )
         .expect("assume successful JsonRpcService start");
         let thread = rpc_service.thread_hdl.thread();
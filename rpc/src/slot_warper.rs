@@ -0,0 +1,53 @@
+//! Warping the working bank ahead, for the test validator methods.
+//!
+//! Rather than waiting for the validator to reach a slot, the test methods
+//! send a [`SlotWarper`] request to the replay stage, which roots a bank
+//! warped to the requested slot and resets PoH onto it, like
+//! `--warp-slot` does at startup.
+use {
+    crossbeam_channel::{bounded, unbounded, Receiver, RecvTimeoutError, Sender},
+    solana_clock::Slot,
+    std::time::Duration,
+};
+
+/// A request to warp the working bank ahead, and the channel the replay stage
+/// answers it on, with the slot of the warped bank or why it could not warp.
+pub struct WarpRequest {
+    pub slot: Slot,
+    pub response_sender: Sender<Result<Slot, String>>,
+}
+
+pub type WarpRequestReceiver = Receiver<WarpRequest>;
+
+/// Create a pair of a [`SlotWarper`], to request warps, and of the receiver
+/// that the replay stage warps the bank forks with.
+pub fn slot_warper_channel() -> (SlotWarper, WarpRequestReceiver) {
+    let (sender, receiver) = unbounded();
+    (SlotWarper { sender }, receiver)
+}
+
+/// Requests warps from the replay stage of a test validator.
+#[derive(Clone, Debug)]
+pub struct SlotWarper {
+    sender: Sender<WarpRequest>,
+}
+
+impl SlotWarper {
+    /// Roots a frozen bank at `slot`, so that the next working bank is its
+    /// child. Returns the slot of the warped bank, or why it could not warp.
+    pub fn warp_to_slot(&self, slot: Slot, timeout: Duration) -> Result<Slot, String> {
+        let (response_sender, response_receiver) = bounded(1);
+        self.sender
+            .send(WarpRequest {
+                slot,
+                response_sender,
+            })
+            .map_err(|_| "The replay stage has exited".to_string())?;
+        response_receiver
+            .recv_timeout(timeout)
+            .map_err(|err| match err {
+                RecvTimeoutError::Timeout => "Timed out warping the bank forks".to_string(),
+                RecvTimeoutError::Disconnected => "The replay stage has exited".to_string(),
+            })?
+    }
+}
@@ -37,6 +37,9 @@ use {
     crate::{
         account_saver::collect_accounts_to_store,
         bank::{
+            account_fallback::{AccountFallback, BankAccountFallback},
+            commit_notifier::BankCommitNotifier,
+            instruction_recorder::{BankInstructionRecorder, BankInstructionRecorderAdapter},
             metrics::*,
             partitioned_epoch_rewards::{
                 CachedVoteAccounts, EpochRewardStatus, RewardCommissionAccounts,
@@ -126,6 +129,7 @@ use {
     solana_packet::PACKET_DATA_SIZE,
     solana_precompile_error::PrecompileError,
     solana_program_runtime::{
+        execution_profiler::ExecutionProfiler,
         invoke_context::BuiltinFunctionWithContext,
         loaded_programs::{ProgramCacheEntry, ProgramRuntimeEnvironments},
     },
@@ -145,6 +149,7 @@ use {
     solana_svm::{
         account_loader::LoadedTransaction,
         account_overrides::AccountOverrides,
+        instruction_recorder::InstructionRecorder,
         program_loader::load_program_with_pubkey,
         transaction_balances::{BalanceCollector, SvmTokenInfo},
         transaction_commit_result::{CommittedTransaction, TransactionCommitResult},
@@ -192,7 +197,7 @@ use {
                 AtomicBool, AtomicI64, AtomicU64,
                 Ordering::{self, AcqRel, Acquire, Relaxed},
             },
-            Arc, LockResult, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak,
+            Arc, LockResult, Mutex, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak,
         },
         time::{Duration, Instant},
     },
@@ -215,16 +220,20 @@ struct VerifyAccountsHashConfig {
     require_rooted_bank: bool,
 }
 
+pub mod account_fallback;
 mod accounts_lt_hash;
 mod address_lookup_table;
 pub mod bank_hash_details;
 pub mod builtins;
 mod check_transactions;
+pub mod commit_notifier;
 mod fee_distribution;
+pub mod instruction_recorder;
 mod metrics;
 pub(crate) mod partitioned_epoch_rewards;
 mod recent_blockhashes_account;
 mod serde_snapshot;
+pub mod state_overrides;
 mod sysvar_cache;
 pub(crate) mod tests;
 
@@ -575,6 +584,11 @@ impl PartialEq for Bank {
             feature_set: _,
             reserved_account_keys: _,
             drop_callback: _,
+            instruction_recorder: _,
+            execution_profiler: _,
+            commit_notifiers: _,
+            account_fallback: _,
+            scheduled_feature_deactivations: _,
             freeze_started: _,
             vote_only_bank: _,
             cost_tracker: _,
@@ -587,6 +601,7 @@ impl PartialEq for Bank {
             collector_fee_details: _,
             compute_budget: _,
             transaction_account_lock_limit: _,
+            debugging_features: _,
             fee_structure: _,
             cache_for_accounts_lt_hash: _,
             stats_for_accounts_lt_hash: _,
@@ -876,6 +891,28 @@ pub struct Bank {
     /// callback function only to be called when dropping and should only be called once
     pub drop_callback: RwLock<OptionalDropCallback>,
 
+    /// Receives the top-level instructions executed while replaying or
+    /// producing this bank's transactions. Inherited by child banks.
+    instruction_recorder: RwLock<Option<Arc<dyn BankInstructionRecorder>>>,
+
+    /// Receives measurements of the instructions and syscalls executed by
+    /// this bank's transactions. Inherited by child banks.
+    execution_profiler: RwLock<Option<Arc<dyn ExecutionProfiler>>>,
+
+    /// Receive the transactions committed by this bank and are told once it
+    /// is frozen. Inherited by child banks.
+    commit_notifiers: RwLock<Vec<Arc<dyn BankCommitNotifier>>>,
+
+    /// Supplies the accounts missing from this bank. Unset outside of test
+    /// validators, so that it costs a single check otherwise. Inherited by
+    /// child banks.
+    account_fallback: OnceLock<Arc<AccountFallback>>,
+
+    /// Active features to deactivate at the next epoch, scheduled by test
+    /// validators. `None` unless enabled by the runtime config. Inherited by
+    /// child banks.
+    scheduled_feature_deactivations: Option<RwLock<AHashSet<Pubkey>>>,
+
     pub freeze_started: AtomicBool,
 
     vote_only_bank: bool,
@@ -904,6 +941,9 @@ pub struct Bank {
     /// The max number of accounts that a transaction may lock.
     transaction_account_lock_limit: Option<usize>,
 
+    /// Whether SBF programs are executed with register tracing and symbols.
+    debugging_features: bool,
+
     /// Fee structure to use for assessing transaction fees.
     fee_structure: FeeStructure,
 
@@ -1132,6 +1172,11 @@ impl Bank {
             feature_set: Arc::<FeatureSet>::default(),
             reserved_account_keys: Arc::<ReservedAccountKeys>::default(),
             drop_callback: RwLock::new(OptionalDropCallback(None)),
+            instruction_recorder: RwLock::default(),
+            execution_profiler: RwLock::default(),
+            commit_notifiers: RwLock::default(),
+            account_fallback: OnceLock::new(),
+            scheduled_feature_deactivations: None,
             freeze_started: AtomicBool::default(),
             vote_only_bank: false,
             cost_tracker: RwLock::<CostTracker>::default(),
@@ -1144,6 +1189,7 @@ impl Bank {
             collector_fee_details: RwLock::new(CollectorFeeDetails::default()),
             compute_budget: None,
             transaction_account_lock_limit: None,
+            debugging_features: false,
             fee_structure: FeeStructure::default(),
             #[cfg(feature = "dev-context-only-utils")]
             hash_overrides: Arc::new(Mutex::new(HashOverrides::default())),
@@ -1188,6 +1234,9 @@ impl Bank {
                 .set_execution_cost(compute_budget.to_cost());
         }
         bank.transaction_account_lock_limit = runtime_config.transaction_account_lock_limit;
+        bank.debugging_features = runtime_config.debugging_features;
+        bank.scheduled_feature_deactivations =
+            runtime_config.feature_deactivations.then(RwLock::default);
         bank.transaction_debug_keys = debug_keys;
         bank.cluster_type = Some(genesis_config.cluster_type);
 
@@ -1381,6 +1430,14 @@ impl Bank {
                     .as_ref()
                     .map(|drop_callback| drop_callback.clone_box()),
             )),
+            instruction_recorder: RwLock::new(parent.instruction_recorder.read().unwrap().clone()),
+            execution_profiler: RwLock::new(parent.execution_profiler.read().unwrap().clone()),
+            commit_notifiers: RwLock::new(parent.commit_notifiers.read().unwrap().clone()),
+            account_fallback: parent.account_fallback.clone(),
+            scheduled_feature_deactivations: parent
+                .scheduled_feature_deactivations
+                .as_ref()
+                .map(|deactivations| RwLock::new(deactivations.read().unwrap().clone())),
             freeze_started: AtomicBool::new(false),
             cost_tracker: RwLock::new(parent.read_cost_tracker().unwrap().new_from_parent_limits()),
             accounts_data_size_initial,
@@ -1392,6 +1449,7 @@ impl Bank {
             collector_fee_details: RwLock::new(CollectorFeeDetails::default()),
             compute_budget: parent.compute_budget,
             transaction_account_lock_limit: parent.transaction_account_lock_limit,
+            debugging_features: parent.debugging_features,
             fee_structure: parent.fee_structure.clone(),
             #[cfg(feature = "dev-context-only-utils")]
             hash_overrides: parent.hash_overrides.clone(),
@@ -1799,6 +1857,40 @@ impl Bank {
         *self.drop_callback.write().unwrap() = OptionalDropCallback(callback);
     }
 
+    /// Sets the recorder that receives the top-level instructions executed by
+    /// this bank and by any bank created from it afterwards.
+    pub fn set_instruction_recorder(&self, recorder: Option<Arc<dyn BankInstructionRecorder>>) {
+        *self.instruction_recorder.write().unwrap() = recorder;
+    }
+
+    /// Sets the profiler that receives measurements of the instructions and
+    /// syscalls executed by this bank and by any bank created from it
+    /// afterwards.
+    pub fn set_execution_profiler(&self, profiler: Option<Arc<dyn ExecutionProfiler>>) {
+        *self.execution_profiler.write().unwrap() = profiler;
+    }
+
+    /// Adds a notifier that receives the transactions committed by this bank
+    /// and by any bank created from it afterwards, and that is told when those
+    /// banks are frozen. Notifiers added before are notified as well.
+    pub fn add_commit_notifier(&self, notifier: Arc<dyn BankCommitNotifier>) {
+        self.commit_notifiers.write().unwrap().push(notifier);
+    }
+
+    /// Sets the fallback that supplies the accounts missing from this bank
+    /// and from any bank created from it afterwards.
+    ///
+    /// Panics if the bank already has a fallback.
+    pub fn set_account_fallback(&self, fallback: Arc<dyn BankAccountFallback>) {
+        if self
+            .account_fallback
+            .set(Arc::new(AccountFallback::new(fallback)))
+            .is_err()
+        {
+            panic!("bank {} already has an account fallback", self.slot());
+        }
+    }
+
     pub fn vote_only_bank(&self) -> bool {
         self.vote_only_bank
     }
@@ -1921,6 +2013,13 @@ impl Bank {
             feature_set: Arc::<FeatureSet>::default(),
             reserved_account_keys: Arc::<ReservedAccountKeys>::default(),
             drop_callback: RwLock::new(OptionalDropCallback(None)),
+            instruction_recorder: RwLock::default(),
+            execution_profiler: RwLock::default(),
+            commit_notifiers: RwLock::default(),
+            account_fallback: OnceLock::new(),
+            scheduled_feature_deactivations: runtime_config
+                .feature_deactivations
+                .then(RwLock::default),
             freeze_started: AtomicBool::new(fields.hash != Hash::default()),
             vote_only_bank: false,
             cost_tracker: RwLock::new(CostTracker::default()),
@@ -1934,6 +2033,7 @@ impl Bank {
             collector_fee_details: RwLock::new(CollectorFeeDetails::default()),
             compute_budget: runtime_config.compute_budget,
             transaction_account_lock_limit: runtime_config.transaction_account_lock_limit,
+            debugging_features: runtime_config.debugging_features,
             fee_structure: FeeStructure::default(),
             #[cfg(feature = "dev-context-only-utils")]
             hash_overrides: Arc::new(Mutex::new(HashOverrides::default())),
@@ -2597,6 +2697,13 @@ impl Bank {
             self.update_accounts_lt_hash();
             *hash = self.hash_internal_state();
             self.rc.accounts.accounts_db.mark_slot_frozen(self.slot());
+
+            // the notifier may read the bank hash, so release the lock first
+            drop(hash);
+            let commit_notifiers = self.commit_notifiers.read().unwrap().clone();
+            for commit_notifier in commit_notifiers {
+                commit_notifier.bank_frozen(self);
+            }
         }
     }
 
@@ -3288,6 +3395,8 @@ impl Bank {
                 },
                 drop_on_failure: false,
                 all_or_nothing: false,
+                instruction_recorder: None,
+                execution_profiler: None,
             },
         );
 
@@ -3445,6 +3554,7 @@ impl Bank {
         processing_config: TransactionProcessingConfig,
     ) -> LoadAndExecuteTransactionsOutput {
         let sanitized_txs = batch.sanitized_transactions();
+        self.prefetch_missing_accounts(sanitized_txs);
 
         let (check_results, check_us) = measure_us!(self.check_transactions(
             sanitized_txs,
@@ -3791,6 +3901,9 @@ impl Bank {
                 &processing_results,
             );
 
+            self.record_stored_for_account_fallback(
+                accounts_to_store.iter().map(|(pubkey, _)| *pubkey),
+            );
             let to_store = (self.slot(), accounts_to_store.as_slice());
             self.update_bank_hash_stats(&to_store);
             // See https://github.com/solana-labs/solana/pull/31455 for discussion
@@ -3848,6 +3961,9 @@ impl Bank {
 
         self.filter_program_errors_and_collect_fee_details(&processing_results);
 
+        let commit_notifiers = self.commit_notifiers.read().unwrap().clone();
+        self.notify_committed_transactions(&commit_notifiers, sanitized_txs, &processing_results);
+
         timings.saturating_add_in_place(ExecuteTimingType::StoreUs, store_accounts_us);
         timings.saturating_add_in_place(
             ExecuteTimingType::UpdateStakesCacheUs,
@@ -4008,6 +4124,25 @@ impl Bank {
             impl FnOnce(&mut ExecuteTimings, &[TransactionProcessingResult]) -> PreCommitResult<'a>,
         >,
     ) -> Result<(Vec<TransactionCommitResult>, Option<BalanceCollector>)> {
+        let instruction_recorder = self.instruction_recorder.read().unwrap().clone();
+        let instruction_recorder =
+            instruction_recorder
+                .as_deref()
+                .map(|recorder| BankInstructionRecorderAdapter {
+                    bank: self,
+                    recorder,
+                });
+        let execution_profiler = self.execution_profiler.read().unwrap().clone();
+        let recording_config = ExecutionRecordingConfig {
+            enable_log_recording: recording_config.enable_log_recording
+                || self
+                    .commit_notifiers
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .any(|commit_notifier| commit_notifier.should_record_logs()),
+            ..recording_config
+        };
         let LoadAndExecuteTransactionsOutput {
             processing_results,
             processed_counts,
@@ -4025,6 +4160,10 @@ impl Bank {
                 recording_config,
                 drop_on_failure: false,
                 all_or_nothing: false,
+                instruction_recorder: instruction_recorder
+                    .as_ref()
+                    .map(|recorder| recorder as &dyn InstructionRecorder),
+                execution_profiler: execution_profiler.as_deref(),
             },
         );
 
@@ -4177,6 +4316,7 @@ impl Bank {
             })
         });
         self.update_bank_hash_stats(&accounts);
+        self.record_stored_for_account_fallback((0..accounts.len()).map(|i| accounts.pubkey(i)));
         self.rc.accounts.store_accounts_par(accounts, None);
         m.stop();
         self.rc
@@ -4350,13 +4490,13 @@ impl Bank {
                     &feature_set.runtime_features(),
                     &compute_budget,
                     false, /* deployment */
-                    false, /* debugging_features */
+                    self.debugging_features,
                 )
                 .unwrap(),
             ),
             program_runtime_v2: Arc::new(create_program_runtime_environment_v2(
                 &compute_budget,
-                false, /* debugging_features */
+                self.debugging_features,
             )),
         }
     }
@@ -4455,6 +4595,7 @@ impl Bank {
 
     pub fn get_account_modified_slot(&self, pubkey: &Pubkey) -> Option<(AccountSharedData, Slot)> {
         self.load_slow(&self.ancestors, pubkey)
+            .or_else(|| self.load_missing_account(pubkey))
     }
 
     fn load_slow(
@@ -4691,7 +4832,7 @@ impl Bank {
 
     /// Hash the `accounts` HashMap. This represents a validator's interpretation
     ///  of the delta of the ledger since the last vote and up to now
-    fn hash_internal_state(&self) -> Hash {
+    pub(crate) fn hash_internal_state(&self) -> Hash {
         let measure_total = Measure::start("");
         let slot = self.slot();
 
@@ -5471,6 +5612,9 @@ impl Bank {
         let (feature_set, new_feature_activations) =
             self.compute_active_feature_set(include_pending);
         self.feature_set = Arc::new(feature_set);
+        if let Some(deactivations) = &mut self.scheduled_feature_deactivations {
+            deactivations.get_mut().unwrap().clear();
+        }
 
         // Update activation slot of features in `new_feature_activations`
         for feature_id in new_feature_activations.iter() {
@@ -5680,6 +5824,18 @@ impl Bank {
         let mut pending = AHashSet::new();
         let slot = self.slot();
 
+        if let Some(deactivations) = self
+            .scheduled_feature_deactivations
+            .as_ref()
+            .filter(|_| include_pending)
+        {
+            for feature_id in deactivations.read().unwrap().iter() {
+                if active.remove(feature_id).is_some() {
+                    inactive.insert(*feature_id);
+                }
+            }
+        }
+
         for feature_id in self.feature_set.inactive() {
             let mut activated = None;
             if let Some(account) = self.get_account_with_fixed_root(feature_id) {
@@ -5992,6 +6148,7 @@ impl TransactionProcessingCallback for Bank {
             .accounts
             .accounts_db
             .load_with_fixed_root(&self.ancestors, pubkey)
+            .or_else(|| self.load_missing_account(pubkey))
     }
 
     fn inspect_account(&self, address: &Pubkey, account_state: AccountState, is_writable: bool) {
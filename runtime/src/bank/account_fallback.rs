@@ -0,0 +1,241 @@
+//! Loading of the accounts missing from a bank from elsewhere, e.g. to fork
+//! another cluster in a test validator.
+
+use {
+    super::{accounts_lt_hash::CacheValue as AccountsLtHashCacheValue, Bank},
+    dashmap::DashSet,
+    solana_account::AccountSharedData,
+    solana_clock::Slot,
+    solana_hash::Hash,
+    solana_pubkey::Pubkey,
+    solana_svm_transaction::svm_message::SVMMessage,
+    std::sync::Arc,
+};
+
+/// Supplies the accounts missing from a bank when they are loaded by
+/// transactions, including program and program data accounts, or through
+/// [`Bank::get_account`].
+///
+/// Unless the bank is frozen, the accounts it supplies are stored in the
+/// bank, where the bank and its descendants find them from then on. A
+/// fallback set on a bank is inherited by all of its descendants.
+pub trait BankAccountFallback: Send + Sync {
+    /// Returns the account at `pubkey`, or `None` if it doesn't exist.
+    fn load_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData>;
+
+    /// Called before a batch of transactions executes, with the accounts
+    /// they reference that the bank is missing, so that they can be loaded
+    /// at once instead of one at a time while executing.
+    fn prefetch_accounts(&self, _pubkeys: &[Pubkey]) {}
+}
+
+/// The fallback of a bank and its descendants, and the accounts they have
+/// stored since it was set.
+///
+/// Those accounts are never loaded from the fallback again, so that closed
+/// accounts stay closed. The stored accounts are shared by all descendants,
+/// including those of other forks.
+pub(super) struct AccountFallback {
+    fallback: Arc<dyn BankAccountFallback>,
+    stored_pubkeys: DashSet<Pubkey>,
+}
+
+impl AccountFallback {
+    pub(super) fn new(fallback: Arc<dyn BankAccountFallback>) -> Self {
+        Self {
+            fallback,
+            stored_pubkeys: DashSet::default(),
+        }
+    }
+}
+
+impl Bank {
+    pub(super) fn load_missing_account(
+        &self,
+        pubkey: &Pubkey,
+    ) -> Option<(AccountSharedData, Slot)> {
+        let fallback = self.account_fallback.get()?;
+        if fallback.stored_pubkeys.contains(pubkey) {
+            return None;
+        }
+        let account = fallback.fallback.load_account(pubkey)?;
+        // Don't wait for a bank that is freezing, it can't be stored to anymore
+        if let Ok(hash) = self.hash.try_read() {
+            if *hash == Hash::default() {
+                // Transactions loading the account later in the slot must not
+                // take it for its initial state for the accounts lt hash
+                self.cache_for_accounts_lt_hash
+                    .entry(*pubkey)
+                    .or_insert(AccountsLtHashCacheValue::BankNew);
+                self.store_account_and_update_capitalization(pubkey, &account);
+            }
+        }
+        Some((account, self.slot()))
+    }
+
+    /// Records that the accounts at `pubkeys` were stored, so they are never
+    /// loaded from the fallback again.
+    pub(super) fn record_stored_for_account_fallback<'a>(
+        &self,
+        pubkeys: impl IntoIterator<Item = &'a Pubkey>,
+    ) {
+        if let Some(fallback) = self.account_fallback.get() {
+            for pubkey in pubkeys {
+                fallback.stored_pubkeys.insert(*pubkey);
+            }
+        }
+    }
+
+    /// Prefetches the accounts referenced by `transactions` that neither this
+    /// bank nor the accounts it stored have.
+    pub(super) fn prefetch_missing_accounts(&self, transactions: &[impl SVMMessage]) {
+        let Some(fallback) = self.account_fallback.get() else {
+            return;
+        };
+        let accounts_index = &self.rc.accounts.accounts_db.accounts_index;
+        let mut pubkeys: Vec<_> = transactions
+            .iter()
+            .flat_map(|transaction| transaction.account_keys().iter())
+            .filter(|pubkey| {
+                !fallback.stored_pubkeys.contains(*pubkey) && !accounts_index.contains(pubkey)
+            })
+            .copied()
+            .collect();
+        if pubkeys.is_empty() {
+            return;
+        }
+        pubkeys.sort_unstable();
+        pubkeys.dedup();
+        fallback.fallback.prefetch_accounts(&pubkeys);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::genesis_utils::create_genesis_config,
+        solana_account::ReadableAccount,
+        solana_keypair::Keypair,
+        solana_sdk_ids::system_program,
+        solana_signer::Signer,
+        std::{
+            collections::HashMap,
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc,
+            },
+        },
+    };
+
+    #[derive(Default)]
+    struct TestFallback {
+        accounts: HashMap<Pubkey, AccountSharedData>,
+        loads: AtomicUsize,
+    }
+
+    impl BankAccountFallback for TestFallback {
+        fn load_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+            self.loads.fetch_add(1, Ordering::Relaxed);
+            self.accounts.get(pubkey).cloned()
+        }
+    }
+
+    #[test]
+    fn test_account_fallback() {
+        let (genesis_config, mint_keypair) = create_genesis_config(1_000_000_000);
+        let (bank, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        let bank = Bank::new_from_parent_with_bank_forks(&bank_forks, bank, &Pubkey::default(), 1);
+        let forked = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let fallback = Arc::new(TestFallback {
+            accounts: HashMap::from([
+                (
+                    forked,
+                    AccountSharedData::new(1_000_000, 0, &system_program::id()),
+                ),
+                (other, AccountSharedData::new(42, 0, &system_program::id())),
+            ]),
+            ..TestFallback::default()
+        });
+        bank.set_account_fallback(fallback.clone());
+
+        // Transactions load missing accounts from the fallback, once
+        bank.transfer(1_000_000, &mint_keypair, &forked).unwrap();
+        assert_eq!(bank.get_balance(&forked), 2_000_000);
+        assert_eq!(
+            bank.capitalization(),
+            bank.calculate_capitalization_for_tests()
+        );
+        assert_eq!(fallback.loads.load(Ordering::Relaxed), 1);
+        assert!(bank.get_account(&Pubkey::new_unique()).is_none());
+        assert_eq!(fallback.loads.load(Ordering::Relaxed), 2);
+
+        bank.freeze();
+        let expected_accounts_lt_hash = bank.accounts_lt_hash.lock().unwrap().clone();
+        bank.squash();
+        bank.force_flush_accounts_cache();
+        let calculated_accounts_lt_hash = bank
+            .rc
+            .accounts
+            .accounts_db
+            .calculate_accounts_lt_hash_at_startup_from_index(&bank.ancestors, bank.slot());
+        assert_eq!(expected_accounts_lt_hash, calculated_accounts_lt_hash);
+
+        // Frozen banks return missing accounts without storing them
+        let capitalization = bank.capitalization();
+        assert_eq!(bank.get_account(&other).unwrap().lamports(), 42);
+        assert_eq!(bank.capitalization(), capitalization);
+        assert_eq!(fallback.loads.load(Ordering::Relaxed), 3);
+
+        // Descendants inherit the fallback
+        let bank = Bank::new_from_parent_with_bank_forks(&bank_forks, bank, &Pubkey::default(), 2);
+        assert_eq!(bank.get_balance(&other), 42);
+        assert_eq!(bank.get_balance(&forked), 2_000_000);
+        assert_eq!(fallback.loads.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn test_account_fallback_closed_account() {
+        let (genesis_config, mint_keypair) = create_genesis_config(1_000_000_000);
+        let (bank, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        let bank = Bank::new_from_parent_with_bank_forks(&bank_forks, bank, &Pubkey::default(), 1);
+        let forked = Keypair::new();
+        let fallback = Arc::new(TestFallback {
+            accounts: HashMap::from([(
+                forked.pubkey(),
+                AccountSharedData::new(1_000_000, 0, &system_program::id()),
+            )]),
+            ..TestFallback::default()
+        });
+        bank.set_account_fallback(fallback.clone());
+
+        // Closing an account from the fallback must not bring it back
+        let fee = bank.get_lamports_per_signature();
+        bank.transfer(
+            1_000_000u64.saturating_sub(fee),
+            &forked,
+            &mint_keypair.pubkey(),
+        )
+        .unwrap();
+        assert_eq!(fallback.loads.load(Ordering::Relaxed), 1);
+        assert!(bank.get_account(&forked.pubkey()).is_none());
+        let bank = Bank::new_from_parent_with_bank_forks(&bank_forks, bank, &Pubkey::default(), 2);
+        assert!(bank.get_account(&forked.pubkey()).is_none());
+        assert_eq!(fallback.loads.load(Ordering::Relaxed), 1);
+
+        // Nor can an account created in the bank be replaced by the fallback
+        // once it is closed
+        let created = Keypair::new();
+        bank.transfer(1_000_000, &mint_keypair, &created.pubkey())
+            .unwrap();
+        bank.transfer(
+            1_000_000u64.saturating_sub(fee),
+            &created,
+            &mint_keypair.pubkey(),
+        )
+        .unwrap();
+        assert!(bank.get_account(&created.pubkey()).is_none());
+        assert_eq!(fallback.loads.load(Ordering::Relaxed), 2);
+    }
+}
@@ -126,7 +126,7 @@ impl Bank {
     /// Invoking the loader's `direct_deploy_program` function will update the
     /// program cache in the currently executing context, but the runtime must
     /// also propagate those updates to the currently active cache.
-    fn directly_invoke_loader_v3_deploy(
+    pub(crate) fn directly_invoke_loader_v3_deploy(
         &self,
         program_id: &Pubkey,
         programdata: &[u8],
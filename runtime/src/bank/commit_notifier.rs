@@ -0,0 +1,103 @@
+//! Notification of the transactions a bank commits and of the bank freezing.
+
+use {
+    super::Bank,
+    crate::account_saver::collect_accounts_to_store,
+    solana_account::AccountSharedData,
+    solana_pubkey::Pubkey,
+    solana_runtime_transaction::transaction_with_meta::TransactionWithMeta,
+    solana_signature::Signature,
+    solana_svm::transaction_processing_result::{
+        ProcessedTransaction, TransactionProcessingResult, TransactionProcessingResultExtensions,
+    },
+    solana_transaction::sanitized::SanitizedTransaction,
+    solana_transaction_error::TransactionResult,
+    std::sync::Arc,
+};
+
+/// Receives the transactions a bank commits and is told once the bank is
+/// frozen, e.g. to push account, log and slot updates to subscribers.
+///
+/// A notifier added to a bank is inherited by all of its descendants.
+pub trait BankCommitNotifier: Send + Sync {
+    /// Returns whether anything is subscribed to the transactions committed
+    /// from now on. The bank skips collecting them for `transaction_committed`
+    /// otherwise.
+    fn has_transaction_subscribers(&self) -> bool;
+
+    /// Returns whether log messages should be recorded for the transactions
+    /// executed from now on, so that they are passed to
+    /// `transaction_committed`.
+    fn should_record_logs(&self) -> bool;
+
+    /// Called once `transaction` has been committed by `bank`.
+    fn transaction_committed(&self, bank: &Bank, transaction: &CommittedTransactionInfo);
+
+    /// Called once `bank` has been frozen.
+    fn bank_frozen(&self, bank: &Bank);
+}
+
+/// A transaction committed by a bank, whether it succeeded or not.
+pub struct CommittedTransactionInfo<'a> {
+    pub signature: &'a Signature,
+    pub is_vote: bool,
+    pub status: TransactionResult<()>,
+    /// All the accounts the transaction references, including those loaded
+    /// from address lookup tables.
+    pub account_keys: Vec<Pubkey>,
+    /// The accounts stored by the commit, in their new state. Failed
+    /// transactions only store their fee payer and nonce account.
+    pub written_accounts: Vec<(&'a Pubkey, &'a AccountSharedData)>,
+    /// The log messages of the transaction, if they were recorded.
+    pub log_messages: Option<&'a [String]>,
+}
+
+impl Bank {
+    pub(super) fn notify_committed_transactions(
+        &self,
+        commit_notifiers: &[Arc<dyn BankCommitNotifier>],
+        transactions: &[impl TransactionWithMeta],
+        processing_results: &[TransactionProcessingResult],
+    ) {
+        let commit_notifiers: Vec<_> = commit_notifiers
+            .iter()
+            .filter(|commit_notifier| commit_notifier.has_transaction_subscribers())
+            .collect();
+        if commit_notifiers.is_empty() {
+            return;
+        }
+
+        let no_transaction_refs: Option<Vec<&SanitizedTransaction>> = None;
+        for (transaction, processing_result) in transactions.iter().zip(processing_results) {
+            let Some(processed_tx) = processing_result.processed_transaction() else {
+                // Unprocessed transactions are not committed
+                continue;
+            };
+            let (status, log_messages) = match processed_tx {
+                ProcessedTransaction::Executed(executed_tx) => (
+                    executed_tx.execution_details.status.clone(),
+                    executed_tx.execution_details.log_messages.as_deref(),
+                ),
+                ProcessedTransaction::FeesOnly(fees_only_tx) => {
+                    (Err(fees_only_tx.load_error.clone()), None)
+                }
+            };
+            let (written_accounts, _) = collect_accounts_to_store(
+                std::slice::from_ref(transaction),
+                &no_transaction_refs,
+                std::slice::from_ref(processing_result),
+            );
+            let committed_transaction = CommittedTransactionInfo {
+                signature: transaction.signature(),
+                is_vote: transaction.is_simple_vote_transaction(),
+                status,
+                account_keys: transaction.account_keys().iter().copied().collect(),
+                written_accounts,
+                log_messages,
+            };
+            for commit_notifier in &commit_notifiers {
+                commit_notifier.transaction_committed(self, &committed_transaction);
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Recording of the top-level instructions a bank executes.
+
+use {
+    super::Bank,
+    solana_account::Account,
+    solana_pubkey::Pubkey,
+    solana_svm::instruction_recorder::{InstructionRecorder, RecordedInstruction},
+};
+
+/// Receives the top-level instructions a bank executes, e.g. to capture test
+/// fixtures while replaying a ledger.
+///
+/// A recorder set on a bank is inherited by all of its descendants.
+pub trait BankInstructionRecorder: Send + Sync {
+    /// Returns whether instructions invoking `program_id` should be recorded.
+    fn should_record(&self, program_id: &Pubkey) -> bool;
+
+    /// Returns the state in `bank` of the accounts that a selected instruction
+    /// may read besides `accounts`, its own. Called before the instruction is
+    /// executed.
+    fn capture_extra_accounts(
+        &self,
+        _bank: &Bank,
+        _accounts: &[(Pubkey, Account)],
+    ) -> Vec<(Pubkey, Account)> {
+        Vec::new()
+    }
+
+    /// Called once a selected instruction has been executed by `bank`.
+    fn record(&self, bank: &Bank, instruction: RecordedInstruction);
+}
+
+/// Hands the bank executing the batch to a [`BankInstructionRecorder`].
+pub(super) struct BankInstructionRecorderAdapter<'a> {
+    pub(super) bank: &'a Bank,
+    pub(super) recorder: &'a dyn BankInstructionRecorder,
+}
+
+impl InstructionRecorder for BankInstructionRecorderAdapter<'_> {
+    fn should_record(&self, program_id: &Pubkey) -> bool {
+        self.recorder.should_record(program_id)
+    }
+
+    fn capture_extra_accounts(&self, accounts: &[(Pubkey, Account)]) -> Vec<(Pubkey, Account)> {
+        self.recorder.capture_extra_accounts(self.bank, accounts)
+    }
+
+    fn record(&self, instruction: RecordedInstruction) {
+        self.recorder.record(self.bank, instruction)
+    }
+}
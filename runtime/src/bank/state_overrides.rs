@@ -0,0 +1,613 @@
+//! Modification of the state of a bank outside of transactions, for test
+//! validators.
+//!
+//! A bank modified this way no longer agrees with the rest of a cluster, so
+//! these are only meant for single node test clusters, to set up end-to-end
+//! tests. All modifications are made to a bank that is not frozen yet, which
+//! is held from freezing until the modification is complete.
+
+use {
+    super::{accounts_lt_hash::CacheValue as AccountsLtHashCacheValue, Bank},
+    solana_account::{
+        accounts_equal, create_account_shared_data_with_fields as create_account,
+        AccountSharedData, ReadableAccount, WritableAccount,
+    },
+    solana_accounts_db::accounts_index::ScanError,
+    solana_clock::{Clock, Slot, UnixTimestamp},
+    solana_feature_gate_interface::{self as feature, Feature},
+    solana_hash::Hash,
+    solana_instruction::error::InstructionError,
+    solana_loader_v3_interface::state::UpgradeableLoaderState,
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::{bpf_loader_upgradeable, native_loader, sysvar, vote},
+    std::collections::{HashMap, HashSet},
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum StateOverrideError {
+    #[error("bank {0} is frozen")]
+    BankFrozen(Slot),
+    #[error("the clock can't move backwards, from {current} to {requested}")]
+    ClockMovedBackwards {
+        current: UnixTimestamp,
+        requested: UnixTimestamp,
+    },
+    #[error("{0} is not an upgradeable program")]
+    NotUpgradeableProgram(Pubkey),
+    #[error("invalid program: {0}")]
+    InvalidProgram(#[from] InstructionError),
+    #[error("{0} is not a known feature")]
+    UnknownFeature(Pubkey),
+    #[error("feature {0} is already active")]
+    FeatureActive(Pubkey),
+    #[error("feature {0} is not active")]
+    FeatureInactive(Pubkey),
+    #[error("feature deactivations are not enabled")]
+    FeatureDeactivationsDisabled,
+    #[error("failed to scan accounts: {0}")]
+    Scan(#[from] ScanError),
+}
+
+pub type Result<T> = std::result::Result<T, StateOverrideError>;
+
+/// The accounts of a bank, taken by [`Bank::snapshot_state`].
+#[derive(Debug)]
+pub struct BankStateSnapshot {
+    slot: Slot,
+    accounts: HashMap<Pubkey, AccountSharedData>,
+}
+
+impl BankStateSnapshot {
+    /// The slot of the bank the snapshot was taken from.
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+}
+
+impl Bank {
+    /// Runs `f` while preventing the bank from freezing, unless it is already
+    /// frozen.
+    fn with_freeze_lock<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let hash = self.freeze_lock();
+        if *hash != Hash::default() {
+            return Err(StateOverrideError::BankFrozen(self.slot()));
+        }
+        f()
+    }
+
+    /// Stores `account` at `pubkey`, replacing any existing account.
+    ///
+    /// Programs are reloaded from their accounts on their next invocation,
+    /// but program data accounts of the upgradeable loader must be replaced
+    /// with [`Bank::override_program`] to take effect in the same slot.
+    pub fn override_account(&self, pubkey: &Pubkey, account: &AccountSharedData) -> Result<()> {
+        self.with_freeze_lock(|| {
+            self.store_overridden_account(pubkey, account);
+            self.refresh_overridden_caches(&[*pubkey]);
+            Ok(())
+        })
+    }
+
+    /// Moves the clock forward to `unix_timestamp`.
+    ///
+    /// Descendant banks keep the timestamp until the timestamps voted by the
+    /// validators move past it, as the clock never moves backwards.
+    pub fn override_unix_timestamp(&self, unix_timestamp: UnixTimestamp) -> Result<()> {
+        self.with_freeze_lock(|| {
+            let clock = self.clock();
+            if unix_timestamp < clock.unix_timestamp {
+                return Err(StateOverrideError::ClockMovedBackwards {
+                    current: clock.unix_timestamp,
+                    requested: unix_timestamp,
+                });
+            }
+            self.update_sysvar_account(&sysvar::clock::id(), |account| {
+                create_account(
+                    &Clock {
+                        unix_timestamp,
+                        ..clock.clone()
+                    },
+                    self.inherit_specially_retained_account_fields(account),
+                )
+            });
+            self.transaction_processor.refresh_sysvar_cache(self);
+            Ok(())
+        })
+    }
+
+    /// Replaces the ELF of the upgradeable program `program_id` in place,
+    /// keeping its upgrade authority. The new program is verified and can be
+    /// invoked right away.
+    pub fn override_program(&self, program_id: &Pubkey, elf: &[u8]) -> Result<()> {
+        self.with_freeze_lock(|| {
+            let not_upgradeable = || StateOverrideError::NotUpgradeableProgram(*program_id);
+            let program_account = self
+                .get_account(program_id)
+                .filter(|account| account.owner() == &bpf_loader_upgradeable::id())
+                .ok_or_else(not_upgradeable)?;
+            let Ok(UpgradeableLoaderState::Program {
+                programdata_address,
+            }) = program_account.deserialize_data()
+            else {
+                return Err(not_upgradeable());
+            };
+            let Some(Ok(UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address,
+                ..
+            })) = self.get_account(&programdata_address).map(|account| {
+                let metadata_size = UpgradeableLoaderState::size_of_programdata_metadata();
+                bincode::deserialize(&account.data()[..metadata_size.min(account.data().len())])
+            })
+            else {
+                return Err(not_upgradeable());
+            };
+
+            let mut programdata = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+                slot: self.slot(),
+                upgrade_authority_address,
+            })
+            .unwrap();
+            programdata.extend_from_slice(elf);
+            // Deploying verifies the program and updates the program cache
+            self.directly_invoke_loader_v3_deploy(program_id, &programdata)?;
+
+            let mut programdata_account = AccountSharedData::new(
+                self.get_minimum_balance_for_rent_exemption(programdata.len()),
+                0,
+                &bpf_loader_upgradeable::id(),
+            );
+            programdata_account.set_data_from_slice(&programdata);
+            self.store_overridden_account(&programdata_address, &programdata_account);
+            Ok(())
+        })
+    }
+
+    /// Makes the inactive feature `feature_id` pending, to be activated at
+    /// the next epoch. Returns the first slot of the next epoch.
+    pub fn schedule_feature_activation(&self, feature_id: &Pubkey) -> Result<Slot> {
+        self.check_known_feature(feature_id)?;
+        if self.feature_set.is_active(feature_id) {
+            return Err(StateOverrideError::FeatureActive(*feature_id));
+        }
+        self.with_freeze_lock(|| {
+            let account = feature::create_account(
+                &Feature::default(),
+                self.get_minimum_balance_for_rent_exemption(Feature::size_of()),
+            );
+            self.store_overridden_account(feature_id, &account);
+            if let Some(deactivations) = &self.scheduled_feature_deactivations {
+                deactivations.write().unwrap().remove(feature_id);
+            }
+            Ok(self.first_slot_of_next_epoch())
+        })
+    }
+
+    /// Schedules the deactivation of the active feature `feature_id` at the
+    /// next epoch, and removes its feature account. Returns the first slot of
+    /// the next epoch.
+    ///
+    /// Effects of the feature that were applied once when it was activated,
+    /// such as program migrations, are not undone. Only available when the
+    /// bank was created with feature deactivations enabled in its runtime
+    /// config.
+    pub fn schedule_feature_deactivation(&self, feature_id: &Pubkey) -> Result<Slot> {
+        let deactivations = self
+            .scheduled_feature_deactivations
+            .as_ref()
+            .ok_or(StateOverrideError::FeatureDeactivationsDisabled)?;
+        self.check_known_feature(feature_id)?;
+        if !self.feature_set.is_active(feature_id) {
+            return Err(StateOverrideError::FeatureInactive(*feature_id));
+        }
+        self.with_freeze_lock(|| {
+            self.store_overridden_account(feature_id, &AccountSharedData::default());
+            deactivations.write().unwrap().insert(*feature_id);
+            Ok(self.first_slot_of_next_epoch())
+        })
+    }
+
+    /// Takes a snapshot of the accounts of the bank, which
+    /// [`Bank::revert_state`] restores.
+    ///
+    /// Sysvars, builtin programs, feature and vote accounts as well as the
+    /// leader of the bank are left out, as they track the progress of the
+    /// cluster rather than the state under test.
+    pub fn snapshot_state(&self) -> Result<BankStateSnapshot> {
+        let accounts = self
+            .get_all_accounts(false)?
+            .into_iter()
+            .filter(|(pubkey, account, _slot)| !self.is_cluster_account(pubkey, account))
+            .map(|(pubkey, account, _slot)| (pubkey, account))
+            .collect();
+        Ok(BankStateSnapshot {
+            slot: self.slot(),
+            accounts,
+        })
+    }
+
+    /// Restores the accounts of `snapshot`, closing the accounts created
+    /// since it was taken. Returns the number of accounts restored.
+    pub fn revert_state(&self, snapshot: &BankStateSnapshot) -> Result<usize> {
+        self.with_freeze_lock(|| {
+            let mut reverted_accounts = vec![];
+            let mut current_pubkeys = HashSet::new();
+            for (pubkey, account, _slot) in self.get_all_accounts(false)? {
+                if self.is_cluster_account(&pubkey, &account) {
+                    continue;
+                }
+                current_pubkeys.insert(pubkey);
+                match snapshot.accounts.get(&pubkey) {
+                    Some(snapshot_account) if accounts_equal(snapshot_account, &account) => {}
+                    Some(snapshot_account) => {
+                        reverted_accounts.push((pubkey, snapshot_account.clone()))
+                    }
+                    None => reverted_accounts.push((pubkey, AccountSharedData::default())),
+                }
+            }
+            reverted_accounts.extend(
+                snapshot
+                    .accounts
+                    .iter()
+                    .filter(|(pubkey, _account)| !current_pubkeys.contains(pubkey))
+                    .map(|(pubkey, account)| (*pubkey, account.clone())),
+            );
+
+            for (pubkey, account) in &reverted_accounts {
+                self.store_overridden_account(pubkey, account);
+            }
+            let reverted_pubkeys: Vec<_> = reverted_accounts
+                .into_iter()
+                .map(|(pubkey, _account)| pubkey)
+                .collect();
+            self.refresh_overridden_caches(&reverted_pubkeys);
+            Ok(reverted_pubkeys.len())
+        })
+    }
+
+    /// Stores `account` outside of transaction processing, making sure that
+    /// transactions later in the slot don't take it for the initial state of
+    /// the account when calculating the accounts lt hash.
+    fn store_overridden_account(&self, pubkey: &Pubkey, account: &AccountSharedData) {
+        self.cache_for_accounts_lt_hash
+            .entry(*pubkey)
+            .or_insert(AccountsLtHashCacheValue::BankNew);
+        self.store_account_and_update_capitalization(pubkey, account);
+    }
+
+    fn check_known_feature(&self, feature_id: &Pubkey) -> Result<()> {
+        if self.feature_set.active().contains_key(feature_id)
+            || self.feature_set.inactive().contains(feature_id)
+        {
+            Ok(())
+        } else {
+            Err(StateOverrideError::UnknownFeature(*feature_id))
+        }
+    }
+
+    fn first_slot_of_next_epoch(&self) -> Slot {
+        self.epoch_schedule
+            .get_first_slot_in_epoch(self.epoch.saturating_add(1))
+    }
+
+    fn is_cluster_account(&self, pubkey: &Pubkey, account: &AccountSharedData) -> bool {
+        let owner = account.owner();
+        owner == &sysvar::id()
+            || owner == &native_loader::id()
+            || owner == &feature::id()
+            || owner == &vote::id()
+            || pubkey == self.leader_id()
+    }
+
+    /// Reloads the sysvars and evicts the programs that may have been
+    /// modified by overriding the accounts at `pubkeys`.
+    fn refresh_overridden_caches(&self, pubkeys: &[Pubkey]) {
+        self.transaction_processor.refresh_sysvar_cache(self);
+
+        // Programs are cached under their program id, which isn't known for
+        // program data accounts, so look up the program data addresses of the
+        // cached upgradeable programs
+        let pubkeys: HashSet<_> = pubkeys.iter().copied().collect();
+        let cached_program_ids = self
+            .transaction_processor
+            .global_program_cache
+            .read()
+            .unwrap()
+            .get_program_ids_owned_by(&bpf_loader_upgradeable::id());
+        let program_ids = cached_program_ids
+            .into_iter()
+            .filter(|program_id| {
+                self.get_account(program_id)
+                    .filter(|account| account.owner() == &bpf_loader_upgradeable::id())
+                    .is_some_and(|account| {
+                        matches!(
+                            account.deserialize_data(),
+                            Ok(UpgradeableLoaderState::Program {
+                                programdata_address,
+                            }) if pubkeys.contains(&programdata_address)
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+        self.transaction_processor
+            .global_program_cache
+            .write()
+            .unwrap()
+            .remove_programs(pubkeys.into_iter().chain(program_ids));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            bank::BankTestConfig, genesis_utils::create_genesis_config,
+            runtime_config::RuntimeConfig,
+        },
+        agave_feature_set::FeatureSet,
+        solana_account::Account,
+        solana_epoch_schedule::EpochSchedule,
+        solana_sdk_ids::system_program,
+        solana_signer::Signer,
+        std::sync::Arc,
+    };
+
+    #[test]
+    fn test_override_account() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(1_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let capitalization = bank.capitalization();
+        let pubkey = Pubkey::new_unique();
+        let account = AccountSharedData::new(42, 3, &system_program::id());
+
+        bank.override_account(&pubkey, &account).unwrap();
+        assert!(accounts_equal(
+            &bank.get_account(&pubkey).unwrap(),
+            &account
+        ));
+        assert_eq!(bank.capitalization(), capitalization + 42);
+
+        bank.freeze();
+        assert!(matches!(
+            bank.override_account(&pubkey, &account),
+            Err(StateOverrideError::BankFrozen(0))
+        ));
+    }
+
+    #[test]
+    fn test_override_account_accounts_lt_hash() {
+        let (genesis_config, mint_keypair) = create_genesis_config(1_000_000_000);
+        let (bank, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        let bank = Bank::new_from_parent_with_bank_forks(&bank_forks, bank, &Pubkey::default(), 1);
+        let pubkey = Pubkey::new_unique();
+        bank.override_account(
+            &mint_keypair.pubkey(),
+            &AccountSharedData::new(2_000_000_000, 0, &system_program::id()),
+        )
+        .unwrap();
+        bank.override_account(
+            &pubkey,
+            &AccountSharedData::new(1_000_000, 0, &system_program::id()),
+        )
+        .unwrap();
+
+        // Transactions modifying the overridden accounts must not take their
+        // overridden state for their initial state
+        bank.transfer(1_000_000, &mint_keypair, &pubkey).unwrap();
+        bank.freeze();
+        let expected_accounts_lt_hash = bank.accounts_lt_hash.lock().unwrap().clone();
+        bank.squash();
+        bank.force_flush_accounts_cache();
+        let calculated_accounts_lt_hash = bank
+            .rc
+            .accounts
+            .accounts_db
+            .calculate_accounts_lt_hash_at_startup_from_index(&bank.ancestors, bank.slot());
+        assert_eq!(expected_accounts_lt_hash, calculated_accounts_lt_hash);
+    }
+
+    #[test]
+    fn test_override_unix_timestamp() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(1_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let unix_timestamp = bank.clock().unix_timestamp;
+
+        bank.override_unix_timestamp(unix_timestamp + 3600).unwrap();
+        assert_eq!(bank.clock().unix_timestamp, unix_timestamp + 3600);
+        assert_eq!(
+            bank.transaction_processor
+                .sysvar_cache()
+                .get_clock()
+                .unwrap()
+                .unix_timestamp,
+            unix_timestamp + 3600
+        );
+        assert!(matches!(
+            bank.override_unix_timestamp(unix_timestamp),
+            Err(StateOverrideError::ClockMovedBackwards { .. })
+        ));
+
+        // The clock doesn't move back in descendant banks
+        bank.freeze();
+        let bank = Bank::new_from_parent(Arc::new(bank), &Pubkey::default(), 1);
+        assert!(bank.clock().unix_timestamp >= unix_timestamp + 3600);
+    }
+
+    #[test]
+    fn test_override_program() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(1_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let program_id = Pubkey::new_unique();
+        let programdata_address =
+            Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0;
+        let authority = Pubkey::new_unique();
+        let mut programdata = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(authority),
+        })
+        .unwrap();
+        programdata.extend_from_slice(b"not an ELF");
+        bank.store_account(
+            &programdata_address,
+            &AccountSharedData::from(Account {
+                lamports: 1,
+                data: programdata,
+                owner: bpf_loader_upgradeable::id(),
+                ..Account::default()
+            }),
+        );
+        let mut program_account = AccountSharedData::new_data(
+            1,
+            &UpgradeableLoaderState::Program {
+                programdata_address,
+            },
+            &bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        program_account.set_executable(true);
+        bank.store_account(&program_id, &program_account);
+
+        assert!(matches!(
+            bank.override_program(&program_id, b"still not an ELF"),
+            Err(StateOverrideError::InvalidProgram(_))
+        ));
+        assert!(matches!(
+            bank.override_program(&programdata_address, b"still not an ELF"),
+            Err(StateOverrideError::NotUpgradeableProgram(_))
+        ));
+
+        let elf = std::fs::read("../programs/bpf_loader/test_elfs/out/noop_aligned.so").unwrap();
+        bank.override_program(&program_id, &elf).unwrap();
+        let programdata = bank.get_account(&programdata_address).unwrap();
+        assert_eq!(
+            bincode::deserialize::<UpgradeableLoaderState>(programdata.data()).unwrap(),
+            UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(authority),
+            }
+        );
+        assert_eq!(
+            &programdata.data()[UpgradeableLoaderState::size_of_programdata_metadata()..],
+            &elf[..]
+        );
+        let program_cache = bank
+            .transaction_processor
+            .global_program_cache
+            .read()
+            .unwrap();
+        assert!(program_cache
+            .get_flattened_entries(true, true)
+            .iter()
+            .any(|(id, _slot, _entry)| id == &program_id));
+    }
+
+    #[test]
+    fn test_schedule_feature_activation_and_deactivation() {
+        let (mut genesis_config, _mint_keypair) = create_genesis_config(1_000_000);
+        genesis_config.epoch_schedule = EpochSchedule::without_warmup();
+        assert!(matches!(
+            Bank::new_for_tests(&genesis_config).schedule_feature_deactivation(
+                &agave_feature_set::increase_cpi_account_info_limit::id()
+            ),
+            Err(StateOverrideError::FeatureDeactivationsDisabled)
+        ));
+        let mut bank = Bank::new_with_paths_for_tests(
+            &genesis_config,
+            Arc::new(RuntimeConfig {
+                feature_deactivations: true,
+                ..RuntimeConfig::default()
+            }),
+            BankTestConfig::default(),
+            Vec::new(),
+        );
+        let mut feature_set = FeatureSet::all_enabled();
+        let inactive_feature = agave_feature_set::raise_cpi_nesting_limit_to_8::id();
+        feature_set.deactivate(&inactive_feature);
+        bank.feature_set = Arc::new(feature_set);
+        let active_feature = agave_feature_set::increase_cpi_account_info_limit::id();
+
+        assert!(matches!(
+            bank.schedule_feature_activation(&active_feature),
+            Err(StateOverrideError::FeatureActive(_))
+        ));
+        assert!(matches!(
+            bank.schedule_feature_deactivation(&Pubkey::new_unique()),
+            Err(StateOverrideError::UnknownFeature(_))
+        ));
+        let next_epoch_slot = genesis_config.epoch_schedule.get_first_slot_in_epoch(1);
+        assert_eq!(
+            bank.schedule_feature_activation(&inactive_feature).unwrap(),
+            next_epoch_slot
+        );
+        bank.store_account(
+            &active_feature,
+            &feature::create_account(
+                &Feature {
+                    activated_at: Some(0),
+                },
+                1,
+            ),
+        );
+        assert_eq!(
+            bank.schedule_feature_deactivation(&active_feature).unwrap(),
+            next_epoch_slot
+        );
+        assert!(bank.get_account(&active_feature).is_none());
+
+        let bank = Arc::new(bank);
+        bank.freeze();
+        let bank = Bank::new_from_parent(bank, &Pubkey::default(), next_epoch_slot);
+        assert!(bank.feature_set.is_active(&inactive_feature));
+        assert!(!bank.feature_set.is_active(&active_feature));
+        assert!(bank
+            .scheduled_feature_deactivations
+            .as_ref()
+            .unwrap()
+            .read()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_and_revert_state() {
+        let (genesis_config, mint_keypair) = create_genesis_config(1_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let mint = mint_keypair.pubkey();
+        let modified = Pubkey::new_unique();
+        let created = Pubkey::new_unique();
+        let closed = Pubkey::new_unique();
+        bank.store_account(
+            &modified,
+            &AccountSharedData::new(1, 0, &system_program::id()),
+        );
+        bank.store_account(
+            &closed,
+            &AccountSharedData::new(2, 0, &system_program::id()),
+        );
+        let snapshot = bank.snapshot_state().unwrap();
+        assert_eq!(snapshot.slot(), 0);
+        let capitalization = bank.capitalization();
+
+        bank.override_account(
+            &modified,
+            &AccountSharedData::new(10, 0, &system_program::id()),
+        )
+        .unwrap();
+        bank.override_account(
+            &created,
+            &AccountSharedData::new(20, 0, &system_program::id()),
+        )
+        .unwrap();
+        bank.override_account(&closed, &AccountSharedData::default())
+            .unwrap();
+
+        assert_eq!(bank.revert_state(&snapshot).unwrap(), 3);
+        assert_eq!(bank.get_balance(&modified), 1);
+        assert_eq!(bank.get_balance(&closed), 2);
+        assert!(bank.get_account(&created).is_none());
+        assert_eq!(bank.get_balance(&mint), 1_000_000);
+        assert_eq!(bank.capitalization(), capitalization);
+        assert_eq!(bank.revert_state(&snapshot).unwrap(), 0);
+    }
+}
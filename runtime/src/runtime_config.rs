@@ -14,4 +14,10 @@ pub struct RuntimeConfig {
     pub compute_budget: Option<ComputeBudget>,
     pub log_messages_bytes_limit: Option<usize>,
     pub transaction_account_lock_limit: Option<usize>,
+    /// Execute SBF programs with register tracing and symbols enabled, as
+    /// needed to debug them or to measure their coverage. Slows down execution.
+    pub debugging_features: bool,
+    /// Allow active features to be scheduled for deactivation, as the test
+    /// methods of test validators do.
+    pub feature_deactivations: bool,
 }
@@ -23,7 +23,8 @@ use {
         account_storage_entry::AccountStorageEntry,
         accounts::Accounts,
         accounts_db::{
-            AccountsDb, AccountsDbConfig, AccountsFileId, AtomicAccountsFileId, IndexGenerationInfo,
+            AccountsDb, AccountsDbConfig, AccountsFileId, AtomicAccountsFileId,
+            IndexGenerationInfo, PersistedAccountsIndexReader,
         },
         accounts_file::{AccountsFile, StorageAccess},
         accounts_hash::AccountsLtHash,
@@ -578,6 +579,7 @@ where
         runtime_config,
         account_paths,
         storage_and_next_append_vec_id,
+        None,
         debug_keys,
         limit_load_slot_count_from_snapshot,
         verify_index,
@@ -800,6 +802,7 @@ pub(crate) fn reconstruct_bank_from_fields<E>(
     runtime_config: &RuntimeConfig,
     account_paths: &[PathBuf],
     storage_and_next_append_vec_id: StorageAndNextAccountsFileId,
+    persisted_accounts_index: Option<PersistedAccountsIndexReader>,
     debug_keys: Option<Arc<HashSet<Pubkey>>>,
     limit_load_slot_count_from_snapshot: Option<usize>,
     verify_index: bool,
@@ -825,6 +828,7 @@ where
         snapshot_accounts_db_fields,
         account_paths,
         storage_and_next_append_vec_id,
+        persisted_accounts_index,
         limit_load_slot_count_from_snapshot,
         verify_index,
         accounts_db_config,
@@ -1015,6 +1019,7 @@ fn reconstruct_accountsdb_from_fields<E>(
     snapshot_accounts_db_fields: SnapshotAccountsDbFields<E>,
     account_paths: &[PathBuf],
     storage_and_next_append_vec_id: StorageAndNextAccountsFileId,
+    persisted_accounts_index: Option<PersistedAccountsIndexReader>,
     limit_load_slot_count_from_snapshot: Option<usize>,
     verify_index: bool,
     accounts_db_config: AccountsDbConfig,
@@ -1024,12 +1029,15 @@ fn reconstruct_accountsdb_from_fields<E>(
 where
     E: SerializableStorage + std::marker::Sync,
 {
-    let mut accounts_db = AccountsDb::new_with_config(
-        account_paths.to_vec(),
-        accounts_db_config,
-        accounts_update_notifier,
-        exit,
-    );
+    let new_accounts_db = || {
+        AccountsDb::new_with_config(
+            account_paths.to_vec(),
+            accounts_db_config.clone(),
+            accounts_update_notifier.clone(),
+            exit.clone(),
+        )
+    };
+    let mut accounts_db = new_accounts_db();
 
     let snapshot_bank_hash_info = snapshot_accounts_db_fields.into_bank_hash_info();
 
@@ -1057,6 +1065,11 @@ where
     );
 
     // Process deserialized data, set necessary fields in self
+    let persisted_accounts_index =
+        persisted_accounts_index.filter(|_| limit_load_slot_count_from_snapshot.is_none());
+    // Keep the storages to fall back with, in case generating the index from the persisted
+    // index fails part way through.
+    let fallback_storage = persisted_accounts_index.as_ref().map(|_| storage.clone());
     accounts_db.storage.initialize(storage);
     accounts_db
         .next_id
@@ -1064,10 +1077,31 @@ where
 
     info!("Building accounts index...");
     let start = Instant::now();
+    let index_from_persisted = persisted_accounts_index.and_then(|persisted_accounts_index| {
+        accounts_db
+            .generate_index_from_persisted(persisted_accounts_index, verify_index)
+            .inspect_err(|err| {
+                warn!("Unable to build accounts index from persisted index: {err}");
+            })
+            .ok()
+    });
     let IndexGenerationInfo {
         accounts_data_len,
         calculated_accounts_lt_hash,
-    } = accounts_db.generate_index(limit_load_slot_count_from_snapshot, verify_index);
+    } = match index_from_persisted {
+        Some(info) => info,
+        None => {
+            if let Some(storage) = fallback_storage {
+                // The index may be partially generated, so start over with a new AccountsDb
+                accounts_db = new_accounts_db();
+                accounts_db.storage.initialize(storage);
+                accounts_db
+                    .next_id
+                    .store(next_append_vec_id, Ordering::Release);
+            }
+            accounts_db.generate_index(limit_load_slot_count_from_snapshot, verify_index)
+        }
+    };
     info!("Building accounts index... Done in {:?}", start.elapsed());
 
     Ok((
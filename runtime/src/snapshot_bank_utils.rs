@@ -32,7 +32,7 @@ use {
         },
         paths::{
             self as snapshot_paths, get_highest_full_snapshot_archive_info,
-            get_highest_incremental_snapshot_archive_info,
+            get_highest_incremental_snapshot_archive_chain,
         },
         snapshot_archive_info::{
             FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
@@ -48,6 +48,7 @@ use {
     },
     solana_clock::{Epoch, Slot},
     solana_genesis_config::GenesisConfig,
+    solana_hash::Hash,
     solana_measure::{measure::Measure, measure_time},
     solana_pubkey::Pubkey,
     solana_slot_history::{Check, SlotHistory},
@@ -55,6 +56,7 @@ use {
         collections::{HashMap, HashSet},
         ops::RangeInclusive,
         path::{Path, PathBuf},
+        slice,
         sync::{atomic::AtomicBool, Arc},
     },
 };
@@ -72,7 +74,7 @@ pub fn bank_fields_from_snapshot_archives(
             SnapshotError::NoSnapshotArchives(full_snapshot_archives_dir.as_ref().to_path_buf())
         })?;
 
-    let incremental_snapshot_archive_info = get_highest_incremental_snapshot_archive_info(
+    let incremental_snapshot_archive_infos = get_highest_incremental_snapshot_archive_chain(
         &incremental_snapshot_archives_dir,
         full_snapshot_archive_info.slot(),
     );
@@ -92,7 +94,7 @@ pub fn bank_fields_from_snapshot_archives(
     ) = verify_and_unarchive_snapshots(
         &temp_unpack_dir,
         &full_snapshot_archive_info,
-        incremental_snapshot_archive_info.as_ref(),
+        &incremental_snapshot_archive_infos,
         &account_paths,
         accounts_db_config,
     )?;
@@ -147,18 +149,61 @@ pub fn bank_from_snapshot_archives(
     accounts_db_config: AccountsDbConfig,
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     exit: Arc<AtomicBool>,
+) -> agave_snapshots::Result<Bank> {
+    bank_from_snapshot_archive_chain(
+        account_paths,
+        bank_snapshots_dir,
+        full_snapshot_archive_info,
+        incremental_snapshot_archive_info
+            .map(slice::from_ref)
+            .unwrap_or_default(),
+        genesis_config,
+        runtime_config,
+        debug_keys,
+        limit_load_slot_count_from_snapshot,
+        accounts_db_skip_shrink,
+        accounts_db_force_initial_clean,
+        verify_index,
+        accounts_db_config,
+        accounts_update_notifier,
+        exit,
+    )
+}
+
+/// Rebuild bank from a full snapshot archive and a chain of incremental snapshot archives.
+///
+/// The incremental snapshot archives are applied in order, so the first one must be based on the
+/// full snapshot, and every other one must be based on the one before it.  The chain may be empty.
+#[allow(clippy::too_many_arguments)]
+pub fn bank_from_snapshot_archive_chain(
+    account_paths: &[PathBuf],
+    bank_snapshots_dir: impl AsRef<Path>,
+    full_snapshot_archive_info: &FullSnapshotArchiveInfo,
+    incremental_snapshot_archive_infos: &[IncrementalSnapshotArchiveInfo],
+    genesis_config: &GenesisConfig,
+    runtime_config: &RuntimeConfig,
+    debug_keys: Option<Arc<HashSet<Pubkey>>>,
+    limit_load_slot_count_from_snapshot: Option<usize>,
+    accounts_db_skip_shrink: bool,
+    accounts_db_force_initial_clean: bool,
+    verify_index: bool,
+    accounts_db_config: AccountsDbConfig,
+    accounts_update_notifier: Option<AccountsUpdateNotifier>,
+    exit: Arc<AtomicBool>,
 ) -> agave_snapshots::Result<Bank> {
     info!(
-        "Loading bank from full snapshot archive: {}, and incremental snapshot archive: {:?}",
+        "Loading bank from full snapshot archive: {}, and incremental snapshot archives: {:?}",
         full_snapshot_archive_info.path().display(),
-        incremental_snapshot_archive_info
-            .as_ref()
+        incremental_snapshot_archive_infos
+            .iter()
             .map(
                 |incremental_snapshot_archive_info| incremental_snapshot_archive_info
                     .path()
                     .display()
             )
+            .collect::<Vec<_>>(),
     );
+    let incremental_snapshot_archive_info = incremental_snapshot_archive_infos.last();
 
     let (
         UnarchivedSnapshots {
@@ -169,7 +214,7 @@ pub fn bank_from_snapshot_archives(
             full_unpacked_snapshots_dir_and_version,
             incremental_unpacked_snapshots_dir_and_version,
             full_measure_untar,
-            incremental_measure_untar,
+            incremental_untar_us,
             next_append_vec_id,
             ..
         },
@@ -177,7 +222,7 @@ pub fn bank_from_snapshot_archives(
     ) = verify_and_unarchive_snapshots(
         bank_snapshots_dir,
         full_snapshot_archive_info,
-        incremental_snapshot_archive_info,
+        incremental_snapshot_archive_infos,
         account_paths,
         &accounts_db_config,
     )?;
@@ -199,6 +244,7 @@ pub fn bank_from_snapshot_archives(
         runtime_config,
         account_paths,
         storage_and_next_append_vec_id,
+        None,
         debug_keys,
         limit_load_slot_count_from_snapshot,
         verify_index,
@@ -212,7 +258,7 @@ pub fn bank_from_snapshot_archives(
     verify_epoch_stakes(&bank)?;
 
     // The status cache is rebuilt from the latest snapshot.  So, if there's an incremental
-    // snapshot, use that.  Otherwise use the full snapshot.
+    // snapshot, use the last one in the chain.  Otherwise use the full snapshot.
     let status_cache_path = incremental_unpacked_snapshots_dir_and_version
         .as_ref()
         .unwrap_or(&full_unpacked_snapshots_dir_and_version)
@@ -261,19 +307,166 @@ pub fn bank_from_snapshot_archives(
         ),
         (
             "untar_incremental_snapshot_archive_us",
-            incremental_measure_untar.as_ref().map(Measure::as_us),
+            incremental_untar_us,
             Option<i64>
         ),
+        (
+            "num_incremental_snapshot_archives",
+            incremental_snapshot_archive_infos.len(),
+            i64
+        ),
         ("rebuild_bank_us", measure_rebuild.as_us(), i64),
         ("verify_bank_us", measure_verify.as_us(), i64),
     );
     Ok(bank)
 }
 
+/// The results of verifying snapshot archives, see `verify_snapshot_archives()`.
+///
+/// Each pair of fields holds the value stored in the snapshot, followed by the
+/// value recalculated from the snapshot's contents.
+#[derive(Debug)]
+pub struct SnapshotArchivesVerification {
+    pub slot: Slot,
+    pub full_snapshot_slot: Slot,
+    /// The snapshot hash in the name of the latest snapshot archive
+    pub archive_snapshot_hash: SnapshotHash,
+    /// The snapshot hash derived from the bank's accounts lt hash
+    pub snapshot_hash: SnapshotHash,
+    /// The snapshot hash derived from the accounts lt hash recalculated from storages
+    pub calculated_snapshot_hash: SnapshotHash,
+    pub bank_hash: Hash,
+    pub calculated_bank_hash: Hash,
+    pub capitalization: u64,
+    pub calculated_capitalization: u64,
+    pub status_cache: std::result::Result<(), VerifySlotDeltasError>,
+    pub epoch_stakes: std::result::Result<(), VerifyEpochStakesError>,
+}
+
+impl SnapshotArchivesVerification {
+    /// Returns true if all checks passed
+    pub fn is_ok(&self) -> bool {
+        self.archive_snapshot_hash == self.snapshot_hash
+            && self.snapshot_hash == self.calculated_snapshot_hash
+            && self.bank_hash == self.calculated_bank_hash
+            && self.capitalization == self.calculated_capitalization
+            && self.status_cache.is_ok()
+            && self.epoch_stakes.is_ok()
+    }
+}
+
+/// Rebuilds a bank from snapshot archives and verifies it, without trusting
+/// the hash in the archive's name.
+///
+/// Unlike `bank_from_snapshot_archives()`, failed checks do not abort loading.
+/// Instead, the stored and recalculated values of every check are returned so
+/// that a snapshot can be audited before it is used.  Errors are only returned
+/// if the archives cannot be unpacked or the bank cannot be rebuilt at all.
+pub fn verify_snapshot_archives(
+    account_paths: &[PathBuf],
+    bank_snapshots_dir: impl AsRef<Path>,
+    full_snapshot_archive_info: &FullSnapshotArchiveInfo,
+    incremental_snapshot_archive_info: Option<&IncrementalSnapshotArchiveInfo>,
+    genesis_config: &GenesisConfig,
+    runtime_config: &RuntimeConfig,
+    accounts_db_config: AccountsDbConfig,
+    exit: Arc<AtomicBool>,
+) -> agave_snapshots::Result<SnapshotArchivesVerification> {
+    let (
+        UnarchivedSnapshots {
+            full_storage: mut storage,
+            incremental_storage,
+            bank_fields,
+            accounts_db_fields,
+            full_unpacked_snapshots_dir_and_version,
+            incremental_unpacked_snapshots_dir_and_version,
+            next_append_vec_id,
+            ..
+        },
+        _guard,
+    ) = verify_and_unarchive_snapshots(
+        bank_snapshots_dir,
+        full_snapshot_archive_info,
+        incremental_snapshot_archive_info
+            .map(slice::from_ref)
+            .unwrap_or_default(),
+        account_paths,
+        &accounts_db_config,
+    )?;
+
+    if let Some(incremental_storage) = incremental_storage {
+        storage.extend(incremental_storage);
+    }
+
+    let storage_and_next_append_vec_id = StorageAndNextAccountsFileId {
+        storage,
+        next_append_vec_id,
+    };
+
+    let (bank, info) = reconstruct_bank_from_fields(
+        bank_fields,
+        accounts_db_fields,
+        genesis_config,
+        runtime_config,
+        account_paths,
+        storage_and_next_append_vec_id,
+        None,
+        None,
+        None,
+        true,
+        accounts_db_config,
+        None,
+        exit,
+    )?;
+
+    let epoch_stakes = verify_epoch_stakes(&bank);
+
+    let status_cache_path = incremental_unpacked_snapshots_dir_and_version
+        .as_ref()
+        .unwrap_or(&full_unpacked_snapshots_dir_and_version)
+        .unpacked_snapshots_dir
+        .join(snapshot_paths::SNAPSHOT_STATUS_CACHE_FILENAME);
+    let slot_deltas = serde_snapshot::deserialize_status_cache(&status_cache_path)?;
+    let status_cache = verify_slot_deltas(slot_deltas.as_slice(), &bank);
+
+    let snapshot_archive_info = incremental_snapshot_archive_info.map_or_else(
+        || full_snapshot_archive_info.snapshot_archive_info(),
+        |incremental_snapshot_archive_info| {
+            incremental_snapshot_archive_info.snapshot_archive_info()
+        },
+    );
+    if bank.slot() != snapshot_archive_info.slot {
+        return Err(SnapshotError::MismatchedSlot(
+            bank.slot(),
+            snapshot_archive_info.slot,
+        ));
+    }
+
+    info!("Calculating capitalization...");
+    let calculated_capitalization = bank.calculate_capitalization_for_tests();
+    info!("Calculating bank hash...");
+    let calculated_bank_hash = bank.hash_internal_state();
+
+    Ok(SnapshotArchivesVerification {
+        slot: bank.slot(),
+        full_snapshot_slot: full_snapshot_archive_info.slot(),
+        archive_snapshot_hash: snapshot_archive_info.hash,
+        snapshot_hash: bank.get_snapshot_hash(),
+        calculated_snapshot_hash: SnapshotHash::new(info.calculated_accounts_lt_hash.0.checksum()),
+        bank_hash: bank.hash(),
+        calculated_bank_hash,
+        capitalization: bank.capitalization(),
+        calculated_capitalization,
+        status_cache,
+        epoch_stakes,
+    })
+}
+
 /// Rebuild bank from snapshot archives
 ///
 /// This function searches `full_snapshot_archives_dir` and `incremental_snapshot_archives_dir` for
-/// the highest full snapshot and highest corresponding incremental snapshot, then rebuilds the bank.
+/// the highest full snapshot and highest corresponding chain of incremental snapshots, then
+/// rebuilds the bank.  The last incremental snapshot in the chain is returned, if any.
 #[allow(clippy::too_many_arguments)]
 pub fn bank_from_latest_snapshot_archives(
     bank_snapshots_dir: impl AsRef<Path>,
@@ -300,16 +493,16 @@ pub fn bank_from_latest_snapshot_archives(
             SnapshotError::NoSnapshotArchives(full_snapshot_archives_dir.as_ref().to_path_buf())
         })?;
 
-    let incremental_snapshot_archive_info = get_highest_incremental_snapshot_archive_info(
+    let mut incremental_snapshot_archive_infos = get_highest_incremental_snapshot_archive_chain(
         &incremental_snapshot_archives_dir,
         full_snapshot_archive_info.slot(),
     );
 
-    let bank = bank_from_snapshot_archives(
+    let bank = bank_from_snapshot_archive_chain(
         account_paths,
         bank_snapshots_dir.as_ref(),
         &full_snapshot_archive_info,
-        incremental_snapshot_archive_info.as_ref(),
+        &incremental_snapshot_archive_infos,
         genesis_config,
         runtime_config,
         debug_keys,
@@ -325,7 +518,7 @@ pub fn bank_from_latest_snapshot_archives(
     Ok((
         bank,
         full_snapshot_archive_info,
-        incremental_snapshot_archive_info,
+        incremental_snapshot_archive_infos.pop(),
     ))
 }
 
@@ -373,6 +566,11 @@ pub fn bank_from_snapshot_dir(
         storage,
         next_append_vec_id,
     };
+    let persisted_accounts_index = limit_load_slot_count_from_snapshot
+        .is_none()
+        .then(|| snapshot_utils::open_persisted_accounts_index(bank_snapshot))
+        .flatten();
+
     let snapshot_bank_fields = SnapshotBankFields::new(bank_fields, None);
     let snapshot_accounts_db_fields = SnapshotAccountsDbFields::new(accounts_db_fields, None);
     let ((bank, info), measure_rebuild_bank) = measure_time!(
@@ -383,6 +581,7 @@ pub fn bank_from_snapshot_dir(
             runtime_config,
             account_paths,
             storage_and_next_append_vec_id,
+            persisted_accounts_index,
             debug_keys,
             limit_load_slot_count_from_snapshot,
             verify_index,
@@ -712,13 +911,16 @@ pub fn bank_to_full_snapshot_archive(
 /// state.  The Bank will be frozen during the process.
 /// This is only called from ledger-tool or tests. Warping is a special case as well.
 ///
+/// The incremental snapshot is based on `incremental_snapshot_base_slot`, which is either the
+/// slot of the full snapshot, or the slot of an incremental snapshot to chain onto.
+///
 /// Requires:
 ///     - `bank` is complete
-///     - `bank`'s slot is greater than `full_snapshot_slot`
+///     - `bank`'s slot is greater than `incremental_snapshot_base_slot`
 pub fn bank_to_incremental_snapshot_archive(
     bank_snapshots_dir: impl AsRef<Path>,
     bank: &Bank,
-    full_snapshot_slot: Slot,
+    incremental_snapshot_base_slot: Slot,
     snapshot_version: Option<SnapshotVersion>,
     full_snapshot_archives_dir: impl AsRef<Path>,
     incremental_snapshot_archives_dir: impl AsRef<Path>,
@@ -727,24 +929,25 @@ pub fn bank_to_incremental_snapshot_archive(
     let snapshot_version = snapshot_version.unwrap_or_default();
 
     assert!(bank.is_complete());
-    assert!(bank.slot() > full_snapshot_slot);
+    assert!(bank.slot() > incremental_snapshot_base_slot);
     // set accounts-db's latest full snapshot slot here to ensure zero lamport
-    // accounts are handled properly.
+    // accounts are handled properly.  When chaining, the zero lamport accounts
+    // up to the base slot are already in the incremental snapshot it is based on.
     bank.rc
         .accounts
         .accounts_db
-        .set_latest_full_snapshot_slot(full_snapshot_slot);
+        .set_latest_full_snapshot_slot(incremental_snapshot_base_slot);
     bank.squash(); // Bank may not be a root
     bank.rehash(); // Bank may have been manually modified by the caller
     bank.force_flush_accounts_cache();
     bank.clean_accounts();
 
-    let snapshot_archive_kind = SnapshotArchiveKind::Incremental(full_snapshot_slot);
+    let snapshot_archive_kind = SnapshotArchiveKind::Incremental(incremental_snapshot_base_slot);
 
     let snapshot_package = SnapshotPackage::new(
         SnapshotKind::Archive(snapshot_archive_kind),
         bank,
-        bank.get_snapshot_storages(Some(full_snapshot_slot)),
+        bank.get_snapshot_storages(Some(incremental_snapshot_base_slot)),
         bank.status_cache.read().unwrap().root_slot_deltas(),
     );
 
@@ -781,7 +984,7 @@ pub fn bank_to_incremental_snapshot_archive(
     )?;
 
     Ok(IncrementalSnapshotArchiveInfo::new(
-        full_snapshot_slot,
+        incremental_snapshot_base_slot,
         snapshot_archive_info,
     ))
 }
@@ -929,6 +1132,57 @@ mod tests {
         assert_eq!(original_bank, roundtrip_bank);
     }
 
+    #[test]
+    fn test_verify_snapshot_archives() {
+        let (genesis_config, mint_keypair) = create_genesis_config(1_000_000 * LAMPORTS_PER_SOL);
+        let original_bank = Bank::new_for_tests(&genesis_config);
+        original_bank
+            .transfer(LAMPORTS_PER_SOL, &mint_keypair, &Pubkey::new_unique())
+            .unwrap();
+        original_bank.fill_bank_with_ticks_for_tests();
+
+        let (_tmp_dir, accounts_dir) = create_tmp_accounts_dir_for_tests();
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_format = SnapshotConfig::default().archive_format;
+
+        let snapshot_archive_info = bank_to_full_snapshot_archive(
+            &bank_snapshots_dir,
+            &original_bank,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            snapshot_archive_format,
+        )
+        .unwrap();
+
+        let verification = verify_snapshot_archives(
+            &[accounts_dir],
+            bank_snapshots_dir.path(),
+            &snapshot_archive_info,
+            None,
+            &genesis_config,
+            &RuntimeConfig::default(),
+            ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            Arc::default(),
+        )
+        .unwrap();
+        assert!(verification.is_ok(), "{verification:?}");
+        assert_eq!(verification.slot, original_bank.slot());
+        assert_eq!(verification.full_snapshot_slot, original_bank.slot());
+        assert_eq!(verification.bank_hash, original_bank.hash());
+        assert_eq!(verification.calculated_bank_hash, original_bank.hash());
+        assert_eq!(
+            verification.snapshot_hash,
+            original_bank.get_snapshot_hash()
+        );
+        assert_eq!(
+            verification.calculated_capitalization,
+            original_bank.capitalization()
+        );
+    }
+
     /// This tests handling of obsolete accounts during a full snapshot with obsolete accounts
     /// marked in the accounts database. This test injects them directly
     #[test]
@@ -1227,6 +1481,127 @@ mod tests {
         assert_eq!(*bank4, roundtrip_bank);
     }
 
+    /// Test roundtrip of bank to a full snapshot and a chain of incremental snapshots, where the
+    /// second incremental snapshot is based on the first.  Then rebuild the bank from the chain.
+    #[test]
+    fn test_roundtrip_bank_to_and_from_incremental_snapshot_chain() {
+        let collector = Pubkey::new_unique();
+        let key1 = Keypair::new();
+        let key2 = Keypair::new();
+        let key3 = Keypair::new();
+
+        let (genesis_config, mint_keypair) = create_genesis_config(1_000_000 * LAMPORTS_PER_SOL);
+        let (bank0, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        bank0
+            .transfer(LAMPORTS_PER_SOL, &mint_keypair, &key1.pubkey())
+            .unwrap();
+        bank0.fill_bank_with_ticks_for_tests();
+
+        let (_tmp_dir, accounts_dir) = create_tmp_accounts_dir_for_tests();
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_format = SnapshotConfig::default().archive_format;
+
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank0,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            snapshot_archive_format,
+        )
+        .unwrap();
+
+        let slot = 1;
+        let bank1 =
+            Bank::new_from_parent_with_bank_forks(bank_forks.as_ref(), bank0, &collector, slot);
+        bank1
+            .transfer(2 * LAMPORTS_PER_SOL, &mint_keypair, &key2.pubkey())
+            .unwrap();
+        bank1.fill_bank_with_ticks_for_tests();
+
+        let incremental_snapshot_archive_info1 = bank_to_incremental_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank1,
+            full_snapshot_archive_info.slot(),
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            snapshot_archive_format,
+        )
+        .unwrap();
+
+        let slot = slot + 1;
+        let bank2 =
+            Bank::new_from_parent_with_bank_forks(bank_forks.as_ref(), bank1, &collector, slot);
+        bank2
+            .transfer(3 * LAMPORTS_PER_SOL, &mint_keypair, &key3.pubkey())
+            .unwrap();
+        bank2
+            .transfer(LAMPORTS_PER_SOL, &mint_keypair, &key1.pubkey())
+            .unwrap();
+        bank2.fill_bank_with_ticks_for_tests();
+
+        // chain the second incremental snapshot onto the first one
+        let incremental_snapshot_archive_info2 = bank_to_incremental_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank2,
+            incremental_snapshot_archive_info1.slot(),
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            snapshot_archive_format,
+        )
+        .unwrap();
+        assert_eq!(
+            incremental_snapshot_archive_info2.base_slot(),
+            incremental_snapshot_archive_info1.slot(),
+        );
+
+        let roundtrip_bank = bank_from_snapshot_archive_chain(
+            &[accounts_dir],
+            bank_snapshots_dir.path(),
+            &full_snapshot_archive_info,
+            &[
+                incremental_snapshot_archive_info1,
+                incremental_snapshot_archive_info2.clone(),
+            ],
+            &genesis_config,
+            &RuntimeConfig::default(),
+            None,
+            None,
+            false,
+            false,
+            false,
+            ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            None,
+            Arc::default(),
+        )
+        .unwrap();
+        assert_eq!(*bank2, roundtrip_bank);
+
+        // the chain is not complete without the first incremental snapshot
+        let (_tmp_dir, accounts_dir) = create_tmp_accounts_dir_for_tests();
+        assert!(bank_from_snapshot_archives(
+            &[accounts_dir],
+            bank_snapshots_dir.path(),
+            &full_snapshot_archive_info,
+            Some(&incremental_snapshot_archive_info2),
+            &genesis_config,
+            &RuntimeConfig::default(),
+            None,
+            None,
+            false,
+            false,
+            false,
+            ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            None,
+            Arc::default(),
+        )
+        .is_err());
+    }
+
     /// Test rebuilding bank from the latest snapshot archives
     #[test]
     fn test_bank_from_latest_snapshot_archives() {
@@ -26,7 +26,7 @@ use {
             FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfo,
             SnapshotArchiveInfoGetter,
         },
-        snapshot_config::SnapshotConfig,
+        snapshot_config::{SnapshotConfig, MAX_INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_LEN},
         snapshot_hash::SnapshotHash,
         streaming_unarchive_snapshot, ArchiveFormat, Result, SnapshotArchiveKind, SnapshotVersion,
     },
@@ -37,7 +37,10 @@ use {
     solana_accounts_db::{
         account_storage::AccountStorageMap,
         account_storage_entry::AccountStorageEntry,
-        accounts_db::{AccountsDbConfig, AtomicAccountsFileId},
+        accounts_db::{
+            write_persisted_accounts_index, AccountsDbConfig, AtomicAccountsFileId,
+            PersistedAccountsIndexReader,
+        },
         accounts_file::{AccountsFile, StorageAccess},
         utils::{move_and_async_delete_path, ACCOUNTS_RUN_DIR, ACCOUNTS_SNAPSHOT_DIR},
     },
@@ -45,7 +48,7 @@ use {
     solana_measure::{measure::Measure, measure_time, measure_us},
     std::{
         cmp::Ordering,
-        collections::{HashMap, HashSet},
+        collections::HashSet,
         fs,
         io::{self, BufReader, Error as IoError, Read, Seek, Write},
         mem,
@@ -219,7 +222,7 @@ pub struct UnarchivedSnapshots {
     pub full_unpacked_snapshots_dir_and_version: UnpackedSnapshotsDirAndVersion,
     pub incremental_unpacked_snapshots_dir_and_version: Option<UnpackedSnapshotsDirAndVersion>,
     pub full_measure_untar: Measure,
-    pub incremental_measure_untar: Option<Measure>,
+    pub incremental_untar_us: Option<u64>,
     pub next_append_vec_id: AtomicAccountsFileId,
 }
 
@@ -229,7 +232,7 @@ pub struct UnarchivedSnapshots {
 #[derive(Debug)]
 pub struct UnarchivedSnapshotsGuard {
     full_unpack_dir: TempDir,
-    incremental_unpack_dir: Option<TempDir>,
+    incremental_unpack_dirs: Vec<TempDir>,
 }
 /// Helper type for passing around the unpacked snapshots dir and the snapshot version together
 #[derive(Debug)]
@@ -440,6 +443,35 @@ pub fn remove_tmp_snapshot_archives(snapshot_archives_dir: impl AsRef<Path>) {
     }
 }
 
+/// Get the slot to base the incremental snapshot archive at `snapshot_slot` on
+///
+/// With chaining, this is the slot of the latest incremental snapshot archive for the full
+/// snapshot.  Archives are created in order, so it is older than `snapshot_slot`.  Without
+/// chaining, without an earlier incremental snapshot archive, or once the chain is
+/// `MAX_INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_LEN` archives long, this is the full snapshot slot.
+pub fn get_incremental_snapshot_archive_base_slot(
+    snapshot_config: &SnapshotConfig,
+    full_snapshot_slot: Slot,
+    snapshot_slot: Slot,
+) -> Slot {
+    if !snapshot_config.chain_incremental_snapshots {
+        return full_snapshot_slot;
+    }
+    let incremental_snapshot_archive_chain =
+        snapshot_paths::get_highest_incremental_snapshot_archive_chain(
+            &snapshot_config.incremental_snapshot_archives_dir,
+            full_snapshot_slot,
+        );
+    if incremental_snapshot_archive_chain.len() >= MAX_INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_LEN {
+        return full_snapshot_slot;
+    }
+    incremental_snapshot_archive_chain
+        .last()
+        .map(SnapshotArchiveInfoGetter::slot)
+        .filter(|slot| *slot < snapshot_slot)
+        .unwrap_or(full_snapshot_slot)
+}
+
 /// Creates an archive based on the bank snapshot and snapshot storages
 pub fn archive_snapshot_package(
     snapshot_archive_kind: SnapshotArchiveKind,
@@ -694,6 +726,65 @@ pub fn write_obsolete_accounts_to_snapshot(
     )
 }
 
+/// Writes the accounts index for `snapshot_storages` into the bank snapshot dir
+///
+/// The next boot from this bank snapshot can then generate the accounts index without
+/// scanning the storages.
+///
+/// Returns the number of bytes written.
+pub fn write_accounts_index_to_snapshot(
+    bank_snapshot_dir: impl AsRef<Path>,
+    snapshot_storages: &[Arc<AccountStorageEntry>],
+    snapshot_slot: Slot,
+) -> io::Result<u64> {
+    let accounts_index_path = bank_snapshot_dir
+        .as_ref()
+        .join(snapshot_paths::SNAPSHOT_ACCOUNTS_INDEX_FILENAME);
+    write_persisted_accounts_index(&accounts_index_path, snapshot_storages, snapshot_slot).map_err(
+        |err| {
+            IoError::other(format!(
+                "failed to write accounts index file '{}': {err}",
+                accounts_index_path.display(),
+            ))
+        },
+    )
+}
+
+/// Opens the accounts index in the bank snapshot dir, if there is one and it is usable
+///
+/// A missing or unusable accounts index is not an error; the index will be generated by
+/// scanning the storages instead.
+pub(crate) fn open_persisted_accounts_index(
+    bank_snapshot_info: &BankSnapshotInfo,
+) -> Option<PersistedAccountsIndexReader> {
+    let accounts_index_path = bank_snapshot_info
+        .snapshot_dir
+        .join(snapshot_paths::SNAPSHOT_ACCOUNTS_INDEX_FILENAME);
+    if !accounts_index_path.is_file() {
+        return None;
+    }
+    match PersistedAccountsIndexReader::open(&accounts_index_path) {
+        Ok(reader) if reader.slot() == bank_snapshot_info.slot => Some(reader),
+        Ok(reader) => {
+            warn!(
+                "Ignoring accounts index file '{}': it is for slot {}, but the bank snapshot is \
+                 for slot {}",
+                accounts_index_path.display(),
+                reader.slot(),
+                bank_snapshot_info.slot,
+            );
+            None
+        }
+        Err(err) => {
+            warn!(
+                "Ignoring accounts index file '{}': {err}",
+                accounts_index_path.display(),
+            );
+            None
+        }
+    }
+}
+
 fn serialize_obsolete_accounts(
     bank_snapshot_dir: impl AsRef<Path>,
     obsolete_accounts_map: &SerdeObsoleteAccountsMap,
@@ -1013,17 +1104,22 @@ pub fn hard_link_storages_to_snapshot(
     Ok(())
 }
 
-/// Unarchives the given full and incremental snapshot archives, as long as they are compatible.
+/// Unarchives the given full snapshot archive and chain of incremental snapshot archives, as long
+/// as they are compatible.
+///
+/// The incremental snapshot archives must be in chain order; see
+/// `get_highest_incremental_snapshot_archive_chain()`.  The storages of all the incremental
+/// snapshot archives are combined, and the bank fields are taken from the last one.
 pub fn verify_and_unarchive_snapshots(
     bank_snapshots_dir: impl AsRef<Path>,
     full_snapshot_archive_info: &FullSnapshotArchiveInfo,
-    incremental_snapshot_archive_info: Option<&IncrementalSnapshotArchiveInfo>,
+    incremental_snapshot_archive_infos: &[IncrementalSnapshotArchiveInfo],
     account_paths: &[PathBuf],
     accounts_db_config: &AccountsDbConfig,
 ) -> Result<(UnarchivedSnapshots, UnarchivedSnapshotsGuard)> {
     check_are_snapshots_compatible(
         full_snapshot_archive_info,
-        incremental_snapshot_archive_info,
+        incremental_snapshot_archive_infos,
     )?;
 
     let next_append_vec_id = Arc::new(AtomicAccountsFileId::new(0));
@@ -1046,14 +1142,13 @@ pub fn verify_and_unarchive_snapshots(
         accounts_db_config,
     )?;
 
-    let (
-        incremental_unpack_dir,
-        incremental_storage,
-        incremental_bank_fields,
-        incremental_accounts_db_fields,
-        incremental_unpacked_snapshots_dir_and_version,
-        incremental_measure_untar,
-    ) = if let Some(incremental_snapshot_archive_info) = incremental_snapshot_archive_info {
+    let mut incremental_unpack_dirs = Vec::with_capacity(incremental_snapshot_archive_infos.len());
+    let mut incremental_storage = None;
+    let mut incremental_bank_fields = None;
+    let mut incremental_accounts_db_fields = None;
+    let mut incremental_unpacked_snapshots_dir_and_version = None;
+    let mut incremental_untar_us = None;
+    for incremental_snapshot_archive_info in incremental_snapshot_archive_infos {
         let UnarchivedSnapshot {
             unpack_dir,
             storage,
@@ -1072,17 +1167,17 @@ pub fn verify_and_unarchive_snapshots(
             Some(incremental_snapshot_archive_info.base_slot()),
             accounts_db_config,
         )?;
-        (
-            Some(unpack_dir),
-            Some(storage),
-            Some(bank_fields),
-            Some(accounts_db_fields),
-            Some(unpacked_snapshots_dir_and_version),
-            Some(measure_untar),
-        )
-    } else {
-        (None, None, None, None, None, None)
-    };
+        // Each archive in the chain only contains storages for slots after its base slot, so the
+        // storages never overlap
+        incremental_storage
+            .get_or_insert_with(AccountStorageMap::default)
+            .extend(storage);
+        incremental_unpack_dirs.push(unpack_dir);
+        incremental_bank_fields = Some(bank_fields);
+        incremental_accounts_db_fields = Some(accounts_db_fields);
+        incremental_unpacked_snapshots_dir_and_version = Some(unpacked_snapshots_dir_and_version);
+        *incremental_untar_us.get_or_insert(0) += measure_untar.as_us();
+    }
 
     let bank_fields = SnapshotBankFields::new(full_bank_fields, incremental_bank_fields);
     let accounts_db_fields =
@@ -1098,12 +1193,12 @@ pub fn verify_and_unarchive_snapshots(
             full_unpacked_snapshots_dir_and_version,
             incremental_unpacked_snapshots_dir_and_version,
             full_measure_untar,
-            incremental_measure_untar,
+            incremental_untar_us,
             next_append_vec_id,
         },
         UnarchivedSnapshotsGuard {
             full_unpack_dir,
-            incremental_unpack_dir,
+            incremental_unpack_dirs,
         },
     ))
 }
@@ -1513,26 +1608,24 @@ fn snapshot_version_from_file(mut file_info: FileInfo) -> io::Result<String> {
     Ok(snapshot_version.trim().to_string())
 }
 
-/// Check if an incremental snapshot is compatible with a full snapshot.  This is done by checking
-/// if the incremental snapshot's base slot is the same as the full snapshot's slot.
+/// Check if a chain of incremental snapshots is compatible with a full snapshot.  This is done by
+/// checking if the first incremental snapshot's base slot is the same as the full snapshot's slot,
+/// and if every other incremental snapshot's base slot is the same as the previous one's slot.
 fn check_are_snapshots_compatible(
     full_snapshot_archive_info: &FullSnapshotArchiveInfo,
-    incremental_snapshot_archive_info: Option<&IncrementalSnapshotArchiveInfo>,
+    incremental_snapshot_archive_infos: &[IncrementalSnapshotArchiveInfo],
 ) -> Result<()> {
-    if incremental_snapshot_archive_info.is_none() {
-        return Ok(());
-    }
-
-    let incremental_snapshot_archive_info = incremental_snapshot_archive_info.unwrap();
-
-    (full_snapshot_archive_info.slot() == incremental_snapshot_archive_info.base_slot())
-        .then_some(())
-        .ok_or_else(|| {
-            SnapshotError::MismatchedBaseSlot(
-                full_snapshot_archive_info.slot(),
+    let mut expected_base_slot = full_snapshot_archive_info.slot();
+    for incremental_snapshot_archive_info in incremental_snapshot_archive_infos {
+        if incremental_snapshot_archive_info.base_slot() != expected_base_slot {
+            return Err(SnapshotError::MismatchedBaseSlot(
+                expected_base_slot,
                 incremental_snapshot_archive_info.base_slot(),
-            )
-        })
+            ));
+        }
+        expected_base_slot = incremental_snapshot_archive_info.slot();
+    }
+    Ok(())
 }
 
 pub fn purge_old_snapshot_archives(
@@ -1593,41 +1686,46 @@ pub fn purge_old_snapshot_archives(
         incremental_snapshot_archives_dir.as_ref().display(),
         maximum_incremental_snapshot_archives_to_retain
     );
-    let mut incremental_snapshot_archives_by_base_slot = HashMap::<Slot, Vec<_>>::new();
-    for incremental_snapshot_archive in
-        get_incremental_snapshot_archives(&incremental_snapshot_archives_dir)
-    {
-        incremental_snapshot_archives_by_base_slot
-            .entry(incremental_snapshot_archive.base_slot())
-            .or_default()
-            .push(incremental_snapshot_archive)
-    }
-
+    // Incremental snapshot archives are retained by chain: retaining an archive also retains all
+    // the archives it is based on, back to the full snapshot archive.  Incremental snapshot
+    // archives that are not chained are simply chains of length one.
+    let incremental_snapshot_archives =
+        get_incremental_snapshot_archives(&incremental_snapshot_archives_dir);
     let highest_full_snapshot_slot = retained_full_snapshot_slots.iter().max().copied();
-    for (base_slot, mut incremental_snapshot_archives) in incremental_snapshot_archives_by_base_slot
-    {
-        incremental_snapshot_archives.sort_unstable();
-        let num_to_retain = if Some(base_slot) == highest_full_snapshot_slot {
+    let mut incremental_snapshot_archives_to_retain = HashSet::new();
+    for full_snapshot_slot in &retained_full_snapshot_slots {
+        let num_to_retain = if Some(*full_snapshot_slot) == highest_full_snapshot_slot {
             maximum_incremental_snapshot_archives_to_retain.get()
         } else {
-            usize::from(retained_full_snapshot_slots.contains(&base_slot))
+            1
         };
+        let chains = snapshot_paths::get_incremental_snapshot_archive_chains(
+            incremental_snapshot_archives.clone(),
+            *full_snapshot_slot,
+        );
         trace!(
-            "There are {} incremental snapshot archives for base slot {}, removing {} of them",
-            incremental_snapshot_archives.len(),
-            base_slot,
-            incremental_snapshot_archives
-                .len()
-                .saturating_sub(num_to_retain),
+            "There are {} incremental snapshot archive chains for base slot {}, retaining {}",
+            chains.len(),
+            full_snapshot_slot,
+            chains.len().min(num_to_retain),
         );
-
-        incremental_snapshot_archives.truncate(
-            incremental_snapshot_archives
-                .len()
-                .saturating_sub(num_to_retain),
+        incremental_snapshot_archives_to_retain.extend(
+            chains
+                .into_values()
+                .rev()
+                .take(num_to_retain)
+                .flatten()
+                .map(|incremental_snapshot_archive| incremental_snapshot_archive.path().clone()),
         );
-        remove_archives(&incremental_snapshot_archives);
     }
+
+    let incremental_snapshot_archives_to_remove = incremental_snapshot_archives
+        .into_iter()
+        .filter(|incremental_snapshot_archive| {
+            !incremental_snapshot_archives_to_retain.contains(incremental_snapshot_archive.path())
+        })
+        .collect::<Vec<_>>();
+    remove_archives(&incremental_snapshot_archives_to_remove);
 }
 
 pub fn verify_unpacked_snapshots_dir_and_version(
@@ -1802,7 +1900,7 @@ mod tests {
         bincode::{deserialize_from, serialize_into},
         solana_accounts_db::accounts_file::AccountsFileProvider,
         solana_hash::Hash,
-        std::{convert::TryFrom, mem::size_of},
+        std::{convert::TryFrom, mem::size_of, slice},
         tempfile::NamedTempFile,
         test_case::test_case,
     };
@@ -1973,7 +2071,7 @@ mod tests {
         ))
         .unwrap();
 
-        assert!(check_are_snapshots_compatible(&full_snapshot_archive_info, None,).is_ok());
+        assert!(check_are_snapshots_compatible(&full_snapshot_archive_info, &[]).is_ok());
 
         let incremental_snapshot_archive_info =
             IncrementalSnapshotArchiveInfo::new_from_path(PathBuf::from(format!(
@@ -1986,11 +2084,11 @@ mod tests {
 
         assert!(check_are_snapshots_compatible(
             &full_snapshot_archive_info,
-            Some(&incremental_snapshot_archive_info)
+            slice::from_ref(&incremental_snapshot_archive_info),
         )
         .is_ok());
 
-        let incremental_snapshot_archive_info =
+        let chained_incremental_snapshot_archive_info =
             IncrementalSnapshotArchiveInfo::new_from_path(PathBuf::from(format!(
                 "/dir/incremental-snapshot-{}-{}-{}.tar.zst",
                 slot2,
@@ -2001,7 +2099,25 @@ mod tests {
 
         assert!(check_are_snapshots_compatible(
             &full_snapshot_archive_info,
-            Some(&incremental_snapshot_archive_info)
+            slice::from_ref(&chained_incremental_snapshot_archive_info),
+        )
+        .is_err());
+
+        // a chain is compatible if each incremental snapshot is based on the previous one
+        assert!(check_are_snapshots_compatible(
+            &full_snapshot_archive_info,
+            &[
+                incremental_snapshot_archive_info.clone(),
+                chained_incremental_snapshot_archive_info.clone(),
+            ],
+        )
+        .is_ok());
+        assert!(check_are_snapshots_compatible(
+            &full_snapshot_archive_info,
+            &[
+                chained_incremental_snapshot_archive_info,
+                incremental_snapshot_archive_info,
+            ],
         )
         .is_err());
     }
@@ -2523,6 +2639,138 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_purge_old_incremental_snapshot_archives_keeps_chains_intact() {
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+
+        for full_snapshot_slot in [100, 200] {
+            let snapshot_filename =
+                format!("snapshot-{full_snapshot_slot}-{}.tar.zst", Hash::default());
+            fs::File::create(full_snapshot_archives_dir.path().join(snapshot_filename)).unwrap();
+        }
+        for (base_slot, slot) in [
+            // based on the older full snapshot
+            (100, 105),
+            (100, 110),
+            (110, 120),
+            (120, 130),
+            // based on the latest full snapshot
+            (200, 205),
+            (200, 210),
+            (210, 220),
+            (220, 230),
+            (230, 240),
+            // based on a missing incremental snapshot
+            (215, 225),
+            // based on a missing full snapshot
+            (50, 60),
+        ] {
+            let snapshot_filename = format!(
+                "incremental-snapshot-{base_slot}-{slot}-{}.tar.zst",
+                Hash::default()
+            );
+            fs::File::create(
+                incremental_snapshot_archives_dir
+                    .path()
+                    .join(snapshot_filename),
+            )
+            .unwrap();
+        }
+
+        purge_old_snapshot_archives(
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            NonZeroUsize::new(2).unwrap(),
+            NonZeroUsize::new(2).unwrap(),
+        );
+
+        // The two newest chains for the latest full snapshot are retained, which share all but
+        // their last archive, and the newest chain for the older full snapshot is retained.
+        let mut remaining_incremental_snapshot_archives =
+            get_incremental_snapshot_archives(incremental_snapshot_archives_dir.path())
+                .iter()
+                .map(|incremental_snapshot_archive| {
+                    (
+                        incremental_snapshot_archive.base_slot(),
+                        incremental_snapshot_archive.slot(),
+                    )
+                })
+                .collect::<Vec<_>>();
+        remaining_incremental_snapshot_archives.sort_unstable();
+        assert_eq!(
+            remaining_incremental_snapshot_archives,
+            vec![
+                (100, 110),
+                (110, 120),
+                (120, 130),
+                (200, 210),
+                (210, 220),
+                (220, 230),
+                (230, 240),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_get_incremental_snapshot_archive_base_slot() {
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let mut snapshot_config = SnapshotConfig {
+            incremental_snapshot_archives_dir: incremental_snapshot_archives_dir
+                .path()
+                .to_path_buf(),
+            ..SnapshotConfig::default()
+        };
+        let full_snapshot_slot = 100;
+        let create_incremental_snapshot_archive = |base_slot, slot| {
+            let snapshot_filename = format!(
+                "incremental-snapshot-{base_slot}-{slot}-{}.tar.zst",
+                Hash::default()
+            );
+            fs::File::create(
+                incremental_snapshot_archives_dir
+                    .path()
+                    .join(snapshot_filename),
+            )
+            .unwrap();
+        };
+
+        // The first incremental snapshot is always based on the full snapshot
+        snapshot_config.chain_incremental_snapshots = true;
+        assert_eq!(
+            get_incremental_snapshot_archive_base_slot(&snapshot_config, full_snapshot_slot, 110),
+            full_snapshot_slot,
+        );
+
+        // Later ones are based on the end of the chain, if chaining is enabled
+        create_incremental_snapshot_archive(full_snapshot_slot, 110);
+        assert_eq!(
+            get_incremental_snapshot_archive_base_slot(&snapshot_config, full_snapshot_slot, 120),
+            110,
+        );
+        snapshot_config.chain_incremental_snapshots = false;
+        assert_eq!(
+            get_incremental_snapshot_archive_base_slot(&snapshot_config, full_snapshot_slot, 120),
+            full_snapshot_slot,
+        );
+
+        // A full chain starts a new chain
+        snapshot_config.chain_incremental_snapshots = true;
+        let mut base_slot = 110;
+        for _ in 1..MAX_INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_LEN {
+            create_incremental_snapshot_archive(base_slot, base_slot + 10);
+            base_slot += 10;
+        }
+        assert_eq!(
+            get_incremental_snapshot_archive_base_slot(
+                &snapshot_config,
+                full_snapshot_slot,
+                base_slot + 10
+            ),
+            full_snapshot_slot,
+        );
+    }
+
     #[test]
     fn test_purge_all_incremental_snapshot_archives_when_no_full_snapshot_archives() {
         let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
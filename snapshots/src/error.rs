@@ -43,8 +43,8 @@ pub enum SnapshotError {
     ParseSnapshotArchiveFileNameError(String),
 
     #[error(
-        "snapshots are incompatible: full snapshot slot ({0}) and incremental snapshot base slot \
-         ({1}) do not match"
+        "snapshots are incompatible: full (or previous incremental) snapshot slot ({0}) and \
+         incremental snapshot base slot ({1}) do not match"
     )]
     MismatchedBaseSlot(Slot, Slot),
 
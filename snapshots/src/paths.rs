@@ -12,6 +12,7 @@ use {
     solana_clock::Slot,
     solana_hash::Hash,
     std::{
+        collections::{btree_map::Entry, BTreeMap},
         fs,
         path::{Path, PathBuf},
         sync::LazyLock,
@@ -24,6 +25,7 @@ pub const SNAPSHOT_FASTBOOT_VERSION_FILENAME: &str = "fastboot_version";
 pub const SNAPSHOT_ACCOUNTS_HARDLINKS: &str = "accounts_hardlinks";
 pub const SNAPSHOT_ARCHIVE_DOWNLOAD_DIR: &str = "remote";
 pub const SNAPSHOT_OBSOLETE_ACCOUNTS_FILENAME: &str = "obsolete_accounts";
+pub const SNAPSHOT_ACCOUNTS_INDEX_FILENAME: &str = "accounts_index";
 /// When a snapshot is taken of a bank, the state is serialized under this directory.
 /// Specifically in `BANK_SNAPSHOTS_DIR/SLOT/`.
 /// This is also where the bank state is located in the snapshot archive.
@@ -259,6 +261,69 @@ pub fn get_highest_incremental_snapshot_archive_info(
     incremental_snapshot_archives.into_iter().next_back()
 }
 
+/// Get the chain of incremental snapshot archives with the highest slot, for a given full
+/// snapshot slot, in a directory
+///
+/// The first archive in the chain is based on the full snapshot, and every other archive is based
+/// on the archive before it.  Archives must be applied in this order.  If there are no incremental
+/// snapshot archives for the full snapshot slot, the chain is empty.
+pub fn get_highest_incremental_snapshot_archive_chain(
+    incremental_snapshot_archives_dir: impl AsRef<Path>,
+    full_snapshot_slot: Slot,
+) -> Vec<IncrementalSnapshotArchiveInfo> {
+    get_incremental_snapshot_archive_chains(
+        get_incremental_snapshot_archives(incremental_snapshot_archives_dir),
+        full_snapshot_slot,
+    )
+    .pop_last()
+    .map(|(_slot, chain)| chain)
+    .unwrap_or_default()
+}
+
+/// Get the chains of incremental snapshot archives for a given full snapshot slot
+///
+/// Returns a map from slot to the shortest chain of archives that starts at the full snapshot slot
+/// and ends at that slot.  Incremental snapshot archives based directly on the full snapshot are
+/// chains of length one.  Archives that cannot be reached from the full snapshot are ignored.
+pub fn get_incremental_snapshot_archive_chains(
+    mut incremental_snapshot_archives: Vec<IncrementalSnapshotArchiveInfo>,
+    full_snapshot_slot: Slot,
+) -> BTreeMap<Slot, Vec<IncrementalSnapshotArchiveInfo>> {
+    // An archive's base slot is always less than its slot, so sorting by slot ensures the chain
+    // to an archive's base slot is complete by the time the archive is visited.
+    incremental_snapshot_archives.sort_unstable_by_key(|incremental_snapshot_archive| {
+        (
+            incremental_snapshot_archive.slot(),
+            incremental_snapshot_archive.base_slot(),
+        )
+    });
+
+    let mut chains = BTreeMap::<Slot, Vec<IncrementalSnapshotArchiveInfo>>::new();
+    for incremental_snapshot_archive in incremental_snapshot_archives {
+        let base_slot = incremental_snapshot_archive.base_slot();
+        let mut chain = if base_slot == full_snapshot_slot {
+            Vec::new()
+        } else if let Some(base_chain) = chains.get(&base_slot) {
+            base_chain.clone()
+        } else {
+            continue;
+        };
+        chain.push(incremental_snapshot_archive);
+
+        match chains.entry(chain.last().unwrap().slot()) {
+            Entry::Vacant(entry) => {
+                entry.insert(chain);
+            }
+            Entry::Occupied(mut entry) => {
+                if chain.len() < entry.get().len() {
+                    entry.insert(chain);
+                }
+            }
+        }
+    }
+    chains
+}
+
 #[cfg(test)]
 mod tests {
     use {super::*, crate::ZstdConfig};
@@ -334,6 +399,68 @@ mod tests {
         .is_err());
     }
 
+    fn new_incremental_snapshot_archive_info(
+        base_slot: Slot,
+        slot: Slot,
+    ) -> IncrementalSnapshotArchiveInfo {
+        IncrementalSnapshotArchiveInfo::new_from_path(PathBuf::from(format!(
+            "incremental-snapshot-{base_slot}-{slot}-{}.tar.zst",
+            Hash::default()
+        )))
+        .unwrap()
+    }
+
+    fn chain_slots(chain: &[IncrementalSnapshotArchiveInfo]) -> Vec<(Slot, Slot)> {
+        chain
+            .iter()
+            .map(|incremental_snapshot_archive| {
+                (
+                    incremental_snapshot_archive.base_slot(),
+                    incremental_snapshot_archive.slot(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_get_incremental_snapshot_archive_chains() {
+        let full_snapshot_slot = 100;
+        let incremental_snapshot_archives = vec![
+            // not chained
+            new_incremental_snapshot_archive_info(100, 110),
+            new_incremental_snapshot_archive_info(100, 120),
+            // chained onto 120
+            new_incremental_snapshot_archive_info(120, 130),
+            new_incremental_snapshot_archive_info(130, 140),
+            // reachable both directly and through the chain; the shorter chain is used
+            new_incremental_snapshot_archive_info(140, 150),
+            new_incremental_snapshot_archive_info(100, 150),
+            new_incremental_snapshot_archive_info(150, 160),
+            // based on an older full snapshot
+            new_incremental_snapshot_archive_info(50, 170),
+            // based on a missing link
+            new_incremental_snapshot_archive_info(175, 180),
+        ];
+
+        let chains = get_incremental_snapshot_archive_chains(
+            incremental_snapshot_archives,
+            full_snapshot_slot,
+        );
+        assert_eq!(
+            chains.keys().copied().collect::<Vec<_>>(),
+            vec![110, 120, 130, 140, 150, 160],
+        );
+        assert_eq!(chain_slots(&chains[&110]), vec![(100, 110)]);
+        assert_eq!(
+            chain_slots(&chains[&140]),
+            vec![(100, 120), (120, 130), (130, 140)],
+        );
+        assert_eq!(chain_slots(&chains[&150]), vec![(100, 150)]);
+        assert_eq!(chain_slots(&chains[&160]), vec![(100, 150), (150, 160)]);
+
+        assert!(get_incremental_snapshot_archive_chains(Vec::new(), full_snapshot_slot).is_empty());
+    }
+
     #[test]
     fn test_parse_incremental_snapshot_archive_filename() {
         assert_eq!(
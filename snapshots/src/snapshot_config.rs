@@ -14,6 +14,9 @@ pub const DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN: NonZeroUsize =
     NonZeroUsize::new(2).unwrap();
 pub const DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN: NonZeroUsize =
     NonZeroUsize::new(4).unwrap();
+/// Maximum number of incremental snapshot archives in a chain, so that the whole chain can be
+/// advertised in the incremental snapshot hashes of a gossip message
+pub const MAX_INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_LEN: usize = 25;
 
 /// Snapshot configuration and runtime information
 #[derive(Clone, Debug)]
@@ -47,7 +50,19 @@ pub struct SnapshotConfig {
 
     /// Maximum number of incremental snapshot archives to retain
     /// NOTE: Incremental snapshots will only be kept for the latest full snapshot
+    /// NOTE: Chained incremental snapshots are retained by chain, and every archive in a retained
+    /// chain is kept
     pub maximum_incremental_snapshot_archives_to_retain: NonZeroUsize,
+
+    /// Base each incremental snapshot archive on the previous incremental snapshot archive, instead
+    /// of on the full snapshot archive, so that each one only contains the changes since the
+    /// previous one.  A new chain is started once a chain reaches
+    /// `MAX_INCREMENTAL_SNAPSHOT_ARCHIVE_CHAIN_LEN` archives.
+    pub chain_incremental_snapshots: bool,
+
+    /// Persist the accounts index with the bank snapshot taken at shutdown, so the next boot
+    /// from that snapshot can skip scanning the storages to generate the index
+    pub persist_accounts_index: bool,
 }
 
 impl Default for SnapshotConfig {
@@ -70,6 +85,8 @@ impl Default for SnapshotConfig {
             maximum_full_snapshot_archives_to_retain: DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
             maximum_incremental_snapshot_archives_to_retain:
                 DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            chain_incremental_snapshots: false,
+            persist_accounts_index: false,
         }
     }
 }
@@ -8,10 +8,12 @@ use {
 /// SnapshotPackagerService, which is in charge of pushing the hashes to CRDS.  This struct wraps
 /// up those values make it easier to pass from bank_forks_utils, through validator, to
 /// SnapshotPackagerService.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StartingSnapshotHashes {
     pub full: FullSnapshotHash,
-    pub incremental: Option<IncrementalSnapshotHash>,
+    /// The chain of incremental snapshots applied on top of the full snapshot, in order.  Each one
+    /// is based on the one before it, and the first on the full snapshot.
+    pub incremental: Vec<IncrementalSnapshotHash>,
 }
 
 /// Used by SnapshotPackagerService and SnapshotGossipManager, this struct adds type safety to
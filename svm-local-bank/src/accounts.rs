@@ -0,0 +1,124 @@
+//! In-memory account store.
+
+use {
+    agave_feature_set::FeatureSet,
+    agave_precompiles::{get_precompile, is_precompile},
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_clock::Slot,
+    solana_precompile_error::PrecompileError,
+    solana_pubkey::Pubkey,
+    solana_svm_callback::{InvokeContextCallback, TransactionProcessingCallback},
+    std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    },
+};
+
+/// Accounts and the slot at which each was last written.
+pub(crate) type AccountsMap = HashMap<Pubkey, (AccountSharedData, Slot)>;
+
+/// Accounts of a [`LocalBank`](crate::LocalBank), along with the slot at
+/// which each was last written.
+///
+/// The accounts as of the last checkpoint are shared with the checkpoint and
+/// only the accounts written since are held separately, so that returning to
+/// the checkpoint only costs as much as the writes it discards.
+pub(crate) struct LocalAccounts {
+    pub(crate) feature_set: FeatureSet,
+    base: Arc<AccountsMap>,
+    /// Accounts written since the last checkpoint, `None` for those purged.
+    changes: HashMap<Pubkey, Option<(AccountSharedData, Slot)>>,
+}
+
+impl LocalAccounts {
+    pub(crate) fn new(feature_set: FeatureSet) -> Self {
+        Self {
+            feature_set,
+            base: Arc::default(),
+            changes: HashMap::new(),
+        }
+    }
+
+    fn get_with_slot(&self, pubkey: &Pubkey) -> Option<&(AccountSharedData, Slot)> {
+        match self.changes.get(pubkey) {
+            Some(change) => change.as_ref(),
+            None => self.base.get(pubkey),
+        }
+    }
+
+    pub(crate) fn get(&self, pubkey: &Pubkey) -> Option<&AccountSharedData> {
+        self.get_with_slot(pubkey).map(|(account, _slot)| account)
+    }
+
+    pub(crate) fn contains(&self, pubkey: &Pubkey) -> bool {
+        self.get_with_slot(pubkey).is_some()
+    }
+
+    /// Stores `account` as modified at `slot`, purging it if it holds no
+    /// lamports.
+    pub(crate) fn store(&mut self, pubkey: Pubkey, account: AccountSharedData, slot: Slot) {
+        let change = (account.lamports() != 0).then_some((account, slot));
+        self.changes.insert(pubkey, change);
+    }
+
+    /// Folds the accounts written since the last checkpoint into the shared
+    /// accounts, and returns them for a new checkpoint.
+    pub(crate) fn checkpoint(&mut self) -> Arc<AccountsMap> {
+        if !self.changes.is_empty() {
+            let base = Arc::make_mut(&mut self.base);
+            for (pubkey, change) in self.changes.drain() {
+                match change {
+                    Some(entry) => base.insert(pubkey, entry),
+                    None => base.remove(&pubkey),
+                };
+            }
+        }
+        Arc::clone(&self.base)
+    }
+
+    /// Returns to the accounts of a checkpoint, and returns the addresses of
+    /// the accounts that differ from it.
+    pub(crate) fn restore(&mut self, base: &Arc<AccountsMap>) -> HashSet<Pubkey> {
+        let mut restored: HashSet<Pubkey> =
+            self.changes.drain().map(|(pubkey, _)| pubkey).collect();
+        if !Arc::ptr_eq(&self.base, base) {
+            restored.extend(
+                self.base
+                    .keys()
+                    .chain(base.keys())
+                    .filter(|pubkey| self.base.get(*pubkey) != base.get(*pubkey)),
+            );
+            self.base = Arc::clone(base);
+        }
+        restored
+    }
+}
+
+impl InvokeContextCallback for LocalAccounts {
+    fn is_precompile(&self, program_id: &Pubkey) -> bool {
+        is_precompile(program_id, |feature_id| {
+            self.feature_set.is_active(feature_id)
+        })
+    }
+
+    fn process_precompile(
+        &self,
+        program_id: &Pubkey,
+        data: &[u8],
+        instruction_datas: Vec<&[u8]>,
+    ) -> Result<(), PrecompileError> {
+        if let Some(precompile) = get_precompile(program_id, |feature_id| {
+            self.feature_set.is_active(feature_id)
+        }) {
+            precompile.verify(data, &instruction_datas, &self.feature_set)
+        } else {
+            Err(PrecompileError::InvalidPublicKey)
+        }
+    }
+}
+
+impl TransactionProcessingCallback for LocalAccounts {
+    fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<(AccountSharedData, Slot)> {
+        self.get_with_slot(pubkey).cloned()
+    }
+}
@@ -0,0 +1,799 @@
+//! In-memory bank.
+
+use {
+    crate::{
+        accounts::{AccountsMap, LocalAccounts},
+        transaction_checks::{self, LinearForkGraph},
+    },
+    agave_feature_set::{
+        increase_cpi_account_info_limit, raise_cpi_nesting_limit_to_8, FeatureSet,
+    },
+    agave_precompiles::get_precompiles,
+    agave_reserved_account_keys::ReservedAccountKeys,
+    agave_syscalls::{
+        create_program_runtime_environment_v1, create_program_runtime_environment_v2,
+    },
+    solana_account::{Account, AccountSharedData, ReadableAccount},
+    solana_builtins::BUILTINS,
+    solana_clock::{Clock, Slot, MAX_PROCESSING_AGE},
+    solana_compute_budget::compute_budget::ComputeBudget,
+    solana_epoch_rewards::EpochRewards,
+    solana_epoch_schedule::EpochSchedule,
+    solana_fee_structure::{FeeDetails, FeeStructure},
+    solana_hash::Hash,
+    solana_last_restart_slot::LastRestartSlot,
+    solana_program_runtime::{
+        invoke_context::BuiltinFunctionWithContext,
+        loaded_programs::{ProgramCacheEntry, ProgramRuntimeEnvironments},
+    },
+    solana_pubkey::Pubkey,
+    solana_rent::Rent,
+    solana_runtime_transaction::{
+        runtime_transaction::RuntimeTransaction, transaction_meta::StaticMeta,
+    },
+    solana_sdk_ids::{native_loader, system_program, sysvar},
+    solana_sha256_hasher::hashv,
+    solana_signature::Signature,
+    solana_slot_hashes::SlotHashes,
+    solana_stake_interface::stake_history::StakeHistory,
+    solana_svm::{
+        account_loader::TransactionCheckResult,
+        transaction_execution_result::ExecutedTransaction,
+        transaction_processing_result::ProcessedTransaction,
+        transaction_processor::{
+            ExecutionRecordingConfig, TransactionBatchProcessor, TransactionProcessingConfig,
+            TransactionProcessingEnvironment,
+        },
+    },
+    solana_svm_transaction::svm_message::SVMMessage,
+    solana_svm_type_overrides::sync::{Arc, RwLock},
+    solana_sysvar_id::SysvarId,
+    solana_transaction::{
+        sanitized::{MessageHash, SanitizedTransaction},
+        versioned::VersionedTransaction,
+    },
+    solana_transaction_context::transaction::TransactionReturnData,
+    solana_transaction_error::{TransactionError, TransactionResult},
+    std::collections::{HashMap, HashSet, VecDeque},
+};
+
+/// The outcome of a transaction that was processed, i.e. that was charged a
+/// fee whether or not it executed successfully.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionOutcome {
+    pub signature: Signature,
+    pub status: TransactionResult<()>,
+    pub fee_details: FeeDetails,
+    pub compute_units_consumed: u64,
+    pub logs: Vec<String>,
+    pub return_data: Option<TransactionReturnData>,
+    /// State of the accounts written by the transaction. Only the fee payer
+    /// and nonce account are written when execution failed.
+    pub post_accounts: Vec<(Pubkey, AccountSharedData)>,
+}
+
+/// State of a [`LocalBank`] saved by [`LocalBank::checkpoint`].
+///
+/// Checkpoints share the accounts of the bank, so they are cheap to take and
+/// to hold on to.
+#[derive(Clone)]
+pub struct LocalBankCheckpoint {
+    accounts: Arc<AccountsMap>,
+    blockhash: Hash,
+    blockhash_queue: VecDeque<Hash>,
+    processed_message_hashes: HashMap<Hash, u64>,
+    block_height: u64,
+    slot: Slot,
+    rent: Rent,
+}
+
+impl LocalBankCheckpoint {
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+}
+
+/// A bank holding its accounts in memory, which processes transactions one at
+/// a time with the same checks and effects as a validator's bank.
+///
+/// Slots are only advanced on request, so that the state observed by a test
+/// only changes through the transactions it processes. Like on a cluster,
+/// programs deployed by a transaction only become executable in the next slot.
+pub struct LocalBank {
+    accounts: LocalAccounts,
+    _fork_graph: Arc<RwLock<LinearForkGraph>>,
+    processor: TransactionBatchProcessor<LinearForkGraph>,
+    processing_environment: TransactionProcessingEnvironment,
+    reserved_account_keys: HashSet<Pubkey>,
+    /// Blockhashes of the slots transactions may still refer to, newest last.
+    blockhash_queue: VecDeque<Hash>,
+    /// Hashes of the processed messages and the block height at which they
+    /// were processed, kept until their blockhash expires.
+    processed_message_hashes: HashMap<Hash, u64>,
+    block_height: u64,
+    slot: Slot,
+    sigverify: bool,
+}
+
+impl Default for LocalBank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalBank {
+    /// Creates a bank with all features enabled.
+    pub fn new() -> Self {
+        Self::new_with_feature_set(FeatureSet::all_enabled())
+    }
+
+    /// Creates a bank with the given features enabled.
+    ///
+    /// The bank starts at slot 1, with the builtin and Core BPF programs and
+    /// the sysvars of a freshly created cluster.
+    pub fn new_with_feature_set(feature_set: FeatureSet) -> Self {
+        let slot = 1;
+        let mut accounts = LocalAccounts::new(feature_set.clone());
+        let rent = Rent::default();
+        let epoch_schedule = EpochSchedule::default();
+        let epoch = epoch_schedule.get_epoch(slot);
+        let genesis_blockhash = hashv(&[&slot.to_le_bytes()]);
+
+        let simd_0268_active = feature_set.is_active(&raise_cpi_nesting_limit_to_8::id());
+        let simd_0339_active = feature_set.is_active(&increase_cpi_account_info_limit::id());
+        let compute_budget =
+            ComputeBudget::new_with_defaults(simd_0268_active, simd_0339_active).to_budget();
+        let runtime_features = feature_set.runtime_features();
+        let environments = ProgramRuntimeEnvironments {
+            program_runtime_v1: Arc::new(
+                create_program_runtime_environment_v1(
+                    &runtime_features,
+                    &compute_budget,
+                    false, /* deployment */
+                    false, /* debugging_features */
+                )
+                .unwrap(),
+            ),
+            program_runtime_v2: Arc::new(create_program_runtime_environment_v2(
+                &compute_budget,
+                false, /* debugging_features */
+            )),
+        };
+
+        let fork_graph = Arc::new(RwLock::new(LinearForkGraph));
+        let mut processor = TransactionBatchProcessor::new_uninitialized(slot, epoch);
+        {
+            let mut program_cache = processor.global_program_cache.write().unwrap();
+            program_cache.set_fork_graph(Arc::downgrade(&fork_graph));
+            program_cache.latest_root_slot = slot;
+        }
+        processor.environments = environments.clone();
+
+        // Core BPF programs take precedence over the builtins they replace.
+        for (pubkey, account) in solana_program_binaries::core_bpf_programs(&rent, |feature_id| {
+            feature_set.is_active(feature_id)
+        }) {
+            accounts.store(pubkey, account, 0);
+        }
+        for builtin in BUILTINS {
+            let is_enabled = builtin
+                .enable_feature_id
+                .map(|feature_id| feature_set.is_active(&feature_id))
+                .unwrap_or(true);
+            if !is_enabled || accounts.contains(&builtin.program_id) {
+                continue;
+            }
+            accounts.store(
+                builtin.program_id,
+                native_program_account(builtin.name.as_bytes().to_vec()),
+                0,
+            );
+            processor.add_builtin(
+                builtin.program_id,
+                ProgramCacheEntry::new_builtin(0, builtin.name.len(), builtin.entrypoint),
+            );
+        }
+        for precompile in get_precompiles() {
+            let is_enabled = precompile
+                .feature
+                .map(|feature_id| feature_set.is_active(&feature_id))
+                .unwrap_or(true);
+            if is_enabled {
+                accounts.store(precompile.program_id, native_program_account(vec![]), 0);
+            }
+        }
+
+        let mut reserved_account_keys = ReservedAccountKeys::default();
+        reserved_account_keys.update_active_set(&feature_set);
+
+        let mut bank = Self {
+            accounts,
+            _fork_graph: fork_graph,
+            processor,
+            processing_environment: TransactionProcessingEnvironment {
+                blockhash: genesis_blockhash,
+                blockhash_lamports_per_signature: FeeStructure::default().lamports_per_signature,
+                feature_set: runtime_features,
+                program_runtime_environments_for_execution: environments.clone(),
+                program_runtime_environments_for_deployment: environments,
+                rent: rent.clone(),
+                ..TransactionProcessingEnvironment::default()
+            },
+            reserved_account_keys: reserved_account_keys.active,
+            blockhash_queue: VecDeque::from([genesis_blockhash]),
+            processed_message_hashes: HashMap::new(),
+            block_height: 0,
+            slot,
+            sigverify: true,
+        };
+
+        bank.set_sysvar(&Clock {
+            slot,
+            epoch,
+            leader_schedule_epoch: epoch_schedule.get_leader_schedule_epoch(slot),
+            ..Clock::default()
+        });
+        bank.set_sysvar(&epoch_schedule);
+        bank.set_sysvar(&rent);
+        bank.set_sysvar(&SlotHashes::new(&[(0, Hash::default())]));
+        bank.set_sysvar(&StakeHistory::default());
+        bank.set_sysvar(&EpochRewards::default());
+        bank.set_sysvar(&LastRestartSlot::default());
+        bank
+    }
+
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    pub fn feature_set(&self) -> &FeatureSet {
+        &self.accounts.feature_set
+    }
+
+    /// Returns the blockhash of the current slot.
+    pub fn latest_blockhash(&self) -> Hash {
+        self.processing_environment.blockhash
+    }
+
+    /// Enables or disables signature verification of processed transactions,
+    /// which is enabled by default.
+    pub fn set_sigverify(&mut self, sigverify: bool) {
+        self.sigverify = sigverify;
+    }
+
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts.get(pubkey).cloned()
+    }
+
+    pub fn get_balance(&self, pubkey: &Pubkey) -> u64 {
+        self.accounts
+            .get(pubkey)
+            .map(|account| account.lamports())
+            .unwrap_or_default()
+    }
+
+    /// Overwrites an account, removing it if it holds no lamports.
+    pub fn set_account(&mut self, pubkey: Pubkey, account: AccountSharedData) {
+        // A program whose account is replaced has to be reloaded.
+        self.processor
+            .global_program_cache
+            .write()
+            .unwrap()
+            .remove_programs(std::iter::once(pubkey));
+        let is_sysvar = sysvar::check_id(account.owner());
+        self.accounts.store(pubkey, account, self.slot);
+        if is_sysvar {
+            self.refresh_sysvar_cache();
+        }
+    }
+
+    /// Credits `lamports` to a system account, creating it if needed.
+    pub fn airdrop(&mut self, pubkey: &Pubkey, lamports: u64) {
+        let mut account = self
+            .get_account(pubkey)
+            .unwrap_or_else(|| AccountSharedData::new(0, 0, &system_program::id()));
+        account.set_lamports(account.lamports().saturating_add(lamports));
+        self.set_account(*pubkey, account);
+    }
+
+    /// Adds a builtin program implemented by `entrypoint`.
+    pub fn add_builtin(
+        &mut self,
+        program_id: Pubkey,
+        name: &str,
+        entrypoint: BuiltinFunctionWithContext,
+    ) {
+        self.accounts.store(
+            program_id,
+            native_program_account(name.as_bytes().to_vec()),
+            self.slot,
+        );
+        self.processor.add_builtin(
+            program_id,
+            ProgramCacheEntry::new_builtin(self.slot, name.len(), entrypoint),
+        );
+    }
+
+    /// Deploys `elf` at `program_id` as a program of the upgradeable loader,
+    /// replacing any existing program. The program is executable right away.
+    pub fn add_program(&mut self, program_id: Pubkey, elf: &[u8]) {
+        for (pubkey, account) in solana_program_binaries::bpf_loader_upgradeable_program_accounts(
+            &program_id,
+            elf,
+            &self.processing_environment.rent,
+        ) {
+            self.set_account(pubkey, account.into());
+        }
+    }
+
+    pub fn get_sysvar<T: SysvarId + serde::de::DeserializeOwned>(&self) -> Option<T> {
+        self.accounts
+            .get(&T::id())
+            .and_then(|account| bincode::deserialize(account.data()).ok())
+    }
+
+    /// Overwrites a sysvar, e.g. to move the clock forward.
+    pub fn set_sysvar<T: SysvarId + serde::Serialize>(&mut self, sysvar: &T) {
+        let data = bincode::serialize(sysvar).unwrap();
+        let account = AccountSharedData::from(Account {
+            lamports: self
+                .processing_environment
+                .rent
+                .minimum_balance(data.len())
+                .max(1),
+            data,
+            owner: sysvar::id(),
+            executable: false,
+            rent_epoch: u64::MAX,
+        });
+        self.accounts.store(T::id(), account, self.slot);
+        self.refresh_sysvar_cache();
+    }
+
+    pub fn set_clock(&mut self, clock: &Clock) {
+        self.set_sysvar(clock);
+    }
+
+    pub fn set_rent(&mut self, rent: &Rent) {
+        self.processing_environment.rent = rent.clone();
+        self.set_sysvar(rent);
+    }
+
+    pub fn set_epoch_schedule(&mut self, epoch_schedule: &EpochSchedule) {
+        self.set_sysvar(epoch_schedule);
+    }
+
+    fn refresh_sysvar_cache(&self) {
+        self.processor.reset_sysvar_cache();
+        self.processor
+            .fill_missing_sysvar_cache_entries(&self.accounts);
+    }
+
+    /// Saves the current state, so that the bank can return to it with
+    /// [`LocalBank::restore`].
+    pub fn checkpoint(&mut self) -> LocalBankCheckpoint {
+        LocalBankCheckpoint {
+            accounts: self.accounts.checkpoint(),
+            blockhash: self.processing_environment.blockhash,
+            blockhash_queue: self.blockhash_queue.clone(),
+            processed_message_hashes: self.processed_message_hashes.clone(),
+            block_height: self.block_height,
+            slot: self.slot,
+            rent: self.processing_environment.rent.clone(),
+        }
+    }
+
+    /// Returns to the state saved by `checkpoint`, discarding all the changes
+    /// made since.
+    ///
+    /// Only the accounts written since the checkpoint are reverted, which
+    /// makes this cheap enough to do before every run of a fuzzer or a
+    /// property-based test.
+    pub fn restore(&mut self, checkpoint: &LocalBankCheckpoint) {
+        let restored = self.accounts.restore(&checkpoint.accounts);
+        self.processing_environment.blockhash = checkpoint.blockhash;
+        self.processing_environment.rent = checkpoint.rent.clone();
+        self.blockhash_queue.clone_from(&checkpoint.blockhash_queue);
+        self.processed_message_hashes
+            .clone_from(&checkpoint.processed_message_hashes);
+        self.block_height = checkpoint.block_height;
+
+        if self.slot != checkpoint.slot {
+            self.slot = checkpoint.slot;
+            let epoch_schedule = self.get_sysvar::<EpochSchedule>().unwrap_or_default();
+            self.processor = self
+                .processor
+                .new_from(self.slot, epoch_schedule.get_epoch(self.slot));
+        }
+        {
+            // Programs deployed since the checkpoint have to be evicted, and
+            // the ones they replaced reloaded.
+            let mut program_cache = self.processor.global_program_cache.write().unwrap();
+            program_cache.remove_programs(restored.into_iter());
+            program_cache.latest_root_slot = self.slot;
+        }
+        self.refresh_sysvar_cache();
+    }
+
+    /// Moves to the next slot, registering a new blockhash.
+    pub fn advance_slot(&mut self) {
+        self.warp_to_slot(self.slot.saturating_add(1));
+    }
+
+    /// Moves to `slot`, registering a new blockhash and updating the clock
+    /// and slot hashes sysvars accordingly.
+    pub fn warp_to_slot(&mut self, slot: Slot) {
+        assert!(
+            slot > self.slot,
+            "cannot warp from slot {} back to slot {slot}",
+            self.slot,
+        );
+        let parent_slot = self.slot;
+        let parent_blockhash = self.latest_blockhash();
+        let blockhash = hashv(&[parent_blockhash.as_ref(), &slot.to_le_bytes()]);
+
+        self.slot = slot;
+        self.block_height = self.block_height.saturating_add(1);
+        self.blockhash_queue.push_back(blockhash);
+        if self.blockhash_queue.len() > MAX_PROCESSING_AGE.saturating_add(1) {
+            self.blockhash_queue.pop_front();
+        }
+        let min_block_height = self.block_height.saturating_sub(MAX_PROCESSING_AGE as u64);
+        self.processed_message_hashes
+            .retain(|_, block_height| *block_height >= min_block_height);
+        self.processing_environment.blockhash = blockhash;
+
+        let epoch_schedule = self.get_sysvar::<EpochSchedule>().unwrap_or_default();
+        let epoch = epoch_schedule.get_epoch(slot);
+        self.processor = self.processor.new_from(slot, epoch);
+        self.processor
+            .global_program_cache
+            .write()
+            .unwrap()
+            .prune(slot, None);
+
+        let mut slot_hashes = self.get_sysvar::<SlotHashes>().unwrap_or_default();
+        slot_hashes.add(parent_slot, parent_blockhash);
+        self.set_sysvar(&slot_hashes);
+        let clock = self.get_sysvar::<Clock>().unwrap_or_default();
+        self.set_sysvar(&Clock {
+            slot,
+            epoch_start_timestamp: if epoch > clock.epoch {
+                clock.unix_timestamp
+            } else {
+                clock.epoch_start_timestamp
+            },
+            epoch,
+            leader_schedule_epoch: epoch_schedule.get_leader_schedule_epoch(slot),
+            unix_timestamp: clock.unix_timestamp,
+        });
+    }
+
+    /// Verifies, executes and commits a transaction.
+    ///
+    /// Returns an error if the transaction could not be processed, in which
+    /// case it was not charged a fee and left the bank untouched.
+    pub fn process_transaction(
+        &mut self,
+        transaction: impl Into<VersionedTransaction>,
+    ) -> TransactionResult<TransactionOutcome> {
+        let transaction = self.sanitize_transaction(transaction.into(), self.sigverify)?;
+        let (outcome, programs_modified_by_tx) =
+            self.execute_transaction(&transaction, true /* check_status_cache */)?;
+
+        if !programs_modified_by_tx.is_empty() {
+            self.processor.global_program_cache.write().unwrap().merge(
+                &self.processor.environments,
+                self.slot,
+                &programs_modified_by_tx,
+            );
+        }
+        for (pubkey, account) in &outcome.post_accounts {
+            self.accounts.store(*pubkey, account.clone(), self.slot);
+        }
+        self.processed_message_hashes
+            .insert(*transaction.message_hash(), self.block_height);
+        Ok(outcome)
+    }
+
+    /// Executes a transaction without committing its effects nor verifying
+    /// its signatures.
+    pub fn simulate_transaction(
+        &self,
+        transaction: impl Into<VersionedTransaction>,
+    ) -> TransactionResult<TransactionOutcome> {
+        let transaction = self.sanitize_transaction(transaction.into(), false)?;
+        self.execute_transaction(&transaction, false /* check_status_cache */)
+            .map(|(outcome, _programs_modified_by_tx)| outcome)
+    }
+
+    fn sanitize_transaction(
+        &self,
+        transaction: VersionedTransaction,
+        sigverify: bool,
+    ) -> TransactionResult<RuntimeTransaction<SanitizedTransaction>> {
+        let message_hash = if sigverify {
+            transaction.verify_and_hash_message()?
+        } else {
+            transaction.message.hash()
+        };
+        transaction_checks::sanitize_transaction(
+            transaction,
+            MessageHash::Precomputed(message_hash),
+            self.slot,
+            &self.processor.sysvar_cache(),
+            |pubkey| self.accounts.get(pubkey),
+            &self.reserved_account_keys,
+            &self.accounts.feature_set,
+        )
+    }
+
+    fn check_transaction(
+        &self,
+        transaction: &RuntimeTransaction<SanitizedTransaction>,
+        check_status_cache: bool,
+    ) -> TransactionCheckResult {
+        transaction_checks::check_transaction(
+            transaction,
+            &self.accounts.feature_set,
+            &self.processing_environment,
+            |recent_blockhash| self.blockhash_queue.contains(recent_blockhash),
+            |message_hash| {
+                check_status_cache && self.processed_message_hashes.contains_key(message_hash)
+            },
+            |pubkey| self.accounts.get(pubkey),
+        )
+    }
+
+    /// Checks and executes a sanitized transaction, returning the state of
+    /// the accounts and programs it would write.
+    fn execute_transaction(
+        &self,
+        transaction: &RuntimeTransaction<SanitizedTransaction>,
+        check_status_cache: bool,
+    ) -> TransactionResult<(TransactionOutcome, HashMap<Pubkey, Arc<ProgramCacheEntry>>)> {
+        let check_result = self.check_transaction(transaction, check_status_cache);
+        let processing_config = TransactionProcessingConfig {
+            recording_config: ExecutionRecordingConfig {
+                enable_cpi_recording: false,
+                enable_log_recording: true,
+                enable_return_data_recording: true,
+                enable_transaction_balance_recording: false,
+            },
+            ..TransactionProcessingConfig::default()
+        };
+        let output = self.processor.load_and_execute_sanitized_transactions(
+            &self.accounts,
+            std::slice::from_ref(transaction),
+            vec![check_result],
+            &self.processing_environment,
+            &processing_config,
+        );
+        let processed_transaction = output.processing_results.into_iter().next().unwrap()?;
+
+        let signature = *transaction.signature();
+        let (outcome, programs_modified_by_tx) = match processed_transaction {
+            ProcessedTransaction::Executed(executed_transaction) => {
+                let ExecutedTransaction {
+                    loaded_transaction,
+                    execution_details,
+                    programs_modified_by_tx,
+                } = *executed_transaction;
+                let (post_accounts, programs_modified_by_tx) = if execution_details.was_successful()
+                {
+                    let post_accounts = loaded_transaction
+                        .accounts
+                        .into_iter()
+                        .enumerate()
+                        .filter(|(index, _)| transaction.is_writable(*index))
+                        .map(|(_, account)| account)
+                        .collect();
+                    (post_accounts, programs_modified_by_tx)
+                } else {
+                    let post_accounts = loaded_transaction
+                        .rollback_accounts
+                        .iter()
+                        .map(|(pubkey, account)| (*pubkey, account.clone()))
+                        .collect();
+                    (post_accounts, HashMap::new())
+                };
+
+                let outcome = TransactionOutcome {
+                    signature,
+                    status: execution_details.status,
+                    fee_details: loaded_transaction.fee_details,
+                    compute_units_consumed: execution_details.executed_units,
+                    logs: execution_details.log_messages.unwrap_or_default(),
+                    return_data: execution_details.return_data,
+                    post_accounts,
+                };
+                (outcome, programs_modified_by_tx)
+            }
+            ProcessedTransaction::FeesOnly(fees_only_transaction) => {
+                let outcome = TransactionOutcome {
+                    signature,
+                    status: Err(fees_only_transaction.load_error),
+                    fee_details: fees_only_transaction.fee_details,
+                    compute_units_consumed: 0,
+                    logs: vec![],
+                    return_data: None,
+                    post_accounts: fees_only_transaction
+                        .rollback_accounts
+                        .iter()
+                        .map(|(pubkey, account)| (*pubkey, account.clone()))
+                        .collect(),
+                };
+                (outcome, HashMap::new())
+            }
+        };
+        Ok((outcome, programs_modified_by_tx))
+    }
+}
+
+fn native_program_account(data: Vec<u8>) -> AccountSharedData {
+    AccountSharedData::from(Account {
+        lamports: Rent::default().minimum_balance(data.len()).max(1),
+        data,
+        owner: native_loader::id(),
+        executable: true,
+        rent_epoch: u64::MAX,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_keypair::Keypair, solana_signer::Signer,
+        solana_system_transaction as system_transaction,
+    };
+
+    const LAMPORTS_PER_SIGNATURE: u64 = 5000;
+
+    #[test]
+    fn test_process_transaction_transfer() {
+        let mut bank = LocalBank::new();
+        let from_keypair = Keypair::new();
+        let to_pubkey = Pubkey::new_unique();
+        let rent_exempt_minimum = Rent::default().minimum_balance(0);
+        bank.airdrop(&from_keypair.pubkey(), 10_000_000);
+
+        let transfer = system_transaction::transfer(
+            &from_keypair,
+            &to_pubkey,
+            rent_exempt_minimum,
+            bank.latest_blockhash(),
+        );
+        let outcome = bank.process_transaction(transfer.clone()).unwrap();
+        assert_eq!(outcome.status, Ok(()));
+        assert_eq!(outcome.signature, transfer.signatures[0]);
+        assert_eq!(outcome.fee_details.total_fee(), LAMPORTS_PER_SIGNATURE);
+        assert_eq!(
+            bank.get_balance(&from_keypair.pubkey()),
+            10_000_000 - rent_exempt_minimum - LAMPORTS_PER_SIGNATURE,
+        );
+        assert_eq!(bank.get_balance(&to_pubkey), rent_exempt_minimum);
+
+        assert_eq!(
+            bank.process_transaction(transfer),
+            Err(TransactionError::AlreadyProcessed),
+        );
+    }
+
+    #[test]
+    fn test_process_transaction_failure_charges_fee() {
+        let mut bank = LocalBank::new();
+        let from_keypair = Keypair::new();
+        let to_pubkey = Pubkey::new_unique();
+        bank.airdrop(&from_keypair.pubkey(), 10_000_000);
+
+        // Transferring less than the rent exempt minimum into a new account
+        // fails, but the fee is still charged.
+        let transfer =
+            system_transaction::transfer(&from_keypair, &to_pubkey, 1, bank.latest_blockhash());
+        let outcome = bank.process_transaction(transfer).unwrap();
+        assert_eq!(
+            outcome.status,
+            Err(TransactionError::InsufficientFundsForRent { account_index: 1 }),
+        );
+        assert_eq!(
+            bank.get_balance(&from_keypair.pubkey()),
+            10_000_000 - LAMPORTS_PER_SIGNATURE,
+        );
+        assert!(bank.get_account(&to_pubkey).is_none());
+    }
+
+    #[test]
+    fn test_process_transaction_sigverify() {
+        let mut bank = LocalBank::new();
+        let from_keypair = Keypair::new();
+        bank.airdrop(&from_keypair.pubkey(), 10_000_000);
+
+        let mut transfer = system_transaction::transfer(
+            &from_keypair,
+            &Pubkey::new_unique(),
+            Rent::default().minimum_balance(0),
+            bank.latest_blockhash(),
+        );
+        transfer.signatures[0] = Signature::default();
+        assert_eq!(
+            bank.process_transaction(transfer.clone()),
+            Err(TransactionError::SignatureFailure),
+        );
+
+        bank.set_sigverify(false);
+        assert_eq!(bank.process_transaction(transfer).unwrap().status, Ok(()));
+    }
+
+    #[test]
+    fn test_blockhash_expiry() {
+        let mut bank = LocalBank::new();
+        let from_keypair = Keypair::new();
+        let to_pubkey = Pubkey::new_unique();
+        let rent_exempt_minimum = Rent::default().minimum_balance(0);
+        bank.airdrop(&from_keypair.pubkey(), 10_000_000);
+        let blockhash = bank.latest_blockhash();
+
+        for _ in 0..MAX_PROCESSING_AGE {
+            bank.advance_slot();
+        }
+        let transfer =
+            system_transaction::transfer(&from_keypair, &to_pubkey, rent_exempt_minimum, blockhash);
+        assert_eq!(bank.process_transaction(transfer).unwrap().status, Ok(()));
+
+        bank.advance_slot();
+        let transfer =
+            system_transaction::transfer(&from_keypair, &to_pubkey, rent_exempt_minimum, blockhash);
+        assert_eq!(
+            bank.process_transaction(transfer),
+            Err(TransactionError::BlockhashNotFound),
+        );
+    }
+
+    #[test]
+    fn test_simulate_transaction() {
+        let mut bank = LocalBank::new();
+        let from_keypair = Keypair::new();
+        let to_pubkey = Pubkey::new_unique();
+        let rent_exempt_minimum = Rent::default().minimum_balance(0);
+        bank.airdrop(&from_keypair.pubkey(), 10_000_000);
+
+        let transfer = system_transaction::transfer(
+            &from_keypair,
+            &to_pubkey,
+            rent_exempt_minimum,
+            bank.latest_blockhash(),
+        );
+        let outcome = bank.simulate_transaction(transfer.clone()).unwrap();
+        assert_eq!(outcome.status, Ok(()));
+        assert!(outcome
+            .post_accounts
+            .iter()
+            .any(|(pubkey, account)| *pubkey == to_pubkey
+                && account.lamports() == rent_exempt_minimum));
+        assert!(bank.get_account(&to_pubkey).is_none());
+
+        // Simulating doesn't mark the transaction as processed.
+        assert_eq!(bank.process_transaction(transfer).unwrap(), outcome);
+    }
+
+    #[test]
+    fn test_warp_to_slot() {
+        let mut bank = LocalBank::new();
+        let blockhash = bank.latest_blockhash();
+        bank.set_clock(&Clock {
+            unix_timestamp: 1_000,
+            ..bank.get_sysvar::<Clock>().unwrap()
+        });
+
+        bank.warp_to_slot(100);
+        assert_eq!(bank.slot(), 100);
+        assert_ne!(bank.latest_blockhash(), blockhash);
+        let clock = bank.get_sysvar::<Clock>().unwrap();
+        assert_eq!(clock.slot, 100);
+        assert_eq!(clock.unix_timestamp, 1_000);
+        assert_eq!(
+            bank.get_sysvar::<SlotHashes>().unwrap().get(&1),
+            Some(&blockhash),
+        );
+    }
+}
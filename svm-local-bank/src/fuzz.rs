@@ -0,0 +1,507 @@
+//! Deterministic fuzzing of programs against a [`LocalBank`].
+//!
+//! A [`Fuzzer`] turns the bytes of a fuzzing input into a sequence of
+//! transactions, using the instruction generators registered by the test,
+//! and executes them from the same initial state on every run. After each
+//! transaction it checks that neither the runtime nor an on-chain program
+//! panicked, that the transaction didn't consume more compute units than
+//! allowed, and that the registered invariants hold. A failing sequence is
+//! minimized to the fewest transactions that still fail the same way.
+//!
+//! Inputs are decoded with [`arbitrary::Unstructured`], so a fuzzer plugs
+//! into `cargo fuzz` as is:
+//!
+//! ```ignore
+//! thread_local! {
+//!     static FUZZER: RefCell<Fuzzer> = RefCell::new(build_fuzzer());
+//! }
+//!
+//! fuzz_target!(|data: &[u8]| FUZZER.with_borrow_mut(|fuzzer| fuzzer.fuzz(data)));
+//! ```
+
+use {
+    crate::{LocalBank, LocalBankCheckpoint, TransactionOutcome},
+    arbitrary::Unstructured,
+    solana_instruction::{error::InstructionError, Instruction},
+    solana_message::Message,
+    solana_pubkey::Pubkey,
+    solana_transaction::Transaction,
+    solana_transaction_error::{TransactionError, TransactionResult},
+    std::{
+        any::Any,
+        fmt, mem,
+        panic::{self, AssertUnwindSafe},
+    },
+    thiserror::Error,
+};
+
+/// Lamports of the fee payer of the generated transactions.
+const PAYER_LAMPORTS: u64 = 1_000_000_000_000_000;
+
+const DEFAULT_MAX_TRANSACTIONS: usize = 32;
+
+/// Generates the instructions of a transaction out of fuzzing input, given
+/// the current state of the bank.
+pub type InstructionGenerator =
+    Box<dyn Fn(&mut Unstructured, &LocalBank) -> arbitrary::Result<Vec<Instruction>>>;
+
+/// Checks a property of the state of the bank, returning a description of
+/// the violation if it doesn't hold.
+pub type Invariant = Box<dyn Fn(&LocalBank) -> Result<(), String>>;
+
+/// A transaction generated during a run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzStep {
+    /// Name of the generator of the instructions.
+    pub generator: String,
+    pub instructions: Vec<Instruction>,
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum FuzzFailureKind {
+    #[error("transaction panicked: {0}")]
+    Panic(String),
+    #[error("invariant `{name}` violated: {message}")]
+    InvariantViolated { name: String, message: String },
+    #[error("transaction consumed {consumed} compute units, over the limit of {limit}")]
+    ComputeUnitsExceeded { consumed: u64, limit: u64 },
+}
+
+impl FuzzFailureKind {
+    /// Returns whether both failures are likely to have the same cause, to
+    /// tell whether a smaller sequence of transactions still reproduces a
+    /// failure.
+    fn is_same_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::InvariantViolated { name, .. }, Self::InvariantViolated { name: other, .. }) => {
+                name == other
+            }
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+/// A sequence of transactions that fails a run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzFailure {
+    pub kind: FuzzFailureKind,
+    /// The transactions executed up to and including the failing one.
+    pub steps: Vec<FuzzStep>,
+}
+
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} after {} transactions:", self.kind, self.steps.len())?;
+        for (index, step) in self.steps.iter().enumerate() {
+            writeln!(f, "{index}: {} {:?}", step.generator, step.instructions)?;
+        }
+        Ok(())
+    }
+}
+
+/// Fuzzes the programs of a [`LocalBank`] with sequences of generated
+/// transactions, see the [module documentation](self).
+///
+/// Transactions are paid for by [`Fuzzer::payer`] and are not signed, as
+/// signature verification is disabled, so instructions may require the
+/// signature of any account.
+pub struct Fuzzer {
+    bank: LocalBank,
+    initial_state: LocalBankCheckpoint,
+    payer: Pubkey,
+    generators: Vec<(String, InstructionGenerator)>,
+    invariants: Vec<(String, Invariant)>,
+    compute_unit_limit: Option<u64>,
+    max_transactions: usize,
+}
+
+impl Fuzzer {
+    /// Creates a fuzzer that starts every run from the current state of
+    /// `bank`, with a funded fee payer added.
+    pub fn new(mut bank: LocalBank) -> Self {
+        // A fixed payer keeps the runs reproducible across processes.
+        let payer = Pubkey::new_from_array([0xfe; 32]);
+        bank.airdrop(&payer, PAYER_LAMPORTS);
+        bank.set_sigverify(false);
+        let initial_state = bank.checkpoint();
+        Self {
+            bank,
+            initial_state,
+            payer,
+            generators: vec![],
+            invariants: vec![],
+            compute_unit_limit: None,
+            max_transactions: DEFAULT_MAX_TRANSACTIONS,
+        }
+    }
+
+    /// The fee payer of the generated transactions.
+    pub fn payer(&self) -> Pubkey {
+        self.payer
+    }
+
+    /// The bank in the state the last run left it.
+    pub fn bank(&self) -> &LocalBank {
+        &self.bank
+    }
+
+    /// Adds a generator of transactions, which is picked by the fuzzing
+    /// input among all the generators for each transaction.
+    pub fn add_generator(
+        &mut self,
+        name: impl Into<String>,
+        generator: impl Fn(&mut Unstructured, &LocalBank) -> arbitrary::Result<Vec<Instruction>>
+            + 'static,
+    ) -> &mut Self {
+        self.generators.push((name.into(), Box::new(generator)));
+        self
+    }
+
+    /// Adds an invariant, checked after every transaction. Invariants that
+    /// panic count as violated.
+    pub fn add_invariant(
+        &mut self,
+        name: impl Into<String>,
+        invariant: impl Fn(&LocalBank) -> Result<(), String> + 'static,
+    ) -> &mut Self {
+        self.invariants.push((name.into(), Box::new(invariant)));
+        self
+    }
+
+    /// Fails runs with a transaction that consumes more than `limit` compute
+    /// units.
+    pub fn set_compute_unit_limit(&mut self, limit: u64) -> &mut Self {
+        self.compute_unit_limit = Some(limit);
+        self
+    }
+
+    /// Sets the maximum number of transactions of a run, 32 by default.
+    pub fn set_max_transactions(&mut self, max_transactions: usize) -> &mut Self {
+        self.max_transactions = max_transactions;
+        self
+    }
+
+    /// Runs the fuzzing input and panics with the minimized failure if it
+    /// fails, which is how fuzzing engines like libFuzzer detect crashes.
+    pub fn fuzz(&mut self, data: &[u8]) {
+        if let Err(failure) = self.run(data) {
+            let failure = self.minimize(failure);
+            panic!("{failure}");
+        }
+    }
+
+    /// Generates and executes transactions out of `data`, from the initial
+    /// state, until the input or the maximum number of transactions is
+    /// exhausted.
+    pub fn run(&mut self, data: &[u8]) -> Result<(), FuzzFailure> {
+        assert!(
+            !self.generators.is_empty(),
+            "the fuzzer has no instruction generators"
+        );
+        self.bank.restore(&self.initial_state);
+        let mut input = Unstructured::new(data);
+        let mut steps = vec![];
+        while steps.len() < self.max_transactions && !input.is_empty() {
+            let Ok(index) = input.choose_index(self.generators.len()) else {
+                break;
+            };
+            let (name, generator) = &self.generators[index];
+            // Generators fail once they run out of input
+            let Ok(instructions) = generator(&mut input, &self.bank) else {
+                break;
+            };
+            let step = FuzzStep {
+                generator: name.clone(),
+                instructions,
+            };
+            let result = self.execute(&step);
+            steps.push(step);
+            if let Err(kind) = result {
+                return Err(FuzzFailure { kind, steps });
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes `steps` from the initial state, e.g. to reproduce a failure
+    /// of an earlier fuzzing session.
+    pub fn replay(&mut self, steps: &[FuzzStep]) -> Result<(), FuzzFailure> {
+        self.bank.restore(&self.initial_state);
+        for (index, step) in steps.iter().enumerate() {
+            self.execute(step).map_err(|kind| FuzzFailure {
+                kind,
+                steps: steps[..=index].to_vec(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Removes the transactions of `failure` that are not needed to fail the
+    /// same way.
+    pub fn minimize(&mut self, mut failure: FuzzFailure) -> FuzzFailure {
+        let mut index = 0;
+        while index < failure.steps.len() {
+            let mut steps = failure.steps.clone();
+            steps.remove(index);
+            match self.replay(&steps) {
+                Err(smaller_failure) if smaller_failure.kind.is_same_as(&failure.kind) => {
+                    failure = smaller_failure;
+                }
+                _ => index = index.saturating_add(1),
+            }
+        }
+        failure
+    }
+
+    /// Processes the transaction of `step` and checks its effects.
+    fn execute(&mut self, step: &FuzzStep) -> Result<(), FuzzFailureKind> {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.process(&step.instructions)))
+            .map_err(|payload| FuzzFailureKind::Panic(panic_message(payload)))?;
+        // Transactions that can't be processed are a normal outcome of
+        // arbitrary instructions, and leave the bank untouched
+        if let Ok(outcome) = result {
+            if let Some(message) = program_panic_message(&outcome) {
+                return Err(FuzzFailureKind::Panic(message));
+            }
+            if let Some(limit) = self
+                .compute_unit_limit
+                .filter(|limit| outcome.compute_units_consumed > *limit)
+            {
+                return Err(FuzzFailureKind::ComputeUnitsExceeded {
+                    consumed: outcome.compute_units_consumed,
+                    limit,
+                });
+            }
+        }
+
+        for (name, invariant) in &self.invariants {
+            let violation = match panic::catch_unwind(AssertUnwindSafe(|| invariant(&self.bank))) {
+                Ok(result) => result.err(),
+                Err(payload) => Some(panic_message(payload)),
+            };
+            if let Some(message) = violation {
+                return Err(FuzzFailureKind::InvariantViolated {
+                    name: name.clone(),
+                    message,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn process(&mut self, instructions: &[Instruction]) -> TransactionResult<TransactionOutcome> {
+        let result = self
+            .bank
+            .process_transaction(self.transaction(instructions));
+        if !matches!(result, Err(TransactionError::AlreadyProcessed)) {
+            return result;
+        }
+        // Repeating a transaction is legitimate, it just needs a new blockhash
+        self.bank.advance_slot();
+        self.bank
+            .process_transaction(self.transaction(instructions))
+    }
+
+    fn transaction(&self, instructions: &[Instruction]) -> Transaction {
+        Transaction::new_unsigned(Message::new_with_blockhash(
+            instructions,
+            Some(&self.payer),
+            &self.bank.latest_blockhash(),
+        ))
+    }
+}
+
+/// Returns a description of the panic of an on-chain program executed by the
+/// transaction, which the runtime reports as a failed instruction rather than
+/// unwinding.
+fn program_panic_message(outcome: &TransactionOutcome) -> Option<String> {
+    if let Some(log) = outcome.logs.iter().find(|log| log.contains("panicked at")) {
+        return Some(log.clone());
+    }
+    match &outcome.status {
+        Err(
+            err @ TransactionError::InstructionError(_, InstructionError::ProgramFailedToComplete),
+        ) => Some(err.to_string()),
+        _ => None,
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic payload".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_account::ReadableAccount, solana_instruction::AccountMeta,
+        solana_program_runtime::declare_process_instruction, solana_rent::Rent,
+        solana_system_interface::instruction as system_instruction,
+    };
+
+    const TRANSFER_LAMPORTS: u64 = 10_000_000;
+
+    declare_process_instruction!(PanickingEntrypoint, 0, |_invoke_context| {
+        panic!("boom");
+    });
+
+    /// A fuzzer whose transactions transfer to `recipient` or to a bystander
+    fn transfer_fuzzer(recipient: Pubkey) -> Fuzzer {
+        let mut bank = LocalBank::new();
+        let bystander = Pubkey::new_unique();
+        let rent_exempt_minimum = Rent::default().minimum_balance(0);
+        bank.airdrop(&recipient, rent_exempt_minimum);
+        bank.airdrop(&bystander, rent_exempt_minimum);
+        let mut fuzzer = Fuzzer::new(bank);
+        let payer = fuzzer.payer();
+        fuzzer
+            .add_generator("transfer", move |_input, _bank| {
+                Ok(vec![system_instruction::transfer(
+                    &payer,
+                    &recipient,
+                    TRANSFER_LAMPORTS,
+                )])
+            })
+            .add_generator("bystander", move |input, _bank| {
+                Ok(vec![system_instruction::transfer(
+                    &payer,
+                    &bystander,
+                    input.int_in_range(1..=TRANSFER_LAMPORTS)?,
+                )])
+            });
+        fuzzer
+    }
+
+    #[test]
+    fn test_run_restores_initial_state() {
+        let recipient = Pubkey::new_unique();
+        let mut fuzzer = transfer_fuzzer(recipient);
+        let initial_balance = fuzzer.bank().get_balance(&recipient);
+
+        fuzzer.run(&[0u8; 64]).unwrap();
+        assert!(fuzzer.bank().get_balance(&recipient) > initial_balance);
+        fuzzer.run(&[]).unwrap();
+        assert_eq!(fuzzer.bank().get_balance(&recipient), initial_balance);
+    }
+
+    #[test]
+    fn test_minimize_invariant_violation() {
+        let recipient = Pubkey::new_unique();
+        let mut fuzzer = transfer_fuzzer(recipient);
+        let max_balance = fuzzer
+            .bank()
+            .get_balance(&recipient)
+            .saturating_add(TRANSFER_LAMPORTS.saturating_mul(2));
+        fuzzer.add_invariant("max balance", move |bank| {
+            let balance = bank.get_balance(&recipient);
+            (balance <= max_balance)
+                .then_some(())
+                .ok_or_else(|| format!("recipient holds {balance} lamports"))
+        });
+
+        let step = |generator: &str, to: &Pubkey| FuzzStep {
+            generator: generator.to_string(),
+            instructions: vec![system_instruction::transfer(
+                &fuzzer.payer(),
+                to,
+                TRANSFER_LAMPORTS,
+            )],
+        };
+        let bystander = Pubkey::new_unique();
+        let steps = vec![
+            step("bystander", &bystander),
+            step("transfer", &recipient),
+            step("bystander", &bystander),
+            step("transfer", &recipient),
+            step("transfer", &recipient),
+            step("bystander", &bystander),
+        ];
+        let failure = fuzzer.replay(&steps).unwrap_err();
+        assert_eq!(failure.steps.len(), 5);
+        assert!(matches!(
+            failure.kind,
+            FuzzFailureKind::InvariantViolated { ref name, .. } if name == "max balance"
+        ));
+
+        // Repeated transactions are processed in the next slot, and the
+        // minimized failure is reproducible
+        let failure = fuzzer.minimize(failure);
+        assert_eq!(failure.steps, vec![step("transfer", &recipient); 3]);
+        assert_eq!(fuzzer.replay(&failure.steps), Err(failure));
+    }
+
+    #[test]
+    fn test_compute_unit_limit() {
+        let mut fuzzer = transfer_fuzzer(Pubkey::new_unique());
+        fuzzer.set_compute_unit_limit(1);
+        let failure = fuzzer.run(&[0u8; 64]).unwrap_err();
+        assert_eq!(failure.steps.len(), 1);
+        assert!(matches!(
+            failure.kind,
+            FuzzFailureKind::ComputeUnitsExceeded { limit: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_program_panic() {
+        // The program panics on instruction data shorter than 8 bytes
+        let program_id = Pubkey::new_unique();
+        let mut bank = LocalBank::new();
+        bank.add_program(
+            program_id,
+            include_bytes!(
+                "../../svm/tests/example-programs/simple-transfer/simple_transfer_program.so"
+            ),
+        );
+        let recipient = Pubkey::new_unique();
+        bank.airdrop(&recipient, Rent::default().minimum_balance(0));
+        let mut fuzzer = Fuzzer::new(bank);
+        let payer = fuzzer.payer();
+        fuzzer.add_generator("transfer", move |input, _bank| {
+            let data_len = input.int_in_range(0..=8)?;
+            let data = input.bytes(data_len)?.to_vec();
+            Ok(vec![Instruction::new_with_bytes(
+                program_id,
+                &data,
+                vec![
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new(recipient, false),
+                    AccountMeta::new_readonly(solana_sdk_ids::system_program::id(), false),
+                ],
+            )])
+        });
+
+        // Full-length instruction data transfers, and short data panics
+        let mut data = vec![8];
+        data.extend_from_slice(&1u64.to_be_bytes());
+        fuzzer.run(&data).unwrap();
+        data.extend_from_slice(&[4, 1, 2, 3, 4]);
+        let failure = fuzzer.run(&data).unwrap_err();
+        assert_eq!(failure.steps.len(), 2);
+        assert!(matches!(failure.kind, FuzzFailureKind::Panic(_)));
+        let failure = fuzzer.minimize(failure);
+        assert_eq!(failure.steps.len(), 1);
+        assert!(matches!(failure.kind, FuzzFailureKind::Panic(_)));
+    }
+
+    #[test]
+    fn test_panic() {
+        let program_id = Pubkey::new_unique();
+        let mut bank = LocalBank::new();
+        bank.add_builtin(program_id, "panicking", PanickingEntrypoint::vm);
+        let mut fuzzer = Fuzzer::new(bank);
+        fuzzer.add_generator("panic", move |_input, _bank| {
+            Ok(vec![Instruction::new_with_bytes(program_id, &[], vec![])])
+        });
+
+        let failure = fuzzer.run(&[0u8; 64]).unwrap_err();
+        assert_eq!(failure.kind, FuzzFailureKind::Panic("boom".to_string()));
+        assert!(fuzzer
+            .bank()
+            .get_account(&program_id)
+            .is_some_and(|account| account.executable()));
+    }
+}
@@ -0,0 +1,16 @@
+#![cfg(feature = "agave-unstable-api")]
+//! An in-memory bank built on the SVM transaction batch processor.
+//!
+//! [`LocalBank`] executes transactions against accounts held in a hash map,
+//! without the accounts database, genesis config or any other validator
+//! plumbing. Transactions go through the same sanitization, signature
+//! verification, age, fee and rent checks as on a validator, which makes it
+//! suitable for unit and property-based tests that process many transactions
+//! per second.
+
+mod accounts;
+mod bank;
+pub mod fuzz;
+pub mod transaction_checks;
+
+pub use bank::{LocalBank, LocalBankCheckpoint, TransactionOutcome};
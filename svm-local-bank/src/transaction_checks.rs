@@ -0,0 +1,243 @@
+//! Sanitization and checks of transactions against accounts held in memory.
+//!
+//! These mirror what the bank does before it executes a transaction, for
+//! [`LocalBank`](crate::LocalBank) and other in-memory banks built on the
+//! SVM transaction batch processor, such as the SVM test harness.
+
+use {
+    agave_feature_set::{
+        limit_instruction_accounts, raise_cpi_nesting_limit_to_8, require_static_nonce_account,
+        static_instruction_limit, FeatureSet,
+    },
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_address_lookup_table_interface::{
+        self as address_lookup_table, error::AddressLookupError, state::AddressLookupTable,
+    },
+    solana_clock::Slot,
+    solana_fee::{calculate_fee_details, FeeFeatures},
+    solana_fee_structure::FeeBudgetLimits,
+    solana_hash::Hash,
+    solana_message::{
+        v0::{LoadedAddresses, MessageAddressTableLookup},
+        AddressLoader,
+    },
+    solana_nonce::state::DurableNonce,
+    solana_program_runtime::{
+        loaded_programs::{BlockRelation, ForkGraph},
+        sysvar_cache::SysvarCache,
+    },
+    solana_pubkey::Pubkey,
+    solana_runtime_transaction::{
+        runtime_transaction::RuntimeTransaction, transaction_meta::StaticMeta,
+    },
+    solana_slot_hashes::SlotHashes,
+    solana_svm::{
+        account_loader::{CheckedTransactionDetails, TransactionCheckResult},
+        transaction_processor::TransactionProcessingEnvironment,
+    },
+    solana_svm_transaction::svm_message::SVMStaticMessage,
+    solana_transaction::{
+        sanitized::{MessageHash, SanitizedTransaction},
+        versioned::VersionedTransaction,
+    },
+    solana_transaction_error::{AddressLoaderError, TransactionError, TransactionResult},
+    std::{cmp::Ordering, collections::HashSet},
+};
+
+/// Fork graph of a bank whose slots form a single chain.
+pub struct LinearForkGraph;
+
+impl ForkGraph for LinearForkGraph {
+    fn relationship(&self, a: Slot, b: Slot) -> BlockRelation {
+        match a.cmp(&b) {
+            Ordering::Less => BlockRelation::Ancestor,
+            Ordering::Equal => BlockRelation::Equal,
+            Ordering::Greater => BlockRelation::Descendant,
+        }
+    }
+}
+
+/// Resolves address table lookups against the accounts returned by
+/// `get_account`.
+pub struct AccountsAddressLoader<'a, F> {
+    pub get_account: F,
+    pub slot: Slot,
+    pub slot_hashes: &'a SlotHashes,
+}
+
+impl<'a, F> AccountsAddressLoader<'a, F>
+where
+    F: Fn(&Pubkey) -> Option<&'a AccountSharedData>,
+{
+    fn load_lookup_table_addresses_into(
+        &self,
+        address_table_lookup: &MessageAddressTableLookup,
+        loaded_addresses: &mut LoadedAddresses,
+    ) -> Result<(), AddressLookupError> {
+        let table_account = (self.get_account)(&address_table_lookup.account_key)
+            .ok_or(AddressLookupError::LookupTableAccountNotFound)?;
+        if table_account.owner() != &address_lookup_table::program::id() {
+            return Err(AddressLookupError::InvalidAccountOwner);
+        }
+
+        let lookup_table = AddressLookupTable::deserialize(table_account.data())
+            .map_err(|_ix_err| AddressLookupError::InvalidAccountData)?;
+        for (indexes, addresses) in [
+            (
+                &address_table_lookup.writable_indexes,
+                &mut loaded_addresses.writable,
+            ),
+            (
+                &address_table_lookup.readonly_indexes,
+                &mut loaded_addresses.readonly,
+            ),
+        ] {
+            for address in lookup_table.lookup_iter(self.slot, indexes, self.slot_hashes)? {
+                addresses.push(address.ok_or(AddressLookupError::InvalidLookupIndex)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, F> AddressLoader for AccountsAddressLoader<'a, F>
+where
+    F: Fn(&Pubkey) -> Option<&'a AccountSharedData>,
+{
+    fn load_addresses(
+        self,
+        address_table_lookups: &[MessageAddressTableLookup],
+    ) -> Result<LoadedAddresses, AddressLoaderError> {
+        let mut loaded_addresses = LoadedAddresses::default();
+        for address_table_lookup in address_table_lookups {
+            self.load_lookup_table_addresses_into(address_table_lookup, &mut loaded_addresses)
+                .map_err(|err| match err {
+                    AddressLookupError::LookupTableAccountNotFound => {
+                        AddressLoaderError::LookupTableAccountNotFound
+                    }
+                    AddressLookupError::InvalidAccountOwner => {
+                        AddressLoaderError::InvalidAccountOwner
+                    }
+                    AddressLookupError::InvalidAccountData => {
+                        AddressLoaderError::InvalidAccountData
+                    }
+                    AddressLookupError::InvalidLookupIndex => {
+                        AddressLoaderError::InvalidLookupIndex
+                    }
+                })?;
+        }
+        Ok(loaded_addresses)
+    }
+}
+
+/// Sanitizes `transaction` at `slot`, resolving its address table lookups
+/// against the accounts returned by `get_account`.
+pub fn sanitize_transaction<'a>(
+    transaction: VersionedTransaction,
+    message_hash: MessageHash,
+    slot: Slot,
+    sysvar_cache: &SysvarCache,
+    get_account: impl Fn(&Pubkey) -> Option<&'a AccountSharedData>,
+    reserved_account_keys: &HashSet<Pubkey>,
+    feature_set: &FeatureSet,
+) -> TransactionResult<RuntimeTransaction<SanitizedTransaction>> {
+    let slot_hashes = sysvar_cache
+        .get_slot_hashes()
+        .map_err(|_| AddressLoaderError::SlotHashesSysvarNotFound)?;
+    let address_loader = AccountsAddressLoader {
+        get_account,
+        slot,
+        slot_hashes: &slot_hashes,
+    };
+    RuntimeTransaction::try_create(
+        transaction,
+        message_hash,
+        None,
+        address_loader,
+        reserved_account_keys,
+        feature_set.is_active(&static_instruction_limit::id()),
+        feature_set.is_active(&limit_instruction_accounts::id()),
+    )
+}
+
+/// Mirrors the bank's compute budget, age and status cache checks.
+///
+/// `is_recent_blockhash` tells whether a blockhash is recent enough for a
+/// transaction to be processed, and `is_processed` whether a message hash was
+/// already processed.
+pub fn check_transaction<'a>(
+    transaction: &RuntimeTransaction<SanitizedTransaction>,
+    feature_set: &FeatureSet,
+    processing_environment: &TransactionProcessingEnvironment,
+    is_recent_blockhash: impl FnOnce(&Hash) -> bool,
+    is_processed: impl FnOnce(&Hash) -> bool,
+    get_account: impl Fn(&Pubkey) -> Option<&'a AccountSharedData>,
+) -> TransactionCheckResult {
+    let compute_budget_and_limits = transaction
+        .compute_budget_instruction_details()
+        .sanitize_and_convert_to_compute_budget_limits(feature_set)
+        .map(|limits| {
+            let fee_budget = FeeBudgetLimits::from(limits);
+            let fee_details = calculate_fee_details(
+                transaction,
+                false, /* zero_fees_for_test */
+                processing_environment.blockhash_lamports_per_signature,
+                fee_budget.prioritization_fee,
+                FeeFeatures::from(feature_set),
+            );
+            limits.get_compute_budget_and_limits(
+                fee_budget.loaded_accounts_data_size_limit,
+                fee_details,
+                feature_set.is_active(&raise_cpi_nesting_limit_to_8::id()),
+            )
+        })?;
+
+    let nonce_address = if is_recent_blockhash(transaction.recent_blockhash()) {
+        None
+    } else {
+        Some(check_durable_nonce(
+            transaction,
+            feature_set,
+            &processing_environment.blockhash,
+            get_account,
+        )?)
+    };
+
+    if is_processed(transaction.message_hash()) {
+        return Err(TransactionError::AlreadyProcessed);
+    }
+
+    Ok(CheckedTransactionDetails::new(
+        nonce_address,
+        compute_budget_and_limits,
+    ))
+}
+
+/// Returns the address of the durable nonce account `transaction` uses in
+/// place of a recent blockhash, which must hold a nonce that can be advanced
+/// to the one derived from `blockhash`.
+pub fn check_durable_nonce<'a>(
+    transaction: &RuntimeTransaction<SanitizedTransaction>,
+    feature_set: &FeatureSet,
+    blockhash: &Hash,
+    get_account: impl Fn(&Pubkey) -> Option<&'a AccountSharedData>,
+) -> TransactionResult<Pubkey> {
+    let recent_blockhash = transaction.recent_blockhash();
+    let next_durable_nonce = DurableNonce::from_blockhash(blockhash);
+    let nonce_is_advanceable = recent_blockhash != next_durable_nonce.as_hash();
+    let nonce_address = nonce_is_advanceable
+        .then(|| {
+            transaction
+                .get_durable_nonce(feature_set.is_active(&require_static_nonce_account::id()))
+        })
+        .flatten()
+        .filter(|nonce_address| {
+            get_account(nonce_address)
+                .and_then(|nonce_account| {
+                    solana_nonce_account::verify_nonce_account(nonce_account, recent_blockhash)
+                })
+                .is_some()
+        })
+        .ok_or(TransactionError::BlockhashNotFound)?;
+    Ok(*nonce_address)
+}
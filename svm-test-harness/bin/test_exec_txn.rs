@@ -0,0 +1,77 @@
+use {
+    clap::Parser,
+    prost::Message,
+    solana_svm_test_harness::txn::{
+        fixture::proto::{SlotTxnsFixture as ProtoSlotTxnsFixture, TxnFixture as ProtoTxnFixture},
+        fuzz::{execute_slot_proto, execute_txn_proto},
+    },
+    std::path::PathBuf,
+};
+
+#[derive(Parser)]
+#[command(version, about, long_about = None)]
+struct Cli {
+    /// Treat the inputs as slot fixtures instead of transaction fixtures.
+    #[arg(long)]
+    slot: bool,
+    inputs: Vec<PathBuf>,
+}
+
+fn exec_txn(input: &PathBuf) -> bool {
+    let blob = std::fs::read(input).unwrap();
+    let fixture = ProtoTxnFixture::decode(&blob[..]).unwrap();
+    let Some(context) = fixture.input else {
+        println!("No context found.");
+        return false;
+    };
+
+    let Some(expected) = fixture.output else {
+        println!("No fixture found.");
+        return false;
+    };
+    let Some(result) = execute_txn_proto(context) else {
+        println!("FAIL: No transaction result returned for input: {input:?}",);
+        return false;
+    };
+
+    result == expected
+}
+
+fn exec_slot(input: &PathBuf) -> bool {
+    let blob = std::fs::read(input).unwrap();
+    let fixture = ProtoSlotTxnsFixture::decode(&blob[..]).unwrap();
+    let Some(context) = fixture.input else {
+        println!("No context found.");
+        return false;
+    };
+
+    let Some(expected) = fixture.output else {
+        println!("No fixture found.");
+        return false;
+    };
+    let Some(effects) = execute_slot_proto(context) else {
+        println!("FAIL: No slot effects returned for input: {input:?}",);
+        return false;
+    };
+
+    effects == expected
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let mut fail_cnt: i32 = 0;
+    for input in cli.inputs {
+        let ok = if cli.slot {
+            exec_slot(&input)
+        } else {
+            exec_txn(&input)
+        };
+        if ok {
+            println!("OK: {input:?}");
+        } else {
+            println!("FAIL: {input:?}");
+            fail_cnt = fail_cnt.saturating_add(1);
+        }
+    }
+    std::process::exit(fail_cnt);
+}
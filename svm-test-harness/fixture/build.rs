@@ -23,6 +23,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
 
+        // Add the harness's own protos, which build on the protosol ones
+        let local_proto_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?).join("proto");
+        println!("cargo:rerun-if-changed={}", local_proto_dir.display());
+        for entry in fs::read_dir(&local_proto_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("proto") {
+                println!("cargo:rerun-if-changed={}", path.display());
+                proto_files.push(path);
+            }
+        }
+
         // Ensure deterministic order for rebuilds
         proto_files.sort();
 
@@ -36,7 +47,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .iter()
                 .map(|p| p.display().to_string())
                 .collect::<Vec<_>>(),
-            &[proto_dir.to_str().unwrap()],
+            &[proto_dir.to_str().unwrap(), local_proto_dir.to_str().unwrap()],
         )?;
     }
 
@@ -39,3 +39,16 @@ impl From<&ProtoFeatureSet> for FeatureSet {
         feature_set
     }
 }
+
+impl From<&FeatureSet> for ProtoFeatureSet {
+    fn from(value: &FeatureSet) -> Self {
+        let mut features: Vec<u64> = value
+            .active()
+            .keys()
+            .filter(|pubkey| INDEXED_FEATURES.contains_key(&feature_u64(pubkey)))
+            .map(feature_u64)
+            .collect();
+        features.sort_unstable();
+        ProtoFeatureSet { features }
+    }
+}
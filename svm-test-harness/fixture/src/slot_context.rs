@@ -0,0 +1,61 @@
+//! Slot context (input).
+
+use {
+    agave_feature_set::FeatureSet, solana_account::Account, solana_clock::Slot, solana_hash::Hash,
+    solana_pubkey::Pubkey, solana_transaction::versioned::VersionedTransaction,
+};
+
+/// Slot context fixture.
+///
+/// A sequence of transactions executed in order against shared account
+/// state, as they would be within a single slot.
+pub struct SlotContext {
+    pub feature_set: FeatureSet,
+    pub accounts: Vec<(Pubkey, Account)>,
+    pub transactions: Vec<VersionedTransaction>,
+    /// Recent blockhashes, oldest first. The last entry is the blockhash of
+    /// the slot being processed, from which durable nonces are derived.
+    pub blockhash_queue: Vec<Hash>,
+    pub slot: Slot,
+}
+
+#[cfg(feature = "fuzz")]
+use crate::{
+    error::FixtureError,
+    proto::SlotTxnsContext as ProtoSlotTxnsContext,
+    txn_context::{
+        blockhash_queue_from_proto, feature_set_from_epoch_context, slot_from_slot_context,
+    },
+};
+
+#[cfg(feature = "fuzz")]
+impl TryFrom<ProtoSlotTxnsContext> for SlotContext {
+    type Error = FixtureError;
+
+    fn try_from(value: ProtoSlotTxnsContext) -> Result<Self, Self::Error> {
+        let feature_set = feature_set_from_epoch_context(value.epoch_ctx.as_ref());
+        let slot = slot_from_slot_context(value.slot_ctx.as_ref());
+
+        let accounts = value
+            .account_shared_data
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let transactions = value
+            .txs
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let blockhash_queue = blockhash_queue_from_proto(value.blockhash_queue)?;
+
+        Ok(Self {
+            feature_set,
+            accounts,
+            transactions,
+            blockhash_queue,
+            slot,
+        })
+    }
+}
@@ -0,0 +1,40 @@
+//! Slot effects (output).
+
+use {crate::txn_result::TxnResult, solana_account::Account, solana_pubkey::Pubkey};
+
+/// Represents the effects of a sequence of transactions within a slot.
+pub struct SlotEffects {
+    /// Results of each transaction, in execution order.
+    pub txn_results: Vec<TxnResult>,
+    /// Final state of every account modified by any transaction in the slot.
+    pub resulting_accounts: Vec<(Pubkey, Account)>,
+}
+
+impl SlotEffects {
+    /// Returns the final state of the account for the given pubkey, if it was
+    /// modified by the slot.
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<&Account> {
+        self.resulting_accounts
+            .iter()
+            .find(|(pk, _)| pk == pubkey)
+            .map(|(_, acc)| acc)
+    }
+}
+
+#[cfg(feature = "fuzz")]
+use crate::proto::SlotTxnsEffects as ProtoSlotTxnsEffects;
+
+#[cfg(feature = "fuzz")]
+impl From<SlotEffects> for ProtoSlotTxnsEffects {
+    fn from(value: SlotEffects) -> Self {
+        let SlotEffects {
+            txn_results,
+            resulting_accounts,
+        } = value;
+
+        Self {
+            txn_results: txn_results.into_iter().map(Into::into).collect(),
+            acct_states: resulting_accounts.into_iter().map(Into::into).collect(),
+        }
+    }
+}
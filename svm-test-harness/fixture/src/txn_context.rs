@@ -0,0 +1,215 @@
+//! Transaction context (input).
+
+use {
+    agave_feature_set::FeatureSet, solana_account::Account, solana_clock::Slot, solana_hash::Hash,
+    solana_pubkey::Pubkey, solana_transaction::versioned::VersionedTransaction,
+};
+
+/// Transaction context fixture.
+pub struct TxnContext {
+    pub feature_set: FeatureSet,
+    pub accounts: Vec<(Pubkey, Account)>,
+    pub transaction: VersionedTransaction,
+    /// Recent blockhashes, oldest first. The last entry is the blockhash of
+    /// the slot being processed, from which durable nonces are derived.
+    pub blockhash_queue: Vec<Hash>,
+    pub slot: Slot,
+}
+
+#[cfg(feature = "fuzz")]
+use {
+    crate::{
+        error::FixtureError,
+        proto::{
+            CompiledInstruction as ProtoCompiledInstruction, EpochContext as ProtoEpochContext,
+            MessageAddressTableLookup as ProtoMessageAddressTableLookup,
+            MessageHeader as ProtoMessageHeader, SanitizedTransaction as ProtoSanitizedTransaction,
+            SlotContext as ProtoSlotContext, TransactionMessage as ProtoTransactionMessage,
+            TxnContext as ProtoTxnContext,
+        },
+    },
+    solana_message::{
+        compiled_instruction::CompiledInstruction,
+        legacy,
+        v0::{self, MessageAddressTableLookup},
+        MessageHeader, VersionedMessage,
+    },
+    solana_signature::Signature,
+};
+
+#[cfg(feature = "fuzz")]
+fn pubkey_from_bytes(bytes: Vec<u8>) -> Result<Pubkey, FixtureError> {
+    Pubkey::try_from(bytes).map_err(FixtureError::InvalidPubkeyBytes)
+}
+
+#[cfg(feature = "fuzz")]
+fn hash_from_bytes(bytes: Vec<u8>) -> Result<Hash, FixtureError> {
+    <[u8; 32]>::try_from(bytes)
+        .map(Hash::new_from_array)
+        .map_err(FixtureError::InvalidHashBytes)
+}
+
+#[cfg(feature = "fuzz")]
+pub(crate) fn feature_set_from_epoch_context(epoch_ctx: Option<&ProtoEpochContext>) -> FeatureSet {
+    epoch_ctx
+        .and_then(|epoch_ctx| epoch_ctx.features.as_ref())
+        .map(|fs| fs.into())
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "fuzz")]
+pub(crate) fn slot_from_slot_context(slot_ctx: Option<&ProtoSlotContext>) -> Slot {
+    slot_ctx.map(|slot_ctx| slot_ctx.slot).unwrap_or_default()
+}
+
+#[cfg(feature = "fuzz")]
+impl From<ProtoMessageHeader> for MessageHeader {
+    fn from(value: ProtoMessageHeader) -> Self {
+        Self {
+            num_required_signatures: value.num_required_signatures as u8,
+            num_readonly_signed_accounts: value.num_readonly_signed_accounts as u8,
+            num_readonly_unsigned_accounts: value.num_readonly_unsigned_accounts as u8,
+        }
+    }
+}
+
+#[cfg(feature = "fuzz")]
+impl From<ProtoCompiledInstruction> for CompiledInstruction {
+    fn from(value: ProtoCompiledInstruction) -> Self {
+        Self {
+            program_id_index: value.program_id_index as u8,
+            accounts: value
+                .accounts
+                .into_iter()
+                .map(|index| index as u8)
+                .collect(),
+            data: value.data,
+        }
+    }
+}
+
+#[cfg(feature = "fuzz")]
+impl TryFrom<ProtoMessageAddressTableLookup> for MessageAddressTableLookup {
+    type Error = FixtureError;
+
+    fn try_from(value: ProtoMessageAddressTableLookup) -> Result<Self, Self::Error> {
+        Ok(Self {
+            account_key: pubkey_from_bytes(value.account_key)?,
+            writable_indexes: value
+                .writable_indexes
+                .into_iter()
+                .map(|index| index as u8)
+                .collect(),
+            readonly_indexes: value
+                .readonly_indexes
+                .into_iter()
+                .map(|index| index as u8)
+                .collect(),
+        })
+    }
+}
+
+#[cfg(feature = "fuzz")]
+impl TryFrom<ProtoTransactionMessage> for VersionedMessage {
+    type Error = FixtureError;
+
+    fn try_from(value: ProtoTransactionMessage) -> Result<Self, Self::Error> {
+        let header = value
+            .header
+            .map(Into::into)
+            .ok_or(FixtureError::InvalidFixtureInput)?;
+        let account_keys = value
+            .account_keys
+            .into_iter()
+            .map(pubkey_from_bytes)
+            .collect::<Result<Vec<_>, _>>()?;
+        let recent_blockhash = hash_from_bytes(value.recent_blockhash)?;
+        let instructions = value.instructions.into_iter().map(Into::into).collect();
+
+        if value.is_legacy {
+            Ok(VersionedMessage::Legacy(legacy::Message {
+                header,
+                account_keys,
+                recent_blockhash,
+                instructions,
+            }))
+        } else {
+            let address_table_lookups = value
+                .address_table_lookups
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(VersionedMessage::V0(v0::Message {
+                header,
+                account_keys,
+                recent_blockhash,
+                instructions,
+                address_table_lookups,
+            }))
+        }
+    }
+}
+
+#[cfg(feature = "fuzz")]
+impl TryFrom<ProtoSanitizedTransaction> for VersionedTransaction {
+    type Error = FixtureError;
+
+    fn try_from(value: ProtoSanitizedTransaction) -> Result<Self, Self::Error> {
+        let message = value
+            .message
+            .ok_or(FixtureError::InvalidFixtureInput)?
+            .try_into()?;
+        let signatures = value
+            .signatures
+            .into_iter()
+            .map(|signature| {
+                <[u8; 64]>::try_from(signature)
+                    .map(Signature::from)
+                    .map_err(FixtureError::InvalidSignatureBytes)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            signatures,
+            message,
+        })
+    }
+}
+
+#[cfg(feature = "fuzz")]
+pub(crate) fn blockhash_queue_from_proto(
+    blockhash_queue: Vec<Vec<u8>>,
+) -> Result<Vec<Hash>, FixtureError> {
+    blockhash_queue.into_iter().map(hash_from_bytes).collect()
+}
+
+#[cfg(feature = "fuzz")]
+impl TryFrom<ProtoTxnContext> for TxnContext {
+    type Error = FixtureError;
+
+    fn try_from(value: ProtoTxnContext) -> Result<Self, Self::Error> {
+        let feature_set = feature_set_from_epoch_context(value.epoch_ctx.as_ref());
+        let slot = slot_from_slot_context(value.slot_ctx.as_ref());
+
+        let accounts = value
+            .account_shared_data
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let transaction = value
+            .tx
+            .ok_or(FixtureError::InvalidFixtureInput)?
+            .try_into()?;
+
+        let blockhash_queue = blockhash_queue_from_proto(value.blockhash_queue)?;
+
+        Ok(Self {
+            feature_set,
+            accounts,
+            transaction,
+            blockhash_queue,
+            slot,
+        })
+    }
+}
@@ -0,0 +1,121 @@
+//! Transaction result (output).
+
+use {
+    solana_account::Account, solana_fee_structure::FeeDetails, solana_pubkey::Pubkey,
+    solana_transaction_error::TransactionResult,
+};
+
+/// Represents the result of processing a single transaction.
+pub struct TxnResult {
+    /// Whether the transaction was processed, i.e. it will be committed and
+    /// pays fees, even if its execution failed.
+    pub executed: bool,
+    /// Whether the transaction was rejected before processing because it
+    /// could not be sanitized.
+    pub sanitization_error: bool,
+    pub status: TransactionResult<()>,
+    /// Accounts modified by the transaction, including the fee payer and any
+    /// advanced nonce account when execution failed.
+    pub resulting_accounts: Vec<(Pubkey, Account)>,
+    pub fee_details: FeeDetails,
+    pub executed_units: u64,
+    pub loaded_accounts_data_size: u32,
+    pub return_data: Vec<u8>,
+    pub logs: Vec<String>,
+}
+
+impl TxnResult {
+    /// Returns a result for a transaction that failed sanitization.
+    pub fn sanitization_failure(status: TransactionResult<()>) -> Self {
+        Self {
+            executed: false,
+            sanitization_error: true,
+            status,
+            resulting_accounts: vec![],
+            fee_details: FeeDetails::default(),
+            executed_units: 0,
+            loaded_accounts_data_size: 0,
+            return_data: vec![],
+            logs: vec![],
+        }
+    }
+
+    /// Returns the resulting account for the given pubkey, if it exists.
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<&Account> {
+        self.resulting_accounts
+            .iter()
+            .find(|(pk, _)| pk == pubkey)
+            .map(|(_, acc)| acc)
+    }
+}
+
+#[cfg(feature = "fuzz")]
+use {
+    crate::proto::{
+        FeeDetails as ProtoFeeDetails, ResultingState as ProtoResultingState,
+        TxnResult as ProtoTxnResult,
+    },
+    bincode,
+    serde::Serialize,
+    solana_instruction_error::InstructionError,
+    solana_transaction_error::TransactionError,
+};
+
+/// Encodes an error as its enum discriminant plus one, leaving zero for
+/// success.
+#[cfg(feature = "fuzz")]
+pub(crate) fn error_code(error: &impl Serialize) -> u32 {
+    let serialized_err = bincode::serialize(error).unwrap();
+    u32::from_le_bytes((&serialized_err[0..4]).try_into().unwrap()).saturating_add(1)
+}
+
+#[cfg(feature = "fuzz")]
+impl From<TxnResult> for ProtoTxnResult {
+    fn from(value: TxnResult) -> Self {
+        let TxnResult {
+            executed,
+            sanitization_error,
+            status,
+            resulting_accounts,
+            fee_details,
+            executed_units,
+            loaded_accounts_data_size,
+            return_data,
+            ..
+        } = value;
+
+        let (instruction_error, instruction_error_index, custom_error) = match &status {
+            Err(TransactionError::InstructionError(index, error)) => (
+                error_code(error),
+                u32::from(*index),
+                match error {
+                    InstructionError::Custom(code) => *code,
+                    _ => 0,
+                },
+            ),
+            _ => (0, 0, 0),
+        };
+
+        Self {
+            executed,
+            sanitization_error,
+            resulting_state: Some(ProtoResultingState {
+                acct_states: resulting_accounts.into_iter().map(Into::into).collect(),
+                ..ProtoResultingState::default()
+            }),
+            is_ok: status.is_ok(),
+            status: status.as_ref().err().map(error_code).unwrap_or_default(),
+            instruction_error,
+            instruction_error_index,
+            custom_error,
+            return_data,
+            executed_units,
+            fee_details: Some(ProtoFeeDetails {
+                transaction_fee: fee_details.transaction_fee(),
+                prioritization_fee: fee_details.prioritization_fee(),
+            }),
+            loaded_accounts_data_size: u64::from(loaded_accounts_data_size),
+            ..Self::default()
+        }
+    }
+}
@@ -1,3 +1,6 @@
 //! Solana SVM test harness.
 
-pub use {solana_svm_test_harness_fixture as fixture, solana_svm_test_harness_instr as instr};
+pub use {
+    solana_svm_test_harness_fixture as fixture, solana_svm_test_harness_instr as instr,
+    solana_svm_test_harness_txn as txn,
+};
@@ -0,0 +1,79 @@
+#![allow(clippy::missing_safety_doc)]
+
+use {
+    crate::{
+        execute_slot, execute_txn,
+        fixture::{
+            proto::{
+                SlotTxnsContext as ProtoSlotTxnsContext, SlotTxnsEffects as ProtoSlotTxnsEffects,
+                TxnContext as ProtoTxnContext, TxnResult as ProtoTxnResult,
+            },
+            slot_context::SlotContext,
+            txn_context::TxnContext,
+        },
+    },
+    prost::Message,
+    std::ffi::c_int,
+};
+
+pub fn execute_txn_proto(input: ProtoTxnContext) -> Option<ProtoTxnResult> {
+    let Ok(txn_context) = TxnContext::try_from(input) else {
+        return None;
+    };
+    Some(execute_txn(txn_context).into())
+}
+
+pub fn execute_slot_proto(input: ProtoSlotTxnsContext) -> Option<ProtoSlotTxnsEffects> {
+    let Ok(slot_context) = SlotContext::try_from(input) else {
+        return None;
+    };
+    Some(execute_slot(slot_context).into())
+}
+
+/// Decodes `in_ptr` as `I`, runs `execute` and encodes the output into
+/// `out_ptr`, returning 1 on success and 0 on any failure.
+unsafe fn sol_compat_execute<I: Message + Default, O: Message>(
+    out_ptr: *mut u8,
+    out_psz: *mut u64,
+    in_ptr: *mut u8,
+    in_sz: u64,
+    execute: impl FnOnce(I) -> Option<O>,
+) -> c_int {
+    let in_slice = unsafe { std::slice::from_raw_parts(in_ptr, in_sz as usize) };
+    let Ok(input) = I::decode(in_slice) else {
+        return 0;
+    };
+    let Some(output) = execute(input) else {
+        return 0;
+    };
+    let out_slice = unsafe { std::slice::from_raw_parts_mut(out_ptr, (*out_psz) as usize) };
+    let out_vec = output.encode_to_vec();
+    if out_vec.len() > out_slice.len() {
+        return 0;
+    }
+    out_slice[..out_vec.len()].copy_from_slice(&out_vec);
+    unsafe {
+        *out_psz = out_vec.len() as u64;
+    }
+    1
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sol_compat_txn_execute_v1(
+    out_ptr: *mut u8,
+    out_psz: *mut u64,
+    in_ptr: *mut u8,
+    in_sz: u64,
+) -> c_int {
+    unsafe { sol_compat_execute(out_ptr, out_psz, in_ptr, in_sz, execute_txn_proto) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sol_compat_slot_execute_v1(
+    out_ptr: *mut u8,
+    out_psz: *mut u64,
+    in_ptr: *mut u8,
+    in_sz: u64,
+) -> c_int {
+    unsafe { sol_compat_execute(out_ptr, out_psz, in_ptr, in_sz, execute_slot_proto) }
+}
@@ -0,0 +1,826 @@
+//! Transaction harness.
+
+use {
+    crate::fixture::{
+        slot_context::SlotContext, slot_effects::SlotEffects, txn_context::TxnContext,
+        txn_result::TxnResult,
+    },
+    agave_feature_set::{
+        increase_cpi_account_info_limit, raise_cpi_nesting_limit_to_8, FeatureSet,
+    },
+    agave_precompiles::{get_precompile, is_precompile},
+    agave_reserved_account_keys::ReservedAccountKeys,
+    agave_syscalls::{
+        create_program_runtime_environment_v1, create_program_runtime_environment_v2,
+    },
+    solana_account::{Account, AccountSharedData, ReadableAccount},
+    solana_builtins::BUILTINS,
+    solana_clock::{Clock, Epoch, Slot},
+    solana_compute_budget::compute_budget::ComputeBudget,
+    solana_epoch_schedule::EpochSchedule,
+    solana_fee_structure::FeeStructure,
+    solana_hash::Hash,
+    solana_precompile_error::PrecompileError,
+    solana_program_runtime::loaded_programs::{ProgramCacheEntry, ProgramRuntimeEnvironments},
+    solana_pubkey::Pubkey,
+    solana_rent::Rent,
+    solana_runtime_transaction::{
+        runtime_transaction::RuntimeTransaction, transaction_meta::StaticMeta,
+    },
+    solana_sdk_ids::{native_loader, sysvar},
+    solana_slot_hashes::SlotHashes,
+    solana_svm::{
+        account_loader::TransactionCheckResult,
+        transaction_processing_result::ProcessedTransaction,
+        transaction_processor::{
+            ExecutionRecordingConfig, TransactionBatchProcessor, TransactionProcessingConfig,
+            TransactionProcessingEnvironment,
+        },
+    },
+    solana_svm_callback::{InvokeContextCallback, TransactionProcessingCallback},
+    solana_svm_local_bank::transaction_checks::{self, LinearForkGraph},
+    solana_svm_transaction::svm_message::SVMMessage,
+    solana_svm_type_overrides::sync::{Arc, RwLock},
+    solana_sysvar_id::SysvarId,
+    solana_transaction::{
+        sanitized::{MessageHash, SanitizedTransaction},
+        versioned::VersionedTransaction,
+    },
+    solana_transaction_error::TransactionError,
+    std::collections::{HashMap, HashSet},
+};
+
+/// Account state and features shared by all transactions of a fixture.
+struct HarnessBank {
+    feature_set: FeatureSet,
+    accounts: HashMap<Pubkey, AccountSharedData>,
+}
+
+impl InvokeContextCallback for HarnessBank {
+    fn is_precompile(&self, program_id: &Pubkey) -> bool {
+        is_precompile(program_id, |feature_id| {
+            self.feature_set.is_active(feature_id)
+        })
+    }
+
+    fn process_precompile(
+        &self,
+        program_id: &Pubkey,
+        data: &[u8],
+        instruction_datas: Vec<&[u8]>,
+    ) -> Result<(), PrecompileError> {
+        if let Some(precompile) = get_precompile(program_id, |feature_id| {
+            self.feature_set.is_active(feature_id)
+        }) {
+            precompile.verify(data, &instruction_datas, &self.feature_set)
+        } else {
+            Err(PrecompileError::InvalidPublicKey)
+        }
+    }
+}
+
+impl TransactionProcessingCallback for HarnessBank {
+    fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<(AccountSharedData, Slot)> {
+        self.accounts
+            .get(pubkey)
+            .map(|account| (account.clone(), 0u64))
+    }
+}
+
+impl HarnessBank {
+    fn insert_default_account(
+        &mut self,
+        pubkey: Pubkey,
+        owner: &Pubkey,
+        data: Vec<u8>,
+        executable: bool,
+    ) {
+        self.accounts.entry(pubkey).or_insert_with(|| {
+            AccountSharedData::from(Account {
+                lamports: Rent::default().minimum_balance(data.len()).max(1),
+                data,
+                owner: *owner,
+                executable,
+                rent_epoch: u64::MAX,
+            })
+        });
+    }
+
+    fn insert_default_sysvar<T: SysvarId + serde::Serialize>(&mut self, sysvar: &T) {
+        self.insert_default_account(
+            T::id(),
+            &sysvar::id(),
+            bincode::serialize(sysvar).unwrap(),
+            false, /* executable */
+        );
+    }
+
+    fn get_sysvar<T: SysvarId + serde::de::DeserializeOwned>(&self) -> Option<T> {
+        self.accounts
+            .get(&T::id())
+            .and_then(|account| bincode::deserialize(account.data()).ok())
+    }
+
+    /// Commits the accounts resulting from a transaction, purging any that
+    /// were closed.
+    fn store_accounts(&mut self, accounts: &[(Pubkey, Account)]) {
+        for (pubkey, account) in accounts {
+            if account.lamports == 0 {
+                self.accounts.remove(pubkey);
+            } else {
+                self.accounts
+                    .insert(*pubkey, AccountSharedData::from(account.clone()));
+            }
+        }
+    }
+}
+
+/// A single slot's worth of SVM state, against which fixture transactions are
+/// sanitized, checked, executed and committed in order.
+struct HarnessEnvironment {
+    bank: HarnessBank,
+    _fork_graph: Arc<RwLock<LinearForkGraph>>,
+    processor: TransactionBatchProcessor<LinearForkGraph>,
+    processing_environment: TransactionProcessingEnvironment,
+    blockhash_queue: Vec<Hash>,
+    reserved_account_keys: HashSet<Pubkey>,
+    processed_message_hashes: HashSet<Hash>,
+    slot: Slot,
+}
+
+impl HarnessEnvironment {
+    fn new(
+        feature_set: &FeatureSet,
+        accounts: &[(Pubkey, Account)],
+        blockhash_queue: &[Hash],
+        slot: Slot,
+    ) -> Self {
+        let mut bank = HarnessBank {
+            feature_set: feature_set.clone(),
+            accounts: accounts
+                .iter()
+                .map(|(pubkey, account)| (*pubkey, AccountSharedData::from(account.clone())))
+                .collect(),
+        };
+
+        // Fixtures only need to provide the sysvars they care about.
+        let epoch_schedule = bank.get_sysvar::<EpochSchedule>().unwrap_or_default();
+        bank.insert_default_sysvar(&Clock {
+            slot,
+            epoch: epoch_schedule.get_epoch(slot),
+            ..Clock::default()
+        });
+        bank.insert_default_sysvar(&epoch_schedule);
+        bank.insert_default_sysvar(&Rent::default());
+        bank.insert_default_sysvar(&SlotHashes::new(&[]));
+        let epoch: Epoch = bank.get_sysvar::<Clock>().unwrap_or_default().epoch;
+
+        let simd_0268_active = feature_set.is_active(&raise_cpi_nesting_limit_to_8::id());
+        let simd_0339_active = feature_set.is_active(&increase_cpi_account_info_limit::id());
+        let compute_budget =
+            ComputeBudget::new_with_defaults(simd_0268_active, simd_0339_active).to_budget();
+        let runtime_features = feature_set.runtime_features();
+        let environments = ProgramRuntimeEnvironments {
+            program_runtime_v1: Arc::new(
+                create_program_runtime_environment_v1(
+                    &runtime_features,
+                    &compute_budget,
+                    false, /* deployment */
+                    false, /* debugging_features */
+                )
+                .unwrap(),
+            ),
+            program_runtime_v2: Arc::new(create_program_runtime_environment_v2(
+                &compute_budget,
+                false, /* debugging_features */
+            )),
+        };
+
+        let fork_graph = Arc::new(RwLock::new(LinearForkGraph));
+        let mut processor = TransactionBatchProcessor::new_uninitialized(slot, epoch);
+        {
+            let mut program_cache = processor.global_program_cache.write().unwrap();
+            program_cache.set_fork_graph(Arc::downgrade(&fork_graph));
+            program_cache.latest_root_slot = slot;
+        }
+        processor.environments = environments.clone();
+
+        for builtin in BUILTINS {
+            let is_enabled = builtin
+                .enable_feature_id
+                .map(|feature_id| feature_set.is_active(&feature_id))
+                .unwrap_or(true);
+            // Migrated builtins are expected to be provided by the fixture as
+            // regular program accounts.
+            let is_migrated = builtin
+                .core_bpf_migration_config
+                .as_ref()
+                .map(|config| feature_set.is_active(&config.feature_id))
+                .unwrap_or(false);
+            if !is_enabled || is_migrated {
+                continue;
+            }
+            bank.insert_default_account(
+                builtin.program_id,
+                &native_loader::id(),
+                builtin.name.as_bytes().to_vec(),
+                true, /* executable */
+            );
+            processor.add_builtin(
+                builtin.program_id,
+                ProgramCacheEntry::new_builtin(0, builtin.name.len(), builtin.entrypoint),
+            );
+        }
+        processor.fill_missing_sysvar_cache_entries(&bank);
+
+        let processing_environment = TransactionProcessingEnvironment {
+            blockhash: blockhash_queue.last().copied().unwrap_or_default(),
+            blockhash_lamports_per_signature: FeeStructure::default().lamports_per_signature,
+            feature_set: runtime_features,
+            program_runtime_environments_for_execution: environments.clone(),
+            program_runtime_environments_for_deployment: environments,
+            rent: bank.get_sysvar::<Rent>().unwrap_or_default(),
+            ..TransactionProcessingEnvironment::default()
+        };
+
+        let mut reserved_account_keys = ReservedAccountKeys::default();
+        reserved_account_keys.update_active_set(feature_set);
+
+        Self {
+            bank,
+            _fork_graph: fork_graph,
+            processor,
+            processing_environment,
+            blockhash_queue: blockhash_queue.to_vec(),
+            reserved_account_keys: reserved_account_keys.active,
+            processed_message_hashes: HashSet::new(),
+            slot,
+        }
+    }
+
+    fn sanitize_transaction(
+        &self,
+        transaction: VersionedTransaction,
+    ) -> Result<RuntimeTransaction<SanitizedTransaction>, TransactionError> {
+        transaction_checks::sanitize_transaction(
+            transaction,
+            MessageHash::Compute,
+            self.slot,
+            &self.processor.sysvar_cache(),
+            |pubkey| self.bank.accounts.get(pubkey),
+            &self.reserved_account_keys,
+            &self.bank.feature_set,
+        )
+    }
+
+    fn check_transaction(
+        &self,
+        transaction: &RuntimeTransaction<SanitizedTransaction>,
+    ) -> TransactionCheckResult {
+        transaction_checks::check_transaction(
+            transaction,
+            &self.bank.feature_set,
+            &self.processing_environment,
+            |recent_blockhash| self.blockhash_queue.contains(recent_blockhash),
+            |message_hash| self.processed_message_hashes.contains(message_hash),
+            |pubkey| self.bank.accounts.get(pubkey),
+        )
+    }
+
+    /// Sanitizes, checks and executes a transaction, then commits its effects
+    /// so they are visible to subsequent transactions.
+    fn process_transaction(&mut self, transaction: VersionedTransaction) -> TxnResult {
+        let transaction = match self.sanitize_transaction(transaction) {
+            Ok(transaction) => transaction,
+            Err(err) => return TxnResult::sanitization_failure(Err(err)),
+        };
+        let check_result = self.check_transaction(&transaction);
+
+        let processing_config = TransactionProcessingConfig {
+            recording_config: ExecutionRecordingConfig {
+                enable_cpi_recording: false,
+                enable_log_recording: true,
+                enable_return_data_recording: true,
+                enable_transaction_balance_recording: false,
+            },
+            ..TransactionProcessingConfig::default()
+        };
+        let output = self.processor.load_and_execute_sanitized_transactions(
+            &self.bank,
+            std::slice::from_ref(&transaction),
+            vec![check_result],
+            &self.processing_environment,
+            &processing_config,
+        );
+        let processing_result = output.processing_results.into_iter().next().unwrap();
+
+        let result = match processing_result {
+            Err(err) => TxnResult {
+                executed: false,
+                sanitization_error: false,
+                status: Err(err),
+                resulting_accounts: vec![],
+                fee_details: Default::default(),
+                executed_units: 0,
+                loaded_accounts_data_size: 0,
+                return_data: vec![],
+                logs: vec![],
+            },
+            Ok(ProcessedTransaction::Executed(executed_transaction)) => {
+                let loaded_transaction = &executed_transaction.loaded_transaction;
+                let execution_details = &executed_transaction.execution_details;
+                let resulting_accounts = if executed_transaction.was_successful() {
+                    loaded_transaction
+                        .accounts
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| transaction.is_writable(*index))
+                        .map(|(_, (pubkey, account))| (*pubkey, account.clone().into()))
+                        .collect()
+                } else {
+                    loaded_transaction
+                        .rollback_accounts
+                        .iter()
+                        .map(|(pubkey, account)| (*pubkey, account.clone().into()))
+                        .collect()
+                };
+
+                if executed_transaction.was_successful()
+                    && !executed_transaction.programs_modified_by_tx.is_empty()
+                {
+                    self.processor.global_program_cache.write().unwrap().merge(
+                        &self.processor.environments,
+                        self.slot,
+                        &executed_transaction.programs_modified_by_tx,
+                    );
+                }
+
+                TxnResult {
+                    executed: true,
+                    sanitization_error: false,
+                    status: execution_details.status.clone(),
+                    resulting_accounts,
+                    fee_details: loaded_transaction.fee_details,
+                    executed_units: execution_details.executed_units,
+                    loaded_accounts_data_size: loaded_transaction.loaded_accounts_data_size,
+                    return_data: execution_details
+                        .return_data
+                        .as_ref()
+                        .map(|return_data| return_data.data.clone())
+                        .unwrap_or_default(),
+                    logs: execution_details.log_messages.clone().unwrap_or_default(),
+                }
+            }
+            Ok(ProcessedTransaction::FeesOnly(fees_only_transaction)) => TxnResult {
+                executed: true,
+                sanitization_error: false,
+                status: Err(fees_only_transaction.load_error.clone()),
+                resulting_accounts: fees_only_transaction
+                    .rollback_accounts
+                    .iter()
+                    .map(|(pubkey, account)| (*pubkey, account.clone().into()))
+                    .collect(),
+                fee_details: fees_only_transaction.fee_details,
+                executed_units: 0,
+                loaded_accounts_data_size: 0,
+                return_data: vec![],
+                logs: vec![],
+            },
+        };
+
+        if result.executed {
+            self.bank.store_accounts(&result.resulting_accounts);
+            self.processed_message_hashes
+                .insert(*transaction.message_hash());
+        }
+        result
+    }
+}
+
+/// Execute a single transaction against the SVM.
+///
+/// The transaction goes through the same sanitization, compute budget, age
+/// and fee payer checks as in the bank, so the result covers fee payer,
+/// durable nonce, address lookup table and rent state handling, including
+/// rollback of failed transactions.
+pub fn execute_txn(input: TxnContext) -> TxnResult {
+    let TxnContext {
+        feature_set,
+        accounts,
+        transaction,
+        blockhash_queue,
+        slot,
+    } = input;
+    let mut environment = HarnessEnvironment::new(&feature_set, &accounts, &blockhash_queue, slot);
+    environment.process_transaction(transaction)
+}
+
+/// Execute a sequence of transactions within a single slot against the SVM.
+///
+/// Each transaction observes the committed effects of the ones before it,
+/// and a transaction that was already processed earlier in the slot is
+/// rejected.
+pub fn execute_slot(input: SlotContext) -> SlotEffects {
+    let SlotContext {
+        feature_set,
+        accounts,
+        transactions,
+        blockhash_queue,
+        slot,
+    } = input;
+    let mut environment = HarnessEnvironment::new(&feature_set, &accounts, &blockhash_queue, slot);
+
+    let mut modified_accounts = HashMap::new();
+    let txn_results = transactions
+        .into_iter()
+        .map(|transaction| {
+            let result = environment.process_transaction(transaction);
+            for (pubkey, account) in &result.resulting_accounts {
+                modified_accounts.insert(*pubkey, account.clone());
+            }
+            result
+        })
+        .collect();
+
+    let mut resulting_accounts: Vec<_> = modified_accounts.into_iter().collect();
+    resulting_accounts.sort_unstable_by_key(|(pubkey, _)| *pubkey);
+
+    SlotEffects {
+        txn_results,
+        resulting_accounts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_address_lookup_table_interface::{
+            self as address_lookup_table,
+            state::{AddressLookupTable, LookupTableMeta},
+        },
+        solana_compute_budget_interface::ComputeBudgetInstruction,
+        solana_keypair::Keypair,
+        solana_message::{v0, AddressLookupTableAccount, VersionedMessage},
+        solana_nonce::{
+            state::{Data as NonceData, DurableNonce, State as NonceState},
+            versions::Versions as NonceVersions,
+        },
+        solana_signer::Signer,
+        solana_system_interface::instruction as system_instruction,
+        solana_system_transaction as system_transaction,
+        solana_transaction::Transaction,
+        std::borrow::Cow,
+    };
+
+    const LAMPORTS_PER_SIGNATURE: u64 = 5000;
+
+    fn system_account(lamports: u64) -> Account {
+        Account {
+            lamports,
+            owner: solana_system_interface::program::id(),
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_execute_txn_transfer() {
+        let from_keypair = Keypair::new();
+        let to_pubkey = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let rent_exempt_minimum = Rent::default().minimum_balance(0);
+
+        let context = TxnContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts: vec![(from_keypair.pubkey(), system_account(10_000_000))],
+            transaction: system_transaction::transfer(
+                &from_keypair,
+                &to_pubkey,
+                rent_exempt_minimum,
+                blockhash,
+            )
+            .into(),
+            blockhash_queue: vec![blockhash],
+            slot: 10,
+        };
+
+        let result = execute_txn(context);
+        assert!(result.executed);
+        assert!(!result.sanitization_error);
+        assert_eq!(result.status, Ok(()));
+        assert_eq!(result.fee_details.total_fee(), LAMPORTS_PER_SIGNATURE);
+        assert_eq!(
+            result.get_account(&from_keypair.pubkey()).unwrap().lamports,
+            10_000_000 - rent_exempt_minimum - LAMPORTS_PER_SIGNATURE,
+        );
+        assert_eq!(
+            result.get_account(&to_pubkey).unwrap().lamports,
+            rent_exempt_minimum,
+        );
+    }
+
+    #[test]
+    fn test_execute_txn_rent_state_rollback() {
+        let from_keypair = Keypair::new();
+        let to_pubkey = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+
+        // Transferring less than the rent exempt minimum into a new account
+        // fails, but the fee is still charged.
+        let context = TxnContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts: vec![(from_keypair.pubkey(), system_account(10_000_000))],
+            transaction: system_transaction::transfer(&from_keypair, &to_pubkey, 1, blockhash)
+                .into(),
+            blockhash_queue: vec![blockhash],
+            slot: 10,
+        };
+
+        let result = execute_txn(context);
+        assert!(result.executed);
+        assert_eq!(
+            result.status,
+            Err(TransactionError::InsufficientFundsForRent { account_index: 1 }),
+        );
+        assert_eq!(
+            result.get_account(&from_keypair.pubkey()).unwrap().lamports,
+            10_000_000 - LAMPORTS_PER_SIGNATURE,
+        );
+        assert!(result.get_account(&to_pubkey).is_none());
+    }
+
+    #[test]
+    fn test_execute_txn_blockhash_not_found() {
+        let from_keypair = Keypair::new();
+        let context = TxnContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts: vec![(from_keypair.pubkey(), system_account(10_000_000))],
+            transaction: system_transaction::transfer(
+                &from_keypair,
+                &Pubkey::new_unique(),
+                1,
+                Hash::new_unique(),
+            )
+            .into(),
+            blockhash_queue: vec![Hash::new_unique()],
+            slot: 10,
+        };
+
+        let result = execute_txn(context);
+        assert!(!result.executed);
+        assert_eq!(result.status, Err(TransactionError::BlockhashNotFound));
+        assert!(result.resulting_accounts.is_empty());
+    }
+
+    fn nonce_account(authority: &Pubkey, durable_nonce: DurableNonce) -> Account {
+        let state = NonceState::Initialized(NonceData::new(
+            *authority,
+            durable_nonce,
+            LAMPORTS_PER_SIGNATURE,
+        ));
+        Account {
+            lamports: Rent::default().minimum_balance(NonceState::size()),
+            data: bincode::serialize(&NonceVersions::new(state)).unwrap(),
+            owner: solana_system_interface::program::id(),
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_execute_txn_durable_nonce() {
+        let from_keypair = Keypair::new();
+        let to_pubkey = Pubkey::new_unique();
+        let nonce_pubkey = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let durable_nonce = DurableNonce::from_blockhash(&Hash::new_unique());
+        let rent_exempt_minimum = Rent::default().minimum_balance(0);
+
+        // The transaction uses the stored nonce in place of a recent
+        // blockhash, which is not in the blockhash queue.
+        let nonced_transfer = system_transaction::nonced_transfer(
+            &from_keypair,
+            &to_pubkey,
+            rent_exempt_minimum,
+            &nonce_pubkey,
+            &from_keypair,
+            *durable_nonce.as_hash(),
+        );
+        let context = |nonce_account| TxnContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts: vec![
+                (from_keypair.pubkey(), system_account(10_000_000)),
+                (nonce_pubkey, nonce_account),
+            ],
+            transaction: nonced_transfer.clone().into(),
+            blockhash_queue: vec![blockhash],
+            slot: 10,
+        };
+
+        let result = execute_txn(context(nonce_account(
+            &from_keypair.pubkey(),
+            durable_nonce,
+        )));
+        assert!(result.executed);
+        assert_eq!(result.status, Ok(()));
+        assert_eq!(
+            result.get_account(&to_pubkey).unwrap().lamports,
+            rent_exempt_minimum,
+        );
+        // The nonce is advanced to the one derived from the blockhash of the
+        // slot.
+        let nonce_versions: NonceVersions =
+            bincode::deserialize(&result.get_account(&nonce_pubkey).unwrap().data).unwrap();
+        let NonceState::Initialized(nonce_data) = nonce_versions.state() else {
+            panic!("nonce account is not initialized");
+        };
+        assert_eq!(
+            nonce_data.durable_nonce,
+            DurableNonce::from_blockhash(&blockhash),
+        );
+
+        // A nonce account holding another nonce does not stand in for the
+        // blockhash.
+        let result = execute_txn(context(nonce_account(
+            &from_keypair.pubkey(),
+            DurableNonce::from_blockhash(&Hash::new_unique()),
+        )));
+        assert!(!result.executed);
+        assert_eq!(result.status, Err(TransactionError::BlockhashNotFound));
+    }
+
+    #[test]
+    fn test_execute_txn_address_lookup_table() {
+        let from_keypair = Keypair::new();
+        let to_pubkey = Pubkey::new_unique();
+        let table_pubkey = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let rent_exempt_minimum = Rent::default().minimum_balance(0);
+
+        let table_data = AddressLookupTable {
+            meta: LookupTableMeta::default(),
+            addresses: Cow::Owned(vec![to_pubkey]),
+        }
+        .serialize_for_tests()
+        .unwrap();
+        let table_account = Account {
+            lamports: Rent::default().minimum_balance(table_data.len()),
+            data: table_data,
+            owner: address_lookup_table::program::id(),
+            ..Account::default()
+        };
+
+        // The recipient is only referenced through the lookup table.
+        let message = v0::Message::try_compile(
+            &from_keypair.pubkey(),
+            &[system_instruction::transfer(
+                &from_keypair.pubkey(),
+                &to_pubkey,
+                rent_exempt_minimum,
+            )],
+            &[AddressLookupTableAccount {
+                key: table_pubkey,
+                addresses: vec![to_pubkey],
+            }],
+            blockhash,
+        )
+        .unwrap();
+        assert_eq!(message.address_table_lookups.len(), 1);
+        let transaction =
+            VersionedTransaction::try_new(VersionedMessage::V0(message), &[&from_keypair]).unwrap();
+
+        let context = |accounts| TxnContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts,
+            transaction: transaction.clone(),
+            blockhash_queue: vec![blockhash],
+            slot: 10,
+        };
+
+        let result = execute_txn(context(vec![
+            (from_keypair.pubkey(), system_account(10_000_000)),
+            (table_pubkey, table_account),
+        ]));
+        assert!(result.executed);
+        assert_eq!(result.status, Ok(()));
+        assert_eq!(
+            result.get_account(&to_pubkey).unwrap().lamports,
+            rent_exempt_minimum,
+        );
+
+        // Without the lookup table, the transaction fails sanitization.
+        let result = execute_txn(context(vec![(
+            from_keypair.pubkey(),
+            system_account(10_000_000),
+        )]));
+        assert!(!result.executed);
+        assert!(result.sanitization_error);
+        assert_eq!(
+            result.status,
+            Err(TransactionError::AddressLookupTableNotFound),
+        );
+    }
+
+    #[test]
+    fn test_execute_txn_compute_budget() {
+        let from_keypair = Keypair::new();
+        let to_pubkey = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let rent_exempt_minimum = Rent::default().minimum_balance(0);
+
+        let transfer_with_compute_budget = |compute_unit_limit| {
+            Transaction::new_signed_with_payer(
+                &[
+                    ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+                    ComputeBudgetInstruction::set_compute_unit_price(1_000_000),
+                    system_instruction::transfer(
+                        &from_keypair.pubkey(),
+                        &to_pubkey,
+                        rent_exempt_minimum,
+                    ),
+                ],
+                Some(&from_keypair.pubkey()),
+                &[&from_keypair],
+                blockhash,
+            )
+        };
+        let context = |compute_unit_limit| TxnContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts: vec![(from_keypair.pubkey(), system_account(10_000_000))],
+            transaction: transfer_with_compute_budget(compute_unit_limit).into(),
+            blockhash_queue: vec![blockhash],
+            slot: 10,
+        };
+
+        // A price of one lamport per compute unit adds a prioritization fee
+        // of one lamport per requested compute unit.
+        let result = execute_txn(context(1_000));
+        assert!(result.executed);
+        assert_eq!(result.status, Ok(()));
+        assert_eq!(result.fee_details.prioritization_fee(), 1_000);
+        assert_eq!(
+            result.fee_details.total_fee(),
+            LAMPORTS_PER_SIGNATURE + 1_000,
+        );
+
+        // Too low a limit fails execution, but the fee is still charged.
+        let result = execute_txn(context(1));
+        assert!(result.executed);
+        assert!(matches!(
+            result.status,
+            Err(TransactionError::InstructionError(..)),
+        ));
+        assert_eq!(result.fee_details.total_fee(), LAMPORTS_PER_SIGNATURE + 1);
+        assert_eq!(
+            result.get_account(&from_keypair.pubkey()).unwrap().lamports,
+            10_000_000 - LAMPORTS_PER_SIGNATURE - 1,
+        );
+    }
+
+    #[test]
+    fn test_execute_slot_shared_state() {
+        let from_keypair = Keypair::new();
+        let to_pubkey = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let rent_exempt_minimum = Rent::default().minimum_balance(0);
+
+        let first_transfer =
+            system_transaction::transfer(&from_keypair, &to_pubkey, rent_exempt_minimum, blockhash);
+        // The second transfer only succeeds because the first one funded the
+        // recipient above the rent exempt minimum.
+        let second_transfer = system_transaction::transfer(&from_keypair, &to_pubkey, 1, blockhash);
+
+        let context = SlotContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts: vec![(from_keypair.pubkey(), system_account(10_000_000))],
+            transactions: vec![
+                first_transfer.clone().into(),
+                second_transfer.into(),
+                first_transfer.into(),
+            ],
+            blockhash_queue: vec![blockhash],
+            slot: 10,
+        };
+
+        let effects = execute_slot(context);
+        let statuses: Vec<_> = effects
+            .txn_results
+            .iter()
+            .map(|result| result.status.clone())
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![Ok(()), Ok(()), Err(TransactionError::AlreadyProcessed)],
+        );
+        assert_eq!(
+            effects
+                .get_account(&from_keypair.pubkey())
+                .unwrap()
+                .lamports,
+            10_000_000 - rent_exempt_minimum - 1 - 2 * LAMPORTS_PER_SIGNATURE,
+        );
+        assert_eq!(
+            effects.get_account(&to_pubkey).unwrap().lamports,
+            rent_exempt_minimum + 1,
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! Solana SVM test harness for transaction execution.
+//!
+//! This crate provides an API for Agave's transaction batch processor in
+//! order to execute whole transactions, or sequences of transactions within
+//! a slot, against shared account state.
+
+mod harness;
+
+pub use {
+    harness::{execute_slot, execute_txn},
+    solana_svm_test_harness_fixture as fixture,
+};
+
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
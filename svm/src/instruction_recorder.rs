@@ -0,0 +1,177 @@
+//! Opt-in capture of top-level instructions and their effects.
+//!
+//! An [`InstructionRecorder`] is handed every top-level instruction whose
+//! program it selects, together with the state of the accounts it executed
+//! against and the effects it produced. That is enough to re-execute the
+//! instruction in isolation, e.g. to build regression fixtures from real
+//! traffic.
+
+use {
+    solana_account::{Account, ReadableAccount},
+    solana_instruction::{AccountMeta, error::InstructionError},
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    solana_svm_transaction::{instruction::SVMInstruction, svm_message::SVMMessage},
+    solana_transaction_context::{IndexOfAccount, transaction::TransactionContext},
+};
+
+/// Receives top-level instructions selected for recording.
+pub trait InstructionRecorder: Sync {
+    /// Returns whether instructions invoking `program_id` should be recorded.
+    fn should_record(&self, program_id: &Pubkey) -> bool;
+
+    /// Returns the state of the accounts that a selected instruction may read
+    /// besides `accounts`, its own, such as sysvars or program data. Called
+    /// before the instruction is executed.
+    fn capture_extra_accounts(&self, _accounts: &[(Pubkey, Account)]) -> Vec<(Pubkey, Account)> {
+        Vec::new()
+    }
+
+    /// Called once a selected instruction has been executed.
+    fn record(&self, instruction: RecordedInstruction);
+}
+
+/// A top-level instruction captured during execution.
+#[derive(Debug)]
+pub struct RecordedInstruction {
+    /// Signature of the transaction the instruction belongs to.
+    pub signature: Signature,
+    /// Index of the instruction within its transaction.
+    pub instruction_index: usize,
+    pub program_id: Pubkey,
+    pub instruction_accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+    /// State of the program account and of every instruction account before
+    /// the instruction was executed, in transaction order.
+    pub accounts: Vec<(Pubkey, Account)>,
+    /// State of the accounts returned by
+    /// [`InstructionRecorder::capture_extra_accounts`] before the instruction
+    /// was executed.
+    pub extra_accounts: Vec<(Pubkey, Account)>,
+    /// Compute units left in the transaction when the instruction started.
+    pub compute_units_available: u64,
+    pub result: Result<(), InstructionError>,
+    /// State of the same accounts as `accounts` once the instruction
+    /// returned. Failed instructions may leave partial modifications here,
+    /// which the transaction discards.
+    pub resulting_accounts: Vec<(Pubkey, Account)>,
+    /// Compute units left in the transaction when the instruction returned.
+    pub compute_units_remaining: u64,
+    pub return_data: Vec<u8>,
+}
+
+/// Pre-execution state of an instruction selected for recording.
+pub(crate) struct InstructionCapture {
+    program_id: Pubkey,
+    instruction_accounts: Vec<AccountMeta>,
+    data: Vec<u8>,
+    account_indices: Vec<IndexOfAccount>,
+    accounts: Vec<(Pubkey, Account)>,
+    extra_accounts: Vec<(Pubkey, Account)>,
+    compute_units_available: u64,
+}
+
+impl InstructionCapture {
+    pub(crate) fn new(
+        recorder: &dyn InstructionRecorder,
+        transaction_context: &TransactionContext,
+        message: &impl SVMMessage,
+        instruction: &SVMInstruction,
+        program_account_index: IndexOfAccount,
+        compute_units_available: u64,
+    ) -> Self {
+        let instruction_accounts = instruction
+            .accounts
+            .iter()
+            .map(|index_in_transaction| {
+                let index_in_transaction = usize::from(*index_in_transaction);
+                AccountMeta {
+                    pubkey: account_key(
+                        transaction_context,
+                        index_in_transaction as IndexOfAccount,
+                    ),
+                    is_signer: message.is_signer(index_in_transaction),
+                    is_writable: message.is_writable(index_in_transaction),
+                }
+            })
+            .collect();
+
+        let mut account_indices: Vec<IndexOfAccount> = instruction
+            .accounts
+            .iter()
+            .map(|index| IndexOfAccount::from(*index))
+            .chain(std::iter::once(program_account_index))
+            .collect();
+        account_indices.sort_unstable();
+        account_indices.dedup();
+
+        let accounts = snapshot_accounts(transaction_context, &account_indices);
+        Self {
+            program_id: account_key(transaction_context, program_account_index),
+            instruction_accounts,
+            data: instruction.data.to_vec(),
+            extra_accounts: recorder.capture_extra_accounts(&accounts),
+            accounts,
+            account_indices,
+            compute_units_available,
+        }
+    }
+
+    pub(crate) fn finish(
+        self,
+        transaction_context: &TransactionContext,
+        signature: Signature,
+        instruction_index: usize,
+        result: &Result<(), InstructionError>,
+        compute_units_consumed: u64,
+    ) -> RecordedInstruction {
+        RecordedInstruction {
+            signature,
+            instruction_index,
+            program_id: self.program_id,
+            instruction_accounts: self.instruction_accounts,
+            data: self.data,
+            accounts: self.accounts,
+            extra_accounts: self.extra_accounts,
+            compute_units_available: self.compute_units_available,
+            result: result.clone(),
+            resulting_accounts: snapshot_accounts(transaction_context, &self.account_indices),
+            compute_units_remaining: self
+                .compute_units_available
+                .saturating_sub(compute_units_consumed),
+            return_data: transaction_context.get_return_data().1.to_vec(),
+        }
+    }
+}
+
+fn account_key(transaction_context: &TransactionContext, index: IndexOfAccount) -> Pubkey {
+    transaction_context
+        .get_key_of_account_at_index(index)
+        .copied()
+        .unwrap_or_default()
+}
+
+fn snapshot_accounts(
+    transaction_context: &TransactionContext,
+    account_indices: &[IndexOfAccount],
+) -> Vec<(Pubkey, Account)> {
+    account_indices
+        .iter()
+        .filter_map(|index| {
+            let pubkey = transaction_context
+                .get_key_of_account_at_index(*index)
+                .ok()?;
+            let account = transaction_context.accounts().try_borrow(*index).ok()?;
+            Some((
+                *pubkey,
+                Account {
+                    lamports: account.lamports(),
+                    data: account.data().to_vec(),
+                    owner: *account.owner(),
+                    executable: account.executable(),
+                    rent_epoch: account.rent_epoch(),
+                },
+            ))
+        })
+        .collect()
+}
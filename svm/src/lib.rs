@@ -4,6 +4,7 @@
 
 pub mod account_loader;
 pub mod account_overrides;
+pub mod instruction_recorder;
 pub mod message_processor;
 pub mod nonce_info;
 pub mod program_loader;
@@ -1,5 +1,7 @@
 use {
+    crate::instruction_recorder::{InstructionCapture, InstructionRecorder},
     solana_program_runtime::invoke_context::InvokeContext,
+    solana_signature::Signature,
     solana_svm_measure::measure_us,
     solana_svm_timings::{ExecuteDetailsTimings, ExecuteTimings},
     solana_svm_transaction::svm_message::SVMMessage,
@@ -12,12 +14,15 @@ use {
 /// For each instruction it calls the program entrypoint method and verifies that the result of
 /// the call does not violate the bank's accounting rules.
 /// The accounts are committed back to the bank only if every instruction succeeds.
+/// Instructions selected by `instruction_recorder` are reported to it, tagged with the
+/// given transaction signature, once they have been executed.
 pub(crate) fn process_message<'ix_data>(
     message: &'ix_data impl SVMMessage,
     program_indices: &[IndexOfAccount],
     invoke_context: &mut InvokeContext<'_, 'ix_data>,
     execute_timings: &mut ExecuteTimings,
     accumulated_consumed_units: &mut u64,
+    instruction_recorder: Option<(&dyn InstructionRecorder, &Signature)>,
 ) -> Result<(), TransactionError> {
     debug_assert_eq!(program_indices.len(), message.num_instructions());
     for (top_level_instruction_index, ((program_id, instruction), program_account_index)) in message
@@ -36,6 +41,22 @@ pub(crate) fn process_message<'ix_data>(
                 TransactionError::InstructionError(top_level_instruction_index as u8, err)
             })?;
 
+        let capture = instruction_recorder
+            .filter(|(recorder, _)| recorder.should_record(program_id))
+            .map(|(recorder, _)| {
+                InstructionCapture::new(
+                    recorder,
+                    invoke_context.transaction_context,
+                    message,
+                    &instruction,
+                    *program_account_index,
+                    invoke_context
+                        .get_compute_budget()
+                        .compute_unit_limit
+                        .saturating_sub(*accumulated_consumed_units),
+                )
+            });
+
         let mut compute_units_consumed = 0;
         let (result, process_instruction_us) = measure_us!({
             if invoke_context.is_precompile(program_id) {
@@ -70,6 +91,16 @@ pub(crate) fn process_message<'ix_data>(
             .process_instructions
             .total_us += process_instruction_us;
 
+        if let (Some((recorder, signature)), Some(capture)) = (instruction_recorder, capture) {
+            recorder.record(capture.finish(
+                invoke_context.transaction_context,
+                *signature,
+                top_level_instruction_index,
+                &result,
+                compute_units_consumed,
+            ));
+        }
+
         result.map_err(|err| {
             TransactionError::InstructionError(top_level_instruction_index as u8, err)
         })?;
@@ -81,6 +112,7 @@ pub(crate) fn process_message<'ix_data>(
 mod tests {
     use {
         super::*,
+        crate::instruction_recorder::RecordedInstruction,
         ed25519_dalek::ed25519::signature::Signer,
         openssl::{
             ec::{EcGroup, EcKey},
@@ -115,7 +147,10 @@ mod tests {
         solana_svm_callback::InvokeContextCallback,
         solana_svm_feature_set::SVMFeatureSet,
         solana_transaction_context::transaction::TransactionContext,
-        std::{collections::HashSet, sync::Arc},
+        std::{
+            collections::HashSet,
+            sync::{Arc, Mutex},
+        },
     };
 
     struct MockCallback {}
@@ -250,6 +285,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert!(result.is_ok());
         assert_eq!(
@@ -307,6 +343,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert_eq!(
             result,
@@ -354,6 +391,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert_eq!(
             result,
@@ -364,6 +402,174 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_process_message_instruction_recorder() {
+        struct MockRecorder {
+            program_id: Pubkey,
+            recorded: Mutex<Vec<RecordedInstruction>>,
+        }
+
+        impl InstructionRecorder for MockRecorder {
+            fn should_record(&self, program_id: &Pubkey) -> bool {
+                *program_id == self.program_id
+            }
+
+            fn capture_extra_accounts(
+                &self,
+                accounts: &[(Pubkey, Account)],
+            ) -> Vec<(Pubkey, Account)> {
+                accounts.to_vec()
+            }
+
+            fn record(&self, instruction: RecordedInstruction) {
+                self.recorded.lock().unwrap().push(instruction);
+            }
+        }
+
+        declare_process_instruction!(MockBuiltin, 1, |invoke_context| {
+            let transaction_context = &invoke_context.transaction_context;
+            let instruction_context = transaction_context.get_current_instruction_context()?;
+            let lamports = u64::from(instruction_context.get_instruction_data()[0]);
+            instruction_context
+                .try_borrow_instruction_account(0)?
+                .checked_sub_lamports(lamports)?;
+            instruction_context
+                .try_borrow_instruction_account(1)?
+                .checked_add_lamports(lamports)?;
+            Ok(())
+        });
+
+        let from_pubkey = Pubkey::new_unique();
+        let to_pubkey = Pubkey::new_unique();
+        let recorded_program_id = Pubkey::new_unique();
+        let other_program_id = Pubkey::new_unique();
+
+        let accounts = vec![
+            (
+                from_pubkey,
+                AccountSharedData::new(100, 0, &recorded_program_id),
+            ),
+            (
+                to_pubkey,
+                AccountSharedData::new(0, 0, &recorded_program_id),
+            ),
+            (
+                recorded_program_id,
+                create_loadable_account_for_test("recorded_program"),
+            ),
+            (
+                other_program_id,
+                create_loadable_account_for_test("other_program"),
+            ),
+        ];
+        let mut transaction_context = TransactionContext::new(accounts, Rent::default(), 1, 3, 3);
+        let program_indices = vec![2, 3, 2];
+        let mut program_cache_for_tx_batch = ProgramCacheForTxBatch::default();
+        for program_id in [recorded_program_id, other_program_id] {
+            program_cache_for_tx_batch.replenish(
+                program_id,
+                Arc::new(ProgramCacheEntry::new_builtin(0, 0, MockBuiltin::vm)),
+            );
+        }
+        let account_keys = (0..transaction_context.get_number_of_accounts())
+            .map(|index| {
+                *transaction_context
+                    .get_key_of_account_at_index(index)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let account_metas = vec![
+            AccountMeta::new(from_pubkey, true),
+            AccountMeta::new(to_pubkey, false),
+        ];
+
+        let message = new_sanitized_message(Message::new_with_compiled_instructions(
+            1,
+            0,
+            2,
+            account_keys.clone(),
+            Hash::default(),
+            AccountKeys::new(&account_keys, None).compile_instructions(&[
+                Instruction::new_with_bytes(recorded_program_id, &[10], account_metas.clone()),
+                Instruction::new_with_bytes(other_program_id, &[20], account_metas.clone()),
+                Instruction::new_with_bytes(recorded_program_id, &[30], account_metas.clone()),
+            ]),
+        ));
+        let sysvar_cache = SysvarCache::default();
+        let feature_set = SVMFeatureSet::all_enabled();
+        let program_runtime_environments = ProgramRuntimeEnvironments::default();
+        let environment_config = EnvironmentConfig::new(
+            Hash::default(),
+            0,
+            &MockCallback {},
+            &feature_set,
+            &program_runtime_environments,
+            &program_runtime_environments,
+            &sysvar_cache,
+        );
+        let mut invoke_context = InvokeContext::new(
+            &mut transaction_context,
+            &mut program_cache_for_tx_batch,
+            environment_config,
+            None,
+            SVMTransactionExecutionBudget::default(),
+            SVMTransactionExecutionCost::default(),
+        );
+        let recorder = MockRecorder {
+            program_id: recorded_program_id,
+            recorded: Mutex::default(),
+        };
+        let signature = Signature::from([1; 64]);
+        let result = process_message(
+            &message,
+            &program_indices,
+            &mut invoke_context,
+            &mut ExecuteTimings::default(),
+            &mut 0,
+            Some((&recorder as &dyn InstructionRecorder, &signature)),
+        );
+        assert!(result.is_ok());
+
+        let recorded = recorder.recorded.into_inner().unwrap();
+        assert_eq!(recorded.len(), 2);
+        let lamports_of = |accounts: &[(Pubkey, Account)], pubkey: &Pubkey| {
+            accounts
+                .iter()
+                .find(|(key, _)| key == pubkey)
+                .map(|(_, account)| account.lamports)
+                .unwrap()
+        };
+        for (instruction, instruction_index, data, from_lamports) in
+            [(&recorded[0], 0, 10, 100), (&recorded[1], 2, 30, 70)]
+        {
+            assert_eq!(instruction.signature, signature);
+            assert_eq!(instruction.instruction_index, instruction_index);
+            assert_eq!(instruction.program_id, recorded_program_id);
+            assert_eq!(instruction.instruction_accounts, account_metas);
+            assert_eq!(instruction.data, vec![data]);
+            assert_eq!(instruction.result, Ok(()));
+            assert_eq!(instruction.accounts.len(), 3);
+            // Captured before the instruction was executed
+            assert_eq!(instruction.extra_accounts, instruction.accounts);
+            assert_eq!(
+                lamports_of(&instruction.accounts, &from_pubkey),
+                from_lamports
+            );
+            assert_eq!(
+                lamports_of(&instruction.resulting_accounts, &from_pubkey),
+                from_lamports - u64::from(data)
+            );
+            assert_eq!(
+                instruction.compute_units_available - instruction.compute_units_remaining,
+                1
+            );
+        }
+        assert_eq!(
+            recorded[0].compute_units_available - recorded[1].compute_units_available,
+            2
+        );
+    }
+
     #[test]
     fn test_process_message_duplicate_accounts() {
         #[derive(serde::Serialize, serde::Deserialize)]
@@ -490,6 +696,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert_eq!(
             result,
@@ -532,6 +739,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert!(result.is_ok());
 
@@ -571,6 +779,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
         assert!(result.is_ok());
         assert_eq!(
@@ -722,6 +931,7 @@ mod tests {
             &mut invoke_context,
             &mut ExecuteTimings::default(),
             &mut 0,
+            None,
         );
 
         assert_eq!(
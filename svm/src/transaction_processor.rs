@@ -6,6 +6,7 @@ use {
             update_rent_exempt_status_for_account, validate_fee_payer,
         },
         account_overrides::AccountOverrides,
+        instruction_recorder::InstructionRecorder,
         message_processor::process_message,
         nonce_info::NonceInfo,
         program_loader::{get_program_deployment_slot, load_program_with_pubkey},
@@ -36,6 +37,7 @@ use {
         execution_budget::{
             SVMTransactionExecutionAndFeeBudgetLimits, SVMTransactionExecutionCost,
         },
+        execution_profiler::ExecutionProfiler,
         invoke_context::{EnvironmentConfig, InvokeContext},
         loaded_programs::{
             EpochBoundaryPreparation, ForkGraph, ProgramCache, ProgramCacheEntry,
@@ -137,6 +139,11 @@ pub struct TransactionProcessingConfig<'a> {
     /// failing transactions to be committed. If both flags are set then any
     /// failing transaction will cause all transactions to be aborted.
     pub all_or_nothing: bool,
+    /// Receives the top-level instructions it selects, along with their
+    /// input accounts and effects, as they are executed.
+    pub instruction_recorder: Option<&'a dyn InstructionRecorder>,
+    /// Receives measurements of every instruction and syscall executed.
+    pub execution_profiler: Option<&'a dyn ExecutionProfiler>,
 }
 
 /// Runtime environment for transaction batch processing.
@@ -990,6 +997,9 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             compute_budget,
             self.execution_cost,
         );
+        if let Some(execution_profiler) = config.execution_profiler {
+            invoke_context.set_execution_profiler(execution_profiler);
+        }
 
         let mut process_message_time = Measure::start("process_message_time");
         let process_result = process_message(
@@ -998,6 +1008,9 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             &mut invoke_context,
             execute_timings,
             &mut executed_units,
+            config
+                .instruction_recorder
+                .map(|recorder| (recorder, tx.signature())),
         );
         process_message_time.stop();
 
@@ -1155,6 +1168,18 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         sysvar_cache.reset();
     }
 
+    /// Reloads all sysvars from `callbacks`, for sysvar accounts modified
+    /// outside of transactions.
+    pub fn refresh_sysvar_cache<CB: TransactionProcessingCallback>(&self, callbacks: &CB) {
+        let mut sysvar_cache = self.sysvar_cache.write().unwrap();
+        sysvar_cache.reset();
+        sysvar_cache.fill_missing_entries(|pubkey, set_sysvar| {
+            if let Some((account, _slot)) = callbacks.get_account_shared_data(pubkey) {
+                set_sysvar(account.data());
+            }
+        });
+    }
+
     pub fn get_sysvar_cache_for_tests(&self) -> SysvarCache {
         self.sysvar_cache.read().unwrap().clone()
     }
@@ -34,7 +34,7 @@ use {
         declare_builtin_function,
         memory_region::{AccessType, MemoryMapping},
         program::{BuiltinProgram, SBPFVersion},
-        vm::Config,
+        vm::{get_runtime_environment_key, Config, EbpfVm},
     },
     solana_secp256k1_recover::{
         Secp256k1RecoverError, SECP256K1_PUBLIC_KEY_LENGTH, SECP256K1_SIGNATURE_LENGTH,
@@ -277,15 +277,88 @@ fn consume_compute_meter(invoke_context: &InvokeContext, amount: u64) -> Result<
     Ok(())
 }
 
-// NOTE: This macro name is checked by gen-syscall-list to create the list of
-// syscalls. If this macro name is changed, or if a new one is added, then
-// gen-syscall-list/build.rs must also be updated.
-macro_rules! register_feature_gated_function {
-    ($result:expr, $is_feature_active:expr, $name:expr, $call:expr $(,)?) => {
-        if $is_feature_active {
-            $result.register_function($name, $call)
+/// Returns the invoke context of the VM a syscall was invoked from
+///
+/// # Safety
+/// `vm` must be the pointer handed to the syscall, and the returned reference
+/// must not be held while the syscall itself runs.
+unsafe fn syscall_invoke_context<'r, 'a, 'ix_data>(
+    vm: *mut EbpfVm<InvokeContext<'a, 'ix_data>>,
+) -> &'r InvokeContext<'a, 'ix_data> {
+    let vm = unsafe {
+        &*((vm as *mut u64).offset((get_runtime_environment_key() as isize).wrapping_neg())
+            as *mut EbpfVm<InvokeContext<'a, 'ix_data>>)
+    };
+    &*vm.context_object_pointer
+}
+
+/// Runs `syscall`, reporting it to the execution profiler if there is one
+///
+/// Only syscalls of environments with debugging features are wrapped in this.
+fn invoke_profiled<'a, 'ix_data>(
+    name: &'static str,
+    vm: *mut EbpfVm<InvokeContext<'a, 'ix_data>>,
+    syscall: impl FnOnce(),
+) {
+    let invoke_context = unsafe { syscall_invoke_context(vm) };
+    let Some(execution_profiler) = invoke_context.get_execution_profiler() else {
+        return syscall();
+    };
+    let program_id = invoke_context
+        .transaction_context
+        .get_current_instruction_context()
+        .and_then(|instruction_context| instruction_context.get_program_key().copied());
+    let compute_meter_prev = invoke_context.get_remaining();
+    syscall();
+    let invoke_context = unsafe { syscall_invoke_context(vm) };
+    let compute_units = compute_meter_prev.saturating_sub(invoke_context.get_remaining());
+    if let Ok(program_id) = program_id {
+        execution_profiler.syscall_invoked(&program_id, name, compute_units);
+    }
+}
+
+/// Wraps the syscall `$call` so that its invocations are reported to the
+/// execution profiler as `$name`
+macro_rules! profiled_syscall {
+    ($name:expr, $call:expr) => {{
+        fn profiled<'c, 'ix>(
+            vm: *mut EbpfVm<InvokeContext<'c, 'ix>>,
+            arg_a: u64,
+            arg_b: u64,
+            arg_c: u64,
+            arg_d: u64,
+            arg_e: u64,
+        ) {
+            invoke_profiled($name, vm, || $call(vm, arg_a, arg_b, arg_c, arg_d, arg_e));
+        }
+        profiled
+    }};
+}
+
+// NOTE: These macro names are checked by gen-syscall-list to create the list
+// of syscalls. If these macro names are changed, or if a new one is added,
+// then gen-syscall-list/build.rs must also be updated.
+//
+// Syscalls are only wrapped for the execution profiler with `$profiled`, so
+// that production environments call them directly.
+macro_rules! register_syscall {
+    ($result:expr, $profiled:expr, $name:expr, $call:expr $(,)?) => {
+        if $profiled {
+            $result.register_function($name, profiled_syscall!($name, $call))
         } else {
+            $result.register_function($name, $call)
+        }
+    };
+}
+
+macro_rules! register_feature_gated_function {
+    ($result:expr, $profiled:expr, $is_feature_active:expr, $name:expr, $call:expr $(,)?) => {
+        if !$is_feature_active {
             Ok(())
+        } else if $profiled {
+            $result.register_function($name, profiled_syscall!($name, $call))
+        } else {
+            $result.register_function($name, $call)
         }
     };
 }
@@ -347,45 +420,75 @@ pub fn create_program_runtime_environment_v1<'a, 'ix_data>(
         // Warning, do not use `Config::default()` so that configuration here is explicit.
     };
 
-    // NOTE: `register_function` calls are checked by gen-syscall-list to create
-    // the list of syscalls. If this function name is changed, or if a new one
-    // is added, then gen-syscall-list/build.rs must also be updated.
+    // NOTE: `register_syscall` calls are checked by gen-syscall-list to create
+    // the list of syscalls. If this macro name is changed, or if a new one is
+    // added, then gen-syscall-list/build.rs must also be updated.
     let mut result = BuiltinProgram::new_loader(config);
 
     // Abort
-    result.register_function("abort", SyscallAbort::vm)?;
+    register_syscall!(result, debugging_features, "abort", SyscallAbort::vm)?;
 
     // Panic
-    result.register_function("sol_panic_", SyscallPanic::vm)?;
+    register_syscall!(result, debugging_features, "sol_panic_", SyscallPanic::vm)?;
 
     // Logging
-    result.register_function("sol_log_", SyscallLog::vm)?;
-    result.register_function("sol_log_64_", SyscallLogU64::vm)?;
-    result.register_function("sol_log_pubkey", SyscallLogPubkey::vm)?;
-    result.register_function("sol_log_compute_units_", SyscallLogBpfComputeUnits::vm)?;
+    register_syscall!(result, debugging_features, "sol_log_", SyscallLog::vm)?;
+    register_syscall!(result, debugging_features, "sol_log_64_", SyscallLogU64::vm)?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_log_pubkey",
+        SyscallLogPubkey::vm
+    )?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_log_compute_units_",
+        SyscallLogBpfComputeUnits::vm
+    )?;
 
     // Program defined addresses (PDA)
-    result.register_function(
+    register_syscall!(
+        result,
+        debugging_features,
         "sol_create_program_address",
         SyscallCreateProgramAddress::vm,
     )?;
-    result.register_function(
+    register_syscall!(
+        result,
+        debugging_features,
         "sol_try_find_program_address",
         SyscallTryFindProgramAddress::vm,
     )?;
 
     // Sha256
-    result.register_function("sol_sha256", SyscallHash::vm::<Sha256Hasher>)?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_sha256",
+        SyscallHash::vm::<Sha256Hasher>
+    )?;
 
     // Keccak256
-    result.register_function("sol_keccak256", SyscallHash::vm::<Keccak256Hasher>)?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_keccak256",
+        SyscallHash::vm::<Keccak256Hasher>
+    )?;
 
     // Secp256k1 Recover
-    result.register_function("sol_secp256k1_recover", SyscallSecp256k1Recover::vm)?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_secp256k1_recover",
+        SyscallSecp256k1Recover::vm
+    )?;
 
     // Blake3
     register_feature_gated_function!(
         result,
+        debugging_features,
         blake3_syscall_enabled,
         "sol_blake3",
         SyscallHash::vm::<Blake3Hasher>,
@@ -394,87 +497,141 @@ pub fn create_program_runtime_environment_v1<'a, 'ix_data>(
     // Elliptic Curve Operations
     register_feature_gated_function!(
         result,
+        debugging_features,
         curve25519_syscall_enabled,
         "sol_curve_validate_point",
         SyscallCurvePointValidation::vm,
     )?;
     register_feature_gated_function!(
         result,
+        debugging_features,
         curve25519_syscall_enabled,
         "sol_curve_group_op",
         SyscallCurveGroupOps::vm,
     )?;
     register_feature_gated_function!(
         result,
+        debugging_features,
         curve25519_syscall_enabled,
         "sol_curve_multiscalar_mul",
         SyscallCurveMultiscalarMultiplication::vm,
     )?;
     register_feature_gated_function!(
         result,
+        debugging_features,
         enable_bls12_381_syscall,
         "sol_curve_decompress",
         SyscallCurveDecompress::vm,
     )?;
     register_feature_gated_function!(
         result,
+        debugging_features,
         enable_bls12_381_syscall,
         "sol_curve_pairing_map",
         SyscallCurvePairingMap::vm,
     )?;
 
     // Sysvars
-    result.register_function("sol_get_clock_sysvar", SyscallGetClockSysvar::vm)?;
-    result.register_function(
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_get_clock_sysvar",
+        SyscallGetClockSysvar::vm
+    )?;
+    register_syscall!(
+        result,
+        debugging_features,
         "sol_get_epoch_schedule_sysvar",
         SyscallGetEpochScheduleSysvar::vm,
     )?;
     register_feature_gated_function!(
         result,
+        debugging_features,
         !disable_fees_sysvar,
         "sol_get_fees_sysvar",
         SyscallGetFeesSysvar::vm,
     )?;
-    result.register_function("sol_get_rent_sysvar", SyscallGetRentSysvar::vm)?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_get_rent_sysvar",
+        SyscallGetRentSysvar::vm
+    )?;
 
     register_feature_gated_function!(
         result,
+        debugging_features,
         last_restart_slot_syscall_enabled,
         "sol_get_last_restart_slot",
         SyscallGetLastRestartSlotSysvar::vm,
     )?;
 
-    result.register_function(
+    register_syscall!(
+        result,
+        debugging_features,
         "sol_get_epoch_rewards_sysvar",
         SyscallGetEpochRewardsSysvar::vm,
     )?;
 
     // Memory ops
-    result.register_function("sol_memcpy_", SyscallMemcpy::vm)?;
-    result.register_function("sol_memmove_", SyscallMemmove::vm)?;
-    result.register_function("sol_memset_", SyscallMemset::vm)?;
-    result.register_function("sol_memcmp_", SyscallMemcmp::vm)?;
+    register_syscall!(result, debugging_features, "sol_memcpy_", SyscallMemcpy::vm)?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_memmove_",
+        SyscallMemmove::vm
+    )?;
+    register_syscall!(result, debugging_features, "sol_memset_", SyscallMemset::vm)?;
+    register_syscall!(result, debugging_features, "sol_memcmp_", SyscallMemcmp::vm)?;
 
     // Processed sibling instructions
-    result.register_function(
+    register_syscall!(
+        result,
+        debugging_features,
         "sol_get_processed_sibling_instruction",
         SyscallGetProcessedSiblingInstruction::vm,
     )?;
 
     // Stack height
-    result.register_function("sol_get_stack_height", SyscallGetStackHeight::vm)?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_get_stack_height",
+        SyscallGetStackHeight::vm
+    )?;
 
     // Return data
-    result.register_function("sol_set_return_data", SyscallSetReturnData::vm)?;
-    result.register_function("sol_get_return_data", SyscallGetReturnData::vm)?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_set_return_data",
+        SyscallSetReturnData::vm
+    )?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_get_return_data",
+        SyscallGetReturnData::vm
+    )?;
 
     // Cross-program invocation
-    result.register_function("sol_invoke_signed_c", SyscallInvokeSignedC::vm)?;
-    result.register_function("sol_invoke_signed_rust", SyscallInvokeSignedRust::vm)?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_invoke_signed_c",
+        SyscallInvokeSignedC::vm
+    )?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_invoke_signed_rust",
+        SyscallInvokeSignedRust::vm
+    )?;
 
     // Memory allocator
     register_feature_gated_function!(
         result,
+        debugging_features,
         !disable_deploy_of_alloc_free_syscall,
         "sol_alloc_free_",
         SyscallAllocFree::vm,
@@ -483,6 +640,7 @@ pub fn create_program_runtime_environment_v1<'a, 'ix_data>(
     // Alt_bn128
     register_feature_gated_function!(
         result,
+        debugging_features,
         enable_alt_bn128_syscall,
         "sol_alt_bn128_group_op",
         SyscallAltBn128::vm,
@@ -491,6 +649,7 @@ pub fn create_program_runtime_environment_v1<'a, 'ix_data>(
     // Big_mod_exp
     register_feature_gated_function!(
         result,
+        debugging_features,
         enable_big_mod_exp_syscall,
         "sol_big_mod_exp",
         SyscallBigModExp::vm,
@@ -499,6 +658,7 @@ pub fn create_program_runtime_environment_v1<'a, 'ix_data>(
     // Poseidon
     register_feature_gated_function!(
         result,
+        debugging_features,
         enable_poseidon_syscall,
         "sol_poseidon",
         SyscallPoseidon::vm,
@@ -507,6 +667,7 @@ pub fn create_program_runtime_environment_v1<'a, 'ix_data>(
     // Accessing remaining compute units
     register_feature_gated_function!(
         result,
+        debugging_features,
         remaining_compute_units_syscall_enabled,
         "sol_remaining_compute_units",
         SyscallRemainingComputeUnits::vm
@@ -515,6 +676,7 @@ pub fn create_program_runtime_environment_v1<'a, 'ix_data>(
     // Alt_bn128_compression
     register_feature_gated_function!(
         result,
+        debugging_features,
         enable_alt_bn128_compression_syscall,
         "sol_alt_bn128_compression",
         SyscallAltBn128Compression::vm,
@@ -523,6 +685,7 @@ pub fn create_program_runtime_environment_v1<'a, 'ix_data>(
     // Sysvar getter
     register_feature_gated_function!(
         result,
+        debugging_features,
         get_sysvar_syscall_enabled,
         "sol_get_sysvar",
         SyscallGetSysvar::vm,
@@ -531,13 +694,19 @@ pub fn create_program_runtime_environment_v1<'a, 'ix_data>(
     // Get Epoch Stake
     register_feature_gated_function!(
         result,
+        debugging_features,
         enable_get_epoch_stake_syscall,
         "sol_get_epoch_stake",
         SyscallGetEpochStake::vm,
     )?;
 
     // Log data
-    result.register_function("sol_log_data", SyscallLogData::vm)?;
+    register_syscall!(
+        result,
+        debugging_features,
+        "sol_log_data",
+        SyscallLogData::vm
+    )?;
 
     Ok(result)
 }
@@ -0,0 +1,390 @@
+//! Lazy forking of another cluster: the accounts missing from the test
+//! validator are fetched from the cluster the first time they are accessed,
+//! by transactions or over RPC, then kept.
+
+use {
+    crate::try_transform_program_data,
+    log::*,
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_cli_output::CliAccount,
+    solana_clock::Slot,
+    solana_commitment_config::CommitmentConfig,
+    solana_loader_v3_interface::state::UpgradeableLoaderState,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::{
+        config::{RpcAccountInfoConfig, UiAccountEncoding},
+        request::MAX_MULTIPLE_ACCOUNTS,
+    },
+    solana_runtime::bank::account_fallback::BankAccountFallback,
+    solana_sdk_ids::bpf_loader_upgradeable,
+    std::{
+        collections::HashMap,
+        ffi::OsStr,
+        fs,
+        path::Path,
+        str::FromStr,
+        sync::{Arc, RwLock},
+    },
+};
+
+/// Where a forking test validator fetches its missing accounts from.
+pub trait ForkAccountSource: Send + Sync {
+    /// Returns the accounts at `addresses`, `None` for those that don't
+    /// exist.
+    fn get_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<AccountSharedData>>, String>;
+}
+
+/// Fetches accounts over JSON RPC, from a cluster or from a local stand-in.
+///
+/// The state of an account is that of the cluster when it is first fetched,
+/// which RPC nodes don't serve from before the fork slot.
+pub struct RpcForkSource {
+    rpc_client: RpcClient,
+    fork_slot: Slot,
+}
+
+impl RpcForkSource {
+    /// Forks the cluster at its latest confirmed slot.
+    pub fn new(rpc_client: RpcClient) -> Result<Self, String> {
+        let fork_slot = rpc_client
+            .get_slot_with_commitment(CommitmentConfig::confirmed())
+            .map_err(|err| format!("Failed to get the slot to fork at: {err}"))?;
+        Ok(Self::new_at_slot(rpc_client, fork_slot))
+    }
+
+    pub fn new_at_slot(rpc_client: RpcClient, fork_slot: Slot) -> Self {
+        Self {
+            rpc_client,
+            fork_slot,
+        }
+    }
+
+    pub fn fork_slot(&self) -> Slot {
+        self.fork_slot
+    }
+}
+
+impl ForkAccountSource for RpcForkSource {
+    fn get_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<AccountSharedData>>, String> {
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for addresses in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            accounts.extend(
+                self.rpc_client
+                    .get_multiple_ui_accounts_with_config(
+                        addresses,
+                        RpcAccountInfoConfig {
+                            encoding: Some(UiAccountEncoding::Base64),
+                            commitment: Some(CommitmentConfig::confirmed()),
+                            min_context_slot: Some(self.fork_slot),
+                            ..RpcAccountInfoConfig::default()
+                        },
+                    )
+                    .map_err(|err| format!("Failed to fetch: {err}"))?
+                    .value,
+            );
+        }
+        addresses
+            .iter()
+            .zip(accounts)
+            .map(|(address, account)| {
+                account
+                    .map(|account| {
+                        account
+                            .to_account_shared_data()
+                            .ok_or_else(|| format!("Failed to decode {address}"))
+                    })
+                    .transpose()
+            })
+            .collect()
+    }
+}
+
+/// Serves the accounts of a directory of JSON files, in the format of
+/// `solana account --output json` and `--account-dir`, to fork a cluster
+/// offline. Accounts without a file don't exist.
+pub struct DirectoryForkSource {
+    accounts: HashMap<Pubkey, AccountSharedData>,
+}
+
+impl DirectoryForkSource {
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, String> {
+        let dir = dir.as_ref();
+        let entries = fs::read_dir(dir)
+            .map_err(|err| format!("Cannot read directory {}: {err}", dir.display()))?;
+        let mut accounts = HashMap::new();
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if !path.is_file() || path.extension() != Some(OsStr::new("json")) {
+                continue;
+            }
+            let account = fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|json| {
+                    serde_json::from_str::<CliAccount>(&json).map_err(|err| err.to_string())
+                })
+                .map_err(|err| format!("Unable to read {}: {err}", path.display()))?;
+            let address = Pubkey::from_str(&account.keyed_account.pubkey)
+                .map_err(|err| format!("Invalid address in {}: {err}", path.display()))?;
+            let account = account
+                .keyed_account
+                .account
+                .to_account_shared_data()
+                .ok_or_else(|| format!("Unable to decode {}", path.display()))?;
+            accounts.insert(address, account);
+        }
+        Ok(Self { accounts })
+    }
+}
+
+impl ForkAccountSource for DirectoryForkSource {
+    fn get_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<AccountSharedData>>, String> {
+        Ok(addresses
+            .iter()
+            .map(|address| self.accounts.get(address).cloned())
+            .collect())
+    }
+}
+
+/// Supplies the accounts missing from the banks of a test validator from a
+/// [`ForkAccountSource`].
+///
+/// Every account is fetched once: later accesses get the same state, even if
+/// it changed in the source since. The accounts of a batch of transactions
+/// are prefetched together, along with the program data of the upgradeable
+/// programs among them, so that they are fetched at once rather than while
+/// the transactions execute. Feature accounts are never fetched, as the
+/// features of the test validator are set at genesis, and the deployment slot
+/// of upgradeable programs is reset to zero so that they can be invoked
+/// right away.
+pub struct ForkAccountFallback {
+    source: Arc<dyn ForkAccountSource>,
+    fetched_accounts: RwLock<HashMap<Pubkey, Option<AccountSharedData>>>,
+}
+
+impl ForkAccountFallback {
+    pub fn new(source: Arc<dyn ForkAccountSource>) -> Self {
+        Self {
+            source,
+            fetched_accounts: RwLock::default(),
+        }
+    }
+
+    /// Fetches the accounts at `pubkeys` that weren't fetched yet.
+    fn fetch_accounts(&self, pubkeys: &[Pubkey]) -> Result<(), String> {
+        let pubkeys: Vec<_> = {
+            let fetched_accounts = self.fetched_accounts.read().unwrap();
+            pubkeys
+                .iter()
+                .filter(|pubkey| !fetched_accounts.contains_key(pubkey))
+                .copied()
+                .collect()
+        };
+        if pubkeys.is_empty() {
+            return Ok(());
+        }
+        let accounts = self.source.get_accounts(&pubkeys)?;
+        let mut fetched_accounts = self.fetched_accounts.write().unwrap();
+        for (pubkey, account) in pubkeys.into_iter().zip(accounts) {
+            let account = account
+                .filter(|account| account.owner() != &solana_sdk_ids::feature::id())
+                .map(|mut account| {
+                    // Not all accounts of the upgradeable loader are program data
+                    try_transform_program_data(&pubkey, &mut account).ok();
+                    account
+                });
+            debug!(
+                "Fetched forked account {pubkey}: {}",
+                if account.is_some() {
+                    "found"
+                } else {
+                    "missing"
+                }
+            );
+            fetched_accounts.entry(pubkey).or_insert(account);
+        }
+        Ok(())
+    }
+}
+
+impl BankAccountFallback for ForkAccountFallback {
+    fn load_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        if let Err(err) = self.fetch_accounts(std::slice::from_ref(pubkey)) {
+            // Not remembered, to try again on the next access
+            warn!("Failed to fetch forked account {pubkey}: {err}");
+            return None;
+        }
+        self.fetched_accounts
+            .read()
+            .unwrap()
+            .get(pubkey)
+            .cloned()
+            .flatten()
+    }
+
+    fn prefetch_accounts(&self, pubkeys: &[Pubkey]) {
+        if let Err(err) = self.fetch_accounts(pubkeys) {
+            warn!("Failed to prefetch forked accounts: {err}");
+            return;
+        }
+        let programdata_addresses: Vec<_> = {
+            let fetched_accounts = self.fetched_accounts.read().unwrap();
+            pubkeys
+                .iter()
+                .filter_map(|pubkey| {
+                    let account = fetched_accounts.get(pubkey)?.as_ref()?;
+                    if account.owner() != &bpf_loader_upgradeable::id() {
+                        return None;
+                    }
+                    match account.deserialize_data() {
+                        Ok(UpgradeableLoaderState::Program {
+                            programdata_address,
+                        }) => Some(programdata_address),
+                        _ => None,
+                    }
+                })
+                .collect()
+        };
+        if let Err(err) = self.fetch_accounts(&programdata_addresses) {
+            warn!("Failed to prefetch forked program data accounts: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_account::WritableAccount,
+        solana_feature_gate_interface::Feature,
+        std::sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    struct CountingSource {
+        source: DirectoryForkSource,
+        fetches: AtomicUsize,
+    }
+
+    impl ForkAccountSource for CountingSource {
+        fn get_accounts(
+            &self,
+            addresses: &[Pubkey],
+        ) -> Result<Vec<Option<AccountSharedData>>, String> {
+            self.fetches.fetch_add(1, Ordering::Relaxed);
+            self.source.get_accounts(addresses)
+        }
+    }
+
+    fn write_account(dir: &Path, address: &Pubkey, account: &AccountSharedData) {
+        let account = CliAccount::new(address, account, true);
+        fs::write(
+            dir.join(format!("{address}.json")),
+            serde_json::to_string(&account).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_fork_account_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let wallet = Pubkey::new_unique();
+        let wallet_account = AccountSharedData::new(42, 0, &Pubkey::new_unique());
+        write_account(dir.path(), &wallet, &wallet_account);
+        let feature = Pubkey::new_unique();
+        write_account(
+            dir.path(),
+            &feature,
+            &solana_feature_gate_interface::create_account(&Feature::default(), 1),
+        );
+        let programdata = Pubkey::new_unique();
+        let mut programdata_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: 1234,
+            upgrade_authority_address: None,
+        })
+        .unwrap();
+        programdata_data.extend_from_slice(b"ELF");
+        let mut programdata_account = AccountSharedData::new(
+            1,
+            programdata_data.len(),
+            &solana_sdk_ids::bpf_loader_upgradeable::id(),
+        );
+        programdata_account.set_data_from_slice(&programdata_data);
+        write_account(dir.path(), &programdata, &programdata_account);
+
+        let source = Arc::new(CountingSource {
+            source: DirectoryForkSource::new(dir.path()).unwrap(),
+            fetches: AtomicUsize::default(),
+        });
+        let fallback = ForkAccountFallback::new(source.clone());
+
+        assert_eq!(fallback.load_account(&wallet), Some(wallet_account.clone()));
+        assert_eq!(fallback.load_account(&wallet), Some(wallet_account));
+        assert_eq!(source.fetches.load(Ordering::Relaxed), 1);
+
+        let missing = Pubkey::new_unique();
+        assert_eq!(fallback.load_account(&missing), None);
+        assert_eq!(fallback.load_account(&missing), None);
+        assert_eq!(source.fetches.load(Ordering::Relaxed), 2);
+
+        assert_eq!(fallback.load_account(&feature), None);
+
+        let programdata_account = fallback.load_account(&programdata).unwrap();
+        assert_eq!(
+            bincode::deserialize::<UpgradeableLoaderState>(programdata_account.data()).unwrap(),
+            UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_fork_account_fallback_prefetch() {
+        let dir = tempfile::tempdir().unwrap();
+        let wallet = Pubkey::new_unique();
+        let wallet_account = AccountSharedData::new(42, 0, &Pubkey::new_unique());
+        write_account(dir.path(), &wallet, &wallet_account);
+        let program = Pubkey::new_unique();
+        let programdata = Pubkey::new_unique();
+        let program_account = AccountSharedData::new_data(
+            1,
+            &UpgradeableLoaderState::Program {
+                programdata_address: programdata,
+            },
+            &bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        write_account(dir.path(), &program, &program_account);
+        let programdata_account = AccountSharedData::new_data(
+            1,
+            &UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: None,
+            },
+            &bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        write_account(dir.path(), &programdata, &programdata_account);
+
+        let source = Arc::new(CountingSource {
+            source: DirectoryForkSource::new(dir.path()).unwrap(),
+            fetches: AtomicUsize::default(),
+        });
+        let fallback = ForkAccountFallback::new(source.clone());
+
+        // The accounts and the program data of the program are fetched once
+        let missing = Pubkey::new_unique();
+        fallback.prefetch_accounts(&[wallet, program, missing]);
+        assert_eq!(source.fetches.load(Ordering::Relaxed), 2);
+        fallback.prefetch_accounts(&[wallet, program, missing]);
+        assert_eq!(source.fetches.load(Ordering::Relaxed), 2);
+
+        assert_eq!(fallback.load_account(&wallet), Some(wallet_account));
+        assert_eq!(fallback.load_account(&program), Some(program_account));
+        assert_eq!(
+            fallback.load_account(&programdata),
+            Some(programdata_account)
+        );
+        assert_eq!(fallback.load_account(&missing), None);
+        assert_eq!(source.fetches.load(Ordering::Relaxed), 2);
+    }
+}
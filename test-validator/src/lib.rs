@@ -1,5 +1,9 @@
 #![allow(clippy::arithmetic_side_effects)]
+pub mod fork;
+pub mod scenario;
+
 use {
+    crate::fork::{ForkAccountFallback, ForkAccountSource},
     agave_feature_set::{
         alpenglow, increase_cpi_account_info_limit, raise_cpi_nesting_limit_to_8, FeatureSet,
         FEATURE_NAMES,
@@ -49,6 +53,7 @@ use {
     solana_net_utils::{
         find_available_ports_in_range, multihomed_sockets::BindIpAddrs, PortRange, SocketAddrSpace,
     },
+    solana_poh::automine::AutomineConfig,
     solana_program_runtime::{
         execution_budget::SVMTransactionExecutionBudget, invoke_context::InvokeContext,
     },
@@ -57,11 +62,12 @@ use {
     solana_rpc::{rpc::JsonRpcConfig, rpc_pubsub_service::PubSubConfig},
     solana_rpc_client::{nonblocking, rpc_client::RpcClient},
     solana_rpc_client_api::{
-        client_error::Error as RpcClientError, request::MAX_MULTIPLE_ACCOUNTS,
+        client_error::Error as RpcClientError,
+        request::{RpcRequest, MAX_MULTIPLE_ACCOUNTS},
     },
     solana_runtime::{
-        bank_forks::BankForks, genesis_utils::create_genesis_config_with_leader_ex,
-        runtime_config::RuntimeConfig,
+        bank::account_fallback::BankAccountFallback, bank_forks::BankForks,
+        genesis_utils::create_genesis_config_with_leader_ex, runtime_config::RuntimeConfig,
     },
     solana_sbpf::{elf::Executable, verifier::RequisiteVerifier},
     solana_sdk_ids::address_lookup_table,
@@ -85,6 +91,8 @@ use {
     tokio::time::sleep,
 };
 
+pub use scenario::Scenario;
+
 #[derive(Clone)]
 pub struct AccountInfo<'a> {
     pub address: Option<Pubkey>,
@@ -150,6 +158,8 @@ pub struct TestValidatorGenesis {
     pub transaction_account_lock_limit: Option<usize>,
     pub geyser_plugin_manager: Arc<RwLock<GeyserPluginManager>>,
     admin_rpc_service_post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
+    fork_source: Option<Arc<dyn ForkAccountSource>>,
+    automine: Option<AutomineConfig>,
 }
 
 impl Default for TestValidatorGenesis {
@@ -161,7 +171,10 @@ impl Default for TestValidatorGenesis {
             ledger_path: Option::<PathBuf>::default(),
             tower_storage: Option::<Arc<dyn TowerStorage>>::default(),
             rent: Rent::default(),
-            rpc_config: JsonRpcConfig::default_for_test(),
+            rpc_config: JsonRpcConfig {
+                enable_test_methods: true,
+                ..JsonRpcConfig::default_for_test()
+            },
             pubsub_config: PubSubConfig::default(),
             rpc_ports: Option::<(u16, u16)>::default(),
             warp_slot: Option::<Slot>::default(),
@@ -186,6 +199,8 @@ impl Default for TestValidatorGenesis {
             geyser_plugin_manager: Arc::new(RwLock::new(GeyserPluginManager::default())),
             admin_rpc_service_post_init:
                 Arc::<RwLock<Option<AdminRpcRequestMetadataPostInit>>>::default(),
+            fork_source: None,
+            automine: None,
         }
     }
 }
@@ -370,6 +385,23 @@ impl TestValidatorGenesis {
         Ok(self)
     }
 
+    /// Forks another cluster lazily: the accounts missing from the test
+    /// validator are fetched from `source` when they are first accessed,
+    /// instead of being cloned upfront.
+    pub fn fork(&mut self, source: Arc<dyn ForkAccountSource>) -> &mut Self {
+        self.fork_source = Some(source);
+        self
+    }
+
+    /// Mines blocks on demand instead of every slot time: a block is mined
+    /// and rooted as soon as transactions arrive, on a `mineBlock` RPC
+    /// request, or at the interval of `config` if any. Blocks are not voted
+    /// on, and the clock advances by the nominal slot time per block.
+    pub fn automine(&mut self, config: AutomineConfig) -> &mut Self {
+        self.automine = Some(config);
+        self
+    }
+
     pub fn clone_accounts<T>(
         &mut self,
         addresses: T,
@@ -804,6 +836,7 @@ pub struct TestValidator {
     gossip: SocketAddr,
     validator: Option<Validator>,
     vote_account_address: Pubkey,
+    automine: bool,
 }
 
 impl TestValidator {
@@ -1185,6 +1218,8 @@ impl TestValidator {
                 }),
             log_messages_bytes_limit: config.log_messages_bytes_limit,
             transaction_account_lock_limit: config.transaction_account_lock_limit,
+            feature_deactivations: config.rpc_config.enable_test_methods,
+            ..RuntimeConfig::default()
         };
 
         let mut validator_config = ValidatorConfig {
@@ -1225,6 +1260,10 @@ impl TestValidator {
             accounts_db_config,
             runtime_config,
             enable_scheduler_bindings: config.enable_scheduler_bindings,
+            account_fallback: config.fork_source.clone().map(|source| {
+                Arc::new(ForkAccountFallback::new(source)) as Arc<dyn BankAccountFallback>
+            }),
+            automine: config.automine.clone(),
             ..ValidatorConfig::default_for_test()
         };
         if let Some(ref tower_storage) = config.tower_storage {
@@ -1257,6 +1296,7 @@ impl TestValidator {
             gossip,
             validator,
             vote_account_address,
+            automine: config.automine.is_some(),
         };
         Ok(test_validator)
     }
@@ -1305,7 +1345,23 @@ impl TestValidator {
                     break;
                 }
             }
-            sleep(Duration::from_millis(DEFAULT_MS_PER_SLOT)).await;
+            if self.automine {
+                // Blocks are not produced on their own
+                if let Err(err) = rpc_client
+                    .send::<Slot>(
+                        RpcRequest::Custom {
+                            method: "mineBlock",
+                        },
+                        serde_json::Value::Null,
+                    )
+                    .await
+                {
+                    warn!("mineBlock failed: {err:?}");
+                    break;
+                }
+            } else {
+                sleep(Duration::from_millis(DEFAULT_MS_PER_SLOT)).await;
+            }
         }
     }
 
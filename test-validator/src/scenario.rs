@@ -0,0 +1,698 @@
+//! Declarative description of a test validator environment.
+//!
+//! A scenario file declares the programs, accounts, features and cluster
+//! settings of a test validator, so that the same local environment can be
+//! checked into a repository and shared between Rust tests, through
+//! [`TestValidatorGenesis::add_scenario`], and `solana-test-validator
+//! --scenario`. Scenarios are written in TOML or YAML, with the format picked
+//! from the file extension:
+//!
+//! ```toml
+//! version = 1
+//! # Cluster the cloned accounts are fetched from
+//! url = "https://api.mainnet-beta.solana.com"
+//! warp-slot = 1000
+//!
+//! [[programs]]
+//! address = "target/deploy/my_program-keypair.json"
+//! path = "target/deploy/my_program.so"
+//! upgrade-authority = "AUTHoRiTy1111111111111111111111111111111111"
+//!
+//! [[accounts]]
+//! address = "Config1111111111111111111111111111111111111"
+//! inline = { lamports = 1000000, owner = "MyProgram1111111111111111111111111111111111", data = "AQID" }
+//!
+//! [[accounts]]
+//! file = "fixtures/oracle.json"
+//!
+//! [[accounts]]
+//! address = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+//! clone = "account"
+//!
+//! [features]
+//! deactivate = ["EenyoWx9UMXYKpR8mW5Jmfmy2fRjzUtM7NduYMY8bx33"]
+//!
+//! [epoch-schedule]
+//! slots-per-epoch = 32
+//!
+//! [fees]
+//! lamports-per-signature = 5000
+//!
+//! [[funded-keypairs]]
+//! keypair = "keys/alice.json"
+//! lamports = 100000000000
+//!
+//! [[token-mints]]
+//! address = "keys/usdc-mint.json"
+//! decimals = 6
+//! mint-authority = "keys/alice.json"
+//! holders = [{ owner = "keys/alice.json", amount = 1000000000 }]
+//! ```
+//!
+//! Addresses are either base58 pubkeys or paths to keypair files, and paths
+//! are relative to the directory of the scenario file.
+
+use {
+    crate::{TestValidatorGenesis, UpgradeableProgramInfo},
+    base64::{prelude::BASE64_STANDARD, Engine},
+    log::*,
+    serde::Deserialize,
+    solana_account::{Account, AccountSharedData},
+    solana_clock::Slot,
+    solana_epoch_schedule::EpochSchedule,
+    solana_fee_calculator::FeeRateGovernor,
+    solana_keypair::{read_keypair_file, write_keypair_file, Keypair},
+    solana_program_option::COption,
+    solana_program_pack::Pack,
+    solana_pubkey::Pubkey,
+    solana_rent::Rent,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_sdk_ids::{bpf_loader_upgradeable, system_program},
+    solana_signer::Signer,
+    spl_associated_token_account_interface::address::get_associated_token_address_with_program_id,
+    spl_token_interface::state::{Account as TokenAccount, AccountState, Mint},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// A test validator environment, see the [module documentation](self).
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Scenario {
+    /// Version of the scenario format, [`Scenario::VERSION`].
+    pub version: u32,
+    /// URL of the cluster the cloned accounts and feature set are fetched
+    /// from.
+    pub url: Option<String>,
+    #[serde(default)]
+    pub programs: Vec<ScenarioProgram>,
+    #[serde(default)]
+    pub accounts: Vec<ScenarioAccount>,
+    #[serde(default)]
+    pub features: ScenarioFeatures,
+    pub epoch_schedule: Option<ScenarioEpochSchedule>,
+    pub fees: Option<ScenarioFees>,
+    pub rent: Option<ScenarioRent>,
+    pub ticks_per_slot: Option<u64>,
+    pub warp_slot: Option<Slot>,
+    #[serde(default)]
+    pub funded_keypairs: Vec<ScenarioFundedKeypair>,
+    #[serde(default)]
+    pub token_mints: Vec<ScenarioTokenMint>,
+    /// Directory the paths of the scenario are relative to.
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+/// An upgradeable SBF program.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ScenarioProgram {
+    /// Program id, as a pubkey or a keypair file.
+    pub address: String,
+    /// The program shared object.
+    pub path: PathBuf,
+    /// Upgrade authority, as a pubkey or a keypair file. The program is not
+    /// upgradeable without one.
+    pub upgrade_authority: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ScenarioAccount {
+    /// Address of the account, as a pubkey or a keypair file. Only optional
+    /// for accounts loaded from a file, which then provides the address.
+    pub address: Option<String>,
+    #[serde(flatten)]
+    pub source: ScenarioAccountSource,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScenarioAccountSource {
+    /// An account declared in the scenario.
+    Inline {
+        lamports: u64,
+        /// Owner of the account, the system program by default.
+        owner: Option<String>,
+        /// Base64 encoded data of the account.
+        #[serde(default)]
+        data: String,
+        #[serde(default)]
+        executable: bool,
+    },
+    /// A JSON file written by `solana account --output json`.
+    File(PathBuf),
+    /// An account cloned from the cluster at the scenario `url`.
+    Clone(ScenarioClone),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScenarioClone {
+    /// Clones the account, which must exist.
+    Account,
+    /// Clones the account if it exists.
+    MaybeAccount,
+    /// Clones an upgradeable program and its program data account.
+    UpgradeableProgram,
+    /// Clones an address lookup table and all the accounts it references.
+    AddressLookupTable,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ScenarioFeatures {
+    /// Features to deactivate at genesis.
+    #[serde(default)]
+    pub deactivate: Vec<String>,
+    /// Deactivates the features that are not active on the cluster at the
+    /// scenario `url`.
+    #[serde(default)]
+    pub clone_from_cluster: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ScenarioEpochSchedule {
+    pub slots_per_epoch: u64,
+    #[serde(default)]
+    pub warmup: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ScenarioFees {
+    pub lamports_per_signature: u64,
+}
+
+/// Rent parameters, each defaulting to the parameter of [`Rent::default`].
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ScenarioRent {
+    pub lamports_per_byte_year: Option<u64>,
+    pub exemption_threshold: Option<f64>,
+    pub burn_percent: Option<u8>,
+}
+
+/// A system account funded at genesis.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ScenarioFundedKeypair {
+    /// Keypair file of the account. If it doesn't exist, a new keypair is
+    /// generated and written to it, and its path is logged.
+    pub keypair: PathBuf,
+    pub lamports: u64,
+}
+
+/// An SPL token mint, with its holders' associated token accounts.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ScenarioTokenMint {
+    /// Address of the mint, as a pubkey or a keypair file.
+    pub address: String,
+    pub decimals: u8,
+    /// Mint authority, as a pubkey or a keypair file. The supply is fixed
+    /// without one.
+    pub mint_authority: Option<String>,
+    /// Freeze authority, as a pubkey or a keypair file.
+    pub freeze_authority: Option<String>,
+    /// The token program owning the mint, `token` by default.
+    #[serde(default)]
+    pub program: ScenarioTokenProgram,
+    #[serde(default)]
+    pub holders: Vec<ScenarioTokenHolder>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScenarioTokenProgram {
+    #[default]
+    Token,
+    Token2022,
+}
+
+impl ScenarioTokenProgram {
+    pub fn id(&self) -> Pubkey {
+        match self {
+            Self::Token => spl_token_interface::id(),
+            Self::Token2022 => spl_token_2022_interface::id(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ScenarioTokenHolder {
+    /// Owner of the associated token account, as a pubkey or a keypair file.
+    pub owner: String,
+    pub amount: u64,
+}
+
+impl Scenario {
+    /// The version of the scenario format supported.
+    pub const VERSION: u32 = 1;
+
+    /// Loads the scenario file at `path`, in TOML if it has the `toml`
+    /// extension and in YAML if it has the `yaml` or `yml` one.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("Unable to read {}: {err}", path.display()))?;
+        let mut scenario = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml_str(&contents),
+            Some("yaml" | "yml") => Self::from_yaml_str(&contents),
+            _ => Err("expected a .toml, .yaml or .yml file".to_string()),
+        }
+        .map_err(|err| format!("Unable to load scenario {}: {err}", path.display()))?;
+        scenario.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(scenario)
+    }
+
+    pub fn from_toml_str(contents: &str) -> Result<Self, String> {
+        toml::from_str::<Self>(contents)
+            .map_err(|err| err.to_string())
+            .and_then(Self::check_version)
+    }
+
+    pub fn from_yaml_str(contents: &str) -> Result<Self, String> {
+        serde_yaml::from_str::<Self>(contents)
+            .map_err(|err| err.to_string())
+            .and_then(Self::check_version)
+    }
+
+    fn check_version(self) -> Result<Self, String> {
+        if self.version == Self::VERSION {
+            Ok(self)
+        } else {
+            Err(format!(
+                "unsupported scenario version {}, expected {}",
+                self.version,
+                Self::VERSION
+            ))
+        }
+    }
+
+    fn path(&self, path: &Path) -> PathBuf {
+        self.base_dir.join(path)
+    }
+
+    /// Parses `address` as a pubkey, or else reads it from a keypair file.
+    fn address(&self, address: &str) -> Result<Pubkey, String> {
+        address.parse::<Pubkey>().or_else(|_| {
+            read_keypair_file(self.path(Path::new(address)))
+                .map(|keypair| keypair.pubkey())
+                .map_err(|err| format!("Invalid address {address}: {err}"))
+        })
+    }
+
+    fn rpc_client(&self, what: &str) -> Result<RpcClient, String> {
+        self.url
+            .as_ref()
+            .map(RpcClient::new)
+            .ok_or_else(|| format!("The scenario url is required to clone {what}"))
+    }
+}
+
+impl TestValidatorGenesis {
+    /// Adds the programs, accounts and settings of `scenario` to the test
+    /// environment, fetching the cloned accounts from the scenario `url`.
+    pub fn add_scenario(&mut self, scenario: &Scenario) -> Result<&mut Self, String> {
+        if let Some(epoch_schedule) = scenario.epoch_schedule {
+            self.epoch_schedule(EpochSchedule::custom(
+                epoch_schedule.slots_per_epoch,
+                epoch_schedule.slots_per_epoch,
+                epoch_schedule.warmup,
+            ));
+            self.rent = Rent::with_slots_per_epoch(epoch_schedule.slots_per_epoch);
+        }
+        if let Some(rent) = scenario.rent {
+            let default_rent = Rent::default();
+            self.rent(Rent {
+                lamports_per_byte_year: rent
+                    .lamports_per_byte_year
+                    .unwrap_or(default_rent.lamports_per_byte_year),
+                exemption_threshold: rent
+                    .exemption_threshold
+                    .unwrap_or(default_rent.exemption_threshold),
+                burn_percent: rent.burn_percent.unwrap_or(default_rent.burn_percent),
+            });
+        }
+        if let Some(fees) = scenario.fees {
+            self.fee_rate_governor(FeeRateGovernor::new(fees.lamports_per_signature, 0));
+        }
+        if let Some(ticks_per_slot) = scenario.ticks_per_slot {
+            self.ticks_per_slot(ticks_per_slot);
+        }
+        if let Some(warp_slot) = scenario.warp_slot {
+            self.warp_slot(warp_slot);
+        }
+
+        let features_to_deactivate = scenario
+            .features
+            .deactivate
+            .iter()
+            .map(|feature| scenario.address(feature))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.deactivate_features(&features_to_deactivate);
+        if scenario.features.clone_from_cluster {
+            self.clone_feature_set(&scenario.rpc_client("the feature set")?)?;
+        }
+
+        for program in &scenario.programs {
+            let program_path = scenario.path(&program.path);
+            if !program_path.exists() {
+                return Err(format!(
+                    "Program file does not exist: {}",
+                    program_path.display()
+                ));
+            }
+            let upgrade_authority = program
+                .upgrade_authority
+                .as_deref()
+                .map(|authority| scenario.address(authority))
+                .transpose()?
+                .unwrap_or_default();
+            self.upgradeable_programs.push(UpgradeableProgramInfo {
+                program_id: scenario.address(&program.address)?,
+                loader: bpf_loader_upgradeable::id(),
+                upgrade_authority,
+                program_path,
+            });
+        }
+
+        for account in &scenario.accounts {
+            self.add_scenario_account(scenario, account)?;
+        }
+
+        for funded_keypair in &scenario.funded_keypairs {
+            let keypair_path = scenario.path(&funded_keypair.keypair);
+            let address = if keypair_path.exists() {
+                read_keypair_file(&keypair_path).map(|keypair| keypair.pubkey())
+            } else {
+                let keypair = Keypair::new();
+                write_keypair_file(&keypair, &keypair_path).map(|_| {
+                    info!(
+                        "Generated keypair {} for funded account {}",
+                        keypair_path.display(),
+                        keypair.pubkey()
+                    );
+                    keypair.pubkey()
+                })
+            }
+            .map_err(|err| format!("Unable to use keypair {}: {err}", keypair_path.display()))?;
+            self.add_account(
+                address,
+                AccountSharedData::new(funded_keypair.lamports, 0, &system_program::id()),
+            );
+        }
+
+        for token_mint in &scenario.token_mints {
+            self.add_scenario_token_mint(scenario, token_mint)?;
+        }
+        Ok(self)
+    }
+
+    fn add_scenario_account(
+        &mut self,
+        scenario: &Scenario,
+        account: &ScenarioAccount,
+    ) -> Result<(), String> {
+        let address = account
+            .address
+            .as_deref()
+            .map(|address| scenario.address(address))
+            .transpose()?;
+        match &account.source {
+            ScenarioAccountSource::Inline {
+                lamports,
+                owner,
+                data,
+                executable,
+            } => {
+                let address = address.ok_or("Inline accounts require an address")?;
+                let owner = owner
+                    .as_deref()
+                    .map(|owner| scenario.address(owner))
+                    .transpose()?
+                    .unwrap_or_else(system_program::id);
+                let data = BASE64_STANDARD
+                    .decode(data)
+                    .map_err(|err| format!("Invalid data of account {address}: {err}"))?;
+                self.add_account(
+                    address,
+                    AccountSharedData::from(Account {
+                        lamports: *lamports,
+                        data,
+                        owner,
+                        executable: *executable,
+                        rent_epoch: 0,
+                    }),
+                );
+            }
+            ScenarioAccountSource::File(path) => {
+                let path = scenario.path(path);
+                let filename = path.to_str().ok_or("Account file paths must be UTF-8")?;
+                self.add_accounts_from_json_files(&[crate::AccountInfo { address, filename }])?;
+            }
+            ScenarioAccountSource::Clone(clone) => {
+                let address = address.ok_or("Cloned accounts require an address")?;
+                let rpc_client = scenario.rpc_client(&address.to_string())?;
+                match clone {
+                    ScenarioClone::Account => self.clone_accounts([address], &rpc_client, false),
+                    ScenarioClone::MaybeAccount => {
+                        self.clone_accounts([address], &rpc_client, true)
+                    }
+                    ScenarioClone::UpgradeableProgram => {
+                        self.clone_upgradeable_programs([address], &rpc_client)
+                    }
+                    ScenarioClone::AddressLookupTable => {
+                        self.deep_clone_address_lookup_table_accounts([address], &rpc_client)
+                    }
+                }?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_scenario_token_mint(
+        &mut self,
+        scenario: &Scenario,
+        token_mint: &ScenarioTokenMint,
+    ) -> Result<(), String> {
+        let mint_address = scenario.address(&token_mint.address)?;
+        let token_program_id = token_mint.program.id();
+        let authority = |authority: &Option<String>| {
+            authority
+                .as_deref()
+                .map(|authority| scenario.address(authority))
+                .transpose()
+                .map(COption::from)
+        };
+
+        let mut supply = 0u64;
+        for holder in &token_mint.holders {
+            let owner = scenario.address(&holder.owner)?;
+            supply = supply
+                .checked_add(holder.amount)
+                .ok_or_else(|| format!("Supply of mint {mint_address} overflows"))?;
+            let mut data = vec![0; TokenAccount::LEN];
+            TokenAccount::pack(
+                TokenAccount {
+                    mint: mint_address,
+                    owner,
+                    amount: holder.amount,
+                    state: AccountState::Initialized,
+                    ..TokenAccount::default()
+                },
+                &mut data,
+            )
+            .map_err(|err| format!("Unable to pack token account: {err}"))?;
+            self.add_token_program_account(
+                get_associated_token_address_with_program_id(
+                    &owner,
+                    &mint_address,
+                    &token_program_id,
+                ),
+                data,
+                token_program_id,
+            );
+        }
+
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(
+            Mint {
+                mint_authority: authority(&token_mint.mint_authority)?,
+                supply,
+                decimals: token_mint.decimals,
+                is_initialized: true,
+                freeze_authority: authority(&token_mint.freeze_authority)?,
+            },
+            &mut data,
+        )
+        .map_err(|err| format!("Unable to pack mint {mint_address}: {err}"))?;
+        self.add_token_program_account(mint_address, data, token_program_id);
+        Ok(())
+    }
+
+    fn add_token_program_account(&mut self, address: Pubkey, data: Vec<u8>, owner: Pubkey) {
+        self.add_account(
+            address,
+            AccountSharedData::from(Account {
+                lamports: self.rent.minimum_balance(data.len()),
+                data,
+                owner,
+                executable: false,
+                rent_epoch: 0,
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_account::ReadableAccount};
+
+    const TOML_SCENARIO: &str = r#"
+version = 1
+warp-slot = 1000
+
+[[accounts]]
+address = "4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi"
+inline = { lamports = 42, data = "AQID" }
+
+[features]
+deactivate = ["8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR"]
+
+[epoch-schedule]
+slots-per-epoch = 32
+
+[fees]
+lamports-per-signature = 10000
+
+[[token-mints]]
+address = "CktRuQ2mttgRGkXJtyksdKHjUdc2C4TgDzyB98oEzy8"
+decimals = 6
+holders = [
+    { owner = "4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi", amount = 100 },
+    { owner = "GgBaCs3NCBuZN12kCJgAW63ydqohFkHEdfdEXBPzLHq", amount = 23 },
+]
+"#;
+
+    const YAML_SCENARIO: &str = r#"
+version: 1
+warp-slot: 1000
+accounts:
+  - address: 4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi
+    inline:
+      lamports: 42
+      data: AQID
+features:
+  deactivate:
+    - 8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR
+epoch-schedule:
+  slots-per-epoch: 32
+fees:
+  lamports-per-signature: 10000
+token-mints:
+  - address: CktRuQ2mttgRGkXJtyksdKHjUdc2C4TgDzyB98oEzy8
+    decimals: 6
+    holders:
+      - owner: 4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi
+        amount: 100
+      - owner: GgBaCs3NCBuZN12kCJgAW63ydqohFkHEdfdEXBPzLHq
+        amount: 23
+"#;
+
+    #[test]
+    fn test_parse_scenario() {
+        let scenario = Scenario::from_toml_str(TOML_SCENARIO).unwrap();
+        assert_eq!(Scenario::from_yaml_str(YAML_SCENARIO).unwrap(), scenario);
+        assert_eq!(scenario.warp_slot, Some(1000));
+        assert_eq!(
+            scenario.accounts,
+            vec![ScenarioAccount {
+                address: Some("4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi".to_string()),
+                source: ScenarioAccountSource::Inline {
+                    lamports: 42,
+                    owner: None,
+                    data: "AQID".to_string(),
+                    executable: false,
+                },
+            }]
+        );
+        assert_eq!(scenario.token_mints[0].program, ScenarioTokenProgram::Token);
+
+        let scenario = Scenario::from_yaml_str(
+            "version: 1\naccounts:\n  - address: 4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi\n    \
+             clone: upgradeable-program\n",
+        )
+        .unwrap();
+        assert_eq!(
+            scenario.accounts[0].source,
+            ScenarioAccountSource::Clone(ScenarioClone::UpgradeableProgram)
+        );
+
+        assert!(Scenario::from_toml_str("version = 2")
+            .unwrap_err()
+            .contains("unsupported scenario version 2"));
+        assert!(Scenario::from_toml_str("version = 1\nwarp = 1").is_err());
+    }
+
+    #[test]
+    fn test_add_scenario() {
+        let scenario = Scenario::from_toml_str(TOML_SCENARIO).unwrap();
+        let mut genesis = TestValidatorGenesis::default();
+        genesis.add_scenario(&scenario).unwrap();
+
+        assert_eq!(genesis.warp_slot, Some(1000));
+        assert_eq!(genesis.epoch_schedule.as_ref().unwrap().slots_per_epoch, 32);
+        assert_eq!(
+            genesis.fee_rate_governor.target_lamports_per_signature,
+            10000
+        );
+        assert!(genesis
+            .deactivate_feature_set
+            .contains(&scenario.address(&scenario.features.deactivate[0]).unwrap()));
+
+        let holder = scenario
+            .address(scenario.accounts[0].address.as_deref().unwrap())
+            .unwrap();
+        let account = &genesis.accounts[&holder];
+        assert_eq!(account.lamports(), 42);
+        assert_eq!(account.data(), &[1, 2, 3]);
+        assert_eq!(account.owner(), &system_program::id());
+
+        let mint_address = scenario.address(&scenario.token_mints[0].address).unwrap();
+        let mint = Mint::unpack(genesis.accounts[&mint_address].data()).unwrap();
+        assert_eq!(mint.supply, 123);
+        assert_eq!(mint.decimals, 6);
+        assert_eq!(mint.mint_authority, COption::None);
+
+        let token_account_address = get_associated_token_address_with_program_id(
+            &holder,
+            &mint_address,
+            &spl_token_interface::id(),
+        );
+        let token_account = &genesis.accounts[&token_account_address];
+        assert_eq!(token_account.owner(), &spl_token_interface::id());
+        let token_account = TokenAccount::unpack(token_account.data()).unwrap();
+        assert_eq!(token_account.owner, holder);
+        assert_eq!(token_account.amount, 100);
+    }
+
+    #[test]
+    fn test_add_scenario_clone_requires_url() {
+        let scenario = Scenario::from_toml_str(
+            "version = 1\n[[accounts]]\naddress = \
+             \"4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi\"\nclone = \"account\"\n",
+        )
+        .unwrap();
+        assert!(TestValidatorGenesis::default()
+            .add_scenario(&scenario)
+            .unwrap_err()
+            .contains("scenario url is required"));
+    }
+}
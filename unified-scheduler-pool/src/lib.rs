@@ -24,6 +24,7 @@ use {
     dyn_clone::{clone_trait_object, DynClone},
     log::*,
     scopeguard::defer,
+    solana_accounts_db::accounts_db::ReadCacheCaller,
     solana_clock::{Epoch, Slot},
     solana_cost_model::cost_model::CostModel,
     solana_ledger::blockstore_processor::{
@@ -2476,6 +2477,13 @@ impl<S: SpawnableScheduler<TH>, TH: TaskHandler> ThreadManager<S, TH> {
             }
         };
 
+        // Handlers execute the transactions of the blocks being replayed or produced, so their
+        // loads from the read cache are attributed accordingly.
+        let read_cache_caller = match scheduling_mode {
+            BlockVerification => ReadCacheCaller::Replay,
+            BlockProduction => ReadCacheCaller::Banking,
+        };
+
         let handler_main_loop = || {
             let mut handler_context = handler_context.clone();
             let mut runnable_task_receiver = runnable_task_receiver.clone();
@@ -2491,6 +2499,7 @@ impl<S: SpawnableScheduler<TH>, TH: TaskHandler> ThreadManager<S, TH> {
             //    `select_biased!`, which are sent from `.send_chained_channel()` in the scheduler
             //    thread for all-but-initial sessions.
             move || {
+                read_cache_caller.set_for_current_thread();
                 loop {
                     let (task, sender) = select_biased! {
                         recv(runnable_task_receiver.for_select()) -> message => {
@@ -21,6 +21,7 @@ use {
     solana_keypair::{read_keypair_file, write_keypair_file, Keypair},
     solana_native_token::sol_str_to_lamports,
     solana_net_utils::SocketAddrSpace,
+    solana_poh::automine::AutomineConfig,
     solana_pubkey::Pubkey,
     solana_rent::Rent,
     solana_rpc::{
@@ -30,7 +31,10 @@ use {
     solana_rpc_client::rpc_client::RpcClient,
     solana_signer::Signer,
     solana_system_interface::program as system_program,
-    solana_test_validator::*,
+    solana_test_validator::{
+        fork::{DirectoryForkSource, RpcForkSource},
+        *,
+    },
     std::{
         collections::{HashMap, HashSet},
         env, fs, io,
@@ -297,6 +301,13 @@ fn main() {
 
     let clone_feature_set = matches.is_present("clone_feature_set");
 
+    let scenario = matches.value_of("scenario").map(|scenario| {
+        Scenario::load(scenario).unwrap_or_else(|err| {
+            println!("Error: {err}");
+            exit(1);
+        })
+    });
+
     let warp_slot = if matches.is_present("warp_slot") {
         Some(match matches.value_of("warp_slot") {
             Some(_) => value_t_or_exit!(matches, "warp_slot", Slot),
@@ -382,6 +393,7 @@ fn main() {
             ("bpf_program", "--bpf-program"),
             ("clone_account", "--clone"),
             ("account", "--account"),
+            ("scenario", "--scenario"),
             ("mint_address", "--mint"),
             ("ticks_per_slot", "--ticks-per-slot"),
             ("slots_per_epoch", "--slots-per-epoch"),
@@ -462,6 +474,16 @@ fn main() {
         None
     };
 
+    // Apply the scenario first so that the flags below override its settings
+    if let Some(scenario) = &scenario {
+        if !TestValidatorGenesis::ledger_exists(&ledger_path) {
+            if let Err(e) = genesis.add_scenario(scenario) {
+                println!("Error: add_scenario failed: {e}");
+                exit(1);
+            }
+        }
+    }
+
     genesis
         .ledger_path(&ledger_path)
         .tower_storage(tower_storage)
@@ -490,6 +512,7 @@ fn main() {
         rpc_bigtable_config,
         faucet_addr: Some(faucet_addr),
         account_indexes,
+        enable_test_methods: true,
         ..JsonRpcConfig::default_for_test()
     });
 
@@ -554,6 +577,42 @@ fn main() {
         }
     }
 
+    if matches.is_present("fork") {
+        let rpc_client = RpcClient::new(normalize_to_url_if_moniker(value_t_or_exit!(
+            matches,
+            "json_rpc_url",
+            String
+        )));
+        match RpcForkSource::new(rpc_client) {
+            Ok(source) => {
+                info!("Forking the cluster at slot {}", source.fork_slot());
+                genesis.fork(Arc::new(source));
+            }
+            Err(e) => {
+                println!("Error: fork failed: {e}");
+                exit(1);
+            }
+        }
+    } else if let Some(fork_dir) = matches.value_of("fork_dir") {
+        match DirectoryForkSource::new(fork_dir) {
+            Ok(source) => {
+                genesis.fork(Arc::new(source));
+            }
+            Err(e) => {
+                println!("Error: fork failed: {e}");
+                exit(1);
+            }
+        }
+    }
+
+    if matches.is_present("automine") {
+        genesis.automine(AutomineConfig {
+            interval: value_t!(matches, "automine_interval", u64)
+                .ok()
+                .map(Duration::from_millis),
+        });
+    }
+
     if let Some(warp_slot) = warp_slot {
         genesis.warp_slot(warp_slot);
     }
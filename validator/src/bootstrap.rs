@@ -303,11 +303,13 @@ pub enum GetRpcNodeError {
 
 /// Struct to wrap the return value from get_rpc_nodes().  The `rpc_contact_info` is the peer to
 /// download from, and `snapshot_hash` is the (optional) full and (optional) incremental
-/// snapshots to download.
+/// snapshots to download.  `incremental_snapshot_chain` is the chain of incremental snapshots
+/// that ends at the incremental snapshot, see `get_incremental_snapshot_chain()`.
 #[derive(Debug)]
 struct GetRpcNodeResult {
     rpc_contact_info: ContactInfo,
     snapshot_hash: Option<SnapshotHash>,
+    incremental_snapshot_chain: Vec<(Slot, Hash)>,
 }
 
 /// Struct to wrap the peers & snapshot hashes together.
@@ -364,6 +366,7 @@ pub fn attempt_download_genesis_and_snapshot(
     maximum_snapshot_download_abort: u64,
     download_abort_count: &mut u64,
     snapshot_hash: Option<SnapshotHash>,
+    incremental_snapshot_chain: &[(Slot, Hash)],
     identity_keypair: &Arc<Keypair>,
     vote_account: &Pubkey,
     authorized_voter_keypairs: Arc<RwLock<Vec<Arc<Keypair>>>>,
@@ -399,6 +402,7 @@ pub fn attempt_download_genesis_and_snapshot(
         maximum_snapshot_download_abort,
         download_abort_count,
         snapshot_hash,
+        incremental_snapshot_chain,
         rpc_contact_info,
     )?;
 
@@ -442,7 +446,12 @@ fn ping(addr: &SocketAddr) -> Option<Duration> {
 // used for downloading latest snapshots and/or the genesis block. Guaranteed to
 // find at least one viable node or terminate the process.
 fn get_vetted_rpc_nodes(
-    vetted_rpc_nodes: &mut Vec<(ContactInfo, Option<SnapshotHash>, RpcClient)>,
+    vetted_rpc_nodes: &mut Vec<(
+        ContactInfo,
+        Option<SnapshotHash>,
+        Vec<(Slot, Hash)>,
+        RpcClient,
+    )>,
     cluster_info: &Arc<ClusterInfo>,
     validator_config: &ValidatorConfig,
     blacklisted_rpc_nodes: &mut HashSet<Pubkey>,
@@ -474,6 +483,7 @@ fn get_vetted_rpc_nodes(
                     let GetRpcNodeResult {
                         rpc_contact_info,
                         snapshot_hash,
+                        incremental_snapshot_chain,
                     } = rpc_node_details;
 
                     info!(
@@ -488,12 +498,22 @@ fn get_vetted_rpc_nodes(
                     let rpc_client =
                         RpcClient::new_socket_with_timeout(rpc_addr, Duration::from_secs(5));
 
-                    Some((rpc_contact_info, snapshot_hash, rpc_client, ping_time))
+                    Some((
+                        rpc_contact_info,
+                        snapshot_hash,
+                        incremental_snapshot_chain,
+                        rpc_client,
+                        ping_time,
+                    ))
                 })
                 .filter(
-                    |(rpc_contact_info, _snapshot_hash, rpc_client, ping_time)| match rpc_client
-                        .get_version()
-                    {
+                    |(
+                        rpc_contact_info,
+                        _snapshot_hash,
+                        _incremental_snapshot_chain,
+                        rpc_client,
+                        ping_time,
+                    )| match rpc_client.get_version() {
                         Ok(rpc_version) => {
                             if let Some(ping_time) = ping_time {
                                 info!(
@@ -526,15 +546,29 @@ fn get_vetted_rpc_nodes(
                 .collect::<Vec<(
                     ContactInfo,
                     Option<SnapshotHash>,
+                    Vec<(Slot, Hash)>,
                     RpcClient,
                     Option<Duration>,
                 )>>()
                 .into_iter()
-                .sorted_by_key(|(_, _, _, ping_time)| ping_time.unwrap())
-                .map(|(rpc_contact_info, snapshot_hash, rpc_client, _)| {
-                    (rpc_contact_info, snapshot_hash, rpc_client)
-                })
-                .collect::<Vec<(ContactInfo, Option<SnapshotHash>, RpcClient)>>(),
+                .sorted_by_key(|(_, _, _, _, ping_time)| ping_time.unwrap())
+                .map(
+                    |(
+                        rpc_contact_info,
+                        snapshot_hash,
+                        incremental_snapshot_chain,
+                        rpc_client,
+                        _,
+                    )| {
+                        (
+                            rpc_contact_info,
+                            snapshot_hash,
+                            incremental_snapshot_chain,
+                            rpc_client,
+                        )
+                    },
+                )
+                .collect::<Vec<_>>(),
         );
         blacklisted_rpc_nodes.extend(newly_blacklisted_rpc_nodes.into_inner().unwrap());
     }
@@ -615,7 +649,8 @@ pub fn rpc_bootstrap(
             &mut blacklisted_rpc_nodes,
             &bootstrap_config,
         );
-        let (rpc_contact_info, snapshot_hash, rpc_client) = vetted_rpc_nodes.pop().unwrap();
+        let (rpc_contact_info, snapshot_hash, incremental_snapshot_chain, rpc_client) =
+            vetted_rpc_nodes.pop().unwrap();
         get_rpc_nodes_time += get_rpc_nodes_start.elapsed();
 
         let snapshot_download_start = Instant::now();
@@ -633,6 +668,7 @@ pub fn rpc_bootstrap(
             maximum_snapshot_download_abort,
             &mut download_abort_count,
             snapshot_hash,
+            &incremental_snapshot_chain,
             identity_keypair,
             vote_account,
             authorized_voter_keypairs.clone(),
@@ -714,6 +750,7 @@ fn get_rpc_nodes(
             return Ok(vec![GetRpcNodeResult {
                 rpc_contact_info: random_peer.clone(),
                 snapshot_hash: None,
+                incremental_snapshot_chain: Vec::new(),
             }]);
         }
 
@@ -765,6 +802,18 @@ fn get_rpc_nodes(
                 .map(|peer_snapshot_hash| GetRpcNodeResult {
                     rpc_contact_info: peer_snapshot_hash.rpc_contact_info.clone(),
                     snapshot_hash: Some(peer_snapshot_hash.snapshot_hash),
+                    incremental_snapshot_chain: get_incremental_snapshot_chain(
+                        &peer_snapshot_hash.snapshot_hash,
+                        cluster_info
+                            .get_snapshot_hashes_for_node(
+                                peer_snapshot_hash.rpc_contact_info.pubkey(),
+                            )
+                            .filter(|snapshot_hashes| {
+                                snapshot_hashes.full == peer_snapshot_hash.snapshot_hash.full
+                            })
+                            .map(|snapshot_hashes| snapshot_hashes.incremental)
+                            .unwrap_or_default(),
+                    ),
                 })
                 .take(MAX_RPC_CONNECTIONS_EVALUATED_PER_ITERATION)
                 .collect();
@@ -783,10 +832,11 @@ fn get_highest_local_snapshot_hash(
     snapshot_paths::get_highest_full_snapshot_archive_info(full_snapshot_archives_dir)
         .and_then(|full_snapshot_info| {
             if incremental_snapshot_fetch {
-                snapshot_paths::get_highest_incremental_snapshot_archive_info(
+                snapshot_paths::get_highest_incremental_snapshot_archive_chain(
                     incremental_snapshot_archives_dir,
                     full_snapshot_info.slot(),
                 )
+                .pop()
                 .map(|incremental_snapshot_info| {
                     (
                         incremental_snapshot_info.slot(),
@@ -1095,6 +1145,7 @@ fn download_snapshots(
     maximum_snapshot_download_abort: u64,
     download_abort_count: &mut u64,
     snapshot_hash: Option<SnapshotHash>,
+    incremental_snapshot_chain: &[(Slot, Hash)],
     rpc_contact_info: &ContactInfo,
 ) -> Result<(), String> {
     if snapshot_hash.is_none() {
@@ -1149,14 +1200,17 @@ fn download_snapshots(
     }
 
     if bootstrap_config.incremental_snapshot_fetch {
-        // Check and see if we've already got the incremental snapshot; if not, download it
-        if let Some(incremental_snapshot_hash) = incremental_snapshot_hash {
+        // Check and see if we've already got each incremental snapshot of the chain; if not,
+        // download it.  The first one is based on the full snapshot, and every other one on the
+        // one before it.
+        let mut base_slot = full_snapshot_hash.0;
+        for &incremental_snapshot_hash in incremental_snapshot_chain {
             if snapshot_paths::get_incremental_snapshot_archives(incremental_snapshot_archives_dir)
                 .into_iter()
                 .any(|snapshot_archive| {
                     snapshot_archive.slot() == incremental_snapshot_hash.0
                         && snapshot_archive.hash().0 == incremental_snapshot_hash.1
-                        && snapshot_archive.base_slot() == full_snapshot_hash.0
+                        && snapshot_archive.base_slot() == base_slot
                 })
             {
                 info!(
@@ -1175,9 +1229,10 @@ fn download_snapshots(
                     download_abort_count,
                     rpc_contact_info,
                     incremental_snapshot_hash,
-                    SnapshotArchiveKind::Incremental(full_snapshot_hash.0),
+                    SnapshotArchiveKind::Incremental(base_slot),
                 )?;
             }
+            base_slot = incremental_snapshot_hash.0;
         }
     }
 
@@ -1334,6 +1389,25 @@ fn get_snapshot_hashes_for_node(cluster_info: &ClusterInfo, node: &Pubkey) -> Op
     )
 }
 
+/// Get the chain of incremental snapshots to download for `snapshot_hash`, from the incremental
+/// snapshot hashes that its node advertises for the same full snapshot
+///
+/// Nodes that chain their incremental snapshots advertise the whole chain, and every incremental
+/// snapshot of the chain up to and including the one in `snapshot_hash` must be downloaded.  The
+/// chain is empty if `snapshot_hash` has no incremental snapshot.
+fn get_incremental_snapshot_chain(
+    snapshot_hash: &SnapshotHash,
+    mut advertised_incremental_snapshot_hashes: Vec<(Slot, Hash)>,
+) -> Vec<(Slot, Hash)> {
+    let Some(incremental_snapshot_hash) = snapshot_hash.incr else {
+        return Vec::new();
+    };
+    advertised_incremental_snapshot_hashes.sort_unstable();
+    advertised_incremental_snapshot_hashes.retain(|(slot, _)| *slot < incremental_snapshot_hash.0);
+    advertised_incremental_snapshot_hashes.push(incremental_snapshot_hash);
+    advertised_incremental_snapshot_hashes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1658,4 +1732,44 @@ mod tests {
             assert_eq!(expected, actual);
         }
     }
+
+    #[test]
+    fn test_get_incremental_snapshot_chain() {
+        let full = (100_000, Hash::new_unique());
+        let chain = [
+            (100_100, Hash::new_unique()),
+            (100_200, Hash::new_unique()),
+            (100_300, Hash::new_unique()),
+        ];
+
+        // Without an incremental snapshot there is nothing to download
+        let snapshot_hash = SnapshotHash { full, incr: None };
+        assert!(get_incremental_snapshot_chain(&snapshot_hash, chain.to_vec()).is_empty());
+
+        // A node that does not chain its incremental snapshots only advertises the one
+        let snapshot_hash = SnapshotHash {
+            full,
+            incr: Some(chain[0]),
+        };
+        assert_eq!(
+            get_incremental_snapshot_chain(&snapshot_hash, vec![chain[0]]),
+            vec![chain[0]],
+        );
+
+        // The chain ends at the incremental snapshot, even if the node has moved on since
+        let snapshot_hash = SnapshotHash {
+            full,
+            incr: Some(chain[1]),
+        };
+        assert_eq!(
+            get_incremental_snapshot_chain(&snapshot_hash, vec![chain[2], chain[0], chain[1]]),
+            vec![chain[0], chain[1]],
+        );
+
+        // If the node no longer advertises the chain, only the incremental snapshot is left
+        assert_eq!(
+            get_incremental_snapshot_chain(&snapshot_hash, Vec::new()),
+            vec![chain[1]],
+        );
+    }
 }
@@ -553,6 +553,17 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                      parameter is silently ignored",
                 ),
         )
+        .arg(
+            Arg::with_name("scenario")
+                .long("scenario")
+                .value_name("FILE")
+                .takes_value(true)
+                .help(
+                    "Load the programs, accounts, features and cluster settings declared in the \
+                     TOML or YAML scenario FILE. Flags override the settings of the scenario. If \
+                     the ledger already exists then this parameter is silently ignored",
+                ),
+        )
         .arg(
             Arg::with_name("ticks_per_slot")
                 .long("ticks-per-slot")
@@ -694,6 +705,49 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                      already exists then this parameter is silently ignored",
                 ),
         )
+        .arg(
+            Arg::with_name("fork")
+                .long("fork")
+                .takes_value(false)
+                .requires("json_rpc_url")
+                .help(
+                    "Fork the cluster referenced by the --url argument lazily: accounts missing \
+                     from the ledger, including programs, are fetched from the cluster the first \
+                     time they are accessed, then kept. The URL may point to a local JSON RPC \
+                     stand-in",
+                ),
+        )
+        .arg(
+            Arg::with_name("fork_dir")
+                .long("fork-dir")
+                .value_name("DIRECTORY")
+                .takes_value(true)
+                .conflicts_with("fork")
+                .help(
+                    "Fork a cluster offline: accounts missing from the ledger are loaded from the \
+                     JSON account files in DIRECTORY, as written by `solana account --output \
+                     json`, the first time they are accessed",
+                ),
+        )
+        .arg(
+            Arg::with_name("automine")
+                .long("automine")
+                .takes_value(false)
+                .help(
+                    "Mine blocks on demand instead of every slot time: a block is mined and \
+                     rooted as soon as transactions arrive, or on a mineBlock RPC request. \
+                     Blocks are not voted on, and the clock advances by the slot time per block",
+                ),
+        )
+        .arg(
+            Arg::with_name("automine_interval")
+                .long("automine-interval")
+                .value_name("MILLISECONDS")
+                .takes_value(true)
+                .requires("automine")
+                .validator(is_parsable::<u64>)
+                .help("Also mine a block every MILLISECONDS, with or without transactions"),
+        )
         .arg(
             Arg::with_name("maybe_clone_account")
                 .long("maybe-clone")
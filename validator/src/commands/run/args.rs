@@ -446,6 +446,22 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
                  older snapshots.",
             ),
     )
+    .arg(
+        Arg::with_name("chain_incremental_snapshots")
+            .long("chain-incremental-snapshots")
+            .takes_value(false)
+            .hidden(hidden_unless_forced())
+            .help(
+                "Base each incremental snapshot archive on the previous incremental snapshot \
+                 archive instead of on the full snapshot archive. Each incremental snapshot \
+                 archive then only contains the changes since the previous one, and loading \
+                 applies the whole chain in order. A new chain is started once a chain has \
+                 25 archives. The whole chain is advertised in gossip, and \
+                 peers bootstrapping from this node download every archive of the chain. Peers \
+                 running older versions can only download the full snapshot archive from this \
+                 node.",
+            ),
+    )
     .arg(
         Arg::with_name("snapshot_packager_niceness_adj")
             .long("snapshot-packager-niceness-adjustment")
@@ -534,6 +550,16 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .default_value(default_args.snapshot_version.into())
             .help("Output snapshot version"),
     )
+    .arg(
+        Arg::with_name("persist_accounts_index")
+            .long("persist-accounts-index")
+            .takes_value(false)
+            .help(
+                "Save the accounts index with the bank snapshot taken at shutdown. Restarting \
+                 from that snapshot then skips scanning all account storages to rebuild the \
+                 index.",
+            ),
+    )
     .arg(
         Arg::with_name("skip_startup_ledger_verification")
             .long("skip-startup-ledger-verification")
@@ -635,6 +661,28 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .takes_value(true)
             .help("Log when transactions are processed which reference a given key."),
     )
+    .arg(
+        Arg::with_name("record_instruction_fixtures")
+            .long("record-instruction-fixtures")
+            .value_name("DIR")
+            .takes_value(true)
+            .requires("record_instruction_fixtures_program_id")
+            .help(
+                "Write an SVM test harness fixture into DIR for every replayed top-level \
+                 instruction that invokes one of the programs given with \
+                 --record-instruction-fixtures-program-id",
+            ),
+    )
+    .arg(
+        Arg::with_name("record_instruction_fixtures_program_id")
+            .long("record-instruction-fixtures-program-id")
+            .validator(is_pubkey)
+            .value_name("ADDRESS")
+            .multiple(true)
+            .takes_value(true)
+            .requires("record_instruction_fixtures")
+            .help("Program whose instructions are recorded as fixtures"),
+    )
     .arg(
         Arg::with_name("repair_validators")
             .long("repair-validator")
@@ -1014,6 +1062,29 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             )
             .hidden(hidden_unless_forced()),
     )
+    .arg(
+        Arg::with_name("accounts_db_read_cache_admission_policy")
+            .long("accounts-db-read-cache-admission-policy")
+            .value_name("POLICY")
+            .takes_value(true)
+            .possible_values(&["always", "frequency"])
+            .help("Controls which loaded accounts are admitted into the read cache")
+            .long_help(
+                "Controls which loaded accounts are admitted into the read cache. With `always`, \
+                 every loaded account is admitted. With `frequency`, once the cache is full an \
+                 account is only admitted if it has been loaded more often than the account it \
+                 would likely evict, which keeps large scans from evicting frequently used \
+                 accounts. This value is currently `always` by default.",
+            )
+            .hidden(hidden_unless_forced()),
+    )
+    .arg(
+        Arg::with_name("accounts_db_read_cache_owner_stats")
+            .long("accounts-db-read-cache-owner-stats")
+            .takes_value(false)
+            .help("Report read cache statistics for the most frequently hit account owners")
+            .hidden(hidden_unless_forced()),
+    )
     .arg(
         Arg::with_name("accounts_db_mark_obsolete_accounts")
             .long("accounts-db-mark-obsolete-accounts")
@@ -17,7 +17,10 @@ use {
     log::*,
     rand::{rng, seq::SliceRandom},
     solana_accounts_db::{
-        accounts_db::{AccountShrinkThreshold, AccountsDbConfig, MarkObsoleteAccounts},
+        accounts_db::{
+            AccountShrinkThreshold, AccountsDbConfig, MarkObsoleteAccounts,
+            ReadCacheAdmissionPolicy,
+        },
         accounts_file::StorageAccess,
         accounts_index::{
             AccountSecondaryIndexes, AccountsIndexConfig, IndexLimit, IndexLimitThreshold,
@@ -57,12 +60,16 @@ use {
     solana_keypair::Keypair,
     solana_ledger::{
         blockstore_cleanup_service::{DEFAULT_MAX_LEDGER_SHREDS, DEFAULT_MIN_MAX_LEDGER_SHREDS},
+        instruction_fixtures::InstructionFixtureWriter,
         use_snapshot_archives_at_startup::{self, UseSnapshotArchivesAtStartup},
     },
     solana_net_utils::multihomed_sockets::BindIpAddrs,
     solana_poh::poh_service,
     solana_pubkey::Pubkey,
-    solana_runtime::{runtime_config::RuntimeConfig, snapshot_utils},
+    solana_runtime::{
+        bank::instruction_recorder::BankInstructionRecorder, runtime_config::RuntimeConfig,
+        snapshot_utils,
+    },
     solana_signer::Signer,
     solana_streamer::{
         nonblocking::{simple_qos::SimpleQosConfig, swqos::SwQosConfig},
@@ -436,6 +443,24 @@ pub fn execute(
         None
     };
 
+    let instruction_recorder = matches
+        .value_of_os("record_instruction_fixtures")
+        .map(|output_dir| {
+            let program_ids =
+                values_t_or_exit!(matches, "record_instruction_fixtures_program_id", Pubkey)
+                    .into_iter()
+                    .collect();
+            InstructionFixtureWriter::new(Path::new(output_dir), program_ids)
+                .map(|writer| Arc::new(writer) as Arc<dyn BankInstructionRecorder>)
+                .map_err(|err| {
+                    format!(
+                        "unable to create instruction fixture directory {}: {err}",
+                        Path::new(output_dir).display()
+                    )
+                })
+        })
+        .transpose()?;
+
     let repair_validators = validators_set(
         &identity_keypair.pubkey(),
         matches,
@@ -635,6 +660,18 @@ pub fn execute(
             }
         });
 
+    let read_cache_admission_policy = matches
+        .value_of("accounts_db_read_cache_admission_policy")
+        .map(|policy| match policy {
+            "always" => ReadCacheAdmissionPolicy::Always,
+            "frequency" => ReadCacheAdmissionPolicy::Frequency,
+            _ => {
+                // clap will enforce one of the above values is given
+                unreachable!("invalid value given to accounts-db-read-cache-admission-policy")
+            }
+        })
+        .unwrap_or_default();
+
     let storage_access = matches
         .value_of("accounts_db_access_storages_method")
         .map(|method| match method {
@@ -685,6 +722,8 @@ pub fn execute(
         shrink_paths: account_shrink_run_paths,
         shrink_ratio,
         read_cache_limit_bytes,
+        read_cache_admission_policy,
+        read_cache_owner_stats: matches.is_present("accounts_db_read_cache_owner_stats"),
         write_cache_limit_bytes: value_t!(matches, "accounts_db_cache_limit_mb", u64)
             .ok()
             .map(|mb| mb * MB as u64),
@@ -802,6 +841,8 @@ pub fn execute(
         blockstore_options: run_args.blockstore_options,
         run_verification: !matches.is_present("skip_startup_ledger_verification"),
         debug_keys,
+        instruction_recorder,
+        account_fallback: None,
         warp_slot: None,
         generator_config: None,
         contact_debug_interval,
@@ -819,6 +860,7 @@ pub fn execute(
             .unwrap_or(poh_service::DEFAULT_PINNED_CPU_CORE),
         poh_hashes_per_batch: value_of(matches, "poh_hashes_per_batch")
             .unwrap_or(poh_service::DEFAULT_HASHES_PER_BATCH),
+        automine: None,
         process_ledger_before_services: matches.is_present("process_ledger_before_services"),
         account_paths: account_run_paths,
         account_snapshot_paths,
@@ -1374,6 +1416,8 @@ fn new_snapshot_config(
         snapshot_version,
         maximum_full_snapshot_archives_to_retain,
         maximum_incremental_snapshot_archives_to_retain,
+        chain_incremental_snapshots: matches.is_present("chain_incremental_snapshots"),
+        persist_accounts_index: matches.is_present("persist_accounts_index"),
     };
 
     if !is_snapshot_config_valid(&snapshot_config) {